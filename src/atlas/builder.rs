@@ -1,15 +1,20 @@
+use std::collections::{BTreeMap, HashMap, VecDeque};
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
 
 use anyhow::Result;
-use image::imageops;
-use log::{debug, info};
+use image::{Rgba, RgbaImage, imageops, imageops::FilterType};
+use log::{debug, info, warn};
+use rayon::prelude::*;
 
-use super::Atlas;
-use crate::cli::{PackMode, PackingHeuristic};
+use super::mipmap::mip_chain;
+use super::{Atlas, AtlasLayout, PackingChoice};
+use crate::cli::{ExtrudeMode, GridCell, PackMode, PackingHeuristic};
 use crate::error::BentoError;
-use crate::packing::MaxRectsPacker;
-use crate::sprite::{PackedSprite, SourceSprite};
+use crate::packing::{MaxRectsPacker, Rect};
+use crate::progress::{ProgressCallback, ProgressEvent};
+use crate::sprite::{ContentBounds, NineSlice, PackedSprite, SourceSprite, TrimInfo, resize_image};
+use crate::warning::Warning;
 
 /// All concrete heuristics to try when using "Best" mode
 const ALL_HEURISTICS: [PackingHeuristic; 5] = [
@@ -20,6 +25,14 @@ const ALL_HEURISTICS: [PackingHeuristic; 5] = [
     PackingHeuristic::ContactPoint,
 ];
 
+/// Widths probed when searching for the smallest non-power-of-two page
+const SIZE_SEARCH_WIDTHS: u32 = 16;
+
+/// Sprites with a side longer than this are flagged as suspiciously large
+const LARGE_SIDE: u32 = 2048;
+/// Sprites covering more than this share of a page are flagged as suspiciously large
+const LARGE_PAGE_SHARE: f64 = 0.25;
+
 /// Sprite ordering strategies for pack-mode best
 #[derive(Debug, Clone, Copy)]
 enum SpriteOrdering {
@@ -39,6 +52,32 @@ enum SpriteOrdering {
     ByWidthHeightRatio,
     /// Sort by diagonal length (largest first)
     ByDiagonal,
+    /// Seeded random shuffle (random-restart trial number)
+    Shuffled(u32),
+    /// Input order, placed in uniform grid cells instead of by MaxRects
+    Grid,
+}
+
+impl SpriteOrdering {
+    /// Report entry for a page packed with this ordering and `heuristic`
+    fn choice(self, heuristic: PackingHeuristic) -> PackingChoice {
+        let ordering = match self {
+            Self::Original => "input".to_string(),
+            Self::ByArea => "area".to_string(),
+            Self::ByPerimeter => "perimeter".to_string(),
+            Self::ByMaxDimension => "max-side".to_string(),
+            Self::ByWidth => "width".to_string(),
+            Self::ByHeight => "height".to_string(),
+            Self::ByWidthHeightRatio => "aspect-ratio".to_string(),
+            Self::ByDiagonal => "diagonal".to_string(),
+            Self::Shuffled(trial) => format!("shuffle-{}", trial),
+            Self::Grid => "grid".to_string(),
+        };
+        PackingChoice {
+            heuristic: (!matches!(self, Self::Grid)).then_some(heuristic),
+            ordering,
+        }
+    }
 }
 
 const ALL_ORDERINGS: [SpriteOrdering; 8] = [
@@ -52,17 +91,69 @@ const ALL_ORDERINGS: [SpriteOrdering; 8] = [
     SpriteOrdering::ByDiagonal,
 ];
 
+/// Per-sprite overrides of the builder's global spacing settings
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SpriteSettings {
+    /// Padding around this sprite (None = use the builder's padding)
+    pub padding: Option<u32>,
+    /// Edge extrusion for this sprite (None = use the builder's extrude)
+    pub extrude: Option<u32>,
+    /// What fills this sprite's extruded border (None = use the builder's mode)
+    pub extrude_mode: Option<ExtrudeMode>,
+    /// Packing priority; higher values are packed onto earlier pages (None = 0)
+    pub priority: Option<i32>,
+}
+
 /// Configuration for atlas building
 pub struct AtlasBuilder {
     pub max_width: u32,
     pub max_height: u32,
-    pub padding: u32,
+    /// Padding left and right of each sprite
+    pub padding_x: u32,
+    /// Padding above and below each sprite
+    pub padding_y: u32,
     pub heuristic: PackingHeuristic,
     pub power_of_two: bool,
     pub extrude: u32,
+    /// What fills the extruded border: repeated edge pixels or the opposite edge
+    pub extrude_mode: ExtrudeMode,
     pub block_align: u32,
+    /// Let sprites be turned 90° clockwise when that packs them tighter
+    pub allow_rotation: bool,
+    /// Search for the smallest page that still fits, instead of cropping the full bin
+    pub size_search: bool,
+    /// Force square pages
+    pub square: bool,
+    /// Make every page exactly `max_width`x`max_height` instead of cropping to content
+    pub fixed_size: bool,
+    /// Round final page dimensions up to a multiple of this (0 or 1 = disabled)
+    pub size_multiple: u32,
     pub pack_mode: PackMode,
+    /// Extra randomly shuffled orderings to try in Best pack mode
+    pub trials: u32,
+    /// Seed for the shuffled orderings (same seed = same layout)
+    pub seed: u64,
+    /// Sprite name patterns packed first, in list order, before every ordering
+    pub pack_order: Vec<glob::Pattern>,
+    /// Sprite name patterns whose matches should share a page, one group per
+    /// pattern (the first matching pattern wins)
+    pub keep_together: Vec<glob::Pattern>,
+    /// Maximum number of sprites per atlas page (None = unlimited)
+    pub max_sprites_per_page: Option<usize>,
+    /// Per-sprite padding/extrude overrides, keyed by sprite name
+    pub sprite_settings: HashMap<String, SpriteSettings>,
+    /// Maximum summed area of all pages in pixels (None = unlimited)
+    pub max_total_pixels: Option<u64>,
+    /// Maximum summed size of all pages as uncompressed RGBA8 (None = unlimited)
+    pub max_total_bytes: Option<u64>,
+    /// Maximum number of pages (None = unlimited)
+    pub max_atlases: Option<usize>,
+    /// Place sprites in uniform cells in input order instead of packing them
+    pub grid: Option<GridCell>,
+    /// Color of page pixels outside sprites and their extrusion (None = transparent)
+    pub background: Option<Rgba<u8>>,
     cancel_token: Option<Arc<AtomicBool>>,
+    progress: Option<ProgressCallback>,
 }
 
 /// Intermediate placement info for a single sprite
@@ -73,8 +164,47 @@ struct SpritePlacement {
     width: u32,
     height: u32,
     name: String,
+    path: std::path::PathBuf,
     trim_info: crate::sprite::TrimInfo,
     atlas_index: usize,
+    input_index: usize,
+    content_bounds: Option<crate::sprite::ContentBounds>,
+    nine_slice: Option<crate::sprite::NineSlice>,
+    pivot: Option<crate::sprite::Pivot>,
+    rotated: bool,
+}
+
+impl SpritePlacement {
+    fn new(
+        sprite_index: usize,
+        sprite: &SourceSprite,
+        atlas_index: usize,
+        x: u32,
+        y: u32,
+        rotated: bool,
+    ) -> Self {
+        let (width, height) = if rotated {
+            (sprite.height(), sprite.width())
+        } else {
+            (sprite.width(), sprite.height())
+        };
+        Self {
+            sprite_index,
+            x,
+            y,
+            width,
+            height,
+            name: sprite.name.clone(),
+            path: sprite.path.clone(),
+            trim_info: sprite.trim_info,
+            atlas_index,
+            input_index: sprite.input_index,
+            content_bounds: sprite.content_bounds,
+            nine_slice: sprite.nine_slice,
+            pivot: sprite.pivot,
+            rotated,
+        }
+    }
 }
 
 /// Result of trying a packing heuristic
@@ -84,12 +214,24 @@ struct PackingLayout {
     max_x: u32,
     max_y: u32,
     occupancy: f64,
+    /// Bin the layout was packed into
+    bin_width: u32,
+    bin_height: u32,
+    /// Free space left in the bin
+    free_rects: Vec<Rect>,
+    /// Placed sprites per priority, highest first (empty without priorities)
+    priorities: Vec<(i32, usize)>,
 }
 
 impl PackingLayout {
     /// Returns true if this layout is better than another.
-    /// Priority: 1) more sprites packed, 2) smaller atlas area, 3) higher occupancy.
+    /// Priority: 1) more high-priority sprites packed, 2) more sprites packed,
+    /// 3) smaller atlas area, 4) higher occupancy.
     fn is_better_than(&self, other: &PackingLayout) -> bool {
+        if let Some(better) = places_more_by_priority(&self.priorities, &other.priorities) {
+            return better;
+        }
+
         let self_packed = self.placements.len();
         let other_packed = other.placements.len();
 
@@ -115,18 +257,52 @@ impl AtlasBuilder {
         Self {
             max_width,
             max_height,
-            padding: 1,
+            padding_x: 1,
+            padding_y: 1,
             heuristic: PackingHeuristic::BestShortSideFit,
             power_of_two: false,
             extrude: 0,
+            extrude_mode: ExtrudeMode::Clamp,
             block_align: 0,
+            allow_rotation: false,
+            size_search: false,
+            square: false,
+            fixed_size: false,
+            size_multiple: 0,
             pack_mode: PackMode::Single,
+            trials: 0,
+            seed: 0,
+            pack_order: Vec::new(),
+            keep_together: Vec::new(),
+            max_sprites_per_page: None,
+            sprite_settings: HashMap::new(),
+            max_total_pixels: None,
+            max_total_bytes: None,
+            max_atlases: None,
+            grid: None,
+            background: None,
             cancel_token: None,
+            progress: None,
         }
     }
 
+    /// Padding on both axes
     pub fn padding(mut self, padding: u32) -> Self {
-        self.padding = padding;
+        self.padding_x = padding;
+        self.padding_y = padding;
+        self
+    }
+
+    /// Padding left and right of each sprite
+    pub fn padding_x(mut self, padding: u32) -> Self {
+        self.padding_x = padding;
+        self
+    }
+
+    /// Padding above and below each sprite. With rotation allowed, both axes
+    /// use the larger of the two paddings, since a rotated sprite swaps them.
+    pub fn padding_y(mut self, padding: u32) -> Self {
+        self.padding_y = padding;
         self
     }
 
@@ -145,22 +321,148 @@ impl AtlasBuilder {
         self
     }
 
+    /// Fill extruded borders by clamping to the edge pixels, or by wrapping
+    /// around to the opposite edge so seamless tiles keep tiling
+    pub fn extrude_mode(mut self, mode: ExtrudeMode) -> Self {
+        self.extrude_mode = mode;
+        self
+    }
+
     pub fn block_align(mut self, block_align: u32) -> Self {
         self.block_align = block_align;
         self
     }
 
+    /// Allow sprites to be stored rotated 90° clockwise. Rotated sprites have
+    /// `rotated` set and their width and height swapped in the atlas.
+    pub fn allow_rotation(mut self, allow: bool) -> Self {
+        self.allow_rotation = allow;
+        self
+    }
+
+    /// Shrink the bin of a page that fits entirely until the smallest page is
+    /// found. Widths and heights are binary-searched (power-of-two sizes with
+    /// [`power_of_two`](Self::power_of_two), one side with [`square`](Self::square)),
+    /// so this repacks the page many times.
+    pub fn size_search(mut self, enabled: bool) -> Self {
+        self.size_search = enabled;
+        self
+    }
+
+    /// Make every page square, using the longer side for both
+    pub fn square(mut self, square: bool) -> Self {
+        self.square = square;
+        self
+    }
+
+    /// Output every page at the full `max_width`x`max_height` instead of
+    /// cropping it to its sprites. Power-of-two, alignment and square
+    /// rounding still apply on top; size search is skipped.
+    pub fn fixed_size(mut self, fixed: bool) -> Self {
+        self.fixed_size = fixed;
+        self
+    }
+
+    /// Round final page width and height up to a multiple of `multiple`
+    pub fn size_multiple(mut self, multiple: u32) -> Self {
+        self.size_multiple = multiple;
+        self
+    }
+
     pub fn pack_mode(mut self, pack_mode: PackMode) -> Self {
         self.pack_mode = pack_mode;
         self
     }
 
+    /// Try `trials` random sprite orderings in addition to the fixed sorts (Best pack mode only)
+    pub fn trials(mut self, trials: u32) -> Self {
+        self.trials = trials;
+        self
+    }
+
+    /// Seed for the random orderings tried by [`trials`](Self::trials)
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Pack sprites whose names match these patterns first, in pattern order.
+    ///
+    /// The remaining sprites follow in the order chosen by the pack mode. Since
+    /// MaxRects fills from the top-left, pinned sprites land in predictable spots.
+    pub fn pack_order(mut self, patterns: Vec<glob::Pattern>) -> Self {
+        self.pack_order = patterns;
+        self
+    }
+
+    /// Keep the sprites matching each pattern on one page. A page that would
+    /// split a group is packed again without it, leaving the group for a later
+    /// page; a group too large for any page is split with a warning.
+    pub fn keep_together(mut self, patterns: Vec<glob::Pattern>) -> Self {
+        self.keep_together = patterns;
+        self
+    }
+
+    /// Start a new page after `limit` sprites, regardless of remaining space
+    pub fn max_sprites_per_page(mut self, limit: Option<usize>) -> Self {
+        self.max_sprites_per_page = limit;
+        self
+    }
+
+    /// Override padding/extrude for individual sprites, keyed by sprite name
+    pub fn sprite_settings(mut self, settings: HashMap<String, SpriteSettings>) -> Self {
+        self.sprite_settings = settings;
+        self
+    }
+
+    /// Fail when the summed area of all pages exceeds `limit` pixels
+    pub fn max_total_pixels(mut self, limit: Option<u64>) -> Self {
+        self.max_total_pixels = limit;
+        self
+    }
+
+    /// Fail when all pages together exceed `limit` bytes of uncompressed RGBA8
+    pub fn max_total_bytes(mut self, limit: Option<u64>) -> Self {
+        self.max_total_bytes = limit;
+        self
+    }
+
+    /// Fail when the sprites need more than `limit` pages
+    pub fn max_atlases(mut self, limit: Option<usize>) -> Self {
+        self.max_atlases = limit;
+        self
+    }
+
+    /// Lay sprites out on a grid of uniform cells (None = pack with MaxRects)
+    pub fn grid(mut self, grid: Option<GridCell>) -> Self {
+        self.grid = grid;
+        self
+    }
+
+    /// Fill the unused area of each page with a color instead of transparent black
+    pub fn background(mut self, color: Option<Rgba<u8>>) -> Self {
+        self.background = color;
+        self
+    }
+
     /// Set a cancellation token for aborting long-running pack operations
     pub fn cancel_token(mut self, token: Arc<AtomicBool>) -> Self {
         self.cancel_token = Some(token);
         self
     }
 
+    /// Report placed sprites and rendered pages to `callback`
+    pub fn progress(mut self, callback: ProgressCallback) -> Self {
+        self.progress = Some(callback);
+        self
+    }
+
+    fn report(&self, event: ProgressEvent) {
+        if let Some(progress) = &self.progress {
+            progress.report(event);
+        }
+    }
+
     /// Check if cancellation has been requested
     fn is_cancelled(&self) -> bool {
         self.cancel_token
@@ -170,36 +472,46 @@ impl AtlasBuilder {
 
     /// Build atlases from the given sprites
     pub fn build(&self, sprites: Vec<SourceSprite>) -> Result<Vec<Atlas>> {
-        if sprites.is_empty() {
-            return Err(BentoError::NoImages.into());
-        }
+        self.build_pages(sprites, &[])
+    }
 
-        // Validate all sprites can fit
-        for sprite in &sprites {
-            let padded_w = self.padded_size(sprite.width());
-            let padded_h = self.padded_size(sprite.height());
-
-            if padded_w > self.max_width || padded_h > self.max_height {
-                return Err(BentoError::SpriteTooLarge {
-                    name: sprite.name.clone(),
-                    width: sprite.width(),
-                    height: sprite.height(),
-                    max_width: self.max_width,
-                    max_height: self.max_height,
-                }
-                .into());
-            }
-        }
+    /// Build atlases as pages that follow `existing` others, which count
+    /// towards the page limit and the pixel budget
+    fn build_pages(&self, sprites: Vec<SourceSprite>, existing: &[Atlas]) -> Result<Vec<Atlas>> {
+        self.validate(&sprites)?;
+        let grid_pitch = self.grid_pitch(&sprites)?;
 
         let mut atlases = Vec::new();
+        let total = sprites.len();
         let mut remaining: Vec<_> = sprites.into_iter().collect();
+        let mut planned = self.plan_min_pages(&mut remaining, grid_pitch)?;
+        let mut total_pixels: u64 = existing
+            .iter()
+            .map(|atlas| u64::from(atlas.width) * u64::from(atlas.height))
+            .sum();
 
         while !remaining.is_empty() {
             if self.is_cancelled() {
                 return Err(BentoError::Cancelled.into());
             }
             let atlas_index = atlases.len();
-            let (atlas, unpacked) = self.pack_atlas(atlas_index, remaining)?;
+            self.check_atlas_count(existing.len() + atlas_index, &remaining)?;
+            let (atlas, unpacked) = match planned.pop_front() {
+                Some((heuristic, ordering, layout)) => {
+                    self.apply_layout(atlas_index, remaining, heuristic, ordering, layout)?
+                }
+                None => self.pack_atlas(atlas_index, remaining, grid_pitch)?,
+            };
+            self.report(ProgressEvent::SpritesPlaced {
+                done: total - unpacked.len(),
+                total,
+            });
+            if atlas.sprites.is_empty() {
+                return Err(self.nothing_placed(existing.len() + atlas_index, &unpacked));
+            }
+            self.report(ProgressEvent::AtlasRendered { index: atlas_index });
+            total_pixels += u64::from(atlas.width) * u64::from(atlas.height);
+            self.check_budget(total_pixels)?;
             atlases.push(atlas);
             remaining = unpacked;
         }
@@ -213,587 +525,2958 @@ impl AtlasBuilder {
         Ok(atlases)
     }
 
-    fn pack_atlas(
+    /// Build atlases that keep sprites where a `previous` layout put them.
+    ///
+    /// A sprite stays at its old position, on a page of the old size, when it
+    /// still has the same packed size, its page still fits the size limits,
+    /// and its cell with the current padding and extrusion neither leaves the
+    /// page nor overlaps another kept sprite. New and changed sprites are then
+    /// packed around the kept ones as with [`append`](Self::append), and pages
+    /// left empty are dropped. Kept pages count towards the pixel budget.
+    pub fn build_stable(
         &self,
-        index: usize,
+        previous: &[AtlasLayout],
         sprites: Vec<SourceSprite>,
-    ) -> Result<(Atlas, Vec<SourceSprite>)> {
-        // If Best heuristic mode, try all heuristics (and orderings if pack_mode is Best)
-        let (best_heuristic, best_ordering, best_layout) =
-            if self.heuristic == PackingHeuristic::Best {
-                self.find_best_heuristic(&sprites, index)?
-            } else {
-                // Use specified heuristic with original ordering (or try orderings/widths if pack_mode is Best)
-                let orderings: &[SpriteOrdering] = if self.pack_mode == PackMode::Best {
-                    &ALL_ORDERINGS
-                } else {
-                    &[SpriteOrdering::Original]
-                };
+    ) -> Result<Vec<Atlas>> {
+        let by_name: HashMap<String, usize> = sprites
+            .iter()
+            .enumerate()
+            .map(|(i, sprite)| (sprite.name.clone(), i))
+            .collect();
+        let mut sprites: Vec<Option<SourceSprite>> = sprites.into_iter().map(Some).collect();
 
-                let width_candidates = self.width_candidates(&sprites);
-
-                let mut best: Option<(SpriteOrdering, PackingLayout)> = None;
-                for &max_width in &width_candidates {
-                    for &ordering in orderings {
-                        if self.is_cancelled() {
-                            break;
-                        }
-                        let order = self.sorted_indices(&sprites, ordering);
-                        let layout = self.try_pack_with_width(
-                            &sprites,
-                            &order,
-                            index,
-                            self.heuristic,
-                            max_width,
-                        );
-
-                        let dominated = best
-                            .as_ref()
-                            .is_some_and(|(_, b)| !layout.is_better_than(b));
-                        if !dominated {
-                            best = Some((ordering, layout));
-                        }
-                    }
+        let mut pages = Vec::new();
+        for layout in previous {
+            if layout.width > self.max_width || layout.height > self.max_height {
+                continue;
+            }
+            let mut atlas = self.new_page(pages.len(), layout.width, layout.height);
+            let page = Rect::new(0, 0, layout.width, layout.height);
+            let mut cells: Vec<Rect> = Vec::new();
+            for packed in &layout.sprites {
+                if self
+                    .max_sprites_per_page
+                    .is_some_and(|limit| atlas.sprites.len() >= limit)
+                {
+                    break;
                 }
-
-                // Check if we broke out due to cancellation before trying any ordering
-                if self.is_cancelled() && best.is_none() {
-                    return Err(BentoError::Cancelled.into());
+                let Some(&i) = by_name.get(&packed.name) else {
+                    continue;
+                };
+                let Some(sprite) = &sprites[i] else {
+                    continue;
+                };
+                if packed.rotated && !self.allow_rotation {
+                    continue;
                 }
+                let placement = SpritePlacement::new(
+                    i,
+                    sprite,
+                    atlas.index,
+                    packed.x,
+                    packed.y,
+                    packed.rotated,
+                );
+                if (placement.width, placement.height) != (packed.width, packed.height) {
+                    continue;
+                }
+                // Padding or extrusion may have grown since the old layout
+                let body = Rect::new(packed.x, packed.y, packed.width, packed.height);
+                let (_, _, extrude) = self.spacing_for(&packed.name);
+                if packed.x < extrude
+                    || packed.y < extrude
+                    || !page.contains(&Rect::new(
+                        packed.x - extrude,
+                        packed.y - extrude,
+                        packed.width + extrude * 2,
+                        packed.height + extrude * 2,
+                    ))
+                {
+                    continue;
+                }
+                let cell = self.kept_cell(&packed.name, body, layout.width, layout.height);
+                if cells.iter().any(|other| other.intersects(&cell)) {
+                    continue;
+                }
+                cells.push(cell);
+                if let Some(source) = sprites[i].take() {
+                    self.render_sprite(&mut atlas, source, placement);
+                }
+            }
+            if atlas.sprites.is_empty() {
+                continue;
+            }
+            atlas.rebuild_index();
+            atlas.occupancy = sprite_occupancy(&atlas);
+            pages.push(atlas);
+        }
+
+        let kept: usize = pages.iter().map(|atlas| atlas.sprites.len()).sum();
+        let remaining: Vec<SourceSprite> = sprites.into_iter().flatten().collect();
+        info!(
+            "Kept {} sprites in place, {} to pack",
+            kept,
+            remaining.len()
+        );
+        self.append(pages, remaining)
+    }
+
+    /// Pack `sprites` into the free space of `existing` pages, such as ones
+    /// read back from metadata, then onto new pages as [`build`](Self::build)
+    /// would. Existing pages keep their size and every sprite stays where it
+    /// is, so their UVs never move; kept sprites are assumed to have this
+    /// builder's padding and extrusion around them.
+    pub fn append(
+        &self,
+        mut existing: Vec<Atlas>,
+        sprites: Vec<SourceSprite>,
+    ) -> Result<Vec<Atlas>> {
+        for sprite in &sprites {
+            self.check_fits(sprite)?;
+        }
 
-                // Orderings slice is non-empty, so best is Some if not cancelled
-                #[expect(clippy::expect_used, reason = "orderings is non-empty")]
-                let (ordering, layout) = best.expect("at least one ordering should be tried");
-                (self.heuristic, ordering, layout)
+        let mut remaining = sprites;
+        for atlas in &mut existing {
+            if remaining.is_empty() {
+                break;
+            }
+            if self.is_cancelled() {
+                return Err(BentoError::Cancelled.into());
+            }
+            let mut packer =
+                MaxRectsPacker::new(atlas.width, atlas.height).allow_rotation(self.allow_rotation);
+            for sprite in &atlas.sprites {
+                let body = Rect::new(sprite.x, sprite.y, sprite.width, sprite.height);
+                packer.occupy(self.kept_cell(&sprite.name, body, atlas.width, atlas.height));
+            }
+
+            let order: Vec<usize> = (0..remaining.len()).collect();
+            let heuristics = if self.heuristic == PackingHeuristic::Best {
+                &ALL_HEURISTICS[..]
+            } else {
+                std::slice::from_ref(&self.heuristic)
             };
+            let kept = atlas.sprites.len();
+            let Some(layout) = heuristics
+                .iter()
+                .map(|&heuristic| {
+                    self.fill_bin(
+                        packer.clone(),
+                        &remaining,
+                        &order,
+                        atlas.index,
+                        heuristic,
+                        kept,
+                    )
+                })
+                .reduce(|best, layout| {
+                    if layout.placements.len() > best.placements.len() {
+                        layout
+                    } else {
+                        best
+                    }
+                })
+            else {
+                continue;
+            };
+            if layout.placements.is_empty() {
+                continue;
+            }
+
+            info!(
+                "Atlas {}: appended {} sprites",
+                atlas.index,
+                layout.placements.len()
+            );
+            atlas.free_rects = layout.free_rects.clone();
+            remaining = self.render_placements(atlas, remaining, layout);
+            atlas.occupancy = sprite_occupancy(atlas);
+        }
+        if remaining.is_empty() {
+            return Ok(existing);
+        }
 
-        // Apply the best layout
-        self.apply_layout(index, sprites, best_heuristic, best_ordering, best_layout)
+        // New pages are numbered after the existing ones
+        let offset = existing.len();
+        let mut pages = self.build_pages(remaining, &existing)?;
+        for page in &mut pages {
+            page.index += offset;
+            for sprite in &mut page.sprites {
+                sprite.atlas_index += offset;
+            }
+            page.rebuild_index();
+        }
+        existing.extend(pages);
+        Ok(existing)
     }
 
-    /// Try packing with a specific heuristic and ordering, return placement info without rendering
-    #[cfg(test)]
-    fn try_pack(
-        &self,
-        sprites: &[SourceSprite],
-        order: &[usize],
-        index: usize,
-        heuristic: PackingHeuristic,
-    ) -> PackingLayout {
-        self.try_pack_with_width(sprites, order, index, heuristic, self.max_width)
+    /// Cell around a kept sprite's `body` with this builder's padding and
+    /// extrusion, clipped to its page
+    fn kept_cell(&self, name: &str, body: Rect, page_width: u32, page_height: u32) -> Rect {
+        let (padding_x, padding_y, extrude) = self.spacing_for(name);
+        let x = body.x.saturating_sub(padding_x + extrude);
+        let y = body.y.saturating_sub(padding_y + extrude);
+        let width = self.padded_width(name, body.width);
+        let height = self.padded_height(name, body.height);
+        Rect::new(
+            x,
+            y,
+            width.min(page_width.saturating_sub(x)),
+            height.min(page_height.saturating_sub(y)),
+        )
     }
 
-    /// Try packing with a specific heuristic, ordering, and width constraint
-    fn try_pack_with_width(
+    /// Generate `levels` mip levels (level 1 onward) for a built atlas.
+    ///
+    /// Each sprite, together with its extruded border, is downsampled on its
+    /// own, so mip levels don't bleed neighboring sprites into each other.
+    pub fn mipmaps(&self, atlas: &Atlas, levels: u32) -> Vec<RgbaImage> {
+        let regions: Vec<Rect> = atlas
+            .sprites
+            .iter()
+            .map(|sprite| {
+                let (_, _, extrude) = self.spacing_for(&sprite.name);
+                let extrude = scale_floor(extrude, atlas.scale);
+                Rect::new(
+                    sprite.x.saturating_sub(extrude),
+                    sprite.y.saturating_sub(extrude),
+                    sprite.width + extrude * 2,
+                    sprite.height + extrude * 2,
+                )
+            })
+            .collect();
+        mip_chain(&atlas.image, &regions, levels)
+    }
+
+    /// Render a built page at another resolution, keeping its layout.
+    ///
+    /// Every sprite is resized on its own, so neighbors never bleed into each
+    /// other, and keeps a size proportional to its source whatever its
+    /// position. Positions, trim offsets, content bounds and nine-slice
+    /// borders are scaled along with it, and extrusion is redone at the
+    /// scaled width. Sprites can touch once padding plus extrude shrinks
+    /// below one pixel. `linear` resizes the page, but not its companions,
+    /// in linear light (see [`resize_image`]).
+    pub fn scale_atlas(
         &self,
-        sprites: &[SourceSprite],
-        order: &[usize],
-        index: usize,
-        heuristic: PackingHeuristic,
-        max_width: u32,
-    ) -> PackingLayout {
-        let mut packer = MaxRectsPacker::new(max_width, self.max_height);
-        let mut placements = Vec::new();
-        let mut unpacked_indices = Vec::new();
-        let mut max_x = 0u32;
-        let mut max_y = 0u32;
+        atlas: &Atlas,
+        scale: f32,
+        filter: FilterType,
+        linear: bool,
+    ) -> Atlas {
+        let total_scale = atlas.scale * scale;
+        let mut width = scale_round(atlas.width, scale).max(1);
+        let mut height = scale_round(atlas.height, scale).max(1);
+        let mut sprites = Vec::with_capacity(atlas.sprites.len());
+        let mut extrudes = Vec::with_capacity(atlas.sprites.len());
+        let mut tight = false;
+
+        for sprite in &atlas.sprites {
+            let (padding_x, padding_y, extrude) = self.spacing_for(&sprite.name);
+            tight |= f64::from(padding_x.min(padding_y) + extrude) * f64::from(total_scale) < 1.0;
+            let extrude = scale_floor(extrude, total_scale);
+            let x = scale_floor(sprite.x, scale);
+            let y = scale_floor(sprite.y, scale);
+            let w = scale_round(sprite.width, scale).max(1);
+            let h = scale_round(sprite.height, scale).max(1);
+            width = width.max(x + w + extrude);
+            height = height.max(y + h + extrude);
+            extrudes.push(extrude);
+            sprites.push(scale_sprite(sprite, x, y, w, h, scale));
+        }
+        if tight {
+            warn!(
+                "Atlas {} at {}x: padding + extrude is under one pixel, sprites may touch",
+                atlas.index, total_scale
+            );
+        }
 
-        for &i in order {
+        let (width, height) = self.round_size(width, height);
+        let mut scaled = self.new_page(atlas.index, width, height);
+        self.draw_scaled(
+            &atlas.image,
+            &mut scaled.image,
+            &atlas.sprites,
+            &sprites,
+            &extrudes,
+            filter,
+            linear,
+        );
+        for (suffix, image) in &atlas.companions {
+            let mut page = RgbaImage::new(width, height);
+            self.draw_scaled(
+                image,
+                &mut page,
+                &atlas.sprites,
+                &sprites,
+                &extrudes,
+                filter,
+                false,
+            );
+            scaled.companions.insert(suffix.clone(), page);
+        }
+        scaled.sprites = sprites;
+        scaled.occupancy = atlas.occupancy;
+        scaled.scale = total_scale;
+        scaled.packing = atlas.packing.clone();
+        let page = Rect::new(0, 0, width, height);
+        scaled.free_rects = atlas
+            .free_rects
+            .iter()
+            .filter_map(|r| {
+                let x = scale_floor(r.x, scale);
+                let y = scale_floor(r.y, scale);
+                let w = scale_round(r.width, scale);
+                let h = scale_round(r.height, scale);
+                Rect::new(x, y, w, h).intersection(&page)
+            })
+            .collect();
+        scaled.rebuild_index();
+        scaled
+    }
+
+    /// Compute page sizes and sprite placements without rendering atlas images.
+    ///
+    /// Produces the same geometry as [`build`](Self::build), so it can be used
+    /// wherever only the layout is needed (dry runs, size comparisons).
+    pub fn layout(&self, sprites: Vec<SourceSprite>) -> Result<Vec<AtlasLayout>> {
+        self.validate(&sprites)?;
+        let grid_pitch = self.grid_pitch(&sprites)?;
+
+        let mut layouts = Vec::new();
+        let total = sprites.len();
+        let mut remaining = sprites;
+        let mut planned = self.plan_min_pages(&mut remaining, grid_pitch)?;
+        let mut total_pixels = 0u64;
+
+        while !remaining.is_empty() {
             if self.is_cancelled() {
-                break;
+                return Err(BentoError::Cancelled.into());
             }
-            let sprite = &sprites[i];
-            let padded_w = self.padded_size(sprite.width());
-            let padded_h = self.padded_size(sprite.height());
-
-            if let Some(rect) = packer.insert(padded_w, padded_h, heuristic) {
-                let sprite_x = rect.x + self.padding + self.extrude;
-                let sprite_y = rect.y + self.padding + self.extrude;
-
-                max_x = max_x.max(rect.x + padded_w);
-                max_y = max_y.max(rect.y + padded_h);
-
-                placements.push(SpritePlacement {
-                    sprite_index: i,
-                    x: sprite_x,
-                    y: sprite_y,
-                    width: sprite.width(),
-                    height: sprite.height(),
-                    name: sprite.name.clone(),
-                    trim_info: sprite.trim_info,
-                    atlas_index: index,
-                });
-            } else {
-                unpacked_indices.push(i);
+            let index = layouts.len();
+            self.check_atlas_count(index, &remaining)?;
+            let (heuristic, ordering, layout) = match planned.pop_front() {
+                Some(plan) => plan,
+                None => self.plan_grouped_page(index, &mut remaining, grid_pitch)?,
+            };
+            if layout.placements.is_empty() {
+                return Err(self.nothing_placed(index, &remaining));
+            }
+            let (width, height) = self.final_size(&layout);
+            self.log_page(index, width, height, &layout, heuristic, ordering);
+            total_pixels += u64::from(width) * u64::from(height);
+            self.check_budget(total_pixels)?;
+
+            let mut unpacked = vec![false; remaining.len()];
+            for &idx in &layout.unpacked_indices {
+                unpacked[idx] = true;
             }
+            layouts.push(AtlasLayout {
+                index,
+                width,
+                height,
+                sprites: layout.placements.into_iter().map(into_packed).collect(),
+                occupancy: layout.occupancy,
+                packing: Some(ordering.choice(heuristic)),
+            });
+            remaining = remaining
+                .into_iter()
+                .zip(unpacked)
+                .filter_map(|(sprite, is_unpacked)| is_unpacked.then_some(sprite))
+                .collect();
+            self.report(ProgressEvent::SpritesPlaced {
+                done: total - remaining.len(),
+                total,
+            });
         }
 
-        // Calculate occupancy based on actual cropped atlas size, not bin size
-        let atlas_area = u64::from(max_x) * u64::from(max_y);
-        let sprite_area: u64 = placements
+        Ok(layouts)
+    }
+
+    /// Check that there is something to pack and every sprite fits on an empty page
+    fn validate(&self, sprites: &[SourceSprite]) -> Result<()> {
+        if sprites.is_empty() {
+            return Err(BentoError::NoImages.into());
+        }
+
+        for sprite in sprites {
+            self.check_fits(sprite)?;
+        }
+
+        Ok(())
+    }
+
+    /// Fail if a sprite, with its padding and extrusion, can't fit on a page
+    /// (either way up, when rotation is allowed)
+    pub fn check_fits(&self, sprite: &SourceSprite) -> Result<(), BentoError> {
+        let padded_w = self.padded_width(&sprite.name, sprite.width());
+        let padded_h = self.padded_height(&sprite.name, sprite.height());
+        let fits = |w: u32, h: u32| w <= self.max_width && h <= self.max_height;
+
+        let fits_rotated = self.allow_rotation && fits(padded_h, padded_w);
+
+        if !fits(padded_w, padded_h) && !fits_rotated {
+            return Err(BentoError::SpriteTooLarge {
+                name: sprite.name.clone(),
+                width: sprite.width(),
+                height: sprite.height(),
+                max_width: self.max_width,
+                max_height: self.max_height,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Warnings about sprites that fit but are suspiciously large: a source
+    /// side over 2048 pixels, or more than a quarter of a page
+    pub fn warnings(&self, sprites: &[SourceSprite]) -> Vec<Warning> {
+        let page_area = f64::from(self.max_width) * f64::from(self.max_height);
+        sprites
             .iter()
-            .map(|p| {
-                let padded_w = self.padded_size(p.width);
-                let padded_h = self.padded_size(p.height);
-                u64::from(padded_w) * u64::from(padded_h)
+            .filter(|sprite| self.check_fits(sprite).is_ok())
+            .filter_map(|sprite| {
+                let width = sprite.trim_info.source_width;
+                let height = sprite.trim_info.source_height;
+                let share = f64::from(sprite.width()) * f64::from(sprite.height()) / page_area;
+                (width.max(height) > LARGE_SIDE || share > LARGE_PAGE_SHARE).then(|| {
+                    Warning::LargeSprite {
+                        name: sprite.name.clone(),
+                        width,
+                        height,
+                        share,
+                    }
+                })
             })
-            .sum();
-        let occupancy = if atlas_area > 0 {
-            #[expect(
-                clippy::cast_precision_loss,
-                reason = "approximation acceptable for occupancy"
-            )]
-            {
-                sprite_area as f64 / atlas_area as f64
+            .collect()
+    }
+
+    /// Fail if `remaining` sprites are left over once `pages` pages, the
+    /// most allowed, have been filled
+    fn check_atlas_count(&self, pages: usize, remaining: &[SourceSprite]) -> Result<()> {
+        if let Some(limit) = self.max_atlases
+            && pages >= limit
+        {
+            let mut names: Vec<&str> = remaining.iter().map(|s| s.name.as_str()).collect();
+            names.sort_unstable();
+            return Err(BentoError::TooManyAtlases {
+                limit,
+                count: names.len(),
+                names: names.join(", "),
             }
-        } else {
-            0.0
-        };
+            .into());
+        }
+        Ok(())
+    }
 
-        PackingLayout {
-            placements,
-            unpacked_indices,
-            max_x,
-            max_y,
-            occupancy,
+    /// Error for a new page that placed none of the `remaining` sprites,
+    /// which would otherwise be retried on page after page
+    fn nothing_placed(&self, index: usize, remaining: &[SourceSprite]) -> anyhow::Error {
+        let mut names: Vec<&str> = remaining.iter().map(|s| s.name.as_str()).collect();
+        names.sort_unstable();
+        BentoError::NothingPlaced {
+            index,
+            count: names.len(),
+            names: names.join(", "),
         }
+        .into()
     }
 
-    /// Create sorted indices for a given ordering strategy
-    fn sorted_indices(&self, sprites: &[SourceSprite], ordering: SpriteOrdering) -> Vec<usize> {
-        let mut indices: Vec<usize> = (0..sprites.len()).collect();
+    /// Fail if the pages produced so far exceed the pixel or byte budget
+    fn check_budget(&self, total_pixels: u64) -> Result<()> {
+        if let Some(budget) = self.max_total_pixels
+            && total_pixels > budget
+        {
+            return Err(BentoError::BudgetExceeded {
+                used: total_pixels,
+                budget,
+                unit: "pixels",
+            }
+            .into());
+        }
 
-        match ordering {
-            SpriteOrdering::Original => {}
-            SpriteOrdering::ByArea => {
-                indices.sort_by(|&a, &b| {
-                    let area_a = u64::from(sprites[a].width()) * u64::from(sprites[a].height());
-                    let area_b = u64::from(sprites[b].width()) * u64::from(sprites[b].height());
-                    area_b.cmp(&area_a) // descending
-                });
-            }
-            SpriteOrdering::ByPerimeter => {
-                indices.sort_by(|&a, &b| {
-                    let perim_a = u64::from(sprites[a].width()) + u64::from(sprites[a].height());
-                    let perim_b = u64::from(sprites[b].width()) + u64::from(sprites[b].height());
-                    perim_b.cmp(&perim_a) // descending
-                });
-            }
-            SpriteOrdering::ByMaxDimension => {
-                indices.sort_by(|&a, &b| {
-                    let max_a = sprites[a].width().max(sprites[a].height());
-                    let max_b = sprites[b].width().max(sprites[b].height());
-                    max_b.cmp(&max_a) // descending
-                });
-            }
-            SpriteOrdering::ByWidth => {
-                indices.sort_by(|&a, &b| {
-                    sprites[b].width().cmp(&sprites[a].width()) // descending
-                });
-            }
-            SpriteOrdering::ByHeight => {
-                indices.sort_by(|&a, &b| {
-                    sprites[b].height().cmp(&sprites[a].height()) // descending
-                });
-            }
-            SpriteOrdering::ByWidthHeightRatio => {
-                // Sort by how far the aspect ratio is from 1:1 (most extreme first)
-                indices.sort_by(|&a, &b| {
-                    let w_a = f64::from(sprites[a].width().max(1));
-                    let h_a = f64::from(sprites[a].height().max(1));
-                    let w_b = f64::from(sprites[b].width().max(1));
-                    let h_b = f64::from(sprites[b].height().max(1));
-                    // Ratio is max/min, so always >= 1.0. Higher = more extreme.
-                    let ratio_a = w_a.max(h_a) / w_a.min(h_a);
-                    let ratio_b = w_b.max(h_b) / w_b.min(h_b);
-                    ratio_b
-                        .partial_cmp(&ratio_a)
-                        .unwrap_or(std::cmp::Ordering::Equal) // descending
-                });
-            }
-            SpriteOrdering::ByDiagonal => {
-                // Sort by diagonal length (sqrt(w^2 + h^2)), largest first
-                indices.sort_by(|&a, &b| {
-                    let diag_sq_a = u64::from(sprites[a].width()).pow(2)
-                        + u64::from(sprites[a].height()).pow(2);
-                    let diag_sq_b = u64::from(sprites[b].width()).pow(2)
-                        + u64::from(sprites[b].height()).pow(2);
-                    diag_sq_b.cmp(&diag_sq_a) // descending (compare squared to avoid sqrt)
-                });
+        let total_bytes = total_pixels * 4;
+        if let Some(budget) = self.max_total_bytes
+            && total_bytes > budget
+        {
+            return Err(BentoError::BudgetExceeded {
+                used: total_bytes,
+                budget,
+                unit: "bytes",
             }
+            .into());
         }
 
-        indices
+        Ok(())
     }
 
-    /// Find the best heuristic (and ordering if pack_mode is Best)
-    fn find_best_heuristic(
+    fn pack_atlas(
         &self,
-        sprites: &[SourceSprite],
         index: usize,
-    ) -> Result<(PackingHeuristic, SpriteOrdering, PackingLayout)> {
-        let mut best: Option<(PackingHeuristic, SpriteOrdering, PackingLayout)> = None;
+        mut sprites: Vec<SourceSprite>,
+        grid_pitch: Option<(u32, u32)>,
+    ) -> Result<(Atlas, Vec<SourceSprite>)> {
+        let (heuristic, ordering, layout) =
+            self.plan_grouped_page(index, &mut sprites, grid_pitch)?;
+        self.apply_layout(index, sprites, heuristic, ordering, layout)
+    }
 
-        // Determine which orderings to try
-        let orderings: &[SpriteOrdering] = if self.pack_mode == PackMode::Best {
-            &ALL_ORDERINGS
-        } else {
-            &[SpriteOrdering::Original]
-        };
+    /// Plan one page without splitting keep-together groups. Groups that would
+    /// be split are moved to the end of `sprites` and left unpacked, and the
+    /// page is planned again from the rest. When a page holds nothing but
+    /// split groups, the first stays (and is split if it must) so the page is
+    /// never empty.
+    fn plan_grouped_page(
+        &self,
+        index: usize,
+        sprites: &mut Vec<SourceSprite>,
+        grid_pitch: Option<(u32, u32)>,
+    ) -> Result<(PackingHeuristic, SpriteOrdering, PackingLayout)> {
+        let mut plan = self.plan_page(index, sprites, grid_pitch)?;
+        if self.keep_together.is_empty() {
+            return Ok(plan);
+        }
 
-        // Generate width candidates to try different atlas shapes.
-        // Different bin widths force different layouts, and the optimal width
-        // depends on the sprite mix. We try multiples of the widest sprite.
-        let width_candidates = self.width_candidates(sprites);
+        let mut groups: Vec<Option<usize>> = sprites
+            .iter()
+            .map(|sprite| {
+                self.keep_together
+                    .iter()
+                    .position(|pattern| pattern.matches(&sprite.name))
+            })
+            .collect();
+        let mut group_sizes = vec![0usize; self.keep_together.len()];
+        for &group in groups.iter().flatten() {
+            group_sizes[group] += 1;
+        }
 
-        for &max_width in &width_candidates {
-            for &ordering in orderings {
-                if self.is_cancelled() {
-                    break;
+        // Sprites before `kept` are planned; the rest wait for a later page
+        let mut kept = sprites.len();
+        loop {
+            let mut placed = vec![0usize; self.keep_together.len()];
+            for placement in &plan.2.placements {
+                if let Some(group) = groups[placement.sprite_index] {
+                    placed[group] += 1;
                 }
-                let order = self.sorted_indices(sprites, ordering);
+            }
+            let split: Vec<usize> = (0..placed.len())
+                .filter(|&group| placed[group] > 0 && placed[group] < group_sizes[group])
+                .collect();
+            let Some((&first, rest)) = split.split_first() else {
+                break;
+            };
 
-                for &heuristic in &ALL_HEURISTICS {
-                    if self.is_cancelled() {
-                        break;
-                    }
-                    let layout =
-                        self.try_pack_with_width(sprites, &order, index, heuristic, max_width);
-
-                    let dominated = best
-                        .as_ref()
-                        .is_some_and(|(_, _, b)| !layout.is_better_than(b));
-
-                    if !dominated {
-                        debug!(
-                            "Ordering {:?} + Heuristic {:?}: packed {}/{}, occupancy {:.1}%",
-                            ordering,
-                            heuristic,
-                            layout.placements.len(),
-                            sprites.len(),
-                            layout.occupancy * 100.0
-                        );
-                        best = Some((heuristic, ordering, layout));
-                    }
-                }
+            let others = plan.2.placements.iter().any(|placement| {
+                groups[placement.sprite_index].is_none_or(|group| !split.contains(&group))
+            });
+            let deferred = if others { &split[..] } else { rest };
+            if deferred.is_empty() {
+                warn!(
+                    "Atlas {}: keep-together group '{}' ({} sprites) does not fit on one page",
+                    index, self.keep_together[first], group_sizes[first]
+                );
+                break;
             }
-        }
 
-        // Check if we broke out due to cancellation before trying any heuristic
-        if self.is_cancelled() && best.is_none() {
-            return Err(BentoError::Cancelled.into());
+            let is_deferred = |group: &Option<usize>| group.is_some_and(|g| deferred.contains(&g));
+            let mut entries: Vec<_> = std::mem::take(sprites).into_iter().zip(groups).collect();
+            // Stable, so both halves keep their order
+            entries[..kept].sort_by_key(|(_, group)| is_deferred(group));
+            kept -= entries[..kept]
+                .iter()
+                .filter(|(_, group)| is_deferred(group))
+                .count();
+            (*sprites, groups) = entries.into_iter().unzip();
+
+            debug!(
+                "Atlas {}: moving {} grouped sprites to a later page",
+                index,
+                sprites.len() - kept
+            );
+            plan = self.plan_page(index, &sprites[..kept], grid_pitch)?;
         }
 
-        // ALL_HEURISTICS and orderings are non-empty, so best is Some if not cancelled
-        #[expect(clippy::expect_used, reason = "heuristics and orderings are non-empty")]
-        Ok(best.expect("at least one heuristic should be tried"))
+        plan.2.unpacked_indices.extend(kept..sprites.len());
+        Ok(plan)
     }
 
-    /// Generate width candidates for the width sweep optimization.
-    /// When pack_mode is Best, try different bin widths (multiples of the widest
-    /// padded sprite) to find the atlas shape that minimizes total area.
-    fn width_candidates(&self, sprites: &[SourceSprite]) -> Vec<u32> {
-        if self.pack_mode != PackMode::Best {
-            return vec![self.max_width];
+    /// Plan every page up front in min-pages mode, regrouping `sprites` so
+    /// each page's sprites come first among those left when it is built.
+    /// The page-by-page plan is kept unless first-fit decreasing across all
+    /// pages at once, which puts each sprite on the first page with room for
+    /// it, needs fewer pages. Other modes, grids and keep-together groups
+    /// (which are only kept page by page) get no plans.
+    fn plan_min_pages(
+        &self,
+        sprites: &mut Vec<SourceSprite>,
+        grid_pitch: Option<(u32, u32)>,
+    ) -> Result<VecDeque<(PackingHeuristic, SpriteOrdering, PackingLayout)>> {
+        if self.pack_mode != PackMode::MinPages || grid_pitch.is_some() {
+            return Ok(VecDeque::new());
+        }
+        if !self.keep_together.is_empty() {
+            warn!("Pack mode min-pages packs page by page to keep keep-together groups");
+            return Ok(VecDeque::new());
         }
 
-        let widest = sprites
-            .iter()
-            .map(|s| self.padded_size(s.width()))
-            .max()
-            .unwrap_or(1);
-
-        let mut candidates = Vec::new();
-        let mut w = widest;
-        while w <= self.max_width {
-            candidates.push(w);
-            w += widest;
+        let plans = self.plan_pages_in_turn(sprites)?;
+        if plans.len() < 2 {
+            return Ok(plans);
         }
-        // Always include the configured max_width
-        if candidates.last() != Some(&self.max_width) {
-            candidates.push(self.max_width);
+        let (heuristic, ordering, pages) = self.first_fit_pages(sprites)?;
+        if pages.len() >= plans.len() {
+            debug!(
+                "First-fit decreasing needs {} pages, keeping {} packed page by page",
+                pages.len(),
+                plans.len()
+            );
+            return Ok(plans);
         }
-        candidates
-    }
+        info!(
+            "Redistributed sprites over {} pages instead of {}",
+            pages.len(),
+            plans.len()
+        );
 
-    /// Apply a computed layout to produce the final atlas
-    fn apply_layout(
-        &self,
-        index: usize,
-        sprites: Vec<SourceSprite>,
-        heuristic: PackingHeuristic,
-        ordering: SpriteOrdering,
-        layout: PackingLayout,
-    ) -> Result<(Atlas, Vec<SourceSprite>)> {
-        let (mut final_width, mut final_height) = if self.power_of_two {
-            (
-                next_power_of_two(layout.max_x),
-                next_power_of_two(layout.max_y),
-            )
-        } else {
-            (layout.max_x, layout.max_y)
-        };
-        if self.block_align > 1 {
-            final_width = align_up(final_width, self.block_align);
-            final_height = align_up(final_height, self.block_align);
+        let mut slots: Vec<Option<SourceSprite>> =
+            std::mem::take(sprites).into_iter().map(Some).collect();
+        let mut plans = VecDeque::new();
+        for (index, page) in pages.iter().enumerate() {
+            let start = sprites.len();
+            sprites.extend(page.iter().filter_map(|&i| slots[i].take()));
+            let page_sprites = &sprites[start..];
+            let mut plan = self.plan_page(index, page_sprites, None)?;
+            // The page-by-page planner may not fit the whole group; placing
+            // the sprites as first-fit did always does
+            if plan.2.placements.len() < page_sprites.len() {
+                let order: Vec<usize> = (0..page_sprites.len()).collect();
+                let layout = self.try_pack_with_width(
+                    page_sprites,
+                    &order,
+                    index,
+                    heuristic,
+                    self.max_width,
+                    self.max_height,
+                );
+                plan = (heuristic, ordering, layout);
+            }
+            plans.push_back(plan);
+        }
+        if self.is_cancelled() {
+            return Err(BentoError::Cancelled.into());
         }
 
-        let mut atlas = Atlas::new(index, final_width, final_height);
-        atlas.occupancy = layout.occupancy;
+        // Later pages' sprites are left for later
+        let mut start = 0;
+        for (plan, page) in plans.iter_mut().zip(&pages) {
+            plan.2.unpacked_indices = (page.len()..sprites.len() - start).collect();
+            start += page.len();
+        }
+        Ok(plans)
+    }
 
-        // Convert sprites vec to allow indexed access
-        let mut sprites: Vec<Option<SourceSprite>> = sprites.into_iter().map(Some).collect();
-        let mut unpacked = Vec::new();
+    /// Plan pages one after another as the page loop would, moving the
+    /// sprites of each page in front of those left over
+    fn plan_pages_in_turn(
+        &self,
+        sprites: &mut Vec<SourceSprite>,
+    ) -> Result<VecDeque<(PackingHeuristic, SpriteOrdering, PackingLayout)>> {
+        let mut plans = VecDeque::new();
+        let mut start = 0;
+        while start < sprites.len() {
+            if self.is_cancelled() {
+                return Err(BentoError::Cancelled.into());
+            }
+            let (heuristic, ordering, mut layout) =
+                self.plan_page(plans.len(), &sprites[start..], None)?;
 
-        // Render packed sprites
-        for placement in layout.placements {
-            // Each sprite_index appears exactly once in placements
-            #[expect(clippy::expect_used, reason = "sprite indices are unique")]
-            let source = sprites[placement.sprite_index]
-                .take()
-                .expect("sprite should exist");
+            let mut placed = vec![false; sprites.len() - start];
+            for placement in &layout.placements {
+                placed[placement.sprite_index] = true;
+            }
+            // Stable, so both halves keep their order
+            let mut rest: Vec<_> = sprites.drain(start..).zip(placed).enumerate().collect();
+            rest.sort_by_key(|(_, (_, is_placed))| !is_placed);
+            let mut new_index = vec![0; rest.len()];
+            for (position, (old, _)) in rest.iter().enumerate() {
+                new_index[*old] = position;
+            }
+            sprites.extend(rest.into_iter().map(|(_, (sprite, _))| sprite));
 
-            if self.extrude > 0 {
-                self.extrude_sprite(&mut atlas.image, &source, placement.x, placement.y);
+            for placement in &mut layout.placements {
+                placement.sprite_index = new_index[placement.sprite_index];
             }
+            let count = layout.placements.len();
+            layout.unpacked_indices = (count..sprites.len() - start).collect();
+            start += count;
+            plans.push_back((heuristic, ordering, layout));
+        }
+        Ok(plans)
+    }
 
-            imageops::overlay(
-                &mut atlas.image,
-                &source.image,
-                i64::from(placement.x),
-                i64::from(placement.y),
-            );
+    /// Spread sprites over full-size pages by first-fit decreasing with every
+    /// ordering and heuristic, keeping the run with the fewest pages (ties
+    /// go to the earliest). Returns the sprite indices of each page in the
+    /// order they were placed.
+    fn first_fit_pages(
+        &self,
+        sprites: &[SourceSprite],
+    ) -> Result<(PackingHeuristic, SpriteOrdering, Vec<Vec<usize>>)> {
+        let heuristics = if self.heuristic == PackingHeuristic::Best {
+            &ALL_HEURISTICS[..]
+        } else {
+            std::slice::from_ref(&self.heuristic)
+        };
+        let orders: Vec<(SpriteOrdering, Vec<usize>)> = self
+            .orderings()
+            .into_par_iter()
+            .map(|ordering| (ordering, self.sorted_indices(sprites, ordering)))
+            .collect();
+        let candidates: Vec<_> = orders
+            .iter()
+            .flat_map(|order| heuristics.iter().map(move |&heuristic| (order, heuristic)))
+            .collect();
 
-            atlas.sprites.push(PackedSprite {
-                name: placement.name,
-                x: placement.x,
-                y: placement.y,
-                width: placement.width,
-                height: placement.height,
-                trim_info: placement.trim_info,
-                atlas_index: placement.atlas_index,
+        let best = candidates
+            .into_par_iter()
+            .enumerate()
+            .filter_map(|(rank, ((ordering, order), heuristic))| {
+                if self.is_cancelled() {
+                    return None;
+                }
+                let pages = self.first_fit(sprites, order, heuristic);
+                Some((rank, heuristic, *ordering, pages))
+            })
+            .reduce_with(|a, b| {
+                let (first, second) = if a.0 < b.0 { (a, b) } else { (b, a) };
+                if second.3.len() < first.3.len() {
+                    second
+                } else {
+                    first
+                }
             });
-        }
 
-        // Collect unpacked sprites
-        for idx in layout.unpacked_indices {
-            if let Some(sprite) = sprites[idx].take() {
-                unpacked.push(sprite);
+        match best {
+            Some((_, heuristic, ordering, pages)) if !self.is_cancelled() => {
+                Ok((heuristic, ordering, pages))
             }
+            _ => Err(BentoError::Cancelled.into()),
         }
+    }
 
-        let optimization_info = match (
-            self.heuristic == PackingHeuristic::Best,
-            self.pack_mode == PackMode::Best,
-        ) {
-            (true, true) => format!(" (best: {:?}, {:?})", heuristic, ordering),
-            (true, false) => format!(" (best: {:?})", heuristic),
-            (false, true) => format!(" (ordering: {:?})", ordering),
-            (false, false) => String::new(),
-        };
-
-        info!(
-            "Atlas {}: {}x{} with {} sprites ({:.1}% efficiency){}",
-            index,
-            final_width,
-            final_height,
-            atlas.sprites.len(),
-            layout.occupancy * 100.0,
-            optimization_info,
-        );
+    /// Place sprites in `order` on the first page with room for them, opening
+    /// a new page when none has
+    fn first_fit(
+        &self,
+        sprites: &[SourceSprite],
+        order: &[usize],
+        heuristic: PackingHeuristic,
+    ) -> Vec<Vec<usize>> {
+        let mut pages: Vec<(MaxRectsPacker, Vec<usize>)> = Vec::new();
+        for &i in order {
+            if self.is_cancelled() {
+                break;
+            }
+            let sprite = &sprites[i];
+            let padded_w = self.padded_width(&sprite.name, sprite.width());
+            let padded_h = self.padded_height(&sprite.name, sprite.height());
+            let has_room = |page: &[usize]| {
+                self.max_sprites_per_page
+                    .is_none_or(|limit| page.len() < limit)
+            };
 
-        Ok((atlas, unpacked))
+            let page = pages.iter_mut().find_map(|(packer, page)| {
+                (has_room(page) && packer.insert(padded_w, padded_h, heuristic).is_some())
+                    .then_some(page)
+            });
+            match page {
+                Some(page) => page.push(i),
+                None => {
+                    let mut packer = MaxRectsPacker::new(self.max_width, self.max_height)
+                        .allow_rotation(self.allow_rotation);
+                    // Every sprite fits an empty page, as checked by validate
+                    let _ = packer.insert(padded_w, padded_h, heuristic);
+                    pages.push((packer, vec![i]));
+                }
+            }
+        }
+        pages.into_iter().map(|(_, page)| page).collect()
     }
 
-    /// Compute the padded cell size for a sprite dimension, including block alignment.
-    /// When block_align is set, the cell is rounded up so that the sprite body
-    /// (at offset padding + extrude from the cell origin) lands on an aligned boundary.
-    /// This prevents VRAM block compression (BPTC, ASTC) from introducing edge artifacts
-    /// that shift the perceived position of sprites.
-    fn padded_size(&self, sprite_dim: u32) -> u32 {
-        let raw = sprite_dim + self.padding * 2 + self.extrude * 2;
-        if self.block_align > 1 {
-            align_up(raw, self.block_align)
-        } else {
-            raw
+    /// Choose the best heuristic, ordering and width for one page, shrinking
+    /// the bin first when size search is on. Grid layouts skip all of that.
+    fn plan_page(
+        &self,
+        index: usize,
+        sprites: &[SourceSprite],
+        grid_pitch: Option<(u32, u32)>,
+    ) -> Result<(PackingHeuristic, SpriteOrdering, PackingLayout)> {
+        if let Some(pitch) = grid_pitch {
+            let layout = self.grid_layout(index, sprites, pitch);
+            return Ok((self.heuristic, SpriteOrdering::Grid, layout));
         }
+        let plan = self.plan_bin(index, sprites, self.max_width, self.max_height)?;
+        // Pages that overflow are full anyway; only the last page can shrink
+        if !self.size_search || self.fixed_size || plan.2.placements.len() < sprites.len() {
+            return Ok(plan);
+        }
+        self.search_bin(index, sprites, plan)
     }
 
-    fn extrude_sprite(&self, atlas: &mut image::RgbaImage, sprite: &SourceSprite, x: u32, y: u32) {
-        let img = &sprite.image;
-        let (w, h) = img.dimensions();
-
-        // Extrude edges
-        for e in 1..=self.extrude {
-            // Top edge
-            if y >= e {
-                for sx in 0..w {
-                    let pixel = img.get_pixel(sx, 0);
-                    atlas.put_pixel(x + sx, y - e, *pixel);
+    /// Look for a smaller bin that still takes every sprite, keeping the plan
+    /// with the smallest final page area (`full` wins ties)
+    fn search_bin(
+        &self,
+        index: usize,
+        sprites: &[SourceSprite],
+        full: (PackingHeuristic, SpriteOrdering, PackingLayout),
+    ) -> Result<(PackingHeuristic, SpriteOrdering, PackingLayout)> {
+        let (min_width, min_height) = self.min_bin_size(sprites);
+        let mut best_area = self.final_area(&full.2);
+        let mut best = full;
+
+        let mut consider = |plan: Option<(PackingHeuristic, SpriteOrdering, PackingLayout)>| {
+            if let Some(plan) = plan {
+                let area = self.final_area(&plan.2);
+                if area < best_area {
+                    best_area = area;
+                    best = plan;
                 }
             }
+        };
 
-            // Bottom edge
-            for sx in 0..w {
-                let pixel = img.get_pixel(sx, h - 1);
-                atlas.put_pixel(x + sx, y + h - 1 + e, *pixel);
+        if self.square {
+            let sides = self.bin_sides(
+                min_width.max(min_height),
+                self.max_width.min(self.max_height),
+            );
+            consider(self.smallest_fitting_bin(index, sprites, &sides, |side| (side, side))?);
+        } else {
+            let mut widths = self.bin_sides(min_width, self.max_width);
+            if !self.power_of_two && widths.len() > SIZE_SEARCH_WIDTHS as usize {
+                let (first, last) = (min_width, self.max_width);
+                widths = (0..SIZE_SEARCH_WIDTHS)
+                    .map(|i| first + (last - first) * i / (SIZE_SEARCH_WIDTHS - 1))
+                    .collect();
             }
-
-            // Left edge
-            if x >= e {
-                for sy in 0..h {
-                    let pixel = img.get_pixel(0, sy);
-                    atlas.put_pixel(x - e, y + sy, *pixel);
-                }
+            let heights = self.bin_sides(min_height, self.max_height);
+            for width in widths {
+                consider(
+                    self.smallest_fitting_bin(index, sprites, &heights, |height| (width, height))?,
+                );
             }
+        }
 
-            // Right edge
-            for sy in 0..h {
-                let pixel = img.get_pixel(w - 1, sy);
-                atlas.put_pixel(x + w - 1 + e, y + sy, *pixel);
-            }
+        debug!(
+            "Atlas {}: size search settled on a {}x{} bin",
+            index, best.2.max_x, best.2.max_y
+        );
+        Ok(best)
+    }
 
-            // Corners
-            if x >= e && y >= e {
-                let pixel = img.get_pixel(0, 0);
-                atlas.put_pixel(x - e, y - e, *pixel);
-            }
-            if y >= e {
-                let pixel = img.get_pixel(w - 1, 0);
-                atlas.put_pixel(x + w - 1 + e, y - e, *pixel);
+    /// Binary-search `sides` (ascending) for the smallest bin that takes every
+    /// sprite. Assumes a bin that fits keeps fitting when it grows.
+    fn smallest_fitting_bin(
+        &self,
+        index: usize,
+        sprites: &[SourceSprite],
+        sides: &[u32],
+        bin: impl Fn(u32) -> (u32, u32),
+    ) -> Result<Option<(PackingHeuristic, SpriteOrdering, PackingLayout)>> {
+        let try_side = |side: u32| -> Result<Option<_>> {
+            // A cancelled pass can drop sprites without reporting them as unpacked
+            if self.is_cancelled() {
+                return Err(BentoError::Cancelled.into());
             }
-            if x >= e {
-                let pixel = img.get_pixel(0, h - 1);
-                atlas.put_pixel(x - e, y + h - 1 + e, *pixel);
+            let (width, height) = bin(side);
+            let plan = self.plan_bin(index, sprites, width, height)?;
+            Ok((plan.2.placements.len() == sprites.len()).then_some(plan))
+        };
+
+        let Some(&largest) = sides.last() else {
+            return Ok(None);
+        };
+        let Some(mut found) = try_side(largest)? else {
+            return Ok(None);
+        };
+        let (mut lo, mut hi) = (0, sides.len() - 1);
+        while lo < hi {
+            let mid = (lo + hi) / 2;
+            if let Some(plan) = try_side(sides[mid])? {
+                found = plan;
+                hi = mid;
+            } else {
+                lo = mid + 1;
             }
-            let pixel = img.get_pixel(w - 1, h - 1);
-            atlas.put_pixel(x + w - 1 + e, y + h - 1 + e, *pixel);
         }
+        Ok(Some(found))
+    }
+
+    /// Smallest bin width and height that can hold the largest sprite
+    fn min_bin_size(&self, sprites: &[SourceSprite]) -> (u32, u32) {
+        sprites.iter().fold((1, 1), |(min_w, min_h), sprite| {
+            let w = self.padded_width(&sprite.name, sprite.width());
+            let h = self.padded_height(&sprite.name, sprite.height());
+            // A rotatable sprite only forces its shorter side onto each axis
+            let (w, h) = if self.allow_rotation {
+                (w.min(h), w.min(h))
+            } else {
+                (w, h)
+            };
+            (min_w.max(w), min_h.max(h))
+        })
+    }
+
+    /// Candidate bin sides between `min` and `max`: every power of two in
+    /// range with power-of-two pages, otherwise every size
+    fn bin_sides(&self, min: u32, max: u32) -> Vec<u32> {
+        if !self.power_of_two {
+            return (min..=max).collect();
+        }
+        let mut sides: Vec<u32> =
+            std::iter::successors(Some(next_power_of_two(min)), |&s| s.checked_mul(2))
+                .take_while(|&s| s <= max)
+                .collect();
+        // A non-power-of-two maximum still works as the largest bin
+        if sides.last() != Some(&max) {
+            sides.push(max);
+        }
+        sides
+    }
+
+    /// Area of the final page for a layout
+    fn final_area(&self, layout: &PackingLayout) -> u64 {
+        let (width, height) = self.final_size(layout);
+        u64::from(width) * u64::from(height)
+    }
+
+    /// Choose the best heuristic, ordering and width for one page in a
+    /// `bin_width`x`bin_height` bin
+    fn plan_bin(
+        &self,
+        index: usize,
+        sprites: &[SourceSprite],
+        bin_width: u32,
+        bin_height: u32,
+    ) -> Result<(PackingHeuristic, SpriteOrdering, PackingLayout)> {
+        // If Best heuristic mode, try all heuristics (and orderings if pack_mode is Best)
+        if self.heuristic == PackingHeuristic::Best {
+            self.find_best_heuristic(sprites, index, bin_width, bin_height)
+        } else {
+            self.find_best_layout(
+                sprites,
+                index,
+                bin_width,
+                bin_height,
+                std::slice::from_ref(&self.heuristic),
+            )
+        }
+    }
+
+    /// Try packing with a specific heuristic and ordering, return placement info without rendering
+    #[cfg(test)]
+    fn try_pack(
+        &self,
+        sprites: &[SourceSprite],
+        order: &[usize],
+        index: usize,
+        heuristic: PackingHeuristic,
+    ) -> PackingLayout {
+        self.try_pack_with_width(
+            sprites,
+            order,
+            index,
+            heuristic,
+            self.max_width,
+            self.max_height,
+        )
+    }
+
+    /// Try packing with a specific heuristic, ordering, and bin size
+    fn try_pack_with_width(
+        &self,
+        sprites: &[SourceSprite],
+        order: &[usize],
+        index: usize,
+        heuristic: PackingHeuristic,
+        max_width: u32,
+        max_height: u32,
+    ) -> PackingLayout {
+        let packer = MaxRectsPacker::new(max_width, max_height).allow_rotation(self.allow_rotation);
+        self.fill_bin(packer, sprites, order, index, heuristic, 0)
+    }
+
+    /// Insert sprites in `order` into a bin's packer, whose page already
+    /// holds `kept` sprites
+    fn fill_bin(
+        &self,
+        mut packer: MaxRectsPacker,
+        sprites: &[SourceSprite],
+        order: &[usize],
+        index: usize,
+        heuristic: PackingHeuristic,
+        kept: usize,
+    ) -> PackingLayout {
+        let (bin_width, bin_height) = packer.bin_size();
+        let mut placements = Vec::new();
+        let mut unpacked_indices = Vec::new();
+        let mut max_x = 0u32;
+        let mut max_y = 0u32;
+
+        for &i in order {
+            if self.is_cancelled() {
+                break;
+            }
+            if self
+                .max_sprites_per_page
+                .is_some_and(|limit| kept + placements.len() >= limit)
+            {
+                unpacked_indices.push(i);
+                continue;
+            }
+
+            let sprite = &sprites[i];
+            let padded_w = self.padded_width(&sprite.name, sprite.width());
+            let padded_h = self.padded_height(&sprite.name, sprite.height());
+
+            if let Some(rect) = packer.insert(padded_w, padded_h, heuristic) {
+                let (padding_x, padding_y, extrude) = self.spacing_for(&sprite.name);
+                let sprite_x = rect.x + padding_x + extrude;
+                let sprite_y = rect.y + padding_y + extrude;
+                // The packer hands back rotated placements with swapped sides
+                let rotated = rect.width != padded_w;
+
+                max_x = max_x.max(rect.x + rect.width);
+                max_y = max_y.max(rect.y + rect.height);
+
+                placements.push(SpritePlacement::new(
+                    i, sprite, index, sprite_x, sprite_y, rotated,
+                ));
+            } else {
+                unpacked_indices.push(i);
+            }
+        }
+
+        let occupancy = self.occupancy(&placements, max_x, max_y);
+        let priorities = self.placed_priorities(&placements);
+        PackingLayout {
+            placements,
+            unpacked_indices,
+            max_x,
+            max_y,
+            occupancy,
+            bin_width,
+            bin_height,
+            free_rects: packer.free_rects().to_vec(),
+            priorities,
+        }
+    }
+
+    /// Cell pitch (cell plus the widest padding and extrusion) of a grid
+    /// layout over all `sprites`, or `None` when not laying out a grid
+    fn grid_pitch(&self, sprites: &[SourceSprite]) -> Result<Option<(u32, u32)>> {
+        let Some(grid) = self.grid else {
+            return Ok(None);
+        };
+
+        let (cell_width, cell_height) = match grid {
+            GridCell::Auto => (
+                sprites.iter().map(SourceSprite::width).max().unwrap_or(1),
+                sprites.iter().map(SourceSprite::height).max().unwrap_or(1),
+            ),
+            GridCell::Fixed { width, height } => {
+                if let Some(sprite) = sprites
+                    .iter()
+                    .find(|s| s.width() > width || s.height() > height)
+                {
+                    return Err(BentoError::GridCellTooSmall {
+                        name: sprite.name.clone(),
+                        width: sprite.width(),
+                        height: sprite.height(),
+                        cell_width: width,
+                        cell_height: height,
+                    }
+                    .into());
+                }
+                (width, height)
+            }
+        };
+
+        let pitch_width = sprites
+            .iter()
+            .map(|s| self.padded_width(&s.name, cell_width))
+            .max()
+            .unwrap_or(cell_width);
+        let pitch_height = sprites
+            .iter()
+            .map(|s| self.padded_height(&s.name, cell_height))
+            .max()
+            .unwrap_or(cell_height);
+        if pitch_width > self.max_width || pitch_height > self.max_height {
+            return Err(BentoError::GridCellTooLarge {
+                width: pitch_width,
+                height: pitch_height,
+                max_width: self.max_width,
+                max_height: self.max_height,
+            }
+            .into());
+        }
+        Ok(Some((pitch_width, pitch_height)))
+    }
+
+    /// Place sprites in input order, left to right and top to bottom, one per
+    /// `pitch`-sized cell. Sprites sit in the top-left corner of their cell.
+    fn grid_layout(
+        &self,
+        index: usize,
+        sprites: &[SourceSprite],
+        (pitch_width, pitch_height): (u32, u32),
+    ) -> PackingLayout {
+        let columns = self.max_width / pitch_width;
+        let rows = self.max_height / pitch_height;
+        let mut capacity =
+            usize::try_from(u64::from(columns) * u64::from(rows)).unwrap_or(usize::MAX);
+        if let Some(limit) = self.max_sprites_per_page {
+            capacity = capacity.min(limit);
+        }
+
+        let order = self.sorted_indices(sprites, SpriteOrdering::Grid);
+        let (placed, unpacked) = order.split_at(capacity.min(order.len()));
+
+        let mut placements = Vec::with_capacity(placed.len());
+        let (mut column, mut row) = (0, 0);
+        let (mut max_x, mut max_y) = (0, 0);
+        for &i in placed {
+            let sprite = &sprites[i];
+            let (padding_x, padding_y, extrude) = self.spacing_for(&sprite.name);
+            let x = column * pitch_width;
+            let y = row * pitch_height;
+            placements.push(SpritePlacement::new(
+                i,
+                sprite,
+                index,
+                x + padding_x + extrude,
+                y + padding_y + extrude,
+                false,
+            ));
+
+            max_x = max_x.max(x + pitch_width);
+            max_y = max_y.max(y + pitch_height);
+            column += 1;
+            if column == columns {
+                column = 0;
+                row += 1;
+            }
+        }
+
+        // Empty cells left in the last, partly filled row
+        let mut free_rects = Vec::new();
+        if column > 0 {
+            free_rects.push(Rect::new(
+                column * pitch_width,
+                row * pitch_height,
+                (columns - column) * pitch_width,
+                pitch_height,
+            ));
+        }
+
+        let occupancy = self.occupancy(&placements, max_x, max_y);
+        let priorities = self.placed_priorities(&placements);
+        PackingLayout {
+            placements,
+            unpacked_indices: unpacked.to_vec(),
+            max_x,
+            max_y,
+            occupancy,
+            bin_width: columns * pitch_width,
+            bin_height: rows * pitch_height,
+            free_rects,
+            priorities,
+        }
+    }
+
+    /// Padded sprite area over page area, measured on the cropped page
+    /// rather than the bin
+    fn occupancy(&self, placements: &[SpritePlacement], max_x: u32, max_y: u32) -> f64 {
+        let atlas_area = u64::from(max_x) * u64::from(max_y);
+        let sprite_area: u64 = placements
+            .iter()
+            .map(|p| {
+                let padded_w = self.padded_width(&p.name, p.width);
+                let padded_h = self.padded_height(&p.name, p.height);
+                u64::from(padded_w) * u64::from(padded_h)
+            })
+            .sum();
+        if atlas_area > 0 {
+            #[expect(
+                clippy::cast_precision_loss,
+                reason = "approximation acceptable for occupancy"
+            )]
+            {
+                sprite_area as f64 / atlas_area as f64
+            }
+        } else {
+            0.0
+        }
+    }
+
+    /// Create sorted indices for a given ordering strategy
+    fn sorted_indices(&self, sprites: &[SourceSprite], ordering: SpriteOrdering) -> Vec<usize> {
+        let mut indices: Vec<usize> = (0..sprites.len()).collect();
+
+        match ordering {
+            SpriteOrdering::Original => {}
+            SpriteOrdering::ByArea => {
+                indices.sort_by(|&a, &b| {
+                    let area_a = u64::from(sprites[a].width()) * u64::from(sprites[a].height());
+                    let area_b = u64::from(sprites[b].width()) * u64::from(sprites[b].height());
+                    area_b.cmp(&area_a) // descending
+                });
+            }
+            SpriteOrdering::ByPerimeter => {
+                indices.sort_by(|&a, &b| {
+                    let perim_a = u64::from(sprites[a].width()) + u64::from(sprites[a].height());
+                    let perim_b = u64::from(sprites[b].width()) + u64::from(sprites[b].height());
+                    perim_b.cmp(&perim_a) // descending
+                });
+            }
+            SpriteOrdering::ByMaxDimension => {
+                indices.sort_by(|&a, &b| {
+                    let max_a = sprites[a].width().max(sprites[a].height());
+                    let max_b = sprites[b].width().max(sprites[b].height());
+                    max_b.cmp(&max_a) // descending
+                });
+            }
+            SpriteOrdering::ByWidth => {
+                indices.sort_by(|&a, &b| {
+                    sprites[b].width().cmp(&sprites[a].width()) // descending
+                });
+            }
+            SpriteOrdering::ByHeight => {
+                indices.sort_by(|&a, &b| {
+                    sprites[b].height().cmp(&sprites[a].height()) // descending
+                });
+            }
+            SpriteOrdering::ByWidthHeightRatio => {
+                // Sort by how far the aspect ratio is from 1:1 (most extreme first)
+                indices.sort_by(|&a, &b| {
+                    let w_a = f64::from(sprites[a].width().max(1));
+                    let h_a = f64::from(sprites[a].height().max(1));
+                    let w_b = f64::from(sprites[b].width().max(1));
+                    let h_b = f64::from(sprites[b].height().max(1));
+                    // Ratio is max/min, so always >= 1.0. Higher = more extreme.
+                    let ratio_a = w_a.max(h_a) / w_a.min(h_a);
+                    let ratio_b = w_b.max(h_b) / w_b.min(h_b);
+                    ratio_b
+                        .partial_cmp(&ratio_a)
+                        .unwrap_or(std::cmp::Ordering::Equal) // descending
+                });
+            }
+            SpriteOrdering::ByDiagonal => {
+                // Sort by diagonal length (sqrt(w^2 + h^2)), largest first
+                indices.sort_by(|&a, &b| {
+                    let diag_sq_a = u64::from(sprites[a].width()).pow(2)
+                        + u64::from(sprites[a].height()).pow(2);
+                    let diag_sq_b = u64::from(sprites[b].width()).pow(2)
+                        + u64::from(sprites[b].height()).pow(2);
+                    diag_sq_b.cmp(&diag_sq_a) // descending (compare squared to avoid sqrt)
+                });
+            }
+            SpriteOrdering::Shuffled(trial) => {
+                shuffle(&mut indices, self.seed ^ u64::from(trial));
+            }
+            SpriteOrdering::Grid => {
+                // Sprites arrive sorted by area; grids follow the input
+                indices.sort_by_key(|&i| sprites[i].input_index);
+            }
+        }
+
+        if !self.pack_order.is_empty() {
+            // Stable sort keeps the chosen ordering among sprites with the same rank
+            indices.sort_by_key(|&i| {
+                self.pack_order
+                    .iter()
+                    .position(|pattern| pattern.matches(&sprites[i].name))
+                    .unwrap_or(usize::MAX)
+            });
+        }
+        if self.has_priorities() {
+            // Priorities outrank pack_order, which still orders each priority
+            indices.sort_by_key(|&i| std::cmp::Reverse(self.priority_of(&sprites[i].name)));
+        }
+
+        indices
+    }
+
+    /// Find the best heuristic (and ordering if pack_mode is Best)
+    fn find_best_heuristic(
+        &self,
+        sprites: &[SourceSprite],
+        index: usize,
+        bin_width: u32,
+        bin_height: u32,
+    ) -> Result<(PackingHeuristic, SpriteOrdering, PackingLayout)> {
+        self.find_best_layout(sprites, index, bin_width, bin_height, &ALL_HEURISTICS)
+    }
+
+    /// Try every combination of width candidate, ordering and heuristic on the
+    /// rayon thread pool and keep the best layout. Ties go to the earliest
+    /// combination, so the result matches a sequential scan on any machine.
+    fn find_best_layout(
+        &self,
+        sprites: &[SourceSprite],
+        index: usize,
+        bin_width: u32,
+        bin_height: u32,
+        heuristics: &[PackingHeuristic],
+    ) -> Result<(PackingHeuristic, SpriteOrdering, PackingLayout)> {
+        // Different bin widths force different layouts, and the optimal width
+        // depends on the sprite mix. We try multiples of the widest sprite.
+        let width_candidates = self.width_candidates(sprites, bin_width);
+        let orders: Vec<(SpriteOrdering, Vec<usize>)> = self
+            .orderings()
+            .into_par_iter()
+            .map(|ordering| (ordering, self.sorted_indices(sprites, ordering)))
+            .collect();
+
+        let mut candidates = Vec::new();
+        for &max_width in &width_candidates {
+            for order in &orders {
+                for &heuristic in heuristics {
+                    candidates.push((max_width, order, heuristic));
+                }
+            }
+        }
+
+        let best = candidates
+            .into_par_iter()
+            .enumerate()
+            .filter_map(|(rank, (max_width, (ordering, order), heuristic))| {
+                if self.is_cancelled() {
+                    return None;
+                }
+                let layout = self
+                    .try_pack_with_width(sprites, order, index, heuristic, max_width, bin_height);
+                Some((rank, heuristic, *ordering, layout))
+            })
+            .reduce_with(|a, b| {
+                // Keep the earlier candidate unless the later one is strictly better
+                let (first, second) = if a.0 < b.0 { (a, b) } else { (b, a) };
+                if second.3.is_better_than(&first.3) {
+                    second
+                } else {
+                    first
+                }
+            });
+
+        // Cancelled before any candidate finished
+        let Some((_, heuristic, ordering, layout)) = best else {
+            return Err(BentoError::Cancelled.into());
+        };
+        debug!(
+            "Ordering {:?} + Heuristic {:?}: packed {}/{}, occupancy {:.1}%",
+            ordering,
+            heuristic,
+            layout.placements.len(),
+            sprites.len(),
+            layout.occupancy * 100.0
+        );
+        Ok((heuristic, ordering, layout))
+    }
+
+    /// Sprite orderings to try: all fixed sorts plus the random trials in Best
+    /// pack mode, or only the original order otherwise
+    fn orderings(&self) -> Vec<SpriteOrdering> {
+        if !self.tries_orderings() {
+            return vec![SpriteOrdering::Original];
+        }
+
+        let mut orderings = ALL_ORDERINGS.to_vec();
+        orderings.extend((0..self.trials).map(SpriteOrdering::Shuffled));
+        orderings
+    }
+
+    /// Whether pages try every sprite ordering and width, as in the best and
+    /// min-pages pack modes
+    fn tries_orderings(&self) -> bool {
+        matches!(self.pack_mode, PackMode::Best | PackMode::MinPages)
+    }
+
+    /// Generate width candidates for the width sweep optimization.
+    /// When pack_mode is Best, try different bin widths (multiples of the widest
+    /// padded sprite) to find the atlas shape that minimizes total area.
+    fn width_candidates(&self, sprites: &[SourceSprite], bin_width: u32) -> Vec<u32> {
+        if !self.tries_orderings() {
+            return vec![bin_width];
+        }
+
+        let widest = sprites
+            .iter()
+            .map(|s| self.padded_width(&s.name, s.width()))
+            .max()
+            .unwrap_or(1);
+
+        let mut candidates = Vec::new();
+        let mut w = widest;
+        while w <= bin_width {
+            candidates.push(w);
+            w += widest;
+        }
+        // Always include the full bin width
+        if candidates.last() != Some(&bin_width) {
+            candidates.push(bin_width);
+        }
+        candidates
+    }
+
+    /// Apply a computed layout to produce the final atlas
+    fn apply_layout(
+        &self,
+        index: usize,
+        sprites: Vec<SourceSprite>,
+        heuristic: PackingHeuristic,
+        ordering: SpriteOrdering,
+        layout: PackingLayout,
+    ) -> Result<(Atlas, Vec<SourceSprite>)> {
+        let (final_width, final_height) = self.final_size(&layout);
+        self.log_page(
+            index,
+            final_width,
+            final_height,
+            &layout,
+            heuristic,
+            ordering,
+        );
+
+        let mut atlas = self.new_page(index, final_width, final_height);
+        atlas.occupancy = layout.occupancy;
+        atlas.packing = Some(ordering.choice(heuristic));
+        atlas.free_rects = free_space(&layout, final_width, final_height);
+
+        let unpacked = self.render_placements(&mut atlas, sprites, layout);
+        Ok((atlas, unpacked))
+    }
+
+    /// Draw the placed sprites of a layout onto an atlas page and add them to
+    /// its sprite list, returning the sprites left unpacked
+    fn render_placements(
+        &self,
+        atlas: &mut Atlas,
+        sprites: Vec<SourceSprite>,
+        layout: PackingLayout,
+    ) -> Vec<SourceSprite> {
+        // Convert sprites vec to allow indexed access
+        let mut sprites: Vec<Option<SourceSprite>> = sprites.into_iter().map(Some).collect();
+        let mut unpacked = Vec::new();
+
+        // Render packed sprites
+        for placement in layout.placements {
+            // Each sprite_index appears exactly once in placements
+            #[expect(clippy::expect_used, reason = "sprite indices are unique")]
+            let source = sprites[placement.sprite_index]
+                .take()
+                .expect("sprite should exist");
+
+            self.render_sprite(atlas, source, placement);
+        }
+        atlas.rebuild_index();
+
+        // Collect unpacked sprites
+        for idx in layout.unpacked_indices {
+            if let Some(sprite) = sprites[idx].take() {
+                unpacked.push(sprite);
+            }
+        }
+
+        unpacked
+    }
+
+    /// Draw one placed sprite, with its extruded border, onto an atlas page
+    /// and add it to the page's sprite list
+    fn render_sprite(&self, atlas: &mut Atlas, source: SourceSprite, placement: SpritePlacement) {
+        let image = if placement.rotated {
+            imageops::rotate90(&source.image)
+        } else {
+            source.image
+        };
+
+        let (_, _, extrude) = self.spacing_for(&placement.name);
+        let mode = self.extrude_mode_for(&placement.name);
+        if extrude > 0 {
+            extrude_sprite(
+                &mut atlas.image,
+                &image,
+                placement.x,
+                placement.y,
+                extrude,
+                mode,
+            );
+        }
+
+        self.draw_sprite(&mut atlas.image, &image, placement.x, placement.y);
+
+        // Companions go to the same place on their own pages, which start transparent
+        for (suffix, companion) in source.companions {
+            let companion = if placement.rotated {
+                imageops::rotate90(&companion)
+            } else {
+                companion
+            };
+            let page = atlas
+                .companions
+                .entry(suffix)
+                .or_insert_with(|| RgbaImage::new(atlas.width, atlas.height));
+            if extrude > 0 {
+                extrude_sprite(page, &companion, placement.x, placement.y, extrude, mode);
+            }
+            imageops::replace(
+                page,
+                &companion,
+                i64::from(placement.x),
+                i64::from(placement.y),
+            );
+        }
+
+        atlas.sprites.push(into_packed(placement));
+    }
+
+    /// Resize each sprite of a source page on its own and draw it, with its
+    /// extruded border, where `scaled` puts it on the target page
+    #[expect(
+        clippy::too_many_arguments,
+        reason = "the page, its sprites before and after scaling, and how to resample"
+    )]
+    fn draw_scaled(
+        &self,
+        source: &RgbaImage,
+        page: &mut RgbaImage,
+        sprites: &[PackedSprite],
+        scaled: &[PackedSprite],
+        extrudes: &[u32],
+        filter: FilterType,
+        linear: bool,
+    ) {
+        let images: Vec<RgbaImage> = sprites
+            .iter()
+            .zip(scaled)
+            .map(|(sprite, target)| {
+                let pixels =
+                    imageops::crop_imm(source, sprite.x, sprite.y, sprite.width, sprite.height);
+                resize_image(
+                    &pixels.to_image(),
+                    target.width,
+                    target.height,
+                    filter,
+                    linear,
+                )
+            })
+            .collect();
+        // Extrusion first, so it can never overwrite a neighboring sprite
+        for ((sprite, image), &extrude) in scaled.iter().zip(&images).zip(extrudes) {
+            if extrude > 0 {
+                let mode = self.extrude_mode_for(&sprite.name);
+                extrude_sprite(page, image, sprite.x, sprite.y, extrude, mode);
+            }
+        }
+        for (sprite, image) in scaled.iter().zip(&images) {
+            self.draw_sprite(page, image, sprite.x, sprite.y);
+        }
+    }
+
+    /// Empty page, filled with the background color if one is set
+    fn new_page(&self, index: usize, width: u32, height: u32) -> Atlas {
+        let mut atlas = Atlas::new(index, width, height);
+        if let Some(color) = self.background {
+            atlas.image = RgbaImage::from_pixel(width, height, color);
+        }
+        atlas
+    }
+
+    /// Draw sprite pixels onto a page. Over a background they replace what is
+    /// there, so transparent sprite pixels stay transparent.
+    fn draw_sprite(&self, page: &mut RgbaImage, image: &RgbaImage, x: u32, y: u32) {
+        if self.background.is_some() {
+            imageops::replace(page, image, i64::from(x), i64::from(y));
+        } else {
+            imageops::overlay(page, image, i64::from(x), i64::from(y));
+        }
+    }
+
+    /// Final page dimensions for a layout (or the full bin with a fixed size),
+    /// after power-of-two, block alignment, size multiple and square rounding
+    fn final_size(&self, layout: &PackingLayout) -> (u32, u32) {
+        let (width, height) = if self.fixed_size {
+            (self.max_width, self.max_height)
+        } else {
+            (layout.max_x, layout.max_y)
+        };
+        self.round_size(width, height)
+    }
+
+    /// Apply power-of-two, block alignment, size multiple and square rounding
+    /// to a page size
+    fn round_size(&self, width: u32, height: u32) -> (u32, u32) {
+        let (mut final_width, mut final_height) = if self.power_of_two {
+            (next_power_of_two(width), next_power_of_two(height))
+        } else {
+            (width, height)
+        };
+        if self.block_align > 1 {
+            final_width = align_up(final_width, self.block_align);
+            final_height = align_up(final_height, self.block_align);
+        }
+        if self.size_multiple > 1 {
+            final_width = align_up(final_width, self.size_multiple);
+            final_height = align_up(final_height, self.size_multiple);
+        }
+        if self.square {
+            final_width = final_width.max(final_height);
+            final_height = final_width;
+        }
+
+        (final_width, final_height)
+    }
+
+    /// Log a summary line for a finished page
+    fn log_page(
+        &self,
+        index: usize,
+        final_width: u32,
+        final_height: u32,
+        layout: &PackingLayout,
+        heuristic: PackingHeuristic,
+        ordering: SpriteOrdering,
+    ) {
+        let optimization_info = if matches!(ordering, SpriteOrdering::Grid) {
+            " (grid)".to_string()
+        } else {
+            match (
+                self.heuristic == PackingHeuristic::Best,
+                self.tries_orderings(),
+            ) {
+                (true, true) => format!(" (best: {:?}, {:?})", heuristic, ordering),
+                (true, false) => format!(" (best: {:?})", heuristic),
+                (false, true) => format!(" (ordering: {:?})", ordering),
+                (false, false) => String::new(),
+            }
+        };
+
+        info!(
+            "Atlas {}: {}x{} with {} sprites ({:.1}% efficiency){}",
+            index,
+            final_width,
+            final_height,
+            layout.placements.len(),
+            layout.occupancy * 100.0,
+            optimization_info,
+        );
+    }
+
+    /// Padded cell width for a sprite as placed in the atlas
+    fn padded_width(&self, name: &str, width: u32) -> u32 {
+        let (padding_x, _, extrude) = self.spacing_for(name);
+        self.padded_size(width, padding_x, extrude)
+    }
+
+    /// Padded cell height for a sprite as placed in the atlas
+    fn padded_height(&self, name: &str, height: u32) -> u32 {
+        let (_, padding_y, extrude) = self.spacing_for(name);
+        self.padded_size(height, padding_y, extrude)
+    }
+
+    /// Compute the padded cell size for a sprite dimension, including block alignment.
+    /// When block_align is set, the cell is rounded up so that the sprite body
+    /// (at offset padding + extrude from the cell origin) lands on an aligned boundary.
+    /// This prevents VRAM block compression (BPTC, ASTC) from introducing edge artifacts
+    /// that shift the perceived position of sprites.
+    fn padded_size(&self, sprite_dim: u32, padding: u32, extrude: u32) -> u32 {
+        let raw = sprite_dim + padding * 2 + extrude * 2;
+        if self.block_align > 1 {
+            align_up(raw, self.block_align)
+        } else {
+            raw
+        }
+    }
+
+    /// Packing priority of a sprite, 0 unless overridden
+    fn priority_of(&self, name: &str) -> i32 {
+        self.sprite_settings
+            .get(name)
+            .and_then(|s| s.priority)
+            .unwrap_or(0)
+    }
+
+    /// Whether any sprite has a priority override
+    fn has_priorities(&self) -> bool {
+        self.sprite_settings.values().any(|s| s.priority.is_some())
+    }
+
+    /// Count placed sprites per priority, highest first, when any sprite has one
+    fn placed_priorities(&self, placements: &[SpritePlacement]) -> Vec<(i32, usize)> {
+        if !self.has_priorities() {
+            return Vec::new();
+        }
+        let mut counts = BTreeMap::new();
+        for placement in placements {
+            *counts
+                .entry(std::cmp::Reverse(self.priority_of(&placement.name)))
+                .or_insert(0) += 1;
+        }
+        counts
+            .into_iter()
+            .map(|(std::cmp::Reverse(priority), count)| (priority, count))
+            .collect()
+    }
+
+    /// Effective (padding_x, padding_y, extrude) for a sprite, applying any
+    /// per-sprite override. A padding override applies to both axes.
+    pub fn spacing_for(&self, name: &str) -> (u32, u32, u32) {
+        let settings = self.sprite_settings.get(name);
+        let (padding_x, padding_y) = match settings.and_then(|s| s.padding) {
+            Some(padding) => (padding, padding),
+            // The packer turns padded cells along with their sprites
+            None if self.allow_rotation => {
+                let padding = self.padding_x.max(self.padding_y);
+                (padding, padding)
+            }
+            None => (self.padding_x, self.padding_y),
+        };
+        (
+            padding_x,
+            padding_y,
+            settings.and_then(|s| s.extrude).unwrap_or(self.extrude),
+        )
+    }
+
+    fn extrude_mode_for(&self, name: &str) -> ExtrudeMode {
+        self.sprite_settings
+            .get(name)
+            .and_then(|s| s.extrude_mode)
+            .unwrap_or(self.extrude_mode)
+    }
+}
+
+/// Fill `extrude` pixels around a sprite drawn at (x, y). Clamp repeats the
+/// edge pixels; wrap takes them from the opposite edge, as if the sprite
+/// were tiled.
+fn extrude_sprite(
+    atlas: &mut image::RgbaImage,
+    img: &RgbaImage,
+    x: u32,
+    y: u32,
+    extrude: u32,
+    mode: ExtrudeMode,
+) {
+    let (w, h) = img.dimensions();
+    // Source row or column for the pixel `e` steps before the start or past
+    // the end of a sprite side `len` pixels long
+    let before = |e: u32, len: u32| match mode {
+        ExtrudeMode::Clamp => 0,
+        ExtrudeMode::Wrap => (len - e % len) % len,
+    };
+    let after = |e: u32, len: u32| match mode {
+        ExtrudeMode::Clamp => len - 1,
+        ExtrudeMode::Wrap => (e - 1) % len,
+    };
+
+    // Extrude edges
+    for e in 1..=extrude {
+        let (top, bottom) = (before(e, h), after(e, h));
+        let (left, right) = (before(e, w), after(e, w));
+
+        // Top edge
+        if y >= e {
+            for sx in 0..w {
+                let pixel = img.get_pixel(sx, top);
+                atlas.put_pixel(x + sx, y - e, *pixel);
+            }
+        }
+
+        // Bottom edge
+        for sx in 0..w {
+            let pixel = img.get_pixel(sx, bottom);
+            atlas.put_pixel(x + sx, y + h - 1 + e, *pixel);
+        }
+
+        // Left edge
+        if x >= e {
+            for sy in 0..h {
+                let pixel = img.get_pixel(left, sy);
+                atlas.put_pixel(x - e, y + sy, *pixel);
+            }
+        }
+
+        // Right edge
+        for sy in 0..h {
+            let pixel = img.get_pixel(right, sy);
+            atlas.put_pixel(x + w - 1 + e, y + sy, *pixel);
+        }
+
+        // Corners
+        if x >= e && y >= e {
+            let pixel = img.get_pixel(left, top);
+            atlas.put_pixel(x - e, y - e, *pixel);
+        }
+        if y >= e {
+            let pixel = img.get_pixel(right, top);
+            atlas.put_pixel(x + w - 1 + e, y - e, *pixel);
+        }
+        if x >= e {
+            let pixel = img.get_pixel(left, bottom);
+            atlas.put_pixel(x - e, y + h - 1 + e, *pixel);
+        }
+        let pixel = img.get_pixel(right, bottom);
+        atlas.put_pixel(x + w - 1 + e, y + h - 1 + e, *pixel);
+    }
+}
+
+/// Scale a pixel length or border, rounding to the nearest pixel
+fn scale_round(n: u32, scale: f32) -> u32 {
+    #[expect(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        reason = "scale is positive, result fits in u32"
+    )]
+    let scaled = (f64::from(n) * f64::from(scale)).round() as u32;
+    scaled
+}
+
+/// Scale a pixel position or border, rounding down
+fn scale_floor(n: u32, scale: f32) -> u32 {
+    #[expect(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        reason = "scale is positive, result fits in u32"
+    )]
+    let scaled = (f64::from(n) * f64::from(scale)).floor() as u32;
+    scaled
+}
+
+/// Sprite record for a scaled page, with the sprite placed at (x, y) and
+/// stored at w x h
+fn scale_sprite(sprite: &PackedSprite, x: u32, y: u32, w: u32, h: u32, scale: f32) -> PackedSprite {
+    let trim = &sprite.trim_info;
+    let (trimmed_width, trimmed_height) = if sprite.rotated { (h, w) } else { (w, h) };
+    let trim_info = if trim.was_trimmed() {
+        let offset_x = scale_floor(trim.offset_x.max(0).unsigned_abs(), scale);
+        let offset_y = scale_floor(trim.offset_y.max(0).unsigned_abs(), scale);
+        TrimInfo {
+            offset_x: offset_x.try_into().unwrap_or(i32::MAX),
+            offset_y: offset_y.try_into().unwrap_or(i32::MAX),
+            source_width: scale_round(trim.source_width, scale).max(offset_x + trimmed_width),
+            source_height: scale_round(trim.source_height, scale).max(offset_y + trimmed_height),
+            trimmed_width,
+            trimmed_height,
+        }
+    } else {
+        TrimInfo::untrimmed(trimmed_width, trimmed_height)
+    };
+
+    PackedSprite {
+        name: sprite.name.clone(),
+        path: sprite.path.clone(),
+        x,
+        y,
+        width: w,
+        height: h,
+        trim_info,
+        atlas_index: sprite.atlas_index,
+        input_index: sprite.input_index,
+        content_bounds: sprite.content_bounds.map(|bounds| ContentBounds {
+            x: scale_floor(bounds.x, scale),
+            y: scale_floor(bounds.y, scale),
+            width: scale_round(bounds.width, scale).max(1),
+            height: scale_round(bounds.height, scale).max(1),
+        }),
+        nine_slice: sprite.nine_slice.map(|borders| NineSlice {
+            left: scale_round(borders.left, scale),
+            top: scale_round(borders.top, scale),
+            right: scale_round(borders.right, scale),
+            bottom: scale_round(borders.bottom, scale),
+        }),
+        pivot: sprite.pivot,
+        rotated: sprite.rotated,
+    }
+}
+
+/// Free rectangles of a layout clipped to its final page, plus the strips
+/// the page extends beyond the bin after rounding its size
+fn free_space(layout: &PackingLayout, width: u32, height: u32) -> Vec<Rect> {
+    let page = Rect::new(0, 0, width, height);
+    let mut free: Vec<Rect> = layout
+        .free_rects
+        .iter()
+        .filter_map(|rect| rect.intersection(&page))
+        .collect();
+    if width > layout.bin_width {
+        free.push(Rect::new(
+            layout.bin_width,
+            0,
+            width - layout.bin_width,
+            height,
+        ));
+    }
+    if height > layout.bin_height {
+        free.push(Rect::new(
+            0,
+            layout.bin_height,
+            width.min(layout.bin_width),
+            height - layout.bin_height,
+        ));
+    }
+    free
+}
+
+/// Whether layout `a` places more sprites than `b` at the highest priority
+/// where their counts differ, or None when they place the same sprites
+/// per priority. Counts are listed highest priority first.
+fn places_more_by_priority(a: &[(i32, usize)], b: &[(i32, usize)]) -> Option<bool> {
+    let (mut a, mut b) = (a.iter().peekable(), b.iter().peekable());
+    loop {
+        match (a.peek(), b.peek()) {
+            (None, None) => return None,
+            (Some(_), None) => return Some(true),
+            (None, Some(_)) => return Some(false),
+            (Some(&&(priority_a, count_a)), Some(&&(priority_b, count_b))) => {
+                if priority_a != priority_b {
+                    return Some(priority_a > priority_b);
+                }
+                if count_a != count_b {
+                    return Some(count_a > count_b);
+                }
+                a.next();
+                b.next();
+            }
+        }
+    }
+}
+
+/// Share of a page's area covered by its sprites
+fn sprite_occupancy(atlas: &Atlas) -> f64 {
+    let sprite_area: u64 = atlas
+        .sprites
+        .iter()
+        .map(|s| u64::from(s.width) * u64::from(s.height))
+        .sum();
+    #[expect(
+        clippy::cast_precision_loss,
+        reason = "approximation acceptable for occupancy"
+    )]
+    {
+        sprite_area as f64 / (u64::from(atlas.width) * u64::from(atlas.height)) as f64
+    }
+}
+
+/// Convert a placement into the public sprite record
+fn into_packed(placement: SpritePlacement) -> PackedSprite {
+    PackedSprite {
+        name: placement.name,
+        path: placement.path,
+        x: placement.x,
+        y: placement.y,
+        width: placement.width,
+        height: placement.height,
+        trim_info: placement.trim_info,
+        atlas_index: placement.atlas_index,
+        input_index: placement.input_index,
+        content_bounds: placement.content_bounds,
+        nine_slice: placement.nine_slice,
+        pivot: placement.pivot,
+        rotated: placement.rotated,
+    }
+}
+
+/// Round up to the next multiple of `align`. `align` must be >= 2.
+fn align_up(n: u32, align: u32) -> u32 {
+    debug_assert!(align >= 2, "align_up requires align >= 2, got {align}");
+    n.div_ceil(align) * align
+}
+
+fn next_power_of_two(n: u32) -> u32 {
+    if n == 0 {
+        return 1;
+    }
+    let mut v = n - 1;
+    v |= v >> 1;
+    v |= v >> 2;
+    v |= v >> 4;
+    v |= v >> 8;
+    v |= v >> 16;
+    v + 1
+}
+
+/// Fisher-Yates shuffle driven by a SplitMix64 generator, so a seed always
+/// produces the same permutation on every platform
+fn shuffle(indices: &mut [usize], seed: u64) {
+    let mut state = seed;
+    let mut next = || {
+        state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    };
+
+    for i in (1..indices.len()).rev() {
+        #[expect(
+            clippy::cast_possible_truncation,
+            reason = "result is at most i, which is a usize"
+        )]
+        let j = (next() % (i as u64 + 1)) as usize;
+        indices.swap(i, j);
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::sprite::TrimInfo;
+    use image::Rgba;
+
+    #[test]
+    fn test_align_up() {
+        assert_eq!(align_up(0, 4), 0);
+        assert_eq!(align_up(1, 4), 4);
+        assert_eq!(align_up(3, 4), 4);
+        assert_eq!(align_up(4, 4), 4);
+        assert_eq!(align_up(5, 4), 8);
+        assert_eq!(align_up(85, 4), 88);
+        assert_eq!(align_up(8, 8), 8);
+        assert_eq!(align_up(9, 8), 16);
+    }
+
+    #[test]
+    fn test_block_align_sprite_positions() {
+        // With padding=2, extrude=2, block_align=4:
+        // padded cell = sprite + 2*2 + 2*2 = sprite + 8, rounded up to next multiple of 4.
+        // For a 20x20 sprite: padded = 28, already aligned.
+        // sprite_x = rect.x + 2 + 2 = rect.x + 4.
+        // Since padded is 28 (multiple of 4), rect.x is a multiple of 28's packing position.
+        // When rect.x = 0: sprite_x = 4, which is 4-aligned.
+        let sprites = vec![SourceSprite {
+            path: std::path::PathBuf::from("test.png"),
+            name: "test".to_string(),
+            image: image::RgbaImage::new(20, 20),
+            trim_info: TrimInfo::untrimmed(20, 20),
+            input_index: 0,
+            content_bounds: None,
+            nine_slice: None,
+            pivot: None,
+            companions: Vec::new(),
+        }];
+
+        let builder = AtlasBuilder::new(256, 256)
+            .padding(2)
+            .extrude(2)
+            .block_align(4);
+
+        let result = builder.build(sprites).unwrap();
+        let packed = &result[0].sprites[0];
+
+        assert_eq!(packed.x % 4, 0, "sprite x={} should be 4-aligned", packed.x);
+        assert_eq!(packed.y % 4, 0, "sprite y={} should be 4-aligned", packed.y);
+    }
+
+    #[test]
+    fn test_block_align_all_sprites_aligned() {
+        // Pack multiple sprites with different sizes and verify all are block-aligned.
+        let sizes = [(20, 20), (30, 25), (15, 40), (50, 10), (85, 85)];
+        let sprites: Vec<SourceSprite> = sizes
+            .iter()
+            .enumerate()
+            .map(|(i, (w, h))| SourceSprite {
+                path: std::path::PathBuf::from(format!("sprite_{}.png", i)),
+                name: format!("sprite_{}", i),
+                image: image::RgbaImage::new(*w, *h),
+                trim_info: TrimInfo::untrimmed(*w, *h),
+                input_index: 0,
+                content_bounds: None,
+                nine_slice: None,
+                pivot: None,
+                companions: Vec::new(),
+            })
+            .collect();
+
+        let builder = AtlasBuilder::new(512, 512)
+            .padding(2)
+            .extrude(2)
+            .block_align(4);
+
+        let result = builder.build(sprites).unwrap();
+        for packed in &result[0].sprites {
+            assert_eq!(
+                packed.x % 4,
+                0,
+                "sprite '{}' x={} should be 4-aligned",
+                packed.name,
+                packed.x
+            );
+            assert_eq!(
+                packed.y % 4,
+                0,
+                "sprite '{}' y={} should be 4-aligned",
+                packed.name,
+                packed.y
+            );
+        }
+    }
+
+    #[test]
+    fn test_block_align_disabled_by_default() {
+        // Without block_align, sprites may land on non-aligned positions.
+        let sprites = vec![SourceSprite {
+            path: std::path::PathBuf::from("test.png"),
+            name: "test".to_string(),
+            image: image::RgbaImage::new(20, 20),
+            trim_info: TrimInfo::untrimmed(20, 20),
+            input_index: 0,
+            content_bounds: None,
+            nine_slice: None,
+            pivot: None,
+            companions: Vec::new(),
+        }];
+
+        let builder = AtlasBuilder::new(256, 256).padding(1).extrude(0);
+
+        let result = builder.build(sprites).unwrap();
+        let packed = &result[0].sprites[0];
+        // With padding=1, extrude=0: sprite_x = rect.x + 1
+        // rect.x = 0, so sprite_x = 1, which is NOT 4-aligned
+        assert_eq!(packed.x, 1);
+        assert_eq!(packed.y, 1);
+    }
+
+    #[test]
+    fn test_free_rects_cover_unused_page_area() {
+        let sprites = [(10, 10), (20, 10)]
+            .iter()
+            .enumerate()
+            .map(|(i, &(w, h))| SourceSprite {
+                path: std::path::PathBuf::from(format!("{}.png", i)),
+                name: format!("{}", i),
+                image: image::RgbaImage::new(w, h),
+                trim_info: TrimInfo::untrimmed(w, h),
+                input_index: i,
+                content_bounds: None,
+                nine_slice: None,
+                pivot: None,
+                companions: Vec::new(),
+            })
+            .collect();
+
+        // 30x10 of sprites on a 32x16 power-of-two page
+        let builder = AtlasBuilder::new(256, 256).padding(0).power_of_two(true);
+        let atlas = &builder.build(sprites).unwrap()[0];
+        assert_eq!((atlas.width, atlas.height), (32, 16));
+        assert_eq!(atlas.free_area(), 32 * 16 - 30 * 10);
+        for sprite in &atlas.sprites {
+            let rect = Rect::new(sprite.x, sprite.y, sprite.width, sprite.height);
+            assert!(atlas.free_rects.iter().all(|free| !free.intersects(&rect)));
+        }
+    }
+
+    #[test]
+    fn test_size_multiple_rounds_page_only() {
+        let make_sprites = || {
+            vec![SourceSprite {
+                path: std::path::PathBuf::from("test.png"),
+                name: "test".to_string(),
+                image: image::RgbaImage::new(10, 30),
+                trim_info: TrimInfo::untrimmed(10, 30),
+                input_index: 0,
+                content_bounds: None,
+                nine_slice: None,
+                pivot: None,
+                companions: Vec::new(),
+            }]
+        };
+
+        // 12x32 with padding, rounded up to multiples of 8
+        let builder = AtlasBuilder::new(256, 256).padding(1).size_multiple(8);
+        let atlas = &builder.build(make_sprites()).unwrap()[0];
+        assert_eq!((atlas.width, atlas.height), (16, 32));
+        // Unlike block_align, sprite positions are untouched
+        assert_eq!((atlas.sprites[0].x, atlas.sprites[0].y), (1, 1));
+
+        // Applied on top of power-of-two: 16x32 is already a multiple of 8
+        let pot = AtlasBuilder::new(256, 256)
+            .padding(1)
+            .power_of_two(true)
+            .size_multiple(8);
+        let atlas = &pot.build(make_sprites()).unwrap()[0];
+        assert_eq!((atlas.width, atlas.height), (16, 32));
+    }
+
+    #[test]
+    fn test_rotation_fills_gap() {
+        let sprite = |name: &str, width: u32, height: u32| {
+            let mut image = image::RgbaImage::new(width, height);
+            image.put_pixel(0, 0, Rgba([255, 0, 0, 255]));
+            SourceSprite {
+                path: std::path::PathBuf::from(name),
+                name: name.to_string(),
+                image,
+                trim_info: TrimInfo::untrimmed(width, height),
+                input_index: 0,
+                content_bounds: None,
+                nine_slice: None,
+                pivot: None,
+                companions: Vec::new(),
+            }
+        };
+        let make_sprites = || vec![sprite("tall", 30, 40), sprite("wide", 40, 10)];
+
+        // The 10x40 gap beside the tall sprite only takes the wide one turned
+        let upright = AtlasBuilder::new(40, 40).padding(0);
+        assert_eq!(upright.build(make_sprites()).unwrap().len(), 2);
+
+        let rotating = AtlasBuilder::new(40, 40).padding(0).allow_rotation(true);
+        let atlases = rotating.build(make_sprites()).unwrap();
+        assert_eq!(atlases.len(), 1);
+        let wide = atlases[0].find("wide").unwrap();
+        assert!(wide.rotated);
+        assert_eq!((wide.x, wide.y, wide.width, wide.height), (30, 0, 10, 40));
+        // Turned clockwise, the top-left pixel ends up top-right
+        assert_eq!(atlases[0].image.get_pixel(39, 0).0[0], 255);
+        assert_eq!(
+            atlases[0].sprite_image(wide, false),
+            make_sprites()[1].image
+        );
+
+        // A sprite too wide for the page still fits turned
+        let narrow = AtlasBuilder::new(20, 60).padding(0);
+        assert!(narrow.check_fits(&sprite("bar", 50, 10)).is_err());
+        assert!(
+            narrow
+                .allow_rotation(true)
+                .check_fits(&sprite("bar", 50, 10))
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_size_search_shrinks_page() {
+        let make_sprites = |sizes: &[(u32, u32)]| -> Vec<SourceSprite> {
+            sizes
+                .iter()
+                .enumerate()
+                .map(|(i, &(w, h))| SourceSprite {
+                    path: std::path::PathBuf::from(format!("sprite_{}.png", i)),
+                    name: format!("sprite_{}", i),
+                    image: image::RgbaImage::new(w, h),
+                    trim_info: TrimInfo::untrimmed(w, h),
+                    input_index: i,
+                    content_bounds: None,
+                    nine_slice: None,
+                    pivot: None,
+                    companions: Vec::new(),
+                })
+                .collect()
+        };
+        let squares = [(32, 32); 4];
+
+        // The full bin lays the squares out in a row, squared up to 128x128
+        let square = AtlasBuilder::new(256, 256).padding(0).square(true);
+        let page = &square.layout(make_sprites(&squares)).unwrap()[0];
+        assert_eq!((page.width, page.height), (128, 128));
+
+        let searched = AtlasBuilder::new(256, 256)
+            .padding(0)
+            .square(true)
+            .size_search(true);
+        let page = &searched.layout(make_sprites(&squares)).unwrap()[0];
+        assert_eq!((page.width, page.height), (64, 64));
+        assert_eq!(page.sprites.len(), 4);
+
+        // Never worse than cropping the full bin
+        let mixed = [(50, 20), (20, 70), (33, 33), (10, 90), (64, 8), (25, 40)];
+        for pot in [false, true] {
+            let plain = AtlasBuilder::new(256, 256).power_of_two(pot);
+            let page = &plain.layout(make_sprites(&mixed)).unwrap()[0];
+            let plain_area = page.width * page.height;
+
+            let searched = AtlasBuilder::new(256, 256)
+                .power_of_two(pot)
+                .size_search(true);
+            let pages = searched.layout(make_sprites(&mixed)).unwrap();
+            assert_eq!(pages.len(), 1);
+            assert!(pages[0].width * pages[0].height <= plain_area);
+        }
+    }
+
+    #[test]
+    fn test_fixed_size_keeps_full_bin() {
+        let make_sprites = || {
+            vec![SourceSprite {
+                path: std::path::PathBuf::from("test.png"),
+                name: "test".to_string(),
+                image: image::RgbaImage::new(10, 30),
+                trim_info: TrimInfo::untrimmed(10, 30),
+                input_index: 0,
+                content_bounds: None,
+                nine_slice: None,
+                pivot: None,
+                companions: Vec::new(),
+            }]
+        };
+
+        let fixed = AtlasBuilder::new(100, 60)
+            .fixed_size(true)
+            .size_search(true);
+        let atlas = &fixed.build(make_sprites()).unwrap()[0];
+        assert_eq!((atlas.width, atlas.height), (100, 60));
+        assert_eq!(atlas.image.dimensions(), (100, 60));
+
+        // Power-of-two rounds the fixed size, not the content
+        let pot = AtlasBuilder::new(100, 60)
+            .fixed_size(true)
+            .power_of_two(true);
+        let layout = &pot.layout(make_sprites()).unwrap()[0];
+        assert_eq!((layout.width, layout.height), (128, 64));
+    }
+
+    #[test]
+    fn test_max_sprites_per_page_starts_new_page() {
+        let sprites: Vec<SourceSprite> = (0..5)
+            .map(|i| SourceSprite {
+                path: std::path::PathBuf::from(format!("sprite_{}.png", i)),
+                name: format!("sprite_{}", i),
+                image: image::RgbaImage::new(10, 10),
+                trim_info: TrimInfo::untrimmed(10, 10),
+                input_index: 0,
+                content_bounds: None,
+                nine_slice: None,
+                pivot: None,
+                companions: Vec::new(),
+            })
+            .collect();
+
+        let builder = AtlasBuilder::new(256, 256).max_sprites_per_page(Some(2));
+        let atlases = builder.build(sprites).unwrap();
+
+        let counts: Vec<usize> = atlases.iter().map(|a| a.sprites.len()).collect();
+        assert_eq!(counts, vec![2, 2, 1]);
+    }
+
+    #[test]
+    fn test_grid_places_sprites_in_input_order() {
+        let sizes = [(10, 10), (6, 4), (8, 12), (2, 2)];
+        // Reversed, like the loader's largest-first sort might leave them
+        let sprites: Vec<SourceSprite> = sizes
+            .iter()
+            .enumerate()
+            .rev()
+            .map(|(i, &(w, h))| SourceSprite {
+                path: std::path::PathBuf::from(format!("tile_{}.png", i)),
+                name: format!("tile_{}", i),
+                image: image::RgbaImage::new(w, h),
+                trim_info: TrimInfo::untrimmed(w, h),
+                input_index: i,
+                content_bounds: None,
+                nine_slice: None,
+                pivot: None,
+                companions: Vec::new(),
+            })
+            .collect();
+
+        // 10x12 cells with padding make a 12x14 pitch, three to a row
+        let builder = AtlasBuilder::new(40, 100)
+            .padding(1)
+            .grid(Some(GridCell::Auto));
+        let atlases = builder.build(sprites.clone()).unwrap();
+        assert_eq!(atlases.len(), 1);
+        assert_eq!((atlases[0].width, atlases[0].height), (36, 28));
+        let positions: Vec<(&str, u32, u32)> = atlases[0]
+            .sprites
+            .iter()
+            .map(|s| (s.name.as_str(), s.x, s.y))
+            .collect();
+        assert_eq!(
+            positions,
+            vec![
+                ("tile_0", 1, 1),
+                ("tile_1", 13, 1),
+                ("tile_2", 25, 1),
+                ("tile_3", 1, 15),
+            ]
+        );
+        let packing = atlases[0].packing.as_ref().unwrap();
+        assert_eq!(packing.heuristic, None);
+        assert_eq!(packing.ordering, "grid");
+
+        // Fixed cells overflow onto a second page
+        let builder = AtlasBuilder::new(40, 14)
+            .padding(1)
+            .grid(Some(GridCell::Fixed {
+                width: 18,
+                height: 12,
+            }));
+        let layouts = builder.layout(sprites.clone()).unwrap();
+        let counts: Vec<usize> = layouts.iter().map(|l| l.sprites.len()).collect();
+        assert_eq!(counts, vec![2, 2]);
+        assert_eq!(layouts[1].sprites[1].name, "tile_3");
+        assert_eq!((layouts[1].sprites[1].x, layouts[1].sprites[1].y), (21, 1));
+
+        let builder = AtlasBuilder::new(40, 100).grid(Some(GridCell::Fixed {
+            width: 8,
+            height: 8,
+        }));
+        let err = builder.build(sprites.clone()).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<BentoError>(),
+            Some(BentoError::GridCellTooSmall { .. })
+        ));
+
+        let builder = AtlasBuilder::new(40, 100).grid(Some(GridCell::Fixed {
+            width: 64,
+            height: 12,
+        }));
+        let err = builder.build(sprites).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<BentoError>(),
+            Some(BentoError::GridCellTooLarge { .. })
+        ));
+    }
+
+    #[test]
+    fn test_separate_horizontal_and_vertical_padding() {
+        let sprites: Vec<SourceSprite> = (0..2)
+            .map(|i| SourceSprite {
+                path: std::path::PathBuf::from(format!("frame_{}.png", i)),
+                name: format!("frame_{}", i),
+                image: image::RgbaImage::new(10, 10),
+                trim_info: TrimInfo::untrimmed(10, 10),
+                input_index: i,
+                content_bounds: None,
+                nine_slice: None,
+                pivot: None,
+                companions: Vec::new(),
+            })
+            .collect();
+
+        let builder = AtlasBuilder::new(100, 14)
+            .padding(1)
+            .padding_x(0)
+            .padding_y(2);
+        let atlases = builder.build(sprites.clone()).unwrap();
+        assert_eq!((atlases[0].width, atlases[0].height), (20, 14));
+        let mut positions: Vec<(u32, u32)> =
+            atlases[0].sprites.iter().map(|s| (s.x, s.y)).collect();
+        positions.sort_unstable();
+        assert_eq!(positions, vec![(0, 2), (10, 2)]);
+
+        // Rotated cells would swap the axes, so rotation pads both by the larger
+        let atlases = builder.allow_rotation(true).build(sprites).unwrap();
+        assert_eq!((atlases[0].width, atlases[0].height), (28, 14));
+    }
+
+    #[test]
+    fn test_keep_together_moves_split_group_to_next_page() {
+        let sprite = |name: String| SourceSprite {
+            path: std::path::PathBuf::from(format!("{}.png", name)),
+            name,
+            image: image::RgbaImage::new(10, 10),
+            trim_info: TrimInfo::untrimmed(10, 10),
+            input_index: 0,
+            content_bounds: None,
+            nine_slice: None,
+            pivot: None,
+            companions: Vec::new(),
+        };
+        // A 30x30 page holds nine sprites: every tile and two walk frames
+        let sprites: Vec<SourceSprite> = (0..7)
+            .map(|i| sprite(format!("tile_{}", i)))
+            .chain((0..4).map(|i| sprite(format!("walk_{}", i))))
+            .collect();
+        let page_names = |atlases: &[Atlas]| -> Vec<Vec<String>> {
+            atlases
+                .iter()
+                .map(|a| {
+                    let mut names: Vec<String> = a.sprites.iter().map(|s| s.name.clone()).collect();
+                    names.sort();
+                    names
+                })
+                .collect()
+        };
+
+        let builder = AtlasBuilder::new(30, 30).padding(0);
+        let split = builder.build(sprites.clone()).unwrap();
+        assert_eq!(split[0].sprites.len(), 9);
+
+        let builder = builder.keep_together(vec![glob::Pattern::new("walk_*").unwrap()]);
+        let pages = page_names(&builder.build(sprites.clone()).unwrap());
+        assert_eq!(pages.len(), 2);
+        assert_eq!(pages[0].len(), 7);
+        assert_eq!(pages[1], vec!["walk_0", "walk_1", "walk_2", "walk_3"]);
+        let layouts = builder.layout(sprites).unwrap();
+        assert_eq!(layouts[1].sprites.len(), 4);
+
+        // A group larger than a page still gets packed, split across pages
+        let sprites: Vec<SourceSprite> = (0..11).map(|i| sprite(format!("walk_{}", i))).collect();
+        let atlases = builder.build(sprites).unwrap();
+        let counts: Vec<usize> = atlases.iter().map(|a| a.sprites.len()).collect();
+        assert_eq!(counts, vec![9, 2]);
+    }
+
+    #[test]
+    fn test_progress_reports_placed_sprites_and_rendered_pages() {
+        let sprites: Vec<SourceSprite> = (0..3)
+            .map(|i| SourceSprite {
+                path: std::path::PathBuf::from(format!("sprite_{}.png", i)),
+                name: format!("sprite_{}", i),
+                image: image::RgbaImage::new(10, 10),
+                trim_info: TrimInfo::untrimmed(10, 10),
+                input_index: i,
+                content_bounds: None,
+                nine_slice: None,
+                pivot: None,
+                companions: Vec::new(),
+            })
+            .collect();
+
+        let events = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = events.clone();
+        let builder = AtlasBuilder::new(256, 256)
+            .max_sprites_per_page(Some(2))
+            .progress(ProgressCallback::new(move |event| {
+                sink.lock().unwrap().push(event);
+            }));
+        builder.build(sprites).unwrap();
+
+        assert_eq!(
+            *events.lock().unwrap(),
+            [
+                ProgressEvent::SpritesPlaced { done: 2, total: 3 },
+                ProgressEvent::AtlasRendered { index: 0 },
+                ProgressEvent::SpritesPlaced { done: 3, total: 3 },
+                ProgressEvent::AtlasRendered { index: 1 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sprite_settings_override_padding_and_extrude() {
+        let sprites: Vec<SourceSprite> = ["tile", "icon"]
+            .iter()
+            .enumerate()
+            .map(|(i, name)| SourceSprite {
+                path: std::path::PathBuf::from(format!("{}.png", name)),
+                name: name.to_string(),
+                image: image::RgbaImage::from_pixel(8, 8, image::Rgba([255, 0, 0, 255])),
+                trim_info: TrimInfo::untrimmed(8, 8),
+                input_index: i,
+                content_bounds: None,
+                nine_slice: None,
+                pivot: None,
+                companions: Vec::new(),
+            })
+            .collect();
+
+        let mut settings = HashMap::new();
+        settings.insert(
+            "tile".to_string(),
+            SpriteSettings {
+                padding: Some(0),
+                extrude: Some(2),
+                extrude_mode: None,
+                priority: None,
+            },
+        );
+
+        // One sprite per page so each page size reflects only that sprite's spacing
+        let builder = AtlasBuilder::new(256, 256)
+            .padding(3)
+            .max_sprites_per_page(Some(1))
+            .sprite_settings(settings);
+        let atlases = builder.build(sprites).unwrap();
+
+        // Tile: 8 + 2*2 extrude, no padding
+        let tile_atlas = atlases.iter().find(|a| a.find("tile").is_some()).unwrap();
+        let tile = tile_atlas.find("tile").unwrap();
+        assert_eq!((tile.x, tile.y), (2, 2));
+        assert_eq!((tile_atlas.width, tile_atlas.height), (12, 12));
+        assert_eq!(tile_atlas.image.get_pixel(0, 0)[3], 255);
+
+        // Icon: 8 + 2*3 global padding, no extrusion
+        let icon_atlas = atlases.iter().find(|a| a.find("icon").is_some()).unwrap();
+        let icon = icon_atlas.find("icon").unwrap();
+        assert_eq!((icon.x, icon.y), (3, 3));
+        assert_eq!((icon_atlas.width, icon_atlas.height), (14, 14));
+        assert_eq!(icon_atlas.image.get_pixel(2, 3)[3], 0);
+    }
+
+    #[test]
+    fn test_scale_atlas() {
+        let sprites = vec![
+            SourceSprite {
+                path: std::path::PathBuf::from("a.png"),
+                name: "a".to_string(),
+                image: image::RgbaImage::from_pixel(8, 8, image::Rgba([255, 0, 0, 255])),
+                trim_info: TrimInfo::untrimmed(8, 8),
+                input_index: 0,
+                content_bounds: None,
+                nine_slice: None,
+                pivot: None,
+                companions: Vec::new(),
+            },
+            SourceSprite {
+                path: std::path::PathBuf::from("b.png"),
+                name: "b".to_string(),
+                image: image::RgbaImage::from_pixel(6, 4, image::Rgba([0, 0, 255, 255])),
+                trim_info: TrimInfo {
+                    offset_x: 2,
+                    offset_y: 4,
+                    source_width: 10,
+                    source_height: 10,
+                    trimmed_width: 6,
+                    trimmed_height: 4,
+                },
+                input_index: 1,
+                content_bounds: None,
+                nine_slice: Some(NineSlice {
+                    left: 3,
+                    top: 0,
+                    right: 3,
+                    bottom: 0,
+                }),
+                pivot: None,
+                companions: Vec::new(),
+            },
+        ];
+        let builder = AtlasBuilder::new(256, 256).padding(2).extrude(2);
+        let atlas = builder.build(sprites).unwrap().remove(0);
+
+        let half = builder.scale_atlas(&atlas, 0.5, FilterType::Triangle, false);
+        assert_eq!(half.scale, 0.5);
+        assert_eq!(
+            (half.width, half.height),
+            (atlas.width.div_ceil(2), atlas.height.div_ceil(2))
+        );
+
+        let a = atlas.find("a").unwrap();
+        let half_a = half.find("a").unwrap();
+        assert_eq!((half_a.x, half_a.y), (a.x / 2, a.y / 2));
+        assert_eq!((half_a.width, half_a.height), (4, 4));
+        assert!(!half_a.trim_info.was_trimmed());
+        // Extruded by one pixel at half size
+        assert_eq!(
+            *half.image.get_pixel(half_a.x - 1, half_a.y),
+            image::Rgba([255, 0, 0, 255])
+        );
+
+        let half_b = half.find("b").unwrap();
+        assert_eq!((half_b.width, half_b.height), (3, 2));
+        assert_eq!(half_b.trim_info.offset_x, 1);
+        assert_eq!(half_b.trim_info.offset_y, 2);
+        assert_eq!(half_b.trim_info.source_width, 5);
+        assert_eq!(half_b.nine_slice.map(|n| (n.left, n.top)), Some((2, 0)));
+        assert_eq!(
+            *half.image.get_pixel(half_b.x + 1, half_b.y + 1),
+            image::Rgba([0, 0, 255, 255])
+        );
+
+        // Mip regions follow the scaled extrusion
+        assert_eq!(builder.mipmaps(&half, 1).len(), 1);
+    }
+
+    #[test]
+    fn test_total_budget_exceeded() {
+        let make_sprites = || -> Vec<SourceSprite> {
+            (0..4)
+                .map(|i| SourceSprite {
+                    path: std::path::PathBuf::from(format!("sprite_{}.png", i)),
+                    name: format!("sprite_{}", i),
+                    image: image::RgbaImage::new(10, 10),
+                    trim_info: TrimInfo::untrimmed(10, 10),
+                    input_index: i,
+                    content_bounds: None,
+                    nine_slice: None,
+                    pivot: None,
+                    companions: Vec::new(),
+                })
+                .collect()
+        };
+
+        // Each sprite gets its own 10x10 page: 400 pixels / 1600 bytes in total
+        let builder = || {
+            AtlasBuilder::new(64, 64)
+                .padding(0)
+                .max_sprites_per_page(Some(1))
+        };
+
+        assert!(
+            builder()
+                .max_total_pixels(Some(400))
+                .build(make_sprites())
+                .is_ok()
+        );
+        let err = builder()
+            .max_total_pixels(Some(399))
+            .build(make_sprites())
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<BentoError>(),
+            Some(BentoError::BudgetExceeded { unit: "pixels", .. })
+        ));
+
+        assert!(
+            builder()
+                .max_total_bytes(Some(1600))
+                .layout(make_sprites())
+                .is_ok()
+        );
+        assert!(
+            builder()
+                .max_total_bytes(Some(1599))
+                .layout(make_sprites())
+                .is_err()
+        );
     }
-}
 
-/// Round up to the next multiple of `align`. `align` must be >= 2.
-fn align_up(n: u32, align: u32) -> u32 {
-    debug_assert!(align >= 2, "align_up requires align >= 2, got {align}");
-    n.div_ceil(align) * align
-}
+    #[test]
+    fn test_max_atlases() {
+        let make_sprites = |names: &[&str]| -> Vec<SourceSprite> {
+            names
+                .iter()
+                .map(|&name| SourceSprite::from_image(name, image::RgbaImage::new(10, 10)))
+                .collect()
+        };
+        // One sprite per page
+        let builder = AtlasBuilder::new(16, 16).padding(0).max_atlases(Some(2));
+
+        let names = ["a.png", "b.png"];
+        assert_eq!(builder.build(make_sprites(&names)).unwrap().len(), 2);
+
+        let names = ["a.png", "b.png", "c.png", "d.png"];
+        let err = builder.build(make_sprites(&names)).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<BentoError>(),
+            Some(BentoError::TooManyAtlases {
+                limit: 2,
+                count: 2,
+                ..
+            })
+        ));
+        assert!(err.to_string().ends_with("2 left over: c.png, d.png"));
+        assert!(builder.layout(make_sprites(&names)).is_err());
 
-fn next_power_of_two(n: u32) -> u32 {
-    if n == 0 {
-        return 1;
+        // Existing pages count towards the limit
+        let existing = builder.build(make_sprites(&["a.png"])).unwrap();
+        assert!(
+            builder
+                .append(existing.clone(), make_sprites(&["b.png"]))
+                .is_ok()
+        );
+        assert!(
+            builder
+                .append(existing, make_sprites(&["b.png", "c.png"]))
+                .is_err()
+        );
     }
-    let mut v = n - 1;
-    v |= v >> 1;
-    v |= v >> 2;
-    v |= v >> 4;
-    v |= v >> 8;
-    v |= v >> 16;
-    v + 1
-}
 
-#[cfg(test)]
-#[allow(clippy::unwrap_used)]
-mod tests {
-    use super::*;
-    use crate::sprite::TrimInfo;
-    use image::Rgba;
+    #[test]
+    fn test_page_placing_nothing_fails() {
+        let sprites = || -> Vec<SourceSprite> {
+            ["b.png", "a.png"]
+                .iter()
+                .map(|&name| SourceSprite::from_image(name, image::RgbaImage::new(10, 10)))
+                .collect()
+        };
+        // A zero limit leaves every page empty; it used to retry forever
+        let builder = AtlasBuilder::new(16, 16).max_sprites_per_page(Some(0));
+
+        let err = builder.build(sprites()).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<BentoError>(),
+            Some(BentoError::NothingPlaced { index: 0, count: 2, names })
+                if names == "a.png, b.png"
+        ));
+        assert!(builder.layout(sprites()).is_err());
+    }
 
     #[test]
-    fn test_align_up() {
-        assert_eq!(align_up(0, 4), 0);
-        assert_eq!(align_up(1, 4), 4);
-        assert_eq!(align_up(3, 4), 4);
-        assert_eq!(align_up(4, 4), 4);
-        assert_eq!(align_up(5, 4), 8);
-        assert_eq!(align_up(85, 4), 88);
-        assert_eq!(align_up(8, 8), 8);
-        assert_eq!(align_up(9, 8), 16);
+    fn test_layout_matches_build() {
+        let sizes = [(40, 30), (25, 60), (70, 10), (15, 15), (50, 50)];
+        let make_sprites = || -> Vec<SourceSprite> {
+            sizes
+                .iter()
+                .enumerate()
+                .map(|(i, (w, h))| SourceSprite {
+                    path: std::path::PathBuf::from(format!("sprite_{}.png", i)),
+                    name: format!("sprite_{}", i),
+                    image: image::RgbaImage::new(*w, *h),
+                    trim_info: TrimInfo::untrimmed(*w, *h),
+                    input_index: i,
+                    content_bounds: None,
+                    nine_slice: None,
+                    pivot: None,
+                    companions: Vec::new(),
+                })
+                .collect()
+        };
+
+        let builder = AtlasBuilder::new(96, 96).padding(1).power_of_two(true);
+        let atlases = builder.build(make_sprites()).unwrap();
+        let layouts = builder.layout(make_sprites()).unwrap();
+
+        assert_eq!(atlases.len(), layouts.len());
+        for (atlas, layout) in atlases.iter().zip(&layouts) {
+            assert_eq!((atlas.width, atlas.height), (layout.width, layout.height));
+            let atlas_rects: Vec<_> = atlas
+                .sprites
+                .iter()
+                .map(|s| (s.name.clone(), s.x, s.y))
+                .collect();
+            let layout_rects: Vec<_> = layout
+                .sprites
+                .iter()
+                .map(|s| (s.name.clone(), s.x, s.y))
+                .collect();
+            assert_eq!(atlas_rects, layout_rects);
+        }
     }
 
     #[test]
-    fn test_block_align_sprite_positions() {
-        // With padding=2, extrude=2, block_align=4:
-        // padded cell = sprite + 2*2 + 2*2 = sprite + 8, rounded up to next multiple of 4.
-        // For a 20x20 sprite: padded = 28, already aligned.
-        // sprite_x = rect.x + 2 + 2 = rect.x + 4.
-        // Since padded is 28 (multiple of 4), rect.x is a multiple of 28's packing position.
-        // When rect.x = 0: sprite_x = 4, which is 4-aligned.
-        let sprites = vec![SourceSprite {
-            path: std::path::PathBuf::from("test.png"),
-            name: "test".to_string(),
-            image: image::RgbaImage::new(20, 20),
-            trim_info: TrimInfo::untrimmed(20, 20),
-        }];
+    fn test_shuffle_is_seeded_permutation() {
+        let shuffled = |seed| {
+            let mut indices: Vec<usize> = (0..20).collect();
+            shuffle(&mut indices, seed);
+            indices
+        };
 
-        let builder = AtlasBuilder::new(256, 256)
-            .padding(2)
-            .extrude(2)
-            .block_align(4);
+        assert_eq!(shuffled(7), shuffled(7));
+        assert_ne!(shuffled(7), shuffled(8));
+        let mut sorted = shuffled(7);
+        sorted.sort_unstable();
+        assert_eq!(sorted, (0..20).collect::<Vec<_>>());
+    }
 
-        let result = builder.build(sprites).unwrap();
-        let packed = &result[0].sprites[0];
+    #[test]
+    fn test_trials_are_deterministic() {
+        let sizes = [(40, 30), (25, 60), (70, 10), (15, 15), (50, 50), (33, 21)];
+        let make_sprites = || -> Vec<SourceSprite> {
+            sizes
+                .iter()
+                .enumerate()
+                .map(|(i, (w, h))| SourceSprite {
+                    path: std::path::PathBuf::from(format!("sprite_{}.png", i)),
+                    name: format!("sprite_{}", i),
+                    image: image::RgbaImage::new(*w, *h),
+                    trim_info: TrimInfo::untrimmed(*w, *h),
+                    input_index: i,
+                    content_bounds: None,
+                    nine_slice: None,
+                    pivot: None,
+                    companions: Vec::new(),
+                })
+                .collect()
+        };
+        let builder = AtlasBuilder::new(256, 256)
+            .pack_mode(PackMode::Best)
+            .trials(16)
+            .seed(42);
+        assert_eq!(builder.orderings().len(), ALL_ORDERINGS.len() + 16);
 
-        assert_eq!(packed.x % 4, 0, "sprite x={} should be 4-aligned", packed.x);
-        assert_eq!(packed.y % 4, 0, "sprite y={} should be 4-aligned", packed.y);
+        let rects = |layouts: Vec<AtlasLayout>| -> Vec<_> {
+            layouts
+                .iter()
+                .flat_map(|l| l.sprites.iter().map(|s| (s.name.clone(), s.x, s.y)))
+                .collect()
+        };
+        let first = rects(builder.layout(make_sprites()).unwrap());
+        let second = rects(builder.layout(make_sprites()).unwrap());
+        assert_eq!(first, second);
+        assert_eq!(first.len(), sizes.len());
     }
 
     #[test]
-    fn test_block_align_all_sprites_aligned() {
-        // Pack multiple sprites with different sizes and verify all are block-aligned.
-        let sizes = [(20, 20), (30, 25), (15, 40), (50, 10), (85, 85)];
+    fn test_parallel_best_matches_any_thread_count() {
+        let sizes = [
+            (40, 30),
+            (25, 60),
+            (70, 10),
+            (15, 15),
+            (50, 50),
+            (33, 21),
+            (12, 48),
+            (64, 8),
+        ];
         let sprites: Vec<SourceSprite> = sizes
             .iter()
             .enumerate()
-            .map(|(i, (w, h))| SourceSprite {
+            .map(|(i, &(w, h))| SourceSprite {
                 path: std::path::PathBuf::from(format!("sprite_{}.png", i)),
                 name: format!("sprite_{}", i),
-                image: image::RgbaImage::new(*w, *h),
-                trim_info: TrimInfo::untrimmed(*w, *h),
+                image: image::RgbaImage::new(w, h),
+                trim_info: TrimInfo::untrimmed(w, h),
+                input_index: i,
+                content_bounds: None,
+                nine_slice: None,
+                pivot: None,
+                companions: Vec::new(),
             })
             .collect();
-
-        let builder = AtlasBuilder::new(512, 512)
-            .padding(2)
-            .extrude(2)
-            .block_align(4);
-
-        let result = builder.build(sprites).unwrap();
-        for packed in &result[0].sprites {
-            assert_eq!(
-                packed.x % 4,
-                0,
-                "sprite '{}' x={} should be 4-aligned",
-                packed.name,
-                packed.x
-            );
-            assert_eq!(
-                packed.y % 4,
-                0,
-                "sprite '{}' y={} should be 4-aligned",
-                packed.name,
-                packed.y
-            );
-        }
+        let builder = AtlasBuilder::new(128, 128)
+            .heuristic(PackingHeuristic::Best)
+            .pack_mode(PackMode::Best)
+            .trials(8);
+
+        // Candidates finish in any order across threads, but ties always go
+        // to the earliest one, so every pool size picks the same layout
+        let layout_with_threads = |threads| {
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .unwrap();
+            let layouts = pool.install(|| builder.layout(sprites.clone()).unwrap());
+            layouts
+                .iter()
+                .flat_map(|l| {
+                    l.sprites
+                        .iter()
+                        .map(move |s| (l.index, s.name.clone(), s.x, s.y, s.rotated))
+                })
+                .collect::<Vec<_>>()
+        };
+        let sequential = layout_with_threads(1);
+        assert_eq!(sequential.len(), sizes.len());
+        assert_eq!(layout_with_threads(4), sequential);
+        assert_eq!(layout_with_threads(7), sequential);
     }
 
     #[test]
-    fn test_block_align_disabled_by_default() {
-        // Without block_align, sprites may land on non-aligned positions.
-        let sprites = vec![SourceSprite {
-            path: std::path::PathBuf::from("test.png"),
-            name: "test".to_string(),
-            image: image::RgbaImage::new(20, 20),
-            trim_info: TrimInfo::untrimmed(20, 20),
-        }];
-
-        let builder = AtlasBuilder::new(256, 256).padding(1).extrude(0);
+    fn test_pack_order_pins_sprites_first() {
+        let sizes = [(64, 64), (32, 32), (16, 16), (8, 8)];
+        let sprites: Vec<SourceSprite> = sizes
+            .iter()
+            .enumerate()
+            .map(|(i, (w, h))| SourceSprite {
+                path: std::path::PathBuf::from(format!("s{}.png", i)),
+                name: format!("s{}", i),
+                image: image::RgbaImage::new(*w, *h),
+                trim_info: TrimInfo::untrimmed(*w, *h),
+                input_index: i,
+                content_bounds: None,
+                nine_slice: None,
+                pivot: None,
+                companions: Vec::new(),
+            })
+            .collect();
+        let builder = AtlasBuilder::new(256, 256).pack_order(vec![
+            glob::Pattern::new("s3").unwrap(),
+            glob::Pattern::new("s[12]").unwrap(),
+        ]);
+
+        for ordering in ALL_ORDERINGS {
+            let order = builder.sorted_indices(&sprites, ordering);
+            assert_eq!(order[0], 3, "{:?}", ordering);
+            assert_eq!(order[3], 0, "{:?}", ordering);
+        }
 
-        let result = builder.build(sprites).unwrap();
-        let packed = &result[0].sprites[0];
-        // With padding=1, extrude=0: sprite_x = rect.x + 1
-        // rect.x = 0, so sprite_x = 1, which is NOT 4-aligned
-        assert_eq!(packed.x, 1);
-        assert_eq!(packed.y, 1);
+        let atlases = builder.build(sprites).unwrap();
+        let pinned = atlases[0].find("s3").unwrap();
+        assert_eq!((pinned.x, pinned.y), (1, 1));
     }
 
     #[test]
@@ -830,6 +3513,11 @@ mod tests {
             name: "test".to_string(),
             image: sprite_img,
             trim_info: TrimInfo::untrimmed(4, 4),
+            input_index: 0,
+            content_bounds: None,
+            nine_slice: None,
+            pivot: None,
+            companions: Vec::new(),
         }];
 
         let builder = AtlasBuilder::new(256, 256).padding(1).extrude(2);
@@ -848,6 +3536,80 @@ mod tests {
         assert_eq!(packed.y, 3);
     }
 
+    #[test]
+    fn test_background_fills_unused_area_only() {
+        let mut image = image::RgbaImage::new(4, 4);
+        image.put_pixel(0, 0, Rgba([255, 0, 0, 255]));
+        image.put_pixel(1, 1, Rgba([0, 255, 0, 128]));
+        let sprites = vec![SourceSprite {
+            path: std::path::PathBuf::from("test.png"),
+            name: "test".to_string(),
+            image,
+            trim_info: TrimInfo::untrimmed(4, 4),
+            input_index: 0,
+            content_bounds: None,
+            nine_slice: None,
+            pivot: None,
+            companions: Vec::new(),
+        }];
+        let blue = Rgba([0, 0, 255, 255]);
+        let builder = AtlasBuilder::new(64, 64)
+            .padding(1)
+            .extrude(1)
+            .background(Some(blue));
+        let atlas = &builder.build(sprites).unwrap()[0];
+
+        let pixel = |x, y| *atlas.image.get_pixel(x, y);
+        assert_eq!(pixel(0, 0), blue);
+        // Extrusion and sprite pixels are copied as-is, never blended
+        assert_eq!(pixel(1, 2), Rgba([255, 0, 0, 255]));
+        assert_eq!(pixel(2, 2), Rgba([255, 0, 0, 255]));
+        assert_eq!(pixel(3, 3), Rgba([0, 255, 0, 128]));
+        assert_eq!(pixel(4, 4), Rgba([0, 0, 0, 0]));
+
+        let scaled = builder.scale_atlas(atlas, 0.5, FilterType::Nearest, false);
+        assert_eq!(*scaled.image.get_pixel(0, 0), blue);
+    }
+
+    #[test]
+    fn test_companions_share_layout() {
+        let red = Rgba([255, 0, 0, 255]);
+        let normal = Rgba([128, 128, 255, 255]);
+        let sprite = |name: &str, width, companion: bool| SourceSprite {
+            path: std::path::PathBuf::from(name),
+            name: name.to_string(),
+            image: image::RgbaImage::from_pixel(width, 4, red),
+            trim_info: TrimInfo::untrimmed(width, 4),
+            input_index: 0,
+            content_bounds: None,
+            nine_slice: None,
+            pivot: None,
+            companions: if companion {
+                vec![(
+                    "_n".to_string(),
+                    image::RgbaImage::from_pixel(width, 4, normal),
+                )]
+            } else {
+                Vec::new()
+            },
+        };
+        let sprites = vec![sprite("a", 8, true), sprite("b", 4, false)];
+        let builder = AtlasBuilder::new(64, 64).padding(1).extrude(1);
+        let atlas = &builder.build(sprites).unwrap()[0];
+
+        let page = &atlas.companions["_n"];
+        assert_eq!(page.dimensions(), atlas.image.dimensions());
+        let a = atlas.find("a").unwrap();
+        let b = atlas.find("b").unwrap();
+        assert_eq!(*page.get_pixel(a.x, a.y), normal);
+        assert_eq!(*page.get_pixel(a.x - 1, a.y), normal, "extruded");
+        assert_eq!(*page.get_pixel(b.x, b.y), Rgba([0, 0, 0, 0]));
+
+        let scaled = builder.scale_atlas(atlas, 0.5, FilterType::Nearest, false);
+        let a = scaled.find("a").unwrap();
+        assert_eq!(*scaled.companions["_n"].get_pixel(a.x, a.y), normal);
+    }
+
     #[test]
     fn test_extrusion_zero_padding() {
         // With padding=0, extrude=1:
@@ -864,6 +3626,11 @@ mod tests {
             name: "test".to_string(),
             image: sprite_img,
             trim_info: TrimInfo::untrimmed(4, 4),
+            input_index: 0,
+            content_bounds: None,
+            nine_slice: None,
+            pivot: None,
+            companions: Vec::new(),
         }];
 
         let builder = AtlasBuilder::new(256, 256).padding(0).extrude(1);
@@ -876,6 +3643,55 @@ mod tests {
         assert_eq!(packed.y, 1);
     }
 
+    #[test]
+    fn test_extrude_mode_wrap() {
+        let channel = |n: u32| u8::try_from(n * 100).unwrap();
+        let tile = image::RgbaImage::from_fn(3, 3, |x, y| Rgba([channel(x), channel(y), 0, 255]));
+        let sprite = |name: &str| SourceSprite {
+            path: std::path::PathBuf::from(name),
+            name: name.to_string(),
+            image: tile.clone(),
+            trim_info: TrimInfo::untrimmed(3, 3),
+            input_index: 0,
+            content_bounds: None,
+            nine_slice: None,
+            pivot: None,
+            companions: Vec::new(),
+        };
+        let settings = HashMap::from([(
+            "clamped".to_string(),
+            SpriteSettings {
+                extrude_mode: Some(ExtrudeMode::Clamp),
+                ..SpriteSettings::default()
+            },
+        )]);
+        let builder = AtlasBuilder::new(64, 64)
+            .padding(0)
+            .extrude(2)
+            .extrude_mode(ExtrudeMode::Wrap)
+            .sprite_settings(settings);
+        let atlas = &builder
+            .build(vec![sprite("tile"), sprite("clamped")])
+            .unwrap()[0];
+
+        // Wrapped borders continue the tile from its opposite edge
+        let s = atlas.find("tile").unwrap();
+        let pixel = |x, y| *atlas.image.get_pixel(x, y);
+        assert_eq!(pixel(s.x - 1, s.y + 1), *tile.get_pixel(2, 1));
+        assert_eq!(pixel(s.x - 2, s.y + 1), *tile.get_pixel(1, 1));
+        assert_eq!(pixel(s.x + 3, s.y), *tile.get_pixel(0, 0));
+        assert_eq!(pixel(s.x + 4, s.y), *tile.get_pixel(1, 0));
+        assert_eq!(pixel(s.x + 1, s.y - 1), *tile.get_pixel(1, 2));
+        assert_eq!(pixel(s.x + 1, s.y + 3), *tile.get_pixel(1, 0));
+        assert_eq!(pixel(s.x - 1, s.y - 1), *tile.get_pixel(2, 2));
+        assert_eq!(pixel(s.x + 3, s.y + 3), *tile.get_pixel(0, 0));
+
+        // A per-sprite override keeps the clamped border
+        let c = atlas.find("clamped").unwrap();
+        assert_eq!(pixel(c.x - 1, c.y + 1), *tile.get_pixel(0, 1));
+        assert_eq!(pixel(c.x + 4, c.y), *tile.get_pixel(2, 0));
+    }
+
     #[test]
     fn test_best_heuristic_packs_all_sprites() {
         // Best mode should try all heuristics and pick the best result.
@@ -891,6 +3707,11 @@ mod tests {
                 name: format!("sprite_{}", i),
                 image: img,
                 trim_info: TrimInfo::untrimmed(20, 20),
+                input_index: 0,
+                content_bounds: None,
+                nine_slice: None,
+                pivot: None,
+                companions: Vec::new(),
             });
         }
 
@@ -923,6 +3744,11 @@ mod tests {
                     name: format!("sprite_{}", i),
                     image: img,
                     trim_info: TrimInfo::untrimmed(*w, *h),
+                    input_index: 0,
+                    content_bounds: None,
+                    nine_slice: None,
+                    pivot: None,
+                    companions: Vec::new(),
                 });
             }
             sprites
@@ -977,6 +3803,11 @@ mod tests {
                     name: format!("sprite_{}", i),
                     image: image::RgbaImage::new(*w, *h),
                     trim_info: TrimInfo::untrimmed(*w, *h),
+                    input_index: 0,
+                    content_bounds: None,
+                    nine_slice: None,
+                    pivot: None,
+                    companions: Vec::new(),
                 })
                 .collect::<Vec<_>>()
         };
@@ -1006,6 +3837,303 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_pack_mode_min_pages_redistributes_sprites() {
+        // Page by page, two 10x3 strips beat 10x7 + 10x3 on the first page
+        // (same count, smaller area), leaving each 10x7 a page of its own.
+        // Spread over all pages at once, each 10x7 shares with a strip.
+        let create_sprites = || {
+            [(10, 3), (10, 3), (10, 7), (10, 7)]
+                .iter()
+                .enumerate()
+                .map(|(i, &(w, h))| {
+                    SourceSprite::from_image(format!("sprite_{}", i), image::RgbaImage::new(w, h))
+                })
+                .collect::<Vec<_>>()
+        };
+        let builder = |mode| {
+            AtlasBuilder::new(10, 10)
+                .padding(0)
+                .heuristic(PackingHeuristic::BestShortSideFit)
+                .pack_mode(mode)
+        };
+
+        let best = builder(PackMode::Best).build(create_sprites()).unwrap();
+        assert_eq!(best.len(), 3);
+
+        let min_pages = builder(PackMode::MinPages).build(create_sprites()).unwrap();
+        assert_eq!(min_pages.len(), 2);
+        for atlas in &min_pages {
+            assert_eq!(atlas.sprites.len(), 2);
+            assert_eq!((atlas.width, atlas.height), (10, 10));
+        }
+        let mut names: Vec<_> = min_pages
+            .iter()
+            .flat_map(|atlas| atlas.sprites.iter().map(|s| s.name.clone()))
+            .collect();
+        names.sort();
+        assert_eq!(names, ["sprite_0", "sprite_1", "sprite_2", "sprite_3"]);
+
+        // Planning alone gives the same pages
+        let layouts = builder(PackMode::MinPages)
+            .layout(create_sprites())
+            .unwrap();
+        assert_eq!(layouts.len(), 2);
+        let rects = |sprites: &[PackedSprite]| -> Vec<_> {
+            sprites.iter().map(|s| (s.name.clone(), s.x, s.y)).collect()
+        };
+        for (layout, atlas) in layouts.iter().zip(&min_pages) {
+            assert_eq!(rects(&layout.sprites), rects(&atlas.sprites));
+        }
+
+        // A set that already fits one page is left alone
+        let one_page = builder(PackMode::MinPages)
+            .build(create_sprites().into_iter().take(2).collect())
+            .unwrap();
+        assert_eq!(one_page.len(), 1);
+    }
+
+    #[test]
+    fn test_append_keeps_packed_sprites_in_place() {
+        let solid = |name: &str, shade: u8| {
+            SourceSprite::from_image(
+                name.to_string(),
+                image::RgbaImage::from_pixel(16, 16, image::Rgba([shade, 0, 0, 255])),
+            )
+        };
+        let builder = AtlasBuilder::new(32, 32)
+            .padding(0)
+            .fixed_size(true)
+            .heuristic(PackingHeuristic::BestShortSideFit);
+
+        let existing = builder.build(vec![solid("a", 10), solid("b", 20)]).unwrap();
+        assert_eq!(existing.len(), 1);
+        let before: Vec<_> = existing[0]
+            .sprites
+            .iter()
+            .map(|s| (s.name.clone(), s.x, s.y))
+            .collect();
+
+        let atlases = builder
+            .append(
+                existing,
+                vec![solid("c", 30), solid("d", 40), solid("e", 50)],
+            )
+            .unwrap();
+        assert_eq!(atlases.len(), 2);
+
+        let page = &atlases[0];
+        assert_eq!((page.width, page.height), (32, 32));
+        assert_eq!(page.sprites.len(), 4);
+        for (name, x, y) in &before {
+            let sprite = page.find(name).unwrap();
+            assert_eq!((sprite.x, sprite.y), (*x, *y));
+        }
+        for sprite in &page.sprites {
+            let shade = match sprite.name.as_str() {
+                "a" => 10,
+                "b" => 20,
+                "c" => 30,
+                "d" => 40,
+                _ => 50,
+            };
+            assert_eq!(page.image.get_pixel(sprite.x, sprite.y)[0], shade);
+        }
+
+        // The sprite that didn't fit starts a page numbered after the old ones
+        let overflow = &atlases[1];
+        assert_eq!(overflow.index, 1);
+        assert_eq!(overflow.sprites.len(), 1);
+        assert_eq!(overflow.sprites[0].atlas_index, 1);
+        assert!(overflow.find("e").is_some());
+    }
+
+    #[test]
+    fn test_append_counts_existing_pages_towards_budget() {
+        let sprite =
+            |name: &str| SourceSprite::from_image(name.to_string(), image::RgbaImage::new(32, 32));
+        let builder = |budget| {
+            AtlasBuilder::new(32, 32)
+                .padding(0)
+                .fixed_size(true)
+                .max_total_pixels(Some(budget))
+        };
+
+        // The new page alone fits 1024 pixels, but not on top of the kept one
+        let existing = builder(1024).build(vec![sprite("a")]).unwrap();
+        let err = builder(1024)
+            .append(existing.clone(), vec![sprite("b")])
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<BentoError>(),
+            Some(BentoError::BudgetExceeded {
+                used: 2048,
+                budget: 1024,
+                unit: "pixels",
+            })
+        ));
+
+        let atlases = builder(2048).append(existing, vec![sprite("b")]).unwrap();
+        assert_eq!(atlases.len(), 2);
+    }
+
+    #[test]
+    fn test_build_stable_keeps_previous_positions() {
+        let sprite = |name: &str, size: u32| {
+            SourceSprite::from_image(name.to_string(), image::RgbaImage::new(size, size))
+        };
+        let builder = AtlasBuilder::new(64, 64)
+            .padding(0)
+            .heuristic(PackingHeuristic::BestShortSideFit);
+        let positions = |atlases: &[Atlas]| -> HashMap<String, (u32, u32)> {
+            atlases
+                .iter()
+                .flat_map(|atlas| atlas.sprites.iter())
+                .map(|s| (s.name.clone(), (s.x, s.y)))
+                .collect()
+        };
+
+        let previous = builder
+            .build(vec![
+                sprite("a", 32),
+                sprite("b", 16),
+                sprite("c", 16),
+                sprite("d", 8),
+            ])
+            .unwrap();
+        let layouts: Vec<AtlasLayout> = previous.iter().map(Atlas::layout).collect();
+        let before = positions(&previous);
+
+        // "c" is removed, "d" grew and "e" is new
+        let atlases = builder
+            .build_stable(
+                &layouts,
+                vec![
+                    sprite("e", 8),
+                    sprite("d", 12),
+                    sprite("b", 16),
+                    sprite("a", 32),
+                ],
+            )
+            .unwrap();
+        assert_eq!(atlases.len(), 1);
+        assert_eq!(
+            (atlases[0].width, atlases[0].height),
+            (layouts[0].width, layouts[0].height)
+        );
+        let after = positions(&atlases);
+        assert_eq!(after.len(), 4);
+        assert!(!after.contains_key("c"));
+        assert_eq!(after["a"], before["a"]);
+        assert_eq!(after["b"], before["b"]);
+        assert_eq!(atlases[0].find("d").unwrap().width, 12);
+
+        // Nothing to keep: a plain build
+        let fresh = builder.build_stable(&[], vec![sprite("a", 32)]).unwrap();
+        assert_eq!(fresh.len(), 1);
+        assert_eq!(fresh[0].sprites.len(), 1);
+    }
+
+    #[test]
+    fn test_build_stable_rechecks_kept_sprites_against_spacing() {
+        let sprite = |name: &str, size: u32| {
+            SourceSprite::from_image(name.to_string(), image::RgbaImage::new(size, size))
+        };
+        let builder = || {
+            AtlasBuilder::new(64, 64)
+                .padding(0)
+                .fixed_size(true)
+                .heuristic(PackingHeuristic::BottomLeft)
+        };
+        let previous = builder()
+            .build(vec![sprite("a", 16), sprite("b", 16)])
+            .unwrap();
+        let layouts: Vec<AtlasLayout> = previous.iter().map(Atlas::layout).collect();
+        let before: HashMap<String, (u32, u32)> = previous[0]
+            .sprites
+            .iter()
+            .map(|s| (s.name.clone(), (s.x, s.y)))
+            .collect();
+
+        // With padding the two touching sprites can't both stay
+        let padded = builder()
+            .padding(2)
+            .build_stable(&layouts, vec![sprite("a", 16), sprite("b", 16)])
+            .unwrap();
+        assert_eq!(padded.len(), 1);
+        let moved = padded[0]
+            .sprites
+            .iter()
+            .filter(|s| (s.x, s.y) != before[&s.name])
+            .count();
+        assert_eq!(moved, 1);
+        let cells: Vec<Rect> = padded[0]
+            .sprites
+            .iter()
+            .map(|s| Rect::new(s.x, s.y, s.width + 2, s.height + 2))
+            .collect();
+        assert!(!cells[0].intersects(&cells[1]));
+
+        // A sprite at the page edge has no room left for extrusion
+        let extruded = builder()
+            .extrude(1)
+            .build_stable(&layouts, vec![sprite("a", 16), sprite("b", 16)])
+            .unwrap();
+        for sprite in &extruded[0].sprites {
+            assert!(sprite.x >= 1 && sprite.y >= 1);
+        }
+
+        // Kept pages count towards the budget
+        let err = builder()
+            .max_total_pixels(Some(4096))
+            .build_stable(&layouts, vec![sprite("a", 16), sprite("c", 64)])
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<BentoError>(),
+            Some(BentoError::BudgetExceeded { used: 8192, .. })
+        ));
+    }
+
+    #[test]
+    fn test_priority_sprites_take_first_page() {
+        // Four small sprites fill page 0 better than the full-page HUD, so
+        // it only gets there by priority
+        let sprites = || {
+            let mut sprites: Vec<_> = (0..4)
+                .map(|i| {
+                    SourceSprite::from_image(format!("small_{}", i), image::RgbaImage::new(32, 32))
+                })
+                .collect();
+            sprites.push(SourceSprite::from_image(
+                "hud".to_string(),
+                image::RgbaImage::new(64, 64),
+            ));
+            sprites
+        };
+        let builder = AtlasBuilder::new(64, 64).padding(0);
+        let page_of = |atlases: &[Atlas], name: &str| {
+            atlases
+                .iter()
+                .position(|atlas| atlas.find(name).is_some())
+                .unwrap()
+        };
+
+        let atlases = builder.build(sprites()).unwrap();
+        assert_eq!(page_of(&atlases, "hud"), 1);
+
+        let settings = HashMap::from([(
+            "hud".to_string(),
+            SpriteSettings {
+                priority: Some(1),
+                ..SpriteSettings::default()
+            },
+        )]);
+        let atlases = builder.sprite_settings(settings).build(sprites()).unwrap();
+        assert_eq!(atlases.len(), 2);
+        assert_eq!(page_of(&atlases, "hud"), 0);
+        assert_eq!(atlases[1].sprites.len(), 4);
+    }
+
     #[test]
     fn test_cancellation_returns_error() {
         use std::sync::atomic::AtomicBool;
@@ -1015,6 +4143,11 @@ mod tests {
             name: "test".to_string(),
             image: image::RgbaImage::new(20, 20),
             trim_info: TrimInfo::untrimmed(20, 20),
+            input_index: 0,
+            content_bounds: None,
+            nine_slice: None,
+            pivot: None,
+            companions: Vec::new(),
         }];
 
         // Set cancel token to true before building
@@ -1046,6 +4179,11 @@ mod tests {
             name: "test".to_string(),
             image: image::RgbaImage::new(20, 20),
             trim_info: TrimInfo::untrimmed(20, 20),
+            input_index: 0,
+            content_bounds: None,
+            nine_slice: None,
+            pivot: None,
+            companions: Vec::new(),
         }];
 
         // Pre-cancelled token with pack_mode Best
@@ -1076,6 +4214,11 @@ mod tests {
             name: "test".to_string(),
             image: image::RgbaImage::new(20, 20),
             trim_info: TrimInfo::untrimmed(20, 20),
+            input_index: 0,
+            content_bounds: None,
+            nine_slice: None,
+            pivot: None,
+            companions: Vec::new(),
         }];
 
         // Pre-cancelled token with Best heuristic
@@ -1102,6 +4245,11 @@ mod tests {
             name: "test".to_string(),
             image: image::RgbaImage::new(20, 20),
             trim_info: TrimInfo::untrimmed(20, 20),
+            input_index: 0,
+            content_bounds: None,
+            nine_slice: None,
+            pivot: None,
+            companions: Vec::new(),
         }];
 
         // Pre-cancelled token
@@ -1114,7 +4262,7 @@ mod tests {
 
         // Directly call find_best_heuristic, bypassing build()'s early check
         // This should return a Cancelled error, not panic
-        let result = builder.find_best_heuristic(&sprites, 0);
+        let result = builder.find_best_heuristic(&sprites, 0, 256, 256);
 
         assert!(result.is_err());
         let err = result.err().unwrap();
@@ -1135,6 +4283,11 @@ mod tests {
             name: "test".to_string(),
             image: image::RgbaImage::new(20, 20),
             trim_info: TrimInfo::untrimmed(20, 20),
+            input_index: 0,
+            content_bounds: None,
+            nine_slice: None,
+            pivot: None,
+            companions: Vec::new(),
         }];
 
         // Pre-cancelled token with pack_mode Best (not Best heuristic)
@@ -1148,7 +4301,7 @@ mod tests {
 
         // Directly call pack_atlas, bypassing build()'s early check
         // This should return a Cancelled error, not panic
-        let result = builder.pack_atlas(0, sprites);
+        let result = builder.pack_atlas(0, sprites, None);
 
         assert!(result.is_err());
         assert!(
@@ -1171,6 +4324,11 @@ mod tests {
                 name: format!("test_{}", i),
                 image: image::RgbaImage::new(20, 20),
                 trim_info: TrimInfo::untrimmed(20, 20),
+                input_index: 0,
+                content_bounds: None,
+                nine_slice: None,
+                pivot: None,
+                companions: Vec::new(),
             });
         }
 
@@ -1214,6 +4372,11 @@ mod tests {
             name: "test".to_string(),
             image: image::RgbaImage::new(100, 100),
             trim_info: TrimInfo::untrimmed(100, 100),
+            input_index: 0,
+            content_bounds: None,
+            nine_slice: None,
+            pivot: None,
+            companions: Vec::new(),
         }];
 
         let cancel_token = Arc::new(AtomicBool::new(true));
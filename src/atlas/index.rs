@@ -0,0 +1,122 @@
+use std::collections::HashMap;
+
+use crate::sprite::PackedSprite;
+
+/// Side length of a grid cell in atlas pixels
+const CELL_SIZE: u32 = 64;
+
+/// Uniform grid over an atlas for fast point and name lookups.
+///
+/// Each cell stores the indices of the sprites whose rectangles overlap it, so a
+/// point query only has to test the handful of sprites in a single cell.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SpriteIndex {
+    columns: u32,
+    rows: u32,
+    cells: Vec<Vec<usize>>,
+    by_name: HashMap<String, usize>,
+}
+
+impl SpriteIndex {
+    /// Build an index over `sprites` for an atlas of the given size
+    pub(crate) fn build(width: u32, height: u32, sprites: &[PackedSprite]) -> Self {
+        let columns = width.div_ceil(CELL_SIZE).max(1);
+        let rows = height.div_ceil(CELL_SIZE).max(1);
+        let mut cells = vec![Vec::new(); (columns * rows) as usize];
+        let mut by_name = HashMap::with_capacity(sprites.len());
+
+        for (i, sprite) in sprites.iter().enumerate() {
+            by_name.insert(sprite.name.clone(), i);
+
+            if sprite.width == 0 || sprite.height == 0 {
+                continue;
+            }
+            let col_start = (sprite.x / CELL_SIZE).min(columns - 1);
+            let col_end = ((sprite.x + sprite.width - 1) / CELL_SIZE).min(columns - 1);
+            let row_start = (sprite.y / CELL_SIZE).min(rows - 1);
+            let row_end = ((sprite.y + sprite.height - 1) / CELL_SIZE).min(rows - 1);
+
+            for row in row_start..=row_end {
+                for col in col_start..=col_end {
+                    cells[(row * columns + col) as usize].push(i);
+                }
+            }
+        }
+
+        Self {
+            columns,
+            rows,
+            cells,
+            by_name,
+        }
+    }
+
+    /// Index of the sprite covering pixel (x, y), if any
+    pub(crate) fn sprite_at(&self, sprites: &[PackedSprite], x: u32, y: u32) -> Option<usize> {
+        let (col, row) = (x / CELL_SIZE, y / CELL_SIZE);
+        if col >= self.columns || row >= self.rows {
+            return None;
+        }
+
+        self.cells
+            .get((row * self.columns + col) as usize)?
+            .iter()
+            .copied()
+            .find(|&i| {
+                sprites.get(i).is_some_and(|s| {
+                    x >= s.x && x < s.x + s.width && y >= s.y && y < s.y + s.height
+                })
+            })
+    }
+
+    /// Index of the sprite with the given name, if any
+    pub(crate) fn find(&self, name: &str) -> Option<usize> {
+        self.by_name.get(name).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sprite::TrimInfo;
+
+    fn sprite(name: &str, x: u32, y: u32, w: u32, h: u32) -> PackedSprite {
+        PackedSprite {
+            name: name.to_string(),
+            path: std::path::PathBuf::from(name),
+            x,
+            y,
+            width: w,
+            height: h,
+            trim_info: TrimInfo::untrimmed(w, h),
+            atlas_index: 0,
+            input_index: 0,
+            content_bounds: None,
+            nine_slice: None,
+            pivot: None,
+            rotated: false,
+        }
+    }
+
+    #[test]
+    fn test_sprite_at_spanning_cells() {
+        let sprites = [sprite("a", 0, 0, 10, 10), sprite("b", 60, 60, 100, 20)];
+        let index = SpriteIndex::build(256, 256, &sprites);
+
+        assert_eq!(index.sprite_at(&sprites, 5, 5), Some(0));
+        assert_eq!(index.sprite_at(&sprites, 10, 10), None);
+        assert_eq!(index.sprite_at(&sprites, 60, 60), Some(1));
+        assert_eq!(index.sprite_at(&sprites, 159, 79), Some(1));
+        assert_eq!(index.sprite_at(&sprites, 160, 79), None);
+        assert_eq!(index.sprite_at(&sprites, 1000, 0), None);
+    }
+
+    #[test]
+    fn test_find_by_name() {
+        let sprites = [sprite("a", 0, 0, 10, 10), sprite("b", 20, 0, 10, 10)];
+        let index = SpriteIndex::build(64, 64, &sprites);
+
+        assert_eq!(index.find("b"), Some(1));
+        assert_eq!(index.find("missing"), None);
+    }
+}
@@ -0,0 +1,191 @@
+use image::{Rgba, RgbaImage};
+
+use crate::packing::Rect;
+
+/// Marks texels that belong to no sprite region (padding and free space)
+const NO_OWNER: u32 = u32::MAX;
+
+/// Number of mip levels below a `width`x`height` image, capped at `requested`.
+/// Halving stops at 1x1, so a 256x64 atlas has at most 8 levels.
+pub fn mip_level_count(width: u32, height: u32, requested: u32) -> u32 {
+    width.max(height).max(1).ilog2().min(requested)
+}
+
+/// Downsample an atlas into successive mip levels, starting with level 1.
+///
+/// Each texel of a smaller level averages only the texels of one region (the
+/// one covering most of its 2x2 block), so neighboring sprites never bleed
+/// into each other and transparent padding never darkens sprite edges. Colors
+/// are averaged with premultiplied alpha. The chain stops early once a 1x1
+/// level is reached.
+pub(crate) fn mip_chain(image: &RgbaImage, regions: &[Rect], levels: u32) -> Vec<RgbaImage> {
+    let (mut width, mut height) = image.dimensions();
+    let mut owners = vec![NO_OWNER; width as usize * height as usize];
+    for (index, region) in (0u32..).zip(regions) {
+        for y in region.y..(region.y + region.height).min(height) {
+            for x in region.x..(region.x + region.width).min(width) {
+                owners[(y * width + x) as usize] = index;
+            }
+        }
+    }
+
+    let mut chain: Vec<RgbaImage> = Vec::new();
+    for _ in 0..levels {
+        if width == 1 && height == 1 {
+            break;
+        }
+        let source = chain.last().unwrap_or(image);
+        let (next, next_owners) = downsample(source, &owners);
+        width = next.width();
+        height = next.height();
+        owners = next_owners;
+        chain.push(next);
+    }
+    chain
+}
+
+/// Halve an image (rounding down, minimum 1), returning the new level and its owner map
+fn downsample(source: &RgbaImage, owners: &[u32]) -> (RgbaImage, Vec<u32>) {
+    let (width, height) = source.dimensions();
+    let next_width = (width / 2).max(1);
+    let next_height = (height / 2).max(1);
+    let mut next = RgbaImage::new(next_width, next_height);
+    let mut next_owners = vec![NO_OWNER; next_width as usize * next_height as usize];
+
+    for y in 0..next_height {
+        // The last row/column also absorbs the leftover texel of an odd dimension
+        let y_range = block_range(y, next_height, height);
+        for x in 0..next_width {
+            let x_range = block_range(x, next_width, width);
+
+            let mut texels = Vec::with_capacity(9);
+            for sy in y_range.clone() {
+                for sx in x_range.clone() {
+                    texels.push((
+                        owners[(sy * width + sx) as usize],
+                        *source.get_pixel(sx, sy),
+                    ));
+                }
+            }
+
+            let owner = majority_owner(&texels);
+            next_owners[(y * next_width + x) as usize] = owner;
+            next.put_pixel(
+                x,
+                y,
+                average(texels.iter().filter(|(o, _)| *o == owner).map(|(_, p)| p)),
+            );
+        }
+    }
+
+    (next, next_owners)
+}
+
+/// Source texel range covered by a destination texel along one axis
+fn block_range(dest: u32, dest_len: u32, source_len: u32) -> std::ops::Range<u32> {
+    let start = (dest * 2).min(source_len - 1);
+    let end = if dest + 1 == dest_len {
+        source_len
+    } else {
+        start + 2
+    };
+    start..end
+}
+
+/// The sprite region covering most of the block, or `NO_OWNER` if none does.
+/// Ties go to the region seen first.
+fn majority_owner(texels: &[(u32, Rgba<u8>)]) -> u32 {
+    let mut best = (NO_OWNER, 0);
+    for (owner, _) in texels {
+        if *owner == NO_OWNER {
+            continue;
+        }
+        let count = texels.iter().filter(|(o, _)| o == owner).count();
+        if count > best.1 {
+            best = (*owner, count);
+        }
+    }
+    best.0
+}
+
+/// Average pixels with premultiplied alpha
+fn average<'a>(pixels: impl Iterator<Item = &'a Rgba<u8>>) -> Rgba<u8> {
+    let mut count = 0u32;
+    let mut alpha = 0u32;
+    let mut color = [0u32; 3];
+    let mut straight = [0u32; 3];
+    for pixel in pixels {
+        let a = u32::from(pixel[3]);
+        count += 1;
+        alpha += a;
+        for c in 0..3 {
+            color[c] += u32::from(pixel[c]) * a;
+            straight[c] += u32::from(pixel[c]);
+        }
+    }
+    if count == 0 {
+        return Rgba([0, 0, 0, 0]);
+    }
+
+    // Fully transparent blocks keep their plain average color
+    let channel = |c: usize| {
+        (color[c] + alpha / 2)
+            .checked_div(alpha)
+            .unwrap_or((straight[c] + count / 2) / count)
+    };
+    #[expect(
+        clippy::cast_possible_truncation,
+        reason = "averages of u8 values fit in u8"
+    )]
+    Rgba([
+        channel(0) as u8,
+        channel(1) as u8,
+        channel(2) as u8,
+        ((alpha + count / 2) / count) as u8,
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RED: Rgba<u8> = Rgba([255, 0, 0, 255]);
+    const BLUE: Rgba<u8> = Rgba([0, 0, 255, 255]);
+
+    #[test]
+    fn test_mip_chain_keeps_neighbors_apart() {
+        // Red region in column 0, blue region in columns 1-3, no padding
+        let image = RgbaImage::from_fn(4, 2, |x, _| if x == 0 { RED } else { BLUE });
+        let regions = [Rect::new(0, 0, 1, 2), Rect::new(1, 0, 3, 2)];
+
+        let chain = mip_chain(&image, &regions, 1);
+        assert_eq!(chain.len(), 1);
+        assert_eq!(chain[0].dimensions(), (2, 1));
+        // A naive box filter would blend the first texel to purple
+        assert_eq!(*chain[0].get_pixel(0, 0), RED);
+        assert_eq!(*chain[0].get_pixel(1, 0), BLUE);
+    }
+
+    #[test]
+    fn test_mip_chain_ignores_padding() {
+        // One opaque texel surrounded by transparent padding
+        let mut image = RgbaImage::new(2, 2);
+        image.put_pixel(0, 0, RED);
+
+        let chain = mip_chain(&image, &[Rect::new(0, 0, 1, 1)], 4);
+        // Stops at 1x1 even though more levels were requested
+        assert_eq!(chain.len(), 1);
+        assert_eq!(*chain[0].get_pixel(0, 0), RED);
+    }
+
+    #[test]
+    fn test_mip_chain_odd_dimensions() {
+        let image = RgbaImage::from_pixel(5, 3, BLUE);
+        let chain = mip_chain(&image, &[Rect::new(0, 0, 5, 3)], 8);
+        let sizes: Vec<_> = chain.iter().map(RgbaImage::dimensions).collect();
+        assert_eq!(sizes, [(2, 1), (1, 1)]);
+        assert_eq!(mip_level_count(5, 3, 8), 2);
+        assert_eq!(mip_level_count(256, 64, 3), 3);
+        assert!(chain.iter().all(|level| level.pixels().all(|p| *p == BLUE)));
+    }
+}
@@ -1,5 +1,8 @@
 mod builder;
+mod index;
+mod mipmap;
 mod types;
 
-pub use builder::AtlasBuilder;
-pub use types::Atlas;
+pub use builder::{AtlasBuilder, SpriteSettings};
+pub use mipmap::mip_level_count;
+pub use types::{Atlas, AtlasLayout, PackingChoice};
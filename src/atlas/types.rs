@@ -1,5 +1,10 @@
-use image::RgbaImage;
+use std::collections::BTreeMap;
 
+use image::{RgbaImage, imageops};
+
+use super::index::SpriteIndex;
+use crate::cli::PackingHeuristic;
+use crate::packing::Rect;
 use crate::sprite::PackedSprite;
 
 /// A completed texture atlas
@@ -13,10 +18,49 @@ pub struct Atlas {
     pub height: u32,
     /// Rendered atlas image
     pub image: RgbaImage,
+    /// Companion pages such as normal maps, keyed by suffix, with the same
+    /// layout as `image`
+    pub companions: BTreeMap<String, RgbaImage>,
     /// All sprites packed into this atlas
     pub sprites: Vec<PackedSprite>,
     /// Packing efficiency (0.0 to 1.0, ratio of sprite area to atlas area)
     pub occupancy: f64,
+    /// Resolution relative to the packed sources (1.0 unless this is a scaled variant)
+    pub scale: f32,
+    /// Heuristic and ordering that produced the layout, when packed by bento
+    pub packing: Option<PackingChoice>,
+    /// Unused regions of the page: the packer's free rectangles, which can
+    /// overlap, plus any area added by rounding the page size. Empty when the
+    /// layout was read back from metadata.
+    pub free_rects: Vec<Rect>,
+    /// Spatial and name lookup over `sprites`
+    lookup: SpriteIndex,
+}
+
+/// Page geometry and sprite placements, without a rendered image
+#[derive(Debug, Clone)]
+pub struct AtlasLayout {
+    /// Atlas index (for multi-atlas support)
+    pub index: usize,
+    /// Atlas width
+    pub width: u32,
+    /// Atlas height
+    pub height: u32,
+    /// All sprites placed on this page
+    pub sprites: Vec<PackedSprite>,
+    /// Packing efficiency (0.0 to 1.0, ratio of sprite area to atlas area)
+    pub occupancy: f64,
+    /// Heuristic and ordering that produced the layout, when packed by bento
+    pub packing: Option<PackingChoice>,
+}
+
+/// The packing strategy chosen for one page
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackingChoice {
+    /// MaxRects heuristic (None for grid layouts)
+    pub heuristic: Option<PackingHeuristic>,
+    /// Sprite ordering, such as "area" or "shuffle-3" for a random trial
+    pub ordering: String,
 }
 
 impl Atlas {
@@ -26,8 +70,181 @@ impl Atlas {
             width,
             height,
             image: RgbaImage::new(width, height),
+            companions: BTreeMap::new(),
             sprites: Vec::new(),
             occupancy: 0.0,
+            scale: 1.0,
+            packing: None,
+            free_rects: Vec::new(),
+            lookup: SpriteIndex::default(),
+        }
+    }
+
+    /// Pair a layout with its rendered image, such as one loaded back from disk
+    pub fn from_layout(layout: AtlasLayout, image: RgbaImage) -> Self {
+        let mut atlas = Self {
+            index: layout.index,
+            width: layout.width,
+            height: layout.height,
+            image,
+            companions: BTreeMap::new(),
+            sprites: layout.sprites,
+            occupancy: layout.occupancy,
+            scale: 1.0,
+            packing: layout.packing,
+            free_rects: Vec::new(),
+            lookup: SpriteIndex::default(),
+        };
+        atlas.rebuild_index();
+        atlas
+    }
+
+    /// Page geometry and sprite placements, without the image
+    pub fn layout(&self) -> AtlasLayout {
+        AtlasLayout {
+            index: self.index,
+            width: self.width,
+            height: self.height,
+            sprites: self.sprites.clone(),
+            occupancy: self.occupancy,
+            packing: self.packing.clone(),
         }
     }
+
+    /// Rebuild the sprite lookup index. Call after modifying `sprites`.
+    pub fn rebuild_index(&mut self) {
+        self.lookup = SpriteIndex::build(self.width, self.height, &self.sprites);
+    }
+
+    /// Sprite covering atlas pixel (x, y), if any
+    pub fn sprite_at(&self, x: u32, y: u32) -> Option<&PackedSprite> {
+        self.lookup
+            .sprite_at(&self.sprites, x, y)
+            .and_then(|i| self.sprites.get(i))
+    }
+
+    /// Unused page area in pixels, counting overlapping free rectangles once
+    pub fn free_area(&self) -> u64 {
+        // Scan columns between rectangle edges, merging the rows each covers
+        let mut edges: Vec<u32> = self
+            .free_rects
+            .iter()
+            .flat_map(|r| [r.x, r.x + r.width])
+            .collect();
+        edges.sort_unstable();
+        edges.dedup();
+        let mut area = 0u64;
+        for pair in edges.windows(2) {
+            let (left, right) = (pair[0], pair[1]);
+            let mut spans: Vec<(u32, u32)> = self
+                .free_rects
+                .iter()
+                .filter(|r| r.x <= left && r.x + r.width >= right)
+                .map(|r| (r.y, r.y + r.height))
+                .collect();
+            spans.sort_unstable();
+            let mut covered = 0u32;
+            let mut end = 0u32;
+            for (top, bottom) in spans {
+                let top = top.max(end);
+                if bottom > top {
+                    covered += bottom - top;
+                    end = bottom;
+                }
+            }
+            area += u64::from(right - left) * u64::from(covered);
+        }
+        area
+    }
+
+    /// Sprite with the given name, if any
+    pub fn find(&self, name: &str) -> Option<&PackedSprite> {
+        self.lookup.find(name).and_then(|i| self.sprites.get(i))
+    }
+
+    /// Copy a sprite's pixels out of the atlas image, turning rotated sprites
+    /// back upright. With `restore_trim`, the trimmed pixels are placed back on
+    /// a transparent canvas of the original source size.
+    pub fn sprite_image(&self, sprite: &PackedSprite, restore_trim: bool) -> RgbaImage {
+        let mut pixels =
+            imageops::crop_imm(&self.image, sprite.x, sprite.y, sprite.width, sprite.height)
+                .to_image();
+        if sprite.rotated {
+            pixels = imageops::rotate270(&pixels);
+        }
+        if !restore_trim || !sprite.trim_info.was_trimmed() {
+            return pixels;
+        }
+
+        let trim = &sprite.trim_info;
+        let mut restored = RgbaImage::new(trim.source_width, trim.source_height);
+        imageops::overlay(
+            &mut restored,
+            &pixels,
+            i64::from(trim.offset_x),
+            i64::from(trim.offset_y),
+        );
+        restored
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sprite::TrimInfo;
+    use image::Rgba;
+
+    #[test]
+    fn test_sprite_image_restores_trim() {
+        let mut atlas = Atlas::new(0, 8, 8);
+        atlas.image.put_pixel(3, 2, Rgba([255, 0, 0, 255]));
+        atlas.image.put_pixel(4, 2, Rgba([0, 255, 0, 255]));
+        let sprite = PackedSprite {
+            name: "a".to_string(),
+            path: std::path::PathBuf::from("a"),
+            x: 3,
+            y: 2,
+            width: 2,
+            height: 1,
+            trim_info: TrimInfo {
+                offset_x: 1,
+                offset_y: 2,
+                source_width: 4,
+                source_height: 4,
+                trimmed_width: 2,
+                trimmed_height: 1,
+            },
+            atlas_index: 0,
+            input_index: 0,
+            content_bounds: None,
+            nine_slice: None,
+            pivot: None,
+            rotated: false,
+        };
+
+        let trimmed = atlas.sprite_image(&sprite, false);
+        assert_eq!(trimmed.dimensions(), (2, 1));
+        assert_eq!(*trimmed.get_pixel(1, 0), Rgba([0, 255, 0, 255]));
+
+        let restored = atlas.sprite_image(&sprite, true);
+        assert_eq!(restored.dimensions(), (4, 4));
+        assert_eq!(*restored.get_pixel(1, 2), Rgba([255, 0, 0, 255]));
+        assert_eq!(*restored.get_pixel(2, 2), Rgba([0, 255, 0, 255]));
+        assert_eq!(restored.get_pixel(0, 0).0[3], 0);
+
+        // Turned clockwise, the pair is stored as a column: red above green
+        let mut atlas = Atlas::new(0, 8, 8);
+        atlas.image.put_pixel(3, 2, Rgba([255, 0, 0, 255]));
+        atlas.image.put_pixel(3, 3, Rgba([0, 255, 0, 255]));
+        let rotated = PackedSprite {
+            width: 1,
+            height: 2,
+            rotated: true,
+            ..sprite
+        };
+        let upright = atlas.sprite_image(&rotated, false);
+        assert_eq!(upright.dimensions(), (2, 1));
+        assert_eq!(*upright.get_pixel(0, 0), Rgba([255, 0, 0, 255]));
+        assert_eq!(*upright.get_pixel(1, 0), Rgba([0, 255, 0, 255]));
+    }
 }
@@ -1,12 +1,34 @@
-use clap::{Args, Parser, Subcommand, ValueEnum};
+use clap::builder::{PossibleValue, TypedValueParser};
+use clap::{Args, Parser, Subcommand, ValueEnum, ValueHint};
+use std::ffi::OsStr;
+use std::num::{NonZeroU8, NonZeroUsize};
 use std::path::PathBuf;
 
+use serde::{Deserialize, Serialize};
+
+use crate::output::OutputTemplate;
+use crate::sprite::TrimMargin;
+
 #[derive(Parser, Debug)]
 #[command(name = "bento")]
 #[command(version, about = "Sprite atlas packer", long_about = None)]
 pub struct CliArgs {
     #[command(subcommand)]
     pub command: Command,
+
+    /// How failures are printed to stderr; exit codes are the same either way
+    #[arg(long, global = true, value_enum, default_value_t = ErrorFormat::Text)]
+    pub error_format: ErrorFormat,
+}
+
+/// How a failed run reports its error
+#[derive(Debug, Clone, Copy, ValueEnum, Default, PartialEq, Eq)]
+pub enum ErrorFormat {
+    /// A human-readable `Error: ...` line
+    #[default]
+    Text,
+    /// A JSON object with the error's kind, exit code, message and details
+    Json,
 }
 
 #[derive(Subcommand, Debug, Clone)]
@@ -17,29 +39,164 @@ pub enum Command {
     Godot(CommonArgs),
     /// Output TexturePacker .tpsheet metadata
     Tpsheet(CommonArgs),
+    /// Output libGDX TextureAtlas .atlas metadata
+    Libgdx(CommonArgs),
+    /// Output Phaser 3 atlas JSON
+    Phaser(CommonArgs),
+    /// Output Cocos2d-x / Cocos Creator .plist metadata
+    Cocos(CommonArgs),
+    /// Render metadata through a Handlebars template given with --template
+    Template(CommonArgs),
+    /// Pack every profile of a config file, or those picked with --profile,
+    /// each in the format set in its config
+    Build(BuildArgs),
+    /// Pack sprites not yet in an existing sheet into its free space,
+    /// keeping packed sprites where they are
+    Append(AppendArgs),
+    /// Check inputs, config and output directory for problems, without packing
+    Doctor(CommonArgs),
+    /// Check a config file's patterns, values and output directory without loading images
+    Validate {
+        /// Config file (.bento or bento.toml) to check
+        #[arg(value_hint = ValueHint::FilePath)]
+        config: PathBuf,
+    },
+    /// Print page sizes, occupancy and trim savings of a packed sheet or project
+    Info {
+        /// Sheet metadata (.json or .tpsheet), or a .bento project to lay out without writing
+        #[arg(value_hint = ValueHint::FilePath)]
+        path: PathBuf,
+        /// List every sprite's rect
+        #[arg(long)]
+        sprites: bool,
+        /// Print the report as JSON, including every sprite
+        #[arg(long)]
+        json: bool,
+    },
+    /// Compare two packed sheets: added, removed, moved and resized sprites
+    /// and page size changes
+    Diff {
+        /// Sheet metadata (.json or .tpsheet) before the change
+        #[arg(value_hint = ValueHint::FilePath)]
+        old: PathBuf,
+        /// Sheet metadata (.json or .tpsheet) after the change
+        #[arg(value_hint = ValueHint::FilePath)]
+        new: PathBuf,
+        /// Print the changes as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Extract the sprites of a packed atlas back into individual PNGs
+    Unpack {
+        /// Atlas metadata (.json or .tpsheet); atlas images are read from its directory
+        #[arg(value_hint = ValueHint::FilePath)]
+        metadata: PathBuf,
+        /// Atlas image to read instead of the one named in the metadata (single-page sheets only)
+        #[arg(long, value_hint = ValueHint::FilePath)]
+        image: Option<PathBuf>,
+        /// Output directory for sprite images
+        #[arg(short, long, default_value = ".", value_hint = ValueHint::DirPath)]
+        output: PathBuf,
+        /// Keep sprites trimmed instead of restoring their original size
+        #[arg(long)]
+        no_restore_trim: bool,
+    },
+    /// Write a starter config for the image folders found in a directory
+    Init {
+        /// Directory to scan for sprite folders
+        #[arg(default_value = ".", value_hint = ValueHint::DirPath)]
+        dir: PathBuf,
+        /// Config file to write [default: DIR/project.bento]; a .toml path writes TOML
+        #[arg(short, long, value_name = "FILE", value_hint = ValueHint::FilePath)]
+        output: Option<PathBuf>,
+        /// Overwrite an existing config file
+        #[arg(long)]
+        force: bool,
+        /// Confirm each inferred input and choose the output directory, name and format
+        #[arg(short, long)]
+        interactive: bool,
+    },
+    /// Print a shell completion script (e.g. `bento completions bash > /etc/bash_completion.d/bento`)
+    Completions {
+        /// Shell to generate completions for
+        shell: clap_complete::Shell,
+    },
     /// Launch the GUI
     #[cfg(feature = "gui")]
-    Gui,
+    Gui {
+        /// Project to open: a .bento file, or a folder containing one
+        #[arg(value_hint = ValueHint::AnyPath)]
+        path: Option<PathBuf>,
+    },
+}
+
+impl Command {
+    /// Metadata format a packing subcommand writes
+    pub fn output_format(&self) -> Option<OutputFormat> {
+        match self {
+            Command::Json(_) => Some(OutputFormat::Json),
+            Command::Godot(_) => Some(OutputFormat::Godot),
+            Command::Tpsheet(_) => Some(OutputFormat::Tpsheet),
+            Command::Libgdx(_) => Some(OutputFormat::Libgdx),
+            Command::Phaser(_) => Some(OutputFormat::Phaser),
+            Command::Cocos(_) => Some(OutputFormat::Cocos),
+            Command::Template(_) => Some(OutputFormat::Template),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Args, Debug, Clone)]
+pub struct BuildArgs {
+    /// Profile to build (repeatable); every profile when omitted
+    #[arg(long, value_name = "NAME")]
+    pub profile: Vec<String>,
+
+    #[command(flatten)]
+    pub common: CommonArgs,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct AppendArgs {
+    /// Sheet metadata (.json or .tpsheet) to add to [default: OUTPUT/NAME.json]
+    #[arg(long, value_name = "FILE", value_hint = ValueHint::FilePath)]
+    pub layout: Option<PathBuf>,
+
+    #[command(flatten)]
+    pub common: CommonArgs,
+}
+
+#[derive(Args, Debug, Clone, Default)]
 pub struct CommonArgs {
-    /// Input image files
-    #[arg(required_unless_present = "config")]
+    /// Input image files (`-` reads a list of paths from stdin, one per line)
+    #[arg(
+        required_unless_present_any = ["config", "input_list"],
+        value_hint = ValueHint::AnyPath
+    )]
     pub input: Vec<PathBuf>,
 
-    /// Load settings from a .bento config file
-    #[arg(short = 'c', long, value_name = "FILE")]
+    /// Read input paths from FILE, one per line (`-` reads stdin)
+    #[arg(long, value_name = "FILE", value_hint = ValueHint::FilePath)]
+    pub input_list: Option<PathBuf>,
+
+    /// Load settings from a .bento config file (`-` reads it from stdin)
+    #[arg(short = 'c', long, value_name = "FILE", value_hint = ValueHint::FilePath)]
     pub config: Option<PathBuf>,
 
     /// Output directory for atlas files [default: .]
-    #[arg(short, long)]
+    #[arg(short, long, value_hint = ValueHint::DirPath)]
     pub output: Option<PathBuf>,
 
     /// Base name for output files (atlas_0.png, atlas.json, etc.) [default: atlas]
     #[arg(short = 'n', long)]
     pub name: Option<String>,
 
+    /// Metadata formats to write from the same pack, comma-separated (e.g.
+    /// json,godot,tpsheet); added to the subcommand's own format, and replacing
+    /// the config's with `build`
+    #[arg(long, value_enum, value_delimiter = ',', value_name = "FORMATS")]
+    pub format: Vec<OutputFormat>,
+
     /// Maximum atlas width in pixels [default: 4096]
     #[arg(long)]
     pub max_width: Option<u32>,
@@ -52,13 +209,34 @@ pub struct CommonArgs {
     #[arg(short, long)]
     pub padding: Option<u32>,
 
+    /// Padding left and right of each sprite, overriding --padding
+    #[arg(long, value_name = "PADDING")]
+    pub padding_x: Option<u32>,
+
+    /// Padding above and below each sprite, overriding --padding
+    #[arg(long, value_name = "PADDING")]
+    pub padding_y: Option<u32>,
+
+    /// Write RGB and alpha as two separate PNGs (opaque color + grayscale mask)
+    #[arg(long)]
+    pub split_alpha: bool,
+
     /// Disable sprite trimming (remove transparent borders)
     #[arg(long)]
     pub no_trim: bool,
 
-    /// Keep N pixels of transparent border after trimming [default: 0]
+    /// Transparent border to keep after trimming: N, or LEFT,TOP,RIGHT,BOTTOM [default: 0]
+    #[arg(long, value_name = "MARGIN")]
+    pub trim_margin: Option<TrimMargin>,
+
+    /// Treat pixels with alpha at or below N (0-255) as transparent when trimming,
+    /// so faint anti-aliasing halos don't stop the trim [default: 0]
+    #[arg(long, value_name = "N")]
+    pub trim_alpha: Option<u8>,
+
+    /// Keep full frames in the atlas but record tight content bounds in metadata
     #[arg(long)]
-    pub trim_margin: Option<u32>,
+    pub record_trim: bool,
 
     /// Packing heuristic to use [default: best-short-side-fit]
     #[arg(long, value_enum)]
@@ -68,6 +246,16 @@ pub struct CommonArgs {
     #[arg(long)]
     pub opaque: bool,
 
+    /// Color that --opaque blends semi-transparent pixels over, e.g. #808080
+    /// (without it, alpha is dropped and pixels keep their color)
+    #[arg(long, value_name = "COLOR")]
+    pub matte_color: Option<Color>,
+
+    /// Fill the unused atlas area with this color, as #RRGGBB or #RRGGBBAA
+    /// [default: transparent]
+    #[arg(long, value_name = "COLOR")]
+    pub background_color: Option<Color>,
+
     /// Force power-of-two atlas dimensions
     #[arg(long)]
     pub pot: bool,
@@ -76,40 +264,324 @@ pub struct CommonArgs {
     #[arg(long)]
     pub extrude: Option<u32>,
 
+    /// What fills the extruded border: clamp (repeat edge pixels) or wrap
+    /// (copy the opposite edge, for seamless tiles) [default: clamp]
+    #[arg(long, value_enum)]
+    pub extrude_mode: Option<ExtrudeMode>,
+
     /// Align sprite regions to N-pixel boundaries (4 for BPTC/S3TC, 8 for ASTC 8x8).
     /// Prevents block-based VRAM compression from shifting sprite edges. [default: 0]
     #[arg(long)]
     pub block_align: Option<u32>,
 
+    /// Let sprites be stored rotated 90° clockwise when that packs them tighter.
+    /// Rotated sprites are flagged in the metadata; the godot format can't use them.
+    #[arg(long)]
+    pub allow_rotation: bool,
+
+    /// Search for the smallest page that fits all sprites instead of cropping the
+    /// full --max-width x --max-height bin. Slower: each page is repacked many times.
+    #[arg(long)]
+    pub size_search: bool,
+
+    /// Force square atlas pages
+    #[arg(long)]
+    pub square: bool,
+
+    /// Output every atlas page at exactly --max-width x --max-height instead of
+    /// cropping it to its sprites (still rounded by --pot)
+    #[arg(long)]
+    pub fixed_size: bool,
+
+    /// Round atlas width and height up to a multiple of N (4 for BCn textures),
+    /// independently of --pot. Sprite positions are unaffected. [default: 0]
+    #[arg(long, value_name = "N")]
+    pub size_multiple: Option<u32>,
+
+    /// Start a new atlas page after N sprites, even if more would fit
+    #[arg(long, value_name = "N")]
+    pub max_sprites_per_page: Option<NonZeroUsize>,
+
+    /// Fail if all atlas pages together exceed N pixels
+    #[arg(long, value_name = "N")]
+    pub max_total_pixels: Option<u64>,
+
+    /// Fail if all atlas pages together exceed N bytes of uncompressed RGBA
+    #[arg(long, value_name = "N")]
+    pub max_total_bytes: Option<u64>,
+
+    /// Fail, listing the sprites that don't fit, if packing needs more than N
+    /// atlas pages
+    #[arg(long, value_name = "N")]
+    pub max_atlases: Option<usize>,
+
+    /// Compute the layout and report page sizes without writing any files
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Write packing statistics as JSON: occupancy, wasted pixels, trim
+    /// savings, file sizes, sprite placements and the winning heuristic
+    #[arg(long, value_name = "FILE", value_hint = ValueHint::FilePath)]
+    pub report: Option<PathBuf>,
+
+    /// Keep sprites where a previous sheet's metadata (.json or .tpsheet)
+    /// placed them, packing only new and changed sprites around them
+    #[arg(long, value_name = "FILE", value_hint = ValueHint::FilePath)]
+    pub stable_layout: Option<PathBuf>,
+
     /// Verbose output
     #[arg(short, long)]
     pub verbose: bool,
 
     /// Resize images to target width in pixels (preserves aspect ratio)
-    #[arg(long, value_name = "PIXELS", conflicts_with = "resize_scale")]
+    #[arg(
+        long,
+        value_name = "PIXELS",
+        conflicts_with_all = ["resize_scale", "resize_max_width", "resize_fit"]
+    )]
     pub resize_width: Option<u32>,
 
     /// Resize images by scale factor (e.g., 0.5 for half size)
-    #[arg(long, value_name = "FACTOR", conflicts_with = "resize_width")]
+    #[arg(
+        long,
+        value_name = "FACTOR",
+        conflicts_with_all = ["resize_width", "resize_max_width", "resize_fit"]
+    )]
     pub resize_scale: Option<f32>,
 
+    /// Shrink images wider than PIXELS to that width (preserves aspect
+    /// ratio); narrower images are never enlarged
+    #[arg(
+        long,
+        value_name = "PIXELS",
+        conflicts_with_all = ["resize_width", "resize_scale", "resize_fit"]
+    )]
+    pub resize_max_width: Option<u32>,
+
+    /// Shrink images larger than a WxH box to fit inside it (preserves
+    /// aspect ratio); images that fit are never enlarged
+    #[arg(
+        long,
+        value_name = "WxH",
+        conflicts_with_all = ["resize_width", "resize_scale", "resize_max_width"]
+    )]
+    pub resize_fit: Option<ResizeFit>,
+
     /// Resize filter algorithm [default: lanczos3]
     #[arg(long, value_enum)]
     pub resize_filter: Option<ResizeFilter>,
 
+    /// Resize in linear light instead of on sRGB values, so high-contrast
+    /// edges don't darken when downscaling
+    #[arg(long)]
+    pub resize_linear: bool,
+
     /// Pack mode: single (use one ordering) or best (try multiple orderings) [default: single]
     #[arg(long, value_enum)]
     pub pack_mode: Option<PackMode>,
 
+    /// Extra random sprite orderings to try with --pack-mode best [default: 0]
+    #[arg(long)]
+    pub trials: Option<u32>,
+
+    /// Seed for the random orderings tried by --trials [default: 0]
+    #[arg(long)]
+    pub seed: Option<u64>,
+
     /// Use only the filename (no directory prefix) in sprite names
     #[arg(long)]
     pub filename_only: bool,
 
-    /// Compress PNG output (0-6 or 'max'). Default level is 2 if flag is present without value.
-    #[arg(long, value_name = "LEVEL", default_missing_value = "2", num_args = 0..=1)]
+    /// Pack each visible layer of Aseprite inputs as its own sprite
+    #[arg(long)]
+    pub split_layers: bool,
+
+    /// Log and skip images that can't be read instead of failing the run
+    #[arg(long, conflicts_with = "strict")]
+    pub skip_errors: bool,
+
+    /// Fail on the first image that can't be read, even when the config sets
+    /// skip_errors
+    #[arg(long)]
+    pub strict: bool,
+
+    /// Produce byte-identical output across runs and platforms: sprites with
+    /// equal sort keys are ordered by name and names always use '/'
+    #[arg(long)]
+    pub deterministic: bool,
+
+    /// Pack each top-level folder of the sprite names into its own atlas set,
+    /// named after the folder
+    #[arg(long)]
+    pub group_by_folder: bool,
+
+    /// Skip input files matching a glob pattern, e.g. "**/_wip/**" or
+    /// "*.backup.png" (repeatable; added to the config's patterns)
+    #[arg(long, value_name = "PATTERN")]
+    pub exclude: Vec<String>,
+
+    /// File name suffix of companion images such as normal maps, e.g. "_n":
+    /// hero_n.png is packed into its own atlas with the layout of hero.png
+    /// (repeatable; added to the config's suffixes)
+    #[arg(long = "companion", value_name = "SUFFIX")]
+    pub companions: Vec<String>,
+
+    /// Prefix for atlas image paths in metadata (e.g. textures/atlases/)
+    #[arg(long, value_name = "PREFIX")]
+    pub image_path_prefix: Option<String>,
+
+    /// Atlas image file names, e.g. "{name}-{index:2}.png"; a single page
+    /// drops {index} [default: {name}_{index}.png]
+    #[arg(long, value_name = "TEMPLATE")]
+    pub output_template: Option<OutputTemplate>,
+
+    /// Sort sprites before packing; 'none' keeps the input order [default: area]
+    #[arg(long, value_enum)]
+    pub sort: Option<SpriteSort>,
+
+    /// Order of sprites in the metadata output [default: packed]
+    #[arg(long, value_enum)]
+    pub sprite_order: Option<SpriteOrder>,
+
+    /// Write JSON, tpsheet and Phaser metadata minified instead of pretty-printed
+    #[arg(long)]
+    pub json_compact: bool,
+
+    /// Record each sprite's source file path in JSON metadata
+    #[arg(long, value_enum, value_name = "STYLE")]
+    pub source_paths: Option<SourcePaths>,
+
+    /// Add SHA-256 digests of the layout and each atlas image to the JSON meta block
+    #[arg(long)]
+    pub content_hash: bool,
+
+    /// Add a triangulated outline of each sprite's visible pixels to JSON
+    /// metadata (vertices, verticesUV, triangles), to draw sprites with less
+    /// overdraw than their full rectangle
+    #[arg(long)]
+    pub polygon: bool,
+
+    /// How far in pixels a --polygon outline may stray from the sprite's
+    /// pixels; higher values give fewer vertices but more overdraw [default: 2]
+    #[arg(long, value_name = "PIXELS")]
+    pub polygon_tolerance: Option<f32>,
+
+    /// Write the bento version, config hash and command line into text
+    /// chunks of each atlas PNG
+    #[arg(long)]
+    pub png_metadata: bool,
+
+    /// Godot version to write .tres resources for [default: 4]
+    #[arg(long, value_enum, value_name = "VERSION")]
+    pub godot_version: Option<GodotVersion>,
+
+    /// Godot directory holding the atlas, for .tres ext_resource paths [default: res://]
+    #[arg(long, value_name = "PATH")]
+    pub godot_res_path: Option<String>,
+
+    /// Handlebars template rendered by `bento template`
+    #[arg(long, value_name = "FILE", value_hint = ValueHint::FilePath)]
+    pub template: Option<PathBuf>,
+
+    /// Frame layout of single-page Phaser sheets [default: hash]
+    #[arg(long, value_enum)]
+    pub phaser_frames: Option<PhaserFrames>,
+
+    /// Write the atlas at each of these scales, e.g. 1,0.5,0.25, from a single
+    /// layout; scales other than 1 get an @{SCALE}x suffix [default: 1]
+    #[arg(long, value_name = "SCALES", value_delimiter = ',')]
+    pub scales: Vec<f32>,
+
+    /// Generate N mip levels below the full-size atlas (0 = none) [default: 0]
+    #[arg(long, value_name = "LEVELS")]
+    pub mipmaps: Option<u32>,
+
+    /// How to write mip levels [default: png]
+    #[arg(long, value_enum)]
+    pub mipmap_format: Option<MipmapFormat>,
+
+    /// Also encode each atlas page as Basis Universal KTX2 (needs the basisu encoder on PATH)
+    #[arg(long, value_enum, value_name = "MODE")]
+    pub basis: Option<BasisMode>,
+
+    /// Place sprites in uniform grid cells in input order instead of packing
+    /// them: 'auto' (the default) sizes cells to the largest sprite, or give WxH
+    #[arg(
+        long,
+        value_name = "WxH",
+        default_missing_value = "auto",
+        num_args = 0..=1
+    )]
+    pub grid: Option<GridCell>,
+
+    /// Compress PNG output (0-6, 'max', or 'zopfli[:ITERATIONS]'). Default level is 2 if flag is present without value.
+    #[arg(
+        long,
+        value_name = "LEVEL",
+        default_missing_value = "2",
+        num_args = 0..=1,
+        value_parser = CompressionLevelParser
+    )]
     pub compress: Option<CompressionLevel>,
 }
 
+/// Metadata format written next to the atlas images (mirrors the packing subcommands)
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    ValueEnum,
+    Default,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    /// JSON metadata
+    #[default]
+    Json,
+    /// One Godot .tres per sprite
+    Godot,
+    /// TexturePacker .tpsheet
+    Tpsheet,
+    /// libGDX TextureAtlas .atlas
+    Libgdx,
+    /// Phaser 3 atlas JSON
+    Phaser,
+    /// Cocos2d-x / Cocos Creator .plist
+    Cocos,
+    /// Handlebars template given with --template
+    Template,
+}
+
+impl OutputFormat {
+    /// Name used for the `format` key in config files and `--format`
+    pub fn config_name(self) -> &'static str {
+        match self {
+            OutputFormat::Json => "json",
+            OutputFormat::Godot => "godot",
+            OutputFormat::Tpsheet => "tpsheet",
+            OutputFormat::Libgdx => "libgdx",
+            OutputFormat::Phaser => "phaser",
+            OutputFormat::Cocos => "cocos",
+            OutputFormat::Template => "template",
+        }
+    }
+
+    /// Format for a config `format` name
+    pub fn from_config_name(name: &str) -> Option<Self> {
+        Self::value_variants()
+            .iter()
+            .copied()
+            .find(|format| format.config_name() == name)
+    }
+}
+
 #[derive(Debug, Clone, Copy, ValueEnum, Default, PartialEq, Eq)]
 pub enum PackMode {
     /// Use sprites in input order
@@ -117,6 +589,91 @@ pub enum PackMode {
     Single,
     /// Try multiple sprite orderings and pick the best result
     Best,
+    /// Like best, then spread sprites over all pages at once when that needs
+    /// fewer pages
+    MinPages,
+}
+
+/// Order of sprites in metadata output
+#[derive(Debug, Clone, Copy, ValueEnum, Default, PartialEq, Eq, Hash)]
+pub enum SpriteOrder {
+    /// Order in which sprites were placed in the atlas
+    #[default]
+    Packed,
+    /// Alphabetical by sprite name (stable diffs in version control)
+    Name,
+    /// Order of the resolved input files
+    Input,
+}
+
+/// How sprites are sorted before packing
+#[derive(Debug, Clone, Copy, ValueEnum, Default, PartialEq, Eq, Hash)]
+pub enum SpriteSort {
+    /// Largest area first
+    #[default]
+    Area,
+    /// Longest side first
+    MaxSide,
+    /// Tallest first
+    Height,
+    /// Widest first
+    Width,
+    /// Largest width plus height first
+    Perimeter,
+    /// Alphabetical by sprite name
+    Name,
+    /// Keep the order of the resolved input files
+    None,
+}
+
+/// How sprite source paths are written to JSON metadata
+#[derive(Debug, Clone, Copy, ValueEnum, PartialEq, Eq, Hash)]
+pub enum SourcePaths {
+    /// Absolute file paths
+    Absolute,
+    /// Relative to the config file directory (or the working directory without a config)
+    Relative,
+}
+
+/// How frames are listed in single-page Phaser sheets
+#[derive(Debug, Clone, Copy, ValueEnum, Default, PartialEq, Eq, Hash)]
+pub enum PhaserFrames {
+    /// An object keyed by frame name (Phaser's JSON hash)
+    #[default]
+    Hash,
+    /// A list of frames with a `filename` each (Phaser's JSON array)
+    Array,
+}
+
+/// Godot resource syntax for `.tres` output
+#[derive(Debug, Clone, Copy, ValueEnum, Default, PartialEq, Eq, Hash)]
+pub enum GodotVersion {
+    /// Godot 3.x (`format=2`, `Texture`)
+    #[value(name = "3")]
+    V3,
+    /// Godot 4.x (`format=3`, `Texture2D`)
+    #[default]
+    #[value(name = "4")]
+    V4,
+}
+
+/// Basis Universal codec for supercompressed KTX2 output
+#[derive(Debug, Clone, Copy, ValueEnum, PartialEq, Eq, Hash)]
+pub enum BasisMode {
+    /// High quality, larger files (UASTC with Zstandard)
+    Uastc,
+    /// Small files, lower quality (ETC1S with BasisLZ)
+    Etc1s,
+}
+
+/// Output container for generated mip levels
+#[derive(Debug, Clone, Copy, ValueEnum, Default, PartialEq, Eq, Hash)]
+pub enum MipmapFormat {
+    /// One PNG per level next to the atlas (`atlas.mip1.png`, ...)
+    #[default]
+    Png,
+    /// A single KTX2 texture holding the atlas and all its levels
+    Ktx2,
 }
 
 /// Resize filter algorithm
@@ -152,21 +709,168 @@ impl ResizeFilter {
     }
 }
 
-/// PNG compression level (0-6 or max)
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// What fills the extruded border around a sprite
+#[derive(Debug, Clone, Copy, ValueEnum, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExtrudeMode {
+    /// Repeat the sprite's edge pixels
+    #[default]
+    Clamp,
+    /// Copy pixels from the opposite edge, for tiles that repeat seamlessly
+    Wrap,
+}
+
+/// Cell size for grid packing
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum GridCell {
+    /// As large as the largest sprite
+    #[default]
+    Auto,
+    /// A fixed cell; every sprite must fit in it
+    Fixed { width: u32, height: u32 },
+}
+
+impl std::str::FromStr for GridCell {
+    type Err = String;
+
+    /// "auto", "WxH", or "N" for square cells
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.eq_ignore_ascii_case("auto") {
+            return Ok(GridCell::Auto);
+        }
+        let invalid = || format!("invalid grid cell '{}': expected 'auto', WxH or N", s);
+        let (width, height) = match s.split_once(['x', 'X']) {
+            Some((w, h)) => (w.parse(), h.parse()),
+            None => (s.parse(), s.parse()),
+        };
+        match (width, height) {
+            (Ok(width), Ok(height)) if width > 0 && height > 0 => {
+                Ok(GridCell::Fixed { width, height })
+            }
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl std::fmt::Display for GridCell {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GridCell::Auto => f.write_str("auto"),
+            GridCell::Fixed { width, height } => write!(f, "{}x{}", width, height),
+        }
+    }
+}
+
+/// Bounding box that sprites are shrunk to fit within
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ResizeFit {
+    pub width: u32,
+    pub height: u32,
+}
+
+impl std::str::FromStr for ResizeFit {
+    type Err = String;
+
+    /// "WxH", or "N" for a square box
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (width, height) = match s.split_once(['x', 'X']) {
+            Some((w, h)) => (w.parse(), h.parse()),
+            None => (s.parse(), s.parse()),
+        };
+        match (width, height) {
+            (Ok(width), Ok(height)) if width > 0 && height > 0 => Ok(ResizeFit { width, height }),
+            _ => Err(format!("invalid resize box '{}': expected WxH or N", s)),
+        }
+    }
+}
+
+impl std::fmt::Display for ResizeFit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}x{}", self.width, self.height)
+    }
+}
+
+/// Color written as `#RRGGBB` or `#RRGGBBAA` (the `#` is optional)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+}
+
+impl std::str::FromStr for Color {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let hex = s.strip_prefix('#').unwrap_or(s);
+        let channel = |i: usize| {
+            hex.get(i * 2..i * 2 + 2)
+                .and_then(|digits| u8::from_str_radix(digits, 16).ok())
+        };
+        let alpha = if hex.len() == 8 {
+            channel(3)
+        } else {
+            Some(255)
+        };
+        match (channel(0), channel(1), channel(2), alpha) {
+            (Some(r), Some(g), Some(b), Some(a)) if matches!(hex.len(), 6 | 8) => {
+                Ok(Color { r, g, b, a })
+            }
+            _ => Err(format!(
+                "invalid color '{}': expected #RRGGBB or #RRGGBBAA",
+                s
+            )),
+        }
+    }
+}
+
+impl From<Color> for image::Rgba<u8> {
+    fn from(color: Color) -> Self {
+        image::Rgba([color.r, color.g, color.b, color.a])
+    }
+}
+
+impl std::fmt::Display for Color {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.r, self.g, self.b)?;
+        if self.a < 255 {
+            write!(f, "{:02x}", self.a)?;
+        }
+        Ok(())
+    }
+}
+
+/// PNG compression level (0-6, max, or zopfli)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum CompressionLevel {
     /// Optimization level 0-6
     Level(u8),
     /// Maximum compression
     Max,
+    /// Zopfli deflate with the given iteration count (much slower, smallest files)
+    Zopfli(NonZeroU8),
+}
+
+impl CompressionLevel {
+    /// Zopfli iteration count used when none is given (matches oxipng)
+    pub const DEFAULT_ZOPFLI_ITERATIONS: NonZeroU8 = NonZeroU8::new(15).unwrap();
 }
 
 impl std::str::FromStr for CompressionLevel {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if s.eq_ignore_ascii_case("max") {
+        let lower = s.to_ascii_lowercase();
+        if lower == "max" {
             Ok(CompressionLevel::Max)
+        } else if lower == "zopfli" {
+            Ok(CompressionLevel::Zopfli(Self::DEFAULT_ZOPFLI_ITERATIONS))
+        } else if let Some(iterations) = lower.strip_prefix("zopfli:") {
+            iterations
+                .parse::<NonZeroU8>()
+                .map(CompressionLevel::Zopfli)
+                .map_err(|_e| format!("zopfli iterations must be 1-255, got {}", iterations))
         } else {
             s.parse::<u8>()
                 .map_err(|_e| format!("invalid compression level: {}", s))
@@ -174,13 +878,52 @@ impl std::str::FromStr for CompressionLevel {
                     if n <= 6 {
                         Ok(CompressionLevel::Level(n))
                     } else {
-                        Err(format!("compression level must be 0-6 or 'max', got {}", n))
+                        Err(format!(
+                            "compression level must be 0-6, 'max', or 'zopfli', got {}",
+                            n
+                        ))
                     }
                 })
         }
     }
 }
 
+/// Parses `--compress` values, and lists the named levels for help and shell completion
+/// (`zopfli:N` is accepted but not offered)
+#[derive(Debug, Clone, Copy)]
+pub struct CompressionLevelParser;
+
+impl TypedValueParser for CompressionLevelParser {
+    type Value = CompressionLevel;
+
+    fn parse_ref(
+        &self,
+        cmd: &clap::Command,
+        arg: Option<&clap::Arg>,
+        value: &OsStr,
+    ) -> Result<Self::Value, clap::Error> {
+        (|s: &str| s.parse::<CompressionLevel>()).parse_ref(cmd, arg, value)
+    }
+
+    fn possible_values(&self) -> Option<Box<dyn Iterator<Item = PossibleValue> + '_>> {
+        let levels = ["0", "1", "2", "3", "4", "5", "6"].map(PossibleValue::new);
+        Some(Box::new(levels.into_iter().chain([
+            PossibleValue::new("max").help("Maximum oxipng effort"),
+            PossibleValue::new("zopfli").help("Zopfli deflate, 15 iterations (slowest)"),
+        ])))
+    }
+}
+
+impl std::fmt::Display for CompressionLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CompressionLevel::Level(n) => write!(f, "{}", n),
+            CompressionLevel::Max => write!(f, "max"),
+            CompressionLevel::Zopfli(iterations) => write!(f, "zopfli:{}", iterations),
+        }
+    }
+}
+
 impl Default for CompressionLevel {
     fn default() -> Self {
         CompressionLevel::Level(2)
@@ -209,3 +952,46 @@ pub enum PackingHeuristic {
     #[value(name = "best")]
     Best,
 }
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn test_compress_values_and_completions() {
+        let compress = |extra: &[&str]| {
+            let args = ["bento", "json", "a.png"].iter().chain(extra);
+            match CliArgs::try_parse_from(args).unwrap().command {
+                Command::Json(args) => args.compress,
+                _ => None,
+            }
+        };
+        assert_eq!(compress(&[]), None);
+        assert_eq!(compress(&["--compress"]), Some(CompressionLevel::Level(2)));
+        assert_eq!(
+            compress(&["--compress", "zopfli:5"]),
+            Some(CompressionLevel::Zopfli(NonZeroU8::new(5).unwrap()))
+        );
+        assert!(CliArgs::try_parse_from(["bento", "json", "a.png", "--compress", "7"]).is_err());
+
+        let script = |shell| {
+            let mut script = Vec::new();
+            clap_complete::generate(shell, &mut CliArgs::command(), "bento", &mut script);
+            String::from_utf8(script).unwrap()
+        };
+        // Named levels are offered, and paths complete as files or directories
+        assert!(script(clap_complete::Shell::Bash).contains("-W \"0 1 2 3 4 5 6 max zopfli\""));
+        assert!(script(clap_complete::Shell::Zsh).contains(":OUTPUT:_files -/"));
+    }
+
+    #[test]
+    fn test_max_sprites_per_page_rejects_zero() {
+        let parse = |value| {
+            CliArgs::try_parse_from(["bento", "json", "a.png", "--max-sprites-per-page", value])
+        };
+        assert!(parse("0").is_err());
+        assert!(parse("1").is_ok());
+    }
+}
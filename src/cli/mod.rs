@@ -1,5 +1,7 @@
 mod args;
 
 pub use args::{
-    CliArgs, Command, CommonArgs, CompressionLevel, PackMode, PackingHeuristic, ResizeFilter,
+    AppendArgs, BasisMode, BuildArgs, CliArgs, Color, Command, CommonArgs, CompressionLevel,
+    ErrorFormat, ExtrudeMode, GodotVersion, GridCell, MipmapFormat, OutputFormat, PackMode,
+    PackingHeuristic, PhaserFrames, ResizeFilter, ResizeFit, SourcePaths, SpriteOrder, SpriteSort,
 };
@@ -1,8 +1,12 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result, bail};
 
+use super::toml::toml_to_json;
 use super::types::BentoConfig;
+use crate::atlas::SpriteSettings;
+use crate::sprite::SpriteOverride;
 
 /// A loaded configuration file with its associated directory.
 ///
@@ -25,9 +29,41 @@ impl LoadedConfig {
         let content = std::fs::read_to_string(path)
             .with_context(|| format!("failed to read config file: {}", path.display()))?;
 
-        let config: BentoConfig = serde_json::from_str(&content)
-            .with_context(|| format!("failed to parse config file: {}", path.display()))?;
+        let config_dir = path
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| PathBuf::from("."));
+
+        let parsed = if is_toml_config(path) {
+            Self::parse_toml(&content, config_dir)
+        } else {
+            Self::parse(&content, config_dir)
+        };
+        parsed.with_context(|| format!("failed to parse config file: {}", path.display()))
+    }
+
+    /// Read a config from standard input.
+    ///
+    /// There is no config file location, so relative paths in the config
+    /// resolve against the current directory.
+    pub fn from_stdin() -> Result<Self> {
+        let content = std::io::read_to_string(std::io::stdin())
+            .context("failed to read config from stdin")?;
+
+        Self::parse(&content, PathBuf::from(".")).context("failed to parse config from stdin")
+    }
+
+    /// Parse config JSON, resolving relative paths against `config_dir`
+    pub fn parse(content: &str, config_dir: PathBuf) -> Result<Self> {
+        Self::new(serde_json::from_str(content)?, config_dir)
+    }
 
+    /// Parse a TOML config with the same schema as JSON configs
+    pub fn parse_toml(content: &str, config_dir: PathBuf) -> Result<Self> {
+        Self::new(serde_json::from_value(toml_to_json(content)?)?, config_dir)
+    }
+
+    fn new(config: BentoConfig, config_dir: PathBuf) -> Result<Self> {
         // Validate config version
         if config.version != CONFIG_VERSION {
             bail!(
@@ -37,11 +73,6 @@ impl LoadedConfig {
             );
         }
 
-        let config_dir = path
-            .parent()
-            .map(Path::to_path_buf)
-            .unwrap_or_else(|| PathBuf::from("."));
-
         Ok(Self { config, config_dir })
     }
 
@@ -85,14 +116,208 @@ impl LoadedConfig {
         Ok(results)
     }
 
+    /// Resolve excluded image paths relative to the config file directory.
+    /// Glob patterns are left out; see [`LoadedConfig::exclude_patterns`].
+    pub fn resolve_excluded(&self) -> HashSet<PathBuf> {
+        self.config
+            .exclude
+            .iter()
+            .filter(|path| !is_glob_pattern(path))
+            .map(|path| self.config_dir.join(path))
+            .collect()
+    }
+
+    /// Glob patterns in the exclude list, matched against input paths while
+    /// collecting images.
+    pub fn exclude_patterns(&self) -> Vec<String> {
+        self.config
+            .exclude
+            .iter()
+            .filter(|entry| is_glob_pattern(entry))
+            .cloned()
+            .collect()
+    }
+
+    /// Resolve sprite name overrides, keyed by path relative to the config file directory.
+    pub fn resolve_names(&self) -> HashMap<PathBuf, String> {
+        self.config
+            .names
+            .iter()
+            .map(|(path, name)| (self.config_dir.join(path), name.clone()))
+            .collect()
+    }
+
+    /// Names of the profiles defined in the config, in alphabetical order.
+    pub fn profile_names(&self) -> Vec<String> {
+        self.config.profiles.keys().cloned().collect()
+    }
+
+    /// The config with a profile's keys replacing the top-level ones.
+    ///
+    /// Paths in the profile stay relative to the config file directory.
+    pub fn profile(&self, name: &str) -> Result<Self> {
+        let Some(profile) = self.config.profiles.get(name) else {
+            let names = self.profile_names();
+            bail!(
+                "unknown profile '{}'; the config defines {}",
+                name,
+                if names.is_empty() {
+                    "no profiles".to_string()
+                } else {
+                    names.join(", ")
+                }
+            );
+        };
+
+        let mut value = serde_json::to_value(&self.config)?;
+        if let serde_json::Value::Object(base) = &mut value {
+            base.remove("profiles");
+            base.extend(profile.clone());
+        }
+        let config = serde_json::from_value(value)
+            .with_context(|| format!("invalid settings in profile '{}'", name))?;
+        Ok(Self {
+            config,
+            config_dir: self.config_dir.clone(),
+        })
+    }
+
     /// Resolve the output directory relative to the config file directory.
     pub fn resolve_output_dir(&self) -> PathBuf {
         self.config_dir.join(&self.config.output_dir)
     }
 }
 
+/// Whether a config path is TOML (`bento.toml`) rather than JSON (`.bento`)
+pub fn is_toml_config(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("toml"))
+}
+
+/// Compile a pack-order list of sprite names and glob patterns.
+pub fn compile_pack_order(entries: &[String]) -> Result<Vec<glob::Pattern>> {
+    entries
+        .iter()
+        .map(|entry| {
+            glob::Pattern::new(entry)
+                .with_context(|| format!("invalid pack_order pattern: {}", entry))
+        })
+        .collect()
+}
+
+/// Compile keep-together sprite names and glob patterns, one group each.
+pub fn compile_keep_together(entries: &[String]) -> Result<Vec<glob::Pattern>> {
+    entries
+        .iter()
+        .map(|entry| {
+            glob::Pattern::new(entry)
+                .with_context(|| format!("invalid keep_together pattern: {}", entry))
+        })
+        .collect()
+}
+
+/// Compile exclude glob patterns.
+pub fn compile_exclude(patterns: &[String]) -> Result<Vec<glob::Pattern>> {
+    patterns
+        .iter()
+        .map(|pattern| {
+            glob::Pattern::new(pattern)
+                .with_context(|| format!("invalid exclude pattern: {}", pattern))
+        })
+        .collect()
+}
+
+/// Resolve per-sprite padding, extrude and extrude mode from the `overrides` section and
+/// extrude overrides, both keyed by sprite name or glob pattern.
+///
+/// An exact sprite name takes precedence; otherwise the most specific matching
+/// pattern applies (see [`compile_overrides`]), and `overrides` wins over
+/// `extrude_overrides`.
+/// Sprites without a match keep the global settings.
+pub fn resolve_sprite_settings<'a>(
+    extrude_overrides: &BTreeMap<String, u32>,
+    overrides: &BTreeMap<String, SpriteOverride>,
+    names: impl IntoIterator<Item = &'a str>,
+) -> Result<HashMap<String, SpriteSettings>> {
+    let patterns = compile_overrides(extrude_overrides, "extrude_overrides")?;
+    let overrides = compile_overrides(overrides, "overrides")?;
+
+    let mut settings = HashMap::new();
+    for name in names {
+        let sprite = SpriteOverride::resolve(&overrides, name);
+        let extrude = sprite.extrude.or_else(|| {
+            patterns
+                .iter()
+                .find(|(pattern, _)| pattern.matches(name))
+                .map(|&(_, extrude)| extrude)
+        });
+        if extrude.is_some()
+            || sprite.extrude_mode.is_some()
+            || sprite.padding.is_some()
+            || sprite.priority.is_some()
+        {
+            settings.insert(
+                name.to_string(),
+                SpriteSettings {
+                    padding: sprite.padding,
+                    extrude,
+                    extrude_mode: sprite.extrude_mode,
+                    priority: sprite.priority,
+                },
+            );
+        }
+    }
+
+    Ok(settings)
+}
+
+/// Compile an override table keyed by sprite name or glob pattern into match order:
+/// exact names first, then the most specific patterns. A longer fixed start wins
+/// (`ui/hud/*` before `ui/*` before `*.png` and `*`), then more fixed characters,
+/// then key order.
+pub fn compile_overrides<T: Copy>(
+    overrides: &BTreeMap<String, T>,
+    section: &str,
+) -> Result<Vec<(glob::Pattern, T)>> {
+    let (exact, mut patterns): (Vec<_>, Vec<_>) =
+        overrides.iter().partition(|(key, _)| !is_glob_pattern(key));
+    // Stable, so equally specific patterns stay in key order
+    patterns.sort_by_key(|(key, _)| std::cmp::Reverse(pattern_specificity(key)));
+
+    exact
+        .into_iter()
+        .chain(patterns)
+        .map(|(key, &value)| {
+            glob::Pattern::new(key)
+                .map(|pattern| (pattern, value))
+                .with_context(|| format!("invalid {} pattern: {}", section, key))
+        })
+        .collect()
+}
+
+/// How specific a glob pattern is: the length of its fixed start, then its
+/// number of fixed characters. Wildcards and `[...]` classes don't count.
+fn pattern_specificity(pattern: &str) -> (usize, usize) {
+    let prefix = pattern
+        .chars()
+        .take_while(|c| !matches!(c, '*' | '?' | '['))
+        .count();
+    let mut fixed = 0;
+    let mut in_class = false;
+    for c in pattern.chars() {
+        match c {
+            '[' if !in_class => in_class = true,
+            ']' if in_class => in_class = false,
+            '*' | '?' => {}
+            _ if !in_class => fixed += 1,
+            _ => {}
+        }
+    }
+    (prefix, fixed)
+}
+
 /// Check if a pattern contains glob characters.
-fn is_glob_pattern(pattern: &str) -> bool {
+pub fn is_glob_pattern(pattern: &str) -> bool {
     pattern.contains('*') || pattern.contains('?') || pattern.contains('[')
 }
 
@@ -112,8 +337,11 @@ fn contains_brace_expansion(pattern: &str) -> bool {
 }
 
 #[cfg(test)]
+#[allow(clippy::unwrap_used)]
 mod tests {
     use super::*;
+    use crate::cli::ExtrudeMode;
+    use crate::config::FormatConfig;
 
     #[test]
     fn test_is_glob_pattern() {
@@ -126,6 +354,252 @@ mod tests {
         assert!(!is_glob_pattern("sprites/hero.png"));
     }
 
+    #[test]
+    fn test_parse_config() {
+        let loaded = LoadedConfig::parse(
+            r#"{"version": 1, "input": ["sprites/*.png"], "output_dir": "out"}"#,
+            PathBuf::from("project"),
+        )
+        .unwrap();
+        assert_eq!(loaded.config.input, ["sprites/*.png"]);
+        assert_eq!(loaded.resolve_output_dir(), Path::new("project/out"));
+
+        let err = LoadedConfig::parse(r#"{"version": 2}"#, PathBuf::from("."))
+            .err()
+            .map(|e| e.to_string());
+        assert!(err.is_some_and(|e| e.contains("unsupported config version: 2")));
+        assert!(LoadedConfig::parse("not json", PathBuf::from(".")).is_err());
+    }
+
+    #[test]
+    fn test_profiles_override_top_level_settings() {
+        let loaded = LoadedConfig::parse(
+            r#"{
+                "version": 1,
+                "input": ["sprites/*.png"],
+                "padding": 2,
+                "pot": true,
+                "profiles": {
+                    "ui": {"input": ["ui/*.png"], "name": "ui", "padding": 0},
+                    "tiles": {"format": "godot"},
+                    "web": {"format": ["json", "phaser"]}
+                }
+            }"#,
+            PathBuf::from("project"),
+        )
+        .unwrap();
+        assert_eq!(loaded.profile_names(), ["tiles", "ui", "web"]);
+
+        let ui = loaded.profile("ui").unwrap();
+        assert_eq!(ui.config.input, ["ui/*.png"]);
+        assert_eq!(ui.config.name, "ui");
+        assert_eq!(ui.config.padding, 0);
+        assert!(ui.config.pot);
+        assert!(ui.config.profiles.is_empty());
+        assert_eq!(ui.config_dir, Path::new("project"));
+
+        let tiles = loaded.profile("tiles").unwrap();
+        assert_eq!(
+            tiles.config.format.as_ref().map(FormatConfig::names),
+            Some(vec!["godot"])
+        );
+        assert_eq!(tiles.config.padding, 2);
+
+        let web = loaded.profile("web").unwrap();
+        assert_eq!(
+            web.config.format.as_ref().map(FormatConfig::names),
+            Some(vec!["json", "phaser"])
+        );
+
+        let err = loaded.profile("chars").err().map(|e| e.to_string());
+        assert!(err.is_some_and(|e| e.contains("tiles, ui, web")));
+    }
+
+    #[test]
+    fn test_parse_toml_config() {
+        let loaded = LoadedConfig::parse_toml(
+            "version = 1\ninput = [\"sprites/*.png\"]\n\n[extrude_overrides]\n\"tiles/*\" = 2\n",
+            PathBuf::from("project"),
+        )
+        .unwrap();
+        assert_eq!(loaded.config.input, ["sprites/*.png"]);
+        assert_eq!(loaded.config.extrude_overrides["tiles/*"], 2);
+        assert!(LoadedConfig::parse_toml("version = 2", PathBuf::from(".")).is_err());
+        assert!(is_toml_config(Path::new("bento.toml")));
+        assert!(!is_toml_config(Path::new("project.bento")));
+    }
+
+    #[test]
+    fn test_exclude_paths_and_patterns() {
+        let loaded = LoadedConfig::parse(
+            r#"{"version": 1, "exclude": ["sprites/old.png", "**/_wip/**"]}"#,
+            PathBuf::from("project"),
+        )
+        .unwrap();
+        assert_eq!(
+            loaded.resolve_excluded(),
+            HashSet::from([PathBuf::from("project/sprites/old.png")])
+        );
+        assert_eq!(loaded.exclude_patterns(), ["**/_wip/**"]);
+        assert!(compile_exclude(&loaded.exclude_patterns()).is_ok());
+        assert!(compile_exclude(&["[".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_resolve_sprite_settings() {
+        let overrides = BTreeMap::from([
+            ("tiles/*".to_string(), 2),
+            ("tiles/water.png".to_string(), 4),
+        ]);
+        let sprite_overrides = BTreeMap::from([
+            (
+                "tiles/grass.png".to_string(),
+                SpriteOverride {
+                    padding: Some(0),
+                    ..SpriteOverride::default()
+                },
+            ),
+            (
+                "tiles/*".to_string(),
+                SpriteOverride {
+                    extrude: Some(3),
+                    extrude_mode: Some(ExtrudeMode::Wrap),
+                    padding: Some(1),
+                    ..SpriteOverride::default()
+                },
+            ),
+            (
+                "ui/*".to_string(),
+                SpriteOverride {
+                    priority: Some(5),
+                    ..SpriteOverride::default()
+                },
+            ),
+        ]);
+        let settings = resolve_sprite_settings(
+            &overrides,
+            &BTreeMap::new(),
+            ["tiles/grass.png", "tiles/water.png", "hero.png"],
+        )
+        .unwrap_or_default();
+
+        assert_eq!(settings["tiles/grass.png"].extrude, Some(2));
+        assert_eq!(settings["tiles/water.png"].extrude, Some(4));
+        assert!(!settings.contains_key("hero.png"));
+
+        // Fields come from the first matching override that sets them, and
+        // the overrides section wins over extrude_overrides
+        let settings = resolve_sprite_settings(
+            &overrides,
+            &sprite_overrides,
+            ["tiles/grass.png", "ui/hud.png", "hero.png"],
+        )
+        .unwrap_or_default();
+        assert_eq!(settings["tiles/grass.png"].padding, Some(0));
+        assert_eq!(settings["tiles/grass.png"].extrude, Some(3));
+        assert_eq!(
+            settings["tiles/grass.png"].extrude_mode,
+            Some(ExtrudeMode::Wrap)
+        );
+        assert_eq!(settings["ui/hud.png"].extrude_mode, None);
+        assert_eq!(settings["ui/hud.png"].priority, Some(5));
+        assert_eq!(settings["ui/hud.png"].padding, None);
+        assert!(!settings.contains_key("hero.png"));
+
+        assert!(
+            resolve_sprite_settings(
+                &BTreeMap::from([("[".to_string(), 1)]),
+                &BTreeMap::new(),
+                []
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_specific_patterns_beat_catch_alls() {
+        let overrides = BTreeMap::from([
+            ("*".to_string(), 1),
+            ("*.png".to_string(), 2),
+            ("ui/*".to_string(), 3),
+            ("ui/hud/*".to_string(), 4),
+            ("ui/hud/bar.png".to_string(), 5),
+            ("ui/[ab]*".to_string(), 6),
+        ]);
+        let compiled = compile_overrides(&overrides, "overrides").unwrap();
+        let order: Vec<&str> = compiled.iter().map(|(p, _)| p.as_str()).collect();
+        // Equally specific patterns keep key order
+        assert_eq!(
+            order,
+            [
+                "ui/hud/bar.png",
+                "ui/hud/*",
+                "ui/*",
+                "ui/[ab]*",
+                "*.png",
+                "*"
+            ]
+        );
+
+        // A catch-all default is narrowed by a more specific pattern, whatever
+        // their key order
+        let sprite_overrides = BTreeMap::from([
+            (
+                "*".to_string(),
+                SpriteOverride {
+                    padding: Some(1),
+                    ..SpriteOverride::default()
+                },
+            ),
+            (
+                "ui/*".to_string(),
+                SpriteOverride {
+                    padding: Some(4),
+                    ..SpriteOverride::default()
+                },
+            ),
+        ]);
+        let settings = resolve_sprite_settings(
+            &BTreeMap::from([("*".to_string(), 0), ("ui/*".to_string(), 2)]),
+            &sprite_overrides,
+            ["ui/panel.png", "hero.png"],
+        )
+        .unwrap();
+        assert_eq!(settings["ui/panel.png"].padding, Some(4));
+        assert_eq!(settings["ui/panel.png"].extrude, Some(2));
+        assert_eq!(settings["hero.png"].padding, Some(1));
+        assert_eq!(settings["hero.png"].extrude, Some(0));
+    }
+
+    #[test]
+    fn test_compress_config_levels() {
+        use crate::cli::CompressionLevel;
+        use crate::config::CompressConfig;
+
+        let parse = |json: &str| {
+            serde_json::from_str::<CompressConfig>(json)
+                .map_err(|e| e.to_string())
+                .and_then(|c| c.to_level())
+        };
+        assert_eq!(parse("4"), Ok(CompressionLevel::Level(4)));
+        assert_eq!(parse("\"max\""), Ok(CompressionLevel::Max));
+        assert_eq!(
+            parse("\"zopfli\""),
+            Ok(CompressionLevel::Zopfli(
+                CompressionLevel::DEFAULT_ZOPFLI_ITERATIONS
+            ))
+        );
+        let level = parse("\"zopfli:5\"");
+        assert!(matches!(level, Ok(CompressionLevel::Zopfli(n)) if n.get() == 5));
+        assert!(parse("\"zopfli:0\"").is_err());
+        assert!(parse("\"fast\"").is_err());
+
+        // Named levels survive a save/load round trip
+        if let Ok(level) = level {
+            assert_eq!(CompressConfig::from(level).to_level(), Ok(level));
+        }
+    }
+
     #[test]
     fn test_contains_brace_expansion() {
         // Patterns with brace expansion
@@ -1,7 +1,11 @@
 mod load;
 mod save;
+mod toml;
 mod types;
 
-pub use load::LoadedConfig;
+pub use load::{
+    LoadedConfig, compile_exclude, compile_keep_together, compile_overrides, compile_pack_order,
+    is_glob_pattern, is_toml_config, resolve_sprite_settings,
+};
 pub use save::{make_relative, save_config};
-pub use types::{BentoConfig, CompressConfig, ResizeConfig};
+pub use types::{BentoConfig, CompressConfig, FormatConfig, ResizeConfig};
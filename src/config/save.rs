@@ -2,12 +2,26 @@ use std::path::Path;
 
 use anyhow::{Context, Result};
 
+use super::load::is_toml_config;
+use super::toml::update_toml;
 use super::types::BentoConfig;
 
-/// Save a config to a JSON file with pretty formatting.
+/// Save a config as pretty-printed JSON, or as TOML when the path ends in `.toml`.
+/// An existing TOML file is updated in place, keeping its comments and layout.
 pub fn save_config(config: &BentoConfig, path: &Path) -> Result<()> {
-    let content = serde_json::to_string_pretty(config)
-        .with_context(|| "failed to serialize config to JSON")?;
+    let content = if is_toml_config(path) {
+        let existing = match std::fs::read_to_string(path) {
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            read => read.with_context(|| format!("failed to read {}", path.display()))?,
+        };
+        serde_json::to_value(config)
+            .map_err(anyhow::Error::from)
+            .and_then(|value| update_toml(&existing, &value))
+            .with_context(|| "failed to serialize config to TOML")?
+    } else {
+        serde_json::to_string_pretty(config)
+            .with_context(|| "failed to serialize config to JSON")?
+    };
 
     std::fs::write(path, content)
         .with_context(|| format!("failed to write config file: {}", path.display()))?;
@@ -30,10 +44,54 @@ pub fn make_relative(path: &Path, base: &Path) -> String {
 }
 
 #[cfg(test)]
+#[allow(clippy::unwrap_used)]
 mod tests {
     use super::*;
+    use crate::config::LoadedConfig;
     use std::path::PathBuf;
 
+    #[test]
+    fn test_save_toml_keeps_comments_and_key_order() {
+        let dir = std::env::temp_dir().join("bento_save_toml_round_trip");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("bento.toml");
+        std::fs::write(
+            &path,
+            "# Sprites for the main menu\n\
+             name = \"menu\"  # base name\n\
+             version = 1\n\
+             padding = 2\n\
+             input = [\"ui/*.png\"]\n\
+             \n\
+             # Tiles need room to bleed\n\
+             [extrude_overrides]\n\
+             \"tiles/*\" = 2\n",
+        )
+        .unwrap();
+
+        let mut config = LoadedConfig::load(&path).unwrap().config;
+        config.padding = 4;
+        config.extrude_overrides.insert("ui/*".to_string(), 1);
+        save_config(&config, &path).unwrap();
+
+        let saved = std::fs::read_to_string(&path).unwrap();
+        assert!(saved.starts_with(
+            "# Sprites for the main menu\nname = \"menu\"  # base name\nversion = 1\npadding = 4\n\
+             input = [\"ui/*.png\"]\n"
+        ));
+        assert!(
+            saved.contains("\n# Tiles need room to bleed\n[extrude_overrides]\n\"tiles/*\" = 2\n")
+        );
+
+        let reloaded = LoadedConfig::load(&path).unwrap().config;
+        assert_eq!(reloaded.padding, 4);
+        assert_eq!(reloaded.extrude_overrides, config.extrude_overrides);
+
+        // Saving an unchanged config leaves the file as it is
+        save_config(&reloaded, &path).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), saved);
+    }
+
     #[test]
     fn test_make_relative_same_dir() {
         let path = PathBuf::from("/project/sprites/hero.png");
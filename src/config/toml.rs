@@ -0,0 +1,204 @@
+//! TOML config files, mapped onto the same schema as JSON configs.
+//!
+//! A TOML document is converted to a JSON value and deserialized from there,
+//! so both formats share every serde rule in [`super::types`].
+
+use anyhow::{Context, Result, bail};
+use serde_json::{Map, Number, Value};
+use toml_edit::{Document, DocumentMut, InlineTable, Item, Table, TableLike};
+
+/// Parse a TOML document into the equivalent JSON value
+pub fn toml_to_json(content: &str) -> Result<Value> {
+    let document = Document::parse(content).map_err(|e| anyhow::anyhow!("{}", e))?;
+    item_to_json(document.as_item())
+}
+
+/// Write a JSON object into a TOML document. Only keys whose value changed
+/// are rewritten, so comments, key order and formatting survive; keys that are
+/// null or gone from `value` are removed. New keys are appended, with nested
+/// objects as one `[table]` each. An empty `existing` gives a fresh document.
+pub fn update_toml(existing: &str, value: &Value) -> Result<String> {
+    let Value::Object(root) = value else {
+        bail!("a TOML document must be a table");
+    };
+    let mut document: DocumentMut = existing.parse().map_err(|e| anyhow::anyhow!("{}", e))?;
+    update_table(document.as_table_mut(), root, true)?;
+    Ok(document.to_string())
+}
+
+/// Bring a table in line with a JSON object in place. New nested objects
+/// become `[table]`s at the top level and inline tables below it.
+fn update_table(table: &mut dyn TableLike, values: &Map<String, Value>, top: bool) -> Result<()> {
+    let stale: Vec<String> = table
+        .iter()
+        .map(|(key, _)| key.to_string())
+        .filter(|key| values.get(key).is_none_or(Value::is_null))
+        .collect();
+    for key in stale {
+        table.remove(&key);
+    }
+
+    for (key, value) in values.iter().filter(|(_, v)| !v.is_null()) {
+        let Some(item) = table.get_mut(key) else {
+            // Empty objects only add noise, like an empty `[names]` table
+            if value
+                .as_object()
+                .is_none_or(|map| map.values().any(|v| !v.is_null()))
+            {
+                table.insert(key, new_item(value, top)?);
+            }
+            continue;
+        };
+        if item_to_json(item).is_ok_and(|current| current == *value) {
+            continue;
+        }
+        match (value, item.as_table_like_mut()) {
+            (Value::Object(map), Some(nested)) => update_table(nested, map, false)?,
+            _ => match item {
+                Item::Value(current) => {
+                    let decor = current.decor().clone();
+                    *current = toml_value(value)?;
+                    *current.decor_mut() = decor;
+                }
+                _ => *item = new_item(value, top)?,
+            },
+        }
+    }
+    Ok(())
+}
+
+fn new_item(value: &Value, top: bool) -> Result<Item> {
+    match value {
+        Value::Object(map) if top => {
+            let mut table = Table::new();
+            for (key, value) in map.iter().filter(|(_, v)| !v.is_null()) {
+                table.insert(key, Item::Value(toml_value(value)?));
+            }
+            Ok(Item::Table(table))
+        }
+        _ => Ok(Item::Value(toml_value(value)?)),
+    }
+}
+
+fn item_to_json(item: &Item) -> Result<Value> {
+    Ok(match item {
+        Item::None => Value::Null,
+        Item::Value(value) => value_to_json(value)?,
+        Item::Table(table) => Value::Object(
+            table
+                .iter()
+                .map(|(key, item)| Ok((key.to_string(), item_to_json(item)?)))
+                .collect::<Result<Map<_, _>>>()?,
+        ),
+        Item::ArrayOfTables(tables) => Value::Array(
+            tables
+                .iter()
+                .map(|table| item_to_json(&Item::Table(table.clone())))
+                .collect::<Result<_>>()?,
+        ),
+    })
+}
+
+fn value_to_json(value: &toml_edit::Value) -> Result<Value> {
+    use toml_edit::Value as Toml;
+    Ok(match value {
+        Toml::String(s) => Value::String(s.value().clone()),
+        Toml::Integer(n) => Value::from(*n.value()),
+        Toml::Float(f) => Number::from_f64(*f.value())
+            .map(Value::Number)
+            .with_context(|| format!("unsupported float: {}", f.value()))?,
+        Toml::Boolean(b) => Value::Bool(*b.value()),
+        Toml::Datetime(d) => Value::String(d.value().to_string()),
+        Toml::Array(array) => Value::Array(array.iter().map(value_to_json).collect::<Result<_>>()?),
+        Toml::InlineTable(table) => Value::Object(
+            table
+                .iter()
+                .map(|(key, value)| Ok((key.to_string(), value_to_json(value)?)))
+                .collect::<Result<Map<_, _>>>()?,
+        ),
+    })
+}
+
+fn toml_value(value: &Value) -> Result<toml_edit::Value> {
+    Ok(match value {
+        Value::Null => bail!("TOML has no null value"),
+        Value::Bool(b) => (*b).into(),
+        Value::Number(n) => match (n.as_i64(), n.as_f64()) {
+            (Some(n), _) => n.into(),
+            (None, Some(f)) if n.is_f64() => f.into(),
+            _ => bail!("{} is too large for a TOML integer", n),
+        },
+        Value::String(s) => s.as_str().into(),
+        Value::Array(items) => toml_edit::Value::Array(
+            items
+                .iter()
+                .map(toml_value)
+                .collect::<Result<toml_edit::Array>>()?,
+        ),
+        Value::Object(table) => toml_edit::Value::InlineTable(
+            table
+                .iter()
+                .filter(|(_, v)| !v.is_null())
+                .map(|(key, value)| Ok((key.as_str(), toml_value(value)?)))
+                .collect::<Result<InlineTable>>()?,
+        ),
+    })
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_toml_to_json() {
+        let value = toml_to_json(
+            r#"
+            # Comments are fine
+            version = 1
+            input = ["sprites/*.png"]
+            scales = [1.0, 0.5]
+            resize = { scale = 0.5 }
+
+            [names]
+            "sprites/hero v2.png" = "hero"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "version": 1,
+                "input": ["sprites/*.png"],
+                "scales": [1.0, 0.5],
+                "resize": {"scale": 0.5},
+                "names": {"sprites/hero v2.png": "hero"}
+            })
+        );
+        assert!(toml_to_json("version = ").is_err());
+    }
+
+    #[test]
+    fn test_update_empty_toml_round_trip() {
+        let value = serde_json::json!({
+            "version": 1,
+            "format": null,
+            "name": "say \"hi\"\n",
+            "scales": [1.0, 0.5],
+            "nine_slice": {"ui/panel.png": {"left": 8, "top": 8}},
+            "names": {},
+        });
+        let toml = update_toml("", &value).unwrap();
+        assert!(toml.starts_with("version = 1\n"));
+        assert!(toml.contains("scales = [1.0, 0.5]\n"));
+        assert!(toml.contains("[nine_slice]\n\"ui/panel.png\" = { left = 8, top = 8 }\n"));
+        assert!(!toml.contains("[names]"));
+
+        let mut expected = value;
+        if let Value::Object(map) = &mut expected {
+            map.remove("format");
+            map.remove("names");
+        }
+        assert_eq!(toml_to_json(&toml).unwrap(), expected);
+    }
+}
@@ -1,5 +1,10 @@
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 
+use crate::cli::{CompressionLevel, OutputFormat};
+use crate::sprite::{GridSlice, NineSlice, SpriteOverride, TrimMargin};
+
 /// Configuration for resizing sprites.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
@@ -8,6 +13,12 @@ pub enum ResizeConfig {
     Width { width: u32 },
     /// Resize by a scale factor (e.g., 0.5 for half size)
     Scale { scale: f32 },
+    /// Shrink images wider than this many pixels to it (preserves aspect
+    /// ratio); narrower images keep their size
+    MaxWidth { max_width: u32 },
+    /// Shrink images larger than a "WxH" box to fit inside it (preserves
+    /// aspect ratio); images that fit keep their size
+    Fit { fit: String },
 }
 
 /// PNG compression level configuration.
@@ -16,8 +27,61 @@ pub enum ResizeConfig {
 pub enum CompressConfig {
     /// Optimization level 0-6
     Level(u8),
-    /// Maximum compression ("max")
-    Max(String),
+    /// Named level: "max" or "zopfli[:ITERATIONS]"
+    Named(String),
+}
+
+impl CompressConfig {
+    /// Resolve to a compression level, rejecting unknown names
+    pub fn to_level(&self) -> Result<CompressionLevel, String> {
+        match self {
+            CompressConfig::Level(n) => Ok(CompressionLevel::Level(*n)),
+            CompressConfig::Named(name) => name.parse(),
+        }
+    }
+}
+
+impl From<CompressionLevel> for CompressConfig {
+    fn from(level: CompressionLevel) -> Self {
+        match level {
+            CompressionLevel::Level(n) => CompressConfig::Level(n),
+            other => CompressConfig::Named(other.to_string()),
+        }
+    }
+}
+
+/// Output format configuration: one format name or a list of them
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum FormatConfig {
+    /// A single format, e.g. "json"
+    One(String),
+    /// Several formats written from the same pack, e.g. ["json", "godot"]
+    List(Vec<String>),
+}
+
+impl FormatConfig {
+    /// The format names, in the order given
+    pub fn names(&self) -> Vec<&str> {
+        match self {
+            FormatConfig::One(name) => vec![name.as_str()],
+            FormatConfig::List(names) => names.iter().map(String::as_str).collect(),
+        }
+    }
+}
+
+impl From<&[OutputFormat]> for FormatConfig {
+    fn from(formats: &[OutputFormat]) -> Self {
+        match formats {
+            [format] => FormatConfig::One(format.config_name().to_string()),
+            formats => FormatConfig::List(
+                formats
+                    .iter()
+                    .map(|format| format.config_name().to_string())
+                    .collect(),
+            ),
+        }
+    }
 }
 
 /// Bento configuration file structure.
@@ -34,41 +98,165 @@ pub struct BentoConfig {
     pub output_dir: String,
     /// Base name for output files (atlas_0.png, atlas.json, etc.)
     pub name: String,
-    /// Output format: "json", "godot", or "tpsheet"
-    pub format: Option<String>,
+    /// Output format: "json", "godot", "tpsheet", "libgdx", "phaser", "cocos", or
+    /// "template", or a list of them to write from one pack
+    pub format: Option<FormatConfig>,
     /// Maximum atlas width in pixels
     pub max_width: u32,
     /// Maximum atlas height in pixels
     pub max_height: u32,
     /// Padding between sprites in pixels
     pub padding: u32,
+    /// Padding left and right of each sprite, overriding `padding` (optional)
+    pub padding_x: Option<u32>,
+    /// Padding above and below each sprite, overriding `padding` (optional)
+    pub padding_y: Option<u32>,
     /// Force power-of-two atlas dimensions
     pub pot: bool,
     /// Enable sprite trimming (remove transparent borders)
     pub trim: bool,
-    /// Keep N pixels of transparent border after trimming
-    pub trim_margin: u32,
+    /// Transparent border to keep after trimming: a number, or an object with
+    /// `left`, `top`, `right` and `bottom`
+    pub trim_margin: TrimMargin,
+    /// Trim margin overrides, keyed by sprite name or glob pattern
+    pub trim_margin_overrides: BTreeMap<String, TrimMargin>,
+    /// Pixels with alpha at or below this count as transparent when trimming
+    pub trim_alpha: u8,
+    /// Keep full frames in the atlas but record tight content bounds in metadata
+    pub record_trim: bool,
     /// Extrude sprite edges by N pixels (helps with texture bleeding)
     pub extrude: u32,
+    /// What fills the extruded border: "clamp" repeats edge pixels, "wrap"
+    /// copies the opposite edge for seamless tiles
+    pub extrude_mode: String,
+    /// Color of the unused atlas area, as "#RRGGBB" or "#RRGGBBAA" (optional)
+    pub background_color: Option<String>,
     /// Align sprite regions to N-pixel boundaries (0 = disabled).
     /// Use 4 for BPTC/S3TC or 8 for ASTC 8x8. Prevents block-based VRAM compression
     /// from shifting sprite edges, which causes visible misalignment when overlaying
     /// sprites from different atlases (e.g. icon + outline).
     pub block_align: u32,
+    /// Allow sprites to be stored rotated 90° clockwise
+    pub allow_rotation: bool,
+    /// Search for the smallest page that fits instead of cropping the full bin
+    pub size_search: bool,
+    /// Force square atlas pages
+    pub square: bool,
+    /// Output every page at exactly max_width x max_height
+    pub fixed_size: bool,
+    /// Round atlas width and height up to a multiple of N (0 = disabled)
+    pub size_multiple: u32,
+    /// Maximum number of sprites per atlas page (optional)
+    pub max_sprites_per_page: Option<usize>,
+    /// Lay sprites out in uniform grid cells: "auto" or "WxH" (optional)
+    pub grid: Option<String>,
+    /// Maximum summed area of all atlas pages in pixels (optional)
+    pub max_total_pixels: Option<u64>,
+    /// Maximum summed size of all atlas pages as uncompressed RGBA in bytes (optional)
+    pub max_total_bytes: Option<u64>,
+    /// Maximum number of atlas pages (optional)
+    pub max_atlases: Option<usize>,
     /// Resize configuration (optional)
     pub resize: Option<ResizeConfig>,
     /// Resize filter algorithm (nearest, triangle, catmull-rom, gaussian, lanczos3)
     pub resize_filter: String,
+    /// Resize in linear light instead of on sRGB values
+    pub resize_linear: bool,
+    /// Scale factors for images whose file name ends in a suffix before the
+    /// extension, e.g. `{"@2x": 0.5}`; the suffix is dropped from the sprite name
+    pub scale_suffixes: BTreeMap<String, f32>,
+    /// File name suffixes of companion images, e.g. `["_n", "_e"]`, packed into
+    /// their own atlases with the layout of the base images
+    pub companions: Vec<String>,
     /// Packing heuristic to use
     pub heuristic: String,
     /// Pack mode: "single" or "best"
     pub pack_mode: String,
+    /// Extra random sprite orderings to try in "best" pack mode
+    pub trials: u32,
+    /// Seed for the random orderings
+    pub seed: u64,
     /// PNG compression configuration (optional)
     pub compress: Option<CompressConfig>,
     /// Output RGB instead of RGBA (opaque atlas)
     pub opaque: bool,
+    /// Color `opaque` output blends semi-transparent pixels over, as "#RRGGBB" (optional)
+    pub matte_color: Option<String>,
+    /// Write color and alpha to separate PNGs
+    pub split_alpha: bool,
+    /// Scales to write the atlas at from a single layout, e.g. [1.0, 0.5]
+    /// (empty = full size only)
+    pub scales: Vec<f32>,
+    /// Number of mip levels to generate below the atlas (0 = none)
+    pub mipmaps: u32,
+    /// Mip level output: "png" or "ktx2"
+    pub mipmap_format: String,
+    /// Also encode atlas pages as Basis Universal KTX2: "uastc" or "etc1s" (optional)
+    pub basis: Option<String>,
     /// Use only the filename (no directory prefix) in sprite names
     pub filename_only: bool,
+    /// Pack each visible layer of Aseprite inputs as its own sprite
+    pub split_layers: bool,
+    /// Log and skip images that can't be read instead of failing the run
+    pub skip_errors: bool,
+    /// Byte-identical output across runs and platforms
+    pub deterministic: bool,
+    /// Pack each top-level sprite folder into its own atlas set
+    pub group_by_folder: bool,
+    /// Sort before packing: "area", "max-side", "height", "width",
+    /// "perimeter", "name", or "none"
+    pub sort: String,
+    /// Metadata sprite order: "packed", "name", or "input"
+    pub sprite_order: String,
+    /// Prefix for atlas image paths in metadata (optional)
+    pub image_path_prefix: Option<String>,
+    /// Atlas image file name template, e.g. "{name}_{index}.png" (optional)
+    pub output_template: Option<String>,
+    /// Write JSON, tpsheet and Phaser metadata minified instead of pretty-printed
+    pub json_compact: bool,
+    /// Record sprite source paths in JSON metadata: "absolute" or "relative" (optional)
+    pub source_paths: Option<String>,
+    /// Add SHA-256 digests of the layout and atlas images to JSON metadata
+    pub content_hash: bool,
+    /// Add a triangulated outline of each sprite to JSON metadata
+    pub polygon: bool,
+    /// Pixels a polygon outline may stray from the sprite's pixels (optional, default 2)
+    pub polygon_tolerance: Option<f32>,
+    /// Record the bento version, config hash and command line in atlas PNG text chunks
+    pub png_metadata: bool,
+    /// Handlebars template for the "template" format, relative to the config file (optional)
+    pub template: Option<String>,
+    /// Packing report to write, relative to the config file (optional)
+    pub report: Option<String>,
+    /// Previous sheet metadata to keep sprite positions from, relative to the config file (optional)
+    pub stable_layout: Option<String>,
+    /// Frame layout of single-page Phaser sheets: "hash" or "array"
+    pub phaser_frames: String,
+    /// Godot version to write .tres resources for: 3 or 4
+    pub godot_version: u32,
+    /// Godot directory holding the atlas, e.g. "res://textures/atlases" (optional)
+    pub godot_res_path: Option<String>,
+    /// Sprite sheets to cut into grid cells, keyed by sprite name or glob pattern
+    pub grid_slice: BTreeMap<String, GridSlice>,
+    /// Image files or glob patterns to skip when packing
+    pub exclude: Vec<String>,
+    /// Sprite name overrides, keyed by image file path
+    pub names: BTreeMap<String, String>,
+    /// Sprite names or glob patterns packed first, in this order
+    pub pack_order: Vec<String>,
+    /// Sprite names or glob patterns whose matches share one atlas page,
+    /// one group per entry
+    pub keep_together: Vec<String>,
+    /// Extrude overrides, keyed by sprite name or glob pattern
+    pub extrude_overrides: BTreeMap<String, u32>,
+    /// Nine-slice borders, keyed by sprite name or glob pattern
+    pub nine_slice: BTreeMap<String, NineSlice>,
+    /// Trim, padding, extrude, extrude mode, pivot and scale for matching
+    /// sprites, keyed by sprite name or glob pattern
+    pub overrides: BTreeMap<String, SpriteOverride>,
+    /// Named profiles for `bento build`, each a set of config keys laid over
+    /// the top-level settings
+    pub profiles: BTreeMap<String, serde_json::Map<String, serde_json::Value>>,
 }
 
 impl Default for BentoConfig {
@@ -82,18 +270,75 @@ impl Default for BentoConfig {
             max_width: 4096,
             max_height: 4096,
             padding: 1,
+            padding_x: None,
+            padding_y: None,
             pot: false,
             trim: true,
-            trim_margin: 0,
+            trim_margin: TrimMargin::default(),
+            trim_margin_overrides: BTreeMap::new(),
+            trim_alpha: 0,
+            record_trim: false,
             extrude: 0,
+            extrude_mode: "clamp".to_string(),
+            background_color: None,
             block_align: 0,
+            allow_rotation: false,
+            size_search: false,
+            square: false,
+            fixed_size: false,
+            size_multiple: 0,
+            max_sprites_per_page: None,
+            grid: None,
+            max_total_pixels: None,
+            max_total_bytes: None,
+            max_atlases: None,
             resize: None,
             resize_filter: "lanczos3".to_string(),
+            resize_linear: false,
+            scale_suffixes: BTreeMap::new(),
+            companions: Vec::new(),
             heuristic: "best-short-side-fit".to_string(),
             pack_mode: "single".to_string(),
+            trials: 0,
+            seed: 0,
             compress: None,
             opaque: false,
+            matte_color: None,
+            split_alpha: false,
+            scales: Vec::new(),
+            mipmaps: 0,
+            mipmap_format: "png".to_string(),
+            basis: None,
             filename_only: false,
+            split_layers: false,
+            skip_errors: false,
+            deterministic: false,
+            group_by_folder: false,
+            sort: "area".to_string(),
+            sprite_order: "packed".to_string(),
+            image_path_prefix: None,
+            output_template: None,
+            json_compact: false,
+            source_paths: None,
+            content_hash: false,
+            polygon: false,
+            polygon_tolerance: None,
+            png_metadata: false,
+            template: None,
+            report: None,
+            stable_layout: None,
+            phaser_frames: "hash".to_string(),
+            godot_version: 4,
+            godot_res_path: None,
+            grid_slice: BTreeMap::new(),
+            exclude: Vec::new(),
+            names: BTreeMap::new(),
+            pack_order: Vec::new(),
+            keep_together: Vec::new(),
+            extrude_overrides: BTreeMap::new(),
+            nine_slice: BTreeMap::new(),
+            overrides: BTreeMap::new(),
+            profiles: BTreeMap::new(),
         }
     }
 }
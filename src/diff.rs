@@ -0,0 +1,332 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use serde::Serialize;
+
+use crate::atlas::AtlasLayout;
+use crate::sprite::PackedSprite;
+
+/// Changes between two packed sheets, as printed by `bento diff`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SheetDiff {
+    /// Sprites only in the new sheet
+    pub added: Vec<PlacedSprite>,
+    /// Sprites only in the old sheet
+    pub removed: Vec<PlacedSprite>,
+    /// Sprites of the same size at another position, page or rotation
+    pub moved: Vec<SpriteChange>,
+    /// Sprites whose size changed, wherever they ended up
+    pub resized: Vec<SpriteChange>,
+    /// Sprites in the same place in both sheets
+    pub unchanged: usize,
+    /// Pages added, removed or resized
+    pub pages: Vec<PageChange>,
+}
+
+/// Where a sprite sits in a sheet
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Placement {
+    pub page: usize,
+    pub x: u32,
+    pub y: u32,
+    /// Width before rotation
+    pub w: u32,
+    /// Height before rotation
+    pub h: u32,
+    pub rotated: bool,
+}
+
+/// A sprite found in only one of the sheets
+#[derive(Debug, Clone, Serialize)]
+pub struct PlacedSprite {
+    pub name: String,
+    #[serde(flatten)]
+    pub placement: Placement,
+}
+
+/// A sprite found in both sheets, placed differently
+#[derive(Debug, Clone, Serialize)]
+pub struct SpriteChange {
+    pub name: String,
+    pub from: Placement,
+    pub to: Placement,
+}
+
+/// A page whose size differs; `from` is None for added pages and `to` for
+/// removed ones
+#[derive(Debug, Clone, Serialize)]
+pub struct PageChange {
+    pub index: usize,
+    pub from: Option<PageSize>,
+    pub to: Option<PageSize>,
+}
+
+/// Page dimensions in pixels
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct PageSize {
+    pub w: u32,
+    pub h: u32,
+}
+
+impl Placement {
+    fn of(page: usize, sprite: &PackedSprite) -> Self {
+        let (w, h) = if sprite.rotated {
+            (sprite.height, sprite.width)
+        } else {
+            (sprite.width, sprite.height)
+        };
+        Self {
+            page,
+            x: sprite.x,
+            y: sprite.y,
+            w,
+            h,
+            rotated: sprite.rotated,
+        }
+    }
+
+    fn position(&self) -> String {
+        let mut text = format!("page {} at {},{}", self.page, self.x, self.y);
+        if self.rotated {
+            text.push_str(" rotated");
+        }
+        text
+    }
+}
+
+impl SheetDiff {
+    /// Compare the pages of an old and a new sheet, matching sprites by name
+    pub fn new(old: &[AtlasLayout], new: &[AtlasLayout]) -> Self {
+        let placements = |pages: &[AtlasLayout]| -> BTreeMap<String, Placement> {
+            pages
+                .iter()
+                .enumerate()
+                .flat_map(|(page, layout)| {
+                    layout
+                        .sprites
+                        .iter()
+                        .map(move |sprite| (sprite.name.clone(), Placement::of(page, sprite)))
+                })
+                .collect()
+        };
+        let old_sprites = placements(old);
+        let mut new_sprites = placements(new);
+
+        let mut diff = Self {
+            added: Vec::new(),
+            removed: Vec::new(),
+            moved: Vec::new(),
+            resized: Vec::new(),
+            unchanged: 0,
+            pages: Vec::new(),
+        };
+        for (name, from) in old_sprites {
+            let Some(to) = new_sprites.remove(&name) else {
+                diff.removed.push(PlacedSprite {
+                    name,
+                    placement: from,
+                });
+                continue;
+            };
+            if (from.w, from.h) != (to.w, to.h) {
+                diff.resized.push(SpriteChange { name, from, to });
+            } else if from != to {
+                diff.moved.push(SpriteChange { name, from, to });
+            } else {
+                diff.unchanged += 1;
+            }
+        }
+        diff.added = new_sprites
+            .into_iter()
+            .map(|(name, placement)| PlacedSprite { name, placement })
+            .collect();
+
+        let size = |pages: &[AtlasLayout], index: usize| {
+            pages.get(index).map(|page| PageSize {
+                w: page.width,
+                h: page.height,
+            })
+        };
+        for index in 0..old.len().max(new.len()) {
+            let (from, to) = (size(old, index), size(new, index));
+            if from != to {
+                diff.pages.push(PageChange { index, from, to });
+            }
+        }
+        diff
+    }
+
+    /// Whether the sheets place every sprite the same way on same-sized pages
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.moved.is_empty()
+            && self.resized.is_empty()
+            && self.pages.is_empty()
+    }
+
+    /// Human-readable report, one line per change
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        for sprite in &self.added {
+            let _ = writeln!(
+                text,
+                "+ {} {}x{} {}",
+                sprite.name,
+                sprite.placement.w,
+                sprite.placement.h,
+                sprite.placement.position()
+            );
+        }
+        for sprite in &self.removed {
+            let _ = writeln!(
+                text,
+                "- {} {}x{} {}",
+                sprite.name,
+                sprite.placement.w,
+                sprite.placement.h,
+                sprite.placement.position()
+            );
+        }
+        for change in &self.resized {
+            let _ = writeln!(
+                text,
+                "~ {} resized {}x{} -> {}x{}, {} -> {}",
+                change.name,
+                change.from.w,
+                change.from.h,
+                change.to.w,
+                change.to.h,
+                change.from.position(),
+                change.to.position()
+            );
+        }
+        for change in &self.moved {
+            let _ = writeln!(
+                text,
+                "~ {} moved {} -> {}",
+                change.name,
+                change.from.position(),
+                change.to.position()
+            );
+        }
+        for page in &self.pages {
+            let size = |size: Option<PageSize>| {
+                size.map_or_else(|| "none".to_string(), |s| format!("{}x{}", s.w, s.h))
+            };
+            let _ = writeln!(
+                text,
+                "Page {}: {} -> {}",
+                page.index,
+                size(page.from),
+                size(page.to)
+            );
+        }
+
+        if self.is_empty() {
+            let _ = writeln!(text, "No changes ({} sprites)", self.unchanged);
+        } else {
+            let _ = writeln!(
+                text,
+                "Total: {} added, {} removed, {} resized, {} moved, {} unchanged",
+                self.added.len(),
+                self.removed.len(),
+                self.resized.len(),
+                self.moved.len(),
+                self.unchanged
+            );
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::sprite::TrimInfo;
+
+    fn sprite(name: &str, x: u32, width: u32, height: u32) -> PackedSprite {
+        PackedSprite {
+            name: name.to_string(),
+            path: std::path::PathBuf::from(name),
+            x,
+            y: 0,
+            width,
+            height,
+            trim_info: TrimInfo::untrimmed(width, height),
+            atlas_index: 0,
+            input_index: 0,
+            content_bounds: None,
+            nine_slice: None,
+            pivot: None,
+            rotated: false,
+        }
+    }
+
+    fn page(index: usize, width: u32, sprites: Vec<PackedSprite>) -> AtlasLayout {
+        AtlasLayout {
+            index,
+            width,
+            height: 16,
+            sprites,
+            occupancy: 0.0,
+            packing: None,
+        }
+    }
+
+    #[test]
+    fn test_sheet_diff() {
+        let old = vec![page(
+            0,
+            64,
+            vec![
+                sprite("a.png", 0, 16, 16),
+                sprite("b.png", 16, 16, 16),
+                sprite("c.png", 32, 16, 16),
+                sprite("d.png", 48, 16, 16),
+            ],
+        )];
+        let mut rotated = sprite("d.png", 0, 16, 16);
+        rotated.rotated = true;
+        let new = vec![
+            page(
+                0,
+                48,
+                vec![
+                    sprite("a.png", 0, 16, 16),
+                    sprite("b.png", 16, 8, 16),
+                    sprite("e.png", 32, 16, 16),
+                ],
+            ),
+            page(1, 16, vec![rotated]),
+        ];
+
+        let diff = SheetDiff::new(&old, &new);
+        assert_eq!(diff.unchanged, 1);
+        assert_eq!(diff.added[0].name, "e.png");
+        assert_eq!(diff.removed[0].name, "c.png");
+        assert_eq!(diff.resized[0].name, "b.png");
+        assert_eq!(diff.moved[0].name, "d.png");
+        assert_eq!(diff.moved[0].to.page, 1);
+        assert_eq!(diff.pages.len(), 2);
+        assert_eq!(diff.pages[1].from, None);
+
+        let text = diff.to_text();
+        assert!(text.contains("+ e.png 16x16 page 0 at 32,0\n"));
+        assert!(text.contains("~ b.png resized 16x16 -> 8x16, page 0 at 16,0 -> page 0 at 16,0\n"));
+        assert!(text.contains("~ d.png moved page 0 at 48,0 -> page 1 at 0,0 rotated\n"));
+        assert!(text.contains("Page 0: 64x16 -> 48x16\nPage 1: none -> 16x16\n"));
+        assert!(text.ends_with("Total: 1 added, 1 removed, 1 resized, 1 moved, 1 unchanged\n"));
+
+        let json = serde_json::to_value(&diff).unwrap();
+        assert_eq!(json["added"][0]["x"], 32);
+        assert_eq!(json["moved"][0]["to"]["rotated"], true);
+        assert!(json["pages"][1]["from"].is_null());
+
+        let same = SheetDiff::new(&old, &old);
+        assert!(same.is_empty());
+        assert_eq!(same.to_text(), "No changes (4 sprites)\n");
+    }
+}
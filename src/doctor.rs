@@ -0,0 +1,537 @@
+use std::path::{Path, PathBuf};
+
+use clap::ValueEnum;
+
+use crate::atlas::AtlasBuilder;
+use crate::cli::{
+    BasisMode, Color, ExtrudeMode, GodotVersion, GridCell, MipmapFormat, OutputFormat, PackMode,
+    PackingHeuristic, PhaserFrames, ResizeFilter, ResizeFit, SourcePaths, SpriteOrder, SpriteSort,
+};
+use crate::config::{
+    BentoConfig, CompressConfig, FormatConfig, LoadedConfig, ResizeConfig, is_glob_pattern,
+};
+use crate::output::OutputTemplate;
+use crate::sprite::{SourceSprite, duplicate_names};
+
+/// How serious a finding is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// Packing will work, but the result is probably not what was intended
+    Warning,
+    /// Packing will fail
+    Error,
+}
+
+/// A problem found by `bento doctor`, with a suggested fix
+#[derive(Debug, Clone)]
+pub struct Finding {
+    pub severity: Severity,
+    pub problem: String,
+    pub fix: String,
+}
+
+impl Finding {
+    pub fn error(problem: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Error,
+            problem: problem.into(),
+            fix: fix.into(),
+        }
+    }
+
+    pub fn warning(problem: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Warning,
+            problem: problem.into(),
+            fix: fix.into(),
+        }
+    }
+}
+
+/// Check that every config input pattern resolves to something
+pub fn check_config_inputs(loaded: &LoadedConfig) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    for pattern in &loaded.config.input {
+        let path = loaded.config_dir.join(pattern);
+        if is_glob_pattern(pattern) {
+            let matches = glob::glob(&path.to_string_lossy())
+                .map(|paths| paths.flatten().count())
+                .unwrap_or(0);
+            if matches == 0 {
+                findings.push(Finding::error(
+                    format!("Input pattern '{}' matches no files", pattern),
+                    format!(
+                        "Patterns are relative to the config file directory ({}); check the \
+                         pattern against the files there",
+                        loaded.config_dir.display()
+                    ),
+                ));
+            }
+        } else if !path.exists() {
+            findings.push(missing_input(&path));
+        }
+    }
+    findings
+}
+
+/// Check config values that would be rejected when packing: unknown names,
+/// out-of-range numbers and malformed patterns. Every problem is reported,
+/// not just the first.
+pub fn check_config_values(config: &BentoConfig) -> Vec<Finding> {
+    let mut findings = Vec::new();
+
+    for format in config.format.iter().flat_map(FormatConfig::names) {
+        check_choice::<OutputFormat>(&mut findings, "format", format);
+    }
+    check_choice::<PackingHeuristic>(&mut findings, "heuristic", &config.heuristic);
+    check_choice::<PackMode>(&mut findings, "pack_mode", &config.pack_mode);
+    check_choice::<SpriteSort>(&mut findings, "sort", &config.sort);
+    check_choice::<SpriteOrder>(&mut findings, "sprite_order", &config.sprite_order);
+    check_choice::<ResizeFilter>(&mut findings, "resize_filter", &config.resize_filter);
+    check_choice::<ExtrudeMode>(&mut findings, "extrude_mode", &config.extrude_mode);
+    check_choice::<MipmapFormat>(&mut findings, "mipmap_format", &config.mipmap_format);
+    check_choice::<PhaserFrames>(&mut findings, "phaser_frames", &config.phaser_frames);
+    check_choice::<GodotVersion>(
+        &mut findings,
+        "godot_version",
+        &config.godot_version.to_string(),
+    );
+    if let Some(basis) = &config.basis {
+        check_choice::<BasisMode>(&mut findings, "basis", basis);
+    }
+    if let Some(source_paths) = &config.source_paths {
+        check_choice::<SourcePaths>(&mut findings, "source_paths", source_paths);
+    }
+
+    for (key, value) in [
+        ("max_width", config.max_width),
+        ("max_height", config.max_height),
+    ] {
+        if value == 0 {
+            findings.push(Finding::error(
+                format!("{} is 0", key),
+                format!("Set {} to the largest page size wanted, e.g. 4096", key),
+            ));
+        }
+    }
+    for (key, value) in [
+        ("max_sprites_per_page", config.max_sprites_per_page),
+        ("max_atlases", config.max_atlases),
+    ] {
+        if value == Some(0) {
+            findings.push(Finding::error(
+                format!("{} is 0", key),
+                format!("Set {} to at least 1, or remove it for no limit", key),
+            ));
+        }
+    }
+    if let Some(grid) = &config.grid
+        && let Err(e) = grid.parse::<GridCell>()
+    {
+        findings.push(Finding::error(
+            format!("Invalid grid in config: {}", e),
+            "Use \"auto\", a cell size like \"32x48\", or \"N\" for square cells",
+        ));
+    }
+    if let Some(template) = &config.output_template
+        && let Err(e) = template.parse::<OutputTemplate>()
+    {
+        findings.push(Finding::error(
+            format!("Invalid output_template in config: {}", e),
+            "Use {name} and {index} (or {index:N} to zero-pad), e.g. \"{name}_{index}.png\"",
+        ));
+    }
+    if let Some(background) = &config.background_color
+        && let Err(e) = background.parse::<Color>()
+    {
+        findings.push(Finding::error(
+            format!("Invalid background_color in config: {}", e),
+            "Use a color such as \"#ff00ff\" or \"#00000080\"",
+        ));
+    }
+    if let Some(matte) = &config.matte_color {
+        match matte.parse::<Color>() {
+            Ok(color) if color.a == 255 => {}
+            Ok(_) => findings.push(Finding::error(
+                "matte_color has alpha",
+                "Use an opaque color such as \"#808080\"",
+            )),
+            Err(e) => findings.push(Finding::error(
+                format!("Invalid matte_color in config: {}", e),
+                "Use a color such as \"#808080\"",
+            )),
+        }
+    }
+    if let Some(compress) = &config.compress {
+        let level = match compress {
+            CompressConfig::Level(n) if *n > 6 => Err(format!(
+                "compression level must be 0-6, 'max', or 'zopfli', got {}",
+                n
+            )),
+            _ => compress.to_level(),
+        };
+        if let Err(e) = level {
+            findings.push(Finding::error(
+                format!("Invalid compress in config: {}", e),
+                "Use a level from 0 to 6, \"max\", or \"zopfli[:ITERATIONS]\"",
+            ));
+        }
+    }
+    match config.resize {
+        Some(ResizeConfig::Width { width: 0 }) => findings.push(Finding::error(
+            "resize width is 0",
+            "Set a positive width, or remove \"resize\"",
+        )),
+        Some(ResizeConfig::MaxWidth { max_width: 0 }) => findings.push(Finding::error(
+            "resize max_width is 0",
+            "Set a positive max_width, or remove \"resize\"",
+        )),
+        Some(ResizeConfig::Fit { ref fit }) => {
+            if let Err(e) = fit.parse::<ResizeFit>() {
+                findings.push(Finding::error(
+                    format!("Invalid resize fit in config: {}", e),
+                    "Use a box such as \"256x256\", or remove \"resize\"",
+                ));
+            }
+        }
+        Some(ResizeConfig::Scale { scale }) if !scale.is_finite() || scale <= 0.0 => {
+            findings.push(Finding::error(
+                format!("Invalid resize scale {}", scale),
+                "Set a positive scale, or remove \"resize\"",
+            ));
+        }
+        _ => {}
+    }
+    for (suffix, factor) in &config.scale_suffixes {
+        if suffix.is_empty() || !factor.is_finite() || *factor <= 0.0 {
+            findings.push(Finding::error(
+                format!("Invalid scale suffix '{}': {}", suffix, factor),
+                "Map each suffix to a positive scale, e.g. {\"@2x\": 0.5}",
+            ));
+        }
+    }
+    if config.companions.iter().any(String::is_empty) {
+        findings.push(Finding::error(
+            "Empty companion suffix",
+            "Give each companion a suffix, e.g. [\"_n\"]",
+        ));
+    }
+    for (i, scale) in config.scales.iter().enumerate() {
+        if !scale.is_finite() || *scale <= 0.0 {
+            findings.push(Finding::error(
+                format!("Invalid scale {}", scale),
+                "Scales must be positive numbers, e.g. [1.0, 0.5]",
+            ));
+        } else if config.scales[..i].contains(scale) {
+            findings.push(Finding::error(
+                format!("Scale {} is listed more than once", scale),
+                "Remove the duplicate from \"scales\"",
+            ));
+        }
+    }
+    if config.basis.is_some() && config.mipmaps > 0 && config.mipmap_format == "ktx2" {
+        findings.push(Finding::error(
+            "basis and mipmap_format ktx2 both write a .ktx2 per page",
+            "Use PNG mip levels with basis",
+        ));
+    }
+    if let Some(tolerance) = config.polygon_tolerance
+        && (!tolerance.is_finite() || tolerance < 0.0)
+    {
+        findings.push(Finding::error(
+            format!("Invalid polygon_tolerance {}", tolerance),
+            "Set a tolerance of zero or more pixels, e.g. 2",
+        ));
+    }
+
+    let patterns = [
+        (
+            "exclude",
+            config
+                .exclude
+                .iter()
+                .filter(|entry| is_glob_pattern(entry))
+                .collect::<Vec<_>>(),
+        ),
+        ("pack_order", config.pack_order.iter().collect()),
+        ("keep_together", config.keep_together.iter().collect()),
+        ("overrides", config.overrides.keys().collect()),
+        (
+            "extrude_overrides",
+            config.extrude_overrides.keys().collect(),
+        ),
+    ];
+    for (section, entries) in patterns {
+        for entry in entries {
+            if let Err(e) = glob::Pattern::new(entry) {
+                findings.push(Finding::error(
+                    format!("Invalid {} pattern '{}': {}", section, entry, e),
+                    "Fix the glob syntax; '[' opens a character class that must be closed",
+                ));
+            }
+        }
+    }
+    findings
+}
+
+/// Report `value` unless it names a variant of `T`, as the CLI option would accept it
+fn check_choice<T: ValueEnum>(findings: &mut Vec<Finding>, key: &str, value: &str) {
+    if T::from_str(value, false).is_err() {
+        let names: Vec<String> = T::value_variants()
+            .iter()
+            .filter_map(|v| v.to_possible_value())
+            .map(|v| v.get_name().to_string())
+            .collect();
+        findings.push(unknown_value(key, value, names.join(", ")));
+    }
+}
+
+fn unknown_value(key: &str, value: &str, valid: String) -> Finding {
+    Finding::error(
+        format!("Unknown {} '{}' in config", key, value),
+        format!("Valid values: {}", valid),
+    )
+}
+
+/// Check that input paths given on the command line exist
+pub fn check_input_paths(inputs: &[PathBuf]) -> Vec<Finding> {
+    inputs
+        .iter()
+        .filter(|path| !path.exists())
+        .map(|path| missing_input(path))
+        .collect()
+}
+
+fn missing_input(path: &Path) -> Finding {
+    Finding::error(
+        format!("Input path does not exist: {}", path.display()),
+        "Fix the path, or remove it from the inputs",
+    )
+}
+
+/// Check that the output directory exists (or can be created) and is writable
+pub fn check_output_dir(dir: &Path) -> Vec<Finding> {
+    // A missing directory is created on export, so probe its nearest existing ancestor
+    // A bare relative path like `out` ends its ancestors at "", the current directory
+    let Some(existing) = dir
+        .ancestors()
+        .map(|p| {
+            if p.as_os_str().is_empty() {
+                Path::new(".")
+            } else {
+                p
+            }
+        })
+        .find(|p| p.exists())
+    else {
+        return vec![Finding::error(
+            format!("Output directory can't be created: {}", dir.display()),
+            "Choose a directory on an existing drive with --output",
+        )];
+    };
+    if !existing.is_dir() {
+        return vec![Finding::error(
+            format!("Output path is not a directory: {}", existing.display()),
+            "Choose a directory with --output (or output_dir in the config)",
+        )];
+    }
+    let probe = existing.join(".bento-doctor-probe");
+    match std::fs::write(&probe, b"") {
+        Ok(()) => {
+            let _ = std::fs::remove_file(&probe);
+            Vec::new()
+        }
+        Err(e) => vec![Finding::error(
+            format!(
+                "Output directory is not writable: {} ({})",
+                existing.display(),
+                e
+            ),
+            "Fix the directory permissions, or choose another directory with --output",
+        )],
+    }
+}
+
+/// Check loaded sprites for duplicate names, sprites that can't fit on a page,
+/// and images that are suspiciously large for a sprite
+pub fn check_sprites(sprites: &[SourceSprite], builder: &AtlasBuilder) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    if sprites.is_empty() {
+        findings.push(Finding::error(
+            "No images found in the inputs",
+            "Add PNG, JPEG, GIF, BMP or WebP files (or folders containing them)",
+        ));
+        return findings;
+    }
+
+    for name in duplicate_names(sprites) {
+        findings.push(Finding::error(
+            format!("Several images would become sprite '{}'", name),
+            "Rename one with \"names\" in the config, or drop --filename-only so \
+             sprites keep their folder prefix",
+        ));
+    }
+
+    for sprite in sprites {
+        if let Err(e) = builder.check_fits(sprite) {
+            findings.push(Finding::error(
+                e.to_string(),
+                "Raise --max-width/--max-height, shrink it with --resize-scale, or lower \
+                 its padding and extrusion",
+            ));
+        }
+    }
+
+    for warning in builder.warnings(sprites) {
+        findings.push(Finding::warning(
+            warning.to_string(),
+            "Check it is meant to be in the atlas (backgrounds usually aren't), or \
+             downscale it with --resize-scale",
+        ));
+    }
+    findings
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::config::BentoConfig;
+    use crate::sprite::TrimInfo;
+
+    fn sprite(name: &str, width: u32, height: u32) -> SourceSprite {
+        SourceSprite {
+            path: PathBuf::from(name),
+            name: name.to_string(),
+            image: image::RgbaImage::new(width, height),
+            trim_info: TrimInfo::untrimmed(width, height),
+            input_index: 0,
+            content_bounds: None,
+            nine_slice: None,
+            pivot: None,
+            companions: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_check_sprites() {
+        let builder = AtlasBuilder::new(256, 256).padding(1);
+        let sprites = [
+            sprite("a.png", 16, 16),
+            sprite("a.png", 8, 8),
+            sprite("huge.png", 300, 10),
+            sprite("big.png", 200, 200),
+        ];
+        let findings = check_sprites(&sprites, &builder);
+        let problems: Vec<(Severity, &str)> = findings
+            .iter()
+            .map(|f| (f.severity, f.problem.as_str()))
+            .collect();
+
+        assert_eq!(problems.len(), 3);
+        assert_eq!(
+            problems[0],
+            (
+                Severity::Error,
+                "Several images would become sprite 'a.png'"
+            )
+        );
+        assert_eq!(problems[1].0, Severity::Error);
+        assert!(problems[1].1.contains("huge.png"));
+        assert_eq!(problems[2].0, Severity::Warning);
+        assert!(problems[2].1.contains("big.png"));
+
+        assert_eq!(check_sprites(&[], &builder)[0].severity, Severity::Error);
+    }
+
+    #[test]
+    fn test_check_config_inputs() {
+        let loaded = LoadedConfig {
+            config: BentoConfig {
+                input: vec![
+                    "src".to_string(),
+                    "src/*.nothing".to_string(),
+                    "missing.png".to_string(),
+                ],
+                ..BentoConfig::default()
+            },
+            config_dir: PathBuf::from(env!("CARGO_MANIFEST_DIR")),
+        };
+        let findings = check_config_inputs(&loaded);
+
+        assert_eq!(findings.len(), 2);
+        assert!(findings[0].problem.contains("src/*.nothing"));
+        assert!(findings[1].problem.contains("missing.png"));
+    }
+
+    #[test]
+    fn test_check_config_values() {
+        assert!(check_config_values(&BentoConfig::default()).is_empty());
+
+        let config = BentoConfig {
+            format: Some(FormatConfig::List(vec![
+                "json".to_string(),
+                "xml".to_string(),
+            ])),
+            heuristic: "fastest".to_string(),
+            resize_filter: "bicubic".to_string(),
+            extrude_mode: "mirror".to_string(),
+            godot_version: 5,
+            max_width: 0,
+            max_sprites_per_page: Some(0),
+            grid: Some("0x8".to_string()),
+            compress: Some(CompressConfig::Level(9)),
+            matte_color: Some("grey".to_string()),
+            background_color: Some("#12345".to_string()),
+            scales: vec![1.0, -0.5, 1.0],
+            scale_suffixes: BTreeMap::from([("@2x".to_string(), 0.0)]),
+            companions: vec![String::new()],
+            polygon_tolerance: Some(-1.0),
+            exclude: vec!["raw/[".to_string()],
+            ..BentoConfig::default()
+        };
+        let problems: Vec<String> = check_config_values(&config)
+            .into_iter()
+            .map(|f| f.problem)
+            .collect();
+
+        assert_eq!(problems.len(), 16);
+        assert_eq!(problems[0], "Unknown format 'xml' in config");
+        assert_eq!(problems[1], "Unknown heuristic 'fastest' in config");
+        assert_eq!(problems[2], "Unknown extrude_mode 'mirror' in config");
+        assert_eq!(problems[3], "Unknown godot_version '5' in config");
+        assert_eq!(problems[4], "max_width is 0");
+        assert_eq!(problems[5], "max_sprites_per_page is 0");
+        assert!(problems[6].contains("grid"));
+        assert!(problems[7].contains("background_color"));
+        assert!(problems[8].contains("matte_color"));
+        assert!(problems[9].contains("compress"));
+        assert_eq!(problems[10], "Invalid scale suffix '@2x': 0");
+        assert_eq!(problems[11], "Empty companion suffix");
+        assert_eq!(problems[12], "Invalid scale -0.5");
+        assert_eq!(problems[13], "Scale 1 is listed more than once");
+        assert_eq!(problems[14], "Invalid polygon_tolerance -1");
+        assert!(problems[15].contains("raw/["));
+    }
+
+    #[test]
+    fn test_check_output_dir() {
+        let file = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("Cargo.toml");
+        assert_eq!(check_output_dir(&file)[0].severity, Severity::Error);
+        assert_eq!(
+            check_output_dir(&file.join("out"))[0].severity,
+            Severity::Error
+        );
+
+        let missing = std::env::temp_dir().join("bento_doctor_missing/nested");
+        assert!(check_output_dir(&missing).is_empty());
+
+        // Relative paths are probed from the current directory
+        for relative in ["bento_doctor_new_dir", "bento_doctor_new_dir/nested"] {
+            let relative = Path::new(relative);
+            assert!(!relative.exists());
+            assert!(check_output_dir(relative).is_empty());
+        }
+    }
+}
@@ -1,17 +1,25 @@
 use std::path::PathBuf;
+
+use serde::Serialize;
 use thiserror::Error;
 
-#[derive(Error, Debug)]
+/// Exit code of failures that are not a `BentoError`, such as config errors
+pub const EXIT_FAILURE: i32 = 1;
+
+#[derive(Error, Debug, Serialize)]
+#[serde(tag = "kind", content = "details", rename_all = "snake_case")]
 pub enum BentoError {
     #[error("Failed to load image '{path}': {source}")]
     ImageLoad {
         path: PathBuf,
+        #[serde(skip)]
         source: image::ImageError,
     },
 
     #[error("Failed to save image '{path}': {source}")]
     ImageSave {
         path: PathBuf,
+        #[serde(skip)]
         source: image::ImageError,
     },
 
@@ -29,21 +37,190 @@ pub enum BentoError {
         max_height: u32,
     },
 
+    #[error(
+        "Sprite '{name}' ({width}x{height}) does not fit in a {cell_width}x{cell_height} grid cell"
+    )]
+    GridCellTooSmall {
+        name: String,
+        width: u32,
+        height: u32,
+        cell_width: u32,
+        cell_height: u32,
+    },
+
+    #[error(
+        "Grid cells of {width}x{height} (with padding) do not fit in the {max_width}x{max_height} page"
+    )]
+    GridCellTooLarge {
+        width: u32,
+        height: u32,
+        max_width: u32,
+        max_height: u32,
+    },
+
     #[error("Failed to write output file '{path}': {source}")]
     OutputWrite {
         path: PathBuf,
+        #[serde(skip)]
         source: std::io::Error,
     },
 
     #[error("Failed to compress PNG '{path}': {message}")]
     PngCompress { path: PathBuf, message: String },
 
+    #[error("Failed to read Aseprite file '{path}': {message}")]
+    Aseprite { path: PathBuf, message: String },
+
+    #[error("Failed to slice sprite sheet '{path}': {message}")]
+    GridSlice { path: PathBuf, message: String },
+
+    #[error("Failed to render template '{path}': {message}")]
+    Template { path: PathBuf, message: String },
+
+    #[error("Texture encoder failed: {message}")]
+    TextureEncode { message: String },
+
     #[error("Input path does not exist: {0}")]
     InputNotFound(PathBuf),
 
     #[error("Duplicate sprite names found: {names}")]
     DuplicateNames { names: String },
 
+    #[error("Atlas pages use {used} {unit}, exceeding the budget of {budget} {unit}")]
+    BudgetExceeded {
+        used: u64,
+        budget: u64,
+        unit: &'static str,
+    },
+
+    #[error(
+        "Sprite '{name}' is packed rotated, which {format} output can't express; pack without --allow-rotation"
+    )]
+    RotationUnsupported { name: String, format: &'static str },
+
+    #[error(
+        "Companion image '{companion}' is {width}x{height}, but its base image '{path}' is {expected_width}x{expected_height}"
+    )]
+    CompanionSize {
+        path: PathBuf,
+        companion: PathBuf,
+        width: u32,
+        height: u32,
+        expected_width: u32,
+        expected_height: u32,
+    },
+
+    #[error("Sprites need more atlas pages than the limit of {limit}; {count} left over: {names}")]
+    TooManyAtlases {
+        limit: usize,
+        count: usize,
+        names: String,
+    },
+
+    #[error("Atlas page {index} has room for none of the {count} remaining sprites: {names}")]
+    NothingPlaced {
+        index: usize,
+        count: usize,
+        names: String,
+    },
+
     #[error("Operation cancelled")]
     Cancelled,
 }
+
+impl BentoError {
+    /// Process exit code for this error, distinct per variant so build
+    /// systems can tell failures apart without parsing messages
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            BentoError::ImageLoad { .. } => 10,
+            BentoError::ImageSave { .. } => 11,
+            BentoError::NoImages => 12,
+            BentoError::SpriteTooLarge { .. } => 13,
+            BentoError::GridCellTooSmall { .. } => 14,
+            BentoError::GridCellTooLarge { .. } => 15,
+            BentoError::OutputWrite { .. } => 16,
+            BentoError::PngCompress { .. } => 17,
+            BentoError::Aseprite { .. } => 18,
+            BentoError::GridSlice { .. } => 19,
+            BentoError::Template { .. } => 20,
+            BentoError::TextureEncode { .. } => 21,
+            BentoError::InputNotFound(_) => 22,
+            BentoError::DuplicateNames { .. } => 23,
+            BentoError::BudgetExceeded { .. } => 24,
+            BentoError::RotationUnsupported { .. } => 25,
+            BentoError::Cancelled => 26,
+            BentoError::CompanionSize { .. } => 27,
+            BentoError::TooManyAtlases { .. } => 28,
+            BentoError::NothingPlaced { .. } => 29,
+        }
+    }
+}
+
+/// Exit code for a failure: that of the first `BentoError` in its chain,
+/// or [`EXIT_FAILURE`]
+pub fn exit_code(error: &anyhow::Error) -> i32 {
+    bento_error(error).map_or(EXIT_FAILURE, BentoError::exit_code)
+}
+
+/// A failure as a JSON object for `--error-format json`: `kind`, `code`,
+/// the full `message` and, for `BentoError`s, their `details`
+pub fn error_json(error: &anyhow::Error) -> serde_json::Value {
+    let mut json = bento_error(error)
+        .and_then(|e| serde_json::to_value(e).ok())
+        .and_then(|value| match value {
+            // A unit variant serializes as its bare kind name
+            serde_json::Value::String(kind) => Some(serde_json::json!({ "kind": kind })),
+            serde_json::Value::Object(map) => Some(serde_json::Value::Object(map)),
+            _ => None,
+        })
+        .unwrap_or_else(|| serde_json::json!({ "kind": "error" }));
+    json["code"] = exit_code(error).into();
+    json["message"] = format!("{:#}", error).into();
+    json
+}
+
+fn bento_error(error: &anyhow::Error) -> Option<&BentoError> {
+    error.chain().find_map(|e| e.downcast_ref::<BentoError>())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_json() {
+        let error = anyhow::Error::from(BentoError::SpriteTooLarge {
+            name: "boss.png".to_string(),
+            width: 5000,
+            height: 64,
+            max_width: 4096,
+            max_height: 4096,
+        })
+        .context("packing failed");
+        let json = error_json(&error);
+        assert_eq!(json["kind"], "sprite_too_large");
+        assert_eq!(json["code"], 13);
+        assert_eq!(json["details"]["name"], "boss.png");
+        assert_eq!(json["details"]["width"], 5000);
+        assert!(
+            json["message"]
+                .as_str()
+                .unwrap()
+                .starts_with("packing failed: Sprite 'boss.png'")
+        );
+
+        let json = error_json(&BentoError::Cancelled.into());
+        assert_eq!(json["kind"], "cancelled");
+        assert_eq!(json["code"], 26);
+
+        let json = error_json(&BentoError::InputNotFound(PathBuf::from("a.png")).into());
+        assert_eq!(json["details"], "a.png");
+        assert_eq!(json["code"], 22);
+
+        let json = error_json(&anyhow::anyhow!("bad config"));
+        assert_eq!(json["kind"], "error");
+        assert_eq!(json["code"], EXIT_FAILURE);
+    }
+}
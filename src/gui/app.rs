@@ -1,41 +1,67 @@
 use eframe::egui;
-use std::path::PathBuf;
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::mpsc;
+use std::sync::{Arc, Mutex, mpsc};
 use std::time::{Duration, Instant};
 
 use super::dialogs::{
-    ConfigChooserDialog, PendingAction, UnsavedChangesChoice, UnsavedChangesDialog,
-    find_bento_files,
+    ConfigChooserDialog, ExportConflictChoice, ExportConflictDialog, PendingAction,
+    UnsavedChangesChoice, UnsavedChangesDialog, find_bento_files,
 };
 use super::state::{
-    AppConfig, AppState, BackgroundTask, FileDialogKind, FileDialogResult, Operation, OutputFormat,
-    PackResult, ResizeMode, Status, StatusResult, ThumbnailState,
+    AppConfig, AppState, BackgroundTask, CompressionEstimate, FileDialogKind, FileDialogResult,
+    Operation, PackProgress, PackResult, ResizeMode, SizeEstimate, Status, StatusResult,
+    ThumbnailState, UiPrefs, formats_from_config,
 };
-use super::thumbnail::spawn_thumbnail_loader;
+use super::system::show_notification;
+use super::thumbnail::{ThumbnailCache, spawn_thumbnail_loader};
 use super::{is_supported_image, panels};
-use crate::atlas::{Atlas, AtlasBuilder};
-use crate::cli::{CompressionLevel, PackMode, PackingHeuristic, ResizeFilter};
-use crate::config::{BentoConfig, LoadedConfig, save_config};
+use crate::atlas::{Atlas, AtlasBuilder, SpriteSettings, mip_level_count};
+use crate::cli::{
+    BasisMode, CompressionLevel, ExtrudeMode, GodotVersion, MipmapFormat, OutputFormat, PackMode,
+    PackingHeuristic, PhaserFrames, ResizeFilter, SourcePaths, SpriteOrder, SpriteSort,
+};
+use crate::config::{
+    BentoConfig, LoadedConfig, compile_exclude, compile_keep_together, compile_overrides,
+    compile_pack_order, resolve_sprite_settings, save_config,
+};
 use crate::output::{
-    atlas_png_filename, save_atlas_image, write_godot_resources, write_json, write_tpsheet,
+    BasisuEncoder, add_png_text, cocos_plist_filename, generator_text, oxipng_options,
+    save_alpha_mask, save_atlas_image, save_encoded, save_image, scaled_base_name, sha256_hex,
+    stylebox_filename, template_output_filename, write_cocos, write_godot_resources, write_json,
+    write_ktx2, write_libgdx, write_phaser, write_template, write_tpsheet,
 };
-use crate::sprite::load_sprites;
+use crate::progress::{ProgressCallback, ProgressEvent};
+use crate::sprite::{
+    Animation, LoadOptions, SourceSprite, SpriteMesh, SpriteStats, apply_nine_slices,
+    apply_sprite_overrides, load_animations, load_sprites_with_warnings,
+};
+use crate::warning::Warning;
 
 /// Debounce delay for auto-repack (milliseconds)
 const AUTO_REPACK_DEBOUNCE_MS: u64 = 300;
 
+/// Packs and exports taking at least this long notify the desktop when they
+/// finish while the window is unfocused
+const NOTIFY_AFTER: Duration = Duration::from_secs(10);
+
 /// Main GUI application
 pub struct BentoApp {
     state: AppState,
     config_chooser: Option<ConfigChooserDialog>,
     unsaved_changes_dialog: Option<UnsavedChangesDialog>,
+    export_conflict_dialog: Option<ExportConflictDialog>,
     /// Set to true when user confirms they want to close (after save/discard dialog)
     allowed_to_close: bool,
 }
 
+/// Application id used by eframe for persisted storage
+pub const APP_ID: &str = "Bento";
+
 const LAST_INPUT_DIR_KEY: &str = "last_input_dir";
+const UI_PREFS_KEY: &str = "ui_prefs";
+const RECENT_PROJECTS_KEY: &str = "recent_projects";
 
 impl BentoApp {
     pub fn new(cc: &eframe::CreationContext<'_>, initial_path: Option<PathBuf>) -> Self {
@@ -43,12 +69,20 @@ impl BentoApp {
             state: AppState::default(),
             config_chooser: None,
             unsaved_changes_dialog: None,
+            export_conflict_dialog: None,
             allowed_to_close: false,
         };
 
+        app.state.runtime.thumbnail_cache = ThumbnailCache::in_storage_dir(APP_ID);
+
         // Restore persisted state
         if let Some(storage) = cc.storage {
             app.state.runtime.last_input_dir = eframe::get_value(storage, LAST_INPUT_DIR_KEY);
+            if let Some(prefs) = eframe::get_value::<UiPrefs>(storage, UI_PREFS_KEY) {
+                app.state.runtime.apply_ui_prefs(prefs);
+            }
+            app.state.runtime.recent_projects =
+                eframe::get_value(storage, RECENT_PROJECTS_KEY).unwrap_or_default();
         }
 
         // Handle initial path
@@ -60,8 +94,12 @@ impl BentoApp {
     }
 
     fn handle_initial_path(&mut self, path: PathBuf) {
-        if path.is_file() && path.extension().is_some_and(|e| e == "bento") {
-            // Direct .bento file - load it
+        if path.is_file()
+            && path
+                .extension()
+                .is_some_and(|e| e == "bento" || e == "toml")
+        {
+            // Direct .bento or bento.toml file - load it
             self.load_config_file(&path);
         } else if path.is_dir() {
             // Directory - look for .bento files
@@ -112,27 +150,81 @@ impl BentoApp {
             }
         }
 
+        self.state.config.excluded = loaded.resolve_excluded().into_iter().collect();
+        self.state.config.exclude_patterns = loaded.exclude_patterns();
+        self.state.config.sprite_names = loaded.resolve_names().into_iter().collect();
+
         // Apply settings
         self.state.config.output_dir = loaded.resolve_output_dir();
         self.state.config.name = cfg.name.clone();
-        self.state.config.format = match cfg.format.as_deref() {
-            Some("godot") => OutputFormat::Godot,
-            Some("tpsheet") => OutputFormat::Tpsheet,
-            _ => OutputFormat::Json,
+        self.state.config.formats = match formats_from_config(cfg.format.as_ref()) {
+            Ok(formats) => formats,
+            Err(e) => {
+                self.state.runtime.status = Status::Done {
+                    result: StatusResult::Error(e),
+                    at: std::time::Instant::now(),
+                };
+                return;
+            }
         };
         self.state.config.max_width = cfg.max_width;
         self.state.config.max_height = cfg.max_height;
-        self.state.config.padding = cfg.padding;
+        self.state.config.padding_x = cfg.padding_x.unwrap_or(cfg.padding);
+        self.state.config.padding_y = cfg.padding_y.unwrap_or(cfg.padding);
         self.state.config.pot = cfg.pot;
         self.state.config.trim = cfg.trim;
         self.state.config.trim_margin = cfg.trim_margin;
+        self.state.config.trim_margin_overrides = cfg.trim_margin_overrides.clone();
+        self.state.config.trim_alpha = cfg.trim_alpha;
+        self.state.config.record_trim = cfg.record_trim;
+        self.state.config.split_layers = cfg.split_layers;
+        self.state.config.skip_errors = cfg.skip_errors;
+        self.state.config.deterministic = cfg.deterministic;
         self.state.config.extrude = cfg.extrude;
+        self.state.config.extrude_mode = match cfg.extrude_mode.as_str() {
+            "wrap" => ExtrudeMode::Wrap,
+            _ => ExtrudeMode::Clamp,
+        };
+        self.state.config.background_color =
+            cfg.background_color.as_deref().and_then(|c| c.parse().ok());
         self.state.config.block_align = cfg.block_align;
+        self.state.config.allow_rotation = cfg.allow_rotation;
+        self.state.config.size_search = cfg.size_search;
+        self.state.config.square = cfg.square;
+        self.state.config.fixed_size = cfg.fixed_size;
+        self.state.config.size_multiple = cfg.size_multiple;
+        self.state.config.max_sprites_per_page = cfg.max_sprites_per_page;
+        self.state.config.trials = cfg.trials;
+        self.state.config.seed = cfg.seed;
+        self.state.config.pack_order = cfg.pack_order.clone();
+        self.state.config.keep_together = cfg.keep_together.clone();
+        self.state.config.extrude_overrides = cfg.extrude_overrides.clone();
+        self.state.config.nine_slice = cfg.nine_slice.clone();
+        self.state.config.grid_slice = cfg.grid_slice.clone();
+        self.state.config.scale_suffixes = cfg.scale_suffixes.clone();
+        self.state.config.companions = cfg.companions.clone();
+        self.state.config.overrides = cfg.overrides.clone();
+        self.state.config.group_by_folder = cfg.group_by_folder;
+        self.state.config.report = cfg.report.clone();
+        self.state.config.stable_layout = cfg.stable_layout.clone();
+        self.state.config.profiles = cfg.profiles.clone();
+        self.state.config.max_total_pixels = cfg.max_total_pixels;
+        self.state.config.max_total_bytes = cfg.max_total_bytes;
+        self.state.config.max_atlases = cfg.max_atlases;
 
         // Resize mode
         self.state.config.resize_mode = match &cfg.resize {
             Some(crate::config::ResizeConfig::Width { width }) => ResizeMode::Width(*width),
             Some(crate::config::ResizeConfig::Scale { scale }) => ResizeMode::Scale(*scale),
+            Some(crate::config::ResizeConfig::MaxWidth { max_width }) => {
+                ResizeMode::MaxWidth(*max_width)
+            }
+            Some(crate::config::ResizeConfig::Fit { fit }) => fit
+                .parse::<crate::cli::ResizeFit>()
+                .map_or(ResizeMode::None, |fit| ResizeMode::Fit {
+                    width: fit.width,
+                    height: fit.height,
+                }),
             None => ResizeMode::None,
         };
 
@@ -144,6 +236,7 @@ impl BentoApp {
             "gaussian" => ResizeFilter::Gaussian,
             _ => ResizeFilter::Lanczos3,
         };
+        self.state.config.resize_linear = cfg.resize_linear;
 
         // Heuristic
         self.state.config.heuristic = match cfg.heuristic.as_str() {
@@ -170,10 +263,115 @@ impl BentoApp {
         self.state.config.pack_mode = match cfg.pack_mode.as_str() {
             "single" => PackMode::Single,
             "best" => PackMode::Best,
+            "min-pages" => PackMode::MinPages,
+            unknown => {
+                self.state.runtime.status = Status::Done {
+                    result: StatusResult::Error(format!(
+                        "Unknown pack_mode '{}' in config. Valid: single, best, min-pages",
+                        unknown
+                    )),
+                    at: std::time::Instant::now(),
+                };
+                return;
+            }
+        };
+
+        // Sort
+        self.state.config.sort = match cfg.sort.as_str() {
+            "area" => SpriteSort::Area,
+            "max-side" => SpriteSort::MaxSide,
+            "height" => SpriteSort::Height,
+            "width" => SpriteSort::Width,
+            "perimeter" => SpriteSort::Perimeter,
+            "name" => SpriteSort::Name,
+            "none" => SpriteSort::None,
+            unknown => {
+                self.state.runtime.status = Status::Done {
+                    result: StatusResult::Error(format!(
+                        "Unknown sort '{}' in config. Valid: area, max-side, height, width, \
+                         perimeter, name, none",
+                        unknown
+                    )),
+                    at: std::time::Instant::now(),
+                };
+                return;
+            }
+        };
+
+        // Mipmap format
+        self.state.config.mipmap_format = match cfg.mipmap_format.as_str() {
+            "png" => MipmapFormat::Png,
+            "ktx2" => MipmapFormat::Ktx2,
+            unknown => {
+                self.state.runtime.status = Status::Done {
+                    result: StatusResult::Error(format!(
+                        "Unknown mipmap_format '{}' in config. Valid: png, ktx2",
+                        unknown
+                    )),
+                    at: std::time::Instant::now(),
+                };
+                return;
+            }
+        };
+
+        // Godot resource syntax
+        self.state.config.godot_version = match cfg.godot_version {
+            3 => GodotVersion::V3,
+            4 => GodotVersion::V4,
+            unknown => {
+                self.state.runtime.status = Status::Done {
+                    result: StatusResult::Error(format!(
+                        "Unknown godot_version {} in config. Valid: 3, 4",
+                        unknown
+                    )),
+                    at: std::time::Instant::now(),
+                };
+                return;
+            }
+        };
+
+        // Phaser frame layout
+        self.state.config.phaser_frames = match cfg.phaser_frames.as_str() {
+            "hash" => PhaserFrames::Hash,
+            "array" => PhaserFrames::Array,
+            unknown => {
+                self.state.runtime.status = Status::Done {
+                    result: StatusResult::Error(format!(
+                        "Unknown phaser_frames '{}' in config. Valid: hash, array",
+                        unknown
+                    )),
+                    at: std::time::Instant::now(),
+                };
+                return;
+            }
+        };
+
+        // Basis Universal KTX2
+        self.state.config.basis = match cfg.basis.as_deref() {
+            None => None,
+            Some("uastc") => Some(BasisMode::Uastc),
+            Some("etc1s") => Some(BasisMode::Etc1s),
+            Some(unknown) => {
+                self.state.runtime.status = Status::Done {
+                    result: StatusResult::Error(format!(
+                        "Unknown basis '{}' in config. Valid: uastc, etc1s",
+                        unknown
+                    )),
+                    at: std::time::Instant::now(),
+                };
+                return;
+            }
+        };
+
+        // Sprite order
+        self.state.config.sprite_order = match cfg.sprite_order.as_str() {
+            "packed" => SpriteOrder::Packed,
+            "name" => SpriteOrder::Name,
+            "input" => SpriteOrder::Input,
             unknown => {
                 self.state.runtime.status = Status::Done {
                     result: StatusResult::Error(format!(
-                        "Unknown pack_mode '{}' in config. Valid: single, best",
+                        "Unknown sprite_order '{}' in config. Valid: packed, name, input",
                         unknown
                     )),
                     at: std::time::Instant::now(),
@@ -183,14 +381,68 @@ impl BentoApp {
         };
 
         // Compress
-        self.state.config.compress = cfg.compress.as_ref().map(|c| match c {
-            crate::config::CompressConfig::Level(n) => CompressionLevel::Level(*n),
-            crate::config::CompressConfig::Max(_) => CompressionLevel::Max,
-        });
+        self.state.config.compress = match cfg.compress.as_ref().map(|c| c.to_level()).transpose() {
+            Ok(compress) => compress,
+            Err(e) => {
+                self.state.runtime.status = Status::Done {
+                    result: StatusResult::Error(format!("Invalid compress in config: {}", e)),
+                    at: std::time::Instant::now(),
+                };
+                return;
+            }
+        };
+
+        // Grid
+        self.state.config.grid = match cfg.grid.as_deref().map(str::parse).transpose() {
+            Ok(grid) => grid,
+            Err(e) => {
+                self.state.runtime.status = Status::Done {
+                    result: StatusResult::Error(format!("Invalid grid in config: {}", e)),
+                    at: std::time::Instant::now(),
+                };
+                return;
+            }
+        };
 
         self.state.config.opaque = cfg.opaque;
+        self.state.config.matte_color = cfg.matte_color.as_deref().and_then(|c| c.parse().ok());
+        self.state.config.split_alpha = cfg.split_alpha;
+        self.state.config.mipmaps = cfg.mipmaps;
+        self.state.config.scales = cfg
+            .scales
+            .iter()
+            .map(f32::to_string)
+            .collect::<Vec<_>>()
+            .join(", ");
+        self.state.config.image_path_prefix = cfg.image_path_prefix.clone().unwrap_or_default();
+        self.state.config.output_template = cfg.output_template.clone().unwrap_or_default();
+        self.state.config.godot_res_path = cfg.godot_res_path.clone().unwrap_or_default();
+        self.state.config.template = cfg.template.clone().unwrap_or_default();
+        self.state.config.json_compact = cfg.json_compact;
+        self.state.config.content_hash = cfg.content_hash;
+        self.state.config.polygon = cfg.polygon;
+        self.state.config.polygon_tolerance = cfg
+            .polygon_tolerance
+            .unwrap_or(SpriteMesh::DEFAULT_TOLERANCE);
+        self.state.config.png_metadata = cfg.png_metadata;
+        self.state.config.source_paths = match cfg.source_paths.as_deref() {
+            None => None,
+            Some("absolute") => Some(SourcePaths::Absolute),
+            Some("relative") => Some(SourcePaths::Relative),
+            Some(unknown) => {
+                self.state.runtime.status = Status::Done {
+                    result: StatusResult::Error(format!(
+                        "Unknown source_paths '{}' in config. Valid: absolute, relative",
+                        unknown
+                    )),
+                    at: std::time::Instant::now(),
+                };
+                return;
+            }
+        };
 
         // Set config path and save hash
+        self.state.runtime.remember_project(&config_path);
         self.state.runtime.config_path = Some(config_path);
         self.state.runtime.last_saved_config_hash = Some(self.state.config.full_config_hash());
 
@@ -200,13 +452,14 @@ impl BentoApp {
     }
 
     fn save_current_config(&mut self) -> Result<(), String> {
-        let Some(path) = &self.state.runtime.config_path else {
+        let Some(path) = self.state.runtime.config_path.clone() else {
             return Err("No config file path set".to_string());
         };
 
-        let bento_config = self.config_to_bento_config(path);
-        save_config(&bento_config, path).map_err(|e| e.to_string())?;
+        let bento_config = self.config_to_bento_config(&path);
+        save_config(&bento_config, &path).map_err(|e| e.to_string())?;
 
+        self.state.runtime.remember_project(&path);
         self.state.runtime.last_saved_config_hash = Some(self.state.config.full_config_hash());
         Ok(())
     }
@@ -225,25 +478,66 @@ impl BentoApp {
                 .iter()
                 .map(|p| crate::config::make_relative(p, config_dir))
                 .collect(),
+            exclude: self
+                .state
+                .config
+                .excluded
+                .iter()
+                .map(|p| crate::config::make_relative(p, config_dir))
+                .chain(self.state.config.exclude_patterns.iter().cloned())
+                .collect(),
+            names: self
+                .state
+                .config
+                .sprite_names
+                .iter()
+                .map(|(p, name)| (crate::config::make_relative(p, config_dir), name.clone()))
+                .collect(),
             output_dir: crate::config::make_relative(&self.state.config.output_dir, config_dir),
             name: self.state.config.name.clone(),
-            format: Some(match self.state.config.format {
-                OutputFormat::Json => "json".to_string(),
-                OutputFormat::Godot => "godot".to_string(),
-                OutputFormat::Tpsheet => "tpsheet".to_string(),
-            }),
+            format: Some(self.state.config.format_config()),
             max_width: self.state.config.max_width,
             max_height: self.state.config.max_height,
-            padding: self.state.config.padding,
+            padding: self.state.config.padding_x,
+            // Only written when the axes differ
+            padding_x: (self.state.config.padding_x != self.state.config.padding_y)
+                .then_some(self.state.config.padding_x),
+            padding_y: (self.state.config.padding_x != self.state.config.padding_y)
+                .then_some(self.state.config.padding_y),
             pot: self.state.config.pot,
             trim: self.state.config.trim,
             trim_margin: self.state.config.trim_margin,
+            trim_margin_overrides: self.state.config.trim_margin_overrides.clone(),
+            trim_alpha: self.state.config.trim_alpha,
+            record_trim: self.state.config.record_trim,
+            split_layers: self.state.config.split_layers,
+            skip_errors: self.state.config.skip_errors,
+            deterministic: self.state.config.deterministic,
             extrude: self.state.config.extrude,
+            extrude_mode: match self.state.config.extrude_mode {
+                ExtrudeMode::Clamp => "clamp".to_string(),
+                ExtrudeMode::Wrap => "wrap".to_string(),
+            },
+            background_color: self.state.config.background_color.map(|c| c.to_string()),
             block_align: self.state.config.block_align,
+            allow_rotation: self.state.config.allow_rotation,
+            size_search: self.state.config.size_search,
+            square: self.state.config.square,
+            fixed_size: self.state.config.fixed_size,
+            size_multiple: self.state.config.size_multiple,
+            max_sprites_per_page: self.state.config.max_sprites_per_page,
+            grid: self.state.config.grid.map(|grid| grid.to_string()),
+            max_total_pixels: self.state.config.max_total_pixels,
+            max_total_bytes: self.state.config.max_total_bytes,
+            max_atlases: self.state.config.max_atlases,
             resize: match self.state.config.resize_mode {
                 ResizeMode::None => None,
                 ResizeMode::Width(w) => Some(CfgResize::Width { width: w }),
                 ResizeMode::Scale(s) => Some(CfgResize::Scale { scale: s }),
+                ResizeMode::MaxWidth(w) => Some(CfgResize::MaxWidth { max_width: w }),
+                ResizeMode::Fit { width, height } => Some(CfgResize::Fit {
+                    fit: crate::cli::ResizeFit { width, height }.to_string(),
+                }),
             },
             resize_filter: match self.state.config.resize_filter {
                 ResizeFilter::Nearest => "nearest".to_string(),
@@ -252,6 +546,7 @@ impl BentoApp {
                 ResizeFilter::Gaussian => "gaussian".to_string(),
                 ResizeFilter::Lanczos3 => "lanczos3".to_string(),
             },
+            resize_linear: self.state.config.resize_linear,
             heuristic: match self.state.config.heuristic {
                 PackingHeuristic::BestShortSideFit => "best-short-side-fit".to_string(),
                 PackingHeuristic::BestLongSideFit => "best-long-side-fit".to_string(),
@@ -263,13 +558,83 @@ impl BentoApp {
             pack_mode: match self.state.config.pack_mode {
                 PackMode::Single => "single".to_string(),
                 PackMode::Best => "best".to_string(),
+                PackMode::MinPages => "min-pages".to_string(),
             },
-            compress: self.state.config.compress.map(|c| match c {
-                CompressionLevel::Level(n) => CompressConfig::Level(n),
-                CompressionLevel::Max => CompressConfig::Max("max".to_string()),
-            }),
+            sort: match self.state.config.sort {
+                SpriteSort::Area => "area".to_string(),
+                SpriteSort::MaxSide => "max-side".to_string(),
+                SpriteSort::Height => "height".to_string(),
+                SpriteSort::Width => "width".to_string(),
+                SpriteSort::Perimeter => "perimeter".to_string(),
+                SpriteSort::Name => "name".to_string(),
+                SpriteSort::None => "none".to_string(),
+            },
+            trials: self.state.config.trials,
+            seed: self.state.config.seed,
+            pack_order: self.state.config.pack_order.clone(),
+            keep_together: self.state.config.keep_together.clone(),
+            extrude_overrides: self.state.config.extrude_overrides.clone(),
+            nine_slice: self.state.config.nine_slice.clone(),
+            grid_slice: self.state.config.grid_slice.clone(),
+            scale_suffixes: self.state.config.scale_suffixes.clone(),
+            companions: self.state.config.companions.clone(),
+            overrides: self.state.config.overrides.clone(),
+            group_by_folder: self.state.config.group_by_folder,
+            report: self.state.config.report.clone(),
+            stable_layout: self.state.config.stable_layout.clone(),
+            profiles: self.state.config.profiles.clone(),
+            compress: self.state.config.compress.map(CompressConfig::from),
             opaque: self.state.config.opaque,
+            matte_color: self.state.config.matte_color.map(|c| c.to_string()),
+            split_alpha: self.state.config.split_alpha,
+            mipmaps: self.state.config.mipmaps,
+            scales: parse_scales(&self.state.config.scales).unwrap_or_default(),
+            mipmap_format: match self.state.config.mipmap_format {
+                MipmapFormat::Png => "png".to_string(),
+                MipmapFormat::Ktx2 => "ktx2".to_string(),
+            },
+            basis: self.state.config.basis.map(|mode| {
+                match mode {
+                    BasisMode::Uastc => "uastc",
+                    BasisMode::Etc1s => "etc1s",
+                }
+                .to_string()
+            }),
             filename_only: false,
+            image_path_prefix: Some(self.state.config.image_path_prefix.clone())
+                .filter(|prefix| !prefix.is_empty()),
+            output_template: Some(self.state.config.output_template.trim().to_string())
+                .filter(|template| !template.is_empty()),
+            source_paths: self.state.config.source_paths.map(|style| {
+                match style {
+                    SourcePaths::Absolute => "absolute",
+                    SourcePaths::Relative => "relative",
+                }
+                .to_string()
+            }),
+            json_compact: self.state.config.json_compact,
+            content_hash: self.state.config.content_hash,
+            polygon: self.state.config.polygon,
+            polygon_tolerance: (self.state.config.polygon_tolerance
+                != SpriteMesh::DEFAULT_TOLERANCE)
+                .then_some(self.state.config.polygon_tolerance),
+            png_metadata: self.state.config.png_metadata,
+            godot_version: match self.state.config.godot_version {
+                GodotVersion::V3 => 3,
+                GodotVersion::V4 => 4,
+            },
+            godot_res_path: Some(self.state.config.godot_res_path.clone())
+                .filter(|path| !path.is_empty()),
+            template: Some(self.state.config.template.clone()).filter(|path| !path.is_empty()),
+            phaser_frames: match self.state.config.phaser_frames {
+                PhaserFrames::Hash => "hash".to_string(),
+                PhaserFrames::Array => "array".to_string(),
+            },
+            sprite_order: match self.state.config.sprite_order {
+                SpriteOrder::Packed => "packed".to_string(),
+                SpriteOrder::Name => "name".to_string(),
+                SpriteOrder::Input => "input".to_string(),
+            },
         }
     }
 
@@ -278,7 +643,12 @@ impl BentoApp {
         self.state.runtime.config_path = None;
         self.state.runtime.last_saved_config_hash = None;
         self.state.runtime.atlases = None;
+        self.state.runtime.input_stats = None;
+        self.state.runtime.last_input_stats_hash = None;
+        self.state.runtime.packed_stats = None;
+        self.state.runtime.warnings.clear();
         self.state.runtime.atlas_textures.clear();
+        self.state.runtime.channel_texture = None;
         self.state.runtime.thumbnails.clear();
         self.state.runtime.last_packed_hash = None;
     }
@@ -358,12 +728,29 @@ impl BentoApp {
         {
             // Task completed, clear it
             self.state.runtime.pack_task = None;
+            let elapsed = self.operation_elapsed();
 
             match result {
                 Ok(pack_result) => {
                     let count = pack_result.atlases.len();
+                    self.notify_if_unfocused(
+                        ctx,
+                        elapsed,
+                        "Pack finished",
+                        &format!(
+                            "{} atlas{}, {} sprites",
+                            count,
+                            if count == 1 { "" } else { "es" },
+                            pack_result
+                                .atlases
+                                .iter()
+                                .map(|a| a.sprites.len())
+                                .sum::<usize>()
+                        ),
+                    );
 
                     // Create textures from atlases
+                    self.state.runtime.channel_texture = None;
                     self.state.runtime.atlas_textures = pack_result
                         .atlases
                         .iter()
@@ -382,7 +769,9 @@ impl BentoApp {
                         .collect();
 
                     // Use pre-computed PNG sizes from background thread
-                    self.state.runtime.atlas_png_sizes = pack_result.png_sizes;
+                    self.state.runtime.atlas_png_sizes = pack_result.sizes.png_sizes;
+                    self.state.runtime.compression_estimate = pack_result.sizes.compression;
+                    self.state.runtime.sprite_settings = pack_result.sprite_settings;
 
                     // Store hashes for auto-repack detection
                     self.state.runtime.last_packed_hash =
@@ -390,14 +779,23 @@ impl BentoApp {
                     self.state.runtime.last_export_hash =
                         Some(self.state.config.export_settings_hash());
 
+                    self.state.runtime.packed_stats = Some(pack_result.stats);
                     self.state.runtime.atlases = Some(pack_result.atlases);
+                    self.state.runtime.animations = pack_result.animations;
                     self.state.runtime.selected_atlas = 0;
                     self.state.runtime.needs_fit_to_view = true;
+                    let warnings = match pack_result.warnings.len() {
+                        0 => String::new(),
+                        1 => ", 1 warning".to_string(),
+                        n => format!(", {} warnings", n),
+                    };
+                    self.state.runtime.warnings = pack_result.warnings;
                     self.state.runtime.status = Status::Done {
                         result: StatusResult::Success(format!(
-                            "{} atlas{} packed",
+                            "{} atlas{} packed{}",
                             count,
-                            if count == 1 { "" } else { "es" }
+                            if count == 1 { "" } else { "es" },
+                            warnings
                         )),
                         at: Instant::now(),
                     };
@@ -407,6 +805,7 @@ impl BentoApp {
                     self.state.runtime.status = Status::Idle;
                 }
                 Err(err) => {
+                    self.notify_if_unfocused(ctx, elapsed, "Pack failed", &err);
                     // Update hash to prevent auto-repack retry with same failing config
                     self.state.runtime.last_packed_hash =
                         Some(self.state.config.pack_settings_hash());
@@ -429,9 +828,18 @@ impl BentoApp {
         let cancel_token = Arc::new(AtomicBool::new(false));
         let token_clone = cancel_token.clone();
 
+        // Fresh progress for the preview's progress bar
+        let progress = Arc::new(Mutex::new(PackProgress::default()));
+        self.state.runtime.pack_progress = progress.clone();
+        let callback = ProgressCallback::new(move |event| {
+            if let Ok(mut progress) = progress.lock() {
+                progress.update(event);
+            }
+        });
+
         // Spawn worker thread
         std::thread::spawn(move || {
-            let result = pack_atlases(&config, token_clone);
+            let result = pack_atlases(&config, token_clone, callback);
             let _ = tx.send(result);
         });
 
@@ -442,6 +850,8 @@ impl BentoApp {
             started_at: Instant::now(),
         };
         self.state.runtime.atlases = None; // Clear old atlases
+        self.state.runtime.packed_stats = None;
+        self.state.runtime.warnings.clear();
     }
 
     /// Cancel the current packing operation
@@ -452,15 +862,35 @@ impl BentoApp {
     }
 
     /// Poll background export task for completion
-    fn poll_export_task(&mut self) {
+    fn poll_export_task(&mut self, ctx: &egui::Context) {
         if let Some(task) = &self.state.runtime.export_task
             && let Some(result) = task.poll()
         {
             // Task completed, clear it
             self.state.runtime.export_task = None;
+            let elapsed = self.operation_elapsed();
 
             match result {
                 Ok(()) => {
+                    let count = self
+                        .state
+                        .runtime
+                        .atlases
+                        .as_ref()
+                        .map_or(0, |atlases| atlases.len());
+                    let bytes: usize = self.state.runtime.atlas_png_sizes.iter().sum();
+                    self.notify_if_unfocused(
+                        ctx,
+                        elapsed,
+                        "Export finished",
+                        &format!(
+                            "{} atlas{}, {}",
+                            count,
+                            if count == 1 { "" } else { "es" },
+                            panels::format_file_size(bytes)
+                        ),
+                    );
+
                     let path = self.state.config.output_dir.display();
                     self.state.runtime.status = Status::Done {
                         result: StatusResult::Success(format!("Exported to {}", path)),
@@ -476,6 +906,7 @@ impl BentoApp {
                     }
                 }
                 Err(err) => {
+                    self.notify_if_unfocused(ctx, elapsed, "Export failed", &err);
                     self.state.runtime.status = Status::Done {
                         result: StatusResult::Error(err),
                         at: Instant::now(),
@@ -485,6 +916,32 @@ impl BentoApp {
         }
     }
 
+    /// Time since the running pack or export started
+    fn operation_elapsed(&self) -> Option<Duration> {
+        match self.state.runtime.status {
+            Status::Working { started_at, .. } => Some(started_at.elapsed()),
+            _ => None,
+        }
+    }
+
+    /// Show a desktop notification for a long operation that finished while
+    /// the user was in another window
+    fn notify_if_unfocused(
+        &self,
+        ctx: &egui::Context,
+        elapsed: Option<Duration>,
+        title: &str,
+        body: &str,
+    ) {
+        let unfocused = ctx.input(|i| i.viewport().focused) == Some(false);
+        if unfocused
+            && elapsed.is_some_and(|e| e >= NOTIFY_AFTER)
+            && let Err(e) = show_notification(title, body)
+        {
+            log::warn!("Failed to show notification: {}", e);
+        }
+    }
+
     /// Start export in a background thread
     pub fn start_export(&mut self) {
         // Need atlases to export
@@ -496,15 +953,133 @@ impl BentoApp {
             return;
         };
 
-        // Clone config for the worker thread
-        let config = self.state.config.clone();
+        // Confirm before replacing files from an earlier export
+        let (overwritten, stale) = find_export_conflicts(&atlases, &self.state.config);
+        if !overwritten.is_empty() || !stale.is_empty() {
+            self.export_conflict_dialog = Some(ExportConflictDialog::new(
+                overwritten,
+                stale,
+                timestamped_folder_name(),
+            ));
+            return;
+        }
+
+        self.spawn_export(self.state.config.clone());
+    }
+
+    /// Handle global keyboard shortcuts. Keys that would type into a focused
+    /// text field are left alone.
+    fn handle_shortcuts(&mut self, ctx: &egui::Context) {
+        // Dialogs own the keyboard while they are open
+        if self.unsaved_changes_dialog.is_some()
+            || self.config_chooser.is_some()
+            || self.export_conflict_dialog.is_some()
+            || self.state.runtime.file_dialog_task.is_some()
+        {
+            return;
+        }
+
+        let command = egui::Modifiers::COMMAND;
+        let typing = ctx.wants_keyboard_input();
+        let (save_as, save, open, new, export, pack, zoom_in, zoom_out) = ctx.input_mut(|i| {
+            (
+                i.consume_key(command | egui::Modifiers::SHIFT, egui::Key::S),
+                i.consume_key(command, egui::Key::S),
+                i.consume_key(command, egui::Key::O),
+                i.consume_key(command, egui::Key::N),
+                i.consume_key(command, egui::Key::E),
+                !typing && i.consume_key(egui::Modifiers::NONE, egui::Key::Space),
+                !typing
+                    && (i.consume_key(egui::Modifiers::NONE, egui::Key::Plus)
+                        || i.consume_key(egui::Modifiers::NONE, egui::Key::Equals)),
+                !typing && i.consume_key(egui::Modifiers::NONE, egui::Key::Minus),
+            )
+        });
+
+        // Ctrl+S on a project that was never saved asks where to save it
+        if save_as || (save && self.state.runtime.config_path.is_none()) {
+            self.spawn_file_dialog(FileDialogKind::SaveConfigAs);
+        } else if save && let Err(e) = self.save_current_config() {
+            self.state.runtime.status = Status::Done {
+                result: StatusResult::Error(format!("Failed to save: {}", e)),
+                at: Instant::now(),
+            };
+        }
+        if open {
+            self.spawn_file_dialog(FileDialogKind::OpenConfig);
+        }
+        if new && self.check_unsaved_changes(PendingAction::NewProject) {
+            self.new_project();
+        }
+
+        // Pack and export follow the enabled state of the bottom bar buttons
+        let is_busy = matches!(self.state.runtime.status, Status::Working { .. });
+        if pack && !is_busy && !self.state.config.input_paths.is_empty() {
+            self.start_pack();
+        }
+        if export && !is_busy && self.state.runtime.atlases.is_some() {
+            self.start_export();
+        }
+
+        if zoom_in || zoom_out {
+            let factor = if zoom_in { 1.25 } else { 0.8 };
+            let runtime = &mut self.state.runtime;
+            let zoom = (runtime.preview_zoom * factor).clamp(0.1, 10.0);
+            // Zoom toward the center of the preview
+            runtime.preview_offset *= zoom / runtime.preview_zoom;
+            runtime.preview_zoom = zoom;
+        }
+    }
+
+    /// Update the window's full-screen state if the preview mode changed this frame
+    fn sync_fullscreen(&mut self, ctx: &egui::Context, was_fullscreen: bool) {
+        let fullscreen = self.state.runtime.preview_fullscreen;
+        if fullscreen != was_fullscreen {
+            ctx.send_viewport_cmd(egui::ViewportCommand::Fullscreen(fullscreen));
+            self.state.runtime.needs_fit_to_view = true;
+        }
+    }
+
+    /// Run the export on a worker thread with the given config
+    fn spawn_export(&mut self, config: AppConfig) {
+        let Some(atlases) = self.state.runtime.atlases.clone() else {
+            return;
+        };
+        let animations = Arc::clone(&self.state.runtime.animations);
+
+        // Relative source paths resolve against the project file's folder
+        let project_dir = self
+            .state
+            .runtime
+            .config_path
+            .as_ref()
+            .and_then(|path| path.parent())
+            .map(Path::to_path_buf);
+
+        // Text chunks describe the project file as it would be saved
+        let png_text = config.png_metadata.then(|| {
+            let config_hash = self
+                .state
+                .runtime
+                .config_path
+                .as_ref()
+                .and_then(|path| serde_json::to_vec(&self.config_to_bento_config(path)).ok())
+                .map(|json| sha256_hex(&json));
+            generator_text(config_hash, None)
+        });
 
         // Set up channel
         let (tx, rx) = mpsc::channel();
 
         // Spawn worker thread
         std::thread::spawn(move || {
-            let result = export_atlases(&atlases, &config);
+            let result = export_atlases(
+                &atlases,
+                &animations,
+                &config,
+                project_dir.as_deref(),
+                png_text.as_deref(),
+            );
             let _ = tx.send(result);
         });
 
@@ -592,22 +1167,54 @@ impl BentoApp {
 
         // Spawn background thread to re-estimate PNG sizes
         let atlases = atlases.clone();
-        let opaque = self.state.config.opaque;
+        // Split-alpha output stores the color image without alpha
+        let opaque = self.state.config.opaque || self.state.config.split_alpha;
         let compress = self.state.config.compress;
 
         let (tx, rx) = mpsc::channel();
         std::thread::spawn(move || {
-            let sizes: Vec<usize> = atlases
-                .iter()
-                .map(|a| estimate_png_size(&a.image, opaque, compress))
-                .collect();
-            let _ = tx.send(Ok(sizes));
+            let result = estimate_sizes(&atlases, opaque, compress, None, None)
+                .ok_or_else(|| "cancelled".to_string());
+            let _ = tx.send(result);
         });
 
         self.state.runtime.size_estimate_task = Some(BackgroundTask::new(rx));
         self.state.runtime.last_export_hash = Some(current_export_hash);
     }
 
+    /// Reload the input sprites for their statistics when the inputs or the
+    /// settings that shape them change, without packing
+    fn handle_input_settings_change(&mut self) {
+        let current_hash = self.state.config.input_settings_hash();
+        if self.state.runtime.last_input_stats_hash == Some(current_hash)
+            || self.state.runtime.input_stats_task.is_some()
+        {
+            return;
+        }
+        self.state.runtime.last_input_stats_hash = Some(current_hash);
+        if self.state.config.input_paths.is_empty() {
+            self.state.runtime.input_stats = None;
+            return;
+        }
+
+        let config = self.state.config.clone();
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            let _ = tx.send(input_stats(&config));
+        });
+        self.state.runtime.input_stats_task = Some(BackgroundTask::new(rx));
+    }
+
+    /// Poll background input statistics task for completion
+    fn poll_input_stats_task(&mut self) {
+        if let Some(task) = &self.state.runtime.input_stats_task
+            && let Some(result) = task.poll()
+        {
+            self.state.runtime.input_stats_task = None;
+            self.state.runtime.input_stats = Some(result);
+        }
+    }
+
     /// Poll background size estimation task for completion
     fn poll_size_estimate_task(&mut self) {
         if let Some(task) = &self.state.runtime.size_estimate_task
@@ -615,7 +1222,8 @@ impl BentoApp {
         {
             self.state.runtime.size_estimate_task = None;
             if let Ok(sizes) = result {
-                self.state.runtime.atlas_png_sizes = sizes;
+                self.state.runtime.atlas_png_sizes = sizes.png_sizes;
+                self.state.runtime.compression_estimate = sizes.compression;
             }
         }
     }
@@ -646,7 +1254,10 @@ impl BentoApp {
 
         // Spawn loader if not already running
         if self.state.runtime.thumbnail_receiver.is_none() {
-            self.state.runtime.thumbnail_receiver = Some(spawn_thumbnail_loader(paths_to_load));
+            self.state.runtime.thumbnail_receiver = Some(spawn_thumbnail_loader(
+                paths_to_load,
+                self.state.runtime.thumbnail_cache.clone(),
+            ));
         }
     }
 
@@ -721,8 +1332,11 @@ impl BentoApp {
                         Some(FileDialogKind::SaveConfigAs),
                         FileDialogResult::SinglePath(Some(path)),
                     ) => {
-                        // Ensure .bento extension
-                        let path = if path.extension().is_some_and(|e| e == "bento") {
+                        // Ensure .bento extension, unless saving as TOML
+                        let path = if path
+                            .extension()
+                            .is_some_and(|e| e == "bento" || e == "toml")
+                        {
                             path
                         } else {
                             path.with_extension("bento")
@@ -774,6 +1388,25 @@ impl BentoApp {
                     ) => {
                         self.state.config.output_dir = folder;
                     }
+                    (
+                        Some(FileDialogKind::ExportSprite { name, restore_trim }),
+                        FileDialogResult::SinglePath(Some(path)),
+                    ) => {
+                        let result = match self.export_sprite(&name, restore_trim, &path) {
+                            Ok(()) => StatusResult::Success(format!(
+                                "Exported {} to {}",
+                                name,
+                                path.display()
+                            )),
+                            Err(e) => {
+                                StatusResult::Error(format!("Failed to export {}: {}", name, e))
+                            }
+                        };
+                        self.state.runtime.status = Status::Done {
+                            result,
+                            at: Instant::now(),
+                        };
+                    }
                     // Dialog was cancelled or returned None
                     _ => {}
                 }
@@ -781,6 +1414,37 @@ impl BentoApp {
         }
     }
 
+    /// Handle actions requested by the preview panel
+    fn handle_preview_action(&mut self, action: panels::PreviewAction) {
+        if let Some((name, restore_trim)) = action.export_sprite {
+            self.spawn_file_dialog(FileDialogKind::ExportSprite { name, restore_trim });
+        }
+    }
+
+    /// Save a packed sprite's pixels from the current atlases as a PNG
+    fn export_sprite(&self, name: &str, restore_trim: bool, path: &Path) -> Result<(), String> {
+        let (atlas, sprite) = self
+            .state
+            .runtime
+            .atlases
+            .iter()
+            .flat_map(|atlases| atlases.iter())
+            .find_map(|atlas| atlas.find(name).map(|sprite| (atlas, sprite)))
+            .ok_or_else(|| "sprite is no longer in the atlas".to_string())?;
+        let path = if path
+            .extension()
+            .is_some_and(|e| e.eq_ignore_ascii_case("png"))
+        {
+            path.to_path_buf()
+        } else {
+            path.with_extension("png")
+        };
+        atlas
+            .sprite_image(sprite, restore_trim)
+            .save(&path)
+            .map_err(|e| e.to_string())
+    }
+
     /// Spawn a file dialog if not already running
     fn spawn_file_dialog(&mut self, kind: FileDialogKind) {
         // Don't spawn if one is already running
@@ -788,7 +1452,7 @@ impl BentoApp {
             return;
         }
 
-        let task = match kind {
+        let task = match &kind {
             FileDialogKind::OpenConfig => {
                 spawn_open_config_dialog(self.state.runtime.last_input_dir.clone())
             }
@@ -804,6 +1468,9 @@ impl BentoApp {
             FileDialogKind::OutputFolder => {
                 spawn_output_folder_dialog(self.state.config.output_dir.clone())
             }
+            FileDialogKind::ExportSprite { name, .. } => {
+                spawn_export_sprite_dialog(self.state.config.output_dir.clone(), name)
+            }
         };
 
         self.state.runtime.file_dialog_task = Some(task);
@@ -812,96 +1479,757 @@ impl BentoApp {
 }
 
 /// Perform packing on a background thread
-fn pack_atlases(config: &AppConfig, cancel_token: Arc<AtomicBool>) -> Result<PackResult, String> {
+fn pack_atlases(
+    config: &AppConfig,
+    cancel_token: Arc<AtomicBool>,
+    progress: ProgressCallback,
+) -> Result<PackResult, String> {
+    let inputs = included_inputs(config)?;
+    let load_options = project_load_options(config, Some(&cancel_token), Some(&progress))?;
+    let ProjectSprites {
+        sprites,
+        mut warnings,
+        sprite_settings,
+    } = load_project_sprites(config, &inputs, &load_options)?;
+    let animations = load_animations(&inputs, &load_options).map_err(|e| e.to_string())?;
+
+    // Build atlas
+    let builder = project_builder(config, sprite_settings.clone())?
+        .cancel_token(cancel_token.clone())
+        .progress(progress.clone());
+    warnings.extend(builder.warnings(&sprites));
+    let atlases = builder.build(sprites).map_err(|e| e.to_string())?;
+    let stats = SpriteStats::from_packed(atlases.iter().flat_map(|a| &a.sprites), |name| {
+        builder.spacing_for(name)
+    });
+
+    // Estimate PNG sizes on background thread (check cancellation)
+    let sizes = estimate_sizes(
+        &atlases,
+        config.opaque || config.split_alpha,
+        config.compress,
+        Some(&cancel_token),
+        Some(&progress),
+    )
+    .ok_or_else(|| "cancelled".to_string())?;
+
+    Ok(PackResult {
+        atlases: Arc::new(atlases),
+        animations: Arc::new(animations),
+        sizes,
+        sprite_settings,
+        stats,
+        warnings,
+    })
+}
+
+/// Statistics for the input sprites as loaded, before packing, so they can
+/// guide the page size
+fn input_stats(config: &AppConfig) -> Result<SpriteStats, String> {
+    let inputs = included_inputs(config)?;
+    let load_options = project_load_options(config, None, None)?;
+    let loaded = load_project_sprites(config, &inputs, &load_options)?;
+    let builder = project_builder(config, loaded.sprite_settings)?;
+    Ok(SpriteStats::from_sources(&loaded.sprites, |name| {
+        builder.spacing_for(name)
+    }))
+}
+
+/// Input paths that aren't excluded
+fn included_inputs(config: &AppConfig) -> Result<Vec<&PathBuf>, String> {
     if config.input_paths.is_empty() {
         return Err("No input files".to_string());
     }
+    let inputs: Vec<&PathBuf> = config
+        .input_paths
+        .iter()
+        .filter(|path| !config.excluded.contains(*path))
+        .collect();
+    if inputs.is_empty() {
+        return Err("All input files are excluded".to_string());
+    }
+    Ok(inputs)
+}
 
+/// Sprite loading options from the project settings
+fn project_load_options(
+    config: &AppConfig,
+    cancel_token: Option<&Arc<AtomicBool>>,
+    progress: Option<&ProgressCallback>,
+) -> Result<LoadOptions, String> {
     // Extract resize options
-    let (resize_width, resize_scale) = match config.resize_mode {
-        ResizeMode::None => (None, None),
-        ResizeMode::Width(w) => (Some(w), None),
-        ResizeMode::Scale(s) => (None, Some(s)),
+    let (resize_width, resize_scale, resize_max_width, resize_fit) = match config.resize_mode {
+        ResizeMode::None => (None, None, None, None),
+        ResizeMode::Width(w) => (Some(w), None, None, None),
+        ResizeMode::Scale(s) => (None, Some(s), None, None),
+        ResizeMode::MaxWidth(w) => (None, None, Some(w), None),
+        ResizeMode::Fit { width, height } => (
+            None,
+            None,
+            None,
+            Some(crate::cli::ResizeFit { width, height }),
+        ),
     };
 
-    // Load sprites (check cancellation during load)
-    let sprites = load_sprites(
-        &config.input_paths,
-        config.trim,
-        config.trim_margin,
+    Ok(LoadOptions {
+        trim: config.trim,
+        trim_margin: config.trim_margin,
+        trim_margin_overrides: compile_overrides(
+            &config.trim_margin_overrides,
+            "trim_margin_overrides",
+        )
+        .map_err(|e| e.to_string())?,
+        trim_alpha: config.trim_alpha,
+        grid_slices: compile_overrides(&config.grid_slice, "grid_slice")
+            .map_err(|e| e.to_string())?,
+        overrides: compile_overrides(&config.overrides, "overrides").map_err(|e| e.to_string())?,
+        record_trim: config.record_trim,
         resize_width,
         resize_scale,
-        config.resize_filter,
-        Some(&cancel_token),
-        None,
-        false,
+        resize_max_width,
+        resize_fit,
+        resize_filter: config.resize_filter,
+        resize_linear: config.resize_linear,
+        scale_suffixes: config
+            .scale_suffixes
+            .iter()
+            .map(|(suffix, &factor)| (suffix.clone(), factor))
+            .collect(),
+        companions: config.companions.clone(),
+        cancel_token: cancel_token.cloned(),
+        progress: progress.cloned(),
+        base_dir: None,
+        filename_only: false,
+        exclude: compile_exclude(&config.exclude_patterns).map_err(|e| e.to_string())?,
+        split_layers: config.split_layers,
+        skip_errors: config.skip_errors,
+        deterministic: config.deterministic,
+        sort: config.sort,
+    })
+}
+
+/// Input sprites ready to pack
+struct ProjectSprites {
+    sprites: Vec<SourceSprite>,
+    /// Problems found while loading that didn't stop it
+    warnings: Vec<Warning>,
+    sprite_settings: HashMap<String, SpriteSettings>,
+}
+
+/// Load the input sprites as packing sees them: renamed, with nine-slice
+/// borders, and with their per-sprite settings resolved
+fn load_project_sprites(
+    config: &AppConfig,
+    inputs: &[&PathBuf],
+    load_options: &LoadOptions,
+) -> Result<ProjectSprites, String> {
+    let (mut sprites, warnings) =
+        load_sprites_with_warnings(inputs, load_options).map_err(|e| e.to_string())?;
+    apply_sprite_overrides(
+        &mut sprites,
+        &std::collections::HashSet::new(),
+        &config
+            .sprite_names
+            .iter()
+            .map(|(path, name)| (path.clone(), name.clone()))
+            .collect(),
     )
     .map_err(|e| e.to_string())?;
 
-    // Build atlas
-    let atlases = AtlasBuilder::new(config.max_width, config.max_height)
-        .padding(config.padding)
+    apply_nine_slices(
+        &mut sprites,
+        &compile_overrides(&config.nine_slice, "nine_slice").map_err(|e| e.to_string())?,
+    );
+
+    let sprite_settings = resolve_sprite_settings(
+        &config.extrude_overrides,
+        &config.overrides,
+        sprites.iter().map(|s| s.name.as_str()),
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(ProjectSprites {
+        sprites,
+        warnings,
+        sprite_settings,
+    })
+}
+
+/// Atlas builder with the project's packing settings
+fn project_builder(
+    config: &AppConfig,
+    sprite_settings: HashMap<String, SpriteSettings>,
+) -> Result<AtlasBuilder, String> {
+    Ok(AtlasBuilder::new(config.max_width, config.max_height)
+        .padding_x(config.padding_x)
+        .padding_y(config.padding_y)
         .heuristic(config.heuristic)
         .power_of_two(config.pot)
         .extrude(config.extrude)
+        .extrude_mode(config.extrude_mode)
+        .background(config.background_color.map(Into::into))
+        .sprite_settings(sprite_settings)
         .block_align(config.block_align)
+        .allow_rotation(config.allow_rotation)
+        .size_search(config.size_search)
+        .square(config.square)
+        .fixed_size(config.fixed_size)
+        .size_multiple(config.size_multiple)
         .pack_mode(config.pack_mode)
-        .cancel_token(cancel_token.clone())
-        .build(sprites)
-        .map_err(|e| e.to_string())?;
-
-    // Estimate PNG sizes on background thread (check cancellation)
-    let mut png_sizes = Vec::with_capacity(atlases.len());
-    for atlas in &atlases {
-        if cancel_token.load(Ordering::Relaxed) {
-            return Err("cancelled".to_string());
-        }
-        png_sizes.push(estimate_png_size(
-            &atlas.image,
-            config.opaque,
-            config.compress,
-        ));
-    }
-
-    Ok(PackResult {
-        atlases: Arc::new(atlases),
-        png_sizes,
-    })
+        .trials(config.trials)
+        .seed(config.seed)
+        .pack_order(compile_pack_order(&config.pack_order).map_err(|e| e.to_string())?)
+        .keep_together(compile_keep_together(&config.keep_together).map_err(|e| e.to_string())?)
+        .max_sprites_per_page(config.max_sprites_per_page)
+        .grid(config.grid)
+        .max_total_pixels(config.max_total_pixels)
+        .max_total_bytes(config.max_total_bytes)
+        .max_atlases(config.max_atlases))
 }
 
 /// Perform export on a background thread
-fn export_atlases(atlases: &[Atlas], config: &AppConfig) -> Result<(), String> {
+fn export_atlases(
+    atlases: &[Atlas],
+    animations: &[Animation],
+    config: &AppConfig,
+    project_dir: Option<&Path>,
+    png_text: Option<&[(String, String)]>,
+) -> Result<(), String> {
+    let scales = output_scales(config)?;
+    if config.formats.contains(&OutputFormat::Godot) && scales.len() > 1 {
+        return Err(
+            "Godot output writes one .tres per sprite, so it can only export one scale".to_string(),
+        );
+    }
+
     // Ensure output directory exists
     std::fs::create_dir_all(&config.output_dir)
         .map_err(|e| format!("Failed to create output directory: {}", e))?;
 
+    // Mip generation and scaling need each sprite's spacing to keep borders intact
+    let sprite_settings = resolve_sprite_settings(
+        &config.extrude_overrides,
+        &config.overrides,
+        atlases
+            .iter()
+            .flat_map(|atlas| &atlas.sprites)
+            .map(|s| s.name.as_str()),
+    )
+    .map_err(|e| e.to_string())?;
+    let builder = AtlasBuilder::new(config.max_width, config.max_height)
+        .padding_x(config.padding_x)
+        .padding_y(config.padding_y)
+        .extrude(config.extrude)
+        .extrude_mode(config.extrude_mode)
+        .background(config.background_color.map(Into::into))
+        .sprite_settings(sprite_settings)
+        .power_of_two(config.pot)
+        .block_align(config.block_align)
+        .square(config.square)
+        .size_multiple(config.size_multiple);
+
+    for scale in scales {
+        let name = scaled_base_name(&config.name, scale);
+        if scale == 1.0 {
+            export_sheet(
+                atlases,
+                animations,
+                config,
+                &builder,
+                &name,
+                project_dir,
+                png_text,
+            )?;
+        } else {
+            let filter = config.resize_filter.to_image_filter();
+            let scaled: Vec<Atlas> = atlases
+                .iter()
+                .map(|atlas| builder.scale_atlas(atlas, scale, filter, config.resize_linear))
+                .collect();
+            export_sheet(
+                &scaled,
+                animations,
+                config,
+                &builder,
+                &name,
+                project_dir,
+                png_text,
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Parse the comma-separated scales field; an empty field exports full size only
+fn parse_scales(text: &str) -> Result<Vec<f32>, String> {
+    text.split(',')
+        .map(str::trim)
+        .filter(|scale| !scale.is_empty())
+        .map(|scale| {
+            scale
+                .parse::<f32>()
+                .ok()
+                .filter(|value| value.is_finite() && *value > 0.0)
+                .ok_or_else(|| format!("Invalid scale '{}': use positive numbers like 0.5", scale))
+        })
+        .collect()
+}
+
+/// Scales an export writes, full size when none are set
+fn output_scales(config: &AppConfig) -> Result<Vec<f32>, String> {
+    let mut scales = parse_scales(&config.scales)?;
+    if scales.is_empty() {
+        scales.push(1.0);
+    }
+    for (i, scale) in scales.iter().enumerate() {
+        if scales[..i].contains(scale) {
+            return Err(format!("Scale {} is listed more than once", scale));
+        }
+    }
+    Ok(scales)
+}
+
+/// Write the atlas images and metadata for one output scale
+fn export_sheet(
+    atlases: &[Atlas],
+    animations: &[Animation],
+    config: &AppConfig,
+    builder: &AtlasBuilder,
+    name: &str,
+    project_dir: Option<&Path>,
+    png_text: Option<&[(String, String)]>,
+) -> Result<(), String> {
     // Save PNG images for each atlas
+    let output_template = config.output_template()?;
     let total = atlases.len();
     for atlas in atlases {
-        let png_path = config
-            .output_dir
-            .join(atlas_png_filename(&config.name, atlas.index, total));
-        save_atlas_image(atlas, &png_path, config.opaque, config.compress)
-            .map_err(|e| e.to_string())?;
-    }
+        let png_path =
+            config
+                .output_dir
+                .join(output_template.png_filename(name, atlas.index, total));
+        save_atlas_image(
+            atlas,
+            &png_path,
+            config.opaque || config.split_alpha,
+            config.matte_color.filter(|_| !config.split_alpha),
+            config.compress,
+        )
+        .map_err(|e| e.to_string())?;
+        if let Some(text) = png_text {
+            add_png_text(&png_path, text).map_err(|e| e.to_string())?;
+        }
 
-    // Write metadata file based on format
-    match config.format {
-        OutputFormat::Json => {
-            write_json(atlases, &config.output_dir, &config.name).map_err(|e| e.to_string())?;
+        if config.split_alpha {
+            let alpha_path =
+                config
+                    .output_dir
+                    .join(output_template.alpha_filename(name, atlas.index, total));
+            save_alpha_mask(atlas, &alpha_path, config.compress).map_err(|e| e.to_string())?;
         }
-        OutputFormat::Godot => {
-            write_godot_resources(atlases, &config.output_dir, &config.name, None)
+
+        // Every page gets each companion, blank where none of its sprites had one
+        for suffix in &config.companions {
+            let companion_path = config.output_dir.join(output_template.companion_filename(
+                name,
+                atlas.index,
+                total,
+                suffix,
+            ));
+            let blank;
+            let image = match atlas.companions.get(suffix) {
+                Some(image) => image,
+                None => {
+                    blank = image::RgbaImage::new(atlas.width, atlas.height);
+                    &blank
+                }
+            };
+            save_image(image, &companion_path, false, None, config.compress)
                 .map_err(|e| e.to_string())?;
         }
-        OutputFormat::Tpsheet => {
-            write_tpsheet(atlases, &config.output_dir, &config.name).map_err(|e| e.to_string())?;
+
+        if config.mipmaps > 0 {
+            let mips = builder.mipmaps(atlas, config.mipmaps);
+            match config.mipmap_format {
+                MipmapFormat::Png => {
+                    for (level, image) in (1..).zip(&mips) {
+                        let mip_path = config.output_dir.join(output_template.mip_filename(
+                            name,
+                            atlas.index,
+                            total,
+                            level,
+                        ));
+                        save_image(
+                            image,
+                            &mip_path,
+                            // Same encoding as the base page, so levels match it
+                            config.opaque || config.split_alpha,
+                            config.matte_color.filter(|_| !config.split_alpha),
+                            config.compress,
+                        )
+                        .map_err(|e| e.to_string())?;
+                    }
+                }
+                MipmapFormat::Ktx2 => {
+                    let ktx2_path = config.output_dir.join(output_template.ktx2_filename(
+                        name,
+                        atlas.index,
+                        total,
+                    ));
+                    write_ktx2(&atlas.image, &mips, &ktx2_path).map_err(|e| e.to_string())?;
+                }
+            }
+        }
+
+        if let Some(mode) = config.basis {
+            if config.mipmaps > 0 && config.mipmap_format == MipmapFormat::Ktx2 {
+                return Err(
+                    "Basis KTX2 and KTX2 mip levels both write a .ktx2 per page; use PNG mip levels"
+                        .to_string(),
+                );
+            }
+            let ktx2_path =
+                config
+                    .output_dir
+                    .join(output_template.ktx2_filename(name, atlas.index, total));
+            let encoder = BasisuEncoder::new(mode, config.mipmaps > 0);
+            save_encoded(&atlas.image, &ktx2_path, config.opaque, &encoder)
+                .map_err(|e| e.to_string())?;
+        }
+    }
+
+    // Write a metadata file for each format
+    for format in &config.formats {
+        match format {
+            OutputFormat::Json => {
+                write_json(
+                    atlases,
+                    &config.output_dir,
+                    name,
+                    &output_template,
+                    config.sprite_order,
+                    config.json_compact,
+                    config.split_alpha,
+                    Some(config.image_path_prefix.as_str()),
+                    // Unsaved projects have no folder to be relative to
+                    config.source_paths.map(|style| match project_dir {
+                        Some(dir) => (style, dir),
+                        None => (SourcePaths::Absolute, Path::new(".")),
+                    }),
+                    config.polygon.then_some(config.polygon_tolerance),
+                    config.content_hash,
+                    animations,
+                )
+                .map_err(|e| e.to_string())?;
+            }
+            OutputFormat::Godot => {
+                write_godot_resources(
+                    atlases,
+                    &config.output_dir,
+                    name,
+                    &output_template,
+                    Some(config.godot_res_path.as_str()).filter(|path| !path.is_empty()),
+                    config.godot_version,
+                )
+                .map_err(|e| e.to_string())?;
+            }
+            OutputFormat::Tpsheet => {
+                write_tpsheet(
+                    atlases,
+                    &config.output_dir,
+                    name,
+                    &output_template,
+                    config.sprite_order,
+                    config.json_compact,
+                    Some(config.image_path_prefix.as_str()),
+                )
+                .map_err(|e| e.to_string())?;
+            }
+            OutputFormat::Libgdx => {
+                write_libgdx(
+                    atlases,
+                    &config.output_dir,
+                    name,
+                    &output_template,
+                    config.sprite_order,
+                    Some(config.image_path_prefix.as_str()),
+                )
+                .map_err(|e| e.to_string())?;
+            }
+            OutputFormat::Phaser => {
+                write_phaser(
+                    atlases,
+                    &config.output_dir,
+                    name,
+                    &output_template,
+                    config.sprite_order,
+                    config.json_compact,
+                    config.phaser_frames,
+                    Some(config.image_path_prefix.as_str()),
+                )
+                .map_err(|e| e.to_string())?;
+            }
+            OutputFormat::Cocos => {
+                write_cocos(
+                    atlases,
+                    &config.output_dir,
+                    name,
+                    &output_template,
+                    config.sprite_order,
+                    Some(config.image_path_prefix.as_str()),
+                )
+                .map_err(|e| e.to_string())?;
+            }
+            OutputFormat::Template => {
+                if config.template.is_empty() {
+                    return Err("Choose a template file for template output".to_string());
+                }
+                // Relative templates start from the project folder, like in the CLI
+                let template = project_dir.unwrap_or(Path::new(".")).join(&config.template);
+                write_template(
+                    atlases,
+                    &config.output_dir,
+                    name,
+                    &output_template,
+                    config.sprite_order,
+                    Some(config.image_path_prefix.as_str()),
+                    &template,
+                )
+                .map_err(|e| format!("{:#}", e))?;
+            }
         }
     }
 
     Ok(())
 }
 
+/// Names (relative to the output directory) of the files an export will write
+fn export_file_names(atlases: &[Atlas], config: &AppConfig) -> Vec<String> {
+    // Scaled pages are close enough in size to count their mip levels
+    let scales = output_scales(config).unwrap_or_else(|_| vec![1.0]);
+    scales
+        .into_iter()
+        .flat_map(|scale| sheet_file_names(atlases, config, &scaled_base_name(&config.name, scale)))
+        .collect()
+}
+
+/// Names of the files written for one output scale
+fn sheet_file_names(atlases: &[Atlas], config: &AppConfig, name: &str) -> Vec<String> {
+    let output_template = config.output_template().unwrap_or_default();
+    let total = atlases.len();
+    let mut names: Vec<String> = atlases
+        .iter()
+        .map(|atlas| output_template.png_filename(name, atlas.index, total))
+        .collect();
+    if config.split_alpha {
+        names.extend(
+            atlases
+                .iter()
+                .map(|atlas| output_template.alpha_filename(name, atlas.index, total)),
+        );
+    }
+    for atlas in atlases {
+        names.extend(
+            config
+                .companions
+                .iter()
+                .map(|suffix| output_template.companion_filename(name, atlas.index, total, suffix)),
+        );
+        let levels = mip_level_count(atlas.width, atlas.height, config.mipmaps);
+        match config.mipmap_format {
+            MipmapFormat::Png => names.extend(
+                (1..=levels as usize)
+                    .map(|level| output_template.mip_filename(name, atlas.index, total, level)),
+            ),
+            MipmapFormat::Ktx2 if levels > 0 => {
+                names.push(output_template.ktx2_filename(name, atlas.index, total));
+            }
+            MipmapFormat::Ktx2 => {}
+        }
+        if config.basis.is_some() {
+            names.push(output_template.ktx2_filename(name, atlas.index, total));
+        }
+    }
+
+    for format in &config.formats {
+        match format {
+            OutputFormat::Json => names.push(format!("{}.json", name)),
+            OutputFormat::Tpsheet => names.push(format!("{}.tpsheet", name)),
+            OutputFormat::Libgdx => names.push(format!("{}.atlas", name)),
+            OutputFormat::Phaser => names.push(format!("{}.phaser.json", name)),
+            OutputFormat::Template => {
+                names.push(template_output_filename(name, Path::new(&config.template)))
+            }
+            OutputFormat::Cocos => names.extend(
+                atlases
+                    .iter()
+                    .map(|atlas| cocos_plist_filename(&output_template, name, atlas.index, total)),
+            ),
+            OutputFormat::Godot => {
+                for sprite in atlases.iter().flat_map(|atlas| &atlas.sprites) {
+                    names.push(format!("{}.tres", sprite.name));
+                    if sprite.nine_slice.is_some() {
+                        names.push(stylebox_filename(&sprite.name));
+                    }
+                }
+            }
+        }
+    }
+
+    names
+}
+
+/// Find existing files an export would overwrite, and atlas files from a
+/// previous export (e.g. `atlas_3.png` after the page count shrank) that it
+/// would leave behind
+fn find_export_conflicts(atlases: &[Atlas], config: &AppConfig) -> (Vec<String>, Vec<String>) {
+    let planned = export_file_names(atlases, config);
+    let overwritten: Vec<String> = planned
+        .iter()
+        .filter(|name| config.output_dir.join(name).exists())
+        .cloned()
+        .collect();
+
+    let is_atlas_output = |file_name: &str| {
+        let Some(rest) = file_name.strip_prefix(config.name.as_str()) else {
+            return false;
+        };
+        let stem = rest
+            .strip_suffix("_alpha.png")
+            .or_else(|| {
+                let page = rest.strip_suffix(".png")?;
+                config
+                    .companions
+                    .iter()
+                    .filter(|suffix| !suffix.is_empty())
+                    .find_map(|suffix| page.strip_suffix(suffix.as_str()))
+            })
+            .or_else(|| rest.strip_suffix(".png").map(strip_mip_level))
+            .or_else(|| rest.strip_suffix(".ktx2"))
+            .or_else(|| rest.strip_suffix(".phaser.json"))
+            .or_else(|| rest.strip_suffix(".json"))
+            .or_else(|| rest.strip_suffix(".tpsheet"))
+            .or_else(|| rest.strip_suffix(".atlas"))
+            .or_else(|| rest.strip_suffix(".plist"));
+        // Scaled sets carry an `@0.5x` style suffix before any page index
+        let stem = stem.map(
+            |stem| match stem.strip_prefix('@').and_then(|s| s.split_once('x')) {
+                Some((scale, rest)) if scale.parse::<f32>().is_ok() => rest,
+                _ => stem,
+            },
+        );
+        match stem {
+            Some("") => true,
+            Some(suffix) => suffix
+                .strip_prefix('_')
+                .is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit())),
+            None => false,
+        }
+    };
+
+    let mut stale: Vec<String> = std::fs::read_dir(&config.output_dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|name| is_atlas_output(name) && !planned.contains(name))
+        .collect();
+    stale.sort();
+
+    (overwritten, stale)
+}
+
+/// Strip a `.mipN` level suffix from an atlas file stem, if present
+fn strip_mip_level(stem: &str) -> &str {
+    match stem.rsplit_once(".mip") {
+        Some((atlas, level)) if !level.is_empty() && level.chars().all(|c| c.is_ascii_digit()) => {
+            atlas
+        }
+        _ => stem,
+    }
+}
+
+/// Subfolder name for exporting alongside existing files, e.g. `export-20240131-154502` (UTC)
+fn timestamped_folder_name() -> String {
+    let secs = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let (days, day_secs) = (secs / 86_400, secs % 86_400);
+
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z % 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+
+    format!(
+        "export-{:04}{:02}{:02}-{:02}{:02}{:02}",
+        year,
+        month,
+        day,
+        day_secs / 3600,
+        day_secs % 3600 / 60,
+        day_secs % 60
+    )
+}
+
+/// Estimate PNG sizes for all pages. With compression enabled, each page is
+/// also encoded without it, to measure the bytes saved and the time it costs.
+/// Returns None if cancelled.
+fn estimate_sizes(
+    atlases: &[Atlas],
+    opaque: bool,
+    compress: Option<CompressionLevel>,
+    cancel_token: Option<&AtomicBool>,
+    progress: Option<&ProgressCallback>,
+) -> Option<SizeEstimate> {
+    let mut png_sizes = Vec::with_capacity(atlases.len());
+    let mut uncompressed_bytes = 0;
+    let mut compressed_bytes = 0;
+    let mut extra_time = Duration::ZERO;
+    for atlas in atlases {
+        if cancel_token.is_some_and(|t| t.load(Ordering::Relaxed)) {
+            return None;
+        }
+        let started = Instant::now();
+        let plain_size = estimate_png_size(&atlas.image, opaque, None);
+        let plain_time = started.elapsed();
+        if let Some(level) = compress {
+            let started = Instant::now();
+            let size = estimate_png_size(&atlas.image, opaque, Some(level));
+            // Compressed estimation encodes the plain PNG first, then optimizes it
+            extra_time += started.elapsed().saturating_sub(plain_time);
+            uncompressed_bytes += plain_size;
+            compressed_bytes += size;
+            png_sizes.push(size);
+        } else {
+            png_sizes.push(plain_size);
+        }
+        if let Some(progress) = progress {
+            progress.report(ProgressEvent::PngEncoded {
+                done: png_sizes.len(),
+                total: atlases.len(),
+            });
+        }
+    }
+
+    Some(SizeEstimate {
+        png_sizes,
+        compression: compress.map(|level| CompressionEstimate {
+            level,
+            uncompressed_bytes,
+            compressed_bytes,
+            extra_time,
+        }),
+    })
+}
+
 /// Estimate PNG file size by encoding to memory, optionally with compression
 fn estimate_png_size(
     image: &image::RgbaImage,
@@ -940,11 +2268,7 @@ fn estimate_png_size(
 
     // Apply compression if enabled
     if let Some(level) = compress {
-        let opts = match level {
-            CompressionLevel::Level(n) => oxipng::Options::from_preset(n),
-            CompressionLevel::Max => oxipng::Options::max_compression(),
-        };
-        match oxipng::optimize_from_memory(&buffer.into_inner(), &opts) {
+        match oxipng::optimize_from_memory(&buffer.into_inner(), &oxipng_options(level)) {
             Ok(compressed) => compressed.len(),
             Err(_) => 0,
         }
@@ -960,7 +2284,7 @@ fn estimate_png_size(
 fn spawn_open_config_dialog(last_dir: Option<PathBuf>) -> BackgroundTask<FileDialogResult> {
     let (tx, rx) = mpsc::channel();
     std::thread::spawn(move || {
-        let mut dialog = rfd::FileDialog::new().add_filter("Bento Config", &["bento"]);
+        let mut dialog = rfd::FileDialog::new().add_filter("Bento Config", &["bento", "toml"]);
         if let Some(dir) = last_dir {
             dialog = dialog.set_directory(dir);
         }
@@ -978,7 +2302,7 @@ fn spawn_save_as_dialog(
     let default_name = default_name.to_string();
     std::thread::spawn(move || {
         let mut dialog = rfd::FileDialog::new()
-            .add_filter("Bento Config", &["bento"])
+            .add_filter("Bento Config", &["bento", "toml"])
             .set_file_name(&default_name);
         if let Some(dir) = last_dir {
             dialog = dialog.set_directory(dir);
@@ -1026,6 +2350,30 @@ fn spawn_output_folder_dialog(current_dir: PathBuf) -> BackgroundTask<FileDialog
     BackgroundTask::new(rx)
 }
 
+fn spawn_export_sprite_dialog(
+    output_dir: PathBuf,
+    sprite_name: &str,
+) -> BackgroundTask<FileDialogResult> {
+    let (tx, rx) = mpsc::channel();
+    // Sprite names may carry folders and a source extension
+    let file_name = Path::new(sprite_name)
+        .with_extension("png")
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "sprite.png".to_string());
+    std::thread::spawn(move || {
+        let mut dialog = rfd::FileDialog::new()
+            .add_filter("PNG Image", &["png"])
+            .set_file_name(&file_name);
+        if output_dir.is_dir() {
+            dialog = dialog.set_directory(output_dir);
+        }
+        let result = FileDialogResult::SinglePath(dialog.save_file());
+        let _ = tx.send(Ok(result));
+    });
+    BackgroundTask::new(rx)
+}
+
 impl eframe::App for BentoApp {
     fn save(&mut self, storage: &mut dyn eframe::Storage) {
         eframe::set_value(
@@ -1033,6 +2381,12 @@ impl eframe::App for BentoApp {
             LAST_INPUT_DIR_KEY,
             &self.state.runtime.last_input_dir,
         );
+        eframe::set_value(storage, UI_PREFS_KEY, &self.state.runtime.ui_prefs());
+        eframe::set_value(
+            storage,
+            RECENT_PROJECTS_KEY,
+            &self.state.runtime.recent_projects,
+        );
     }
 
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
@@ -1076,6 +2430,24 @@ impl eframe::App for BentoApp {
             }
         }
 
+        // Handle export overwrite confirmation
+        if let Some(ref mut dialog) = self.export_conflict_dialog
+            && let Some(choice) = dialog.show(ctx)
+        {
+            let subfolder = dialog.subfolder.clone();
+            self.export_conflict_dialog = None;
+
+            match choice {
+                ExportConflictChoice::Cancel => {}
+                ExportConflictChoice::Overwrite => self.spawn_export(self.state.config.clone()),
+                ExportConflictChoice::Subfolder => {
+                    let mut config = self.state.config.clone();
+                    config.output_dir = config.output_dir.join(subfolder);
+                    self.spawn_export(config);
+                }
+            }
+        }
+
         // Handle unsaved changes dialog
         if let Some(ref mut dialog) = self.unsaved_changes_dialog {
             if let Some(choice) = dialog.show(ctx) {
@@ -1112,8 +2484,9 @@ impl eframe::App for BentoApp {
 
         // Poll background tasks
         self.poll_pack_task(ctx);
-        self.poll_export_task();
+        self.poll_export_task(ctx);
         self.poll_size_estimate_task();
+        self.poll_input_stats_task();
         self.poll_file_dialog_task(ctx);
 
         // Handle thumbnails
@@ -1127,12 +2500,16 @@ impl eframe::App for BentoApp {
         // Re-estimate PNG sizes if export settings changed
         self.handle_export_settings_change();
 
+        // Refresh input statistics if the inputs changed
+        self.handle_input_settings_change();
+
         // Request repaint if we have an active task or pending repack
         if self.state.runtime.pack_task.is_some()
             || self.state.runtime.export_task.is_some()
             || self.state.runtime.pending_repack_at.is_some()
             || self.state.runtime.thumbnail_receiver.is_some()
             || self.state.runtime.size_estimate_task.is_some()
+            || self.state.runtime.input_stats_task.is_some()
             || self.state.runtime.file_dialog_task.is_some()
         {
             ctx.request_repaint();
@@ -1144,6 +2521,27 @@ impl eframe::App for BentoApp {
             .status
             .maybe_clear(Duration::from_secs(5));
 
+        // Toggle full-screen preview (F11, Escape to leave)
+        let was_fullscreen = self.state.runtime.preview_fullscreen;
+        ctx.input_mut(|i| {
+            if i.consume_key(egui::Modifiers::NONE, egui::Key::F11) {
+                self.state.runtime.preview_fullscreen = !was_fullscreen;
+            } else if was_fullscreen && i.consume_key(egui::Modifiers::NONE, egui::Key::Escape) {
+                self.state.runtime.preview_fullscreen = false;
+            }
+        });
+
+        self.handle_shortcuts(ctx);
+
+        if self.state.runtime.preview_fullscreen {
+            let action = egui::CentralPanel::default()
+                .show(ctx, |ui| panels::preview_panel(ui, &mut self.state))
+                .inner;
+            self.handle_preview_action(action);
+            self.sync_fullscreen(ctx, was_fullscreen);
+            return;
+        }
+
         // Top panel with title/menu bar could go here if needed
 
         // Bottom panel with Pack/Export buttons and status
@@ -1173,6 +2571,12 @@ impl eframe::App for BentoApp {
                     self.new_project();
                 }
 
+                if let Some(path) = action.open_recent
+                    && self.check_unsaved_changes(PendingAction::OpenConfig(path.clone()))
+                {
+                    self.load_config_file(&path);
+                }
+
                 if action.save_config {
                     if let Err(e) = self.save_current_config() {
                         self.state.runtime.status = Status::Done {
@@ -1206,16 +2610,73 @@ impl eframe::App for BentoApp {
             .min_width(200.0)
             .show(ctx, |ui| {
                 egui::ScrollArea::vertical().show(ui, |ui| {
+                    panels::warnings_panel(ui, &self.state);
+                    panels::inspector_panel(ui, &mut self.state);
                     panels::settings_panel(ui, &mut self.state);
+                    panels::stats_panel(ui, &self.state);
                 });
             });
 
         // Central panel with preview
-        egui::CentralPanel::default().show(ctx, |ui| {
-            panels::preview_panel(ui, &mut self.state);
-        });
+        let action = egui::CentralPanel::default()
+            .show(ctx, |ui| panels::preview_panel(ui, &mut self.state))
+            .inner;
+        self.handle_preview_action(action);
+        self.sync_fullscreen(ctx, was_fullscreen);
 
         // Render drag-drop overlay on top of everything
         self.render_drop_overlay(ctx);
     }
 }
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::*;
+
+    #[test]
+    fn test_export_conflicts_cover_companions() {
+        let dir = std::env::temp_dir().join("bento_gui_export_conflicts");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let atlases = AtlasBuilder::new(64, 64)
+            .build(vec![SourceSprite::from_image(
+                "a.png",
+                image::RgbaImage::new(8, 8),
+            )])
+            .unwrap();
+        let config = AppConfig {
+            output_dir: dir.clone(),
+            name: "sheet".to_string(),
+            formats: BTreeSet::from([OutputFormat::Json]),
+            companions: vec!["_n".to_string()],
+            ..AppConfig::default()
+        };
+
+        // A previous export of two pages, with a normal map for each
+        for file in [
+            "sheet_0.png",
+            "sheet_0_n.png",
+            "sheet_1.png",
+            "sheet_1_n.png",
+            "sheet.png",
+            "sheet_n.png",
+            "notes.png",
+        ] {
+            std::fs::write(dir.join(file), b"").unwrap();
+        }
+        let (overwritten, stale) = find_export_conflicts(&atlases, &config);
+        assert_eq!(overwritten, ["sheet.png", "sheet_n.png"]);
+        assert_eq!(
+            stale,
+            [
+                "sheet_0.png",
+                "sheet_0_n.png",
+                "sheet_1.png",
+                "sheet_1_n.png"
+            ]
+        );
+    }
+}
@@ -62,6 +62,90 @@ impl UnsavedChangesDialog {
     }
 }
 
+/// User's choice when an export would replace existing files
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportConflictChoice {
+    /// Replace the existing files
+    Overwrite,
+    /// Export into a new timestamped subfolder instead
+    Subfolder,
+    /// Cancel the export
+    Cancel,
+}
+
+/// Dialog listing files an export would replace or leave stale
+pub struct ExportConflictDialog {
+    /// Existing files the export will overwrite
+    pub overwritten: Vec<String>,
+    /// Files from a previous export that this export will not replace
+    pub stale: Vec<String>,
+    /// Name of the subfolder offered as an alternative
+    pub subfolder: String,
+}
+
+impl ExportConflictDialog {
+    pub fn new(overwritten: Vec<String>, stale: Vec<String>, subfolder: String) -> Self {
+        Self {
+            overwritten,
+            stale,
+            subfolder,
+        }
+    }
+
+    /// Show the dialog, returns Some(choice) when user makes a selection
+    pub fn show(&mut self, ctx: &egui::Context) -> Option<ExportConflictChoice> {
+        let mut result = None;
+
+        egui::Window::new("Overwrite Existing Files?")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                egui::ScrollArea::vertical()
+                    .max_height(240.0)
+                    .show(ui, |ui| {
+                        if !self.overwritten.is_empty() {
+                            ui.label(format!(
+                                "{} file(s) will be overwritten:",
+                                self.overwritten.len()
+                            ));
+                            for name in &self.overwritten {
+                                ui.monospace(name);
+                            }
+                        }
+                        if !self.stale.is_empty() {
+                            ui.add_space(8.0);
+                            ui.label(format!(
+                                "{} file(s) from a previous export will be left stale:",
+                                self.stale.len()
+                            ));
+                            for name in &self.stale {
+                                ui.monospace(name);
+                            }
+                        }
+                    });
+
+                ui.add_space(12.0);
+                ui.horizontal(|ui| {
+                    if ui.button("Cancel").clicked() {
+                        result = Some(ExportConflictChoice::Cancel);
+                    }
+                    if ui
+                        .button(format!("Export to {}/", self.subfolder))
+                        .clicked()
+                    {
+                        result = Some(ExportConflictChoice::Subfolder);
+                    }
+                    if ui.button("Overwrite").clicked() {
+                        result = Some(ExportConflictChoice::Overwrite);
+                    }
+                });
+            });
+
+        result
+    }
+}
+
 /// State for the config file chooser dialog
 pub struct ConfigChooserDialog {
     pub bento_files: Vec<PathBuf>,
@@ -117,13 +201,16 @@ impl ConfigChooserDialog {
     }
 }
 
-/// Find all .bento files in a directory
+/// Find all .bento files and any bento.toml in a directory
 pub fn find_bento_files(dir: &std::path::Path) -> Vec<PathBuf> {
     let mut files = Vec::new();
     if let Ok(entries) = std::fs::read_dir(dir) {
         for entry in entries.flatten() {
             let path = entry.path();
-            if path.is_file() && path.extension().is_some_and(|e| e == "bento") {
+            if path.is_file()
+                && (path.extension().is_some_and(|e| e == "bento")
+                    || path.file_name().is_some_and(|n| n == "bento.toml"))
+            {
                 files.push(path);
             }
         }
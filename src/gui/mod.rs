@@ -2,6 +2,7 @@ mod app;
 mod dialogs;
 mod panels;
 pub mod state;
+mod system;
 mod thumbnail;
 
 use anyhow::Result;
@@ -9,7 +10,9 @@ use eframe::egui;
 
 /// Check if a path has a supported image extension
 pub(crate) fn is_supported_image(path: &std::path::Path) -> bool {
-    const SUPPORTED_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "bmp", "webp"];
+    const SUPPORTED_EXTENSIONS: &[&str] = &[
+        "png", "jpg", "jpeg", "gif", "bmp", "webp", "ase", "aseprite",
+    ];
 
     path.extension()
         .and_then(|ext| ext.to_str())
@@ -22,11 +25,13 @@ pub fn run(initial_path: Option<std::path::PathBuf>) -> Result<()> {
             .with_inner_size([1200.0, 800.0])
             .with_min_inner_size([800.0, 600.0])
             .with_drag_and_drop(true),
+        // Restore window size and position from the previous session
+        persist_window: true,
         ..Default::default()
     };
 
     eframe::run_native(
-        "Bento",
+        app::APP_ID,
         options,
         Box::new(move |cc| Ok(Box::new(app::BentoApp::new(cc, initial_path)))),
     )
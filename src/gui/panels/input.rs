@@ -1,6 +1,11 @@
+use std::path::PathBuf;
+
 use eframe::egui;
 
-use crate::gui::state::{AppState, OutputFormat, ThumbnailState};
+use super::copy_sprite_menu;
+use crate::cli::OutputFormat;
+use crate::gui::state::{AppState, ThumbnailState};
+use crate::gui::system::{open_in_default_app, reveal_in_file_manager};
 use crate::gui::thumbnail::THUMBNAIL_SIZE;
 
 /// Actions requested by the input panel
@@ -8,6 +13,8 @@ use crate::gui::thumbnail::THUMBNAIL_SIZE;
 pub struct InputPanelAction {
     pub new_project: bool,
     pub save_config: bool,
+    /// Recent project config to open
+    pub open_recent: Option<PathBuf>,
     // Dialog requests (run in background threads)
     pub request_open_config_dialog: bool,
     pub request_save_as_dialog: bool,
@@ -16,6 +23,16 @@ pub struct InputPanelAction {
     pub request_output_folder_dialog: bool,
 }
 
+/// Row action from the context menu or rename editor, applied after the list is drawn
+enum RowAction {
+    Remove(usize),
+    ToggleExclude(PathBuf),
+    TogglePackFirst(String),
+    StartRename(PathBuf),
+    CommitRename(PathBuf, String),
+    CancelRename,
+}
+
 /// Input panel with file list, output path, and format selection
 pub fn input_panel(ui: &mut egui::Ui, state: &mut AppState) -> InputPanelAction {
     let mut action = InputPanelAction::default();
@@ -30,6 +47,8 @@ pub fn input_panel(ui: &mut egui::Ui, state: &mut AppState) -> InputPanelAction
             action.request_open_config_dialog = true;
         }
 
+        recent_projects_menu(ui, state, &mut action);
+
         // Save button - enabled only if we have a config path
         let can_save = state.runtime.config_path.is_some();
         if ui
@@ -88,6 +107,8 @@ pub fn input_panel(ui: &mut egui::Ui, state: &mut AppState) -> InputPanelAction
         ui.horizontal(|ui| {
             if ui.button("Clear All").clicked() {
                 state.config.input_paths.clear();
+                state.config.excluded.clear();
+                state.config.sprite_names.clear();
                 state.runtime.selected_sprites.clear();
                 state.runtime.selection_anchor = None;
             }
@@ -164,9 +185,22 @@ pub fn input_panel(ui: &mut egui::Ui, state: &mut AppState) -> InputPanelAction
             let delete_pressed = ui
                 .input(|i| i.key_pressed(egui::Key::Delete) || i.key_pressed(egui::Key::Backspace));
 
+            // Ctrl+A selects every listed sprite
+            if !ui.ctx().wants_keyboard_input()
+                && ui.input_mut(|i| i.consume_key(egui::Modifiers::COMMAND, egui::Key::A))
+            {
+                state.runtime.selected_sprites = filtered.iter().map(|(i, _)| *i).collect();
+                state.runtime.selection_anchor = filtered.first().map(|(i, _)| *i);
+            }
+
             let mut remove_selected = false;
+            let mut row_action = None;
 
-            if delete_pressed && !state.runtime.selected_sprites.is_empty() {
+            // Don't treat Backspace in the rename editor as a removal
+            if delete_pressed
+                && state.runtime.renaming.is_none()
+                && !state.runtime.selected_sprites.is_empty()
+            {
                 remove_selected = true;
             }
 
@@ -238,7 +272,35 @@ pub fn input_panel(ui: &mut egui::Ui, state: &mut AppState) -> InputPanelAction
                             .map(|n| n.to_string_lossy().to_string())
                             .unwrap_or_else(|| path.display().to_string());
 
-                        ui.label(filename);
+                        match &mut state.runtime.renaming {
+                            Some((renaming_path, pending)) if renaming_path == *path => {
+                                let edit = ui.add(
+                                    egui::TextEdit::singleline(pending)
+                                        .hint_text(filename.as_str())
+                                        .desired_width(ui.available_width() - 8.0),
+                                );
+                                edit.request_focus();
+                                if ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+                                    row_action = Some(RowAction::CancelRename);
+                                } else if edit.lost_focus() {
+                                    row_action = Some(RowAction::CommitRename(
+                                        (*path).clone(),
+                                        pending.trim().to_string(),
+                                    ));
+                                }
+                            }
+                            _ => {
+                                let label = match state.config.sprite_names.get(*path) {
+                                    Some(name) => format!("{} \u{2192} {}", filename, name),
+                                    None => filename,
+                                };
+                                let mut text = egui::RichText::new(label);
+                                if state.config.excluded.contains(*path) {
+                                    text = text.weak().strikethrough();
+                                }
+                                ui.label(text);
+                            }
+                        }
                     })
                 });
 
@@ -254,17 +316,100 @@ pub fn input_panel(ui: &mut egui::Ui, state: &mut AppState) -> InputPanelAction
                         *original_idx,
                         modifiers,
                     );
+
+                    // A plain click also selects the packed sprite in the preview
+                    if !modifiers.shift && !modifiers.command {
+                        let packed = state.runtime.atlases.as_ref().and_then(|atlases| {
+                            atlases.iter().enumerate().find_map(|(index, atlas)| {
+                                atlas
+                                    .sprites
+                                    .iter()
+                                    .find(|sprite| sprite.path == **path)
+                                    .map(|sprite| (index, sprite.name.clone()))
+                            })
+                        });
+                        if let Some((atlas_index, name)) = packed {
+                            if state.runtime.selected_atlas != atlas_index {
+                                state.runtime.selected_atlas = atlas_index;
+                                state.runtime.needs_fit_to_view = true;
+                            }
+                            state.runtime.selected_sprite = Some(name);
+                        }
+                    }
                 }
+
+                // Bring a row selected from the preview into view
+                if is_selected && state.runtime.scroll_to_selection {
+                    row_interact.scroll_to_me(Some(egui::Align::Center));
+                }
+
+                row_interact.context_menu(|ui| {
+                    if ui.button("Reveal in File Manager").clicked() {
+                        if let Err(e) = reveal_in_file_manager(path) {
+                            log::warn!("Failed to reveal {}: {}", path.display(), e);
+                        }
+                        ui.close_menu();
+                    }
+                    if ui.button("Open in Default Editor").clicked() {
+                        if let Err(e) = open_in_default_app(path) {
+                            log::warn!("Failed to open {}: {}", path.display(), e);
+                        }
+                        ui.close_menu();
+                    }
+                    ui.separator();
+                    let packed = state.runtime.atlases.as_ref().and_then(|atlases| {
+                        atlases
+                            .iter()
+                            .flat_map(|atlas| &atlas.sprites)
+                            .find(|sprite| sprite.path == **path)
+                    });
+                    if let Some(sprite) = packed {
+                        copy_sprite_menu(ui, sprite, state.config.godot_version);
+                        ui.separator();
+                    }
+                    if ui.button("Rename Sprite...").clicked() {
+                        row_action = Some(RowAction::StartRename((*path).clone()));
+                        ui.close_menu();
+                    }
+                    let exclude_label = if state.config.excluded.contains(*path) {
+                        "Include in Packing"
+                    } else {
+                        "Exclude from Packing"
+                    };
+                    if ui.button(exclude_label).clicked() {
+                        row_action = Some(RowAction::ToggleExclude((*path).clone()));
+                        ui.close_menu();
+                    }
+                    let name = sprite_name(state, path);
+                    let pack_first_label = if state.config.pack_order.contains(&name) {
+                        "Remove from Pack Order"
+                    } else {
+                        "Pack First"
+                    };
+                    if ui.button(pack_first_label).clicked() {
+                        row_action = Some(RowAction::TogglePackFirst(name));
+                        ui.close_menu();
+                    }
+                    if ui.button("Remove").clicked() {
+                        row_action = Some(RowAction::Remove(*original_idx));
+                        ui.close_menu();
+                    }
+                });
             }
 
             // Drop the filtered borrow before modifying state
             drop(filtered);
+            state.runtime.scroll_to_selection = false;
 
             // Handle removal of selected items
             if remove_selected {
                 remove_selected_sprites(state);
             }
 
+            if let Some(action) = row_action {
+                apply_row_action(state, action);
+            }
+
             // Empty state
             if state.config.input_paths.is_empty() {
                 ui.add_space(20.0);
@@ -310,17 +455,68 @@ pub fn input_panel(ui: &mut egui::Ui, state: &mut AppState) -> InputPanelAction
 
     ui.add_space(4.0);
 
-    // Format radio buttons
-    ui.horizontal(|ui| {
-        ui.label("Format:");
-        ui.radio_value(&mut state.config.format, OutputFormat::Json, "JSON");
-        ui.radio_value(&mut state.config.format, OutputFormat::Godot, "Godot");
-        ui.radio_value(&mut state.config.format, OutputFormat::Tpsheet, "tpsheet");
+    // Format checkboxes (wrapping, as there are more than fit in a narrow panel).
+    // Each export writes every checked format from the same pack.
+    ui.horizontal_wrapped(|ui| {
+        ui.label("Formats:");
+        for (format, label) in [
+            (OutputFormat::Json, "JSON"),
+            (OutputFormat::Godot, "Godot"),
+            (OutputFormat::Tpsheet, "tpsheet"),
+            (OutputFormat::Libgdx, "libGDX"),
+            (OutputFormat::Phaser, "Phaser"),
+            (OutputFormat::Cocos, "Cocos"),
+            (OutputFormat::Template, "Template"),
+        ] {
+            let mut checked = state.config.formats.contains(&format);
+            // The last checked format stays on, so exports always write metadata
+            let only = checked && state.config.formats.len() == 1;
+            if ui
+                .add_enabled(!only, egui::Checkbox::new(&mut checked, label))
+                .changed()
+            {
+                if checked {
+                    state.config.formats.insert(format);
+                } else {
+                    state.config.formats.remove(&format);
+                }
+            }
+        }
     });
 
     action
 }
 
+/// Menu of recently opened project configs
+fn recent_projects_menu(ui: &mut egui::Ui, state: &mut AppState, action: &mut InputPanelAction) {
+    let has_recent = !state.runtime.recent_projects.is_empty();
+    ui.add_enabled_ui(has_recent, |ui| {
+        ui.menu_button("Recent", |ui| {
+            for path in &state.runtime.recent_projects {
+                let name = path
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_else(|| path.display().to_string());
+                let response = ui
+                    .add_enabled(path.is_file(), egui::Button::new(name))
+                    .on_hover_text(path.display().to_string())
+                    .on_disabled_hover_text(format!("{} (not found)", path.display()));
+                if response.clicked() {
+                    action.open_recent = Some(path.clone());
+                    ui.close_menu();
+                }
+            }
+            ui.separator();
+            if ui.button("Clear Recent").clicked() {
+                state.runtime.recent_projects.clear();
+                ui.close_menu();
+            }
+        });
+    })
+    .response
+    .on_disabled_hover_text("No recent projects");
+}
+
 /// Handle click on a sprite row, updating selection based on modifiers
 fn handle_sprite_click(
     selected: &mut std::collections::HashSet<usize>,
@@ -370,6 +566,71 @@ fn remove_selected_sprites(state: &mut AppState) {
 
     state.runtime.selected_sprites.clear();
     state.runtime.selection_anchor = None;
+    prune_path_settings(state);
+}
+
+/// Apply a deferred row action
+fn apply_row_action(state: &mut AppState, action: RowAction) {
+    match action {
+        RowAction::Remove(index) => {
+            // Removing a selected row removes the whole selection
+            if !state.runtime.selected_sprites.contains(&index) {
+                state.runtime.selected_sprites.clear();
+                state.runtime.selected_sprites.insert(index);
+            }
+            remove_selected_sprites(state);
+        }
+        RowAction::ToggleExclude(path) => {
+            if !state.config.excluded.remove(&path) {
+                state.config.excluded.insert(path);
+            }
+        }
+        RowAction::TogglePackFirst(name) => {
+            let order = &mut state.config.pack_order;
+            if let Some(i) = order.iter().position(|p| *p == name) {
+                order.remove(i);
+            } else {
+                order.push(name);
+            }
+        }
+        RowAction::StartRename(path) => {
+            let current = state
+                .config
+                .sprite_names
+                .get(&path)
+                .cloned()
+                .unwrap_or_default();
+            state.runtime.renaming = Some((path, current));
+        }
+        RowAction::CommitRename(path, name) => {
+            // An empty name restores the default (file-derived) sprite name
+            if name.is_empty() {
+                state.config.sprite_names.remove(&path);
+            } else {
+                state.config.sprite_names.insert(path, name);
+            }
+            state.runtime.renaming = None;
+        }
+        RowAction::CancelRename => state.runtime.renaming = None,
+    }
+}
+
+/// Sprite name the packer will use for an input file
+pub(super) fn sprite_name(state: &AppState, path: &std::path::Path) -> String {
+    state
+        .config
+        .sprite_names
+        .get(path)
+        .cloned()
+        .or_else(|| path.file_name().map(|n| n.to_string_lossy().to_string()))
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+/// Drop exclusions and name overrides for paths no longer in the input list
+fn prune_path_settings(state: &mut AppState) {
+    let paths: std::collections::HashSet<&PathBuf> = state.config.input_paths.iter().collect();
+    state.config.excluded.retain(|p| paths.contains(p));
+    state.config.sprite_names.retain(|p, _| paths.contains(p));
 }
 
 /// Center a smaller rect within a larger rect
@@ -0,0 +1,162 @@
+use eframe::egui;
+
+use super::input::sprite_name;
+use crate::cli::ExtrudeMode;
+use crate::gui::state::AppState;
+use crate::sprite::{Pivot, SpriteOverride};
+
+/// Inspector for the overrides of the single selected input sprite
+pub fn inspector_panel(ui: &mut egui::Ui, state: &mut AppState) {
+    egui::CollapsingHeader::new("Inspector")
+        .default_open(true)
+        .show(ui, |ui| {
+            let selected = match state.runtime.selected_sprites.len() {
+                1 => state
+                    .runtime
+                    .selected_sprites
+                    .iter()
+                    .next()
+                    .and_then(|&i| state.config.input_paths.get(i))
+                    .cloned(),
+                _ => None,
+            };
+            let Some(path) = selected else {
+                ui.label("Select one input sprite to edit its overrides");
+                return;
+            };
+
+            let name = sprite_name(state, &path);
+            ui.label(egui::RichText::new(&name).strong())
+                .on_hover_text(path.display().to_string());
+
+            let mut excluded = state.config.excluded.contains(&path);
+            if ui.checkbox(&mut excluded, "Exclude from packing").changed() {
+                if excluded {
+                    state.config.excluded.insert(path.clone());
+                } else {
+                    state.config.excluded.remove(&path);
+                }
+            }
+
+            let mut sprite = state
+                .config
+                .overrides
+                .get(&name)
+                .copied()
+                .unwrap_or_default();
+
+            egui::Grid::new("sprite_overrides")
+                .num_columns(2)
+                .show(ui, |ui| {
+                    override_row(
+                        ui,
+                        "Trim",
+                        &mut sprite.trim,
+                        state.config.trim,
+                        |ui, trim| {
+                            ui.checkbox(trim, "");
+                        },
+                    );
+                    override_row(
+                        ui,
+                        "Padding",
+                        &mut sprite.padding,
+                        state.config.padding_x.max(state.config.padding_y),
+                        |ui, padding| {
+                            ui.add(egui::DragValue::new(padding).range(0..=32).speed(1));
+                        },
+                    );
+                    override_row(
+                        ui,
+                        "Extrude",
+                        &mut sprite.extrude,
+                        state.config.extrude,
+                        |ui, extrude| {
+                            ui.add(egui::DragValue::new(extrude).range(0..=8).speed(1));
+                        },
+                    );
+                    override_row(
+                        ui,
+                        "Extrude Mode",
+                        &mut sprite.extrude_mode,
+                        state.config.extrude_mode,
+                        |ui, mode| {
+                            ui.selectable_value(mode, ExtrudeMode::Clamp, "Clamp");
+                            ui.selectable_value(mode, ExtrudeMode::Wrap, "Wrap");
+                        },
+                    );
+                    override_row(
+                        ui,
+                        "Pivot",
+                        &mut sprite.pivot,
+                        Pivot { x: 0.5, y: 0.5 },
+                        |ui, pivot| {
+                            for value in [&mut pivot.x, &mut pivot.y] {
+                                ui.add(
+                                    egui::DragValue::new(value)
+                                        .range(0.0..=1.0)
+                                        .speed(0.01)
+                                        .fixed_decimals(2),
+                                );
+                            }
+                        },
+                    );
+                    override_row(ui, "Scale", &mut sprite.scale, 1.0, |ui, scale| {
+                        ui.add(
+                            egui::DragValue::new(scale)
+                                .range(0.01..=4.0)
+                                .speed(0.01)
+                                .fixed_decimals(2),
+                        );
+                    });
+                    override_row(ui, "Priority", &mut sprite.priority, 0, |ui, priority| {
+                        ui.add(egui::DragValue::new(priority).range(-100..=100).speed(1))
+                            .on_hover_text("Higher priorities are packed onto earlier pages");
+                    });
+                });
+
+            // An override with nothing set is dropped rather than saved empty
+            if sprite == SpriteOverride::default() {
+                state.config.overrides.remove(&name);
+            } else {
+                state.config.overrides.insert(name.clone(), sprite);
+            }
+
+            let patterns: Vec<&str> = state
+                .config
+                .overrides
+                .keys()
+                .filter(|key| **key != name)
+                .filter(|key| glob::Pattern::new(key).is_ok_and(|p| p.matches(&name)))
+                .map(String::as_str)
+                .collect();
+            if !patterns.is_empty() {
+                ui.label(
+                    egui::RichText::new(format!("Also matched by: {}", patterns.join(", "))).weak(),
+                )
+                .on_hover_text("Fields left unset here fall back to these patterns");
+            }
+        });
+}
+
+/// Grid row with a checkbox that turns an override field on, seeded with
+/// `default`, and an editor for its value while set
+fn override_row<T: Copy>(
+    ui: &mut egui::Ui,
+    label: &str,
+    field: &mut Option<T>,
+    default: T,
+    editor: impl FnOnce(&mut egui::Ui, &mut T),
+) {
+    let mut enabled = field.is_some();
+    if ui.checkbox(&mut enabled, label).changed() {
+        *field = enabled.then_some(default);
+    }
+    ui.horizontal(|ui| match field {
+        Some(value) => editor(ui, value),
+        None => {
+            ui.weak("global");
+        }
+    });
+    ui.end_row();
+}
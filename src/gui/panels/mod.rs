@@ -1,14 +1,23 @@
 mod input;
+mod inspector;
 mod preview;
 mod settings;
+mod stats;
+mod warnings;
 
 pub use input::input_panel;
-pub use preview::preview_panel;
+pub use inspector::inspector_panel;
+pub use preview::{PreviewAction, format_file_size, preview_panel};
 pub use settings::settings_panel;
+pub use stats::stats_panel;
+pub use warnings::warnings_panel;
 
 use eframe::egui;
 
 use super::state::{AppState, Operation, Status, StatusResult};
+use crate::cli::GodotVersion;
+use crate::output::{godot_region, json_frame};
+use crate::sprite::PackedSprite;
 
 /// Action requested by the bottom bar
 #[derive(Default)]
@@ -94,7 +103,7 @@ pub fn bottom_bar(ui: &mut egui::Ui, state: &mut AppState) -> BottomBarAction {
             ui.label(status_text);
         }
 
-        // Export button on the right
+        // Export button and preset picker on the right
         ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
             let can_export = !is_busy && state.runtime.atlases.is_some();
             if ui
@@ -103,8 +112,95 @@ pub fn bottom_bar(ui: &mut egui::Ui, state: &mut AppState) -> BottomBarAction {
             {
                 action.export_requested = true;
             }
+
+            export_preset_menu(ui, state);
         });
     });
 
     action
 }
+
+/// Picker for export presets: named sets of format, compression, opacity,
+/// output folder and name, saved as build profiles in the project config
+fn export_preset_menu(ui: &mut egui::Ui, state: &mut AppState) {
+    let config_dir = state.runtime.config_dir();
+    let current = state.config.export_preset(&config_dir);
+    let active = state
+        .config
+        .export_preset_names()
+        .into_iter()
+        .find(|name| {
+            state.config.profiles[*name]
+                .iter()
+                .all(|(key, value)| current.get(key) == Some(value))
+        })
+        .map(str::to_string);
+
+    let mut applied = None;
+    let mut deleted = false;
+    egui::ComboBox::from_id_salt("export_preset")
+        .selected_text(active.as_deref().unwrap_or("Custom"))
+        .width(110.0)
+        .show_ui(ui, |ui| {
+            for name in state.config.export_preset_names() {
+                if ui
+                    .selectable_label(active.as_deref() == Some(name), name)
+                    .clicked()
+                {
+                    applied = Some(name.to_string());
+                }
+            }
+
+            ui.separator();
+            ui.horizontal(|ui| {
+                let edit = ui.add(
+                    egui::TextEdit::singleline(&mut state.runtime.preset_name_input)
+                        .hint_text("preset name")
+                        .desired_width(100.0),
+                );
+                let submitted = edit.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                let name = state.runtime.preset_name_input.trim().to_string();
+                if (ui.button("Save").clicked() || submitted) && !name.is_empty() {
+                    state.config.profiles.insert(name, current.clone());
+                    state.runtime.preset_name_input.clear();
+                }
+            });
+            if let Some(name) = &active
+                && ui.button(format!("Delete \"{}\"", name)).clicked()
+            {
+                deleted = true;
+            }
+        })
+        .response
+        .on_hover_text("Export preset: format, compression, opacity, output folder and name");
+
+    if deleted && let Some(name) = &active {
+        state.config.profiles.remove(name);
+    }
+    if let Some(name) = applied {
+        let preset = state.config.profiles[&name].clone();
+        if let Err(e) = state.config.apply_export_preset(&preset, &config_dir) {
+            state.runtime.status = Status::Done {
+                result: StatusResult::Error(format!("Preset '{}': {}", name, e)),
+                at: std::time::Instant::now(),
+            };
+        }
+    }
+}
+
+/// Context menu entries that copy a packed sprite's name, region or JSON
+/// frame to the clipboard, for pasting into engine code
+fn copy_sprite_menu(ui: &mut egui::Ui, sprite: &PackedSprite, godot_version: GodotVersion) {
+    if ui.button("Copy Name").clicked() {
+        ui.ctx().copy_text(sprite.name.clone());
+        ui.close_menu();
+    }
+    if ui.button("Copy Region as Rect2").clicked() {
+        ui.ctx().copy_text(godot_region(sprite, godot_version));
+        ui.close_menu();
+    }
+    if ui.button("Copy JSON Frame").clicked() {
+        ui.ctx().copy_text(json_frame(sprite));
+        ui.close_menu();
+    }
+}
@@ -1,10 +1,25 @@
+use std::collections::HashMap;
+
 use eframe::egui;
 
-use crate::atlas::Atlas;
-use crate::gui::state::AppState;
+use super::copy_sprite_menu;
+use crate::atlas::{Atlas, SpriteSettings};
+use crate::gui::state::{AppState, ChannelView, PackProgress};
+use crate::sprite::PackedSprite;
+
+/// Minimum zoom at which the pixel grid overlay is drawn
+const PIXEL_GRID_MIN_ZOOM: f32 = 8.0;
+
+/// Action requested by the preview panel
+#[derive(Default)]
+pub struct PreviewAction {
+    /// Sprite to save as a PNG, and whether to restore its trimmed borders
+    pub export_sprite: Option<(String, bool)>,
+}
 
 /// Preview panel showing the packed atlas with zoom/pan support
-pub fn preview_panel(ui: &mut egui::Ui, state: &mut AppState) {
+pub fn preview_panel(ui: &mut egui::Ui, state: &mut AppState) -> PreviewAction {
+    let mut action = PreviewAction::default();
     ui.heading("Preview");
 
     ui.add_space(4.0);
@@ -15,11 +30,17 @@ pub fn preview_panel(ui: &mut egui::Ui, state: &mut AppState) {
     // Check if we have atlases to show
     let Some(atlases) = state.runtime.atlases.as_ref().filter(|a| !a.is_empty()) else {
         if is_packing {
-            show_packing_state(ui);
+            let progress = state
+                .runtime
+                .pack_progress
+                .lock()
+                .map(|progress| *progress)
+                .unwrap_or_default();
+            show_packing_state(ui, progress);
         } else {
             show_empty_state(ui);
         }
-        return;
+        return action;
     };
 
     // Tab bar for multiple atlases
@@ -63,6 +84,20 @@ pub fn preview_panel(ui: &mut egui::Ui, state: &mut AppState) {
         ));
 
         ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            // Full-screen toggle (also F11)
+            let fullscreen_label = if state.runtime.preview_fullscreen {
+                "Exit Full Screen"
+            } else {
+                "Full Screen"
+            };
+            if ui
+                .small_button(fullscreen_label)
+                .on_hover_text("Toggle with F11, leave with Escape")
+                .clicked()
+            {
+                state.runtime.preview_fullscreen = !state.runtime.preview_fullscreen;
+            }
+
             // Reset view button (fits atlas to view)
             if ui.small_button("Reset View").clicked() {
                 state.runtime.needs_fit_to_view = true;
@@ -71,6 +106,37 @@ pub fn preview_panel(ui: &mut egui::Ui, state: &mut AppState) {
             // Debug overlay toggle
             ui.checkbox(&mut state.runtime.show_debug_overlay, "Debug");
 
+            // Free space heatmap toggle
+            let page_area = u64::from(atlas.width) * u64::from(atlas.height);
+            #[expect(
+                clippy::cast_precision_loss,
+                reason = "approximation acceptable for a percentage"
+            )]
+            let free_percent = atlas.free_area() as f64 / page_area.max(1) as f64 * 100.0;
+            ui.checkbox(&mut state.runtime.show_free_space, "Free")
+                .on_hover_text(format!(
+                    "Shade space left unused on this page ({:.1}% of it). \
+                     Free rectangles overlap, so the largest open areas show darkest",
+                    free_percent
+                ));
+
+            // Channel view selector
+            egui::ComboBox::from_id_salt("channel_view")
+                .selected_text(state.runtime.channel_view.label())
+                .width(70.0)
+                .show_ui(ui, |ui| {
+                    for view in ChannelView::ALL {
+                        ui.selectable_value(&mut state.runtime.channel_view, view, view.label());
+                    }
+                });
+
+            // Pixel grid toggle (only drawn at high zoom)
+            ui.checkbox(&mut state.runtime.show_pixel_grid, "Grid")
+                .on_hover_text(format!(
+                    "Show texel grid at {}x zoom and above",
+                    PIXEL_GRID_MIN_ZOOM
+                ));
+
             // Zoom display
             ui.label(format!("{:.0}%", state.runtime.preview_zoom * 100.0));
         });
@@ -81,10 +147,48 @@ pub fn preview_panel(ui: &mut egui::Ui, state: &mut AppState) {
     // Get texture for selected atlas
     if selected >= state.runtime.atlas_textures.len() {
         show_empty_state(ui);
-        return;
+        return action;
     }
 
-    let texture = &state.runtime.atlas_textures[selected];
+    // Build (or reuse) the texture for the selected channel view
+    let view = state.runtime.channel_view;
+    let texture_id = if view == ChannelView::All {
+        state.runtime.atlas_textures[selected].id()
+    } else {
+        let stale = state
+            .runtime
+            .channel_texture
+            .as_ref()
+            .is_none_or(|(i, v, _)| *i != selected || *v != view);
+        if stale {
+            let texture = ui.ctx().load_texture(
+                "atlas_channel_view",
+                channel_image(&atlas.image, view),
+                egui::TextureOptions::NEAREST,
+            );
+            state.runtime.channel_texture = Some((selected, view, texture));
+        }
+        match &state.runtime.channel_texture {
+            Some((_, _, texture)) => texture.id(),
+            None => state.runtime.atlas_textures[selected].id(),
+        }
+    };
+
+    // Detail sidebar for the sprite selected by clicking in the preview
+    if let Some(sprite) = state
+        .runtime
+        .selected_sprite
+        .as_deref()
+        .and_then(|name| atlas.find(name))
+    {
+        let mut deselect = false;
+        egui::SidePanel::right("sprite_details")
+            .default_width(220.0)
+            .show_inside(ui, |ui| deselect = sprite_details(ui, sprite, selected));
+        if deselect {
+            state.runtime.selected_sprite = None;
+        }
+    }
 
     // Preview area with zoom/pan
     let available = ui.available_size();
@@ -98,8 +202,9 @@ pub fn preview_panel(ui: &mut egui::Ui, state: &mut AppState) {
         state.runtime.needs_fit_to_view = false;
     }
 
-    // Draw background - solid black when opaque, checkerboard otherwise
-    if state.config.opaque {
+    // Draw background - solid black when opaque or viewing single channels,
+    // checkerboard otherwise
+    if state.config.opaque || view != ChannelView::All {
         painter.rect_filled(rect, 0.0, egui::Color32::BLACK);
     } else {
         draw_checkerboard(&painter, rect);
@@ -137,7 +242,7 @@ pub fn preview_panel(ui: &mut egui::Ui, state: &mut AppState) {
 
     // Draw the atlas texture
     painter.image(
-        texture.id(),
+        texture_id,
         img_rect,
         egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
         egui::Color32::WHITE,
@@ -150,6 +255,10 @@ pub fn preview_panel(ui: &mut egui::Ui, state: &mut AppState) {
         egui::Stroke::new(1.0, egui::Color32::from_gray(120)),
     );
 
+    if state.runtime.show_free_space {
+        draw_free_space(&painter, atlas, img_rect, zoom);
+    }
+
     // Draw debug overlay if enabled
     if state.runtime.show_debug_overlay {
         draw_debug_overlay(
@@ -157,52 +266,248 @@ pub fn preview_panel(ui: &mut egui::Ui, state: &mut AppState) {
             atlas,
             img_rect,
             zoom,
-            state.config.padding,
+            (state.config.padding_x, state.config.padding_y),
             state.config.extrude,
+            &state.runtime.sprite_settings,
         );
     }
 
+    // Highlight the selected sprite
+    if let Some(sprite) = state
+        .runtime
+        .selected_sprite
+        .as_deref()
+        .and_then(|name| atlas.find(name))
+    {
+        let sprite_rect = egui::Rect::from_min_size(
+            img_rect.min + egui::vec2(sprite.x as f32, sprite.y as f32) * zoom,
+            egui::vec2(sprite.width as f32, sprite.height as f32) * zoom,
+        );
+        let color = ui.visuals().selection.stroke.color;
+        painter.rect_filled(sprite_rect, 0.0, color.gamma_multiply(0.2));
+        painter.rect_stroke(sprite_rect, 0.0, egui::Stroke::new(2.0, color));
+    }
+
+    // Draw pixel grid and hovered texel coordinates at high zoom
+    if state.runtime.show_pixel_grid && zoom >= PIXEL_GRID_MIN_ZOOM {
+        draw_pixel_grid(&painter, img_rect, rect, zoom);
+
+        if let Some(pointer_pos) = ui.input(|i| i.pointer.hover_pos())
+            && img_rect.contains(pointer_pos)
+        {
+            #[expect(
+                clippy::cast_possible_truncation,
+                clippy::cast_sign_loss,
+                reason = "pointer is inside the atlas rect, so coordinates are non-negative"
+            )]
+            let (texel_x, texel_y) = (
+                ((pointer_pos.x - img_rect.left()) / zoom) as u32,
+                ((pointer_pos.y - img_rect.top()) / zoom) as u32,
+            );
+            draw_texel_label(&painter, pointer_pos, texel_x, texel_y);
+        }
+    }
+
     // Sprite hover tooltip
+    let mut hovered_sprite = None;
     if let Some(pointer_pos) = ui.input(|i| i.pointer.hover_pos())
         && img_rect.contains(pointer_pos)
     {
         // Convert screen position to atlas coordinates
-        let atlas_x = (pointer_pos.x - img_rect.left()) / zoom;
-        let atlas_y = (pointer_pos.y - img_rect.top()) / zoom;
+        #[expect(
+            clippy::cast_possible_truncation,
+            clippy::cast_sign_loss,
+            reason = "pointer is inside the atlas rect, so coordinates are non-negative"
+        )]
+        let (atlas_x, atlas_y) = (
+            ((pointer_pos.x - img_rect.left()) / zoom) as u32,
+            ((pointer_pos.y - img_rect.top()) / zoom) as u32,
+        );
 
         // Find sprite under cursor
-        for sprite in &atlas.sprites {
-            let sprite_rect = egui::Rect::from_min_size(
-                egui::pos2(sprite.x as f32, sprite.y as f32),
-                egui::vec2(sprite.width as f32, sprite.height as f32),
-            );
-
-            if sprite_rect.contains(egui::pos2(atlas_x, atlas_y)) {
-                // Build tooltip text
-                let trim_info = &sprite.trim_info;
-                let tooltip_text = if trim_info.was_trimmed() {
-                    format!(
-                        "{}\n{}x{} (trimmed from {}x{})\nOffset: ({}, {})",
-                        sprite.name,
-                        sprite.width,
-                        sprite.height,
-                        trim_info.source_width,
-                        trim_info.source_height,
-                        trim_info.offset_x,
-                        trim_info.offset_y
-                    )
-                } else {
-                    format!("{}\n{}x{}", sprite.name, sprite.width, sprite.height)
-                };
-
-                response.clone().on_hover_ui_at_pointer(|ui| {
-                    ui.set_min_width(200.0);
-                    ui.label(tooltip_text);
-                });
-                break;
+        if let Some(sprite) = atlas.sprite_at(atlas_x, atlas_y) {
+            hovered_sprite = Some((sprite.name.clone(), sprite.path.clone()));
+
+            // Build tooltip text
+            let trim_info = &sprite.trim_info;
+            let mut tooltip_text = if trim_info.was_trimmed() {
+                format!(
+                    "{}\n{}x{} (trimmed from {}x{})\nOffset: ({}, {})",
+                    sprite.name,
+                    sprite.width,
+                    sprite.height,
+                    trim_info.source_width,
+                    trim_info.source_height,
+                    trim_info.offset_x,
+                    trim_info.offset_y
+                )
+            } else {
+                format!("{}\n{}x{}", sprite.name, sprite.width, sprite.height)
+            };
+            if sprite.rotated {
+                tooltip_text.push_str("\nRotated 90° clockwise");
             }
+
+            response.clone().on_hover_ui_at_pointer(|ui| {
+                ui.set_min_width(200.0);
+                ui.label(tooltip_text);
+            });
         }
     }
+
+    // Clicking selects the sprite under the cursor (or clears the selection)
+    // and selects its source file in the input list
+    if response.clicked() {
+        state.runtime.selected_sprite = hovered_sprite.as_ref().map(|(name, _)| name.clone());
+        if let Some(index) = hovered_sprite
+            .as_ref()
+            .and_then(|(_, path)| state.config.input_paths.iter().position(|p| p == path))
+        {
+            state.runtime.selected_sprites.clear();
+            state.runtime.selected_sprites.insert(index);
+            state.runtime.selection_anchor = Some(index);
+            state.runtime.scroll_to_selection = true;
+        }
+    }
+
+    // Remember which sprite the context menu was opened on
+    if response.secondary_clicked() {
+        state.runtime.context_sprite = hovered_sprite.map(|(name, _)| name);
+    }
+    if let Some(name) = state.runtime.context_sprite.clone() {
+        let sprite = atlas.find(&name);
+        let trimmed = sprite.is_some_and(|sprite| sprite.trim_info.was_trimmed());
+        response.context_menu(|ui| {
+            ui.label(&name);
+            ui.separator();
+            if let Some(sprite) = sprite {
+                copy_sprite_menu(ui, sprite, state.config.godot_version);
+                ui.separator();
+            }
+            if ui.button("Export Sprite as PNG...").clicked() {
+                action.export_sprite = Some((name.clone(), false));
+                ui.close_menu();
+            }
+            if ui
+                .add_enabled(trimmed, egui::Button::new("Export Untrimmed as PNG..."))
+                .on_disabled_hover_text("This sprite was not trimmed")
+                .clicked()
+            {
+                action.export_sprite = Some((name.clone(), true));
+                ui.close_menu();
+            }
+        });
+    }
+
+    action
+}
+
+/// Name, source and placement of the selected sprite. Returns true when the
+/// selection should be cleared.
+fn sprite_details(ui: &mut egui::Ui, sprite: &PackedSprite, atlas_index: usize) -> bool {
+    let mut deselect = false;
+    ui.horizontal(|ui| {
+        ui.strong("Selected Sprite");
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            deselect = ui
+                .small_button("✕")
+                .on_hover_text("Clear selection")
+                .clicked();
+        });
+    });
+    ui.separator();
+
+    let trim = &sprite.trim_info;
+    egui::Grid::new("sprite_details_grid")
+        .num_columns(2)
+        .show(ui, |ui| {
+            ui.label("Name:");
+            ui.label(&sprite.name);
+            ui.end_row();
+
+            ui.label("Source:");
+            let file_name = sprite
+                .path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| sprite.path.display().to_string());
+            ui.label(file_name)
+                .on_hover_text(sprite.path.display().to_string());
+            ui.end_row();
+
+            ui.label("Atlas:");
+            ui.label(atlas_index.to_string());
+            ui.end_row();
+
+            ui.label("Position:");
+            ui.label(format!("{}, {}", sprite.x, sprite.y));
+            ui.end_row();
+
+            ui.label("Size:");
+            ui.label(format!("{}x{}", sprite.width, sprite.height));
+            ui.end_row();
+
+            ui.label("Rotated:");
+            ui.label(if sprite.rotated {
+                "90° clockwise"
+            } else {
+                "No"
+            });
+            ui.end_row();
+
+            ui.label("Trimmed:");
+            if trim.was_trimmed() {
+                ui.label(format!("from {}x{}", trim.source_width, trim.source_height));
+                ui.end_row();
+
+                ui.label("Trim Offset:");
+                ui.label(format!("{}, {}", trim.offset_x, trim.offset_y));
+            } else {
+                ui.label("No");
+            }
+            ui.end_row();
+
+            if let Some(pivot) = sprite.pivot {
+                ui.label("Pivot:");
+                ui.label(format!("{:.2}, {:.2}", pivot.x, pivot.y));
+                ui.end_row();
+            }
+
+            if let Some(borders) = sprite.nine_slice {
+                ui.label("Nine-Slice:");
+                ui.label(format!(
+                    "{}, {}, {}, {}",
+                    borders.left, borders.top, borders.right, borders.bottom
+                ))
+                .on_hover_text("Left, top, right, bottom");
+                ui.end_row();
+            }
+        });
+
+    deselect
+}
+
+/// Convert an atlas image to an opaque image showing only the requested channels
+fn channel_image(image: &image::RgbaImage, view: ChannelView) -> egui::ColorImage {
+    let pixels = image
+        .pixels()
+        .map(|p| {
+            let [r, g, b, a] = p.0;
+            match view {
+                ChannelView::All => egui::Color32::from_rgba_unmultiplied(r, g, b, a),
+                ChannelView::Rgb => egui::Color32::from_rgb(r, g, b),
+                ChannelView::Alpha => egui::Color32::from_gray(a),
+                ChannelView::Red => egui::Color32::from_gray(r),
+                ChannelView::Green => egui::Color32::from_gray(g),
+                ChannelView::Blue => egui::Color32::from_gray(b),
+            }
+        })
+        .collect();
+
+    egui::ColorImage {
+        size: [image.width() as usize, image.height() as usize],
+        pixels,
+    }
 }
 
 fn show_empty_state(ui: &mut egui::Ui) {
@@ -223,7 +528,7 @@ fn show_empty_state(ui: &mut egui::Ui) {
     );
 }
 
-fn show_packing_state(ui: &mut egui::Ui) {
+fn show_packing_state(ui: &mut egui::Ui, progress: PackProgress) {
     let available = ui.available_size();
     let rect = ui.allocate_space(available).1;
 
@@ -231,42 +536,22 @@ fn show_packing_state(ui: &mut egui::Ui) {
     ui.painter()
         .rect_filled(rect, 4.0, egui::Color32::from_gray(30));
 
-    // Draw spinner
-    let center = rect.center();
-    let time = ui.input(|i| i.time);
-    let radius = 16.0;
-    let stroke_width = 3.0;
-
-    // Spinning arc
-    #[allow(clippy::cast_possible_truncation)]
-    let start_angle = (time * 2.0) as f32;
-    let arc_length = std::f32::consts::PI * 1.5;
-
-    let points: Vec<egui::Pos2> = (0..32)
-        .map(|i| {
-            let angle = start_angle + (i as f32 / 31.0) * arc_length;
-            egui::pos2(
-                center.x + radius * angle.cos(),
-                center.y + radius * angle.sin(),
-            )
-        })
-        .collect();
-
-    ui.painter().add(egui::Shape::line(
-        points,
-        egui::Stroke::new(stroke_width, egui::Color32::from_gray(150)),
-    ));
-
-    // Text below spinner
-    ui.painter().text(
-        egui::pos2(center.x, center.y + 32.0),
-        egui::Align2::CENTER_CENTER,
-        "Packing...",
-        egui::FontId::default(),
-        egui::Color32::from_gray(100),
+    // Progress bar in the middle, labelled with the current stage
+    let bar_width = (rect.width() - 32.0).clamp(0.0, 320.0);
+    let bar_rect = egui::Rect::from_center_size(rect.center(), egui::vec2(bar_width, 20.0));
+    let stage = if progress.stage.is_empty() {
+        "Packing"
+    } else {
+        progress.stage
+    };
+    ui.put(
+        bar_rect,
+        egui::ProgressBar::new(progress.fraction)
+            .text(format!("{}... {:.0}%", stage, progress.fraction * 100.0))
+            .animate(true),
     );
 
-    // Request continuous repaints for animation
+    // Keep repainting while the worker reports progress
     ui.ctx().request_repaint();
 }
 
@@ -308,7 +593,7 @@ fn draw_checkerboard(painter: &egui::Painter, rect: egui::Rect) {
 }
 
 /// Format file size in human-readable form
-fn format_file_size(bytes: usize) -> String {
+pub fn format_file_size(bytes: usize) -> String {
     const KB: usize = 1024;
     const MB: usize = 1024 * 1024;
 
@@ -344,18 +629,24 @@ fn draw_debug_overlay(
     atlas: &Atlas,
     img_rect: egui::Rect,
     zoom: f32,
-    padding: u32,
+    (padding_x, padding_y): (u32, u32),
     extrude: u32,
+    sprite_settings: &HashMap<String, SpriteSettings>,
 ) {
     // Colors for different regions (semi-transparent)
     let sprite_color = egui::Color32::from_rgba_unmultiplied(0, 255, 0, 180); // Green
     let extrude_color = egui::Color32::from_rgba_unmultiplied(255, 165, 0, 120); // Orange
     let padding_color = egui::Color32::from_rgba_unmultiplied(255, 0, 255, 80); // Magenta
 
-    let padding_f = padding as f32;
-    let extrude_f = extrude as f32;
-
     for sprite in &atlas.sprites {
+        let settings = sprite_settings.get(&sprite.name);
+        let (padding_x, padding_y) = match settings.and_then(|s| s.padding) {
+            Some(padding) => (padding, padding),
+            None => (padding_x, padding_y),
+        };
+        let extrude = settings.and_then(|s| s.extrude).unwrap_or(extrude);
+        let extrude_f = extrude as f32;
+
         // Calculate screen coordinates for sprite content
         let sprite_x = img_rect.left() + sprite.x as f32 * zoom;
         let sprite_y = img_rect.top() + sprite.y as f32 * zoom;
@@ -363,14 +654,12 @@ fn draw_debug_overlay(
         let sprite_h = sprite.height as f32 * zoom;
 
         // 1. Draw padding region (outermost) if padding > 0
-        if padding > 0 {
-            let padding_offset = (padding_f + extrude_f) * zoom;
+        if padding_x > 0 || padding_y > 0 {
+            let offset_x = (padding_x as f32 + extrude_f) * zoom;
+            let offset_y = (padding_y as f32 + extrude_f) * zoom;
             let padding_rect = egui::Rect::from_min_size(
-                egui::pos2(sprite_x - padding_offset, sprite_y - padding_offset),
-                egui::vec2(
-                    sprite_w + 2.0 * padding_offset,
-                    sprite_h + 2.0 * padding_offset,
-                ),
+                egui::pos2(sprite_x - offset_x, sprite_y - offset_y),
+                egui::vec2(sprite_w + 2.0 * offset_x, sprite_h + 2.0 * offset_y),
             );
             painter.rect_stroke(padding_rect, 0.0, egui::Stroke::new(1.0, padding_color));
         }
@@ -396,3 +685,71 @@ fn draw_debug_overlay(
         painter.rect_stroke(sprite_rect, 0.0, egui::Stroke::new(1.5, sprite_color));
     }
 }
+
+/// Shade the page's free rectangles. They overlap, so areas shared by several
+/// free rectangles (the largest open spaces) build up the strongest tint.
+fn draw_free_space(painter: &egui::Painter, atlas: &Atlas, img_rect: egui::Rect, zoom: f32) {
+    let fill = egui::Color32::from_rgba_unmultiplied(255, 40, 40, 50);
+    let stroke = egui::Stroke::new(1.0, egui::Color32::from_rgba_unmultiplied(255, 40, 40, 140));
+    for free in &atlas.free_rects {
+        let rect = egui::Rect::from_min_size(
+            img_rect.min + egui::vec2(free.x as f32, free.y as f32) * zoom,
+            egui::vec2(free.width as f32, free.height as f32) * zoom,
+        );
+        painter.rect_filled(rect, 0.0, fill);
+        painter.rect_stroke(rect, 0.0, stroke);
+    }
+}
+
+/// Draw 1-texel grid lines over the visible part of the atlas
+fn draw_pixel_grid(painter: &egui::Painter, img_rect: egui::Rect, clip: egui::Rect, zoom: f32) {
+    let stroke = egui::Stroke::new(1.0, egui::Color32::from_rgba_unmultiplied(0, 0, 0, 90));
+    let visible = img_rect.intersect(clip);
+    if !visible.is_positive() {
+        return;
+    }
+
+    // Only iterate over texel boundaries that are on screen
+    let first_col = ((visible.left() - img_rect.left()) / zoom).floor();
+    let last_col = ((visible.right() - img_rect.left()) / zoom).ceil();
+    let mut col = first_col;
+    while col <= last_col {
+        let x = img_rect.left() + col * zoom;
+        painter.line_segment(
+            [
+                egui::pos2(x, visible.top()),
+                egui::pos2(x, visible.bottom()),
+            ],
+            stroke,
+        );
+        col += 1.0;
+    }
+
+    let first_row = ((visible.top() - img_rect.top()) / zoom).floor();
+    let last_row = ((visible.bottom() - img_rect.top()) / zoom).ceil();
+    let mut row = first_row;
+    while row <= last_row {
+        let y = img_rect.top() + row * zoom;
+        painter.line_segment(
+            [
+                egui::pos2(visible.left(), y),
+                egui::pos2(visible.right(), y),
+            ],
+            stroke,
+        );
+        row += 1.0;
+    }
+}
+
+/// Draw the texel coordinates under the cursor next to the pointer
+fn draw_texel_label(painter: &egui::Painter, pointer_pos: egui::Pos2, x: u32, y: u32) {
+    let galley = painter.layout_no_wrap(
+        format!("{}, {}", x, y),
+        egui::FontId::monospace(12.0),
+        egui::Color32::WHITE,
+    );
+    let pos = pointer_pos + egui::vec2(14.0, -galley.size().y - 6.0);
+    let bg = egui::Rect::from_min_size(pos, galley.size()).expand(3.0);
+    painter.rect_filled(bg, 2.0, egui::Color32::from_black_alpha(200));
+    painter.galley(pos, galley, egui::Color32::WHITE);
+}
@@ -1,7 +1,16 @@
+use std::num::NonZeroU8;
+
 use eframe::egui;
 
-use crate::cli::{CompressionLevel, PackMode, PackingHeuristic, ResizeFilter};
-use crate::gui::state::{AppState, ResizeMode};
+use super::format_file_size;
+use crate::cli::{
+    BasisMode, Color, CompressionLevel, ExtrudeMode, GodotVersion, GridCell, MipmapFormat,
+    OutputFormat, PackMode, PackingHeuristic, PhaserFrames, ResizeFilter, SourcePaths, SpriteOrder,
+    SpriteSort,
+};
+use crate::gui::state::{AppState, CompressionEstimate, ResizeMode};
+use crate::gui::thumbnail::THUMBNAIL_CACHE_MAX_BYTES;
+use crate::sprite::TrimMargin;
 
 /// Settings panel with all packing/export options
 pub fn settings_panel(ui: &mut egui::Ui, state: &mut AppState) {
@@ -32,15 +41,139 @@ pub fn settings_panel(ui: &mut egui::Ui, state: &mut AppState) {
             });
 
             ui.horizontal(|ui| {
-                ui.label("Padding:");
+                ui.label("Padding X:");
                 ui.add(
-                    egui::DragValue::new(&mut state.config.padding)
+                    egui::DragValue::new(&mut state.config.padding_x)
                         .range(0..=32)
                         .speed(1),
+                )
+                .on_hover_text("Padding left and right of each sprite");
+                ui.label("Y:");
+                ui.add(
+                    egui::DragValue::new(&mut state.config.padding_y)
+                        .range(0..=32)
+                        .speed(1),
+                )
+                .on_hover_text(
+                    "Padding above and below each sprite. With rotation allowed, \
+                     both axes use the larger padding",
                 );
             });
 
             ui.checkbox(&mut state.config.pot, "Power of Two");
+            ui.checkbox(&mut state.config.square, "Square");
+            ui.checkbox(&mut state.config.fixed_size, "Fixed Size")
+                .on_hover_text("Always output pages at the max width and height");
+            ui.checkbox(&mut state.config.size_search, "Minimal Size Search")
+                .on_hover_text(
+                    "Search for the smallest page that fits instead of cropping the full \
+                     max size (slower)",
+                );
+
+            ui.checkbox(&mut state.config.allow_rotation, "Allow Rotation")
+                .on_hover_text(
+                    "Store sprites turned 90° clockwise when that packs tighter \
+                     (not supported by the Godot format)",
+                );
+
+            ui.horizontal(|ui| {
+                ui.label("Size Multiple:");
+                ui.add(
+                    egui::DragValue::new(&mut state.config.size_multiple)
+                        .range(0..=64)
+                        .speed(1),
+                )
+                .on_hover_text("Round atlas width and height up to a multiple of this (0 = off)");
+            });
+
+            // Sprites-per-page limit
+            let mut limit_enabled = state.config.max_sprites_per_page.is_some();
+            ui.horizontal(|ui| {
+                if ui
+                    .checkbox(&mut limit_enabled, "Max Sprites/Page:")
+                    .changed()
+                {
+                    state.config.max_sprites_per_page = limit_enabled.then_some(256);
+                }
+                if let Some(limit) = &mut state.config.max_sprites_per_page {
+                    ui.add(egui::DragValue::new(limit).range(1..=65536).speed(1));
+                }
+            });
+
+            // Uniform grid cells instead of packing
+            let mut grid_enabled = state.config.grid.is_some();
+            ui.horizontal(|ui| {
+                if ui
+                    .checkbox(&mut grid_enabled, "Grid Cells:")
+                    .on_hover_text("Place sprites in input order in uniform cells, for tile sets")
+                    .changed()
+                {
+                    state.config.grid = grid_enabled.then_some(GridCell::Auto);
+                }
+                if let Some(grid) = &mut state.config.grid {
+                    let mut auto = *grid == GridCell::Auto;
+                    if ui
+                        .checkbox(&mut auto, "Auto")
+                        .on_hover_text("Size cells to the largest sprite")
+                        .changed()
+                    {
+                        *grid = if auto {
+                            GridCell::Auto
+                        } else {
+                            GridCell::Fixed {
+                                width: 32,
+                                height: 32,
+                            }
+                        };
+                    }
+                    if let GridCell::Fixed { width, height } = grid {
+                        ui.add(egui::DragValue::new(width).range(1..=8192).speed(1));
+                        ui.label("x");
+                        ui.add(egui::DragValue::new(height).range(1..=8192).speed(1));
+                    }
+                }
+            });
+
+            // Total area budgets across all pages
+            let mut pixels_enabled = state.config.max_total_pixels.is_some();
+            ui.horizontal(|ui| {
+                if ui
+                    .checkbox(&mut pixels_enabled, "Max Total Pixels:")
+                    .changed()
+                {
+                    state.config.max_total_pixels = pixels_enabled.then_some(4096 * 4096);
+                }
+                if let Some(limit) = &mut state.config.max_total_pixels {
+                    ui.add(egui::DragValue::new(limit).range(1..=u64::MAX).speed(65536));
+                }
+            });
+
+            let mut bytes_enabled = state.config.max_total_bytes.is_some();
+            ui.horizontal(|ui| {
+                if ui
+                    .checkbox(&mut bytes_enabled, "Max Total Bytes:")
+                    .changed()
+                {
+                    state.config.max_total_bytes = bytes_enabled.then_some(64 * 1024 * 1024);
+                }
+                if let Some(limit) = &mut state.config.max_total_bytes {
+                    ui.add(egui::DragValue::new(limit).range(1..=u64::MAX).speed(65536));
+                }
+            });
+
+            let mut atlases_enabled = state.config.max_atlases.is_some();
+            ui.horizontal(|ui| {
+                if ui
+                    .checkbox(&mut atlases_enabled, "Max Atlases:")
+                    .on_hover_text("Fail when the sprites need more pages than this")
+                    .changed()
+                {
+                    state.config.max_atlases = atlases_enabled.then_some(1);
+                }
+                if let Some(limit) = &mut state.config.max_atlases {
+                    ui.add(egui::DragValue::new(limit).range(1..=256).speed(1));
+                }
+            });
         });
 
     // Sprites section
@@ -48,15 +181,56 @@ pub fn settings_panel(ui: &mut egui::Ui, state: &mut AppState) {
         .default_open(true)
         .show(ui, |ui| {
             ui.checkbox(&mut state.config.trim, "Trim transparent borders");
+            ui.checkbox(&mut state.config.record_trim, "Record trim only")
+                .on_hover_text(
+                    "Keep full frames in the atlas, but record tight content bounds in metadata",
+                );
+            ui.checkbox(&mut state.config.split_layers, "Split Aseprite layers")
+                .on_hover_text("Pack each visible layer of .aseprite files as its own sprite");
+            ui.checkbox(&mut state.config.skip_errors, "Skip unreadable images")
+                .on_hover_text("Leave out images that fail to load instead of stopping the pack");
 
-            if state.config.trim {
+            if state.config.trim || state.config.record_trim {
+                let margin = &mut state.config.trim_margin;
                 ui.horizontal(|ui| {
                     ui.label("Trim Margin:");
-                    ui.add(
-                        egui::DragValue::new(&mut state.config.trim_margin)
-                            .range(0..=32)
-                            .speed(1),
-                    );
+                    if margin.is_uniform() {
+                        let mut value = margin.left;
+                        if ui
+                            .add(egui::DragValue::new(&mut value).range(0..=32).speed(1))
+                            .changed()
+                        {
+                            *margin = TrimMargin::uniform(value);
+                        }
+                    } else {
+                        ui.label("per edge");
+                    }
+                });
+
+                egui::CollapsingHeader::new("Per-Edge Margin")
+                    .default_open(!margin.is_uniform())
+                    .show(ui, |ui| {
+                        egui::Grid::new("trim_margin_edges").show(ui, |ui| {
+                            for (label, value) in [
+                                ("Left:", &mut margin.left),
+                                ("Top:", &mut margin.top),
+                                ("Right:", &mut margin.right),
+                                ("Bottom:", &mut margin.bottom),
+                            ] {
+                                ui.label(label);
+                                ui.add(egui::DragValue::new(value).range(0..=32).speed(1));
+                                ui.end_row();
+                            }
+                        });
+                    });
+
+                ui.horizontal(|ui| {
+                    ui.label("Trim Alpha:");
+                    ui.add(egui::Slider::new(&mut state.config.trim_alpha, 0..=254))
+                        .on_hover_text(
+                            "Pixels with alpha at or below this count as transparent \
+                             when trimming, so faint halos are trimmed away",
+                        );
                 });
             }
 
@@ -67,8 +241,40 @@ pub fn settings_panel(ui: &mut egui::Ui, state: &mut AppState) {
                         .range(0..=8)
                         .speed(1),
                 );
+                ui.selectable_value(&mut state.config.extrude_mode, ExtrudeMode::Clamp, "Clamp")
+                    .on_hover_text("Repeat the sprite's edge pixels");
+                ui.selectable_value(&mut state.config.extrude_mode, ExtrudeMode::Wrap, "Wrap")
+                    .on_hover_text(
+                        "Copy pixels from the opposite edge, so seamless tiles keep tiling",
+                    );
+            });
+
+            ui.horizontal(|ui| {
+                let mut filled = state.config.background_color.is_some();
+                if ui
+                    .checkbox(&mut filled, "Background")
+                    .on_hover_text("Fill the atlas area outside sprites with a color")
+                    .changed()
+                {
+                    state.config.background_color = filled.then_some(Color {
+                        r: 255,
+                        g: 0,
+                        b: 255,
+                        a: 255,
+                    });
+                }
+                if let Some(color) = &mut state.config.background_color {
+                    let mut rgba = [color.r, color.g, color.b, color.a];
+                    if ui.color_edit_button_srgba_unmultiplied(&mut rgba).changed() {
+                        [color.r, color.g, color.b, color.a] = rgba;
+                    }
+                }
             });
 
+            egui::CollapsingHeader::new("Extrude Overrides")
+                .default_open(!state.config.extrude_overrides.is_empty())
+                .show(ui, |ui| extrude_overrides_list(ui, state));
+
             // Resize mode
             ui.horizontal(|ui| {
                 ui.label("Resize:");
@@ -76,6 +282,8 @@ pub fn settings_panel(ui: &mut egui::Ui, state: &mut AppState) {
                     ResizeMode::None => 0,
                     ResizeMode::Width(_) => 1,
                     ResizeMode::Scale(_) => 2,
+                    ResizeMode::MaxWidth(_) => 3,
+                    ResizeMode::Fit { .. } => 4,
                 };
 
                 let mut selected = current;
@@ -83,12 +291,18 @@ pub fn settings_panel(ui: &mut egui::Ui, state: &mut AppState) {
                     .selected_text(match current {
                         0 => "None",
                         1 => "Width",
-                        _ => "Scale",
+                        2 => "Scale",
+                        3 => "Max Width",
+                        _ => "Fit Box",
                     })
                     .show_ui(ui, |ui| {
                         ui.selectable_value(&mut selected, 0, "None");
                         ui.selectable_value(&mut selected, 1, "Width");
                         ui.selectable_value(&mut selected, 2, "Scale");
+                        ui.selectable_value(&mut selected, 3, "Max Width")
+                            .on_hover_text("Shrink wider images; never enlarge smaller ones");
+                        ui.selectable_value(&mut selected, 4, "Fit Box")
+                            .on_hover_text("Shrink larger images to fit a box; never enlarge");
                     });
 
                 // Update resize mode if selection changed
@@ -96,7 +310,12 @@ pub fn settings_panel(ui: &mut egui::Ui, state: &mut AppState) {
                     state.config.resize_mode = match selected {
                         0 => ResizeMode::None,
                         1 => ResizeMode::Width(256),
-                        _ => ResizeMode::Scale(0.5),
+                        2 => ResizeMode::Scale(0.5),
+                        3 => ResizeMode::MaxWidth(256),
+                        _ => ResizeMode::Fit {
+                            width: 256,
+                            height: 256,
+                        },
                     };
                 }
             });
@@ -111,6 +330,22 @@ pub fn settings_panel(ui: &mut egui::Ui, state: &mut AppState) {
                         ui.label("px");
                     });
                 }
+                ResizeMode::MaxWidth(width) => {
+                    ui.horizontal(|ui| {
+                        ui.label("Max Width:");
+                        ui.add(egui::DragValue::new(width).range(1..=4096).speed(1));
+                        ui.label("px");
+                    });
+                }
+                ResizeMode::Fit { width, height } => {
+                    ui.horizontal(|ui| {
+                        ui.label("Fit Within:");
+                        ui.add(egui::DragValue::new(width).range(1..=4096).speed(1));
+                        ui.label("x");
+                        ui.add(egui::DragValue::new(height).range(1..=4096).speed(1));
+                        ui.label("px");
+                    });
+                }
                 ResizeMode::Scale(scale) => {
                     ui.horizontal(|ui| {
                         ui.label("Scale Factor:");
@@ -158,6 +393,8 @@ pub fn settings_panel(ui: &mut egui::Ui, state: &mut AppState) {
                             );
                         });
                 });
+                ui.checkbox(&mut state.config.resize_linear, "Linear light")
+                    .on_hover_text("Resize in linear light so high-contrast edges don't darken");
             }
         });
 
@@ -214,8 +451,64 @@ pub fn settings_panel(ui: &mut egui::Ui, state: &mut AppState) {
                             "Single",
                         );
                         ui.selectable_value(&mut state.config.pack_mode, PackMode::Best, "Best");
+                        ui.selectable_value(
+                            &mut state.config.pack_mode,
+                            PackMode::MinPages,
+                            "Fewest Pages",
+                        )
+                        .on_hover_text(
+                            "Like Best, then spread sprites over all pages at once \
+                             when that needs fewer pages",
+                        );
                     });
             });
+
+            ui.horizontal(|ui| {
+                ui.label("Sort:");
+                egui::ComboBox::from_id_salt("sort")
+                    .selected_text(sprite_sort_name(state.config.sort))
+                    .show_ui(ui, |ui| {
+                        for sort in [
+                            SpriteSort::Area,
+                            SpriteSort::MaxSide,
+                            SpriteSort::Height,
+                            SpriteSort::Width,
+                            SpriteSort::Perimeter,
+                            SpriteSort::Name,
+                            SpriteSort::None,
+                        ] {
+                            ui.selectable_value(
+                                &mut state.config.sort,
+                                sort,
+                                sprite_sort_name(sort),
+                            );
+                        }
+                    })
+                    .response
+                    .on_hover_text("Order sprites are packed in; None keeps the input order");
+            });
+
+            if state.config.pack_mode != PackMode::Single {
+                ui.horizontal(|ui| {
+                    ui.label("Random Trials:");
+                    ui.add(
+                        egui::DragValue::new(&mut state.config.trials)
+                            .range(0..=1000)
+                            .speed(1),
+                    )
+                    .on_hover_text("Extra shuffled sprite orderings to try");
+                    ui.label("Seed:");
+                    ui.add(egui::DragValue::new(&mut state.config.seed).speed(1));
+                });
+            }
+        });
+
+    // Pack order section
+    egui::CollapsingHeader::new("Pack Order")
+        .default_open(false)
+        .show(ui, |ui| {
+            ui.label("Sprites matching these names or patterns are packed first. Drag to reorder.");
+            pack_order_list(ui, state);
         });
 
     // Output section
@@ -223,6 +516,290 @@ pub fn settings_panel(ui: &mut egui::Ui, state: &mut AppState) {
         .default_open(true)
         .show(ui, |ui| {
             ui.checkbox(&mut state.config.opaque, "Opaque (RGB instead of RGBA)");
+            ui.add_enabled_ui(state.config.opaque, |ui| {
+                ui.horizontal(|ui| {
+                    let mut matte = state.config.matte_color.is_some();
+                    if ui
+                        .checkbox(&mut matte, "Matte color")
+                        .on_hover_text(
+                            "Blend semi-transparent pixels over this color instead of dropping alpha",
+                        )
+                        .changed()
+                    {
+                        state.config.matte_color = matte.then_some(Color {
+                            r: 0,
+                            g: 0,
+                            b: 0,
+                            a: 255,
+                        });
+                    }
+                    if let Some(color) = &mut state.config.matte_color {
+                        let mut rgb = [color.r, color.g, color.b];
+                        if ui.color_edit_button_srgb(&mut rgb).changed() {
+                            [color.r, color.g, color.b] = rgb;
+                        }
+                    }
+                });
+            });
+            ui.checkbox(&mut state.config.split_alpha, "Split alpha")
+                .on_hover_text(
+                    "Write color and alpha as two PNGs: an opaque RGB image and a grayscale mask",
+                );
+            ui.checkbox(&mut state.config.png_metadata, "PNG metadata")
+                .on_hover_text(
+                    "Record the bento version and a hash of the saved project in text chunks \
+                     of each atlas PNG",
+                );
+
+            // Mipmaps
+            ui.horizontal(|ui| {
+                ui.label("Mipmaps:");
+                ui.add(
+                    egui::DragValue::new(&mut state.config.mipmaps)
+                        .range(0..=16)
+                        .speed(0.1),
+                )
+                .on_hover_text(
+                    "Mip levels to generate below the atlas. Each sprite is downsampled \
+                     on its own, so levels don't bleed neighbors together",
+                );
+                if state.config.mipmaps > 0 {
+                    egui::ComboBox::from_id_salt("mipmap_format")
+                        .selected_text(match state.config.mipmap_format {
+                            MipmapFormat::Png => "PNG files",
+                            MipmapFormat::Ktx2 => "KTX2",
+                        })
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(
+                                &mut state.config.mipmap_format,
+                                MipmapFormat::Png,
+                                "PNG files",
+                            );
+                            ui.selectable_value(
+                                &mut state.config.mipmap_format,
+                                MipmapFormat::Ktx2,
+                                "KTX2",
+                            );
+                        });
+                }
+            });
+
+            // Basis Universal KTX2
+            ui.horizontal(|ui| {
+                ui.label("Basis KTX2:");
+                egui::ComboBox::from_id_salt("basis")
+                    .selected_text(basis_mode_name(state.config.basis))
+                    .show_ui(ui, |ui| {
+                        for mode in [None, Some(BasisMode::Uastc), Some(BasisMode::Etc1s)] {
+                            ui.selectable_value(
+                                &mut state.config.basis,
+                                mode,
+                                basis_mode_name(mode),
+                            );
+                        }
+                    })
+                    .response
+                    .on_hover_text(
+                        "Also write each page as a supercompressed KTX2 texture. Needs \
+                         Basis Universal's basisu encoder on PATH",
+                    );
+            });
+
+            // Output scales
+            ui.horizontal(|ui| {
+                ui.label("Scales:");
+                ui.add(
+                    egui::TextEdit::singleline(&mut state.config.scales)
+                        .hint_text("1")
+                        .desired_width(100.0),
+                )
+                .on_hover_text(
+                    "Comma-separated scales to export from the same layout, e.g. \
+                     1, 0.5, 0.25. Scales other than 1 get an @0.5x style suffix",
+                );
+            });
+
+            // Metadata sprite order
+            ui.horizontal(|ui| {
+                ui.label("Sprite Order:");
+                egui::ComboBox::from_id_salt("sprite_order")
+                    .selected_text(sprite_order_name(state.config.sprite_order))
+                    .show_ui(ui, |ui| {
+                        for order in [SpriteOrder::Packed, SpriteOrder::Name, SpriteOrder::Input] {
+                            ui.selectable_value(
+                                &mut state.config.sprite_order,
+                                order,
+                                sprite_order_name(order),
+                            );
+                        }
+                    });
+            });
+
+            // Handlebars template for template output
+            if state.config.formats.contains(&OutputFormat::Template) {
+                ui.horizontal(|ui| {
+                    ui.label("Template:");
+                    ui.add(
+                        egui::TextEdit::singleline(&mut state.config.template)
+                            .hint_text("templates/sheet.xml.hbs")
+                            .desired_width(160.0),
+                    )
+                    .on_hover_text(
+                        "Handlebars template rendered with the atlas data, relative to the \
+                         project file's folder. The output takes its extension",
+                    );
+                });
+            }
+
+            // Godot resource syntax
+            if state.config.formats.contains(&OutputFormat::Godot) {
+                ui.horizontal(|ui| {
+                    ui.label("Godot Version:");
+                    ui.radio_value(&mut state.config.godot_version, GodotVersion::V4, "4.x");
+                    ui.radio_value(&mut state.config.godot_version, GodotVersion::V3, "3.x")
+                        .on_hover_text("Write .tres files in Godot 3 syntax (format=2, Texture)");
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Res Path:");
+                    ui.add(
+                        egui::TextEdit::singleline(&mut state.config.godot_res_path)
+                            .hint_text("res://")
+                            .desired_width(140.0),
+                    )
+                    .on_hover_text(
+                        "Godot directory the atlas image lives in, used for the texture \
+                         path in each .tres (e.g. res://textures/atlases)",
+                    );
+                });
+            }
+
+            // Frame layout of single-page Phaser sheets
+            if state.config.formats.contains(&OutputFormat::Phaser) {
+                ui.horizontal(|ui| {
+                    ui.label("Phaser Frames:");
+                    egui::ComboBox::from_id_salt("phaser_frames")
+                        .selected_text(match state.config.phaser_frames {
+                            PhaserFrames::Hash => "Hash",
+                            PhaserFrames::Array => "Array",
+                        })
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(
+                                &mut state.config.phaser_frames,
+                                PhaserFrames::Hash,
+                                "Hash",
+                            );
+                            ui.selectable_value(
+                                &mut state.config.phaser_frames,
+                                PhaserFrames::Array,
+                                "Array",
+                            );
+                        })
+                        .response
+                        .on_hover_text(
+                            "How frames are listed in a single-page sheet. Several pages \
+                             always use Phaser's multiatlas layout",
+                        );
+                });
+            }
+
+            // Minified output of the JSON-based formats
+            if state.config.formats.iter().any(|format| {
+                matches!(
+                    format,
+                    OutputFormat::Json | OutputFormat::Tpsheet | OutputFormat::Phaser
+                )
+            }) {
+                ui.checkbox(&mut state.config.json_compact, "Compact JSON")
+                    .on_hover_text(
+                        "Write JSON, tpsheet and Phaser metadata without indentation. \
+                         Much smaller for large sheets, but harder to diff",
+                    );
+            }
+
+            // Source image paths in JSON metadata
+            if state.config.formats.contains(&OutputFormat::Json) {
+                ui.horizontal(|ui| {
+                    ui.label("Source Paths:");
+                    egui::ComboBox::from_id_salt("source_paths")
+                        .selected_text(source_paths_name(state.config.source_paths))
+                        .show_ui(ui, |ui| {
+                            for style in [
+                                None,
+                                Some(SourcePaths::Absolute),
+                                Some(SourcePaths::Relative),
+                            ] {
+                                ui.selectable_value(
+                                    &mut state.config.source_paths,
+                                    style,
+                                    source_paths_name(style),
+                                );
+                            }
+                        })
+                        .response
+                        .on_hover_text(
+                            "Record each sprite's source file in the JSON. Relative paths \
+                             start from the project file's folder (absolute until it is saved)",
+                        );
+                });
+                ui.checkbox(&mut state.config.content_hash, "Content hashes")
+                    .on_hover_text(
+                        "Add SHA-256 digests of the layout and each atlas image to the JSON \
+                         meta block, so clients can tell when to download atlases again",
+                    );
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut state.config.polygon, "Polygon outlines")
+                        .on_hover_text(
+                            "Add a triangulated outline of each sprite's visible pixels to \
+                             the JSON, to draw sprites with less overdraw than a full quad",
+                        );
+                    if state.config.polygon {
+                        ui.add(
+                            egui::DragValue::new(&mut state.config.polygon_tolerance)
+                                .range(0.0..=16.0)
+                                .speed(0.1),
+                        )
+                        .on_hover_text(
+                            "How far an outline may stray from the pixels; higher gives \
+                             fewer vertices but more overdraw",
+                        );
+                    }
+                });
+            }
+
+            // Image path prefix for metadata
+            // Godot resources reference the atlas by res:// path instead
+            if state
+                .config
+                .formats
+                .iter()
+                .any(|format| *format != OutputFormat::Godot)
+            {
+                ui.horizontal(|ui| {
+                    ui.label("Image Path Prefix:");
+                    ui.add(
+                        egui::TextEdit::singleline(&mut state.config.image_path_prefix)
+                            .hint_text("textures/atlases/")
+                            .desired_width(140.0),
+                    )
+                    .on_hover_text("Prepended to the atlas image path written in the metadata");
+                });
+            }
+
+            // Atlas image file names
+            ui.horizontal(|ui| {
+                ui.label("File Names:");
+                let valid = state.config.output_template().is_ok();
+                ui.add(
+                    egui::TextEdit::singleline(&mut state.config.output_template)
+                        .hint_text("{name}_{index}.png")
+                        .text_color_opt((!valid).then_some(ui.visuals().error_fg_color))
+                        .desired_width(140.0),
+                )
+                .on_hover_text(
+                    "Atlas image names: {name} is the export name, {index} the page \
+                     ({index:3} zero-pads it). A single page drops {index}",
+                );
+            });
 
             // Compression
             let compress_enabled = state.config.compress.is_some();
@@ -247,12 +824,14 @@ pub fn settings_panel(ui: &mut egui::Ui, state: &mut AppState) {
                     let current = match level {
                         CompressionLevel::Level(n) => i32::from(*n),
                         CompressionLevel::Max => 7,
+                        CompressionLevel::Zopfli(_) => 8,
                     };
 
                     let mut selected = current;
                     egui::ComboBox::from_id_salt("compress_level")
                         .selected_text(match current {
                             7 => "Max".to_string(),
+                            8 => "Zopfli".to_string(),
                             n => n.to_string(),
                         })
                         .show_ui(ui, |ui| {
@@ -260,22 +839,81 @@ pub fn settings_panel(ui: &mut egui::Ui, state: &mut AppState) {
                                 ui.selectable_value(&mut selected, i, i.to_string());
                             }
                             ui.selectable_value(&mut selected, 7, "Max");
+                            ui.selectable_value(&mut selected, 8, "Zopfli")
+                                .on_hover_text("Smallest files, but export is much slower");
                         });
 
                     if selected != current {
-                        *level = if selected == 7 {
-                            CompressionLevel::Max
-                        } else {
+                        *level = match selected {
+                            7 => CompressionLevel::Max,
+                            8 => CompressionLevel::Zopfli(
+                                CompressionLevel::DEFAULT_ZOPFLI_ITERATIONS,
+                            ),
                             // selected is in range 0..=6 from the ComboBox options
                             #[expect(
                                 clippy::cast_possible_truncation,
                                 clippy::cast_sign_loss,
                                 reason = "value is constrained to 0..=6"
                             )]
-                            CompressionLevel::Level(selected as u8)
+                            n => CompressionLevel::Level(n as u8),
                         };
                     }
                 });
+
+                if let CompressionLevel::Zopfli(iterations) = level {
+                    ui.horizontal(|ui| {
+                        ui.label("Iterations:");
+                        let mut value = iterations.get();
+                        if ui
+                            .add(egui::DragValue::new(&mut value).range(1..=255))
+                            .on_hover_text("More iterations squeeze out a few more bytes")
+                            .changed()
+                            && let Some(n) = NonZeroU8::new(value)
+                        {
+                            *iterations = n;
+                        }
+                    });
+                }
+            }
+
+            // Before/after comparison from the latest size estimate
+            if let Some(level) = state.config.compress {
+                let estimate = state
+                    .runtime
+                    .compression_estimate
+                    .filter(|estimate| estimate.level == level);
+                if let Some(estimate) = estimate {
+                    compression_comparison(ui, &estimate);
+                } else if state.runtime.size_estimate_task.is_some()
+                    || state.runtime.pack_task.is_some()
+                {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label("Estimating...");
+                    });
+                }
+            }
+        });
+
+    // Cache section
+    egui::CollapsingHeader::new("Cache")
+        .default_open(false)
+        .show(ui, |ui| {
+            let Some(cache) = &state.runtime.thumbnail_cache else {
+                ui.label("Thumbnail cache unavailable");
+                return;
+            };
+
+            ui.label(format!(
+                "Thumbnail cache limit: {} MB",
+                THUMBNAIL_CACHE_MAX_BYTES / (1024 * 1024)
+            ));
+            if ui.button("Clear Thumbnail Cache").clicked() {
+                if let Err(e) = cache.clear() {
+                    log::warn!("Failed to clear thumbnail cache: {}", e);
+                }
+                // Regenerate thumbnails from the source images
+                state.runtime.thumbnails.clear();
             }
         });
 }
@@ -291,10 +929,146 @@ fn heuristic_name(h: PackingHeuristic) -> &'static str {
     }
 }
 
+/// Editable table of extrude overrides keyed by sprite name or glob pattern
+fn extrude_overrides_list(ui: &mut egui::Ui, state: &mut AppState) {
+    let mut removed = None;
+    for (pattern, extrude) in &mut state.config.extrude_overrides {
+        ui.horizontal(|ui| {
+            ui.label(pattern.as_str());
+            ui.add(egui::DragValue::new(extrude).range(0..=8).speed(1));
+            if ui
+                .small_button("\u{2715}")
+                .on_hover_text("Remove")
+                .clicked()
+            {
+                removed = Some(pattern.clone());
+            }
+        });
+    }
+    if let Some(pattern) = removed {
+        state.config.extrude_overrides.remove(&pattern);
+    }
+
+    ui.horizontal(|ui| {
+        let edit = ui.add(
+            egui::TextEdit::singleline(&mut state.runtime.extrude_override_input)
+                .hint_text("name or pattern, e.g. tiles/*")
+                .desired_width(150.0),
+        );
+        let submitted = edit.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+        let pattern = state.runtime.extrude_override_input.trim().to_string();
+        if (ui.button("Add").clicked() || submitted) && !pattern.is_empty() {
+            state
+                .config
+                .extrude_overrides
+                .entry(pattern)
+                .or_insert(state.config.extrude);
+            state.runtime.extrude_override_input.clear();
+        }
+    });
+}
+
+/// Reorderable list of pack-order patterns, with an entry field to add more
+fn pack_order_list(ui: &mut egui::Ui, state: &mut AppState) {
+    let mut moved: Option<(usize, usize)> = None;
+    let mut removed = None;
+
+    for (i, pattern) in state.config.pack_order.iter().enumerate() {
+        let row = ui.horizontal(|ui| {
+            ui.dnd_drag_source(ui.id().with(("pack_order", i)), i, |ui| {
+                ui.label(format!("\u{2630} {}. {}", i + 1, pattern));
+            });
+            if ui
+                .small_button("\u{2715}")
+                .on_hover_text("Remove")
+                .clicked()
+            {
+                removed = Some(i);
+            }
+        });
+
+        let response = row.response;
+        if response.dnd_hover_payload::<usize>().is_some() {
+            ui.painter().hline(
+                response.rect.x_range(),
+                response.rect.top(),
+                ui.visuals().selection.stroke,
+            );
+        }
+        if let Some(from) = response.dnd_release_payload::<usize>() {
+            moved = Some((*from, i));
+        }
+    }
+
+    if let Some((from, to)) = moved
+        && from != to
+    {
+        let pattern = state.config.pack_order.remove(from);
+        state.config.pack_order.insert(to, pattern);
+    }
+    if let Some(i) = removed {
+        state.config.pack_order.remove(i);
+    }
+
+    ui.horizontal(|ui| {
+        let edit = ui.add(
+            egui::TextEdit::singleline(&mut state.runtime.pack_order_input)
+                .hint_text("name or pattern, e.g. hero*")
+                .desired_width(150.0),
+        );
+        let submitted = edit.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+        let pattern = state.runtime.pack_order_input.trim().to_string();
+        if (ui.button("Add").clicked() || submitted)
+            && !pattern.is_empty()
+            && !state.config.pack_order.contains(&pattern)
+        {
+            state.config.pack_order.push(pattern);
+            state.runtime.pack_order_input.clear();
+        }
+    });
+}
+
 fn pack_mode_name(m: PackMode) -> &'static str {
     match m {
         PackMode::Single => "Single",
         PackMode::Best => "Best",
+        PackMode::MinPages => "Fewest Pages",
+    }
+}
+
+fn sprite_sort_name(sort: SpriteSort) -> &'static str {
+    match sort {
+        SpriteSort::Area => "Area",
+        SpriteSort::MaxSide => "Max Side",
+        SpriteSort::Height => "Height",
+        SpriteSort::Width => "Width",
+        SpriteSort::Perimeter => "Perimeter",
+        SpriteSort::Name => "Name",
+        SpriteSort::None => "None (input order)",
+    }
+}
+
+fn sprite_order_name(o: SpriteOrder) -> &'static str {
+    match o {
+        SpriteOrder::Packed => "Packed",
+        SpriteOrder::Name => "Name",
+        SpriteOrder::Input => "Input",
+    }
+}
+
+fn basis_mode_name(mode: Option<BasisMode>) -> &'static str {
+    match mode {
+        None => "Off",
+        Some(BasisMode::Uastc) => "UASTC",
+        Some(BasisMode::Etc1s) => "ETC1S",
+    }
+}
+
+fn source_paths_name(s: Option<SourcePaths>) -> &'static str {
+    match s {
+        None => "Off",
+        Some(SourcePaths::Absolute) => "Absolute",
+        Some(SourcePaths::Relative) => "Relative",
     }
 }
 
@@ -307,3 +1081,39 @@ fn resize_filter_name(f: ResizeFilter) -> &'static str {
         ResizeFilter::Lanczos3 => "Lanczos3",
     }
 }
+
+/// Show estimated atlas sizes without and with compression, the share saved,
+/// and the extra time compression adds to an export
+fn compression_comparison(ui: &mut egui::Ui, estimate: &CompressionEstimate) {
+    let saved = if estimate.uncompressed_bytes == 0 {
+        0.0
+    } else {
+        (1.0 - estimate.compressed_bytes as f64 / estimate.uncompressed_bytes as f64) * 100.0
+    };
+    let extra = estimate.extra_time.as_secs_f64();
+    let extra = if extra < 1.0 {
+        format!("+{:.0} ms", extra * 1000.0)
+    } else {
+        format!("+{:.1} s", extra)
+    };
+
+    egui::Grid::new("compression_comparison")
+        .num_columns(2)
+        .show(ui, |ui| {
+            ui.label("Uncompressed:");
+            ui.label(format_file_size(estimate.uncompressed_bytes));
+            ui.end_row();
+            ui.label("Compressed:");
+            ui.label(format!(
+                "{} ({:.1}% saved)",
+                format_file_size(estimate.compressed_bytes),
+                saved
+            ));
+            ui.end_row();
+            ui.label("Export time:");
+            ui.label(extra).on_hover_text(
+                "Extra time compression adds to each export, estimated on this machine",
+            );
+            ui.end_row();
+        });
+}
@@ -0,0 +1,251 @@
+use eframe::egui;
+
+use crate::atlas::Atlas;
+use crate::gui::state::AppState;
+use crate::sprite::SpriteStats;
+
+/// Statistics panel summarizing the loaded sprite set, and what the last pack placed
+pub fn stats_panel(ui: &mut egui::Ui, state: &AppState) {
+    egui::CollapsingHeader::new("Statistics")
+        .default_open(false)
+        .show(ui, |ui| {
+            ui.strong("Input sprites");
+            match &state.runtime.input_stats {
+                Some(Ok(stats)) => {
+                    stats_summary(ui, stats);
+                    size_breakdown(ui, stats);
+                }
+                Some(Err(err)) => {
+                    ui.colored_label(ui.visuals().error_fg_color, err);
+                }
+                None if state.runtime.input_stats_task.is_some() => {
+                    ui.label("Loading sprites...");
+                }
+                None => {
+                    ui.label("Add sprites to see statistics");
+                }
+            }
+
+            ui.add_space(8.0);
+            ui.strong("Packed sprites");
+            match &state.runtime.packed_stats {
+                Some(stats) => stats_summary(ui, stats),
+                None => {
+                    ui.label("Pack the atlas to see what was placed");
+                }
+            }
+        });
+
+    egui::CollapsingHeader::new("Area Treemap")
+        .default_open(false)
+        .show(ui, |ui| {
+            let Some(atlases) = &state.runtime.atlases else {
+                ui.label("Pack the atlas to see each sprite's share of the texture budget");
+                return;
+            };
+            ui.label("Each sprite's atlas footprint, including padding and extrusion");
+            area_treemap(ui, &sprite_footprints(atlases, state));
+        });
+}
+
+/// Sprite count and pixel totals
+fn stats_summary(ui: &mut egui::Ui, stats: &SpriteStats) {
+    ui.label(format!("Sprites: {}", stats.count));
+    ui.label(format!(
+        "Source pixels: {}",
+        format_pixels(stats.source_pixels)
+    ));
+    ui.label(format!(
+        "Trimmed pixels: {} ({:.1}% saved)",
+        format_pixels(stats.trimmed_pixels),
+        stats.trim_savings() * 100.0
+    ));
+
+    let side = stats.min_square_side();
+    ui.label(format!(
+        "Min atlas area: {} (~{}x{})",
+        format_pixels(stats.min_atlas_area),
+        side,
+        side
+    ));
+}
+
+/// Size histogram and the largest sprites
+fn size_breakdown(ui: &mut egui::Ui, stats: &SpriteStats) {
+    ui.add_space(4.0);
+    ui.label("Size histogram (max side):");
+    let max_count = stats.histogram.iter().map(|(_, n)| *n).max().unwrap_or(1);
+    for (bucket, count) in &stats.histogram {
+        ui.horizontal(|ui| {
+            ui.monospace(format!("≤{:>5}", bucket));
+            let bar_width = 120.0 * *count as f32 / max_count as f32;
+            let (rect, _) =
+                ui.allocate_exact_size(egui::vec2(bar_width.max(1.0), 10.0), egui::Sense::hover());
+            ui.painter()
+                .rect_filled(rect, 1.0, ui.visuals().selection.bg_fill);
+            ui.label(count.to_string());
+        });
+    }
+
+    ui.add_space(4.0);
+    ui.label("Largest sprites:");
+    for (name, w, h) in &stats.largest {
+        ui.label(format!("  {} ({}x{})", name, w, h));
+    }
+}
+
+/// Atlas area each sprite occupies, including padding, extrusion and block
+/// alignment, largest first
+fn sprite_footprints(atlases: &[Atlas], state: &AppState) -> Vec<(String, u64)> {
+    let config = &state.config;
+    let cell = |dim: u32, spacing: u32| {
+        let raw = dim + spacing * 2;
+        if config.block_align > 1 {
+            raw.next_multiple_of(config.block_align)
+        } else {
+            raw
+        }
+    };
+
+    let mut footprints: Vec<(String, u64)> = atlases
+        .iter()
+        .flat_map(|atlas| &atlas.sprites)
+        .map(|sprite| {
+            let settings = state.runtime.sprite_settings.get(&sprite.name);
+            let padding = settings.and_then(|s| s.padding);
+            let extrude = settings.and_then(|s| s.extrude).unwrap_or(config.extrude);
+            let spacing_x = padding.unwrap_or(config.padding_x) + extrude;
+            let spacing_y = padding.unwrap_or(config.padding_y) + extrude;
+            let area = u64::from(cell(sprite.width, spacing_x))
+                * u64::from(cell(sprite.height, spacing_y));
+            (sprite.name.clone(), area)
+        })
+        .collect();
+    footprints.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    footprints
+}
+
+/// Draw a squarified treemap: one rectangle per sprite, sized by its footprint
+fn area_treemap(ui: &mut egui::Ui, footprints: &[(String, u64)]) {
+    let total: u64 = footprints.iter().map(|(_, area)| area).sum();
+    if total == 0 {
+        return;
+    }
+
+    let width = ui.available_width().max(100.0);
+    let (response, painter) =
+        ui.allocate_painter(egui::vec2(width, width * 0.75), egui::Sense::hover());
+    let bounds = response.rect;
+    let scale = bounds.area() / total as f32;
+    let areas: Vec<f32> = footprints
+        .iter()
+        .map(|(_, area)| *area as f32 * scale)
+        .collect();
+
+    let hover = response.hover_pos();
+    let stroke = egui::Stroke::new(1.0, ui.visuals().extreme_bg_color);
+    for (i, rect) in squarify(&areas, bounds).into_iter().enumerate() {
+        let (name, area) = &footprints[i];
+        painter.rect_filled(rect, 0.0, sprite_color(name));
+        painter.rect_stroke(rect, 0.0, stroke);
+
+        // Label rectangles with room for text
+        if rect.width() > 48.0 && rect.height() > 14.0 {
+            let label = name.rsplit('/').next().unwrap_or(name);
+            painter.with_clip_rect(rect.shrink(2.0)).text(
+                rect.left_top() + egui::vec2(3.0, 2.0),
+                egui::Align2::LEFT_TOP,
+                label,
+                egui::FontId::proportional(11.0),
+                egui::Color32::BLACK,
+            );
+        }
+
+        if hover.is_some_and(|pos| rect.contains(pos)) {
+            painter.rect_stroke(rect, 0.0, egui::Stroke::new(2.0, egui::Color32::WHITE));
+            response.clone().on_hover_text(format!(
+                "{}\n{} ({:.1}% of packed area)",
+                name,
+                format_pixels(*area),
+                *area as f64 / total as f64 * 100.0
+            ));
+        }
+    }
+}
+
+/// Lay out `areas` (sorted largest first, summing to the area of `bounds`)
+/// as a squarified treemap, keeping rectangles as close to square as possible
+fn squarify(areas: &[f32], bounds: egui::Rect) -> Vec<egui::Rect> {
+    // Worst aspect ratio of a row laid along a side of length `side`
+    let worst = |row: &[f32], side: f32| {
+        let sum: f32 = row.iter().sum();
+        row.iter()
+            .map(|&a| (side * side * a / (sum * sum)).max(sum * sum / (side * side * a)))
+            .fold(0.0, f32::max)
+    };
+
+    let mut rects = Vec::with_capacity(areas.len());
+    let mut remaining = bounds;
+    let mut start = 0;
+    while start < areas.len() {
+        let side = remaining.width().min(remaining.height());
+        let mut end = start + 1;
+        while end < areas.len()
+            && worst(&areas[start..=end], side) <= worst(&areas[start..end], side)
+        {
+            end += 1;
+        }
+
+        // Place the row along the shorter side, then shrink the remaining space
+        let row = &areas[start..end];
+        let thickness = row.iter().sum::<f32>() / side;
+        let mut offset = 0.0;
+        if remaining.width() >= remaining.height() {
+            for &area in row {
+                let length = area / thickness;
+                rects.push(egui::Rect::from_min_size(
+                    remaining.min + egui::vec2(0.0, offset),
+                    egui::vec2(thickness, length),
+                ));
+                offset += length;
+            }
+            remaining.min.x += thickness;
+        } else {
+            for &area in row {
+                let length = area / thickness;
+                rects.push(egui::Rect::from_min_size(
+                    remaining.min + egui::vec2(offset, 0.0),
+                    egui::vec2(length, thickness),
+                ));
+                offset += length;
+            }
+            remaining.min.y += thickness;
+        }
+        start = end;
+    }
+    rects
+}
+
+/// Stable pastel color for a sprite name
+fn sprite_color(name: &str) -> egui::Color32 {
+    use std::hash::{Hash, Hasher};
+
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    name.hash(&mut hasher);
+    let hue = (hasher.finish() % 360) as f32 / 360.0;
+    egui::ecolor::Hsva::new(hue, 0.45, 0.9, 1.0).into()
+}
+
+/// Format a pixel count in human-readable form
+fn format_pixels(pixels: u64) -> String {
+    const K: u64 = 1000;
+    const M: u64 = 1000 * 1000;
+
+    if pixels >= M {
+        format!("{:.1} Mpx", pixels as f64 / M as f64)
+    } else if pixels >= K {
+        format!("{:.1} Kpx", pixels as f64 / K as f64)
+    } else {
+        format!("{} px", pixels)
+    }
+}
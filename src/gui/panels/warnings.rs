@@ -0,0 +1,20 @@
+use eframe::egui;
+
+use crate::gui::state::AppState;
+
+/// Warnings from the last pack, shown only when there are some
+pub fn warnings_panel(ui: &mut egui::Ui, state: &AppState) {
+    let warnings = &state.runtime.warnings;
+    if warnings.is_empty() {
+        return;
+    }
+
+    egui::CollapsingHeader::new(format!("Warnings ({})", warnings.len()))
+        .default_open(true)
+        .show(ui, |ui| {
+            let color = ui.visuals().warn_fg_color;
+            for warning in warnings {
+                ui.label(egui::RichText::new(warning.to_string()).color(color));
+            }
+        });
+}
@@ -1,28 +1,62 @@
 use eframe::egui;
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet};
-use std::path::PathBuf;
-use std::sync::Arc;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::mpsc;
+use std::sync::{Arc, Mutex, mpsc};
 use std::time::{Duration, Instant};
 
-use crate::atlas::Atlas;
-use crate::cli::{CompressionLevel, PackMode, PackingHeuristic, ResizeFilter};
+use crate::atlas::{Atlas, SpriteSettings};
+use crate::cli::{
+    BasisMode, Color, CompressionLevel, ExtrudeMode, GodotVersion, GridCell, MipmapFormat,
+    OutputFormat, PackMode, PackingHeuristic, PhaserFrames, ResizeFilter, SourcePaths, SpriteOrder,
+    SpriteSort,
+};
+use crate::config::{CompressConfig, FormatConfig, make_relative};
 use crate::gui::dialogs::PendingAction;
+use crate::gui::thumbnail::ThumbnailCache;
+use crate::output::OutputTemplate;
+use crate::progress::ProgressEvent;
+use crate::sprite::{
+    Animation, GridSlice, NineSlice, SpriteMesh, SpriteOverride, SpriteStats, TrimMargin,
+};
+use crate::warning::Warning;
+
+/// Number of project configs kept in the recent projects list
+const MAX_RECENT_PROJECTS: usize = 10;
+
+/// Config keys an export preset sets. Presets are stored as build profiles
+/// holding only these keys.
+const EXPORT_PRESET_KEYS: [&str; 5] = ["format", "compress", "opaque", "output_dir", "name"];
 
 // ─────────────────────────────────────────────────────────────────────────────
 // GUI-specific enums
 // ─────────────────────────────────────────────────────────────────────────────
 
-/// Output format selection (mirrors CLI subcommands)
-#[derive(Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(rename_all = "lowercase")]
-pub enum OutputFormat {
-    #[default]
-    Json,
-    Godot,
-    Tpsheet,
+/// Formats named by a config `format` value, JSON when it is unset
+pub fn formats_from_config(
+    format: Option<&FormatConfig>,
+) -> Result<BTreeSet<OutputFormat>, String> {
+    let Some(format) = format else {
+        return Ok(BTreeSet::from([OutputFormat::Json]));
+    };
+    let formats = format
+        .names()
+        .into_iter()
+        .map(|name| {
+            OutputFormat::from_config_name(name).ok_or_else(|| {
+                format!(
+                    "Unknown format '{}' in config. Valid: json, godot, tpsheet, libgdx, \
+                     phaser, cocos, template",
+                    name
+                )
+            })
+        })
+        .collect::<Result<BTreeSet<_>, _>>()?;
+    if formats.is_empty() {
+        return Err("The config's format list is empty".to_string());
+    }
+    Ok(formats)
 }
 
 /// Resize mode (mirrors CLI's mutually exclusive resize options)
@@ -35,6 +69,50 @@ pub enum ResizeMode {
     Width(u32),
     #[serde(rename = "scale")]
     Scale(f32),
+    #[serde(rename = "max_width")]
+    MaxWidth(u32),
+    #[serde(rename = "fit")]
+    Fit { width: u32, height: u32 },
+}
+
+/// Which channels the preview displays
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ChannelView {
+    /// Normal RGBA composite
+    #[default]
+    All,
+    /// Color only, alpha forced opaque
+    Rgb,
+    /// Alpha channel as grayscale
+    Alpha,
+    /// Red channel as grayscale
+    Red,
+    /// Green channel as grayscale
+    Green,
+    /// Blue channel as grayscale
+    Blue,
+}
+
+impl ChannelView {
+    pub const ALL: [ChannelView; 6] = [
+        ChannelView::All,
+        ChannelView::Rgb,
+        ChannelView::Alpha,
+        ChannelView::Red,
+        ChannelView::Green,
+        ChannelView::Blue,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ChannelView::All => "RGBA",
+            ChannelView::Rgb => "RGB",
+            ChannelView::Alpha => "Alpha",
+            ChannelView::Red => "Red",
+            ChannelView::Green => "Green",
+            ChannelView::Blue => "Blue",
+        }
+    }
 }
 
 /// State of a thumbnail for an input sprite
@@ -47,10 +125,96 @@ pub enum ThumbnailState {
     Failed,
 }
 
+/// UI preferences persisted across sessions via eframe storage.
+/// Window geometry, panel widths, and collapsing header states are persisted
+/// by eframe/egui themselves.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UiPrefs {
+    pub auto_repack: bool,
+    pub show_debug_overlay: bool,
+    pub show_pixel_grid: bool,
+    pub show_free_space: bool,
+}
+
+impl Default for UiPrefs {
+    fn default() -> Self {
+        Self {
+            auto_repack: true,
+            show_debug_overlay: false,
+            show_pixel_grid: true,
+            show_free_space: false,
+        }
+    }
+}
+
 /// Result of packing operation including atlases and pre-computed PNG sizes
 pub struct PackResult {
     pub atlases: Arc<Vec<Atlas>>,
+    /// Animations from the tags of Aseprite inputs
+    pub animations: Arc<Vec<Animation>>,
+    pub sizes: SizeEstimate,
+    pub sprite_settings: HashMap<String, SpriteSettings>,
+    /// Statistics for the sprites that made it onto the pages
+    pub stats: SpriteStats,
+    /// Problems found while loading and packing that didn't stop it
+    pub warnings: Vec<Warning>,
+}
+
+/// Estimated PNG sizes for the packed atlases
+pub struct SizeEstimate {
+    /// Estimated file size of each page with the current export settings
     pub png_sizes: Vec<usize>,
+    /// Before/after comparison, when PNG compression is enabled
+    pub compression: Option<CompressionEstimate>,
+}
+
+/// What PNG compression saves and costs across all pages
+#[derive(Clone, Copy)]
+pub struct CompressionEstimate {
+    /// Compression level the estimate was made with
+    pub level: CompressionLevel,
+    /// Total size with plain PNG encoding
+    pub uncompressed_bytes: usize,
+    /// Total size after compression
+    pub compressed_bytes: usize,
+    /// Extra encoding time compression adds to an export
+    pub extra_time: Duration,
+}
+
+/// How far the running pack has got, updated from the worker thread
+#[derive(Clone, Copy, Default)]
+pub struct PackProgress {
+    /// Overall completion (0.0 to 1.0)
+    pub fraction: f32,
+    pub stage: &'static str,
+}
+
+impl PackProgress {
+    /// Loading, placing and encoding take roughly 40%, 40% and 20% of the time
+    pub fn update(&mut self, event: ProgressEvent) {
+        let share = |done: usize, total: usize| {
+            #[expect(
+                clippy::cast_precision_loss,
+                reason = "approximation acceptable for a progress bar"
+            )]
+            {
+                done as f32 / total.max(1) as f32
+            }
+        };
+        (self.stage, self.fraction) = match event {
+            ProgressEvent::SpriteLoaded { done, total } => {
+                ("Loading sprites", 0.4 * share(done, total))
+            }
+            ProgressEvent::SpritesPlaced { done, total } => {
+                ("Packing", 0.4 + 0.4 * share(done, total))
+            }
+            ProgressEvent::AtlasRendered { .. } => ("Rendering", self.fraction),
+            ProgressEvent::PngEncoded { done, total } => {
+                ("Estimating sizes", 0.8 + 0.2 * share(done, total))
+            }
+        };
+    }
 }
 
 // ─────────────────────────────────────────────────────────────────────────────
@@ -64,13 +228,18 @@ pub struct BackgroundTask<T> {
 }
 
 /// Types of file dialog operations
-#[derive(Clone, Copy)]
+#[derive(Clone)]
 pub enum FileDialogKind {
     OpenConfig,
     SaveConfigAs,
     AddFiles,
     AddFolder,
     OutputFolder,
+    /// Save one packed sprite as a PNG, optionally restoring trimmed borders
+    ExportSprite {
+        name: String,
+        restore_trim: bool,
+    },
 }
 
 /// Result from a file dialog operation
@@ -127,57 +296,290 @@ pub struct AppState {
 pub struct AppConfig {
     // Input
     pub input_paths: Vec<PathBuf>,
+    // Inputs kept in the list but skipped when packing
+    pub excluded: BTreeSet<PathBuf>,
+    // Glob patterns of input files to skip, kept from the loaded config
+    pub exclude_patterns: Vec<String>,
+    // Sprite name overrides keyed by input path
+    pub sprite_names: BTreeMap<PathBuf, String>,
     pub output_dir: PathBuf,
     pub name: String,
-    pub format: OutputFormat,
+    /// Metadata formats written by each export (never empty)
+    pub formats: BTreeSet<OutputFormat>,
 
     // Pack settings (affect atlas output)
     pub max_width: u32,
     pub max_height: u32,
-    pub padding: u32,
+    pub padding_x: u32,
+    pub padding_y: u32,
     pub pot: bool,
     pub trim: bool,
-    pub trim_margin: u32,
+    pub trim_margin: TrimMargin,
+    /// Trim margin overrides, keyed by sprite name or glob pattern
+    pub trim_margin_overrides: BTreeMap<String, TrimMargin>,
+    pub trim_alpha: u8,
+    pub record_trim: bool,
+    /// Pack each visible layer of Aseprite inputs as its own sprite
+    pub split_layers: bool,
+    /// Skip images that can't be read instead of failing the pack
+    pub skip_errors: bool,
+    /// Byte-identical output across runs and platforms
+    pub deterministic: bool,
     pub extrude: u32,
+    /// What fills the extruded border
+    pub extrude_mode: ExtrudeMode,
+    /// Color of the unused atlas area (None = transparent)
+    pub background_color: Option<Color>,
     pub block_align: u32,
+    pub allow_rotation: bool,
+    pub size_search: bool,
+    pub square: bool,
+    pub fixed_size: bool,
+    pub size_multiple: u32,
+    pub max_sprites_per_page: Option<usize>,
+    /// Uniform grid cells instead of packing (None = pack)
+    pub grid: Option<GridCell>,
+    pub max_total_pixels: Option<u64>,
+    pub max_total_bytes: Option<u64>,
+    pub max_atlases: Option<usize>,
     pub resize_mode: ResizeMode,
     pub resize_filter: ResizeFilter,
+    /// Resize in linear light instead of on sRGB values
+    pub resize_linear: bool,
+    /// Scale factors for suffixed source files
+    pub scale_suffixes: BTreeMap<String, f32>,
+    /// File name suffixes of companion images packed with the same layout
+    pub companions: Vec<String>,
     pub heuristic: PackingHeuristic,
     pub pack_mode: PackMode,
+    /// Sort applied to sprites before packing
+    pub sort: SpriteSort,
+    pub trials: u32,
+    pub seed: u64,
+    /// Sprite names or glob patterns packed first, in this order
+    pub pack_order: Vec<String>,
+    /// Sprite name patterns whose matches share a page, one group each
+    pub keep_together: Vec<String>,
+    /// Extrude overrides, keyed by sprite name or glob pattern
+    pub extrude_overrides: BTreeMap<String, u32>,
+    /// Nine-slice borders, keyed by sprite name or glob pattern
+    pub nine_slice: BTreeMap<String, NineSlice>,
+    /// Sprite sheets to cut into grid cells, keyed by sprite name or glob pattern
+    pub grid_slice: BTreeMap<String, GridSlice>,
+    /// Per-sprite settings, keyed by sprite name or glob pattern
+    pub overrides: BTreeMap<String, SpriteOverride>,
+    /// Per-folder atlas sets of the loaded config, kept as-is for saving
+    pub group_by_folder: bool,
+    /// Packing report path of the loaded config, kept as-is for saving
+    pub report: Option<String>,
+    /// Previous layout path of the loaded config, kept as-is for saving
+    pub stable_layout: Option<String>,
+    /// Build profiles of the loaded config, kept as-is for saving
+    pub profiles: BTreeMap<String, serde_json::Map<String, serde_json::Value>>,
 
     // Export settings (only affect file output, not packing)
     pub compress: Option<CompressionLevel>,
     pub opaque: bool,
+    /// Color opaque output blends semi-transparent pixels over
+    pub matte_color: Option<Color>,
+    pub split_alpha: bool,
+    pub mipmaps: u32,
+    pub mipmap_format: MipmapFormat,
+    pub basis: Option<BasisMode>,
+    /// Output scales, comma-separated (empty = full size only)
+    pub scales: String,
+    pub sprite_order: SpriteOrder,
+    /// Prefix for atlas image paths in metadata (empty = none)
+    pub image_path_prefix: String,
+    /// Atlas image file name template (empty = `{name}_{index}.png`)
+    pub output_template: String,
+    /// Write JSON-based metadata minified instead of pretty-printed
+    pub json_compact: bool,
+    pub source_paths: Option<SourcePaths>,
+    /// Add SHA-256 digests of the layout and images to JSON metadata
+    pub content_hash: bool,
+    /// Add triangulated sprite outlines to JSON metadata
+    pub polygon: bool,
+    /// Pixels a polygon outline may stray from the sprite's pixels
+    pub polygon_tolerance: f32,
+    /// Write generator text chunks into atlas PNGs
+    pub png_metadata: bool,
+    /// Handlebars template for template output, relative to the project folder
+    pub template: String,
+    pub phaser_frames: PhaserFrames,
+    pub godot_version: GodotVersion,
+    /// Godot directory holding the atlas (empty = res://)
+    pub godot_res_path: String,
 }
 
 impl Default for AppConfig {
     fn default() -> Self {
         Self {
             input_paths: Vec::new(),
+            excluded: BTreeSet::new(),
+            exclude_patterns: Vec::new(),
+            sprite_names: BTreeMap::new(),
             output_dir: PathBuf::from("."),
             name: "atlas".to_string(),
-            format: OutputFormat::default(),
+            formats: BTreeSet::from([OutputFormat::default()]),
 
             max_width: 4096,
             max_height: 4096,
-            padding: 1,
+            padding_x: 1,
+            padding_y: 1,
             pot: false,
             trim: true,
-            trim_margin: 0,
+            trim_margin: TrimMargin::default(),
+            trim_margin_overrides: BTreeMap::new(),
+            trim_alpha: 0,
+            record_trim: false,
+            split_layers: false,
+            skip_errors: false,
+            deterministic: false,
             extrude: 0,
+            extrude_mode: ExtrudeMode::Clamp,
+            background_color: None,
             block_align: 0,
+            allow_rotation: false,
+            size_search: false,
+            square: false,
+            fixed_size: false,
+            size_multiple: 0,
+            max_sprites_per_page: None,
+            grid: None,
+            max_total_pixels: None,
+            max_total_bytes: None,
+            max_atlases: None,
             resize_mode: ResizeMode::default(),
             resize_filter: ResizeFilter::Lanczos3,
+            resize_linear: false,
+            scale_suffixes: BTreeMap::new(),
+            companions: Vec::new(),
             heuristic: PackingHeuristic::Best,
             pack_mode: PackMode::Best,
+            trials: 0,
+            seed: 0,
+            pack_order: Vec::new(),
+            keep_together: Vec::new(),
+            extrude_overrides: BTreeMap::new(),
+            nine_slice: BTreeMap::new(),
+            grid_slice: BTreeMap::new(),
+            overrides: BTreeMap::new(),
+            group_by_folder: false,
+            report: None,
+            stable_layout: None,
+            profiles: BTreeMap::new(),
 
             compress: None,
             opaque: false,
+            matte_color: None,
+            split_alpha: false,
+            mipmaps: 0,
+            mipmap_format: MipmapFormat::default(),
+            basis: None,
+            scales: String::new(),
+            sprite_order: SpriteOrder::default(),
+            sort: SpriteSort::default(),
+            image_path_prefix: String::new(),
+            output_template: String::new(),
+            json_compact: false,
+            source_paths: None,
+            content_hash: false,
+            polygon: false,
+            polygon_tolerance: SpriteMesh::DEFAULT_TOLERANCE,
+            png_metadata: false,
+            template: String::new(),
+            phaser_frames: PhaserFrames::default(),
+            godot_version: GodotVersion::default(),
+            godot_res_path: String::new(),
         }
     }
 }
 
 impl AppConfig {
+    /// Current export settings as a build profile, with the output folder
+    /// relative to `config_dir`
+    pub fn export_preset(&self, config_dir: &Path) -> serde_json::Map<String, serde_json::Value> {
+        let compress = self
+            .compress
+            .map(CompressConfig::from)
+            .and_then(|compress| serde_json::to_value(compress).ok())
+            .unwrap_or(serde_json::Value::Null);
+        serde_json::Map::from_iter([
+            (
+                "format".to_string(),
+                serde_json::to_value(self.format_config()).unwrap_or(serde_json::Value::Null),
+            ),
+            ("compress".to_string(), compress),
+            ("opaque".to_string(), self.opaque.into()),
+            (
+                "output_dir".to_string(),
+                make_relative(&self.output_dir, config_dir).into(),
+            ),
+            ("name".to_string(), self.name.clone().into()),
+        ])
+    }
+
+    /// Apply the keys an export preset sets, leaving other settings alone
+    pub fn apply_export_preset(
+        &mut self,
+        preset: &serde_json::Map<String, serde_json::Value>,
+        config_dir: &Path,
+    ) -> Result<(), String> {
+        let invalid = |key: &str, e: serde_json::Error| format!("invalid '{}': {}", key, e);
+        if let Some(format) = preset.get("format") {
+            let format: Option<FormatConfig> =
+                serde_json::from_value(format.clone()).map_err(|e| invalid("format", e))?;
+            self.formats = formats_from_config(format.as_ref())?;
+        }
+        if let Some(compress) = preset.get("compress") {
+            let compress: Option<CompressConfig> =
+                serde_json::from_value(compress.clone()).map_err(|e| invalid("compress", e))?;
+            self.compress = compress.map(|c| c.to_level()).transpose()?;
+        }
+        if let Some(opaque) = preset.get("opaque") {
+            self.opaque =
+                serde_json::from_value(opaque.clone()).map_err(|e| invalid("opaque", e))?;
+        }
+        if let Some(output_dir) = preset.get("output_dir") {
+            let output_dir: String =
+                serde_json::from_value(output_dir.clone()).map_err(|e| invalid("output_dir", e))?;
+            self.output_dir = config_dir.join(output_dir);
+        }
+        if let Some(name) = preset.get("name") {
+            self.name = serde_json::from_value(name.clone()).map_err(|e| invalid("name", e))?;
+        }
+        Ok(())
+    }
+
+    /// The `format` config value for the selected formats
+    pub fn format_config(&self) -> FormatConfig {
+        let formats: Vec<OutputFormat> = self.formats.iter().copied().collect();
+        FormatConfig::from(formats.as_slice())
+    }
+
+    /// Names of the profiles that only set export preset keys
+    pub fn export_preset_names(&self) -> Vec<&str> {
+        self.profiles
+            .iter()
+            .filter(|(_, profile)| {
+                profile
+                    .keys()
+                    .all(|key| EXPORT_PRESET_KEYS.contains(&key.as_str()))
+            })
+            .map(|(name, _)| name.as_str())
+            .collect()
+    }
+
+    /// Template for atlas image file names, the default when the field is empty
+    pub fn output_template(&self) -> Result<OutputTemplate, String> {
+        if self.output_template.trim().is_empty() {
+            Ok(OutputTemplate::default())
+        } else {
+            self.output_template.trim().parse()
+        }
+    }
+
     /// Hash of settings that affect packing output (not export settings)
     /// Used for change detection to trigger auto-repack
     pub fn pack_settings_hash(&self) -> u64 {
@@ -186,14 +588,36 @@ impl AppConfig {
 
         let mut hasher = DefaultHasher::new();
         self.input_paths.hash(&mut hasher);
+        self.excluded.hash(&mut hasher);
+        self.exclude_patterns.hash(&mut hasher);
+        self.sprite_names.hash(&mut hasher);
         self.max_width.hash(&mut hasher);
         self.max_height.hash(&mut hasher);
-        self.padding.hash(&mut hasher);
+        self.padding_x.hash(&mut hasher);
+        self.padding_y.hash(&mut hasher);
         self.pot.hash(&mut hasher);
         self.trim.hash(&mut hasher);
         self.trim_margin.hash(&mut hasher);
+        self.trim_margin_overrides.hash(&mut hasher);
+        self.trim_alpha.hash(&mut hasher);
+        self.record_trim.hash(&mut hasher);
+        self.split_layers.hash(&mut hasher);
+        self.skip_errors.hash(&mut hasher);
+        self.deterministic.hash(&mut hasher);
         self.extrude.hash(&mut hasher);
+        self.extrude_mode.hash(&mut hasher);
+        self.background_color.hash(&mut hasher);
         self.block_align.hash(&mut hasher);
+        self.allow_rotation.hash(&mut hasher);
+        self.size_search.hash(&mut hasher);
+        self.square.hash(&mut hasher);
+        self.fixed_size.hash(&mut hasher);
+        self.size_multiple.hash(&mut hasher);
+        self.max_sprites_per_page.hash(&mut hasher);
+        self.grid.hash(&mut hasher);
+        self.max_total_pixels.hash(&mut hasher);
+        self.max_total_bytes.hash(&mut hasher);
+        self.max_atlases.hash(&mut hasher);
         // Hash resize_mode including inner values (f32 doesn't impl Hash, use bits)
         match self.resize_mode {
             ResizeMode::None => 0u8.hash(&mut hasher),
@@ -205,10 +629,93 @@ impl AppConfig {
                 2u8.hash(&mut hasher);
                 s.to_bits().hash(&mut hasher);
             }
+            ResizeMode::MaxWidth(w) => {
+                3u8.hash(&mut hasher);
+                w.hash(&mut hasher);
+            }
+            ResizeMode::Fit {
+                width: w,
+                height: h,
+            } => {
+                4u8.hash(&mut hasher);
+                w.hash(&mut hasher);
+                h.hash(&mut hasher);
+            }
         }
         self.resize_filter.hash(&mut hasher);
+        self.resize_linear.hash(&mut hasher);
+        for (suffix, factor) in &self.scale_suffixes {
+            suffix.hash(&mut hasher);
+            factor.to_bits().hash(&mut hasher);
+        }
+        self.companions.hash(&mut hasher);
         std::mem::discriminant(&self.heuristic).hash(&mut hasher);
         std::mem::discriminant(&self.pack_mode).hash(&mut hasher);
+        self.trials.hash(&mut hasher);
+        self.sort.hash(&mut hasher);
+        self.seed.hash(&mut hasher);
+        self.pack_order.hash(&mut hasher);
+        self.keep_together.hash(&mut hasher);
+        self.extrude_overrides.hash(&mut hasher);
+        self.nine_slice.hash(&mut hasher);
+        self.grid_slice.hash(&mut hasher);
+        self.overrides.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Hash of settings that decide which sprites load, their trimmed sizes
+    /// and their spacing. Used to refresh the input statistics.
+    pub fn input_settings_hash(&self) -> u64 {
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::{Hash, Hasher};
+
+        let mut hasher = DefaultHasher::new();
+        self.input_paths.hash(&mut hasher);
+        self.excluded.hash(&mut hasher);
+        self.exclude_patterns.hash(&mut hasher);
+        self.sprite_names.hash(&mut hasher);
+        self.padding_x.hash(&mut hasher);
+        self.padding_y.hash(&mut hasher);
+        self.extrude.hash(&mut hasher);
+        self.allow_rotation.hash(&mut hasher);
+        self.trim.hash(&mut hasher);
+        self.trim_margin.hash(&mut hasher);
+        self.trim_margin_overrides.hash(&mut hasher);
+        self.trim_alpha.hash(&mut hasher);
+        self.split_layers.hash(&mut hasher);
+        self.skip_errors.hash(&mut hasher);
+        match self.resize_mode {
+            ResizeMode::None => 0u8.hash(&mut hasher),
+            ResizeMode::Width(w) => {
+                1u8.hash(&mut hasher);
+                w.hash(&mut hasher);
+            }
+            ResizeMode::Scale(s) => {
+                2u8.hash(&mut hasher);
+                s.to_bits().hash(&mut hasher);
+            }
+            ResizeMode::MaxWidth(w) => {
+                3u8.hash(&mut hasher);
+                w.hash(&mut hasher);
+            }
+            ResizeMode::Fit {
+                width: w,
+                height: h,
+            } => {
+                4u8.hash(&mut hasher);
+                w.hash(&mut hasher);
+                h.hash(&mut hasher);
+            }
+        }
+        self.resize_filter.hash(&mut hasher);
+        self.resize_linear.hash(&mut hasher);
+        for (suffix, factor) in &self.scale_suffixes {
+            suffix.hash(&mut hasher);
+            factor.to_bits().hash(&mut hasher);
+        }
+        self.extrude_overrides.hash(&mut hasher);
+        self.grid_slice.hash(&mut hasher);
+        self.overrides.hash(&mut hasher);
         hasher.finish()
     }
 
@@ -220,10 +727,8 @@ impl AppConfig {
 
         let mut hasher = DefaultHasher::new();
         self.opaque.hash(&mut hasher);
-        std::mem::discriminant(&self.compress).hash(&mut hasher);
-        if let Some(level) = &self.compress {
-            std::mem::discriminant(level).hash(&mut hasher);
-        }
+        self.split_alpha.hash(&mut hasher);
+        self.compress.hash(&mut hasher);
         hasher.finish()
     }
 
@@ -234,17 +739,39 @@ impl AppConfig {
 
         let mut hasher = DefaultHasher::new();
         self.input_paths.hash(&mut hasher);
+        self.excluded.hash(&mut hasher);
+        self.exclude_patterns.hash(&mut hasher);
+        self.sprite_names.hash(&mut hasher);
         self.output_dir.hash(&mut hasher);
         self.name.hash(&mut hasher);
-        std::mem::discriminant(&self.format).hash(&mut hasher);
+        self.formats.hash(&mut hasher);
         self.max_width.hash(&mut hasher);
         self.max_height.hash(&mut hasher);
-        self.padding.hash(&mut hasher);
+        self.padding_x.hash(&mut hasher);
+        self.padding_y.hash(&mut hasher);
         self.pot.hash(&mut hasher);
         self.trim.hash(&mut hasher);
         self.trim_margin.hash(&mut hasher);
+        self.trim_margin_overrides.hash(&mut hasher);
+        self.trim_alpha.hash(&mut hasher);
+        self.record_trim.hash(&mut hasher);
+        self.split_layers.hash(&mut hasher);
+        self.skip_errors.hash(&mut hasher);
+        self.deterministic.hash(&mut hasher);
         self.extrude.hash(&mut hasher);
+        self.extrude_mode.hash(&mut hasher);
+        self.background_color.hash(&mut hasher);
         self.block_align.hash(&mut hasher);
+        self.allow_rotation.hash(&mut hasher);
+        self.size_search.hash(&mut hasher);
+        self.square.hash(&mut hasher);
+        self.fixed_size.hash(&mut hasher);
+        self.size_multiple.hash(&mut hasher);
+        self.max_sprites_per_page.hash(&mut hasher);
+        self.grid.hash(&mut hasher);
+        self.max_total_pixels.hash(&mut hasher);
+        self.max_total_bytes.hash(&mut hasher);
+        self.max_atlases.hash(&mut hasher);
         // Hash resize_mode
         match self.resize_mode {
             ResizeMode::None => 0u8.hash(&mut hasher),
@@ -256,20 +783,62 @@ impl AppConfig {
                 2u8.hash(&mut hasher);
                 s.to_bits().hash(&mut hasher);
             }
+            ResizeMode::MaxWidth(w) => {
+                3u8.hash(&mut hasher);
+                w.hash(&mut hasher);
+            }
+            ResizeMode::Fit {
+                width: w,
+                height: h,
+            } => {
+                4u8.hash(&mut hasher);
+                w.hash(&mut hasher);
+                h.hash(&mut hasher);
+            }
         }
         self.resize_filter.hash(&mut hasher);
+        self.resize_linear.hash(&mut hasher);
+        for (suffix, factor) in &self.scale_suffixes {
+            suffix.hash(&mut hasher);
+            factor.to_bits().hash(&mut hasher);
+        }
+        self.companions.hash(&mut hasher);
         std::mem::discriminant(&self.heuristic).hash(&mut hasher);
         std::mem::discriminant(&self.pack_mode).hash(&mut hasher);
+        self.trials.hash(&mut hasher);
+        self.sort.hash(&mut hasher);
+        self.seed.hash(&mut hasher);
+        self.pack_order.hash(&mut hasher);
+        self.keep_together.hash(&mut hasher);
+        self.extrude_overrides.hash(&mut hasher);
+        self.nine_slice.hash(&mut hasher);
+        self.grid_slice.hash(&mut hasher);
+        self.overrides.hash(&mut hasher);
         self.opaque.hash(&mut hasher);
-        // Hash compress
-        match &self.compress {
-            None => 0u8.hash(&mut hasher),
-            Some(CompressionLevel::Level(n)) => {
-                1u8.hash(&mut hasher);
-                n.hash(&mut hasher);
-            }
-            Some(CompressionLevel::Max) => 2u8.hash(&mut hasher),
-        }
+        self.matte_color.hash(&mut hasher);
+        self.split_alpha.hash(&mut hasher);
+        self.mipmaps.hash(&mut hasher);
+        self.mipmap_format.hash(&mut hasher);
+        self.basis.hash(&mut hasher);
+        self.scales.hash(&mut hasher);
+        self.compress.hash(&mut hasher);
+        self.sprite_order.hash(&mut hasher);
+        self.image_path_prefix.hash(&mut hasher);
+        self.output_template.hash(&mut hasher);
+        self.json_compact.hash(&mut hasher);
+        self.source_paths.hash(&mut hasher);
+        self.content_hash.hash(&mut hasher);
+        self.polygon.hash(&mut hasher);
+        self.polygon_tolerance.to_bits().hash(&mut hasher);
+        self.png_metadata.hash(&mut hasher);
+        self.template.hash(&mut hasher);
+        self.phaser_frames.hash(&mut hasher);
+        self.godot_version.hash(&mut hasher);
+        self.godot_res_path.hash(&mut hasher);
+        // JSON values aren't Hash, so hash the profiles' serialized form
+        serde_json::to_string(&self.profiles)
+            .unwrap_or_default()
+            .hash(&mut hasher);
         hasher.finish()
     }
 }
@@ -278,23 +847,45 @@ impl AppConfig {
 pub struct RuntimeState {
     // Packed atlas data
     pub atlases: Option<Arc<Vec<Atlas>>>,
+    // Animations from Aseprite tags, written with the atlases
+    pub animations: Arc<Vec<Animation>>,
     pub selected_atlas: usize,
+    // Summary statistics for the loaded input sprites, kept current as inputs
+    // change, or why they failed to load
+    pub input_stats: Option<Result<SpriteStats, String>>,
+    // Background task loading the inputs for their statistics
+    pub input_stats_task: Option<BackgroundTask<SpriteStats>>,
+    pub last_input_stats_hash: Option<u64>,
+    // Summary statistics for the packed sprites
+    pub packed_stats: Option<SpriteStats>,
+    // Warnings from the last pack
+    pub warnings: Vec<Warning>,
 
     // Texture handles for preview (one per atlas)
     pub atlas_textures: Vec<egui::TextureHandle>,
     // Estimated PNG file sizes (one per atlas)
     pub atlas_png_sizes: Vec<usize>,
+    // Per-sprite spacing overrides used for the current atlases
+    pub sprite_settings: HashMap<String, SpriteSettings>,
+    // Uncompressed vs compressed totals for the current atlases
+    pub compression_estimate: Option<CompressionEstimate>,
     // Background task for re-estimating PNG sizes when export settings change
-    pub size_estimate_task: Option<BackgroundTask<Vec<usize>>>,
+    pub size_estimate_task: Option<BackgroundTask<SizeEstimate>>,
 
     // Preview controls
+    pub channel_view: ChannelView,
+    // Texture for the current non-RGBA channel view: (atlas index, view, texture)
+    pub channel_texture: Option<(usize, ChannelView, egui::TextureHandle)>,
     pub preview_zoom: f32,
     pub preview_offset: egui::Vec2,
     pub needs_fit_to_view: bool,
+    // Full-screen preview with side panels hidden
+    pub preview_fullscreen: bool,
 
     // Status and tasks
     pub status: Status,
     pub pack_task: Option<BackgroundTask<PackResult>>,
+    pub pack_progress: Arc<Mutex<PackProgress>>,
     pub export_task: Option<BackgroundTask<()>>,
 
     // Auto-repack tracking
@@ -305,20 +896,42 @@ pub struct RuntimeState {
 
     // Persisted UI state
     pub last_input_dir: Option<PathBuf>,
+    // Recently opened or saved project configs, most recent first
+    pub recent_projects: Vec<PathBuf>,
 
     // Sprite list filter
     pub sprite_filter: String,
 
     // Debug overlay
     pub show_debug_overlay: bool,
+    // Texel grid overlay at high zoom
+    pub show_pixel_grid: bool,
+    // Shade the free rectangles the packer left on each page
+    pub show_free_space: bool,
+    // Sprite that was right-clicked in the preview (target of its context menu)
+    pub context_sprite: Option<String>,
+    // Sprite clicked in the preview, highlighted and shown in the detail sidebar
+    pub selected_sprite: Option<String>,
+    // Scroll the input list to the selected row on the next frame
+    pub scroll_to_selection: bool,
 
     // Input sprite selection
     pub selected_sprites: HashSet<usize>,
     pub selection_anchor: Option<usize>,
+    // Input row whose sprite name is being edited, with the pending name
+    pub renaming: Option<(PathBuf, String)>,
+    // Pattern being typed into the pack order list
+    pub pack_order_input: String,
+    // Pattern being typed into the extrude overrides table
+    pub extrude_override_input: String,
+    // Name being typed for a new export preset
+    pub preset_name_input: String,
 
     // Thumbnails for input sprites
     pub thumbnails: HashMap<PathBuf, ThumbnailState>,
     pub thumbnail_receiver: Option<mpsc::Receiver<(PathBuf, Option<image::RgbaImage>)>>,
+    // On-disk thumbnail cache (None if the platform has no storage directory)
+    pub thumbnail_cache: Option<ThumbnailCache>,
 
     /// Path to currently loaded .bento config file (None = new unsaved project)
     pub config_path: Option<PathBuf>,
@@ -338,17 +951,29 @@ impl Default for RuntimeState {
     fn default() -> Self {
         Self {
             atlases: None,
+            animations: Arc::new(Vec::new()),
             selected_atlas: 0,
+            input_stats: None,
+            input_stats_task: None,
+            last_input_stats_hash: None,
+            packed_stats: None,
+            warnings: Vec::new(),
 
             atlas_textures: Vec::new(),
             atlas_png_sizes: Vec::new(),
+            sprite_settings: HashMap::new(),
+            compression_estimate: None,
             size_estimate_task: None,
+            channel_view: ChannelView::default(),
+            channel_texture: None,
             preview_zoom: 1.0,
             preview_offset: egui::Vec2::ZERO,
             needs_fit_to_view: false,
+            preview_fullscreen: false,
 
             status: Status::Idle,
             pack_task: None,
+            pack_progress: Arc::default(),
             export_task: None,
 
             auto_repack: true,
@@ -357,16 +982,27 @@ impl Default for RuntimeState {
             pending_repack_at: None,
 
             last_input_dir: None,
+            recent_projects: Vec::new(),
 
             sprite_filter: String::new(),
 
             show_debug_overlay: false,
+            show_pixel_grid: true,
+            show_free_space: false,
+            context_sprite: None,
+            selected_sprite: None,
+            scroll_to_selection: false,
 
             selected_sprites: HashSet::new(),
             selection_anchor: None,
+            renaming: None,
+            pack_order_input: String::new(),
+            extrude_override_input: String::new(),
+            preset_name_input: String::new(),
 
             thumbnails: HashMap::new(),
             thumbnail_receiver: None,
+            thumbnail_cache: None,
 
             config_path: None,
             last_saved_config_hash: None,
@@ -379,6 +1015,41 @@ impl Default for RuntimeState {
 }
 
 impl RuntimeState {
+    /// Apply persisted UI preferences
+    pub fn apply_ui_prefs(&mut self, prefs: UiPrefs) {
+        self.auto_repack = prefs.auto_repack;
+        self.show_debug_overlay = prefs.show_debug_overlay;
+        self.show_pixel_grid = prefs.show_pixel_grid;
+        self.show_free_space = prefs.show_free_space;
+    }
+
+    /// Move a project config to the top of the recent projects list
+    pub fn remember_project(&mut self, path: &Path) {
+        let path = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        self.recent_projects.retain(|p| *p != path);
+        self.recent_projects.insert(0, path);
+        self.recent_projects.truncate(MAX_RECENT_PROJECTS);
+    }
+
+    /// Snapshot UI preferences for persistence
+    pub fn ui_prefs(&self) -> UiPrefs {
+        UiPrefs {
+            auto_repack: self.auto_repack,
+            show_debug_overlay: self.show_debug_overlay,
+            show_pixel_grid: self.show_pixel_grid,
+            show_free_space: self.show_free_space,
+        }
+    }
+
+    /// Directory config paths are relative to: the project folder, or the
+    /// working directory for an unsaved project
+    pub fn config_dir(&self) -> PathBuf {
+        self.config_path
+            .as_deref()
+            .and_then(Path::parent)
+            .map_or_else(|| PathBuf::from("."), Path::to_path_buf)
+    }
+
     /// Check if config has unsaved changes
     pub fn is_config_dirty(&self, config: &AppConfig) -> bool {
         match self.last_saved_config_hash {
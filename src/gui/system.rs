@@ -0,0 +1,75 @@
+use std::io;
+use std::path::Path;
+use std::process::Command;
+
+/// Show the file in the system file manager, selecting it where supported
+pub fn reveal_in_file_manager(path: &Path) -> io::Result<()> {
+    if cfg!(target_os = "macos") {
+        Command::new("open").arg("-R").arg(path).spawn()?;
+    } else if cfg!(target_os = "windows") {
+        let mut select = std::ffi::OsString::from("/select,");
+        select.push(path);
+        Command::new("explorer").arg(select).spawn()?;
+    } else {
+        // xdg-open has no "select" mode, so open the containing folder
+        let dir = path.parent().unwrap_or(path);
+        Command::new("xdg-open").arg(dir).spawn()?;
+    }
+    Ok(())
+}
+
+/// Open the file with its default application (usually the user's image editor)
+pub fn open_in_default_app(path: &Path) -> io::Result<()> {
+    if cfg!(target_os = "macos") {
+        Command::new("open").arg(path).spawn()?;
+    } else if cfg!(target_os = "windows") {
+        // Not `cmd /C start`: cmd would re-parse `&`, `|` and `^` in the file name
+        Command::new("explorer").arg(path).spawn()?;
+    } else {
+        Command::new("xdg-open").arg(path).spawn()?;
+    }
+    Ok(())
+}
+
+/// Show a desktop notification. Best effort: where the platform tool is
+/// missing (e.g. no `notify-send`), this returns the spawn error.
+pub fn show_notification(title: &str, body: &str) -> io::Result<()> {
+    if cfg!(target_os = "macos") {
+        let script = format!(
+            "display notification {} with title {}",
+            applescript_string(body),
+            applescript_string(title)
+        );
+        Command::new("osascript").args(["-e", &script]).spawn()?;
+    } else if cfg!(target_os = "windows") {
+        // Toast via the WinRT API; the app id only labels the toast
+        let script = format!(
+            "$t = [Windows.UI.Notifications.ToastNotificationManager, Windows.UI.Notifications, \
+             ContentType = WindowsRuntime]::GetTemplateContent(1); \
+             $x = $t.GetElementsByTagName('text'); \
+             $x.Item(0).InnerText = {}; $x.Item(1).InnerText = {}; \
+             [Windows.UI.Notifications.ToastNotificationManager]::CreateToastNotifier('Bento')\
+             .Show([Windows.UI.Notifications.ToastNotification]::new($t))",
+            powershell_string(title),
+            powershell_string(body)
+        );
+        Command::new("powershell")
+            .args(["-NoProfile", "-NonInteractive", "-Command", &script])
+            .spawn()?;
+    } else {
+        Command::new("notify-send")
+            .args(["--app-name=Bento", title, body])
+            .spawn()?;
+    }
+    Ok(())
+}
+
+/// Quote a string for AppleScript
+fn applescript_string(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Quote a string for PowerShell (single quotes, with embedded quotes doubled)
+fn powershell_string(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "''"))
+}
@@ -1,13 +1,129 @@
 use image::{ImageReader, RgbaImage, imageops::FilterType};
 use std::path::{Path, PathBuf};
 use std::sync::mpsc;
+use std::time::SystemTime;
+
+use crate::output::sha256_hex;
+use crate::sprite::{first_frame, is_aseprite};
 
 /// Maximum thumbnail dimension (width or height)
 pub const THUMBNAIL_SIZE: u32 = 24;
 
+/// Maximum total size of the on-disk thumbnail cache (bytes)
+pub const THUMBNAIL_CACHE_MAX_BYTES: u64 = 64 * 1024 * 1024;
+
+/// On-disk cache of generated thumbnails, keyed by source path, mtime, and size
+#[derive(Clone)]
+pub struct ThumbnailCache {
+    dir: PathBuf,
+    max_bytes: u64,
+}
+
+impl ThumbnailCache {
+    pub fn new(dir: PathBuf, max_bytes: u64) -> Self {
+        Self { dir, max_bytes }
+    }
+
+    /// Cache in the app's storage directory, if the platform provides one
+    pub fn in_storage_dir(app_id: &str) -> Option<Self> {
+        eframe::storage_dir(app_id)
+            .map(|dir| Self::new(dir.join("thumbnails"), THUMBNAIL_CACHE_MAX_BYTES))
+    }
+
+    /// Cache file for a source image, or None if its metadata can't be read
+    fn entry_path(&self, source: &Path) -> Option<PathBuf> {
+        let meta = std::fs::metadata(source).ok()?;
+        let mtime = meta
+            .modified()
+            .ok()?
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .ok()?;
+
+        // A stable digest, so entries survive toolchain upgrades
+        let mut key = source.as_os_str().as_encoded_bytes().to_vec();
+        key.push(0);
+        key.extend_from_slice(&mtime.as_secs().to_le_bytes());
+        key.extend_from_slice(&mtime.subsec_nanos().to_le_bytes());
+        key.extend_from_slice(&meta.len().to_le_bytes());
+        key.extend_from_slice(&THUMBNAIL_SIZE.to_le_bytes());
+
+        Some(self.dir.join(format!("{}.png", sha256_hex(&key))))
+    }
+
+    fn get(&self, source: &Path) -> Option<RgbaImage> {
+        let entry = self.entry_path(source)?;
+        let img = ImageReader::open(&entry).ok()?.decode().ok()?;
+        // Mark the entry as recently used, so pruning evicts it last
+        if let Err(e) = std::fs::File::options()
+            .append(true)
+            .open(&entry)
+            .and_then(|file| file.set_modified(SystemTime::now()))
+        {
+            log::debug!("Failed to touch thumbnail {}: {}", entry.display(), e);
+        }
+        Some(img.into_rgba8())
+    }
+
+    fn put(&self, source: &Path, thumbnail: &RgbaImage) {
+        let Some(entry) = self.entry_path(source) else {
+            return;
+        };
+        if std::fs::create_dir_all(&self.dir).is_ok()
+            && let Err(e) = thumbnail.save(&entry)
+        {
+            log::debug!("Failed to cache thumbnail {}: {}", entry.display(), e);
+        }
+    }
+
+    /// Delete the least recently used cache entries until the cache fits
+    /// within its size limit. Reads refresh an entry's mtime, so it tracks use.
+    pub fn prune(&self) {
+        let Ok(entries) = std::fs::read_dir(&self.dir) else {
+            return;
+        };
+
+        let mut files: Vec<(SystemTime, u64, PathBuf)> = entries
+            .flatten()
+            .filter_map(|e| {
+                let meta = e.metadata().ok()?;
+                let mtime = meta.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+                meta.is_file().then(|| (mtime, meta.len(), e.path()))
+            })
+            .collect();
+
+        let mut total: u64 = files.iter().map(|(_, len, _)| len).sum();
+        if total <= self.max_bytes {
+            return;
+        }
+
+        // Least recently used first
+        files.sort_by_key(|(mtime, _, _)| *mtime);
+        for (_, len, path) in files {
+            if total <= self.max_bytes {
+                break;
+            }
+            if std::fs::remove_file(&path).is_ok() {
+                total = total.saturating_sub(len);
+            }
+        }
+    }
+
+    /// Remove every cached thumbnail
+    pub fn clear(&self) -> std::io::Result<()> {
+        match std::fs::remove_dir_all(&self.dir) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+}
+
 /// Load a single image and resize to thumbnail size
 fn load_thumbnail(path: &Path) -> Option<RgbaImage> {
-    let img = ImageReader::open(path).ok()?.decode().ok()?.into_rgba8();
+    let img = if is_aseprite(path) {
+        first_frame(path).ok()?
+    } else {
+        ImageReader::open(path).ok()?.decode().ok()?.into_rgba8()
+    };
 
     let (w, h) = img.dimensions();
     if w == 0 || h == 0 {
@@ -30,16 +146,33 @@ fn load_thumbnail(path: &Path) -> Option<RgbaImage> {
     ))
 }
 
-/// Spawn background thread to load thumbnails for given paths
+/// Spawn background thread to load thumbnails for given paths, consulting the
+/// disk cache first when one is provided.
 /// Returns receiver for results
-pub fn spawn_thumbnail_loader(paths: Vec<PathBuf>) -> mpsc::Receiver<(PathBuf, Option<RgbaImage>)> {
+pub fn spawn_thumbnail_loader(
+    paths: Vec<PathBuf>,
+    cache: Option<ThumbnailCache>,
+) -> mpsc::Receiver<(PathBuf, Option<RgbaImage>)> {
     let (tx, rx) = mpsc::channel();
 
     std::thread::spawn(move || {
+        let mut wrote_cache = false;
         for path in paths {
-            let image = load_thumbnail(&path);
+            let cached = cache.as_ref().and_then(|c| c.get(&path));
+            let image = cached.or_else(|| {
+                let thumb = load_thumbnail(&path)?;
+                if let Some(cache) = &cache {
+                    cache.put(&path, &thumb);
+                    wrote_cache = true;
+                }
+                Some(thumb)
+            });
             let _ = tx.send((path, image));
         }
+
+        if wrote_cache && let Some(cache) = &cache {
+            cache.prune();
+        }
     });
 
     rx
@@ -0,0 +1,386 @@
+use std::fmt::Write;
+use std::path::Path;
+
+use clap::ValueEnum;
+use serde::Serialize;
+
+use crate::atlas::AtlasLayout;
+
+/// Summary of a packed sheet, as printed by `bento info`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SheetInfo {
+    pub pages: Vec<PageInfo>,
+    pub sprite_count: usize,
+    /// Summed area of all pages in pixels
+    pub total_pixels: u64,
+    /// Summed sprite area before trimming
+    pub source_pixels: u64,
+    /// Summed sprite area as stored in the atlas
+    pub trimmed_pixels: u64,
+    /// Summed page area not covered by sprites
+    pub wasted_pixels: u64,
+    /// Summed uncompressed RGBA size of all pages
+    pub rgba_bytes: u64,
+    /// Summed size of the page images on disk, once measured
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file_bytes: Option<u64>,
+}
+
+/// Statistics of a pack run, as written by `--report`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackReport {
+    /// Version of bento that wrote the report
+    pub version: &'static str,
+    /// One entry per output scale
+    pub sheets: Vec<SheetReport>,
+}
+
+/// One written sheet of a [`PackReport`]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SheetReport {
+    /// Base name of the sheet's files, e.g. "atlas" or "atlas@0.5x"
+    pub name: String,
+    pub scale: f32,
+    /// Share of source sprite pixels removed by trimming (0.0 to 1.0)
+    pub trim_savings: f64,
+    #[serde(flatten)]
+    pub info: SheetInfo,
+}
+
+impl PackReport {
+    pub fn new(sheets: Vec<SheetReport>) -> Self {
+        Self {
+            version: env!("CARGO_PKG_VERSION"),
+            sheets,
+        }
+    }
+}
+
+impl SheetReport {
+    pub fn new(name: &str, scale: f32, info: SheetInfo) -> Self {
+        Self {
+            name: name.to_string(),
+            scale,
+            trim_savings: info.trim_savings(),
+            info,
+        }
+    }
+}
+
+/// One atlas page of a [`SheetInfo`]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PageInfo {
+    pub index: usize,
+    /// Atlas image path, when known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image: Option<String>,
+    pub width: u32,
+    pub height: u32,
+    /// Share of the page covered by sprites (0.0 to 1.0)
+    pub occupancy: f64,
+    /// Page area not covered by sprites
+    pub wasted_pixels: u64,
+    /// Uncompressed RGBA size of the page
+    pub rgba_bytes: u64,
+    /// Size of the page image on disk, once measured
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file_bytes: Option<u64>,
+    /// Packing heuristic that produced the page, when packed by bento
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub heuristic: Option<String>,
+    /// Sprite ordering that produced the page, when packed by bento
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ordering: Option<String>,
+    pub sprites: Vec<SpriteInfo>,
+}
+
+/// One sprite's placement within a [`PageInfo`]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpriteInfo {
+    pub name: String,
+    pub x: u32,
+    pub y: u32,
+    pub w: u32,
+    pub h: u32,
+    pub rotated: bool,
+    pub trimmed: bool,
+    pub source_w: u32,
+    pub source_h: u32,
+}
+
+impl SheetInfo {
+    /// Summarize pages, each with the image path it was read from (if any)
+    pub fn new(pages: Vec<(AtlasLayout, Option<String>)>) -> Self {
+        let mut pages: Vec<PageInfo> = pages
+            .into_iter()
+            .map(|(layout, image)| PageInfo {
+                index: layout.index,
+                image,
+                width: layout.width,
+                height: layout.height,
+                occupancy: 0.0,
+                wasted_pixels: 0,
+                rgba_bytes: u64::from(layout.width) * u64::from(layout.height) * 4,
+                file_bytes: None,
+                heuristic: layout.packing.as_ref().and_then(|packing| {
+                    packing
+                        .heuristic?
+                        .to_possible_value()
+                        .map(|value| value.get_name().to_string())
+                }),
+                ordering: layout.packing.map(|packing| packing.ordering),
+                sprites: layout
+                    .sprites
+                    .into_iter()
+                    .map(|sprite| SpriteInfo {
+                        trimmed: sprite.trim_info.was_trimmed(),
+                        source_w: sprite.trim_info.source_width,
+                        source_h: sprite.trim_info.source_height,
+                        name: sprite.name,
+                        x: sprite.x,
+                        y: sprite.y,
+                        w: sprite.width,
+                        h: sprite.height,
+                        rotated: sprite.rotated,
+                    })
+                    .collect(),
+            })
+            .collect();
+        // Measured from the sprite rects, so packed sheets and fresh layouts
+        // (whose occupancy includes padding) report the same figure
+        for page in &mut pages {
+            let covered: u64 = page
+                .sprites
+                .iter()
+                .map(|s| u64::from(s.w) * u64::from(s.h))
+                .sum();
+            let area = u64::from(page.width) * u64::from(page.height);
+            page.occupancy = ratio(covered, area);
+            page.wasted_pixels = area.saturating_sub(covered);
+        }
+
+        let sprites = || pages.iter().flat_map(|page| &page.sprites);
+        Self {
+            sprite_count: sprites().count(),
+            total_pixels: pages
+                .iter()
+                .map(|page| u64::from(page.width) * u64::from(page.height))
+                .sum(),
+            source_pixels: sprites()
+                .map(|s| u64::from(s.source_w) * u64::from(s.source_h))
+                .sum(),
+            trimmed_pixels: sprites().map(|s| u64::from(s.w) * u64::from(s.h)).sum(),
+            wasted_pixels: pages.iter().map(|page| page.wasted_pixels).sum(),
+            rgba_bytes: pages.iter().map(|page| page.rgba_bytes).sum(),
+            file_bytes: None,
+            pages,
+        }
+    }
+
+    /// Fill in `file_bytes` from the page images, resolving their paths
+    /// against `dir`. The total is only set when every image could be read.
+    pub fn measure_files(&mut self, dir: &Path) {
+        for page in &mut self.pages {
+            page.file_bytes = page
+                .image
+                .as_ref()
+                .and_then(|image| std::fs::metadata(dir.join(image)).ok())
+                .map(|metadata| metadata.len());
+        }
+        self.file_bytes = self.pages.iter().map(|page| page.file_bytes).sum();
+    }
+
+    /// Human-readable report, listing every sprite's rect with `with_sprites`
+    pub fn to_text(&self, with_sprites: bool) -> String {
+        let mut text = String::new();
+        for page in &self.pages {
+            let _ = writeln!(
+                text,
+                "Page {}: {}{}x{}, {} sprites, {:.1}% occupied",
+                page.index,
+                page.image
+                    .as_ref()
+                    .map(|image| format!("{} ", image))
+                    .unwrap_or_default(),
+                page.width,
+                page.height,
+                page.sprites.len(),
+                page.occupancy * 100.0
+            );
+            if !with_sprites {
+                continue;
+            }
+            for sprite in &page.sprites {
+                let _ = write!(
+                    text,
+                    "  {} {},{} {}x{}",
+                    sprite.name, sprite.x, sprite.y, sprite.w, sprite.h
+                );
+                if sprite.rotated {
+                    text.push_str(" rotated");
+                }
+                if sprite.trimmed {
+                    let _ = write!(
+                        text,
+                        " (trimmed from {}x{})",
+                        sprite.source_w, sprite.source_h
+                    );
+                }
+                text.push('\n');
+            }
+        }
+
+        let _ = writeln!(
+            text,
+            "Total: {} page(s), {} sprites, {} pixels",
+            self.pages.len(),
+            self.sprite_count,
+            self.total_pixels
+        );
+        let _ = writeln!(
+            text,
+            "Trimming: {} -> {} sprite pixels ({:.1}% saved)",
+            self.source_pixels,
+            self.trimmed_pixels,
+            self.trim_savings() * 100.0
+        );
+        text
+    }
+
+    /// Share of source sprite pixels removed by trimming (0.0 to 1.0)
+    pub fn trim_savings(&self) -> f64 {
+        ratio(
+            self.source_pixels.saturating_sub(self.trimmed_pixels),
+            self.source_pixels,
+        )
+    }
+}
+
+#[expect(
+    clippy::cast_precision_loss,
+    reason = "approximation acceptable for a percentage"
+)]
+fn ratio(part: u64, whole: u64) -> f64 {
+    if whole == 0 {
+        return 0.0;
+    }
+    part as f64 / whole as f64
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::sprite::{PackedSprite, TrimInfo};
+
+    fn sprite(name: &str, x: u32, width: u32, trim_info: TrimInfo) -> PackedSprite {
+        PackedSprite {
+            name: name.to_string(),
+            path: std::path::PathBuf::from(name),
+            x,
+            y: 0,
+            width,
+            height: trim_info.trimmed_height,
+            trim_info,
+            atlas_index: 0,
+            input_index: 0,
+            content_bounds: None,
+            nine_slice: None,
+            pivot: None,
+            rotated: false,
+        }
+    }
+
+    #[test]
+    fn test_sheet_info() {
+        let trimmed = TrimInfo {
+            offset_x: 2,
+            offset_y: 2,
+            source_width: 16,
+            source_height: 16,
+            trimmed_width: 8,
+            trimmed_height: 8,
+        };
+        let layout = AtlasLayout {
+            index: 0,
+            width: 32,
+            height: 16,
+            sprites: vec![
+                sprite("a.png", 0, 16, TrimInfo::untrimmed(16, 16)),
+                sprite("b.png", 16, 8, trimmed),
+            ],
+            occupancy: 1.0,
+            packing: None,
+        };
+        let info = SheetInfo::new(vec![(layout, Some("atlas.png".to_string()))]);
+
+        assert_eq!(info.sprite_count, 2);
+        assert_eq!(info.total_pixels, 512);
+        assert_eq!(info.source_pixels, 512);
+        assert_eq!(info.trimmed_pixels, 320);
+        assert!((info.trim_savings() - 0.375).abs() < 1e-9);
+        assert_eq!(info.wasted_pixels, 192);
+        assert_eq!(info.rgba_bytes, 2048);
+        assert_eq!(info.pages[0].heuristic, None);
+
+        let text = info.to_text(true);
+        assert!(text.starts_with("Page 0: atlas.png 32x16, 2 sprites, 62.5% occupied\n"));
+        assert!(text.contains("  b.png 16,0 8x8 (trimmed from 16x16)\n"));
+        assert!(text.contains("(37.5% saved)"));
+        assert!(!info.to_text(false).contains("a.png 0,0"));
+    }
+
+    #[test]
+    fn test_pack_report() {
+        let dir = std::env::temp_dir().join(format!("bento_report_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("atlas.png"), [0u8; 100]).unwrap();
+
+        let layout = AtlasLayout {
+            index: 0,
+            width: 16,
+            height: 16,
+            sprites: vec![sprite("a.png", 0, 16, TrimInfo::untrimmed(16, 8))],
+            occupancy: 0.5,
+            packing: Some(crate::atlas::PackingChoice {
+                heuristic: Some(crate::cli::PackingHeuristic::BottomLeft),
+                ordering: "area".to_string(),
+            }),
+        };
+        let mut info = SheetInfo::new(vec![(layout, Some("atlas.png".to_string()))]);
+        info.measure_files(&dir);
+        assert_eq!(info.pages[0].file_bytes, Some(100));
+        assert_eq!(info.file_bytes, Some(100));
+
+        let report = PackReport::new(vec![SheetReport::new("atlas", 1.0, info)]);
+        let json = serde_json::to_value(&report).unwrap();
+        let sheet = &json["sheets"][0];
+        assert_eq!(sheet["name"], "atlas");
+        assert_eq!(sheet["wastedPixels"], 128);
+        assert_eq!(sheet["fileBytes"], 100);
+        assert_eq!(sheet["pages"][0]["heuristic"], "bottom-left");
+        assert_eq!(sheet["pages"][0]["ordering"], "area");
+
+        // Missing images leave the total unknown
+        let mut info = SheetInfo::new(vec![(
+            AtlasLayout {
+                index: 0,
+                width: 4,
+                height: 4,
+                sprites: Vec::new(),
+                occupancy: 0.0,
+                packing: None,
+            },
+            Some("missing.png".to_string()),
+        )]);
+        info.measure_files(&dir);
+        assert_eq!(info.file_bytes, None);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,125 @@
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+
+use crate::config::{BentoConfig, FormatConfig};
+use crate::sprite::is_supported_image;
+
+/// Output directory of scaffolded configs, skipped when looking for sprites
+pub const DEFAULT_OUTPUT_DIR: &str = "output";
+
+/// Folders that hold build output or tooling rather than source sprites
+const SKIPPED_DIRS: &[&str] = &[
+    DEFAULT_OUTPUT_DIR,
+    "build",
+    "dist",
+    "node_modules",
+    "target",
+];
+
+/// Starter config for the images found under `dir`, writing atlases to
+/// `output/` under a name taken from the directory
+pub fn scaffold_config(dir: &Path) -> Result<BentoConfig> {
+    let input = infer_inputs(dir)?;
+    if input.is_empty() {
+        bail!("no images found under {}", dir.display());
+    }
+
+    let name = fs::canonicalize(dir)
+        .ok()
+        .and_then(|dir| dir.file_name().map(|n| n.to_string_lossy().into_owned()))
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| "atlas".to_string());
+
+    Ok(BentoConfig {
+        input,
+        output_dir: DEFAULT_OUTPUT_DIR.to_string(),
+        name,
+        format: Some(FormatConfig::One("json".to_string())),
+        ..BentoConfig::default()
+    })
+}
+
+/// Glob patterns, relative to `dir`, covering every folder under it that
+/// directly holds images: one `folder/*.ext` per folder and image extension.
+/// Hidden folders and common build output folders are skipped.
+pub fn infer_inputs(dir: &Path) -> Result<Vec<String>> {
+    let mut patterns = BTreeSet::new();
+    collect_patterns(dir, "", &mut patterns)?;
+    Ok(patterns.into_iter().collect())
+}
+
+fn collect_patterns(dir: &Path, prefix: &str, patterns: &mut BTreeSet<String>) -> Result<()> {
+    let entries = fs::read_dir(dir)
+        .with_context(|| format!("failed to read directory: {}", dir.display()))?;
+    for entry in entries {
+        let path = entry
+            .with_context(|| format!("failed to read directory: {}", dir.display()))?
+            .path();
+        let Some(file_name) = path.file_name().map(|n| n.to_string_lossy().into_owned()) else {
+            continue;
+        };
+        if path.is_dir() {
+            if file_name.starts_with('.') || SKIPPED_DIRS.contains(&file_name.as_str()) {
+                continue;
+            }
+            collect_patterns(&path, &format!("{}{}/", prefix, file_name), patterns)?;
+        } else if is_supported_image(&path)
+            && let Some(ext) = path.extension()
+        {
+            // Glob matching is case-sensitive, so `*.PNG` gets its own pattern
+            patterns.insert(format!("{}*.{}", prefix, ext.to_string_lossy()));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scaffold_config_infers_image_folders() {
+        let dir = std::env::temp_dir().join("bento_init_scaffold");
+        let _ = fs::remove_dir_all(&dir);
+        for folder in ["chars/hero", "ui", ".git", "output", "docs"] {
+            fs::create_dir_all(dir.join(folder)).unwrap();
+        }
+        for file in [
+            "chars/hero/idle.png",
+            "chars/hero/run.aseprite",
+            "ui/button.png",
+            "ui/panel.PNG",
+            "logo.jpg",
+            ".git/icon.png",
+            "output/atlas_0.png",
+            "docs/readme.md",
+        ] {
+            fs::write(dir.join(file), b"").unwrap();
+        }
+
+        let config = scaffold_config(&dir).unwrap();
+        assert_eq!(
+            config.input,
+            [
+                "*.jpg",
+                "chars/hero/*.aseprite",
+                "chars/hero/*.png",
+                "ui/*.PNG",
+                "ui/*.png"
+            ]
+        );
+        assert_eq!(config.output_dir, "output");
+        assert_eq!(config.name, "bento_init_scaffold");
+
+        fs::remove_dir_all(dir.join("chars")).unwrap();
+        fs::remove_dir_all(dir.join("ui")).unwrap();
+        fs::remove_file(dir.join("logo.jpg")).unwrap();
+        assert!(scaffold_config(&dir).is_err());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
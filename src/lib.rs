@@ -1,14 +1,23 @@
 pub mod atlas;
 pub mod cli;
 pub mod config;
+pub mod diff;
+pub mod doctor;
 pub mod error;
 #[cfg(feature = "gui")]
 pub mod gui;
+pub mod info;
+pub mod init;
 pub mod output;
 pub mod packing;
+pub mod progress;
 pub mod sprite;
+pub mod unpack;
+pub mod warning;
 
-pub use atlas::{Atlas, AtlasBuilder};
+pub use atlas::{Atlas, AtlasBuilder, AtlasLayout, SpriteSettings};
 pub use cli::{CliArgs, Command, CommonArgs, PackingHeuristic};
 pub use error::BentoError;
-pub use sprite::{PackedSprite, SourceSprite, TrimInfo};
+pub use progress::{ProgressCallback, ProgressEvent};
+pub use sprite::{PackedSprite, SourceSprite, TrimInfo, sprites_from_images};
+pub use warning::Warning;
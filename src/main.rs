@@ -1,130 +1,1320 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
-use std::path::PathBuf;
+use std::io::Write;
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
-use clap::Parser;
-use log::info;
+use clap::{CommandFactory, Parser};
+use log::{error, info, warn};
 
-use bento::atlas::AtlasBuilder;
+use bento::atlas::{Atlas, AtlasBuilder, AtlasLayout, mip_level_count};
 use bento::cli::{
-    CliArgs, Command, CommonArgs, CompressionLevel, PackMode, PackingHeuristic, ResizeFilter,
+    AppendArgs, BasisMode, BuildArgs, CliArgs, Color, Command, CommonArgs, CompressionLevel,
+    ErrorFormat, ExtrudeMode, GodotVersion, GridCell, MipmapFormat, OutputFormat, PackMode,
+    PackingHeuristic, PhaserFrames, ResizeFilter, ResizeFit, SourcePaths, SpriteOrder, SpriteSort,
 };
-use bento::config::{CompressConfig, LoadedConfig, ResizeConfig};
+use bento::config::{
+    BentoConfig, FormatConfig, LoadedConfig, ResizeConfig, compile_exclude, compile_keep_together,
+    compile_overrides, compile_pack_order, is_toml_config, make_relative, resolve_sprite_settings,
+    save_config,
+};
+use bento::diff::SheetDiff;
+use bento::doctor::{
+    Finding, Severity, check_config_inputs, check_config_values, check_input_paths,
+    check_output_dir, check_sprites,
+};
+use bento::error::{error_json, exit_code};
+use bento::info::{PackReport, SheetInfo, SheetReport};
+use bento::init::scaffold_config;
 use bento::output::{
-    atlas_png_filename, save_atlas_image, write_godot_resources, write_json, write_tpsheet,
+    BasisuEncoder, OutputTemplate, add_png_text, generator_text, read_sheet, save_alpha_mask,
+    save_atlas_image, save_encoded, save_image, scaled_base_name, sha256_hex,
+    template_output_filename, write_cocos, write_godot_resources, write_json, write_ktx2,
+    write_libgdx, write_phaser, write_template, write_tpsheet,
+};
+use bento::sprite::{
+    Animation, GridSlice, LoadOptions, NineSlice, SourceSprite, SpriteMesh, SpriteOverride,
+    TrimMargin, apply_nine_slices, apply_sprite_overrides, group_by_folder, load_animations,
+    load_sprites, read_input_list, try_load_sprites,
 };
-use bento::sprite::load_sprites;
+use bento::unpack::{read_atlases, unpack_sheet};
 
 #[allow(clippy::print_stderr)]
 fn main() {
-    if let Err(e) = run() {
-        // Use eprintln instead of error! because logger may not be initialized
-        // (e.g., config loading fails before logger init)
-        eprintln!("Error: {:#}", e);
-        std::process::exit(1);
-    }
-}
-
-fn run() -> Result<()> {
     // Launch GUI if no arguments provided and gui feature is enabled
     #[cfg(feature = "gui")]
     if std::env::args().len() == 1 {
-        return bento::gui::run(None);
+        if let Err(e) = bento::gui::run(None) {
+            eprintln!("Error: {:#}", e);
+            std::process::exit(exit_code(&e));
+        }
+        return;
     }
 
     let cli = CliArgs::parse();
+    if let Err(e) = run(&cli) {
+        // Use eprintln instead of error! because logger may not be initialized
+        // (e.g., config loading fails before logger init)
+        match cli.error_format {
+            ErrorFormat::Text => eprintln!("Error: {:#}", e),
+            ErrorFormat::Json => eprintln!("{}", error_json(&e)),
+        }
+        std::process::exit(exit_code(&e));
+    }
+}
 
+fn run(cli: &CliArgs) -> Result<()> {
     // Handle GUI command
     #[cfg(feature = "gui")]
-    if matches!(cli.command, Command::Gui) {
-        return bento::gui::run(None);
+    if let Command::Gui { path } = &cli.command {
+        if let Some(path) = path
+            && !path.exists()
+        {
+            anyhow::bail!("path does not exist: {}", path.display());
+        }
+        return bento::gui::run(path.clone());
     }
 
     // Extract common args from subcommand
     let args = match &cli.command {
-        Command::Json(args) | Command::Godot(args) | Command::Tpsheet(args) => args.clone(),
+        Command::Json(args)
+        | Command::Godot(args)
+        | Command::Tpsheet(args)
+        | Command::Libgdx(args)
+        | Command::Phaser(args)
+        | Command::Cocos(args)
+        | Command::Template(args) => resolve_input_lists(args)?,
+        Command::Build(build_args) => {
+            return build(&BuildArgs {
+                common: resolve_input_lists(&build_args.common)?,
+                ..build_args.clone()
+            });
+        }
+        Command::Append(append_args) => return append(append_args),
+        Command::Doctor(args) => return doctor(&resolve_input_lists(args)?),
+        Command::Validate { config } => return validate(config),
+        Command::Info {
+            path,
+            sprites,
+            json,
+        } => return sheet_info(path, *sprites, *json),
+        Command::Diff { old, new, json } => return diff_sheets(old, new, *json),
+        Command::Unpack {
+            metadata,
+            image,
+            output,
+            no_restore_trim,
+        } => {
+            init_logging(false);
+            let count = unpack_sheet(metadata, image.as_deref(), output, !no_restore_trim)?;
+            info!("Wrote {} sprites to {}", count, output.display());
+            return Ok(());
+        }
+        Command::Init {
+            dir,
+            output,
+            force,
+            interactive,
+        } => {
+            init_logging(false);
+            return init_project(dir, output.as_deref(), *force, *interactive);
+        }
+        Command::Completions { shell } => {
+            clap_complete::generate(
+                *shell,
+                &mut CliArgs::command(),
+                "bento",
+                &mut std::io::stdout(),
+            );
+            return Ok(());
+        }
         #[cfg(feature = "gui")]
-        Command::Gui => unreachable!(),
+        Command::Gui { .. } => unreachable!(),
     };
 
     // Load config if specified and merge with CLI args
-    let merged = merge_config_with_args(&args)?;
+    let loaded_config = load_config(&args)?;
+    let merged = merge_config_with_args(&args, loaded_config.as_ref())?;
 
-    // Initialize logging
-    env_logger::Builder::new()
-        .filter_level(if merged.verbose {
-            log::LevelFilter::Debug
-        } else {
-            log::LevelFilter::Info
-        })
-        .format_timestamp(None)
-        .format_target(false)
-        .init();
+    init_logging(merged.verbose);
+
+    info!("Bento texture packer v{}", env!("CARGO_PKG_VERSION"));
+
+    // The subcommand's own format, then any others asked for with --format
+    let formats = unique_formats(
+        cli.command
+            .output_format()
+            .into_iter()
+            .chain(args.format.clone()),
+    );
+    pack(&formats, &args, &merged)
+}
+
+/// `bento build`: pack the chosen profiles of a config file, or the config
+/// itself when it has none, each in the format its settings name
+fn build(build_args: &BuildArgs) -> Result<()> {
+    let args = &build_args.common;
+    let Some(loaded_config) = load_config(args)? else {
+        anyhow::bail!("build needs a config file: pass --config");
+    };
+
+    init_logging(args.verbose);
 
     info!("Bento texture packer v{}", env!("CARGO_PKG_VERSION"));
 
+    let profiles = if build_args.profile.is_empty() {
+        loaded_config.profile_names()
+    } else {
+        build_args.profile.clone()
+    };
+    if profiles.is_empty() {
+        return build_config(args, &loaded_config);
+    }
+    // Resolve every profile first so a typo fails before anything is written
+    let configs = profiles
+        .iter()
+        .map(|name| loaded_config.profile(name))
+        .collect::<Result<Vec<_>>>()?;
+    for (name, config) in profiles.iter().zip(&configs) {
+        info!("Building profile '{}'", name);
+        build_config(args, config).with_context(|| format!("profile '{}' failed", name))?;
+    }
+    Ok(())
+}
+
+/// Pack a config in the output formats it names (`--format` overrides them),
+/// JSON by default
+fn build_config(args: &CommonArgs, loaded_config: &LoadedConfig) -> Result<()> {
+    let merged = merge_config_with_args(args, Some(loaded_config))?;
+    let formats = if !args.format.is_empty() {
+        unique_formats(args.format.clone())
+    } else {
+        config_formats(loaded_config)?.unwrap_or_else(|| vec![OutputFormat::Json])
+    };
+    pack(&formats, args, &merged)
+}
+
+/// Output formats a config's `format` names, if it sets one
+fn config_formats(loaded_config: &LoadedConfig) -> Result<Option<Vec<OutputFormat>>> {
+    let Some(format) = &loaded_config.config.format else {
+        return Ok(None);
+    };
+    let formats = format
+        .names()
+        .into_iter()
+        .map(|name| {
+            OutputFormat::from_config_name(name).ok_or_else(|| {
+                anyhow::anyhow!(
+                    "unknown format '{}' in config file. Valid values: json, godot, tpsheet, \
+                     libgdx, phaser, cocos, template",
+                    name
+                )
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    if formats.is_empty() {
+        anyhow::bail!("the config's format list is empty");
+    }
+    Ok(Some(unique_formats(formats)))
+}
+
+/// Formats in first-seen order, without repeats
+fn unique_formats(formats: impl IntoIterator<Item = OutputFormat>) -> Vec<OutputFormat> {
+    let mut unique = Vec::new();
+    for format in formats {
+        if !unique.contains(&format) {
+            unique.push(format);
+        }
+    }
+    unique
+}
+
+/// Load, pack and write the sprites of merged settings in a format command's output
+fn pack(formats: &[OutputFormat], args: &CommonArgs, merged: &MergedConfig) -> Result<()> {
+    check_formats(formats, merged)?;
+    if merged.stable_layout.is_some() {
+        if merged.group_by_folder {
+            anyhow::bail!(
+                "a stable layout is one sheet, so it can't be combined with group_by_folder"
+            );
+        }
+        if merged.grid.is_some() {
+            anyhow::bail!("a grid already keeps sprites in place; drop --stable-layout");
+        }
+    }
+
     // Create output directory if it doesn't exist
-    if !merged.output.exists() {
+    if !merged.output.exists() && !args.dry_run {
         fs::create_dir_all(&merged.output)?;
     }
 
-    // Load sprites
-    let sprites = load_sprites(
-        &merged.input,
-        merged.trim,
-        merged.trim_margin,
-        merged.resize_width,
-        merged.resize_scale,
-        merged.resize_filter,
-        None, // No cancellation for CLI
-        merged.base_dir.as_deref(),
-        merged.filename_only,
+    let sprites = load_project_sprites(merged)?;
+    let animations = if formats.contains(&OutputFormat::Json) {
+        load_animations(&project_inputs(merged), &load_options(merged)?)?
+    } else {
+        Vec::new()
+    };
+
+    let mut sheets = Vec::new();
+    if merged.group_by_folder {
+        for (folder, sprites) in group_by_folder(sprites) {
+            let name = folder.unwrap_or_else(|| merged.name.clone());
+            info!("Packing group '{}' ({} sprites)", name, sprites.len());
+            // Only animations whose frames all landed in this group
+            let names: HashSet<&str> = sprites.iter().map(|s| s.name.as_str()).collect();
+            let animations: Vec<Animation> = animations
+                .iter()
+                .filter(|animation| {
+                    animation
+                        .frames
+                        .iter()
+                        .all(|frame| names.contains(frame.name.as_str()))
+                })
+                .cloned()
+                .collect();
+            pack_sheet(
+                formats,
+                args,
+                merged,
+                sprites,
+                &animations,
+                &name,
+                &mut sheets,
+            )?;
+        }
+    } else {
+        pack_sheet(
+            formats,
+            args,
+            merged,
+            sprites,
+            &animations,
+            &merged.name,
+            &mut sheets,
+        )?;
+    }
+
+    if args.dry_run {
+        info!("Dry run complete, no files written");
+        return Ok(());
+    }
+
+    write_report(merged, sheets)?;
+    info!("Done!");
+
+    Ok(())
+}
+
+/// Reject format combinations the merged settings can't write
+fn check_formats(formats: &[OutputFormat], merged: &MergedConfig) -> Result<()> {
+    if formats.contains(&OutputFormat::Template) && merged.template.is_none() {
+        anyhow::bail!(
+            "template output needs a template file: pass --template or set \"template\" in the config"
+        );
+    }
+    // Every scale would write the same {sprite}.tres files
+    if formats.contains(&OutputFormat::Godot) && merged.scales.len() > 1 {
+        anyhow::bail!("godot output writes one .tres per sprite, so it takes a single scale");
+    }
+    Ok(())
+}
+
+/// Write the pack report, if one was asked for
+fn write_report(merged: &MergedConfig, sheets: Vec<SheetReport>) -> Result<()> {
+    if let Some(path) = &merged.report {
+        let mut report = serde_json::to_string_pretty(&PackReport::new(sheets))?;
+        report.push('\n');
+        if let Some(dir) = path.parent()
+            && !dir.as_os_str().is_empty()
+        {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, report)
+            .with_context(|| format!("failed to write report {}", path.display()))?;
+        info!("Wrote report {}", path.display());
+    }
+    Ok(())
+}
+
+/// Pack one set of sprites into atlases named `name` and write them at every
+/// output scale, adding their statistics to `sheets`. Dry runs only report
+/// the layout.
+fn pack_sheet(
+    formats: &[OutputFormat],
+    args: &CommonArgs,
+    merged: &MergedConfig,
+    sprites: Vec<SourceSprite>,
+    animations: &[Animation],
+    name: &str,
+    sheets: &mut Vec<SheetReport>,
+) -> Result<()> {
+    let builder = atlas_builder(merged, &sprites)?;
+    for warning in builder.warnings(&sprites) {
+        warn!("{}", warning);
+    }
+    let previous = match &merged.stable_layout {
+        Some(path) => previous_layout(path)?,
+        None => None,
+    };
+
+    // Dry run: report the layout without rendering or writing anything
+    if args.dry_run && previous.is_none() {
+        report_dry_runs(merged, &builder.layout(sprites)?, name);
+        return Ok(());
+    }
+
+    // Build atlases once; other scales are rendered from the same layout
+    let atlases = match &previous {
+        Some(previous) => builder.build_stable(previous, sprites)?,
+        None => builder.build(sprites)?,
+    };
+    // Kept sprites are only placed by rendering them
+    if args.dry_run {
+        let layouts: Vec<AtlasLayout> = atlases.iter().map(Atlas::layout).collect();
+        report_dry_runs(merged, &layouts, name);
+        return Ok(());
+    }
+    write_scales(
+        formats, merged, &builder, &atlases, animations, name, sheets,
+    )
+}
+
+/// Write built atlases at every output scale, adding their statistics to `sheets`
+fn write_scales(
+    formats: &[OutputFormat],
+    merged: &MergedConfig,
+    builder: &AtlasBuilder,
+    atlases: &[Atlas],
+    animations: &[Animation],
+    name: &str,
+    sheets: &mut Vec<SheetReport>,
+) -> Result<()> {
+    for &scale in &merged.scales {
+        let name = scaled_base_name(name, scale);
+        if scale == 1.0 {
+            write_sheet(formats, merged, builder, atlases, animations, &name)?;
+            sheets.push(sheet_report(merged, atlases, &name, scale));
+        } else {
+            let scaled: Vec<_> = atlases
+                .iter()
+                .map(|atlas| {
+                    builder.scale_atlas(
+                        atlas,
+                        scale,
+                        merged.resize_filter.to_image_filter(),
+                        merged.resize_linear,
+                    )
+                })
+                .collect();
+            write_sheet(formats, merged, builder, &scaled, animations, &name)?;
+            sheets.push(sheet_report(merged, &scaled, &name, scale));
+        }
+    }
+    Ok(())
+}
+
+/// `bento append`: pack the sprites an existing sheet doesn't have yet into
+/// its free space, then onto new pages, leaving packed sprites where they are
+fn append(append_args: &AppendArgs) -> Result<()> {
+    let args = resolve_input_lists(&append_args.common)?;
+    let loaded_config = load_config(&args)?;
+    let merged = merge_config_with_args(&args, loaded_config.as_ref())?;
+
+    init_logging(merged.verbose);
+
+    info!("Bento texture packer v{}", env!("CARGO_PKG_VERSION"));
+
+    if merged.group_by_folder {
+        anyhow::bail!("append adds to one sheet, so it can't be combined with group_by_folder");
+    }
+    if merged.grid.is_some() {
+        anyhow::bail!("append can't keep a fixed grid; repack the sheet instead");
+    }
+    if !merged.companions.is_empty() {
+        anyhow::bail!("append can't keep companion atlases in step; repack the sheet instead");
+    }
+
+    let layout_path = append_args
+        .layout
+        .clone()
+        .unwrap_or_else(|| merged.output.join(format!("{}.json", merged.name)));
+    // Keep writing the sheet's own format unless told otherwise
+    let formats = if !args.format.is_empty() {
+        unique_formats(args.format.clone())
+    } else if let Some(formats) = loaded_config
+        .as_ref()
+        .map(config_formats)
+        .transpose()?
+        .flatten()
+    {
+        formats
+    } else if layout_path.extension().is_some_and(|ext| ext == "tpsheet") {
+        vec![OutputFormat::Tpsheet]
+    } else {
+        vec![OutputFormat::Json]
+    };
+    check_formats(&formats, &merged)?;
+
+    let existing = read_atlases(&layout_path).with_context(|| {
+        format!(
+            "failed to load the sheet to append to: {}",
+            layout_path.display()
+        )
+    })?;
+    let packed: HashMap<&str, &Atlas> = existing
+        .iter()
+        .flat_map(|atlas| atlas.sprites.iter().map(move |s| (s.name.as_str(), atlas)))
+        .collect();
+
+    let mut sprites = load_project_sprites(&merged)?;
+    sprites.retain(|sprite| {
+        let Some(atlas) = packed.get(sprite.name.as_str()) else {
+            return true;
+        };
+        if atlas
+            .find(&sprite.name)
+            .is_some_and(|kept| atlas.sprite_image(kept, false) != sprite.image)
+        {
+            warn!(
+                "'{}' changed since it was packed; keeping the packed version",
+                sprite.name
+            );
+        }
+        false
+    });
+    if sprites.is_empty() {
+        info!("Every sprite is already in {}", layout_path.display());
+        return Ok(());
+    }
+    info!("Appending {} new sprites", sprites.len());
+
+    // Kept sprites need their own padding and extrusion to reserve their cells
+    let sprite_settings = resolve_sprite_settings(
+        &merged.extrude_overrides,
+        &merged.overrides,
+        packed
+            .keys()
+            .copied()
+            .chain(sprites.iter().map(|s| s.name.as_str())),
     )?;
-    info!("Loaded {} sprites", sprites.len());
+    let builder = atlas_builder(&merged, &sprites)?.sprite_settings(sprite_settings);
+    for warning in builder.warnings(&sprites) {
+        warn!("{}", warning);
+    }
+    let page_count = existing.len();
+    let atlases = builder.append(existing, sprites)?;
+    if atlases.len() > page_count {
+        info!("Added {} atlas pages", atlases.len() - page_count);
+    }
 
-    // Build atlases
-    let atlases = AtlasBuilder::new(merged.max_width, merged.max_height)
-        .padding(merged.padding)
-        .heuristic(merged.heuristic)
-        .power_of_two(merged.pot)
-        .extrude(merged.extrude)
-        .block_align(merged.block_align)
-        .pack_mode(merged.pack_mode)
-        .build(sprites)?;
+    if args.dry_run {
+        let layouts: Vec<AtlasLayout> = atlases.iter().map(Atlas::layout).collect();
+        report_dry_runs(&merged, &layouts, &merged.name);
+        info!("Dry run complete, no files written");
+        return Ok(());
+    }
+
+    if !merged.output.exists() {
+        fs::create_dir_all(&merged.output)?;
+    }
+    let animations = if formats.contains(&OutputFormat::Json) {
+        load_animations(&project_inputs(&merged), &load_options(&merged)?)?
+    } else {
+        Vec::new()
+    };
+    let mut sheets = Vec::new();
+    write_scales(
+        &formats,
+        &merged,
+        &builder,
+        &atlases,
+        &animations,
+        &merged.name,
+        &mut sheets,
+    )?;
+    write_report(&merged, sheets)?;
+    info!("Done!");
+
+    Ok(())
+}
+
+/// Page layouts of the sheet a stable layout starts from, or None when it
+/// hasn't been written yet
+fn previous_layout(path: &Path) -> Result<Option<Vec<AtlasLayout>>> {
+    if !path.exists() {
+        info!(
+            "No previous layout at {}; packing from scratch",
+            path.display()
+        );
+        return Ok(None);
+    }
+    let pages = read_sheet(path)
+        .with_context(|| format!("failed to load the stable layout {}", path.display()))?;
+    Ok(Some(pages.into_iter().map(|page| page.layout).collect()))
+}
+
+/// Statistics of one written sheet, with the sizes of its page images
+fn sheet_report(merged: &MergedConfig, atlases: &[Atlas], name: &str, scale: f32) -> SheetReport {
+    let total = atlases.len();
+    let mut info = SheetInfo::new(
+        atlases
+            .iter()
+            .map(|atlas| {
+                let image = merged
+                    .output_template
+                    .png_filename(name, atlas.index, total);
+                (atlas.layout(), Some(image))
+            })
+            .collect(),
+    );
+    info.measure_files(&merged.output);
+    SheetReport::new(name, scale, info)
+}
+
+/// The arguments bento was run with, quoting any that hold spaces
+fn command_line() -> String {
+    let args: Vec<String> = std::env::args()
+        .skip(1)
+        .map(|arg| {
+            if arg.is_empty() || arg.contains(char::is_whitespace) {
+                format!("{:?}", arg)
+            } else {
+                arg
+            }
+        })
+        .collect();
+    format!("bento {}", args.join(" "))
+}
+
+/// Write the atlas images and format-specific metadata for one output scale
+fn write_sheet(
+    formats: &[OutputFormat],
+    merged: &MergedConfig,
+    builder: &AtlasBuilder,
+    atlases: &[Atlas],
+    animations: &[Animation],
+    name: &str,
+) -> Result<()> {
+    let png_text = merged
+        .png_metadata
+        .then(|| generator_text(merged.config_hash.clone(), Some(command_line())));
 
     // Save atlas images
     let total = atlases.len();
-    for atlas in &atlases {
-        let path = merged
-            .output
-            .join(atlas_png_filename(&merged.name, atlas.index, total));
-        save_atlas_image(atlas, &path, merged.opaque, merged.compress)?;
+    for atlas in atlases {
+        let path = merged.output.join(merged.output_template.png_filename(
+            name,
+            atlas.index,
+            total,
+        ));
+        save_atlas_image(
+            atlas,
+            &path,
+            merged.opaque || merged.split_alpha,
+            // Split alpha keeps color unblended so the mask can restore it
+            merged.matte_color.filter(|_| !merged.split_alpha),
+            merged.compress,
+        )?;
+        #[cfg(test)]
+        tests::record_encode(&path);
+        if let Some(text) = &png_text {
+            add_png_text(&path, text)?;
+        }
         info!("Saved {}", path.display());
+
+        if merged.split_alpha {
+            let alpha_path = merged.output.join(merged.output_template.alpha_filename(
+                name,
+                atlas.index,
+                total,
+            ));
+            save_alpha_mask(atlas, &alpha_path, merged.compress)?;
+            info!("Saved {}", alpha_path.display());
+        }
+
+        // Every page gets each companion, blank where none of its sprites had one
+        for suffix in &merged.companions {
+            let companion_path = merged
+                .output
+                .join(
+                    merged
+                        .output_template
+                        .companion_filename(name, atlas.index, total, suffix),
+                );
+            let blank;
+            let image = match atlas.companions.get(suffix) {
+                Some(image) => image,
+                None => {
+                    blank = image::RgbaImage::new(atlas.width, atlas.height);
+                    &blank
+                }
+            };
+            save_image(image, &companion_path, false, None, merged.compress)?;
+            info!("Saved {}", companion_path.display());
+        }
+
+        if merged.mipmaps > 0 {
+            let mips = builder.mipmaps(atlas, merged.mipmaps);
+            match merged.mipmap_format {
+                MipmapFormat::Png => {
+                    for (level, image) in (1..).zip(&mips) {
+                        let mip_path = merged.output.join(merged.output_template.mip_filename(
+                            name,
+                            atlas.index,
+                            total,
+                            level,
+                        ));
+                        save_image(
+                            image,
+                            &mip_path,
+                            // Same encoding as the base page, so levels match it
+                            merged.opaque || merged.split_alpha,
+                            merged.matte_color.filter(|_| !merged.split_alpha),
+                            merged.compress,
+                        )?;
+                        #[cfg(test)]
+                        tests::record_encode(&mip_path);
+                        info!("Saved {}", mip_path.display());
+                    }
+                }
+                MipmapFormat::Ktx2 => {
+                    let ktx2_path = merged.output.join(merged.output_template.ktx2_filename(
+                        name,
+                        atlas.index,
+                        total,
+                    ));
+                    write_ktx2(&atlas.image, &mips, &ktx2_path)?;
+                    info!("Saved {} ({} mip levels)", ktx2_path.display(), mips.len());
+                }
+            }
+        }
+
+        if let Some(mode) = merged.basis {
+            let ktx2_path = merged.output.join(merged.output_template.ktx2_filename(
+                name,
+                atlas.index,
+                total,
+            ));
+            let encoder = BasisuEncoder::new(mode, merged.mipmaps > 0);
+            save_encoded(&atlas.image, &ktx2_path, merged.opaque, &encoder)?;
+            info!("Saved {}", ktx2_path.display());
+        }
     }
 
     // Write format-specific output
-    match &cli.command {
-        Command::Json(_) => {
-            write_json(&atlases, &merged.output, &merged.name)?;
-            info!("Generated {}.json", merged.name);
+    for format in formats {
+        match format {
+            OutputFormat::Json => {
+                write_json(
+                    atlases,
+                    &merged.output,
+                    name,
+                    &merged.output_template,
+                    merged.sprite_order,
+                    merged.json_compact,
+                    merged.split_alpha,
+                    merged.image_path_prefix.as_deref(),
+                    merged.source_paths.map(|style| {
+                        (
+                            style,
+                            merged.config_dir.as_deref().unwrap_or(Path::new(".")),
+                        )
+                    }),
+                    merged.polygon,
+                    merged.content_hash,
+                    animations,
+                )?;
+                info!("Generated {}.json", name);
+            }
+            OutputFormat::Godot => {
+                write_godot_resources(
+                    atlases,
+                    &merged.output,
+                    name,
+                    &merged.output_template,
+                    merged.godot_res_path.as_deref(),
+                    merged.godot_version,
+                )?;
+                let sprites = atlases.iter().flat_map(|a| &a.sprites);
+                info!(
+                    "Generated {} Godot .tres files ({} StyleBoxTexture)",
+                    sprites.clone().count(),
+                    sprites.filter(|s| s.nine_slice.is_some()).count()
+                );
+            }
+            OutputFormat::Tpsheet => {
+                write_tpsheet(
+                    atlases,
+                    &merged.output,
+                    name,
+                    &merged.output_template,
+                    merged.sprite_order,
+                    merged.json_compact,
+                    merged.image_path_prefix.as_deref(),
+                )?;
+                info!("Generated {}.tpsheet", name);
+            }
+            OutputFormat::Libgdx => {
+                write_libgdx(
+                    atlases,
+                    &merged.output,
+                    name,
+                    &merged.output_template,
+                    merged.sprite_order,
+                    merged.image_path_prefix.as_deref(),
+                )?;
+                info!("Generated {}.atlas", name);
+            }
+            OutputFormat::Phaser => {
+                write_phaser(
+                    atlases,
+                    &merged.output,
+                    name,
+                    &merged.output_template,
+                    merged.sprite_order,
+                    merged.json_compact,
+                    merged.phaser_frames,
+                    merged.image_path_prefix.as_deref(),
+                )?;
+                info!("Generated {}.phaser.json", name);
+            }
+            OutputFormat::Cocos => {
+                write_cocos(
+                    atlases,
+                    &merged.output,
+                    name,
+                    &merged.output_template,
+                    merged.sprite_order,
+                    merged.image_path_prefix.as_deref(),
+                )?;
+                info!("Generated {} .plist file(s)", atlases.len());
+            }
+            OutputFormat::Template => {
+                let template = merged
+                    .template
+                    .as_deref()
+                    .context("template output needs a template file")?;
+                write_template(
+                    atlases,
+                    &merged.output,
+                    name,
+                    &merged.output_template,
+                    merged.sprite_order,
+                    merged.image_path_prefix.as_deref(),
+                    template,
+                )?;
+                info!("Generated {}", template_output_filename(name, template));
+            }
         }
-        Command::Godot(_) => {
-            write_godot_resources(&atlases, &merged.output, &merged.name, None)?;
+    }
+
+    Ok(())
+}
+
+/// Log the files a dry run would write at every output scale
+fn report_dry_runs(merged: &MergedConfig, layouts: &[AtlasLayout], name: &str) {
+    for &scale in &merged.scales {
+        report_dry_run(merged, layouts, &scaled_base_name(name, scale), scale);
+    }
+}
+
+/// Log the files a dry run would write for one output scale
+fn report_dry_run(merged: &MergedConfig, layouts: &[AtlasLayout], name: &str, scale: f32) {
+    let total = layouts.len();
+    for layout in layouts {
+        let image = merged.output.join(merged.output_template.png_filename(
+            name,
+            layout.index,
+            total,
+        ));
+        if scale == 1.0 {
+            info!(
+                "Would write {} ({}x{}, {} sprites)",
+                image.display(),
+                layout.width,
+                layout.height,
+                layout.sprites.len()
+            );
+        } else {
             info!(
-                "Generated {} Godot .tres files",
-                atlases.iter().map(|a| a.sprites.len()).sum::<usize>()
+                "Would write {} ({}x of {}x{}, {} sprites)",
+                image.display(),
+                scale,
+                layout.width,
+                layout.height,
+                layout.sprites.len()
             );
         }
-        Command::Tpsheet(_) => {
-            write_tpsheet(&atlases, &merged.output, &merged.name)?;
-            info!("Generated {}.tpsheet", merged.name);
+        if merged.split_alpha {
+            info!(
+                "Would write {}",
+                merged
+                    .output
+                    .join(
+                        merged
+                            .output_template
+                            .alpha_filename(name, layout.index, total)
+                    )
+                    .display()
+            );
+        }
+        for suffix in &merged.companions {
+            info!(
+                "Would write {}",
+                merged
+                    .output
+                    .join(merged.output_template.companion_filename(
+                        name,
+                        layout.index,
+                        total,
+                        suffix
+                    ))
+                    .display()
+            );
+        }
+        let mip_levels = mip_level_count(layout.width, layout.height, merged.mipmaps);
+        match merged.mipmap_format {
+            MipmapFormat::Png => {
+                for level in 1..=mip_levels as usize {
+                    info!(
+                        "Would write {}",
+                        merged
+                            .output
+                            .join(merged.output_template.mip_filename(
+                                name,
+                                layout.index,
+                                total,
+                                level
+                            ))
+                            .display()
+                    );
+                }
+            }
+            MipmapFormat::Ktx2 if mip_levels > 0 => info!(
+                "Would write {} ({} mip levels)",
+                merged
+                    .output
+                    .join(
+                        merged
+                            .output_template
+                            .ktx2_filename(name, layout.index, total)
+                    )
+                    .display(),
+                mip_levels
+            ),
+            MipmapFormat::Ktx2 => {}
+        }
+        if merged.basis.is_some() {
+            info!(
+                "Would write {}",
+                merged
+                    .output
+                    .join(
+                        merged
+                            .output_template
+                            .ktx2_filename(name, layout.index, total)
+                    )
+                    .display()
+            );
         }
-        #[cfg(feature = "gui")]
-        Command::Gui => unreachable!(),
     }
+}
 
-    info!("Done!");
+/// Sprite loading options for the merged settings
+fn load_options(merged: &MergedConfig) -> Result<LoadOptions> {
+    Ok(LoadOptions {
+        trim: merged.trim,
+        trim_margin: merged.trim_margin,
+        trim_margin_overrides: compile_overrides(
+            &merged.trim_margin_overrides,
+            "trim_margin_overrides",
+        )?,
+        trim_alpha: merged.trim_alpha,
+        grid_slices: compile_overrides(&merged.grid_slice, "grid_slice")?,
+        overrides: compile_overrides(&merged.overrides, "overrides")?,
+        record_trim: merged.record_trim,
+        resize_width: merged.resize_width,
+        resize_scale: merged.resize_scale,
+        resize_max_width: merged.resize_max_width,
+        resize_fit: merged.resize_fit,
+        resize_filter: merged.resize_filter,
+        resize_linear: merged.resize_linear,
+        scale_suffixes: merged
+            .scale_suffixes
+            .iter()
+            .map(|(suffix, &factor)| (suffix.clone(), factor))
+            .collect(),
+        companions: merged.companions.clone(),
+        cancel_token: None, // No cancellation for CLI
+        progress: None,
+        base_dir: merged.base_dir.clone(),
+        filename_only: merged.filename_only,
+        exclude: merged.exclude_patterns.clone(),
+        split_layers: merged.split_layers,
+        skip_errors: merged.skip_errors,
+        deterministic: merged.deterministic,
+        sort: merged.sort,
+    })
+}
+
+/// Atlas builder for the merged settings, with per-sprite overrides resolved
+fn atlas_builder(merged: &MergedConfig, sprites: &[SourceSprite]) -> Result<AtlasBuilder> {
+    let sprite_settings = resolve_sprite_settings(
+        &merged.extrude_overrides,
+        &merged.overrides,
+        sprites.iter().map(|s| s.name.as_str()),
+    )?;
+
+    Ok(AtlasBuilder::new(merged.max_width, merged.max_height)
+        .padding_x(merged.padding_x)
+        .padding_y(merged.padding_y)
+        .heuristic(merged.heuristic)
+        .power_of_two(merged.pot)
+        .extrude(merged.extrude)
+        .extrude_mode(merged.extrude_mode)
+        .background(merged.background_color.map(Into::into))
+        .sprite_settings(sprite_settings)
+        .block_align(merged.block_align)
+        .allow_rotation(merged.allow_rotation)
+        .size_search(merged.size_search)
+        .square(merged.square)
+        .fixed_size(merged.fixed_size)
+        .size_multiple(merged.size_multiple)
+        .pack_mode(merged.pack_mode)
+        .trials(merged.trials)
+        .seed(merged.seed)
+        .pack_order(merged.pack_order.clone())
+        .keep_together(merged.keep_together.clone())
+        .max_sprites_per_page(merged.max_sprites_per_page)
+        .grid(merged.grid)
+        .max_total_pixels(merged.max_total_pixels)
+        .max_total_bytes(merged.max_total_bytes)
+        .max_atlases(merged.max_atlases))
+}
+
+/// Merged inputs without excluded files
+fn project_inputs(merged: &MergedConfig) -> Vec<PathBuf> {
+    merged
+        .input
+        .iter()
+        .filter(|path| !merged.excluded.contains(*path))
+        .cloned()
+        .collect()
+}
+
+/// Load and name the sprites of the merged inputs. Excluded files are skipped
+/// up front; files inside input directories are filtered after loading.
+fn load_project_sprites(merged: &MergedConfig) -> Result<Vec<SourceSprite>> {
+    let mut sprites = load_sprites(&project_inputs(merged), &load_options(merged)?)?;
+    apply_sprite_overrides(&mut sprites, &merged.excluded, &merged.names)?;
+    apply_nine_slices(
+        &mut sprites,
+        &compile_overrides(&merged.nine_slice, "nine_slice")?,
+    );
+    info!("Loaded {} sprites", sprites.len());
+    Ok(sprites)
+}
 
+/// `bento info`: summarize a packed sheet, or lay out a `.bento` project
+/// without writing anything, and print the report to stdout
+fn sheet_info(path: &Path, with_sprites: bool, json: bool) -> Result<()> {
+    init_logging(false);
+
+    let pages = if path.extension().is_some_and(|ext| ext == "bento") || is_toml_config(path) {
+        let args = CommonArgs {
+            config: Some(path.to_path_buf()),
+            ..CommonArgs::default()
+        };
+        let loaded_config = load_config(&args)?;
+        let merged = merge_config_with_args(&args, loaded_config.as_ref())?;
+        let sprites = load_project_sprites(&merged)?;
+        let layouts = atlas_builder(&merged, &sprites)?.layout(sprites)?;
+        let total = layouts.len();
+        layouts
+            .into_iter()
+            .map(|layout| {
+                let image = merged
+                    .output_template
+                    .png_filename(&merged.name, layout.index, total);
+                (layout, Some(image))
+            })
+            .collect()
+    } else {
+        read_sheet(path)?
+            .into_iter()
+            .map(|page| (page.layout, Some(page.image)))
+            .collect()
+    };
+
+    let info = SheetInfo::new(pages);
+    let report = if json {
+        let mut report = serde_json::to_string_pretty(&info)?;
+        report.push('\n');
+        report
+    } else {
+        info.to_text(with_sprites)
+    };
+    std::io::stdout().write_all(report.as_bytes())?;
+    Ok(())
+}
+
+/// `bento diff`: print how the sprite placements and page sizes of two
+/// packed sheets differ
+fn diff_sheets(old: &Path, new: &Path, json: bool) -> Result<()> {
+    init_logging(false);
+
+    let layouts = |path: &Path| -> Result<Vec<AtlasLayout>> {
+        Ok(read_sheet(path)?
+            .into_iter()
+            .map(|page| page.layout)
+            .collect())
+    };
+    let diff = SheetDiff::new(&layouts(old)?, &layouts(new)?);
+    let report = if json {
+        let mut report = serde_json::to_string_pretty(&diff)?;
+        report.push('\n');
+        report
+    } else {
+        diff.to_text()
+    };
+    std::io::stdout().write_all(report.as_bytes())?;
+    Ok(())
+}
+
+/// Formats `bento init` offers; the template format also needs a template file
+const INIT_FORMATS: &[&str] = &["json", "godot", "tpsheet", "libgdx", "phaser", "cocos"];
+
+/// `bento init`: write a starter config for the image folders found under `dir`
+fn init_project(dir: &Path, output: Option<&Path>, force: bool, interactive: bool) -> Result<()> {
+    let path = output.map_or_else(|| dir.join("project.bento"), Path::to_path_buf);
+    if path.exists() && !force {
+        anyhow::bail!(
+            "{} already exists; pass --force to overwrite it",
+            path.display()
+        );
+    }
+
+    let mut config = scaffold_config(dir)?;
+    if interactive {
+        customize_config(&mut config)?;
+    }
+
+    // Scaffolded paths are relative to `dir`, but config paths are relative
+    // to the config file
+    let config_dir = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    if let (Ok(dir), Ok(config_dir)) = (fs::canonicalize(dir), fs::canonicalize(config_dir))
+        && dir != config_dir
+    {
+        let rebase = |p: &str| make_relative(&dir.join(p), &config_dir);
+        config.input = config.input.iter().map(|p| rebase(p)).collect();
+        config.output_dir = rebase(&config.output_dir);
+    }
+
+    // --force writes a fresh file; saving over a TOML config would update it in place
+    if path.exists() {
+        fs::remove_file(&path).with_context(|| format!("failed to replace {}", path.display()))?;
+    }
+    save_config(&config, &path)?;
+    info!(
+        "Wrote {} with {} input pattern(s)",
+        path.display(),
+        config.input.len()
+    );
+    info!("Pack it with: bento build --config {}", path.display());
+    Ok(())
+}
+
+/// Ask on the terminal which inferred inputs to keep, and where and in which
+/// format to write the atlas
+#[allow(clippy::print_stderr)]
+fn customize_config(config: &mut BentoConfig) -> Result<()> {
+    let ask = |question: &str, default: &str| -> Result<String> {
+        eprint!("{} [{}]: ", question, default);
+        let mut answer = String::new();
+        std::io::stdin()
+            .read_line(&mut answer)
+            .context("failed to read answer from stdin")?;
+        let answer = answer.trim();
+        Ok(if answer.is_empty() { default } else { answer }.to_string())
+    };
+
+    let mut input = Vec::new();
+    for pattern in std::mem::take(&mut config.input) {
+        let answer = ask(&format!("Include {}?", pattern), "Y")?;
+        if !matches!(answer.to_lowercase().as_str(), "n" | "no") {
+            input.push(pattern);
+        }
+    }
+    if input.is_empty() {
+        anyhow::bail!("no inputs selected");
+    }
+    config.input = input;
+
+    config.output_dir = ask("Output directory", &config.output_dir)?;
+    config.name = ask("Atlas name", &config.name)?;
+    let answer = ask(
+        &format!("Formats, comma-separated ({})", INIT_FORMATS.join(", ")),
+        "json",
+    )?;
+    let formats = answer
+        .split(',')
+        .map(str::trim)
+        .map(|name| {
+            OutputFormat::from_config_name(name)
+                .filter(|_| INIT_FORMATS.contains(&name))
+                .ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "unknown format '{}'; expected one of {}",
+                        name,
+                        INIT_FORMATS.join(", ")
+                    )
+                })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    config.format = Some(FormatConfig::from(unique_formats(formats).as_slice()));
+    Ok(())
+}
+
+/// Initialize logging. Logs go to stderr so stdout stays free for piping.
+fn init_logging(verbose: bool) {
+    env_logger::Builder::new()
+        .filter_level(if verbose {
+            log::LevelFilter::Debug
+        } else {
+            log::LevelFilter::Info
+        })
+        .target(env_logger::Target::Stderr)
+        .format_timestamp(None)
+        .format_target(false)
+        .init();
+}
+
+/// `bento doctor`: report every problem with the inputs, config and output
+/// directory at once, each with a suggested fix
+fn doctor(args: &CommonArgs) -> Result<()> {
+    init_logging(false);
+
+    let mut findings = Vec::new();
+    let merged = load_config(args).and_then(|loaded_config| {
+        let merged = merge_config_with_args(args, loaded_config.as_ref())?;
+        Ok((loaded_config, merged))
+    });
+    match merged {
+        Err(e) => findings.push(Finding::error(
+            format!("{:#}", e),
+            "Fix the config file or command-line options",
+        )),
+        Ok((loaded_config, merged)) => {
+            if args.input.is_empty()
+                && let Some(loaded) = &loaded_config
+            {
+                findings.extend(check_config_inputs(loaded));
+            }
+
+            let input = project_inputs(&merged);
+            findings.extend(check_input_paths(&input));
+            findings.extend(check_output_dir(&merged.output));
+
+            let existing: Vec<&PathBuf> = input.iter().filter(|path| path.exists()).collect();
+            let (mut sprites, load_errors) = try_load_sprites(&existing, &load_options(&merged)?)?;
+            for e in load_errors {
+                findings.push(Finding::error(
+                    format!("{:#}", e),
+                    "Re-export the image, or skip it with \"exclude\" in the config",
+                ));
+            }
+            info!("Checked {} images", sprites.len());
+
+            // Duplicate names and empty inputs are reported by check_sprites
+            let _ = apply_sprite_overrides(&mut sprites, &merged.excluded, &merged.names);
+            findings.extend(check_sprites(&sprites, &atlas_builder(&merged, &sprites)?));
+        }
+    }
+
+    report_findings(findings)
+}
+
+/// `bento validate`: check a config file, or each of its profiles as
+/// `bento build` would pack them, without loading any images
+fn validate(config_path: &Path) -> Result<()> {
+    init_logging(false);
+
+    let loaded = LoadedConfig::load(config_path)
+        .with_context(|| format!("failed to load config: {}", config_path.display()))?;
+    let profiles = loaded.profile_names();
+    let configs = if profiles.is_empty() {
+        vec![(None, loaded)]
+    } else {
+        profiles
+            .into_iter()
+            .map(|name| {
+                let config = loaded.profile(&name)?;
+                Ok((Some(name), config))
+            })
+            .collect::<Result<Vec<_>>>()?
+    };
+
+    // Problems shared by several profiles are reported once, naming each of them
+    let mut checked: Vec<(Finding, Vec<String>)> = Vec::new();
+    for (profile, config) in &configs {
+        let mut findings = check_config_values(&config.config);
+        findings.extend(check_config_inputs(config));
+        findings.extend(check_output_dir(&config.resolve_output_dir()));
+        for finding in findings {
+            let index = match checked
+                .iter()
+                .position(|(f, _)| f.problem == finding.problem)
+            {
+                Some(index) => index,
+                None => {
+                    checked.push((finding, Vec::new()));
+                    checked.len() - 1
+                }
+            };
+            checked[index].1.extend(profile.clone());
+        }
+    }
+    info!("Checked {}", config_path.display());
+
+    let findings = checked
+        .into_iter()
+        .map(|(mut finding, profiles)| {
+            if !profiles.is_empty() {
+                finding.problem = format!("{} (profile {})", finding.problem, profiles.join(", "));
+            }
+            finding
+        })
+        .collect();
+    report_findings(findings)
+}
+
+/// Log findings, worst first, and fail if any is an error
+fn report_findings(mut findings: Vec<Finding>) -> Result<()> {
+    findings.sort_by_key(|f| std::cmp::Reverse(f.severity));
+    for finding in &findings {
+        match finding.severity {
+            Severity::Error => error!("{}", finding.problem),
+            Severity::Warning => warn!("{}", finding.problem),
+        }
+        info!("  fix: {}", finding.fix);
+    }
+
+    let errors = findings
+        .iter()
+        .filter(|f| f.severity == Severity::Error)
+        .count();
+    if errors > 0 {
+        anyhow::bail!(
+            "found {} problem(s) and {} warning(s)",
+            errors,
+            findings.len() - errors
+        );
+    }
+    info!("No problems found ({} warning(s))", findings.len());
     Ok(())
 }
 
@@ -137,42 +1327,174 @@ struct MergedConfig {
     name: String,
     max_width: u32,
     max_height: u32,
-    padding: u32,
+    padding_x: u32,
+    padding_y: u32,
     trim: bool,
-    trim_margin: u32,
+    trim_margin: TrimMargin,
+    trim_margin_overrides: BTreeMap<String, TrimMargin>,
+    trim_alpha: u8,
+    record_trim: bool,
     heuristic: PackingHeuristic,
     opaque: bool,
+    /// Background blended under semi-transparent pixels of opaque output
+    matte_color: Option<Color>,
+    split_alpha: bool,
+    mipmaps: u32,
+    mipmap_format: MipmapFormat,
+    basis: Option<BasisMode>,
+    /// Output scales; the full-size layout is rendered at each
+    scales: Vec<f32>,
     pot: bool,
     extrude: u32,
+    extrude_mode: ExtrudeMode,
+    /// Color of the unused atlas area (None = transparent)
+    background_color: Option<Color>,
     block_align: u32,
+    allow_rotation: bool,
+    size_search: bool,
+    square: bool,
+    fixed_size: bool,
+    size_multiple: u32,
+    max_sprites_per_page: Option<usize>,
+    grid: Option<GridCell>,
+    max_total_pixels: Option<u64>,
+    max_total_bytes: Option<u64>,
+    max_atlases: Option<usize>,
     verbose: bool,
     resize_width: Option<u32>,
     resize_scale: Option<f32>,
+    resize_max_width: Option<u32>,
+    resize_fit: Option<ResizeFit>,
     resize_filter: ResizeFilter,
+    resize_linear: bool,
+    /// Scale factors for suffixed source files (from config file)
+    scale_suffixes: BTreeMap<String, f32>,
+    /// Companion image suffixes, from the config followed by the CLI
+    companions: Vec<String>,
     pack_mode: PackMode,
+    trials: u32,
+    seed: u64,
     compress: Option<CompressionLevel>,
     filename_only: bool,
+    split_layers: bool,
+    /// Skip unreadable images; `--strict` turns a config's setting off
+    skip_errors: bool,
+    deterministic: bool,
+    group_by_folder: bool,
+    sort: SpriteSort,
+    sprite_order: SpriteOrder,
+    image_path_prefix: Option<String>,
+    output_template: OutputTemplate,
+    json_compact: bool,
+    source_paths: Option<SourcePaths>,
+    content_hash: bool,
+    /// Outline simplification tolerance, when JSON sprites get polygon meshes
+    polygon: Option<f32>,
+    /// Write generator text chunks into atlas PNGs
+    png_metadata: bool,
+    /// SHA-256 of the loaded config's settings, for PNG text chunks
+    config_hash: Option<String>,
+    /// Handlebars template for template output
+    template: Option<PathBuf>,
+    /// Packing report to write after building
+    report: Option<PathBuf>,
+    /// Previous sheet metadata whose sprite positions are kept
+    stable_layout: Option<PathBuf>,
+    phaser_frames: PhaserFrames,
+    godot_version: GodotVersion,
+    godot_res_path: Option<String>,
+    /// Directory of the loaded config file, if any
+    config_dir: Option<PathBuf>,
+    /// Sheets to cut into grid cells, keyed by sprite name or glob (from config file)
+    grid_slice: BTreeMap<String, GridSlice>,
+    /// Nine-slice borders keyed by sprite name or glob (from config file)
+    nine_slice: BTreeMap<String, NineSlice>,
+    /// Extrude overrides keyed by sprite name or glob (from config file)
+    extrude_overrides: BTreeMap<String, u32>,
+    /// Per-sprite settings keyed by sprite name or glob (from config file)
+    overrides: BTreeMap<String, SpriteOverride>,
+    /// Sprite name patterns packed first (from config file)
+    pack_order: Vec<glob::Pattern>,
+    keep_together: Vec<glob::Pattern>,
+    /// Image files to skip (from config file)
+    excluded: HashSet<PathBuf>,
+    /// Glob patterns of input files to skip (config file plus CLI)
+    exclude_patterns: Vec<glob::Pattern>,
+    /// Sprite name overrides keyed by image path (from config file)
+    names: HashMap<PathBuf, String>,
+}
+
+/// Replace `-` inputs and `--input-list` with the paths they list, so the
+/// rest of the run sees plain input paths
+fn resolve_input_lists(args: &CommonArgs) -> Result<CommonArgs> {
+    let stdin = Path::new("-");
+    let stdin_readers = args.input.iter().filter(|path| *path == stdin).count()
+        + usize::from(args.input_list.as_deref() == Some(stdin))
+        + usize::from(args.config.as_deref() == Some(stdin));
+    if stdin_readers > 1 {
+        anyhow::bail!("only one of --config, --input-list and the inputs can read stdin (`-`)");
+    }
+
+    let lists = args
+        .input
+        .iter()
+        .filter(|path| *path == stdin)
+        .chain(&args.input_list);
+    let mut input: Vec<PathBuf> = args
+        .input
+        .iter()
+        .filter(|path| *path != stdin)
+        .cloned()
+        .collect();
+    for list in lists {
+        let paths = read_input_list(list)?;
+        // An empty list would otherwise fall back to the config's inputs
+        if paths.is_empty() {
+            anyhow::bail!(
+                "input list {} is empty",
+                if list == stdin {
+                    "from stdin".to_string()
+                } else {
+                    list.display().to_string()
+                }
+            );
+        }
+        input.extend(paths);
+    }
+
+    Ok(CommonArgs {
+        input,
+        input_list: None,
+        ..args.clone()
+    })
 }
 
-/// Merge config file values with CLI arguments.
-/// CLI arguments always take precedence over config values.
-fn merge_config_with_args(args: &CommonArgs) -> Result<MergedConfig> {
-    // Load config if specified
-    let loaded_config = if let Some(config_path) = &args.config {
-        Some(
-            LoadedConfig::load(config_path)
-                .with_context(|| format!("failed to load config: {}", config_path.display()))?,
-        )
+/// Load the config given with `--config`, if any. `-` reads it from stdin.
+fn load_config(args: &CommonArgs) -> Result<Option<LoadedConfig>> {
+    let Some(config_path) = &args.config else {
+        return Ok(None);
+    };
+    let loaded = if config_path.as_os_str() == "-" {
+        LoadedConfig::from_stdin()?
     } else {
-        None
+        LoadedConfig::load(config_path)
+            .with_context(|| format!("failed to load config: {}", config_path.display()))?
     };
+    Ok(Some(loaded))
+}
 
+/// Merge config file values with CLI arguments.
+/// CLI arguments always take precedence over config values.
+fn merge_config_with_args(
+    args: &CommonArgs,
+    loaded_config: Option<&LoadedConfig>,
+) -> Result<MergedConfig> {
     // Determine input files: CLI args override config
     // When inputs come from a config file, preserve the config directory as the
     // base for computing relative sprite names (e.g., "ironclad/bash.png").
     let (input, base_dir) = if !args.input.is_empty() {
         (args.input.clone(), None)
-    } else if let Some(ref lc) = loaded_config {
+    } else if let Some(lc) = loaded_config {
         let inputs = lc
             .resolve_inputs()
             .context("failed to resolve input files from config")?;
@@ -219,11 +1541,29 @@ fn merge_config_with_args(args: &CommonArgs) -> Result<MergedConfig> {
             .map(|lc| lc.config.padding)
             .unwrap_or(1)
     });
+    // Per-axis padding: CLI over config, each over the shorthand from the same source
+    let padding_x = args
+        .padding_x
+        .or(args.padding)
+        .or_else(|| loaded_config.as_ref().and_then(|lc| lc.config.padding_x))
+        .unwrap_or(padding);
+    let padding_y = args
+        .padding_y
+        .or(args.padding)
+        .or_else(|| loaded_config.as_ref().and_then(|lc| lc.config.padding_y))
+        .unwrap_or(padding);
 
     let trim_margin = args.trim_margin.unwrap_or_else(|| {
         loaded_config
             .as_ref()
             .map(|lc| lc.config.trim_margin)
+            .unwrap_or_default()
+    });
+
+    let trim_alpha = args.trim_alpha.unwrap_or_else(|| {
+        loaded_config
+            .as_ref()
+            .map(|lc| lc.config.trim_alpha)
             .unwrap_or(0)
     });
 
@@ -234,6 +1574,33 @@ fn merge_config_with_args(args: &CommonArgs) -> Result<MergedConfig> {
             .unwrap_or(0)
     });
 
+    // Extrude mode: CLI > config > default
+    let extrude_mode = if let Some(mode) = args.extrude_mode {
+        mode
+    } else if let Some(lc) = loaded_config {
+        parse_extrude_mode(&lc.config.extrude_mode).ok_or_else(|| {
+            anyhow::anyhow!(
+                "unknown extrude_mode '{}' in config file. Valid values: clamp, wrap",
+                lc.config.extrude_mode
+            )
+        })?
+    } else {
+        ExtrudeMode::Clamp
+    };
+
+    let background_color = if args.background_color.is_some() {
+        args.background_color
+    } else if let Some(lc) = loaded_config
+        && let Some(color) = &lc.config.background_color
+    {
+        let color = color
+            .parse::<Color>()
+            .map_err(|e| anyhow::anyhow!("Invalid background_color in config: {}", e))?;
+        Some(color)
+    } else {
+        None
+    };
+
     let block_align = args.block_align.unwrap_or_else(|| {
         loaded_config
             .as_ref()
@@ -241,46 +1608,259 @@ fn merge_config_with_args(args: &CommonArgs) -> Result<MergedConfig> {
             .unwrap_or(0)
     });
 
+    let size_multiple = args.size_multiple.unwrap_or_else(|| {
+        loaded_config
+            .as_ref()
+            .map(|lc| lc.config.size_multiple)
+            .unwrap_or(0)
+    });
+
+    let max_sprites_per_page = args
+        .max_sprites_per_page
+        .map(NonZeroUsize::get)
+        .or_else(|| {
+            loaded_config
+                .as_ref()
+                .and_then(|lc| lc.config.max_sprites_per_page)
+        });
+    if max_sprites_per_page == Some(0) {
+        anyhow::bail!("max_sprites_per_page must be at least 1");
+    }
+
+    // Grid: CLI option overrides config
+    let grid = match args.grid {
+        Some(grid) => Some(grid),
+        None => loaded_config
+            .as_ref()
+            .and_then(|lc| lc.config.grid.as_deref())
+            .map(|grid| {
+                grid.parse::<GridCell>()
+                    .map_err(|e| anyhow::anyhow!("Invalid grid in config: {}", e))
+            })
+            .transpose()?,
+    };
+
+    let max_total_pixels = args.max_total_pixels.or_else(|| {
+        loaded_config
+            .as_ref()
+            .and_then(|lc| lc.config.max_total_pixels)
+    });
+
+    let max_total_bytes = args.max_total_bytes.or_else(|| {
+        loaded_config
+            .as_ref()
+            .and_then(|lc| lc.config.max_total_bytes)
+    });
+
+    let max_atlases = args
+        .max_atlases
+        .or_else(|| loaded_config.as_ref().and_then(|lc| lc.config.max_atlases));
+    if max_atlases == Some(0) {
+        anyhow::bail!("max_atlases must be at least 1");
+    }
+
     // Boolean flags: CLI presence sets them to true, otherwise use config
     let trim = if args.no_trim {
         false
-    } else if let Some(ref lc) = loaded_config {
+    } else if let Some(lc) = loaded_config {
         lc.config.trim
     } else {
         true // default is to trim
     };
 
+    let record_trim = if args.record_trim {
+        true
+    } else if let Some(lc) = loaded_config {
+        lc.config.record_trim
+    } else {
+        false
+    };
+
     let pot = if args.pot {
         true
-    } else if let Some(ref lc) = loaded_config {
+    } else if let Some(lc) = loaded_config {
         lc.config.pot
     } else {
         false
     };
 
+    let allow_rotation = if args.allow_rotation {
+        true
+    } else if let Some(lc) = loaded_config {
+        lc.config.allow_rotation
+    } else {
+        false
+    };
+
+    let size_search = if args.size_search {
+        true
+    } else if let Some(lc) = loaded_config {
+        lc.config.size_search
+    } else {
+        false
+    };
+
+    let square = if args.square {
+        true
+    } else if let Some(lc) = loaded_config {
+        lc.config.square
+    } else {
+        false
+    };
+
+    let fixed_size = if args.fixed_size {
+        true
+    } else if let Some(lc) = loaded_config {
+        lc.config.fixed_size
+    } else {
+        false
+    };
+
     let opaque = if args.opaque {
         true
-    } else if let Some(ref lc) = loaded_config {
+    } else if let Some(lc) = loaded_config {
         lc.config.opaque
     } else {
         false
     };
 
+    let matte_color = if args.matte_color.is_some() {
+        args.matte_color
+    } else if let Some(lc) = loaded_config
+        && let Some(color) = &lc.config.matte_color
+    {
+        let color = color
+            .parse::<Color>()
+            .map_err(|e| anyhow::anyhow!("Invalid matte_color in config: {}", e))?;
+        Some(color)
+    } else {
+        None
+    };
+    if let Some(color) = matte_color
+        && color.a < 255
+    {
+        anyhow::bail!("matte color {} must be opaque (#RRGGBB)", color);
+    }
+
+    let split_alpha = if args.split_alpha {
+        true
+    } else if let Some(lc) = loaded_config {
+        lc.config.split_alpha
+    } else {
+        false
+    };
+
+    let mipmaps = args.mipmaps.unwrap_or_else(|| {
+        loaded_config
+            .as_ref()
+            .map(|lc| lc.config.mipmaps)
+            .unwrap_or(0)
+    });
+
+    // Mipmap format: CLI > config > default
+    let mipmap_format = if let Some(f) = args.mipmap_format {
+        f
+    } else if let Some(lc) = loaded_config {
+        parse_mipmap_format(&lc.config.mipmap_format).ok_or_else(|| {
+            anyhow::anyhow!(
+                "unknown mipmap_format '{}' in config file. Valid values: png, ktx2",
+                lc.config.mipmap_format
+            )
+        })?
+    } else {
+        MipmapFormat::Png
+    };
+
+    // Phaser frame layout: CLI > config > default
+    let phaser_frames = if let Some(frames) = args.phaser_frames {
+        frames
+    } else if let Some(lc) = loaded_config {
+        parse_phaser_frames(&lc.config.phaser_frames).ok_or_else(|| {
+            anyhow::anyhow!(
+                "unknown phaser_frames '{}' in config file. Valid values: hash, array",
+                lc.config.phaser_frames
+            )
+        })?
+    } else {
+        PhaserFrames::Hash
+    };
+
+    // Godot version: CLI > config > default
+    let godot_version = if let Some(version) = args.godot_version {
+        version
+    } else if let Some(lc) = loaded_config {
+        parse_godot_version(lc.config.godot_version).ok_or_else(|| {
+            anyhow::anyhow!(
+                "unknown godot_version {} in config file. Valid values: 3, 4",
+                lc.config.godot_version
+            )
+        })?
+    } else {
+        GodotVersion::V4
+    };
+
+    let basis = if let Some(mode) = args.basis {
+        Some(mode)
+    } else if let Some(mode) = loaded_config.and_then(|lc| lc.config.basis.as_deref()) {
+        Some(parse_basis_mode(mode).ok_or_else(|| {
+            anyhow::anyhow!(
+                "unknown basis '{}' in config file. Valid values: uastc, etc1s",
+                mode
+            )
+        })?)
+    } else {
+        None
+    };
+    // Both would write {name}.ktx2
+    if basis.is_some() && mipmaps > 0 && mipmap_format == MipmapFormat::Ktx2 {
+        anyhow::bail!(
+            "--basis and --mipmap-format ktx2 both write a .ktx2 per page; use PNG mip levels with --basis"
+        );
+    }
+
+    // Scales: CLI > config > full size only
+    let scales = if !args.scales.is_empty() {
+        args.scales.clone()
+    } else if let Some(lc) = loaded_config
+        && !lc.config.scales.is_empty()
+    {
+        lc.config.scales.clone()
+    } else {
+        vec![1.0]
+    };
+    for (i, scale) in scales.iter().enumerate() {
+        if !scale.is_finite() || *scale <= 0.0 {
+            anyhow::bail!("invalid scale {}: scales must be positive numbers", scale);
+        }
+        // Each scale names its own files
+        if scales[..i].contains(scale) {
+            anyhow::bail!("scale {} is listed more than once", scale);
+        }
+    }
+
     // Verbose is CLI-only
     let verbose = args.verbose;
 
     let filename_only = if args.filename_only {
         true
-    } else if let Some(ref lc) = loaded_config {
+    } else if let Some(lc) = loaded_config {
         lc.config.filename_only
     } else {
         false
     };
 
+    let split_layers = args.split_layers || loaded_config.is_some_and(|lc| lc.config.split_layers);
+    let skip_errors =
+        !args.strict && (args.skip_errors || loaded_config.is_some_and(|lc| lc.config.skip_errors));
+    let deterministic =
+        args.deterministic || loaded_config.is_some_and(|lc| lc.config.deterministic);
+    let group_by_folder =
+        args.group_by_folder || loaded_config.is_some_and(|lc| lc.config.group_by_folder);
+
     // Heuristic: CLI > config > default
     let heuristic = if let Some(h) = args.heuristic {
         h
-    } else if let Some(ref lc) = loaded_config {
+    } else if let Some(lc) = loaded_config {
         parse_heuristic(&lc.config.heuristic).ok_or_else(|| {
             anyhow::anyhow!(
                 "unknown heuristic '{}' in config file. Valid values: best-short-side-fit, \
@@ -295,10 +1875,10 @@ fn merge_config_with_args(args: &CommonArgs) -> Result<MergedConfig> {
     // Pack mode: CLI > config > default
     let pack_mode = if let Some(m) = args.pack_mode {
         m
-    } else if let Some(ref lc) = loaded_config {
+    } else if let Some(lc) = loaded_config {
         parse_pack_mode(&lc.config.pack_mode).ok_or_else(|| {
             anyhow::anyhow!(
-                "unknown pack_mode '{}' in config file. Valid values: single, best",
+                "unknown pack_mode '{}' in config file. Valid values: single, best, min-pages",
                 lc.config.pack_mode
             )
         })?
@@ -306,24 +1886,236 @@ fn merge_config_with_args(args: &CommonArgs) -> Result<MergedConfig> {
         PackMode::Single
     };
 
+    let trials = args.trials.unwrap_or_else(|| {
+        loaded_config
+            .as_ref()
+            .map(|lc| lc.config.trials)
+            .unwrap_or(0)
+    });
+
+    let seed = args
+        .seed
+        .unwrap_or_else(|| loaded_config.as_ref().map(|lc| lc.config.seed).unwrap_or(0));
+
     // Resize: CLI options override config
-    let (resize_width, resize_scale) = if args.resize_width.is_some() || args.resize_scale.is_some()
+    let (resize_width, resize_scale, resize_max_width, resize_fit) = if args.resize_width.is_some()
+        || args.resize_scale.is_some()
+        || args.resize_max_width.is_some()
+        || args.resize_fit.is_some()
     {
-        (args.resize_width, args.resize_scale)
-    } else if let Some(ref lc) = loaded_config {
+        (
+            args.resize_width,
+            args.resize_scale,
+            args.resize_max_width,
+            args.resize_fit,
+        )
+    } else if let Some(lc) = loaded_config {
         match &lc.config.resize {
-            Some(ResizeConfig::Width { width }) => (Some(*width), None),
-            Some(ResizeConfig::Scale { scale }) => (None, Some(*scale)),
-            None => (None, None),
+            Some(ResizeConfig::Width { width }) => (Some(*width), None, None, None),
+            Some(ResizeConfig::Scale { scale }) => (None, Some(*scale), None, None),
+            Some(ResizeConfig::MaxWidth { max_width }) => (None, None, Some(*max_width), None),
+            Some(ResizeConfig::Fit { fit }) => {
+                let fit = fit
+                    .parse::<ResizeFit>()
+                    .map_err(|e| anyhow::anyhow!("Invalid resize fit in config: {}", e))?;
+                (None, None, None, Some(fit))
+            }
+            None => (None, None, None, None),
+        }
+    } else {
+        (None, None, None, None)
+    };
+
+    // Name overrides and pack order are config-only; exclude patterns also come from the CLI
+    let excluded = loaded_config
+        .map(LoadedConfig::resolve_excluded)
+        .unwrap_or_default();
+    let names = loaded_config
+        .map(LoadedConfig::resolve_names)
+        .unwrap_or_default();
+    let mut exclude_patterns = loaded_config
+        .map(LoadedConfig::exclude_patterns)
+        .unwrap_or_default();
+    exclude_patterns.extend(args.exclude.iter().cloned());
+    let exclude_patterns = compile_exclude(&exclude_patterns)?;
+    let trim_margin_overrides = loaded_config
+        .as_ref()
+        .map(|lc| lc.config.trim_margin_overrides.clone())
+        .unwrap_or_default();
+    let grid_slice = loaded_config
+        .as_ref()
+        .map(|lc| lc.config.grid_slice.clone())
+        .unwrap_or_default();
+    let scale_suffixes = loaded_config
+        .as_ref()
+        .map(|lc| lc.config.scale_suffixes.clone())
+        .unwrap_or_default();
+    for (suffix, factor) in &scale_suffixes {
+        if suffix.is_empty() {
+            anyhow::bail!("scale_suffixes has an empty suffix");
+        }
+        if !factor.is_finite() || *factor <= 0.0 {
+            anyhow::bail!(
+                "invalid scale {} for suffix '{}': scales must be positive numbers",
+                factor,
+                suffix
+            );
         }
+    }
+    let mut companions = loaded_config
+        .as_ref()
+        .map(|lc| lc.config.companions.clone())
+        .unwrap_or_default();
+    for suffix in &args.companions {
+        if !companions.contains(suffix) {
+            companions.push(suffix.clone());
+        }
+    }
+    if companions.iter().any(String::is_empty) {
+        anyhow::bail!("companion suffixes can't be empty");
+    }
+    let nine_slice = loaded_config
+        .as_ref()
+        .map(|lc| lc.config.nine_slice.clone())
+        .unwrap_or_default();
+    let extrude_overrides = loaded_config
+        .as_ref()
+        .map(|lc| lc.config.extrude_overrides.clone())
+        .unwrap_or_default();
+    let overrides = loaded_config
+        .as_ref()
+        .map(|lc| lc.config.overrides.clone())
+        .unwrap_or_default();
+    let pack_order = match &loaded_config {
+        Some(lc) => compile_pack_order(&lc.config.pack_order)?,
+        None => Vec::new(),
+    };
+    let keep_together = match &loaded_config {
+        Some(lc) => compile_keep_together(&lc.config.keep_together)?,
+        None => Vec::new(),
+    };
+
+    let image_path_prefix = args.image_path_prefix.clone().or_else(|| {
+        loaded_config
+            .as_ref()
+            .and_then(|lc| lc.config.image_path_prefix.clone())
+    });
+
+    let output_template = match &args.output_template {
+        Some(template) => template.clone(),
+        None => loaded_config
+            .and_then(|lc| lc.config.output_template.as_deref())
+            .map(|template| {
+                template
+                    .parse::<OutputTemplate>()
+                    .map_err(|e| anyhow::anyhow!("Invalid output_template in config: {}", e))
+            })
+            .transpose()?
+            .unwrap_or_default(),
+    };
+
+    let report = args.report.clone().or_else(|| {
+        loaded_config.as_ref().and_then(|lc| {
+            lc.config
+                .report
+                .as_ref()
+                .map(|report| lc.config_dir.join(report))
+        })
+    });
+
+    let stable_layout = args.stable_layout.clone().or_else(|| {
+        loaded_config.as_ref().and_then(|lc| {
+            lc.config
+                .stable_layout
+                .as_ref()
+                .map(|path| lc.config_dir.join(path))
+        })
+    });
+
+    let template = args.template.clone().or_else(|| {
+        loaded_config.as_ref().and_then(|lc| {
+            lc.config
+                .template
+                .as_ref()
+                .map(|template| lc.config_dir.join(template))
+        })
+    });
+
+    let godot_res_path = args.godot_res_path.clone().or_else(|| {
+        loaded_config
+            .as_ref()
+            .and_then(|lc| lc.config.godot_res_path.clone())
+    });
+
+    let source_paths = if let Some(style) = args.source_paths {
+        Some(style)
+    } else if let Some(style) = loaded_config.and_then(|lc| lc.config.source_paths.as_deref()) {
+        Some(parse_source_paths(style).ok_or_else(|| {
+            anyhow::anyhow!(
+                "unknown source_paths '{}' in config file. Valid values: absolute, relative",
+                style
+            )
+        })?)
+    } else {
+        None
+    };
+
+    let json_compact = args.json_compact || loaded_config.is_some_and(|lc| lc.config.json_compact);
+
+    let content_hash = args.content_hash || loaded_config.is_some_and(|lc| lc.config.content_hash);
+
+    let polygon_tolerance = args
+        .polygon_tolerance
+        .or_else(|| loaded_config.and_then(|lc| lc.config.polygon_tolerance))
+        .unwrap_or(SpriteMesh::DEFAULT_TOLERANCE);
+    if !polygon_tolerance.is_finite() || polygon_tolerance < 0.0 {
+        anyhow::bail!(
+            "invalid polygon tolerance {}: must be zero or more pixels",
+            polygon_tolerance
+        );
+    }
+    let polygon = (args.polygon || loaded_config.is_some_and(|lc| lc.config.polygon))
+        .then_some(polygon_tolerance);
+
+    let png_metadata = args.png_metadata || loaded_config.is_some_and(|lc| lc.config.png_metadata);
+    let config_hash = match loaded_config {
+        Some(lc) if png_metadata => Some(sha256_hex(&serde_json::to_vec(&lc.config)?)),
+        _ => None,
+    };
+
+    // Sort: CLI > config > default
+    let sort = if let Some(sort) = args.sort {
+        sort
+    } else if let Some(lc) = loaded_config {
+        parse_sprite_sort(&lc.config.sort).ok_or_else(|| {
+            anyhow::anyhow!(
+                "unknown sort '{}' in config file. Valid values: area, max-side, height, width, \
+                 perimeter, name, none",
+                lc.config.sort
+            )
+        })?
+    } else {
+        SpriteSort::Area
+    };
+
+    // Sprite order: CLI > config > default
+    let sprite_order = if let Some(o) = args.sprite_order {
+        o
+    } else if let Some(lc) = loaded_config {
+        parse_sprite_order(&lc.config.sprite_order).ok_or_else(|| {
+            anyhow::anyhow!(
+                "unknown sprite_order '{}' in config file. Valid values: packed, name, input",
+                lc.config.sprite_order
+            )
+        })?
     } else {
-        (None, None)
+        SpriteOrder::Packed
     };
 
     // Resize filter: CLI > config > default
     let resize_filter = if let Some(f) = args.resize_filter {
         f
-    } else if let Some(ref lc) = loaded_config {
+    } else if let Some(lc) = loaded_config {
         parse_resize_filter(&lc.config.resize_filter).ok_or_else(|| {
             anyhow::anyhow!(
                 "unknown resize_filter '{}' in config file. Valid values: nearest, \
@@ -334,15 +2126,21 @@ fn merge_config_with_args(args: &CommonArgs) -> Result<MergedConfig> {
     } else {
         ResizeFilter::Lanczos3
     };
+    let resize_linear =
+        args.resize_linear || loaded_config.is_some_and(|lc| lc.config.resize_linear);
 
     // Compress: CLI option overrides config
     let compress = if args.compress.is_some() {
         args.compress
-    } else if let Some(ref lc) = loaded_config {
-        lc.config.compress.as_ref().map(|c| match c {
-            CompressConfig::Level(n) => CompressionLevel::Level(*n),
-            CompressConfig::Max(_) => CompressionLevel::Max,
-        })
+    } else if let Some(lc) = loaded_config {
+        lc.config
+            .compress
+            .as_ref()
+            .map(|c| {
+                c.to_level()
+                    .map_err(|e| anyhow::anyhow!("Invalid compress in config: {}", e))
+            })
+            .transpose()?
     } else {
         Some(CompressionLevel::Level(2))
     };
@@ -354,21 +2152,80 @@ fn merge_config_with_args(args: &CommonArgs) -> Result<MergedConfig> {
         name,
         max_width,
         max_height,
-        padding,
+        padding_x,
+        padding_y,
         trim,
         trim_margin,
+        trim_margin_overrides,
+        trim_alpha,
+        record_trim,
         heuristic,
         opaque,
+        matte_color,
+        split_alpha,
+        mipmaps,
+        mipmap_format,
+        basis,
+        scales,
         pot,
         extrude,
+        extrude_mode,
+        background_color,
         block_align,
+        allow_rotation,
+        size_search,
+        square,
+        fixed_size,
+        size_multiple,
+        max_sprites_per_page,
+        grid,
+        max_total_pixels,
+        max_total_bytes,
+        max_atlases,
         verbose,
         resize_width,
         resize_scale,
+        resize_max_width,
+        resize_fit,
         resize_filter,
+        resize_linear,
+        scale_suffixes,
+        companions,
         pack_mode,
+        trials,
+        seed,
         compress,
         filename_only,
+        split_layers,
+        skip_errors,
+        deterministic,
+        group_by_folder,
+        sort,
+        sprite_order,
+        image_path_prefix,
+        output_template,
+        json_compact,
+        source_paths,
+        content_hash,
+        polygon,
+        png_metadata,
+        config_hash,
+        template,
+        report,
+        stable_layout,
+        phaser_frames,
+        godot_version,
+        godot_res_path,
+        config_dir: loaded_config.map(|lc| lc.config_dir.clone()),
+        grid_slice,
+        nine_slice,
+        extrude_overrides,
+        overrides,
+        pack_order,
+        keep_together,
+        excluded,
+        exclude_patterns,
+        names,
     })
 }
 
@@ -388,6 +2245,77 @@ fn parse_pack_mode(s: &str) -> Option<PackMode> {
     match s {
         "single" => Some(PackMode::Single),
         "best" => Some(PackMode::Best),
+        "min-pages" => Some(PackMode::MinPages),
+        _ => None,
+    }
+}
+
+fn parse_sprite_sort(s: &str) -> Option<SpriteSort> {
+    match s {
+        "area" => Some(SpriteSort::Area),
+        "max-side" => Some(SpriteSort::MaxSide),
+        "height" => Some(SpriteSort::Height),
+        "width" => Some(SpriteSort::Width),
+        "perimeter" => Some(SpriteSort::Perimeter),
+        "name" => Some(SpriteSort::Name),
+        "none" => Some(SpriteSort::None),
+        _ => None,
+    }
+}
+
+fn parse_sprite_order(s: &str) -> Option<SpriteOrder> {
+    match s {
+        "packed" => Some(SpriteOrder::Packed),
+        "name" => Some(SpriteOrder::Name),
+        "input" => Some(SpriteOrder::Input),
+        _ => None,
+    }
+}
+
+fn parse_source_paths(s: &str) -> Option<SourcePaths> {
+    match s {
+        "absolute" => Some(SourcePaths::Absolute),
+        "relative" => Some(SourcePaths::Relative),
+        _ => None,
+    }
+}
+
+fn parse_godot_version(version: u32) -> Option<GodotVersion> {
+    match version {
+        3 => Some(GodotVersion::V3),
+        4 => Some(GodotVersion::V4),
+        _ => None,
+    }
+}
+
+fn parse_phaser_frames(s: &str) -> Option<PhaserFrames> {
+    match s {
+        "hash" => Some(PhaserFrames::Hash),
+        "array" => Some(PhaserFrames::Array),
+        _ => None,
+    }
+}
+
+fn parse_basis_mode(s: &str) -> Option<BasisMode> {
+    match s {
+        "uastc" => Some(BasisMode::Uastc),
+        "etc1s" => Some(BasisMode::Etc1s),
+        _ => None,
+    }
+}
+
+fn parse_mipmap_format(s: &str) -> Option<MipmapFormat> {
+    match s {
+        "png" => Some(MipmapFormat::Png),
+        "ktx2" => Some(MipmapFormat::Ktx2),
+        _ => None,
+    }
+}
+
+fn parse_extrude_mode(s: &str) -> Option<ExtrudeMode> {
+    match s {
+        "clamp" => Some(ExtrudeMode::Clamp),
+        "wrap" => Some(ExtrudeMode::Wrap),
         _ => None,
     }
 }
@@ -402,3 +2330,144 @@ fn parse_resize_filter(s: &str) -> Option<ResizeFilter> {
         _ => None,
     }
 }
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use std::cell::RefCell;
+
+    use super::*;
+
+    thread_local! {
+        /// PNGs `write_sheet` encoded on this thread
+        static ENCODED: RefCell<Vec<PathBuf>> = const { RefCell::new(Vec::new()) };
+    }
+
+    pub(super) fn record_encode(path: &Path) {
+        ENCODED.with_borrow_mut(|encoded| encoded.push(path.to_path_buf()));
+    }
+
+    /// A fresh input directory of solid `size`x`size` sprites, and an
+    /// output directory next to it
+    fn sprite_dirs(test: &str, names: &[&str], size: u32) -> (PathBuf, PathBuf) {
+        let dir = std::env::temp_dir().join(test);
+        let _ = fs::remove_dir_all(&dir);
+        let input = dir.join("sprites");
+        fs::create_dir_all(&input).unwrap();
+        for name in names {
+            image::RgbaImage::from_pixel(size, size, image::Rgba([200, 50, 50, 255]))
+                .save(input.join(format!("{}.png", name)))
+                .unwrap();
+        }
+        (input, dir.join("out"))
+    }
+
+    fn loaded(config: BentoConfig) -> LoadedConfig {
+        LoadedConfig {
+            config,
+            config_dir: std::env::temp_dir(),
+        }
+    }
+
+    #[test]
+    fn test_godot_res_path_precedence() {
+        let args = CommonArgs {
+            input: vec![PathBuf::from("a.png")],
+            ..CommonArgs::default()
+        };
+        let config = loaded(BentoConfig {
+            godot_res_path: Some("res://from_config".to_string()),
+            ..BentoConfig::default()
+        });
+
+        let merged = merge_config_with_args(&args, None).unwrap();
+        assert_eq!(merged.godot_res_path, None);
+        let merged = merge_config_with_args(&args, Some(&config)).unwrap();
+        assert_eq!(merged.godot_res_path.as_deref(), Some("res://from_config"));
+
+        let args = CommonArgs {
+            godot_res_path: Some("res://from_cli".to_string()),
+            ..args
+        };
+        let merged = merge_config_with_args(&args, Some(&config)).unwrap();
+        assert_eq!(merged.godot_res_path.as_deref(), Some("res://from_cli"));
+    }
+
+    #[test]
+    fn test_pack_writes_every_format_from_one_encode() {
+        let (input, output) = sprite_dirs("bento_multi_format", &["a", "b", "c"], 32);
+        let args = CommonArgs {
+            input: vec![input],
+            output: Some(output.clone()),
+            name: Some("sheet".to_string()),
+            max_sprites_per_page: NonZeroUsize::new(2),
+            mipmaps: Some(2),
+            ..CommonArgs::default()
+        };
+        let formats = [
+            OutputFormat::Json,
+            OutputFormat::Godot,
+            OutputFormat::Libgdx,
+        ];
+        let merged = merge_config_with_args(&args, None).unwrap();
+        pack(&formats, &args, &merged).unwrap();
+
+        let mut expected = vec!["sheet.json".to_string(), "sheet.atlas".to_string()];
+        expected.extend(["a", "b", "c"].map(|name| format!("{}.png.tres", name)));
+        let mut images = Vec::new();
+        for page in 0..2 {
+            images.push(format!("sheet_{}.png", page));
+            for level in 1..=2 {
+                images.push(format!("sheet_{}.mip{}.png", page, level));
+            }
+        }
+        for file in expected.iter().chain(&images) {
+            assert!(output.join(file).is_file(), "{} was not written", file);
+        }
+
+        let mut encoded: Vec<String> = ENCODED.with_borrow(|encoded| {
+            encoded
+                .iter()
+                .map(|path| path.file_name().unwrap().to_string_lossy().into_owned())
+                .collect()
+        });
+        encoded.sort();
+        images.sort();
+        assert_eq!(encoded, images);
+    }
+
+    #[test]
+    fn test_json_compact_writes_one_line_per_file() {
+        let (input, output) = sprite_dirs("bento_json_compact", &["a", "b"], 8);
+        let formats = [
+            OutputFormat::Json,
+            OutputFormat::Tpsheet,
+            OutputFormat::Phaser,
+        ];
+        let files = ["sheet.json", "sheet.tpsheet", "sheet.phaser.json"];
+        let pack_into = |dir: &str, extra: &[&str]| -> Vec<String> {
+            let out = output.join(dir);
+            let mut cli = vec!["bento", "json", "--name", "sheet"];
+            cli.extend([input.to_str().unwrap(), "-o", out.to_str().unwrap()]);
+            cli.extend(extra);
+            let Command::Json(args) = CliArgs::try_parse_from(cli).unwrap().command else {
+                unreachable!("parsed a json command");
+            };
+            let merged = merge_config_with_args(&args, None).unwrap();
+            pack(&formats, &args, &merged).unwrap();
+            files
+                .iter()
+                .map(|file| fs::read_to_string(out.join(file)).unwrap())
+                .collect()
+        };
+
+        let pretty = pack_into("pretty", &[]);
+        let compact = pack_into("compact", &["--json-compact"]);
+        for ((file, pretty), compact) in files.iter().zip(&pretty).zip(&compact) {
+            assert!(pretty.lines().count() > 1, "{} is not pretty", file);
+            assert_eq!(compact.lines().count(), 1, "{} is not one line", file);
+            let parse = |text: &str| serde_json::from_str::<serde_json::Value>(text).unwrap();
+            assert_eq!(parse(compact), parse(pretty), "{} differs", file);
+        }
+    }
+}
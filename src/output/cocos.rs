@@ -0,0 +1,191 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::Result;
+
+use crate::atlas::Atlas;
+use crate::cli::SpriteOrder;
+use crate::error::BentoError;
+use crate::output::plist::PlistValue;
+use crate::output::{OutputTemplate, ordered_sprites, prefixed_image_path};
+use crate::sprite::PackedSprite;
+
+/// Returns the plist filename for an atlas, named like its PNG:
+/// `{name}.plist` or `{name}_{index}.plist` with the default template.
+pub fn cocos_plist_filename(
+    output_template: &OutputTemplate,
+    base_name: &str,
+    index: usize,
+    total: usize,
+) -> String {
+    format!("{}.plist", output_template.stem(base_name, index, total))
+}
+
+/// Write one cocos2d `.plist` per atlas page, in TexturePacker's cocos2d
+/// format (version 3), which Cocos2d-x and Cocos Creator load as sprite frames
+pub fn write_cocos(
+    atlases: &[Atlas],
+    output_dir: &Path,
+    base_name: &str,
+    output_template: &OutputTemplate,
+    order: SpriteOrder,
+    image_path_prefix: Option<&str>,
+) -> Result<()> {
+    let total = atlases.len();
+    for atlas in atlases {
+        let image = prefixed_image_path(
+            image_path_prefix,
+            output_template.png_filename(base_name, atlas.index, total),
+        );
+        let frames = ordered_sprites(&atlas.sprites, order)
+            .into_iter()
+            .map(|sprite| (sprite.name.clone(), sprite_frame(sprite)));
+        let plist = PlistValue::dict([
+            ("frames", PlistValue::dict(frames)),
+            (
+                "metadata",
+                PlistValue::dict([
+                    ("format", PlistValue::Integer(3)),
+                    ("pixelFormat", PlistValue::String("RGBA8888".to_string())),
+                    ("premultiplyAlpha", PlistValue::Bool(false)),
+                    ("realTextureFileName", PlistValue::String(image.clone())),
+                    (
+                        "size",
+                        PlistValue::String(format!("{{{},{}}}", atlas.width, atlas.height)),
+                    ),
+                    ("textureFileName", PlistValue::String(image)),
+                ]),
+            ),
+        ]);
+
+        let plist_path = output_dir.join(cocos_plist_filename(
+            output_template,
+            base_name,
+            atlas.index,
+            total,
+        ));
+        fs::write(&plist_path, plist.to_xml()).map_err(|e| BentoError::OutputWrite {
+            path: plist_path,
+            source: e,
+        })?;
+    }
+
+    Ok(())
+}
+
+fn sprite_frame(sprite: &PackedSprite) -> PlistValue {
+    let trim = &sprite.trim_info;
+    // Offset of the trimmed rect's center from the source center, y up.
+    // Doubled so odd sizes stay exact, then written with a .5 where needed.
+    let offset_x =
+        2 * i64::from(trim.offset_x) + i64::from(trim.trimmed_width) - i64::from(trim.source_width);
+    let offset_y = i64::from(trim.source_height)
+        - 2 * i64::from(trim.offset_y)
+        - i64::from(trim.trimmed_height);
+
+    PlistValue::dict([
+        ("aliases", PlistValue::Array(Vec::new())),
+        (
+            "spriteOffset",
+            PlistValue::String(format!("{{{},{}}}", half(offset_x), half(offset_y))),
+        ),
+        (
+            "spriteSize",
+            PlistValue::String(format!(
+                "{{{},{}}}",
+                trim.trimmed_width, trim.trimmed_height
+            )),
+        ),
+        (
+            "spriteSourceSize",
+            PlistValue::String(format!("{{{},{}}}", trim.source_width, trim.source_height)),
+        ),
+        // The rect has the upright size; rotated frames are stored turned
+        // 90° clockwise, as cocos2d expects
+        (
+            "textureRect",
+            PlistValue::String(format!(
+                "{{{{{},{}}},{{{},{}}}}}",
+                sprite.x, sprite.y, trim.trimmed_width, trim.trimmed_height
+            )),
+        ),
+        ("textureRotated", PlistValue::Bool(sprite.rotated)),
+    ])
+}
+
+/// Format half of `doubled`, e.g. 3 as "1.5" and -4 as "-2"
+fn half(doubled: i64) -> String {
+    if doubled % 2 == 0 {
+        (doubled / 2).to_string()
+    } else {
+        format!(
+            "{}{}.5",
+            if doubled < 0 { "-" } else { "" },
+            doubled.abs() / 2
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sprite::TrimInfo;
+
+    #[test]
+    fn test_half() {
+        assert_eq!(half(4), "2");
+        assert_eq!(half(3), "1.5");
+        assert_eq!(half(-1), "-0.5");
+        assert_eq!(half(-4), "-2");
+    }
+
+    #[test]
+    fn test_sprite_frame() {
+        let sprite = PackedSprite {
+            name: "hero.png".to_string(),
+            path: std::path::PathBuf::from("hero.png"),
+            x: 10,
+            y: 20,
+            width: 30,
+            height: 27,
+            trim_info: TrimInfo {
+                offset_x: 1,
+                offset_y: 2,
+                source_width: 32,
+                source_height: 32,
+                trimmed_width: 27,
+                trimmed_height: 30,
+            },
+            atlas_index: 0,
+            input_index: 0,
+            content_bounds: None,
+            nine_slice: None,
+            pivot: None,
+            rotated: true,
+        };
+        let PlistValue::Dict(entries) = sprite_frame(&sprite) else {
+            unreachable!()
+        };
+        let string = |key: &str| {
+            entries
+                .iter()
+                .find(|(k, _)| k == key)
+                .map(|(_, value)| value.clone())
+        };
+
+        // Center moves right by (2 + 27 - 32) / 2 and down by (4 + 30 - 32) / 2
+        assert_eq!(
+            string("spriteOffset"),
+            Some(PlistValue::String("{-1.5,-1}".to_string()))
+        );
+        assert_eq!(
+            string("textureRect"),
+            Some(PlistValue::String("{{10,20},{27,30}}".to_string()))
+        );
+        assert_eq!(string("textureRotated"), Some(PlistValue::Bool(true)));
+        assert_eq!(
+            string("spriteSourceSize"),
+            Some(PlistValue::String("{32,32}".to_string()))
+        );
+    }
+}
@@ -1,52 +1,307 @@
+use std::ffi::OsString;
 use std::fs;
 use std::io::Cursor;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
-use anyhow::Result;
-use image::{DynamicImage, ImageFormat, RgbImage};
+use anyhow::{Context, Result};
+use image::codecs::png::{CompressionType, FilterType, PngEncoder};
+use image::{
+    DynamicImage, EncodableLayout, GrayImage, ImageBuffer, PixelWithColorType, RgbImage, RgbaImage,
+};
 
 use crate::atlas::Atlas;
-use crate::cli::CompressionLevel;
+use crate::cli::{BasisMode, Color, CompressionLevel};
 use crate::error::BentoError;
 
-/// Save atlas image as PNG, optionally with compression
+/// Save atlas image as PNG, optionally with compression. Opaque output blends
+/// pixels over `matte` when given, and otherwise just drops alpha.
 pub fn save_atlas_image(
     atlas: &Atlas,
     path: &Path,
     opaque: bool,
+    matte: Option<Color>,
     compress: Option<CompressionLevel>,
 ) -> Result<()> {
-    // Encode to PNG in memory
-    let mut png_data = Cursor::new(Vec::new());
-    if opaque {
-        let rgb: RgbImage = DynamicImage::ImageRgba8(atlas.image.clone()).into_rgb8();
-        rgb.write_to(&mut png_data, ImageFormat::Png)
-            .map_err(|e| BentoError::ImageSave {
-                path: path.to_path_buf(),
-                source: e,
-            })?;
+    save_image(&atlas.image, path, opaque, matte, compress)
+}
+
+/// Save an RGBA image (such as a mip level) as PNG, optionally with compression
+pub fn save_image(
+    image: &RgbaImage,
+    path: &Path,
+    opaque: bool,
+    matte: Option<Color>,
+    compress: Option<CompressionLevel>,
+) -> Result<()> {
+    match (opaque, matte) {
+        (true, Some(matte)) => write_png(&flatten(image, matte), path, compress),
+        (true, None) => {
+            let rgb: RgbImage = DynamicImage::ImageRgba8(image.clone()).into_rgb8();
+            write_png(&rgb, path, compress)
+        }
+        (false, _) => write_png(image, path, compress),
+    }
+}
+
+/// Blend each pixel over a solid background color
+fn flatten(image: &RgbaImage, matte: Color) -> RgbImage {
+    RgbImage::from_fn(image.width(), image.height(), |x, y| {
+        let pixel = image.get_pixel(x, y);
+        let alpha = u32::from(pixel[3]);
+        let blend = |color: u8, background: u8| {
+            let mixed =
+                (u32::from(color) * alpha + u32::from(background) * (255 - alpha) + 127) / 255;
+            u8::try_from(mixed).unwrap_or(u8::MAX)
+        };
+        image::Rgb([
+            blend(pixel[0], matte.r),
+            blend(pixel[1], matte.g),
+            blend(pixel[2], matte.b),
+        ])
+    })
+}
+
+/// Save the atlas alpha channel as a grayscale PNG mask, for split-alpha output
+pub fn save_alpha_mask(
+    atlas: &Atlas,
+    path: &Path,
+    compress: Option<CompressionLevel>,
+) -> Result<()> {
+    let mask = GrayImage::from_fn(atlas.image.width(), atlas.image.height(), |x, y| {
+        image::Luma([atlas.image.get_pixel(x, y)[3]])
+    });
+    write_png(&mask, path, compress)
+}
+
+/// Text chunks recording how an atlas was built: the bento version, and the
+/// SHA-256 of the config settings and the command line when known
+pub fn generator_text(
+    config_hash: Option<String>,
+    command_line: Option<String>,
+) -> Vec<(String, String)> {
+    let mut text = vec![(
+        "Software".to_string(),
+        format!("bento {}", env!("CARGO_PKG_VERSION")),
+    )];
+    if let Some(hash) = config_hash {
+        text.push(("bento:config-sha256".to_string(), hash));
+    }
+    if let Some(command_line) = command_line {
+        text.push(("bento:command".to_string(), command_line));
+    }
+    text
+}
+
+/// Add text chunks to a PNG file, right after its header. ASCII text goes in
+/// `tEXt` chunks and anything else in UTF-8 `iTXt` chunks.
+pub fn add_png_text(path: &Path, text: &[(String, String)]) -> Result<()> {
+    let write_error = |source| BentoError::OutputWrite {
+        path: path.to_path_buf(),
+        source,
+    };
+    let png = fs::read(path).map_err(write_error)?;
+    // 8-byte signature, then the 25-byte IHDR chunk
+    const HEADER_END: usize = 33;
+    if png.get(12..16) != Some(b"IHDR".as_slice()) || png.len() < HEADER_END {
+        return Err(write_error(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "not a PNG file",
+        ))
+        .into());
+    }
+
+    let mut output = png[..HEADER_END].to_vec();
+    for (keyword, value) in text {
+        output.extend(text_chunk(keyword, value));
+    }
+    output.extend_from_slice(&png[HEADER_END..]);
+    fs::write(path, output).map_err(write_error)?;
+    Ok(())
+}
+
+/// Encode one `tEXt` or `iTXt` chunk
+fn text_chunk(keyword: &str, text: &str) -> Vec<u8> {
+    let (kind, data) = if text.is_ascii() {
+        (
+            b"tEXt",
+            [keyword.as_bytes(), b"\0", text.as_bytes()].concat(),
+        )
     } else {
-        atlas
-            .image
-            .write_to(&mut png_data, ImageFormat::Png)
-            .map_err(|e| BentoError::ImageSave {
-                path: path.to_path_buf(),
-                source: e,
-            })?;
+        // No compression, empty language tag and translated keyword
+        (
+            b"iTXt",
+            [keyword.as_bytes(), b"\0\0\0\0\0", text.as_bytes()].concat(),
+        )
+    };
+    let mut crc = flate2::Crc::new();
+    crc.update(kind);
+    crc.update(&data);
+
+    let length = u32::try_from(data.len()).unwrap_or(u32::MAX);
+    let mut chunk = Vec::with_capacity(data.len() + 12);
+    chunk.extend_from_slice(&length.to_be_bytes());
+    chunk.extend_from_slice(kind);
+    chunk.extend_from_slice(&data);
+    chunk.extend_from_slice(&crc.sum().to_be_bytes());
+    chunk
+}
+
+/// Encodes an image into a GPU texture file
+pub trait TextureEncoder {
+    /// Encoded file contents for `image`
+    fn encode(&self, image: &RgbaImage, opaque: bool) -> Result<Vec<u8>>;
+}
+
+/// KTX2 with Basis Universal supercompression, encoded by handing the image
+/// to Binomial's `basisu` command-line encoder
+#[derive(Debug, Clone)]
+pub struct BasisuEncoder {
+    pub mode: BasisMode,
+    /// Let basisu generate a mip chain (filtered across the whole page)
+    pub mipmaps: bool,
+    /// Encoder executable, `basisu` on PATH by default
+    pub program: PathBuf,
+}
+
+impl BasisuEncoder {
+    pub fn new(mode: BasisMode, mipmaps: bool) -> Self {
+        Self {
+            mode,
+            mipmaps,
+            program: PathBuf::from("basisu"),
+        }
     }
 
+    fn args(&self, input: &Path, output: &Path) -> Vec<OsString> {
+        let mut args: Vec<OsString> = vec!["-ktx2".into()];
+        // ETC1S is basisu's default mode
+        if self.mode == BasisMode::Uastc {
+            args.push("-uastc".into());
+        }
+        if self.mipmaps {
+            args.push("-mipmap".into());
+        }
+        args.extend([
+            "-file".into(),
+            input.as_os_str().to_owned(),
+            "-output_file".into(),
+            output.as_os_str().to_owned(),
+        ]);
+        args
+    }
+}
+
+impl TextureEncoder for BasisuEncoder {
+    fn encode(&self, image: &RgbaImage, opaque: bool) -> Result<Vec<u8>> {
+        // basisu only reads files, so stage the image in a scratch directory
+        static SCRATCH_ID: AtomicUsize = AtomicUsize::new(0);
+        let scratch = std::env::temp_dir().join(format!(
+            "bento-basisu-{}-{}",
+            std::process::id(),
+            SCRATCH_ID.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::create_dir_all(&scratch)?;
+        let input = scratch.join("atlas.png");
+        let output = scratch.join("atlas.ktx2");
+
+        let encoded = save_image(image, &input, opaque, None, None).and_then(|()| {
+            let result = Command::new(&self.program)
+                .args(self.args(&input, &output))
+                .output()
+                .map_err(|e| BentoError::TextureEncode {
+                    message: format!(
+                        "could not run {} ({}); install the Basis Universal encoder and \
+                         make sure it is on PATH",
+                        self.program.display(),
+                        e
+                    ),
+                })?;
+            if !result.status.success() {
+                return Err(BentoError::TextureEncode {
+                    message: format!(
+                        "{} exited with {}: {}",
+                        self.program.display(),
+                        result.status,
+                        String::from_utf8_lossy(&result.stderr).trim()
+                    ),
+                }
+                .into());
+            }
+            Ok(fs::read(&output)?)
+        });
+        let _ = fs::remove_dir_all(&scratch);
+        encoded
+    }
+}
+
+/// Encode an image with `encoder` and write the result to `path`
+pub fn save_encoded(
+    image: &RgbaImage,
+    path: &Path,
+    opaque: bool,
+    encoder: &dyn TextureEncoder,
+) -> Result<()> {
+    let data = encoder
+        .encode(image, opaque)
+        .with_context(|| format!("failed to encode {}", path.display()))?;
+    fs::write(path, data).map_err(|e| BentoError::OutputWrite {
+        path: path.to_path_buf(),
+        source: e,
+    })?;
+    Ok(())
+}
+
+/// oxipng settings for a compression level
+pub fn oxipng_options(level: CompressionLevel) -> oxipng::Options {
+    match level {
+        CompressionLevel::Level(n) => oxipng::Options::from_preset(n),
+        CompressionLevel::Max => oxipng::Options::max_compression(),
+        CompressionLevel::Zopfli(iterations) => {
+            // Pick filters with the fast default evaluation, then run Zopfli
+            // only for the final deflate; evaluating every filter with Zopfli
+            // would multiply the already long encode time.
+            let mut opts = oxipng::Options::from_preset(2);
+            opts.deflate = oxipng::Deflaters::Zopfli { iterations };
+            opts
+        }
+    }
+}
+
+/// Encode an image as PNG, compress it with oxipng if requested, and write it to `path`
+fn write_png<P>(
+    image: &ImageBuffer<P, Vec<P::Subpixel>>,
+    path: &Path,
+    compress: Option<CompressionLevel>,
+) -> Result<()>
+where
+    P: PixelWithColorType,
+    [P::Subpixel]: EncodableLayout,
+{
+    // Encode to PNG in memory. The settings are spelled out rather than left
+    // to the image crate's defaults, so the same pixels always give the same bytes.
+    let mut png_data = Cursor::new(Vec::new());
+    let encoder = PngEncoder::new_with_quality(
+        &mut png_data,
+        CompressionType::Default,
+        FilterType::Adaptive,
+    );
+    image
+        .write_with_encoder(encoder)
+        .map_err(|e| BentoError::ImageSave {
+            path: path.to_path_buf(),
+            source: e,
+        })?;
+
     let output_data = if let Some(level) = compress {
         // Compress with oxipng
-        let opts = match level {
-            CompressionLevel::Level(n) => oxipng::Options::from_preset(n),
-            CompressionLevel::Max => oxipng::Options::max_compression(),
-        };
-        oxipng::optimize_from_memory(&png_data.into_inner(), &opts).map_err(|e| {
-            BentoError::PngCompress {
+        oxipng::optimize_from_memory(&png_data.into_inner(), &oxipng_options(level)).map_err(
+            |e| BentoError::PngCompress {
                 path: path.to_path_buf(),
                 message: e.to_string(),
-            }
-        })?
+            },
+        )?
     } else {
         png_data.into_inner()
     };
@@ -58,3 +313,93 @@ pub fn save_atlas_image(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_basisu_args() {
+        let args = |encoder: BasisuEncoder| -> Vec<String> {
+            encoder
+                .args(Path::new("in.png"), Path::new("out.ktx2"))
+                .iter()
+                .map(|arg| arg.to_string_lossy().into_owned())
+                .collect()
+        };
+        assert_eq!(
+            args(BasisuEncoder::new(BasisMode::Uastc, true)),
+            [
+                "-ktx2",
+                "-uastc",
+                "-mipmap",
+                "-file",
+                "in.png",
+                "-output_file",
+                "out.ktx2"
+            ]
+        );
+        assert_eq!(
+            args(BasisuEncoder::new(BasisMode::Etc1s, false)),
+            ["-ktx2", "-file", "in.png", "-output_file", "out.ktx2"]
+        );
+    }
+
+    #[test]
+    fn test_flatten_over_matte() {
+        let image = RgbaImage::from_vec(3, 1, vec![255, 0, 0, 255, 255, 0, 0, 128, 255, 0, 0, 0])
+            .unwrap_or_default();
+        let matte = Color {
+            r: 0,
+            g: 0,
+            b: 255,
+            a: 255,
+        };
+        assert_eq!(
+            flatten(&image, matte).into_raw(),
+            [255, 0, 0, 128, 0, 127, 0, 0, 255]
+        );
+    }
+
+    #[test]
+    fn test_add_png_text() {
+        let dir = std::env::temp_dir().join("bento_test_png_text");
+        fs::create_dir_all(&dir).unwrap_or_default();
+        let path = dir.join("atlas.png");
+        let image = RgbaImage::from_pixel(2, 2, image::Rgba([255, 0, 0, 255]));
+        save_image(&image, &path, false, None, None).unwrap_or_default();
+
+        let mut text = generator_text(Some("ab12".to_string()), None);
+        text.push(("bento:command".to_string(), "json sprités/".to_string()));
+        assert!(add_png_text(&path, &text).is_ok());
+
+        let png = fs::read(&path).unwrap_or_default();
+        let contains = |needle: &[u8]| png.windows(needle.len()).any(|w| w == needle);
+        let software = format!("tEXtSoftware\0bento {}", env!("CARGO_PKG_VERSION"));
+        assert!(contains(software.as_bytes()));
+        assert!(contains(b"tEXtbento:config-sha256\0ab12"));
+        assert!(contains(
+            "iTXtbento:command\0\0\0\0\0json sprités/".as_bytes()
+        ));
+        // Chunk checksums hold, so decoders still read the pixels
+        let decoded = image::open(&path).map(DynamicImage::into_rgba8);
+        assert_eq!(decoded.ok(), Some(image));
+
+        assert!(add_png_text(&dir.join("missing.png"), &text).is_err());
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_basisu_missing_encoder() {
+        let encoder = BasisuEncoder {
+            program: PathBuf::from("bento-no-such-encoder"),
+            ..BasisuEncoder::new(BasisMode::Uastc, false)
+        };
+        let error = encoder
+            .encode(&RgbaImage::new(4, 4), false)
+            .err()
+            .map(|e| e.to_string())
+            .unwrap_or_default();
+        assert!(error.contains("could not run bento-no-such-encoder"));
+    }
+}
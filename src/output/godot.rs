@@ -1,61 +1,142 @@
+use std::fmt::Display;
 use std::fs;
 use std::path::Path;
 
 use anyhow::Result;
 
 use crate::atlas::Atlas;
+use crate::cli::GodotVersion;
 use crate::error::BentoError;
-use crate::output::atlas_png_filename;
-use crate::sprite::PackedSprite;
+use crate::output::OutputTemplate;
+use crate::sprite::{NineSlice, PackedSprite};
 
-/// Generate Godot .tres AtlasTexture files
+/// Generate Godot .tres AtlasTexture files, plus a StyleBoxTexture for each
+/// sprite with nine-slice borders, in the resource syntax of `version`.
+/// AtlasTexture regions can't be rotated, so atlases with rotated sprites are
+/// rejected before anything is written.
 pub fn write_godot_resources(
     atlases: &[Atlas],
     output_dir: &Path,
     base_name: &str,
+    output_template: &OutputTemplate,
     godot_res_path: Option<&str>,
+    version: GodotVersion,
 ) -> Result<()> {
+    if let Some(sprite) = atlases
+        .iter()
+        .flat_map(|atlas| &atlas.sprites)
+        .find(|sprite| sprite.rotated)
+    {
+        return Err(BentoError::RotationUnsupported {
+            name: sprite.name.clone(),
+            format: "Godot",
+        }
+        .into());
+    }
+
     let total = atlases.len();
     for atlas in atlases {
-        let atlas_filename = atlas_png_filename(base_name, atlas.index, total);
+        let atlas_filename = output_template.png_filename(base_name, atlas.index, total);
         let res_path = godot_res_path
             .map(|p| format!("{}/{}", p.trim_end_matches('/'), atlas_filename))
             .unwrap_or_else(|| format!("res://{}", atlas_filename));
 
         for sprite in &atlas.sprites {
             let tres_path = output_dir.join(format!("{}.tres", sprite.name));
-            let content = generate_tres(sprite, &res_path);
+            let content = generate_tres(sprite, &res_path, version);
 
             fs::write(&tres_path, content).map_err(|e| BentoError::OutputWrite {
                 path: tres_path,
                 source: e,
             })?;
+
+            if let Some(borders) = sprite.nine_slice {
+                let stylebox_path = output_dir.join(stylebox_filename(&sprite.name));
+                let content = generate_stylebox_tres(sprite, borders, &res_path, version);
+
+                fs::write(&stylebox_path, content).map_err(|e| BentoError::OutputWrite {
+                    path: stylebox_path,
+                    source: e,
+                })?;
+            }
         }
     }
 
     Ok(())
 }
 
-fn generate_tres(sprite: &PackedSprite, atlas_path: &str) -> String {
+/// Resource header, texture ext_resource and the expression referring to it
+fn resource_header(
+    resource_type: &str,
+    atlas_path: &str,
+    version: GodotVersion,
+) -> (String, &'static str) {
+    match version {
+        GodotVersion::V3 => (
+            format!(
+                r#"[gd_resource type="{}" load_steps=2 format=2]
+
+[ext_resource path="{}" type="Texture" id=1]
+"#,
+                resource_type, atlas_path
+            ),
+            "ExtResource( 1 )",
+        ),
+        GodotVersion::V4 => (
+            format!(
+                r#"[gd_resource type="{}" load_steps=2 format=3]
+
+[ext_resource type="Texture2D" path="{}" id="1"]
+"#,
+                resource_type, atlas_path
+            ),
+            r#"ExtResource("1")"#,
+        ),
+    }
+}
+
+/// A `Rect2` literal; Godot 3 pads the parentheses with spaces
+fn rect2(
+    version: GodotVersion,
+    x: impl Display,
+    y: impl Display,
+    w: impl Display,
+    h: impl Display,
+) -> String {
+    match version {
+        GodotVersion::V3 => format!("Rect2( {}, {}, {}, {} )", x, y, w, h),
+        GodotVersion::V4 => format!("Rect2({}, {}, {}, {})", x, y, w, h),
+    }
+}
+
+/// A sprite's atlas region as a `Rect2` literal, ready to paste into GDScript
+pub fn godot_region(sprite: &PackedSprite, version: GodotVersion) -> String {
+    rect2(version, sprite.x, sprite.y, sprite.width, sprite.height)
+}
+
+fn generate_tres(sprite: &PackedSprite, atlas_path: &str, version: GodotVersion) -> String {
     let (margin_left, margin_top, margin_right, margin_bottom) = sprite.trim_info.godot_margin();
 
     let has_margin = margin_left != 0 || margin_top != 0 || margin_right != 0 || margin_bottom != 0;
 
+    let (header, texture) = resource_header("AtlasTexture", atlas_path, version);
     let mut content = format!(
-        r#"[gd_resource type="AtlasTexture" load_steps=2 format=3]
-
-[ext_resource type="Texture2D" path="{}" id="1"]
-
-[resource]
-atlas = ExtResource("1")
-region = Rect2({}, {}, {}, {})"#,
-        atlas_path, sprite.x, sprite.y, sprite.width, sprite.height
+        "{}\n[resource]\natlas = {}\nregion = {}",
+        header,
+        texture,
+        rect2(version, sprite.x, sprite.y, sprite.width, sprite.height)
     );
 
     if has_margin {
         content.push_str(&format!(
-            "\nmargin = Rect2({}, {}, {}, {})",
-            margin_left, margin_top, margin_right, margin_bottom
+            "\nmargin = {}",
+            rect2(
+                version,
+                margin_left,
+                margin_top,
+                margin_right,
+                margin_bottom
+            )
         ));
     }
 
@@ -64,6 +145,46 @@ region = Rect2({}, {}, {}, {})"#,
     content
 }
 
+/// File name of the StyleBoxTexture resource generated for a nine-slice sprite
+pub fn stylebox_filename(sprite_name: &str) -> String {
+    format!("{}.stylebox.tres", sprite_name)
+}
+
+fn generate_stylebox_tres(
+    sprite: &PackedSprite,
+    borders: NineSlice,
+    atlas_path: &str,
+    version: GodotVersion,
+) -> String {
+    // Margins apply to the region, which holds the trimmed frame
+    let margins = borders.trimmed(&sprite.trim_info);
+    // Godot 4 renamed margin_* to texture_margin_*
+    let margin = match version {
+        GodotVersion::V3 => "margin",
+        GodotVersion::V4 => "texture_margin",
+    };
+
+    let (header, texture) = resource_header("StyleBoxTexture", atlas_path, version);
+    format!(
+        r#"{}
+[resource]
+texture = {}
+{margin}_left = {}.0
+{margin}_top = {}.0
+{margin}_right = {}.0
+{margin}_bottom = {}.0
+region_rect = {}
+"#,
+        header,
+        texture,
+        margins.left,
+        margins.top,
+        margins.right,
+        margins.bottom,
+        rect2(version, sprite.x, sprite.y, sprite.width, sprite.height)
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -73,25 +194,36 @@ mod tests {
     fn test_generate_tres_no_margin() {
         let sprite = PackedSprite {
             name: "test".to_string(),
+            path: std::path::PathBuf::from("test"),
             x: 10,
             y: 20,
             width: 32,
             height: 32,
             trim_info: TrimInfo::untrimmed(32, 32),
             atlas_index: 0,
+            input_index: 0,
+            content_bounds: None,
+            nine_slice: None,
+            pivot: None,
+            rotated: false,
         };
 
-        let tres = generate_tres(&sprite, "res://atlas_0.png");
+        let tres = generate_tres(&sprite, "res://atlas_0.png", GodotVersion::V4);
 
         assert!(tres.contains("region = Rect2(10, 20, 32, 32)"));
         assert!(!tres.contains("margin"));
         assert!(tres.contains("filter_clip = true"));
+        assert_eq!(
+            godot_region(&sprite, GodotVersion::V3),
+            "Rect2( 10, 20, 32, 32 )"
+        );
     }
 
     #[test]
     fn test_generate_tres_with_margin() {
         let sprite = PackedSprite {
             name: "test".to_string(),
+            path: std::path::PathBuf::from("test"),
             x: 10,
             y: 20,
             width: 28,
@@ -105,11 +237,72 @@ mod tests {
                 trimmed_height: 28,
             },
             atlas_index: 0,
+            input_index: 0,
+            content_bounds: None,
+            nine_slice: None,
+            pivot: None,
+            rotated: false,
         };
 
-        let tres = generate_tres(&sprite, "res://atlas_0.png");
+        let tres = generate_tres(&sprite, "res://atlas_0.png", GodotVersion::V4);
 
         assert!(tres.contains("region = Rect2(10, 20, 28, 28)"));
         assert!(tres.contains("margin = Rect2(2, 2, 4, 4)"));
+
+        let tres = generate_tres(&sprite, "res://atlas_0.png", GodotVersion::V3);
+        assert!(tres.starts_with(
+            "[gd_resource type=\"AtlasTexture\" load_steps=2 format=2]\n\n\
+             [ext_resource path=\"res://atlas_0.png\" type=\"Texture\" id=1]\n\n\
+             [resource]\natlas = ExtResource( 1 )\n"
+        ));
+        assert!(tres.contains("region = Rect2( 10, 20, 28, 28 )"));
+        assert!(tres.contains("margin = Rect2( 2, 2, 4, 4 )"));
+    }
+
+    #[test]
+    fn test_generate_stylebox_tres_adjusts_for_trim() {
+        let sprite = PackedSprite {
+            name: "panel".to_string(),
+            path: std::path::PathBuf::from("panel"),
+            x: 10,
+            y: 20,
+            width: 28,
+            height: 30,
+            trim_info: TrimInfo {
+                offset_x: 2,
+                offset_y: 0,
+                source_width: 32,
+                source_height: 32,
+                trimmed_width: 28,
+                trimmed_height: 30,
+            },
+            atlas_index: 0,
+            input_index: 0,
+            content_bounds: None,
+            nine_slice: None,
+            pivot: None,
+            rotated: false,
+        };
+        let borders = NineSlice {
+            left: 8,
+            top: 6,
+            right: 8,
+            bottom: 6,
+        };
+
+        let tres = generate_stylebox_tres(&sprite, borders, "res://atlas.png", GodotVersion::V4);
+
+        assert!(tres.contains(r#"type="StyleBoxTexture""#));
+        assert!(tres.contains("region_rect = Rect2(10, 20, 28, 30)"));
+        assert!(tres.contains("texture_margin_left = 6.0"));
+        assert!(tres.contains("texture_margin_top = 6.0"));
+        assert!(tres.contains("texture_margin_right = 6.0"));
+        assert!(tres.contains("texture_margin_bottom = 4.0"));
+
+        let tres = generate_stylebox_tres(&sprite, borders, "res://atlas.png", GodotVersion::V3);
+        assert!(tres.contains("texture = ExtResource( 1 )"));
+        assert!(tres.contains("region_rect = Rect2( 10, 20, 28, 30 )"));
+        assert!(tres.contains("\nmargin_left = 6.0"));
+        assert!(tres.contains("\nmargin_bottom = 4.0"));
     }
 }
@@ -1,18 +1,29 @@
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::Path;
 
-use anyhow::Result;
-use serde::Serialize;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
 
 use crate::atlas::Atlas;
+use crate::cli::{SourcePaths, SpriteOrder};
 use crate::error::BentoError;
-use crate::output::atlas_png_filename;
-use crate::sprite::PackedSprite;
+use crate::output::{
+    OutputTemplate, SheetPage, json_string, ordered_sprites, page_layout, prefixed_image_path,
+    sha256_hex, source_path_string,
+};
+use crate::sprite::{
+    Animation, ContentBounds, NineSlice, PackedSprite, Pivot, SpriteMesh, TrimInfo,
+};
 
+/// Contents of a JSON metadata file, as built by [`json_metadata`]
 #[derive(Serialize)]
-struct JsonOutput {
+pub struct JsonOutput<'a> {
     meta: Meta,
     atlases: Vec<JsonAtlas>,
+    /// Tagged frame ranges of Aseprite inputs
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    animations: &'a [Animation],
 }
 
 #[derive(Serialize)]
@@ -20,32 +31,80 @@ struct Meta {
     app: &'static str,
     version: &'static str,
     format: &'static str,
+    /// SHA-256 digests, when requested with `--content-hash`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hashes: Option<ContentHashes>,
 }
 
 #[derive(Serialize)]
+struct ContentHashes {
+    /// Digest of the `atlases` entries: page images, sizes and sprite frames
+    layout: String,
+    /// Digest of each written page image and alpha mask, keyed by its path
+    /// in `atlases`
+    images: BTreeMap<String, String>,
+}
+
+/// The parts of a JSON file needed to read it back
+#[derive(Deserialize)]
+struct JsonInput {
+    atlases: Vec<JsonAtlas>,
+}
+
+#[derive(Serialize, Deserialize)]
 struct JsonAtlas {
     image: String,
+    /// Grayscale alpha mask, present for split-alpha output
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    alpha: Option<String>,
     size: Size,
     sprites: Vec<JsonSprite>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize)]
 struct Size {
     w: u32,
     h: u32,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct JsonSprite {
     name: String,
     frame: Frame,
+    /// Stored turned 90° clockwise; `frame` holds the rotated size
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    rotated: bool,
     trimmed: bool,
     sprite_source_size: Frame,
     source_size: Size,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    content_bounds: Option<Frame>,
+    /// Nine-slice borders in source-image pixels
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    nine_slice: Option<NineSlice>,
+    /// Origin as a fraction of `sourceSize`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pivot: Option<Pivot>,
+    /// Source image path, when requested with `--source-paths`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    source_path: Option<String>,
+    /// Outline corners in `sourceSize` pixels, when requested with `--polygon`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    vertices: Option<Vec<[u32; 2]>>,
+    /// The same corners in atlas pixels
+    #[serde(
+        default,
+        rename = "verticesUV",
+        skip_serializing_if = "Option::is_none"
+    )]
+    vertices_uv: Option<Vec<[u32; 2]>>,
+    /// Triangles as indices into `vertices`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    triangles: Option<Vec<[usize; 3]>>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize)]
 struct Frame {
     x: u32,
     y: u32,
@@ -53,17 +112,111 @@ struct Frame {
     h: u32,
 }
 
-/// Write JSON metadata file
-pub fn write_json(atlases: &[Atlas], output_dir: &Path, base_name: &str) -> Result<()> {
+/// Write JSON metadata file. With `source_paths`, each sprite records its
+/// source image path in the given style, relative paths resolving against
+/// the accompanying base directory. Animations from Aseprite tags are listed
+/// after the atlases. With `polygon`, each sprite gets an outline mesh
+/// simplified to that tolerance in pixels, traced from the page image. With
+/// `content_hash`, the `meta` block gets SHA-256 digests of the layout and
+/// of the page images, which must already be saved in `output_dir`.
+#[expect(
+    clippy::too_many_arguments,
+    reason = "mirrors the independent JSON output options"
+)]
+pub fn write_json(
+    atlases: &[Atlas],
+    output_dir: &Path,
+    base_name: &str,
+    output_template: &OutputTemplate,
+    order: SpriteOrder,
+    compact: bool,
+    split_alpha: bool,
+    image_path_prefix: Option<&str>,
+    source_paths: Option<(SourcePaths, &Path)>,
+    polygon: Option<f32>,
+    content_hash: bool,
+    animations: &[Animation],
+) -> Result<()> {
+    let mut output = json_metadata(
+        atlases,
+        base_name,
+        output_template,
+        order,
+        split_alpha,
+        image_path_prefix,
+        source_paths,
+        polygon,
+        animations,
+    );
+    if content_hash {
+        output.meta.hashes = Some(content_hashes(
+            &output.atlases,
+            atlases,
+            output_dir,
+            base_name,
+            output_template,
+        )?);
+    }
+
+    let json_path = output_dir.join(format!("{}.json", base_name));
+    let content = json_string(&output, compact)?;
+
+    fs::write(&json_path, content).map_err(|e| BentoError::OutputWrite {
+        path: json_path,
+        source: e,
+    })?;
+
+    Ok(())
+}
+
+/// The metadata [`write_json`] writes, for serializing elsewhere. Image
+/// paths are the file names pages would be saved under for `base_name`.
+#[expect(
+    clippy::too_many_arguments,
+    reason = "mirrors the independent JSON output options"
+)]
+pub fn json_metadata<'a>(
+    atlases: &[Atlas],
+    base_name: &str,
+    output_template: &OutputTemplate,
+    order: SpriteOrder,
+    split_alpha: bool,
+    image_path_prefix: Option<&str>,
+    source_paths: Option<(SourcePaths, &Path)>,
+    polygon: Option<f32>,
+    animations: &'a [Animation],
+) -> JsonOutput<'a> {
     let total = atlases.len();
     let json_atlases: Vec<_> = atlases
         .iter()
         .map(|atlas| {
-            let image = atlas_png_filename(base_name, atlas.index, total);
-            let sprites = atlas.sprites.iter().map(sprite_to_json).collect();
+            let image = prefixed_image_path(
+                image_path_prefix,
+                output_template.png_filename(base_name, atlas.index, total),
+            );
+            let sprites = ordered_sprites(&atlas.sprites, order)
+                .into_iter()
+                .map(|sprite| {
+                    let mut json = sprite_to_json(sprite);
+                    json.source_path = source_paths
+                        .map(|(style, base)| source_path_string(&sprite.path, style, base));
+                    if let Some(mesh) = polygon.and_then(|tolerance| {
+                        SpriteMesh::from_image(&atlas.sprite_image(sprite, false), tolerance)
+                    }) {
+                        set_mesh(&mut json, sprite, mesh);
+                    }
+                    json
+                })
+                .collect();
 
             JsonAtlas {
                 image,
+                alpha: split_alpha.then(|| {
+                    prefixed_image_path(
+                        image_path_prefix,
+                        output_template.alpha_filename(base_name, atlas.index, total),
+                    )
+                }),
                 size: Size {
                     w: atlas.width,
                     h: atlas.height,
@@ -73,24 +226,120 @@ pub fn write_json(atlases: &[Atlas], output_dir: &Path, base_name: &str) -> Resu
         })
         .collect();
 
-    let output = JsonOutput {
+    JsonOutput {
         meta: Meta {
             app: "bento",
             version: env!("CARGO_PKG_VERSION"),
             format: "rgba8888",
+            hashes: None,
         },
         atlases: json_atlases,
-    };
+        animations,
+    }
+}
 
-    let json_path = output_dir.join(format!("{}.json", base_name));
-    let content = serde_json::to_string_pretty(&output)?;
+/// Digests of the serialized `entries` and of the images saved for
+/// `atlases`, read back from `output_dir`
+fn content_hashes(
+    entries: &[JsonAtlas],
+    atlases: &[Atlas],
+    output_dir: &Path,
+    base_name: &str,
+    output_template: &OutputTemplate,
+) -> Result<ContentHashes> {
+    let total = atlases.len();
+    let mut images = BTreeMap::new();
+    for (entry, atlas) in entries.iter().zip(atlases) {
+        let files = [
+            Some((
+                &entry.image,
+                output_template.png_filename(base_name, atlas.index, total),
+            )),
+            entry.alpha.as_ref().map(|alpha| {
+                (
+                    alpha,
+                    output_template.alpha_filename(base_name, atlas.index, total),
+                )
+            }),
+        ];
+        for (image, file) in files.into_iter().flatten() {
+            let path = output_dir.join(file);
+            let bytes = fs::read(&path)
+                .with_context(|| format!("failed to read {} for hashing", path.display()))?;
+            images.insert(image.clone(), sha256_hex(&bytes));
+        }
+    }
 
-    fs::write(&json_path, content).map_err(|e| BentoError::OutputWrite {
-        path: json_path,
-        source: e,
-    })?;
+    Ok(ContentHashes {
+        layout: sha256_hex(&serde_json::to_vec(entries)?),
+        images,
+    })
+}
 
-    Ok(())
+/// Read atlas pages back from JSON metadata written by [`write_json`]
+pub fn read_json(content: &str) -> Result<Vec<SheetPage>> {
+    let input: JsonInput = serde_json::from_str(content)?;
+    input
+        .atlases
+        .into_iter()
+        .enumerate()
+        .map(|(index, atlas)| {
+            let sprites = atlas
+                .sprites
+                .into_iter()
+                .enumerate()
+                .map(|(input_index, sprite)| sprite_from_json(sprite, index, input_index))
+                .collect::<Result<_>>()?;
+            Ok(SheetPage {
+                image: atlas.image,
+                alpha: atlas.alpha,
+                layout: page_layout(index, atlas.size.w, atlas.size.h, sprites),
+            })
+        })
+        .collect()
+}
+
+fn sprite_from_json(
+    sprite: JsonSprite,
+    atlas_index: usize,
+    input_index: usize,
+) -> Result<PackedSprite> {
+    let source = &sprite.sprite_source_size;
+    Ok(PackedSprite {
+        path: sprite
+            .source_path
+            .unwrap_or_else(|| sprite.name.clone())
+            .into(),
+        name: sprite.name,
+        x: sprite.frame.x,
+        y: sprite.frame.y,
+        width: sprite.frame.w,
+        height: sprite.frame.h,
+        trim_info: TrimInfo {
+            offset_x: i32::try_from(source.x)?,
+            offset_y: i32::try_from(source.y)?,
+            source_width: sprite.source_size.w,
+            source_height: sprite.source_size.h,
+            trimmed_width: source.w,
+            trimmed_height: source.h,
+        },
+        atlas_index,
+        input_index,
+        content_bounds: sprite.content_bounds.map(|b| ContentBounds {
+            x: b.x,
+            y: b.y,
+            width: b.w,
+            height: b.h,
+        }),
+        nine_slice: sprite.nine_slice,
+        pivot: sprite.pivot,
+        rotated: sprite.rotated,
+    })
+}
+
+/// One sprite's entry as it appears in JSON metadata, pretty-printed
+pub fn json_frame(sprite: &PackedSprite) -> String {
+    serde_json::to_string_pretty(&sprite_to_json(sprite)).unwrap_or_default()
 }
 
 fn sprite_to_json(sprite: &PackedSprite) -> JsonSprite {
@@ -104,6 +353,7 @@ fn sprite_to_json(sprite: &PackedSprite) -> JsonSprite {
             w: sprite.width,
             h: sprite.height,
         },
+        rotated: sprite.rotated,
         trimmed: trim.was_trimmed(),
         // offset_x/offset_y are always >= 0 (pixels trimmed from left/top edge)
         #[expect(
@@ -120,5 +370,241 @@ fn sprite_to_json(sprite: &PackedSprite) -> JsonSprite {
             w: trim.source_width,
             h: trim.source_height,
         },
+        content_bounds: sprite.content_bounds.map(|b| Frame {
+            x: b.x,
+            y: b.y,
+            w: b.width,
+            h: b.height,
+        }),
+        nine_slice: sprite.nine_slice,
+        pivot: sprite.pivot,
+        source_path: None,
+        vertices: None,
+        vertices_uv: None,
+        triangles: None,
+    }
+}
+
+/// Add a mesh traced from the upright trimmed sprite, placing its corners in
+/// the source frame and on the (possibly rotated) atlas region
+fn set_mesh(json: &mut JsonSprite, sprite: &PackedSprite, mesh: SpriteMesh) {
+    let frame = &json.sprite_source_size;
+    let trimmed_height = sprite.trim_info.trimmed_height;
+    json.vertices = Some(
+        mesh.vertices
+            .iter()
+            .map(|&[x, y]| [frame.x + x, frame.y + y])
+            .collect(),
+    );
+    json.vertices_uv = Some(
+        mesh.vertices
+            .iter()
+            .map(|&[x, y]| {
+                if sprite.rotated {
+                    // Stored turned 90° clockwise
+                    [sprite.x + trimmed_height - y, sprite.y + x]
+                } else {
+                    [sprite.x + x, sprite.y + y]
+                }
+            })
+            .collect(),
+    );
+    json.triangles = Some(mesh.triangles);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sprite::TrimInfo;
+
+    #[test]
+    fn test_sprite_to_json_nine_slice() {
+        let mut sprite = PackedSprite {
+            name: "ui/panel.png".to_string(),
+            path: std::path::PathBuf::from("ui/panel.png"),
+            x: 0,
+            y: 0,
+            width: 32,
+            height: 32,
+            trim_info: TrimInfo::untrimmed(32, 32),
+            atlas_index: 0,
+            input_index: 0,
+            content_bounds: None,
+            nine_slice: None,
+            pivot: None,
+            rotated: false,
+        };
+        let json = serde_json::to_value(sprite_to_json(&sprite)).unwrap_or_default();
+        assert!(json.get("nineSlice").is_none());
+        // Upright sprites leave `rotated` out, as before rotation was supported
+        assert!(json.get("rotated").is_none());
+        let frame: serde_json::Value =
+            serde_json::from_str(&json_frame(&sprite)).unwrap_or_default();
+        assert_eq!(frame, json);
+
+        sprite.nine_slice = Some(NineSlice {
+            left: 8,
+            top: 6,
+            right: 8,
+            bottom: 10,
+        });
+        let json = serde_json::to_value(sprite_to_json(&sprite)).unwrap_or_default();
+        assert_eq!(
+            json["nineSlice"],
+            serde_json::json!({"left": 8, "top": 6, "right": 8, "bottom": 10})
+        );
+    }
+
+    #[test]
+    fn test_set_mesh_places_vertices() {
+        let mut sprite = PackedSprite {
+            name: "leaf.png".to_string(),
+            path: std::path::PathBuf::from("leaf.png"),
+            x: 10,
+            y: 20,
+            width: 4,
+            height: 2,
+            trim_info: TrimInfo {
+                offset_x: 3,
+                offset_y: 1,
+                source_width: 8,
+                source_height: 8,
+                trimmed_width: 4,
+                trimmed_height: 2,
+            },
+            atlas_index: 0,
+            input_index: 0,
+            content_bounds: None,
+            nine_slice: None,
+            pivot: None,
+            rotated: false,
+        };
+        let mesh = SpriteMesh {
+            vertices: vec![[0, 0], [4, 0], [0, 2]],
+            triangles: vec![[0, 1, 2]],
+        };
+
+        let mut json = sprite_to_json(&sprite);
+        set_mesh(&mut json, &sprite, mesh.clone());
+        assert_eq!(json.vertices, Some(vec![[3, 1], [7, 1], [3, 3]]));
+        assert_eq!(json.vertices_uv, Some(vec![[10, 20], [14, 20], [10, 22]]));
+        assert_eq!(json.triangles, Some(vec![[0, 1, 2]]));
+
+        // A rotated sprite's frame is 2x4, with the upright top-left corner at its top right
+        sprite.rotated = true;
+        (sprite.width, sprite.height) = (2, 4);
+        let mut json = sprite_to_json(&sprite);
+        set_mesh(&mut json, &sprite, mesh);
+        assert_eq!(json.vertices, Some(vec![[3, 1], [7, 1], [3, 3]]));
+        assert_eq!(json.vertices_uv, Some(vec![[12, 20], [12, 24], [10, 20]]));
+
+        let value = serde_json::to_value(&json).unwrap_or_default();
+        assert_eq!(value["rotated"], true);
+        assert_eq!(value["verticesUV"][1], serde_json::json!([12, 24]));
+    }
+
+    #[test]
+    fn test_json_metadata_from_in_memory_images() {
+        let images = vec![
+            (
+                "hero".to_string(),
+                image::RgbaImage::from_pixel(8, 4, image::Rgba([255; 4])),
+            ),
+            (
+                "coin".to_string(),
+                image::RgbaImage::from_pixel(2, 2, image::Rgba([255; 4])),
+            ),
+        ];
+        let sprites =
+            crate::sprite::sprites_from_images(images, &crate::sprite::LoadOptions::default())
+                .unwrap_or_default();
+        let atlases = crate::AtlasBuilder::new(64, 64)
+            .build(sprites)
+            .unwrap_or_default();
+
+        let metadata = json_metadata(
+            &atlases,
+            "items",
+            &OutputTemplate::default(),
+            SpriteOrder::Name,
+            false,
+            None,
+            None,
+            None,
+            &[],
+        );
+        let json = serde_json::to_value(&metadata).unwrap_or_default();
+        assert_eq!(json["atlases"][0]["image"], "items.png");
+        assert_eq!(json["atlases"][0]["sprites"][0]["name"], "coin");
+        assert_eq!(json["atlases"][0]["sprites"][1]["sourceSize"]["w"], 8);
+
+        // The serialized metadata reads back like a written file
+        let pages = read_json(&json.to_string()).unwrap_or_default();
+        assert_eq!(pages.len(), 1);
+        assert_eq!(pages[0].layout.sprites.len(), 2);
+    }
+
+    #[test]
+    fn test_write_json_content_hashes() {
+        assert_eq!(
+            sha256_hex(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+
+        let dir = std::env::temp_dir().join("bento_json_content_hash");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap_or_default();
+        let images = vec![(
+            "hero".to_string(),
+            image::RgbaImage::from_pixel(8, 4, image::Rgba([255; 4])),
+        )];
+        let sprites =
+            crate::sprite::sprites_from_images(images, &crate::sprite::LoadOptions::default())
+                .unwrap_or_default();
+        let atlases = crate::AtlasBuilder::new(64, 64)
+            .build(sprites)
+            .unwrap_or_default();
+        let template = OutputTemplate::default();
+        let png = dir.join(template.png_filename("items", 0, 1));
+        crate::output::save_atlas_image(&atlases[0], &png, false, None, None).unwrap_or_default();
+
+        let write = |content_hash| {
+            write_json(
+                &atlases,
+                &dir,
+                "items",
+                &template,
+                SpriteOrder::Name,
+                false,
+                false,
+                Some("textures"),
+                None,
+                None,
+                content_hash,
+                &[],
+            )
+            .is_ok()
+        };
+        let read = || -> serde_json::Value {
+            let content = fs::read_to_string(dir.join("items.json")).unwrap_or_default();
+            serde_json::from_str(&content).unwrap_or_default()
+        };
+
+        assert!(write(false));
+        assert!(read()["meta"].get("hashes").is_none());
+
+        assert!(write(true));
+        let json = read();
+        let hashes = &json["meta"]["hashes"];
+        let png_hash = sha256_hex(&fs::read(&png).unwrap_or_default());
+        assert_eq!(hashes["images"]["textures/items.png"], png_hash);
+        let layout = serde_json::to_vec(&json["atlases"]).unwrap_or_default();
+        assert_eq!(hashes["layout"], sha256_hex(&layout));
+
+        // Hashing needs the page images on disk
+        fs::remove_file(&png).unwrap_or_default();
+        assert!(!write(true));
+
+        let _ = fs::remove_dir_all(&dir);
     }
 }
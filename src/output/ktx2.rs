@@ -0,0 +1,152 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::Result;
+use image::RgbaImage;
+
+use crate::error::BentoError;
+
+/// KTX2 file identifier («KTX 20»\r\n\x1A\n)
+const IDENTIFIER: [u8; 12] = [
+    0xAB, 0x4B, 0x54, 0x58, 0x20, 0x32, 0x30, 0xBB, 0x0D, 0x0A, 0x1A, 0x0A,
+];
+/// VK_FORMAT_R8G8B8A8_SRGB
+const VK_FORMAT_R8G8B8A8_SRGB: u32 = 43;
+/// Identifier, header and index, before the level index
+const HEADER_LEN: usize = 12 + 9 * 4 + 4 * 4 + 2 * 8;
+/// Bytes per level index entry
+const LEVEL_INDEX_ENTRY_LEN: usize = 3 * 8;
+
+/// Write an uncompressed RGBA8 (sRGB) KTX2 texture: the base image followed
+/// by its mip levels (level 1 onward)
+pub fn write_ktx2(base: &RgbaImage, mips: &[RgbaImage], path: &Path) -> Result<()> {
+    let levels: Vec<&RgbaImage> = std::iter::once(base).chain(mips).collect();
+    fs::write(path, encode_ktx2(&levels)).map_err(|e| BentoError::OutputWrite {
+        path: path.to_path_buf(),
+        source: e,
+    })?;
+    Ok(())
+}
+
+#[expect(
+    clippy::cast_possible_truncation,
+    reason = "KTX2 stores level counts and section offsets as u32"
+)]
+fn encode_ktx2(levels: &[&RgbaImage]) -> Vec<u8> {
+    let base = levels[0];
+    let dfd = data_format_descriptor();
+    let dfd_offset = HEADER_LEN + LEVEL_INDEX_ENTRY_LEN * levels.len();
+    // The DFD is a multiple of 4 bytes long, so level data stays 4-byte aligned
+    let data_offset = dfd_offset + dfd.len();
+
+    let mut out = Vec::new();
+    out.extend_from_slice(&IDENTIFIER);
+    for value in [
+        VK_FORMAT_R8G8B8A8_SRGB,
+        1, // typeSize
+        base.width(),
+        base.height(),
+        0, // pixelDepth
+        0, // layerCount
+        1, // faceCount
+        levels.len() as u32,
+        0, // supercompressionScheme
+        dfd_offset as u32,
+        dfd.len() as u32,
+        0, // kvdByteOffset
+        0, // kvdByteLength
+    ] {
+        out.extend_from_slice(&value.to_le_bytes());
+    }
+    out.extend_from_slice(&0u64.to_le_bytes()); // sgdByteOffset
+    out.extend_from_slice(&0u64.to_le_bytes()); // sgdByteLength
+
+    // Level data is stored smallest mip first, but indexed from level 0
+    let mut offsets = vec![0usize; levels.len()];
+    let mut offset = data_offset;
+    for (i, level) in levels.iter().enumerate().rev() {
+        offsets[i] = offset;
+        offset += level.as_raw().len();
+    }
+    for (level, level_offset) in levels.iter().zip(&offsets) {
+        let len = level.as_raw().len() as u64;
+        out.extend_from_slice(&(*level_offset as u64).to_le_bytes());
+        out.extend_from_slice(&len.to_le_bytes()); // byteLength
+        out.extend_from_slice(&len.to_le_bytes()); // uncompressedByteLength
+    }
+
+    out.extend_from_slice(&dfd);
+    for level in levels.iter().rev() {
+        out.extend_from_slice(level.as_raw());
+    }
+    out
+}
+
+/// Basic data format descriptor for 8-bit sRGB RGBA with straight alpha
+fn data_format_descriptor() -> Vec<u8> {
+    const SAMPLES: [(u16, u8); 4] = [
+        (0, 0),     // R
+        (8, 1),     // G
+        (16, 2),    // B
+        (24, 0x1F), // A, linear (alpha is never sRGB-encoded)
+    ];
+    let block_len: u16 = 24 + 16 * 4;
+
+    let mut dfd = Vec::new();
+    dfd.extend_from_slice(&(u32::from(block_len) + 4).to_le_bytes()); // dfdTotalSize
+    dfd.extend_from_slice(&0u32.to_le_bytes()); // vendorId, descriptorType
+    dfd.extend_from_slice(&2u16.to_le_bytes()); // versionNumber
+    dfd.extend_from_slice(&block_len.to_le_bytes());
+    dfd.extend_from_slice(&[1, 1, 2, 0]); // RGBSDA model, BT.709 primaries, sRGB transfer, straight alpha
+    dfd.extend_from_slice(&[0; 4]); // texelBlockDimension (1x1x1x1)
+    dfd.extend_from_slice(&[4, 0, 0, 0, 0, 0, 0, 0]); // bytesPlane
+    for (bit_offset, channel) in SAMPLES {
+        dfd.extend_from_slice(&bit_offset.to_le_bytes());
+        dfd.push(7); // bitLength - 1
+        dfd.push(channel);
+        dfd.extend_from_slice(&[0; 4]); // samplePosition
+        dfd.extend_from_slice(&0u32.to_le_bytes()); // sampleLower
+        dfd.extend_from_slice(&255u32.to_le_bytes()); // sampleUpper
+    }
+    dfd
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn u32_at(bytes: &[u8], offset: usize) -> u32 {
+        u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap_or_default())
+    }
+
+    fn offset_at(bytes: &[u8], offset: usize) -> usize {
+        let value = u64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap_or_default());
+        usize::try_from(value).unwrap_or_default()
+    }
+
+    #[test]
+    fn test_encode_ktx2_layout() {
+        let base = RgbaImage::from_pixel(4, 2, image::Rgba([1, 2, 3, 4]));
+        let mip = RgbaImage::from_pixel(2, 1, image::Rgba([5, 6, 7, 8]));
+        let bytes = encode_ktx2(&[&base, &mip]);
+
+        assert_eq!(bytes[..12], IDENTIFIER);
+        assert_eq!(u32_at(&bytes, 12), VK_FORMAT_R8G8B8A8_SRGB);
+        assert_eq!((u32_at(&bytes, 20), u32_at(&bytes, 24)), (4, 2));
+        assert_eq!(u32_at(&bytes, 40), 2); // levelCount
+
+        // DFD sits right after the level index and reports its own size
+        let dfd_offset = u32_at(&bytes, 48) as usize;
+        assert_eq!(dfd_offset, HEADER_LEN + 2 * LEVEL_INDEX_ENTRY_LEN);
+        assert_eq!(u32_at(&bytes, 52), 92);
+        assert_eq!(u32_at(&bytes, dfd_offset), 92);
+
+        // Level 0 is stored last, after the smaller mip
+        let level0 = offset_at(&bytes, HEADER_LEN);
+        let level1 = offset_at(&bytes, HEADER_LEN + LEVEL_INDEX_ENTRY_LEN);
+        assert_eq!(level1, dfd_offset + 92);
+        assert_eq!(level0, level1 + 8);
+        assert_eq!(bytes[level1..level1 + 4], [5, 6, 7, 8]);
+        assert_eq!(bytes[level0..], *base.as_raw());
+    }
+}
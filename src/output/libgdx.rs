@@ -0,0 +1,165 @@
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+use anyhow::Result;
+
+use crate::atlas::Atlas;
+use crate::cli::SpriteOrder;
+use crate::error::BentoError;
+use crate::output::{OutputTemplate, ordered_sprites, prefixed_image_path};
+use crate::sprite::PackedSprite;
+
+/// Write libGDX TextureAtlas `.atlas` metadata. libGDX rotates regions
+/// counter-clockwise while bento rotates them clockwise, so atlases with
+/// rotated sprites are rejected before anything is written.
+pub fn write_libgdx(
+    atlases: &[Atlas],
+    output_dir: &Path,
+    base_name: &str,
+    output_template: &OutputTemplate,
+    order: SpriteOrder,
+    image_path_prefix: Option<&str>,
+) -> Result<()> {
+    if let Some(sprite) = atlases
+        .iter()
+        .flat_map(|atlas| &atlas.sprites)
+        .find(|sprite| sprite.rotated)
+    {
+        return Err(BentoError::RotationUnsupported {
+            name: sprite.name.clone(),
+            format: "libGDX",
+        }
+        .into());
+    }
+
+    let total = atlases.len();
+    let mut content = String::new();
+    for atlas in atlases {
+        let image = prefixed_image_path(
+            image_path_prefix,
+            output_template.png_filename(base_name, atlas.index, total),
+        );
+        // Pages are separated (and the file starts) with a blank line
+        let _ = write!(
+            content,
+            "\n{}\nsize: {}, {}\nformat: RGBA8888\nfilter: Linear, Linear\nrepeat: none\n",
+            image, atlas.width, atlas.height
+        );
+        for sprite in ordered_sprites(&atlas.sprites, order) {
+            content.push_str(&region_entry(sprite));
+        }
+    }
+
+    let atlas_path = output_dir.join(format!("{}.atlas", base_name));
+    fs::write(&atlas_path, content).map_err(|e| BentoError::OutputWrite {
+        path: atlas_path,
+        source: e,
+    })?;
+
+    Ok(())
+}
+
+fn region_entry(sprite: &PackedSprite) -> String {
+    let trim = &sprite.trim_info;
+    let (name, index) = region_name_and_index(&sprite.name);
+    // libGDX measures the offset from the bottom-left corner of the original frame
+    let offset_y =
+        i64::from(trim.source_height) - i64::from(trim.trimmed_height) - i64::from(trim.offset_y);
+
+    let mut entry = format!(
+        "{}\n  rotate: false\n  xy: {}, {}\n  size: {}, {}\n",
+        name, sprite.x, sprite.y, sprite.width, sprite.height
+    );
+    if let Some(borders) = sprite.nine_slice {
+        // Splits apply to the region, which holds the trimmed frame
+        let borders = borders.trimmed(trim);
+        let _ = writeln!(
+            entry,
+            "  split: {}, {}, {}, {}",
+            borders.left, borders.right, borders.top, borders.bottom
+        );
+    }
+    let _ = write!(
+        entry,
+        "  orig: {}, {}\n  offset: {}, {}\n  index: {}\n",
+        trim.source_width, trim.source_height, trim.offset_x, offset_y, index
+    );
+    entry
+}
+
+/// Region name without its image extension, and the animation frame index
+/// taken from a trailing `_<digits>` (or -1), as libGDX's own packer does:
+/// `walk_01.png` becomes region `walk` with index 1.
+fn region_name_and_index(sprite_name: &str) -> (&str, i64) {
+    let file_start = sprite_name.rfind('/').map_or(0, |i| i + 1);
+    let name = match sprite_name[file_start..].rfind('.') {
+        Some(dot) if dot > 0 => &sprite_name[..file_start + dot],
+        _ => sprite_name,
+    };
+    if let Some((base, digits)) = name.rsplit_once('_')
+        && !digits.is_empty()
+        && digits.chars().all(|c| c.is_ascii_digit())
+        && let Ok(index) = digits.parse()
+    {
+        return (base, index);
+    }
+    (name, -1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sprite::{NineSlice, TrimInfo};
+
+    #[test]
+    fn test_region_name_and_index() {
+        assert_eq!(region_name_and_index("hero.png"), ("hero", -1));
+        assert_eq!(region_name_and_index("walk_01.png"), ("walk", 1));
+        assert_eq!(
+            region_name_and_index("ui/button_hover"),
+            ("ui/button_hover", -1)
+        );
+        assert_eq!(
+            region_name_and_index("dir.v2/.hidden"),
+            ("dir.v2/.hidden", -1)
+        );
+    }
+
+    #[test]
+    fn test_region_entry() {
+        let sprite = PackedSprite {
+            name: "panels/panel_3.png".to_string(),
+            path: std::path::PathBuf::from("panel_3.png"),
+            x: 10,
+            y: 20,
+            width: 28,
+            height: 30,
+            trim_info: TrimInfo {
+                offset_x: 2,
+                offset_y: 0,
+                source_width: 32,
+                source_height: 32,
+                trimmed_width: 28,
+                trimmed_height: 30,
+            },
+            atlas_index: 0,
+            input_index: 0,
+            content_bounds: None,
+            nine_slice: Some(NineSlice {
+                left: 8,
+                top: 6,
+                right: 8,
+                bottom: 6,
+            }),
+            pivot: None,
+            rotated: false,
+        };
+
+        assert_eq!(
+            region_entry(&sprite),
+            "panels/panel\n  rotate: false\n  xy: 10, 20\n  size: 28, 30\n  \
+             split: 6, 6, 6, 4\n  orig: 32, 32\n  offset: 2, 2\n  index: 3\n"
+        );
+    }
+}
@@ -1,26 +1,434 @@
+mod cocos;
 mod format;
 mod godot;
 mod json;
+mod ktx2;
+mod libgdx;
+mod phaser;
+mod plist;
+mod template;
 mod tpsheet;
 
-pub use format::save_atlas_image;
-pub use godot::write_godot_resources;
-pub use json::write_json;
-pub use tpsheet::write_tpsheet;
+pub use cocos::{cocos_plist_filename, write_cocos};
+pub use format::{
+    BasisuEncoder, TextureEncoder, add_png_text, generator_text, oxipng_options, save_alpha_mask,
+    save_atlas_image, save_encoded, save_image,
+};
+pub use godot::{godot_region, stylebox_filename, write_godot_resources};
+pub use json::{JsonOutput, json_frame, json_metadata, read_json, write_json};
+pub use ktx2::write_ktx2;
+pub use libgdx::write_libgdx;
+pub use phaser::write_phaser;
+pub use template::{template_output_filename, write_template};
+pub use tpsheet::{read_tpsheet, write_tpsheet};
 
-/// Returns the PNG filename for an atlas. Single-atlas packs use `{name}.png`,
-/// multi-atlas packs use `{name}_{index}.png`.
+use std::fmt::Write;
+use std::path::{Component, Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::atlas::AtlasLayout;
+use crate::cli::{SourcePaths, SpriteOrder};
+use crate::sprite::PackedSprite;
+
+/// An atlas page read back from JSON or tpsheet metadata
+#[derive(Debug, Clone)]
+pub struct SheetPage {
+    /// Atlas image path as written in the metadata
+    pub image: String,
+    /// Alpha mask image path, for split-alpha output
+    pub alpha: Option<String>,
+    /// Page size and sprite placements
+    pub layout: AtlasLayout,
+}
+
+/// Read atlas pages back from a `.json` or `.tpsheet` metadata file
+pub fn read_sheet(path: &Path) -> Result<Vec<SheetPage>> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    let pages = if path.extension().is_some_and(|ext| ext == "tpsheet") {
+        read_tpsheet(&content)
+    } else {
+        read_json(&content)
+    };
+    pages.with_context(|| format!("{} is not bento metadata", path.display()))
+}
+
+/// Page layout for sprites read back from metadata, with occupancy recomputed
+fn page_layout(index: usize, width: u32, height: u32, sprites: Vec<PackedSprite>) -> AtlasLayout {
+    let sprite_area: u64 = sprites
+        .iter()
+        .map(|s| u64::from(s.width) * u64::from(s.height))
+        .sum();
+    let page_area = u64::from(width) * u64::from(height);
+    #[expect(
+        clippy::cast_precision_loss,
+        reason = "approximation acceptable for occupancy"
+    )]
+    let occupancy = if page_area > 0 {
+        sprite_area as f64 / page_area as f64
+    } else {
+        0.0
+    };
+    AtlasLayout {
+        index,
+        width,
+        height,
+        sprites,
+        occupancy,
+        packing: None,
+    }
+}
+
+/// Base name for the output set at a scale: `{name}` at 1x, otherwise
+/// `{name}@{scale}x`, e.g. `atlas@0.5x`
+pub fn scaled_base_name(base_name: &str, scale: f32) -> String {
+    if scale == 1.0 {
+        base_name.to_string()
+    } else {
+        format!("{}@{}x", base_name, scale)
+    }
+}
+
+/// Atlas image file names, from a pattern like `{name}_{index}.png`.
+///
+/// `{name}` is the base name of the output and `{index}` the page number,
+/// zero-padded to N digits with `{index:N}`. A pack with a single page drops
+/// `{index}` along with the `_`, `-`, `.` or space joining it to the rest of
+/// the name. Alpha masks, companion pages, mip levels and KTX2 textures are
+/// named after the page image.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct OutputTemplate {
+    pattern: String,
+    parts: Vec<TemplatePart>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum TemplatePart {
+    Literal(String),
+    Name,
+    Index { width: usize },
+}
+
+impl OutputTemplate {
+    /// File name of an atlas page image
+    pub fn png_filename(&self, base_name: &str, index: usize, total: usize) -> String {
+        format!("{}.png", self.stem(base_name, index, total))
+    }
+
+    /// File name of an atlas page's alpha mask in split-alpha output
+    pub fn alpha_filename(&self, base_name: &str, index: usize, total: usize) -> String {
+        format!("{}_alpha.png", self.stem(base_name, index, total))
+    }
+
+    /// File name of an atlas page's companion, such as `atlas_0_n.png` for
+    /// the `_n` normal maps
+    pub fn companion_filename(
+        &self,
+        base_name: &str,
+        index: usize,
+        total: usize,
+        suffix: &str,
+    ) -> String {
+        format!("{}{}.png", self.stem(base_name, index, total), suffix)
+    }
+
+    /// File name of one mip level of an atlas page
+    pub fn mip_filename(
+        &self,
+        base_name: &str,
+        index: usize,
+        total: usize,
+        level: usize,
+    ) -> String {
+        format!("{}.mip{}.png", self.stem(base_name, index, total), level)
+    }
+
+    /// File name of an atlas page's KTX2 texture
+    pub fn ktx2_filename(&self, base_name: &str, index: usize, total: usize) -> String {
+        format!("{}.ktx2", self.stem(base_name, index, total))
+    }
+
+    /// Page file name without extension, which other per-page files extend
+    pub(crate) fn stem(&self, base_name: &str, index: usize, total: usize) -> String {
+        const SEPARATORS: [char; 4] = ['_', '-', '.', ' '];
+        let mut stem = String::new();
+        let mut skip_separator = false;
+        for part in &self.parts {
+            match part {
+                TemplatePart::Literal(text) => {
+                    let text = match text.strip_prefix(SEPARATORS) {
+                        Some(rest) if skip_separator => rest,
+                        _ => text,
+                    };
+                    stem.push_str(text);
+                    skip_separator = false;
+                }
+                TemplatePart::Name => stem.push_str(base_name),
+                TemplatePart::Index { width } if total > 1 => {
+                    stem.push_str(&format!("{:0width$}", index, width = *width));
+                }
+                TemplatePart::Index { .. } => {
+                    // Drop the separator before the index, or else the one after it
+                    if stem.ends_with(SEPARATORS) {
+                        stem.pop();
+                    } else {
+                        skip_separator = true;
+                    }
+                }
+            }
+        }
+        stem
+    }
+}
+
+impl Default for OutputTemplate {
+    fn default() -> Self {
+        Self {
+            pattern: "{name}_{index}.png".to_string(),
+            parts: vec![
+                TemplatePart::Name,
+                TemplatePart::Literal("_".to_string()),
+                TemplatePart::Index { width: 0 },
+            ],
+        }
+    }
+}
+
+impl std::str::FromStr for OutputTemplate {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.contains(['/', '\\']) {
+            return Err(format!(
+                "output template '{}' must be a file name, not a path",
+                s
+            ));
+        }
+        // The extension is always .png, so it is optional in the pattern
+        let body = match s.len().checked_sub(4) {
+            Some(end) if s.is_char_boundary(end) && s[end..].eq_ignore_ascii_case(".png") => {
+                &s[..end]
+            }
+            _ => s,
+        };
+
+        let mut parts = Vec::new();
+        let mut rest = body;
+        while let Some(open) = rest.find('{') {
+            if open > 0 {
+                parts.push(TemplatePart::Literal(rest[..open].to_string()));
+            }
+            let Some(close) = rest[open..].find('}') else {
+                return Err(format!("unclosed '{{' in output template '{}'", s));
+            };
+            let token = &rest[open + 1..open + close];
+            parts.push(match token.split_once(':') {
+                None if token == "name" => TemplatePart::Name,
+                None if token == "index" => TemplatePart::Index { width: 0 },
+                Some(("index", width)) => match width.parse() {
+                    Ok(width) if width <= 9 => TemplatePart::Index { width },
+                    _ => {
+                        return Err(format!(
+                            "invalid index width '{}' in output template '{}': expected 0-9",
+                            width, s
+                        ));
+                    }
+                },
+                _ => {
+                    return Err(format!(
+                        "unknown placeholder '{{{}}}' in output template '{}': expected \
+                         {{name}}, {{index}} or {{index:N}}",
+                        token, s
+                    ));
+                }
+            });
+            rest = &rest[open + close + 1..];
+        }
+        if !rest.is_empty() {
+            parts.push(TemplatePart::Literal(rest.to_string()));
+        }
+
+        if !parts
+            .iter()
+            .any(|part| matches!(part, TemplatePart::Index { .. }))
+        {
+            return Err(format!(
+                "output template '{}' needs {{index}} so pages get different names",
+                s
+            ));
+        }
+        Ok(Self {
+            pattern: s.to_string(),
+            parts,
+        })
+    }
+}
+
+impl std::fmt::Display for OutputTemplate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.pattern)
+    }
+}
+
+/// Returns the PNG filename for an atlas with the default [`OutputTemplate`].
+/// Single-atlas packs use `{name}.png`, multi-atlas packs use `{name}_{index}.png`.
 pub fn atlas_png_filename(base_name: &str, index: usize, total: usize) -> String {
-    if total == 1 {
-        format!("{}.png", base_name)
+    OutputTemplate::default().png_filename(base_name, index, total)
+}
+
+/// Returns the alpha mask filename for an atlas in split-alpha output,
+/// e.g. `{name}_alpha.png` or `{name}_{index}_alpha.png`.
+pub fn atlas_alpha_filename(base_name: &str, index: usize, total: usize) -> String {
+    OutputTemplate::default().alpha_filename(base_name, index, total)
+}
+
+/// Returns the PNG filename for one mip level of an atlas,
+/// e.g. `{name}.mip1.png` or `{name}_{index}.mip1.png`.
+pub fn atlas_mip_filename(base_name: &str, index: usize, total: usize, level: usize) -> String {
+    OutputTemplate::default().mip_filename(base_name, index, total, level)
+}
+
+/// Returns the KTX2 filename for an atlas with its mip chain,
+/// e.g. `{name}.ktx2` or `{name}_{index}.ktx2`.
+pub fn atlas_ktx2_filename(base_name: &str, index: usize, total: usize) -> String {
+    OutputTemplate::default().ktx2_filename(base_name, index, total)
+}
+
+/// Returns an atlas image path as written in metadata: the file name, under
+/// `prefix` when one is set (e.g. `textures/atlases/atlas.png`)
+pub fn prefixed_image_path(prefix: Option<&str>, file_name: String) -> String {
+    match prefix.map(|p| p.trim_end_matches('/')) {
+        Some(p) if !p.is_empty() => format!("{}/{}", p, file_name),
+        _ => file_name,
+    }
+}
+
+/// Metadata serialized as JSON: minified when `compact`, otherwise
+/// pretty-printed for readable diffs
+pub(crate) fn json_string<T: Serialize>(value: &T, compact: bool) -> serde_json::Result<String> {
+    if compact {
+        serde_json::to_string(value)
+    } else {
+        serde_json::to_string_pretty(value)
+    }
+}
+
+/// Lowercase hex SHA-256 digest of `bytes`
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .fold(String::with_capacity(64), |mut hex, byte| {
+            let _ = write!(hex, "{:02x}", byte);
+            hex
+        })
+}
+
+/// Returns a sprite's source path as written in metadata: absolute, or
+/// relative to `base` (stepping up with `..` where needed)
+pub fn source_path_string(path: &Path, style: SourcePaths, base: &Path) -> String {
+    let absolute = absolute_path(path);
+    let resolved = match style {
+        SourcePaths::Absolute => absolute,
+        SourcePaths::Relative => relative_path(&absolute, &absolute_path(base)).unwrap_or(absolute),
+    };
+    let resolved = resolved.to_string_lossy();
+    if cfg!(windows) {
+        resolved.replace('\\', "/")
     } else {
-        format!("{}_{}.png", base_name, index)
+        resolved.into_owned()
     }
 }
 
+/// Absolute form of a path with `.` and `..` resolved lexically (symlinks are
+/// not followed)
+fn absolute_path(path: &Path) -> PathBuf {
+    let absolute = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+    let mut normalized = PathBuf::new();
+    for component in absolute.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir
+                if matches!(
+                    normalized.components().next_back(),
+                    Some(Component::Normal(_))
+                ) =>
+            {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+/// `path` relative to `base`. None if they don't share a root, e.g. paths on
+/// different Windows drives.
+fn relative_path(path: &Path, base: &Path) -> Option<PathBuf> {
+    let path: Vec<Component> = path.components().collect();
+    let base: Vec<Component> = base.components().collect();
+    if path.first() != base.first() {
+        return None;
+    }
+    let common = path.iter().zip(&base).take_while(|(a, b)| a == b).count();
+    let mut relative: PathBuf = base[common..]
+        .iter()
+        .map(|_| Component::ParentDir)
+        .collect();
+    relative.extend(&path[common..]);
+    Some(relative)
+}
+
+/// Returns an atlas's sprites in the requested metadata order
+pub fn ordered_sprites(sprites: &[PackedSprite], order: SpriteOrder) -> Vec<&PackedSprite> {
+    let mut ordered: Vec<&PackedSprite> = sprites.iter().collect();
+    match order {
+        SpriteOrder::Packed => {}
+        SpriteOrder::Name => ordered.sort_by(|a, b| a.name.cmp(&b.name)),
+        SpriteOrder::Input => ordered.sort_by_key(|s| s.input_index),
+    }
+    ordered
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::sprite::TrimInfo;
+
+    fn sprite(name: &str, input_index: usize) -> PackedSprite {
+        PackedSprite {
+            name: name.to_string(),
+            path: std::path::PathBuf::from(name),
+            x: 0,
+            y: 0,
+            width: 1,
+            height: 1,
+            trim_info: TrimInfo::untrimmed(1, 1),
+            atlas_index: 0,
+            input_index,
+            content_bounds: None,
+            nine_slice: None,
+            pivot: None,
+            rotated: false,
+        }
+    }
+
+    #[test]
+    fn test_ordered_sprites() {
+        let sprites = [sprite("b", 2), sprite("c", 0), sprite("a", 1)];
+        let names = |order| -> Vec<&str> {
+            ordered_sprites(&sprites, order)
+                .iter()
+                .map(|s| s.name.as_str())
+                .collect()
+        };
+
+        assert_eq!(names(SpriteOrder::Packed), ["b", "c", "a"]);
+        assert_eq!(names(SpriteOrder::Name), ["a", "b", "c"]);
+        assert_eq!(names(SpriteOrder::Input), ["c", "a", "b"]);
+    }
 
     #[test]
     fn test_single_atlas_no_suffix() {
@@ -39,4 +447,104 @@ mod tests {
         assert_eq!(atlas_png_filename("atlas", 0, 2), "atlas_0.png");
         assert_eq!(atlas_png_filename("atlas", 1, 2), "atlas_1.png");
     }
+
+    #[test]
+    fn test_scaled_base_name() {
+        assert_eq!(scaled_base_name("atlas", 1.0), "atlas");
+        assert_eq!(scaled_base_name("atlas", 0.5), "atlas@0.5x");
+        assert_eq!(scaled_base_name("atlas", 2.0), "atlas@2x");
+        assert_eq!(
+            atlas_png_filename(&scaled_base_name("atlas", 0.25), 1, 2),
+            "atlas@0.25x_1.png"
+        );
+    }
+
+    #[test]
+    fn test_alpha_mask_filename() {
+        assert_eq!(atlas_alpha_filename("atlas", 0, 1), "atlas_alpha.png");
+        assert_eq!(atlas_alpha_filename("atlas", 1, 2), "atlas_1_alpha.png");
+    }
+
+    #[test]
+    fn test_source_path_string() {
+        let path = Path::new("/project/sprites/ui/button.png");
+        let base = Path::new("/project/config");
+        assert_eq!(
+            source_path_string(path, SourcePaths::Relative, base),
+            "../sprites/ui/button.png"
+        );
+        assert_eq!(
+            source_path_string(path, SourcePaths::Relative, Path::new("/project")),
+            "sprites/ui/button.png"
+        );
+        assert_eq!(
+            source_path_string(path, SourcePaths::Absolute, base),
+            "/project/sprites/ui/button.png"
+        );
+        assert_eq!(
+            source_path_string(
+                Path::new("/project/config/../sprites/./a.png"),
+                SourcePaths::Absolute,
+                base
+            ),
+            "/project/sprites/a.png"
+        );
+
+        // Relative input paths resolve against the working directory
+        let cwd = std::env::current_dir().unwrap_or_default();
+        assert_eq!(
+            source_path_string(Path::new("./a.png"), SourcePaths::Relative, &cwd),
+            "a.png"
+        );
+    }
+
+    #[test]
+    fn test_prefixed_image_path() {
+        let file = || "atlas.png".to_string();
+        assert_eq!(prefixed_image_path(None, file()), "atlas.png");
+        assert_eq!(prefixed_image_path(Some(""), file()), "atlas.png");
+        assert_eq!(
+            prefixed_image_path(Some("textures/atlases/"), file()),
+            "textures/atlases/atlas.png"
+        );
+        assert_eq!(
+            prefixed_image_path(Some("textures"), file()),
+            "textures/atlas.png"
+        );
+    }
+
+    #[test]
+    fn test_mipmap_filenames() {
+        assert_eq!(atlas_mip_filename("atlas", 0, 1, 1), "atlas.mip1.png");
+        assert_eq!(atlas_mip_filename("atlas", 0, 2, 3), "atlas_0.mip3.png");
+        assert_eq!(atlas_ktx2_filename("atlas", 0, 1), "atlas.ktx2");
+        assert_eq!(atlas_ktx2_filename("atlas", 1, 2), "atlas_1.ktx2");
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_output_template() {
+        let template: OutputTemplate = "sheet-{index:3}-{name}.png".parse().unwrap();
+        assert_eq!(template.png_filename("ui", 7, 12), "sheet-007-ui.png");
+        assert_eq!(
+            template.alpha_filename("ui", 7, 12),
+            "sheet-007-ui_alpha.png"
+        );
+        assert_eq!(
+            template.companion_filename("ui", 7, 12, "_n"),
+            "sheet-007-ui_n.png"
+        );
+        assert_eq!(template.png_filename("ui", 0, 1), "sheet-ui.png");
+
+        let template: OutputTemplate = "{index}_{name}".parse().unwrap();
+        assert_eq!(template.png_filename("ui", 2, 3), "2_ui.png");
+        assert_eq!(template.ktx2_filename("ui", 0, 1), "ui.ktx2");
+        assert_eq!(template.to_string(), "{index}_{name}");
+
+        assert!("{name}.png".parse::<OutputTemplate>().is_err());
+        assert!("{name}_{page}.png".parse::<OutputTemplate>().is_err());
+        assert!("{name}_{index:x}.png".parse::<OutputTemplate>().is_err());
+        assert!("out/{name}_{index}.png".parse::<OutputTemplate>().is_err());
+        assert!("{name}_{index".parse::<OutputTemplate>().is_err());
+    }
 }
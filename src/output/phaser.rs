@@ -0,0 +1,357 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::Result;
+use serde::Serialize;
+use serde::ser::Serializer;
+
+use crate::atlas::Atlas;
+use crate::cli::{PhaserFrames, SpriteOrder};
+use crate::error::BentoError;
+use crate::output::{OutputTemplate, json_string, ordered_sprites, prefixed_image_path};
+use crate::sprite::PackedSprite;
+
+/// Single-page sheet, loaded with `this.load.atlas`
+#[derive(Serialize)]
+struct PhaserAtlas {
+    frames: PhaserFrameList,
+    meta: PhaserMeta,
+}
+
+/// Multi-page sheet, loaded with `this.load.multiatlas`
+#[derive(Serialize)]
+struct PhaserMultiAtlas {
+    textures: Vec<PhaserTexture>,
+    meta: PhaserMeta,
+}
+
+#[derive(Serialize)]
+struct PhaserTexture {
+    image: String,
+    format: &'static str,
+    size: Size,
+    scale: u32,
+    frames: Vec<PhaserFrame>,
+}
+
+#[derive(Serialize)]
+#[serde(untagged)]
+enum PhaserFrameList {
+    /// Frames keyed by name, in sprite order
+    Hash(FrameHash),
+    /// Frames carrying their name as `filename`
+    Array(Vec<PhaserFrame>),
+}
+
+struct FrameHash(Vec<PhaserFrame>);
+
+// A map keyed by filename that keeps sprite order, unlike serde_json's Map
+impl Serialize for FrameHash {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.0.iter().map(|frame| (&frame.filename, &frame.body)))
+    }
+}
+
+#[derive(Serialize)]
+struct PhaserFrame {
+    filename: String,
+    #[serde(flatten)]
+    body: FrameBody,
+}
+
+/// A frame without its name, which is the key in hash output
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct FrameBody {
+    frame: Frame,
+    rotated: bool,
+    trimmed: bool,
+    sprite_source_size: Frame,
+    source_size: Size,
+}
+
+#[derive(Serialize)]
+struct PhaserMeta {
+    app: &'static str,
+    version: &'static str,
+    /// Only set for single-page sheets; multi-page sheets name an image per texture
+    #[serde(skip_serializing_if = "Option::is_none")]
+    image: Option<String>,
+    format: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    size: Option<Size>,
+    scale: String,
+}
+
+#[derive(Serialize)]
+struct Frame {
+    x: u32,
+    y: u32,
+    w: u32,
+    h: u32,
+}
+
+#[derive(Serialize)]
+struct Size {
+    w: u32,
+    h: u32,
+}
+
+/// Write Phaser 3 atlas JSON in the TexturePacker layout. A single page is
+/// written as a JSON hash or array sheet; several pages are written as a
+/// multiatlas, whose frames are always arrays.
+#[expect(
+    clippy::too_many_arguments,
+    reason = "mirrors the independent Phaser output options"
+)]
+pub fn write_phaser(
+    atlases: &[Atlas],
+    output_dir: &Path,
+    base_name: &str,
+    output_template: &OutputTemplate,
+    order: SpriteOrder,
+    compact: bool,
+    frames: PhaserFrames,
+    image_path_prefix: Option<&str>,
+) -> Result<()> {
+    let total = atlases.len();
+    let image = |atlas: &Atlas| {
+        prefixed_image_path(
+            image_path_prefix,
+            output_template.png_filename(base_name, atlas.index, total),
+        )
+    };
+    let atlas_frames = |atlas: &Atlas| -> Vec<PhaserFrame> {
+        ordered_sprites(&atlas.sprites, order)
+            .into_iter()
+            .map(sprite_to_frame)
+            .collect()
+    };
+
+    let content = if let [atlas] = atlases {
+        let frames = match frames {
+            PhaserFrames::Hash => PhaserFrameList::Hash(FrameHash(atlas_frames(atlas))),
+            PhaserFrames::Array => PhaserFrameList::Array(atlas_frames(atlas)),
+        };
+        json_string(
+            &PhaserAtlas {
+                frames,
+                meta: meta(
+                    Some(image(atlas)),
+                    Some(Size {
+                        w: atlas.width,
+                        h: atlas.height,
+                    }),
+                    atlas.scale,
+                ),
+            },
+            compact,
+        )?
+    } else {
+        json_string(
+            &PhaserMultiAtlas {
+                textures: atlases
+                    .iter()
+                    .map(|atlas| PhaserTexture {
+                        image: image(atlas),
+                        format: "RGBA8888",
+                        size: Size {
+                            w: atlas.width,
+                            h: atlas.height,
+                        },
+                        scale: 1,
+                        frames: atlas_frames(atlas),
+                    })
+                    .collect(),
+                meta: meta(None, None, atlases.first().map_or(1.0, |a| a.scale)),
+            },
+            compact,
+        )?
+    };
+
+    let json_path = output_dir.join(format!("{}.phaser.json", base_name));
+    fs::write(&json_path, content).map_err(|e| BentoError::OutputWrite {
+        path: json_path,
+        source: e,
+    })?;
+
+    Ok(())
+}
+
+fn meta(image: Option<String>, size: Option<Size>, scale: f32) -> PhaserMeta {
+    PhaserMeta {
+        app: "bento",
+        version: env!("CARGO_PKG_VERSION"),
+        image,
+        format: "RGBA8888",
+        size,
+        scale: scale.to_string(),
+    }
+}
+
+fn sprite_to_frame(sprite: &PackedSprite) -> PhaserFrame {
+    let trim = &sprite.trim_info;
+    // Phaser expects the upright size; the atlas holds rotated frames turned
+    // 90° clockwise, as TexturePacker does
+    let (w, h) = if sprite.rotated {
+        (sprite.height, sprite.width)
+    } else {
+        (sprite.width, sprite.height)
+    };
+
+    PhaserFrame {
+        filename: sprite.name.clone(),
+        body: FrameBody {
+            frame: Frame {
+                x: sprite.x,
+                y: sprite.y,
+                w,
+                h,
+            },
+            rotated: sprite.rotated,
+            trimmed: trim.was_trimmed(),
+            #[expect(
+                clippy::cast_sign_loss,
+                reason = "trim offsets are always non-negative"
+            )]
+            sprite_source_size: Frame {
+                x: trim.offset_x as u32,
+                y: trim.offset_y as u32,
+                w: trim.trimmed_width,
+                h: trim.trimmed_height,
+            },
+            source_size: Size {
+                w: trim.source_width,
+                h: trim.source_height,
+            },
+        },
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::atlas::AtlasLayout;
+    use crate::sprite::TrimInfo;
+
+    #[test]
+    fn test_phaser_frames() {
+        let sprites = vec![
+            PackedSprite {
+                name: "zombie.png".to_string(),
+                path: std::path::PathBuf::from("zombie.png"),
+                x: 0,
+                y: 0,
+                width: 10,
+                height: 20,
+                trim_info: TrimInfo::untrimmed(20, 10),
+                atlas_index: 0,
+                input_index: 0,
+                content_bounds: None,
+                nine_slice: None,
+                pivot: None,
+                rotated: true,
+            },
+            PackedSprite {
+                name: "apple.png".to_string(),
+                path: std::path::PathBuf::from("apple.png"),
+                x: 10,
+                y: 0,
+                width: 6,
+                height: 8,
+                trim_info: TrimInfo {
+                    offset_x: 1,
+                    offset_y: 2,
+                    source_width: 8,
+                    source_height: 12,
+                    trimmed_width: 6,
+                    trimmed_height: 8,
+                },
+                atlas_index: 0,
+                input_index: 1,
+                content_bounds: None,
+                nine_slice: None,
+                pivot: None,
+                rotated: false,
+            },
+        ];
+        let layout = AtlasLayout {
+            index: 0,
+            width: 16,
+            height: 20,
+            sprites,
+            occupancy: 0.0,
+            packing: None,
+        };
+        let atlas = Atlas::from_layout(layout, image::RgbaImage::new(16, 20));
+        let dir = std::env::temp_dir().join("bento_phaser_test");
+        std::fs::create_dir_all(&dir).unwrap();
+        let read = || -> serde_json::Value {
+            serde_json::from_str(&std::fs::read_to_string(dir.join("sheet.phaser.json")).unwrap())
+                .unwrap()
+        };
+
+        let atlases = [atlas];
+        write_phaser(
+            &atlases,
+            &dir,
+            "sheet",
+            &OutputTemplate::default(),
+            SpriteOrder::Packed,
+            false,
+            PhaserFrames::Hash,
+            None,
+        )
+        .unwrap();
+        let hash = read();
+        assert_eq!(hash["meta"]["image"], "sheet.png");
+        // Keys keep sprite order rather than sorting
+        let text = std::fs::read_to_string(dir.join("sheet.phaser.json")).unwrap();
+        assert!(text.find("zombie.png") < text.find("apple.png"));
+        // Rotated frames report their upright size
+        assert_eq!(
+            hash["frames"]["zombie.png"]["frame"],
+            serde_json::json!({"x": 0, "y": 0, "w": 20, "h": 10})
+        );
+        assert_eq!(
+            hash["frames"]["apple.png"]["spriteSourceSize"],
+            serde_json::json!({"x": 1, "y": 2, "w": 6, "h": 8})
+        );
+
+        write_phaser(
+            &atlases,
+            &dir,
+            "sheet",
+            &OutputTemplate::default(),
+            SpriteOrder::Packed,
+            false,
+            PhaserFrames::Array,
+            None,
+        )
+        .unwrap();
+        let array = read();
+        assert_eq!(array["frames"][1]["filename"], "apple.png");
+        assert_eq!(array["frames"][1]["trimmed"], true);
+        assert_eq!(
+            array["frames"][1]["sourceSize"],
+            serde_json::json!({"w": 8, "h": 12})
+        );
+
+        // Compact output holds the same sheet on a single line
+        write_phaser(
+            &atlases,
+            &dir,
+            "sheet",
+            &OutputTemplate::default(),
+            SpriteOrder::Packed,
+            true,
+            PhaserFrames::Array,
+            None,
+        )
+        .unwrap();
+        let text = std::fs::read_to_string(dir.join("sheet.phaser.json")).unwrap();
+        assert!(!text.contains('\n'));
+        assert_eq!(read(), array);
+    }
+}
@@ -0,0 +1,94 @@
+/// A value in an XML property list
+#[derive(Debug, Clone, PartialEq)]
+pub enum PlistValue {
+    String(String),
+    Integer(i64),
+    Bool(bool),
+    Array(Vec<PlistValue>),
+    /// Entries in the order they are written
+    Dict(Vec<(String, PlistValue)>),
+}
+
+impl PlistValue {
+    /// Build a dict from `(key, value)` pairs
+    pub fn dict<K: Into<String>>(entries: impl IntoIterator<Item = (K, PlistValue)>) -> Self {
+        Self::Dict(
+            entries
+                .into_iter()
+                .map(|(key, value)| (key.into(), value))
+                .collect(),
+        )
+    }
+
+    /// Serialize as a complete XML plist document with `self` as the root
+    pub fn to_xml(&self) -> String {
+        let mut out = String::from(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <!DOCTYPE plist PUBLIC \"-//Apple Computer//DTD PLIST 1.0//EN\" \
+             \"http://www.apple.com/DTDs/PropertyList-1.0.dtd\">\n\
+             <plist version=\"1.0\">\n",
+        );
+        self.write_xml(&mut out, 1);
+        out.push_str("</plist>\n");
+        out
+    }
+
+    fn write_xml(&self, out: &mut String, depth: usize) {
+        let indent = "    ".repeat(depth);
+        match self {
+            Self::String(s) => {
+                out.push_str(&format!("{}<string>{}</string>\n", indent, escape(s)));
+            }
+            Self::Integer(n) => out.push_str(&format!("{}<integer>{}</integer>\n", indent, n)),
+            Self::Bool(b) => out.push_str(&format!("{}<{}/>\n", indent, b)),
+            Self::Array(items) if items.is_empty() => {
+                out.push_str(&format!("{}<array/>\n", indent))
+            }
+            Self::Array(items) => {
+                out.push_str(&format!("{}<array>\n", indent));
+                for item in items {
+                    item.write_xml(out, depth + 1);
+                }
+                out.push_str(&format!("{}</array>\n", indent));
+            }
+            Self::Dict(entries) => {
+                out.push_str(&format!("{}<dict>\n", indent));
+                for (key, value) in entries {
+                    out.push_str(&format!("{}    <key>{}</key>\n", indent, escape(key)));
+                    value.write_xml(out, depth + 1);
+                }
+                out.push_str(&format!("{}</dict>\n", indent));
+            }
+        }
+    }
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plist_to_xml() {
+        let plist = PlistValue::dict([
+            ("name", PlistValue::String("a<b>&c".to_string())),
+            ("format", PlistValue::Integer(3)),
+            ("rotated", PlistValue::Bool(false)),
+            ("aliases", PlistValue::Array(Vec::new())),
+        ]);
+        let xml = plist.to_xml();
+
+        assert!(xml.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<!DOCTYPE plist"));
+        assert!(xml.ends_with(
+            "<plist version=\"1.0\">\n    <dict>\n        <key>name</key>\n        \
+             <string>a&lt;b&gt;&amp;c</string>\n        <key>format</key>\n        \
+             <integer>3</integer>\n        <key>rotated</key>\n        <false/>\n        \
+             <key>aliases</key>\n        <array/>\n    </dict>\n</plist>\n"
+        ));
+    }
+}
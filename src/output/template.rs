@@ -0,0 +1,222 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use handlebars::Handlebars;
+use serde::Serialize;
+
+use crate::atlas::Atlas;
+use crate::cli::SpriteOrder;
+use crate::error::BentoError;
+use crate::output::{OutputTemplate, ordered_sprites, prefixed_image_path};
+use crate::sprite::{NineSlice, PackedSprite};
+
+/// Everything a template can refer to
+#[derive(Serialize)]
+struct TemplateContext {
+    app: &'static str,
+    version: &'static str,
+    name: String,
+    atlases: Vec<TemplateAtlas>,
+}
+
+#[derive(Serialize)]
+struct TemplateAtlas {
+    index: usize,
+    image: String,
+    width: u32,
+    height: u32,
+    occupancy: f64,
+    sprites: Vec<TemplateSprite>,
+}
+
+#[derive(Serialize)]
+struct TemplateSprite {
+    name: String,
+    /// Rect in the atlas, as stored (width and height swapped when rotated)
+    x: u32,
+    y: u32,
+    w: u32,
+    h: u32,
+    rotated: bool,
+    trimmed: bool,
+    /// Position of the trimmed rect within the original frame
+    offset_x: i32,
+    offset_y: i32,
+    source_w: u32,
+    source_h: u32,
+    nine_slice: Option<NineSlice>,
+}
+
+/// Output filename for a template: the base name plus the template's own
+/// extension, ignoring a trailing `.hbs`, so `godot.tres.hbs` writes
+/// `{name}.tres`. Templates without one write `{name}.txt`.
+pub fn template_output_filename(base_name: &str, template: &Path) -> String {
+    let file_name = template
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let stem = file_name
+        .strip_suffix(".hbs")
+        .or_else(|| file_name.strip_suffix(".handlebars"))
+        .unwrap_or(&file_name);
+    let extension = Path::new(stem).extension().map_or_else(
+        || "txt".to_string(),
+        |ext| ext.to_string_lossy().into_owned(),
+    );
+    format!("{}.{}", base_name, extension)
+}
+
+/// Render atlas metadata through a Handlebars template and write it next to
+/// the atlases (see [`template_output_filename`]). Values are inserted as-is,
+/// without HTML escaping.
+pub fn write_template(
+    atlases: &[Atlas],
+    output_dir: &Path,
+    base_name: &str,
+    output_template: &OutputTemplate,
+    order: SpriteOrder,
+    image_path_prefix: Option<&str>,
+    template: &Path,
+) -> Result<()> {
+    let source = fs::read_to_string(template)
+        .with_context(|| format!("failed to read template {}", template.display()))?;
+    let context = template_context(
+        atlases,
+        base_name,
+        output_template,
+        order,
+        image_path_prefix,
+    );
+    let content = render(&source, &context).map_err(|message| BentoError::Template {
+        path: template.to_path_buf(),
+        message,
+    })?;
+
+    let output_path = output_dir.join(template_output_filename(base_name, template));
+    fs::write(&output_path, content).map_err(|e| BentoError::OutputWrite {
+        path: output_path,
+        source: e,
+    })?;
+
+    Ok(())
+}
+
+fn template_context(
+    atlases: &[Atlas],
+    base_name: &str,
+    output_template: &OutputTemplate,
+    order: SpriteOrder,
+    image_path_prefix: Option<&str>,
+) -> TemplateContext {
+    let total = atlases.len();
+    TemplateContext {
+        app: "bento",
+        version: env!("CARGO_PKG_VERSION"),
+        name: base_name.to_string(),
+        atlases: atlases
+            .iter()
+            .map(|atlas| TemplateAtlas {
+                index: atlas.index,
+                image: prefixed_image_path(
+                    image_path_prefix,
+                    output_template.png_filename(base_name, atlas.index, total),
+                ),
+                width: atlas.width,
+                height: atlas.height,
+                occupancy: atlas.occupancy,
+                sprites: ordered_sprites(&atlas.sprites, order)
+                    .into_iter()
+                    .map(template_sprite)
+                    .collect(),
+            })
+            .collect(),
+    }
+}
+
+fn template_sprite(sprite: &PackedSprite) -> TemplateSprite {
+    let trim = &sprite.trim_info;
+    TemplateSprite {
+        name: sprite.name.clone(),
+        x: sprite.x,
+        y: sprite.y,
+        w: sprite.width,
+        h: sprite.height,
+        rotated: sprite.rotated,
+        trimmed: trim.was_trimmed(),
+        offset_x: trim.offset_x,
+        offset_y: trim.offset_y,
+        source_w: trim.source_width,
+        source_h: trim.source_height,
+        nine_slice: sprite.nine_slice,
+    }
+}
+
+fn render(source: &str, context: &TemplateContext) -> Result<String, String> {
+    let mut handlebars = Handlebars::new();
+    handlebars.register_escape_fn(handlebars::no_escape);
+    handlebars
+        .render_template(source, context)
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::atlas::AtlasLayout;
+    use crate::sprite::TrimInfo;
+
+    #[test]
+    fn test_template_output_filename() {
+        let name = |template: &str| template_output_filename("atlas", Path::new(template));
+        assert_eq!(name("templates/godot.tres.hbs"), "atlas.tres");
+        assert_eq!(name("sheet.xml"), "atlas.xml");
+        assert_eq!(name("sheet.handlebars"), "atlas.txt");
+        assert_eq!(name("Makefile"), "atlas.txt");
+    }
+
+    #[test]
+    fn test_render_template() {
+        let sprite = |name: &str, x: u32| PackedSprite {
+            name: name.to_string(),
+            path: std::path::PathBuf::from(name),
+            x,
+            y: 0,
+            width: 8,
+            height: 8,
+            trim_info: TrimInfo::untrimmed(8, 8),
+            atlas_index: 0,
+            input_index: 0,
+            content_bounds: None,
+            nine_slice: None,
+            pivot: None,
+            rotated: false,
+        };
+        let layout = AtlasLayout {
+            index: 0,
+            width: 16,
+            height: 8,
+            sprites: vec![sprite("a&b.png", 0), sprite("c.png", 8)],
+            occupancy: 1.0,
+            packing: None,
+        };
+        let atlases = [Atlas::from_layout(layout, image::RgbaImage::new(16, 8))];
+        let context = template_context(
+            &atlases,
+            "atlas",
+            &OutputTemplate::default(),
+            SpriteOrder::Packed,
+            Some("tex/"),
+        );
+
+        let source = "{{#each atlases}}{{image}} {{width}}x{{height}}:\
+                      {{#each sprites}} {{name}}={{x}},{{y}}{{#unless @last}};{{/unless}}\
+                      {{/each}}{{/each}}";
+        assert_eq!(
+            render(source, &context).unwrap(),
+            "tex/atlas.png 16x8: a&b.png=0,0; c.png=8,0"
+        );
+        assert!(render("{{#each}}", &context).is_err());
+    }
+}
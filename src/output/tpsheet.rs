@@ -2,12 +2,15 @@ use std::fs;
 use std::path::Path;
 
 use anyhow::Result;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use crate::atlas::Atlas;
+use crate::cli::SpriteOrder;
 use crate::error::BentoError;
-use crate::output::atlas_png_filename;
-use crate::sprite::PackedSprite;
+use crate::output::{
+    OutputTemplate, SheetPage, json_string, ordered_sprites, page_layout, prefixed_image_path,
+};
+use crate::sprite::{PackedSprite, TrimInfo};
 
 #[derive(Serialize)]
 struct TpsheetOutput {
@@ -15,27 +18,30 @@ struct TpsheetOutput {
     meta: TpMeta,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize)]
 struct TpTexture {
     image: String,
     size: TpSize,
     sprites: Vec<TpSprite>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize)]
 struct TpSize {
     w: u32,
     h: u32,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize)]
 struct TpSprite {
     filename: String,
     region: TpRegion,
     margin: TpMargin,
+    /// Only written for rotated sprites, so unrotated sheets stay unchanged
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    rotated: bool,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize)]
 struct TpRegion {
     x: u32,
     y: u32,
@@ -43,7 +49,7 @@ struct TpRegion {
     h: u32,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize)]
 struct TpMargin {
     x: i32,
     y: i32,
@@ -51,6 +57,12 @@ struct TpMargin {
     h: u32,
 }
 
+/// The parts of a tpsheet file needed to read it back
+#[derive(Deserialize)]
+struct TpsheetInput {
+    textures: Vec<TpTexture>,
+}
+
 #[derive(Serialize)]
 struct TpMeta {
     app: &'static str,
@@ -58,13 +70,27 @@ struct TpMeta {
 }
 
 /// Write TexturePacker .tpsheet metadata file
-pub fn write_tpsheet(atlases: &[Atlas], output_dir: &Path, base_name: &str) -> Result<()> {
+pub fn write_tpsheet(
+    atlases: &[Atlas],
+    output_dir: &Path,
+    base_name: &str,
+    output_template: &OutputTemplate,
+    order: SpriteOrder,
+    compact: bool,
+    image_path_prefix: Option<&str>,
+) -> Result<()> {
     let total = atlases.len();
     let textures: Vec<_> = atlases
         .iter()
         .map(|atlas| {
-            let image = atlas_png_filename(base_name, atlas.index, total);
-            let sprites = atlas.sprites.iter().map(sprite_to_tpsprite).collect();
+            let image = prefixed_image_path(
+                image_path_prefix,
+                output_template.png_filename(base_name, atlas.index, total),
+            );
+            let sprites = ordered_sprites(&atlas.sprites, order)
+                .into_iter()
+                .map(sprite_to_tpsprite)
+                .collect();
 
             TpTexture {
                 image,
@@ -86,7 +112,7 @@ pub fn write_tpsheet(atlases: &[Atlas], output_dir: &Path, base_name: &str) -> R
     };
 
     let tpsheet_path = output_dir.join(format!("{}.tpsheet", base_name));
-    let content = serde_json::to_string_pretty(&output)?;
+    let content = json_string(&output, compact)?;
 
     fs::write(&tpsheet_path, content).map_err(|e| BentoError::OutputWrite {
         path: tpsheet_path,
@@ -96,6 +122,61 @@ pub fn write_tpsheet(atlases: &[Atlas], output_dir: &Path, base_name: &str) -> R
     Ok(())
 }
 
+/// Read atlas pages back from tpsheet metadata written by [`write_tpsheet`]
+pub fn read_tpsheet(content: &str) -> Result<Vec<SheetPage>> {
+    let input: TpsheetInput = serde_json::from_str(content)?;
+    Ok(input
+        .textures
+        .into_iter()
+        .enumerate()
+        .map(|(index, texture)| {
+            let sprites = texture
+                .sprites
+                .into_iter()
+                .enumerate()
+                .map(|(input_index, sprite)| sprite_from_tpsprite(sprite, index, input_index))
+                .collect();
+            SheetPage {
+                image: texture.image,
+                alpha: None,
+                layout: page_layout(index, texture.size.w, texture.size.h, sprites),
+            }
+        })
+        .collect())
+}
+
+fn sprite_from_tpsprite(sprite: TpSprite, atlas_index: usize, input_index: usize) -> PackedSprite {
+    let region = &sprite.region;
+    // The region is stored turned, but trim sizes describe the upright sprite
+    let (trimmed_width, trimmed_height) = if sprite.rotated {
+        (region.h, region.w)
+    } else {
+        (region.w, region.h)
+    };
+    PackedSprite {
+        path: sprite.filename.clone().into(),
+        name: sprite.filename,
+        x: region.x,
+        y: region.y,
+        width: region.w,
+        height: region.h,
+        trim_info: TrimInfo {
+            offset_x: sprite.margin.x,
+            offset_y: sprite.margin.y,
+            source_width: trimmed_width + sprite.margin.w,
+            source_height: trimmed_height + sprite.margin.h,
+            trimmed_width,
+            trimmed_height,
+        },
+        atlas_index,
+        input_index,
+        content_bounds: None,
+        nine_slice: None,
+        pivot: None,
+        rotated: sprite.rotated,
+    }
+}
+
 fn sprite_to_tpsprite(sprite: &PackedSprite) -> TpSprite {
     let trim = &sprite.trim_info;
 
@@ -113,6 +194,7 @@ fn sprite_to_tpsprite(sprite: &PackedSprite) -> TpSprite {
             w: trim.source_width - trim.trimmed_width,
             h: trim.source_height - trim.trimmed_height,
         },
+        rotated: sprite.rotated,
     }
 }
 
@@ -125,12 +207,18 @@ mod tests {
     fn test_sprite_to_tpsprite_untrimmed() {
         let sprite = PackedSprite {
             name: "sprite1.png".to_string(),
+            path: std::path::PathBuf::from("sprite1.png"),
             x: 10,
             y: 20,
             width: 32,
             height: 32,
             trim_info: TrimInfo::untrimmed(32, 32),
             atlas_index: 0,
+            input_index: 0,
+            content_bounds: None,
+            nine_slice: None,
+            pivot: None,
+            rotated: false,
         };
 
         let tp = sprite_to_tpsprite(&sprite);
@@ -150,6 +238,7 @@ mod tests {
     fn test_sprite_to_tpsprite_trimmed() {
         let sprite = PackedSprite {
             name: "folder/sprite2.png".to_string(),
+            path: std::path::PathBuf::from("folder/sprite2.png"),
             x: 34,
             y: 0,
             width: 28,
@@ -163,6 +252,11 @@ mod tests {
                 trimmed_height: 30,
             },
             atlas_index: 0,
+            input_index: 0,
+            content_bounds: None,
+            nine_slice: None,
+            pivot: None,
+            rotated: false,
         };
 
         let tp = sprite_to_tpsprite(&sprite);
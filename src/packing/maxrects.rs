@@ -2,11 +2,13 @@ use super::Rect;
 use crate::cli::PackingHeuristic;
 
 /// MaxRects bin packer implementation
+#[derive(Clone)]
 pub struct MaxRectsPacker {
     bin_width: u32,
     bin_height: u32,
     free_rects: Vec<Rect>,
     placed_rects: Vec<Rect>,
+    allow_rotation: bool,
 }
 
 impl MaxRectsPacker {
@@ -17,11 +19,19 @@ impl MaxRectsPacker {
             bin_height: height,
             free_rects: vec![initial_rect],
             placed_rects: Vec::new(),
+            allow_rotation: false,
         }
     }
 
+    /// Also try each rectangle turned 90°, keeping whichever orientation scores better
+    pub fn allow_rotation(mut self, allow: bool) -> Self {
+        self.allow_rotation = allow;
+        self
+    }
+
     /// Try to insert a rectangle with the given dimensions
-    /// Returns the placed rectangle if successful
+    /// Returns the placed rectangle if successful. When rotation is allowed,
+    /// a rotated placement comes back with its width and height swapped.
     pub fn insert(&mut self, width: u32, height: u32, heuristic: PackingHeuristic) -> Option<Rect> {
         let best_rect = self.find_position(width, height, heuristic)?;
         self.place_rect(best_rect);
@@ -29,6 +39,18 @@ impl MaxRectsPacker {
         Some(best_rect)
     }
 
+    /// Width and height of the bin
+    pub fn bin_size(&self) -> (u32, u32) {
+        (self.bin_width, self.bin_height)
+    }
+
+    /// Mark a rectangle as taken, such as a sprite kept from an earlier pack,
+    /// so nothing is inserted over it
+    pub fn occupy(&mut self, rect: Rect) {
+        self.place_rect(rect);
+        self.placed_rects.push(rect);
+    }
+
     /// Check if a rectangle of the given size can fit
     pub fn can_fit(&self, width: u32, height: u32) -> bool {
         self.free_rects
@@ -36,16 +58,31 @@ impl MaxRectsPacker {
             .any(|r| width <= r.width && height <= r.height)
     }
 
+    /// Free rectangles left in the bin. They are maximal, so neighbors can overlap.
+    pub fn free_rects(&self) -> &[Rect] {
+        &self.free_rects
+    }
+
     fn find_position(&self, width: u32, height: u32, heuristic: PackingHeuristic) -> Option<Rect> {
         let mut best_score = (i64::MAX, i64::MAX);
         let mut best_rect = None;
 
+        // Upright first, so ties keep the rectangle unrotated
+        let orientations = [(width, height), (height, width)];
+        let count = if self.allow_rotation && width != height {
+            2
+        } else {
+            1
+        };
+
         for free_rect in &self.free_rects {
-            if width <= free_rect.width && height <= free_rect.height {
-                let score = self.score_rect(free_rect, width, height, heuristic);
-                if score < best_score {
-                    best_score = score;
-                    best_rect = Some(Rect::new(free_rect.x, free_rect.y, width, height));
+            for &(w, h) in &orientations[..count] {
+                if w <= free_rect.width && h <= free_rect.height {
+                    let score = self.score_rect(free_rect, w, h, heuristic);
+                    if score < best_score {
+                        best_score = score;
+                        best_rect = Some(Rect::new(free_rect.x, free_rect.y, w, h));
+                    }
                 }
             }
         }
@@ -334,6 +371,36 @@ mod tests {
         assert!(!r3.intersects(&r4));
     }
 
+    #[test]
+    fn test_rotation() {
+        // A 10x40 column is left free; a 40x10 rect only fits there turned
+        let mut packer = MaxRectsPacker::new(50, 40);
+        packer
+            .insert(40, 40, PackingHeuristic::BestShortSideFit)
+            .unwrap();
+        assert!(
+            packer
+                .insert(40, 10, PackingHeuristic::BestShortSideFit)
+                .is_none()
+        );
+
+        let mut packer = MaxRectsPacker::new(50, 40).allow_rotation(true);
+        packer
+            .insert(40, 40, PackingHeuristic::BestShortSideFit)
+            .unwrap();
+        let rect = packer
+            .insert(40, 10, PackingHeuristic::BestShortSideFit)
+            .unwrap();
+        assert_eq!(rect, Rect::new(40, 0, 10, 40));
+
+        // Upright placements win ties
+        let mut packer = MaxRectsPacker::new(100, 100).allow_rotation(true);
+        let rect = packer
+            .insert(30, 20, PackingHeuristic::BestShortSideFit)
+            .unwrap();
+        assert_eq!((rect.width, rect.height), (30, 20));
+    }
+
     #[test]
     fn test_too_large() {
         let mut packer = MaxRectsPacker::new(100, 100);
@@ -354,6 +421,18 @@ mod tests {
         assert!(!packer.can_fit(1, 1));
     }
 
+    #[test]
+    fn test_occupy_reserves_space() {
+        let mut packer = MaxRectsPacker::new(100, 100);
+        packer.occupy(Rect::new(0, 0, 100, 60));
+        assert!(!packer.can_fit(100, 41));
+
+        let rect = packer
+            .insert(50, 40, PackingHeuristic::BestShortSideFit)
+            .unwrap();
+        assert_eq!((rect.x, rect.y), (0, 60));
+    }
+
     #[test]
     fn test_occupancy_known_limitation() {
         // The occupancy calculation is approximate because free_rects can overlap.
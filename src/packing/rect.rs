@@ -36,6 +36,18 @@ impl Rect {
             && self.x + self.width >= other.x + other.width
             && self.y + self.height >= other.y + other.height
     }
+
+    /// Overlap of this rectangle with another, if they intersect
+    pub fn intersection(&self, other: &Rect) -> Option<Rect> {
+        if !self.intersects(other) {
+            return None;
+        }
+        let x = self.x.max(other.x);
+        let y = self.y.max(other.y);
+        let right = (self.x + self.width).min(other.x + other.width);
+        let bottom = (self.y + self.height).min(other.y + other.height);
+        Some(Rect::new(x, y, right - x, bottom - y))
+    }
 }
 
 #[cfg(test)]
@@ -63,4 +75,14 @@ mod tests {
         assert!(!inner.contains(&outer));
         assert!(!outer.contains(&partial));
     }
+
+    #[test]
+    fn test_intersection() {
+        let a = Rect::new(0, 0, 10, 10);
+        let b = Rect::new(5, 5, 10, 10);
+        let c = Rect::new(20, 20, 10, 10);
+
+        assert_eq!(a.intersection(&b), Some(Rect::new(5, 5, 5, 5)));
+        assert_eq!(a.intersection(&c), None);
+    }
 }
@@ -0,0 +1,40 @@
+//! Progress reporting for loading, packing and encoding.
+
+use std::fmt;
+use std::sync::Arc;
+
+/// A step of work that has finished
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProgressEvent {
+    /// `done` of `total` input files have been loaded by
+    /// [`load_sprites`](crate::sprite::load_sprites)
+    SpriteLoaded { done: usize, total: usize },
+    /// A page has been laid out by [`AtlasBuilder`](crate::AtlasBuilder),
+    /// bringing the placed sprites to `done` of `total`
+    SpritesPlaced { done: usize, total: usize },
+    /// The image of atlas page `index` has been rendered
+    AtlasRendered { index: usize },
+    /// `done` of `total` atlas pages have been encoded as PNG
+    PngEncoded { done: usize, total: usize },
+}
+
+/// Callback receiving [`ProgressEvent`]s. Loading runs in parallel, so it may
+/// be called from several threads at once.
+#[derive(Clone)]
+pub struct ProgressCallback(Arc<dyn Fn(ProgressEvent) + Send + Sync>);
+
+impl ProgressCallback {
+    pub fn new(callback: impl Fn(ProgressEvent) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(callback))
+    }
+
+    pub fn report(&self, event: ProgressEvent) {
+        (self.0)(event);
+    }
+}
+
+impl fmt::Debug for ProgressCallback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ProgressCallback")
+    }
+}
@@ -0,0 +1,129 @@
+//! Frame extraction for animated GIF and APNG inputs.
+
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+use image::codecs::gif::GifDecoder;
+use image::codecs::png::PngDecoder;
+use image::{AnimationDecoder, Frames, ImageError, ImageResult, RgbaImage};
+
+use crate::error::BentoError;
+
+/// One fully composited frame of an animated image
+pub(crate) struct AnimatedFrame {
+    pub image: RgbaImage,
+    /// Display time in milliseconds
+    pub duration: u32,
+}
+
+/// Every frame of an animated GIF or APNG, or `None` for other files and
+/// images with a single frame.
+pub(crate) fn read_animated_frames(path: &Path) -> Result<Option<Vec<AnimatedFrame>>, BentoError> {
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_lowercase);
+    let frames = match extension.as_deref() {
+        Some("gif") => decode_gif(path),
+        Some("png") => decode_apng(path),
+        _ => return Ok(None),
+    }
+    .and_then(|frames| frames.map_or(Ok(Vec::new()), Frames::collect_frames))
+    .map_err(|source| BentoError::ImageLoad {
+        path: path.to_path_buf(),
+        source,
+    })?;
+
+    if frames.len() < 2 {
+        return Ok(None);
+    }
+    Ok(Some(
+        frames
+            .into_iter()
+            .map(|frame| {
+                let (numer, denom) = frame.delay().numer_denom_ms();
+                AnimatedFrame {
+                    duration: numer.checked_div(denom).unwrap_or(0),
+                    image: frame.into_buffer(),
+                }
+            })
+            .collect(),
+    ))
+}
+
+/// Sprite name of one frame: `walk.gif` becomes `walk_frame0.gif`, `walk_frame1.gif`, ...
+pub(crate) fn animated_frame_name(base: &str, frame: usize) -> String {
+    match base.rsplit_once('.') {
+        Some((title, ext)) if !ext.contains('/') => format!("{}_frame{}.{}", title, frame, ext),
+        _ => format!("{}_frame{}", base, frame),
+    }
+}
+
+fn open(path: &Path) -> ImageResult<BufReader<File>> {
+    Ok(BufReader::new(
+        File::open(path).map_err(ImageError::IoError)?,
+    ))
+}
+
+fn decode_gif(path: &Path) -> ImageResult<Option<Frames<'static>>> {
+    Ok(Some(GifDecoder::new(open(path)?)?.into_frames()))
+}
+
+/// Frames of an APNG; plain PNGs return `None` without decoding pixel data
+fn decode_apng(path: &Path) -> ImageResult<Option<Frames<'static>>> {
+    let decoder = PngDecoder::new(open(path)?)?;
+    if !decoder.is_apng()? {
+        return Ok(None);
+    }
+    Ok(Some(decoder.apng()?.into_frames()))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use image::codecs::gif::GifEncoder;
+    use image::{Delay, Frame, Rgba};
+
+    #[test]
+    fn test_read_gif_frames() {
+        let dir = std::env::temp_dir().join(format!("bento_animated_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("walk.gif");
+        {
+            let mut encoder = GifEncoder::new(File::create(&path).unwrap());
+            for (color, delay) in [([255, 0, 0, 255], 100), ([0, 0, 255, 255], 50)] {
+                let image = RgbaImage::from_pixel(3, 2, Rgba(color));
+                let delay = Delay::from_numer_denom_ms(delay, 1);
+                encoder
+                    .encode_frame(Frame::from_parts(image, 0, 0, delay))
+                    .unwrap();
+            }
+        }
+
+        let frames = read_animated_frames(&path).unwrap().unwrap();
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].duration, 100);
+        assert_eq!(frames[1].duration, 50);
+        assert_eq!(frames[1].image.dimensions(), (3, 2));
+        assert_eq!(*frames[1].image.get_pixel(0, 0), Rgba([0, 0, 255, 255]));
+
+        // A plain PNG has no animation
+        let still = dir.join("still.png");
+        RgbaImage::new(1, 1).save(&still).unwrap();
+        assert!(read_animated_frames(&still).unwrap().is_none());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_animated_frame_name() {
+        assert_eq!(animated_frame_name("walk.gif", 0), "walk_frame0.gif");
+        assert_eq!(
+            animated_frame_name("fx/burst.png", 12),
+            "fx/burst_frame12.png"
+        );
+        assert_eq!(animated_frame_name("noext", 1), "noext_frame1");
+    }
+}
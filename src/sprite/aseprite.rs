@@ -0,0 +1,809 @@
+use std::io::Read;
+use std::path::Path;
+
+use anyhow::Result;
+use flate2::read::ZlibDecoder;
+use image::{Rgba, RgbaImage};
+use serde::Serialize;
+
+use crate::error::BentoError;
+
+const FILE_MAGIC: u16 = 0xA5E0;
+const FRAME_MAGIC: u16 = 0xF1FA;
+const HEADER_SIZE: usize = 128;
+
+const CHUNK_OLD_PALETTE: u16 = 0x0004;
+const CHUNK_LAYER: u16 = 0x2004;
+const CHUNK_CEL: u16 = 0x2005;
+const CHUNK_TAGS: u16 = 0x2018;
+const CHUNK_PALETTE: u16 = 0x2019;
+
+const LAYER_VISIBLE: u16 = 1;
+const LAYER_BACKGROUND: u16 = 8;
+const LAYER_REFERENCE: u16 = 64;
+/// Header flag: layer opacity fields hold valid values
+const HEADER_LAYER_OPACITY: u32 = 1;
+
+/// Playback direction of an Aseprite tag
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnimationDirection {
+    Forward,
+    Reverse,
+    Pingpong,
+    PingpongReverse,
+}
+
+/// One frame of an animation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AnimationFrame {
+    /// Sprite name of the frame
+    pub name: String,
+    /// Display time in milliseconds
+    pub duration: u32,
+}
+
+/// A tagged frame range from an Aseprite file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Animation {
+    /// Tag name
+    pub name: String,
+    /// Sprite name of the file the tag belongs to
+    pub sprite: String,
+    /// Layer the frames were taken from, when layers are split
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub layer: Option<String>,
+    pub direction: AnimationDirection,
+    pub frames: Vec<AnimationFrame>,
+}
+
+/// Returns true for `.ase` and `.aseprite` files
+pub(crate) fn is_aseprite(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| matches!(ext.to_lowercase().as_str(), "ase" | "aseprite"))
+}
+
+/// Sprite name of one frame, following Aseprite's own `{title} {frame}.{extension}`
+/// sheet naming, with the layer in parentheses when layers are split. A file
+/// with a single frame keeps its name.
+pub(crate) fn frame_name(
+    base: &str,
+    layer: Option<&str>,
+    frame: usize,
+    frame_count: usize,
+) -> String {
+    let (title, extension) = match base.rsplit_once('.') {
+        Some((title, ext)) if !ext.contains('/') => (title, Some(ext)),
+        _ => (base, None),
+    };
+    let mut name = title.to_string();
+    if let Some(layer) = layer {
+        name.push_str(&format!(" ({})", layer));
+    }
+    if frame_count > 1 {
+        name.push_str(&format!(" {}", frame));
+    }
+    if let Some(ext) = extension {
+        name.push('.');
+        name.push_str(ext);
+    }
+    name
+}
+
+/// A decoded Aseprite file
+pub(crate) struct AseFile {
+    pub width: u32,
+    pub height: u32,
+    pub frames: Vec<AseFrame>,
+    pub layers: Vec<AseLayer>,
+    pub tags: Vec<AseTag>,
+}
+
+pub(crate) struct AseFrame {
+    /// Display time in milliseconds
+    pub duration: u32,
+    cels: Vec<Cel>,
+}
+
+pub(crate) struct AseLayer {
+    pub name: String,
+    flags: u16,
+    /// 0 = normal, 1 = group, 2 = tilemap
+    kind: u16,
+    child_level: u16,
+    opacity: u8,
+    /// Visible itself and inside visible groups
+    visible: bool,
+}
+
+pub(crate) struct AseTag {
+    pub name: String,
+    pub from: usize,
+    pub to: usize,
+    pub direction: AnimationDirection,
+}
+
+/// A layer's image in one frame, already converted to RGBA
+struct Cel {
+    layer: usize,
+    x: i64,
+    y: i64,
+    opacity: u8,
+    z_index: i16,
+    image: RgbaImage,
+}
+
+/// Cel chunk as stored, before palette lookup and link resolution
+struct RawCel {
+    frame: usize,
+    layer: usize,
+    x: i16,
+    y: i16,
+    opacity: u8,
+    z_index: i16,
+    content: CelContent,
+}
+
+enum CelContent {
+    Pixels {
+        width: u32,
+        height: u32,
+        data: Vec<u8>,
+    },
+    /// Shares the cel of the same layer in another frame
+    Linked(usize),
+}
+
+/// Read and decode an Aseprite file
+pub(crate) fn read_aseprite(path: &Path) -> Result<AseFile> {
+    let data = std::fs::read(path).map_err(|e| BentoError::ImageLoad {
+        path: path.to_path_buf(),
+        source: e.into(),
+    })?;
+    parse_aseprite(&data).map_err(|message| {
+        BentoError::Aseprite {
+            path: path.to_path_buf(),
+            message,
+        }
+        .into()
+    })
+}
+
+/// First frame of an Aseprite file with every exported layer, e.g. for thumbnails
+#[cfg(feature = "gui")]
+pub(crate) fn first_frame(path: &Path) -> Result<RgbaImage> {
+    let file = read_aseprite(path)?;
+    Ok(file.render(0, &file.exported_layers()))
+}
+
+impl AseFile {
+    /// Layers written to the atlas: visible, non-reference image layers
+    pub fn exported_layers(&self) -> Vec<usize> {
+        self.layers
+            .iter()
+            .enumerate()
+            .filter(|(_, layer)| {
+                layer.visible && layer.kind != 1 && layer.flags & LAYER_REFERENCE == 0
+            })
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Composite a frame from the given layers, bottom to top
+    pub fn render(&self, frame: usize, layers: &[usize]) -> RgbaImage {
+        let mut canvas = RgbaImage::new(self.width, self.height);
+        let Some(frame) = self.frames.get(frame) else {
+            return canvas;
+        };
+
+        // Aseprite orders cels by layer index shifted by z-index, ties by z-index
+        let mut cels: Vec<&Cel> = frame
+            .cels
+            .iter()
+            .filter(|cel| layers.contains(&cel.layer))
+            .collect();
+        cels.sort_by_key(|cel| {
+            (
+                i64::try_from(cel.layer).unwrap_or(i64::MAX) + i64::from(cel.z_index),
+                cel.z_index,
+            )
+        });
+
+        for cel in cels {
+            let layer_opacity = self.layers.get(cel.layer).map_or(255, |l| l.opacity);
+            let opacity = mul_alpha(cel.opacity, layer_opacity);
+            for (px, py, pixel) in cel.image.enumerate_pixels() {
+                let x = cel.x + i64::from(px);
+                let y = cel.y + i64::from(py);
+                let (Ok(x), Ok(y)) = (u32::try_from(x), u32::try_from(y)) else {
+                    continue;
+                };
+                if x < self.width && y < self.height {
+                    blend_over(canvas.get_pixel_mut(x, y), *pixel, opacity);
+                }
+            }
+        }
+        canvas
+    }
+}
+
+fn parse_aseprite(data: &[u8]) -> Result<AseFile, String> {
+    let mut reader = Reader::new(data);
+    reader.skip(4)?; // file size
+    if reader.u16()? != FILE_MAGIC {
+        return Err("not an Aseprite file".to_string());
+    }
+    let frame_count = usize::from(reader.u16()?);
+    let width = u32::from(reader.u16()?);
+    let height = u32::from(reader.u16()?);
+    let depth = reader.u16()?;
+    if !matches!(depth, 8 | 16 | 32) {
+        return Err(format!("unsupported color depth {}", depth));
+    }
+    let flags = reader.u32()?;
+    reader.skip(2 + 4 + 4)?; // speed, reserved
+    let transparent_index = reader.u8()?;
+    reader.seek(HEADER_SIZE)?;
+
+    let mut frames = Vec::with_capacity(frame_count);
+    let mut layers = Vec::new();
+    let mut tags = Vec::new();
+    let mut palette: Vec<Rgba<u8>> = Vec::new();
+    let mut raw_cels = Vec::new();
+
+    for frame in 0..frame_count {
+        let frame_start = reader.pos;
+        let frame_size = reader.u32()? as usize;
+        if reader.u16()? != FRAME_MAGIC {
+            return Err(format!("frame {} is corrupt", frame));
+        }
+        let old_chunk_count = reader.u16()?;
+        let duration = u32::from(reader.u16()?);
+        reader.skip(2)?;
+        let chunk_count = match reader.u32()? {
+            0 => u32::from(old_chunk_count),
+            n => n,
+        };
+
+        for _ in 0..chunk_count {
+            let chunk_start = reader.pos;
+            let chunk_size = reader.u32()? as usize;
+            let kind = reader.u16()?;
+            let body = data
+                .get(chunk_start + 6..chunk_start.saturating_add(chunk_size))
+                .ok_or_else(|| format!("chunk in frame {} runs past the end of the file", frame))?;
+            let mut chunk = Reader::new(body);
+            match kind {
+                CHUNK_LAYER => layers.push(parse_layer(&mut chunk, flags)?),
+                CHUNK_CEL => raw_cels.push(parse_cel(&mut chunk, frame, depth)?),
+                CHUNK_TAGS => tags = parse_tags(&mut chunk)?,
+                CHUNK_PALETTE => parse_palette(&mut chunk, &mut palette)?,
+                CHUNK_OLD_PALETTE if palette.is_empty() => {
+                    parse_old_palette(&mut chunk, &mut palette)?;
+                }
+                _ => {}
+            }
+            reader.seek(chunk_start + chunk_size)?;
+        }
+
+        reader.seek(frame_start + frame_size)?;
+        frames.push(AseFrame {
+            duration,
+            cels: Vec::new(),
+        });
+    }
+
+    // Hidden groups hide everything inside them
+    let mut parents: Vec<bool> = Vec::new();
+    for layer in &mut layers {
+        let level = usize::from(layer.child_level);
+        let parent_visible = level == 0 || parents.get(level - 1).copied().unwrap_or(true);
+        layer.visible &= parent_visible;
+        parents.truncate(level);
+        parents.push(layer.visible);
+    }
+
+    let cels = resolve_cels(raw_cels, &layers, depth, &palette, transparent_index)?;
+    for (frame, cel) in cels {
+        if let Some(frame) = frames.get_mut(frame) {
+            frame.cels.push(cel);
+        }
+    }
+
+    Ok(AseFile {
+        width,
+        height,
+        frames,
+        layers,
+        tags: tags
+            .into_iter()
+            .filter(|tag| tag.from <= tag.to && tag.to < frame_count)
+            .collect(),
+    })
+}
+
+fn parse_layer(chunk: &mut Reader, header_flags: u32) -> Result<AseLayer, String> {
+    let flags = chunk.u16()?;
+    let kind = chunk.u16()?;
+    let child_level = chunk.u16()?;
+    chunk.skip(2 + 2 + 2)?; // default size, blend mode
+    let opacity = chunk.u8()?;
+    chunk.skip(3)?;
+    let name = chunk.string()?;
+    Ok(AseLayer {
+        name,
+        flags,
+        kind,
+        child_level,
+        opacity: if header_flags & HEADER_LAYER_OPACITY != 0 {
+            opacity
+        } else {
+            255
+        },
+        visible: flags & LAYER_VISIBLE != 0,
+    })
+}
+
+fn parse_cel(chunk: &mut Reader, frame: usize, depth: u16) -> Result<RawCel, String> {
+    let layer = usize::from(chunk.u16()?);
+    let x = chunk.i16()?;
+    let y = chunk.i16()?;
+    let opacity = chunk.u8()?;
+    let kind = chunk.u16()?;
+    let z_index = chunk.i16()?;
+    chunk.skip(5)?;
+
+    let bytes_per_pixel = usize::from(depth / 8);
+    let content = match kind {
+        0 | 2 => {
+            let width = u32::from(chunk.u16()?);
+            let height = u32::from(chunk.u16()?);
+            let rest = chunk.rest();
+            let data = if kind == 0 {
+                rest.to_vec()
+            } else {
+                let mut data = Vec::new();
+                ZlibDecoder::new(rest)
+                    .read_to_end(&mut data)
+                    .map_err(|e| format!("cel in frame {} is corrupt: {}", frame, e))?;
+                data
+            };
+            if data.len() < width as usize * height as usize * bytes_per_pixel {
+                return Err(format!("cel in frame {} is truncated", frame));
+            }
+            CelContent::Pixels {
+                width,
+                height,
+                data,
+            }
+        }
+        1 => CelContent::Linked(usize::from(chunk.u16()?)),
+        3 => {
+            return Err(
+                "tilemap layers are not supported; convert them to regular layers".to_string(),
+            );
+        }
+        other => return Err(format!("unknown cel type {} in frame {}", other, frame)),
+    };
+
+    Ok(RawCel {
+        frame,
+        layer,
+        x,
+        y,
+        opacity,
+        z_index,
+        content,
+    })
+}
+
+fn parse_tags(chunk: &mut Reader) -> Result<Vec<AseTag>, String> {
+    let count = chunk.u16()?;
+    chunk.skip(8)?;
+    (0..count)
+        .map(|_| {
+            let from = usize::from(chunk.u16()?);
+            let to = usize::from(chunk.u16()?);
+            let direction = match chunk.u8()? {
+                1 => AnimationDirection::Reverse,
+                2 => AnimationDirection::Pingpong,
+                3 => AnimationDirection::PingpongReverse,
+                _ => AnimationDirection::Forward,
+            };
+            chunk.skip(2 + 6 + 3 + 1)?; // repeat, reserved, color
+            Ok(AseTag {
+                name: chunk.string()?,
+                from,
+                to,
+                direction,
+            })
+        })
+        .collect()
+}
+
+fn parse_palette(chunk: &mut Reader, palette: &mut Vec<Rgba<u8>>) -> Result<(), String> {
+    let size = chunk.u32()? as usize;
+    let first = chunk.u32()? as usize;
+    let last = chunk.u32()? as usize;
+    chunk.skip(8)?;
+    if palette.len() < size {
+        palette.resize(size, Rgba([0, 0, 0, 0]));
+    }
+    for index in first..=last {
+        let flags = chunk.u16()?;
+        let color = Rgba([chunk.u8()?, chunk.u8()?, chunk.u8()?, chunk.u8()?]);
+        if flags & 1 != 0 {
+            chunk.string()?;
+        }
+        if let Some(entry) = palette.get_mut(index) {
+            *entry = color;
+        }
+    }
+    Ok(())
+}
+
+fn parse_old_palette(chunk: &mut Reader, palette: &mut Vec<Rgba<u8>>) -> Result<(), String> {
+    let packets = chunk.u16()?;
+    let mut index = 0;
+    for _ in 0..packets {
+        index += usize::from(chunk.u8()?);
+        let count = match chunk.u8()? {
+            0 => 256,
+            n => usize::from(n),
+        };
+        for _ in 0..count {
+            let color = Rgba([chunk.u8()?, chunk.u8()?, chunk.u8()?, 255]);
+            if palette.len() <= index {
+                palette.resize(index + 1, Rgba([0, 0, 0, 0]));
+            }
+            palette[index] = color;
+            index += 1;
+        }
+    }
+    Ok(())
+}
+
+/// Convert cel pixels to RGBA and copy linked cels, returning each cel with its frame
+fn resolve_cels(
+    raw_cels: Vec<RawCel>,
+    layers: &[AseLayer],
+    depth: u16,
+    palette: &[Rgba<u8>],
+    transparent_index: u8,
+) -> Result<Vec<(usize, Cel)>, String> {
+    let mut images: Vec<Option<RgbaImage>> = Vec::with_capacity(raw_cels.len());
+    for raw in &raw_cels {
+        images.push(match &raw.content {
+            CelContent::Pixels {
+                width,
+                height,
+                data,
+            } => {
+                let background = layers
+                    .get(raw.layer)
+                    .is_some_and(|l| l.flags & LAYER_BACKGROUND != 0);
+                Some(decode_pixels(
+                    *width,
+                    *height,
+                    data,
+                    depth,
+                    palette,
+                    (!background).then_some(transparent_index),
+                ))
+            }
+            CelContent::Linked(_) => None,
+        });
+    }
+
+    raw_cels
+        .iter()
+        .enumerate()
+        .map(|(index, raw)| {
+            // Linked cels share position, opacity and pixels with their source
+            let source = match raw.content {
+                CelContent::Pixels { .. } => Some(index),
+                CelContent::Linked(target) => raw_cels.iter().position(|other| {
+                    other.frame == target
+                        && other.layer == raw.layer
+                        && matches!(other.content, CelContent::Pixels { .. })
+                }),
+            };
+            let (source, image) = source
+                .and_then(|source| Some((&raw_cels[source], images[source].clone()?)))
+                .ok_or_else(|| format!("linked cel in frame {} points nowhere", raw.frame))?;
+            Ok((
+                raw.frame,
+                Cel {
+                    layer: raw.layer,
+                    x: i64::from(source.x),
+                    y: i64::from(source.y),
+                    opacity: source.opacity,
+                    z_index: raw.z_index,
+                    image,
+                },
+            ))
+        })
+        .collect()
+}
+
+fn decode_pixels(
+    width: u32,
+    height: u32,
+    data: &[u8],
+    depth: u16,
+    palette: &[Rgba<u8>],
+    transparent_index: Option<u8>,
+) -> RgbaImage {
+    let bytes_per_pixel = usize::from(depth / 8);
+    RgbaImage::from_fn(width, height, |x, y| {
+        let offset = (y as usize * width as usize + x as usize) * bytes_per_pixel;
+        let pixel = &data[offset..offset + bytes_per_pixel];
+        match pixel {
+            [r, g, b, a] => Rgba([*r, *g, *b, *a]),
+            [value, alpha] => Rgba([*value, *value, *value, *alpha]),
+            [index] if Some(*index) == transparent_index => Rgba([0, 0, 0, 0]),
+            [index] => palette
+                .get(usize::from(*index))
+                .copied()
+                .unwrap_or(Rgba([0, 0, 0, 0])),
+            _ => Rgba([0, 0, 0, 0]),
+        }
+    })
+}
+
+/// Product of two 0-255 alpha values
+fn mul_alpha(a: u8, b: u8) -> u8 {
+    u8::try_from(u32::from(a) * u32::from(b) / 255).unwrap_or(u8::MAX)
+}
+
+/// Draw `src` over `dst` with straight alpha, scaled by `opacity`
+fn blend_over(dst: &mut Rgba<u8>, src: Rgba<u8>, opacity: u8) {
+    let src_alpha = u32::from(mul_alpha(src[3], opacity));
+    if src_alpha == 0 {
+        return;
+    }
+    let dst_alpha = u32::from(dst[3]);
+    let out_alpha = src_alpha + dst_alpha * (255 - src_alpha) / 255;
+    for channel in 0..3 {
+        let color = (u32::from(src[channel]) * src_alpha * 255
+            + u32::from(dst[channel]) * dst_alpha * (255 - src_alpha))
+            / (out_alpha * 255);
+        dst[channel] = u8::try_from(color).unwrap_or(u8::MAX);
+    }
+    dst[3] = u8::try_from(out_alpha).unwrap_or(u8::MAX);
+}
+
+/// Little-endian cursor over a byte slice
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8], String> {
+        let bytes = self
+            .pos
+            .checked_add(len)
+            .and_then(|end| self.data.get(self.pos..end))
+            .ok_or_else(|| "unexpected end of file".to_string())?;
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    fn rest(&mut self) -> &'a [u8] {
+        let rest = self.data.get(self.pos..).unwrap_or_default();
+        self.pos = self.data.len();
+        rest
+    }
+
+    fn seek(&mut self, pos: usize) -> Result<(), String> {
+        if pos > self.data.len() {
+            return Err("unexpected end of file".to_string());
+        }
+        self.pos = pos;
+        Ok(())
+    }
+
+    fn skip(&mut self, len: usize) -> Result<(), String> {
+        self.bytes(len).map(|_| ())
+    }
+
+    fn u8(&mut self) -> Result<u8, String> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, String> {
+        let bytes = self.bytes(2)?;
+        Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn i16(&mut self) -> Result<i16, String> {
+        let bytes = self.bytes(2)?;
+        Ok(i16::from_le_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn u32(&mut self) -> Result<u32, String> {
+        let bytes = self.bytes(4)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        let len = usize::from(self.u16()?);
+        Ok(String::from_utf8_lossy(self.bytes(len)?).into_owned())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+pub(crate) mod tests {
+    use super::*;
+    use flate2::Compression;
+    use flate2::write::ZlibEncoder;
+    use std::io::Write;
+
+    fn chunk(kind: u16, body: &[u8]) -> Vec<u8> {
+        let mut out = (u32::try_from(body.len()).unwrap() + 6)
+            .to_le_bytes()
+            .to_vec();
+        out.extend(kind.to_le_bytes());
+        out.extend(body);
+        out
+    }
+
+    fn string(s: &str) -> Vec<u8> {
+        let mut out = u16::try_from(s.len()).unwrap().to_le_bytes().to_vec();
+        out.extend(s.as_bytes());
+        out
+    }
+
+    fn layer(name: &str, visible: bool, opacity: u8) -> Vec<u8> {
+        let mut body = u16::from(visible).to_le_bytes().to_vec();
+        body.extend([0; 10]); // type, child level, default size, blend mode
+        body.push(opacity);
+        body.extend([0; 3]);
+        body.extend(string(name));
+        chunk(CHUNK_LAYER, &body)
+    }
+
+    fn cel(layer: u16, x: i16, y: i16, image: &RgbaImage) -> Vec<u8> {
+        let mut body = layer.to_le_bytes().to_vec();
+        body.extend(x.to_le_bytes());
+        body.extend(y.to_le_bytes());
+        body.push(255);
+        body.extend(2u16.to_le_bytes());
+        body.extend([0; 7]); // z-index, reserved
+        body.extend(u16::try_from(image.width()).unwrap().to_le_bytes());
+        body.extend(u16::try_from(image.height()).unwrap().to_le_bytes());
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(image.as_raw()).unwrap();
+        body.extend(encoder.finish().unwrap());
+        chunk(CHUNK_CEL, &body)
+    }
+
+    fn linked_cel(layer: u16, frame: u16) -> Vec<u8> {
+        let mut body = layer.to_le_bytes().to_vec();
+        body.extend([0; 5]); // x, y, opacity
+        body.extend(1u16.to_le_bytes());
+        body.extend([0; 7]);
+        body.extend(frame.to_le_bytes());
+        chunk(CHUNK_CEL, &body)
+    }
+
+    fn tag(name: &str, from: u16, to: u16, direction: u8) -> Vec<u8> {
+        let mut body = 1u16.to_le_bytes().to_vec();
+        body.extend([0; 8]);
+        body.extend(from.to_le_bytes());
+        body.extend(to.to_le_bytes());
+        body.push(direction);
+        body.extend([0; 12]); // repeat, reserved, color
+        body.extend(string(name));
+        chunk(CHUNK_TAGS, &body)
+    }
+
+    fn file(width: u16, height: u16, frames: &[(u16, Vec<Vec<u8>>)]) -> Vec<u8> {
+        let mut out = vec![0; HEADER_SIZE];
+        out[4..6].copy_from_slice(&FILE_MAGIC.to_le_bytes());
+        out[6..8].copy_from_slice(&u16::try_from(frames.len()).unwrap().to_le_bytes());
+        out[8..10].copy_from_slice(&width.to_le_bytes());
+        out[10..12].copy_from_slice(&height.to_le_bytes());
+        out[12..14].copy_from_slice(&32u16.to_le_bytes());
+        out[14..18].copy_from_slice(&HEADER_LAYER_OPACITY.to_le_bytes());
+        for (duration, chunks) in frames {
+            let body: Vec<u8> = chunks.concat();
+            out.extend((u32::try_from(body.len()).unwrap() + 16).to_le_bytes());
+            out.extend(FRAME_MAGIC.to_le_bytes());
+            out.extend(0xFFFFu16.to_le_bytes());
+            out.extend(duration.to_le_bytes());
+            out.extend([0; 2]);
+            out.extend(u32::try_from(chunks.len()).unwrap().to_le_bytes());
+            out.extend(body);
+        }
+        let size = u32::try_from(out.len()).unwrap();
+        out[0..4].copy_from_slice(&size.to_le_bytes());
+        out
+    }
+
+    pub(crate) const RED: Rgba<u8> = Rgba([255, 0, 0, 255]);
+    pub(crate) const BLUE: Rgba<u8> = Rgba([0, 0, 255, 255]);
+
+    /// Two 2x2 frames tagged "walk": a red "base" layer (linked in frame 1),
+    /// a hidden green layer and a blue pixel on the "top" layer in frame 0
+    pub(crate) fn sample_file() -> Vec<u8> {
+        let red = RgbaImage::from_pixel(2, 2, RED);
+        let green = RgbaImage::from_pixel(2, 2, Rgba([0, 255, 0, 255]));
+        let blue = RgbaImage::from_pixel(1, 1, BLUE);
+        file(
+            2,
+            2,
+            &[
+                (
+                    100,
+                    vec![
+                        layer("base", true, 255),
+                        layer("hidden", false, 255),
+                        layer("top", true, 255),
+                        tag("walk", 0, 1, 2),
+                        cel(0, 0, 0, &red),
+                        cel(1, 0, 0, &green),
+                        cel(2, 1, 1, &blue),
+                    ],
+                ),
+                (50, vec![linked_cel(0, 0)]),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_parse_and_render() {
+        let file = parse_aseprite(&sample_file()).unwrap();
+        assert_eq!((file.width, file.height), (2, 2));
+        assert_eq!(
+            file.frames.iter().map(|f| f.duration).collect::<Vec<_>>(),
+            vec![100, 50]
+        );
+        assert_eq!(file.exported_layers(), vec![0, 2]);
+
+        let layers = file.exported_layers();
+        let first = file.render(0, &layers);
+        assert_eq!(*first.get_pixel(0, 0), RED);
+        assert_eq!(*first.get_pixel(1, 1), BLUE);
+        // Frame 1 links the base cel and has nothing on top
+        let second = file.render(1, &layers);
+        assert_eq!(*second.get_pixel(1, 1), RED);
+        // A single layer renders alone
+        assert_eq!(file.render(0, &[2]).get_pixel(0, 0)[3], 0);
+
+        assert_eq!(file.tags.len(), 1);
+        let tag = &file.tags[0];
+        assert_eq!((tag.name.as_str(), tag.from, tag.to), ("walk", 0, 1));
+        assert_eq!(tag.direction, AnimationDirection::Pingpong);
+
+        assert!(parse_aseprite(b"not an aseprite file at all").is_err());
+    }
+
+    #[test]
+    fn test_blend_over() {
+        let mut pixel = RED;
+        blend_over(&mut pixel, BLUE, 128);
+        assert_eq!(pixel, Rgba([127, 0, 128, 255]));
+
+        let mut pixel = Rgba([0, 0, 0, 0]);
+        blend_over(&mut pixel, BLUE, 255);
+        assert_eq!(pixel, BLUE);
+    }
+
+    #[test]
+    fn test_frame_name() {
+        assert_eq!(frame_name("hero.aseprite", None, 3, 8), "hero 3.aseprite");
+        assert_eq!(frame_name("hero.aseprite", None, 0, 1), "hero.aseprite");
+        assert_eq!(
+            frame_name("chars/hero.ase", Some("body"), 0, 2),
+            "chars/hero (body) 0.ase"
+        );
+    }
+}
@@ -1,47 +1,230 @@
-use std::collections::HashMap;
-use std::path::Path;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Component, Path, PathBuf};
 use std::sync::Arc;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 
 use anyhow::{Context, Result};
-use image::ImageReader;
-use log::info;
+use image::{ImageReader, RgbaImage};
+use log::{info, warn};
 use rayon::prelude::*;
 
-use super::{SourceSprite, TrimInfo, resize_by_scale, resize_to_width, trim_sprite};
-use crate::cli::ResizeFilter;
+use super::animated::{animated_frame_name, read_animated_frames};
+use super::aseprite::{
+    Animation, AnimationDirection, AnimationFrame, AseFile, frame_name, is_aseprite, read_aseprite,
+};
+use super::{
+    ContentBounds, GridSlice, NineSlice, SourceSprite, SpriteOverride, TrimInfo, TrimMargin,
+    resize_by_scale, resize_to_fit, resize_to_width, trim_sprite,
+};
+use crate::cli::{ResizeFilter, ResizeFit, SpriteSort};
 use crate::error::BentoError;
+use crate::progress::{ProgressCallback, ProgressEvent};
+use crate::warning::Warning;
 
-const SUPPORTED_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "bmp", "webp"];
+const SUPPORTED_EXTENSIONS: &[&str] = &[
+    "png", "jpg", "jpeg", "gif", "bmp", "webp", "ase", "aseprite",
+];
 
 /// Image path with its base directory for computing relative paths
 struct ImagePath {
-    path: std::path::PathBuf,
-    base: Option<std::path::PathBuf>,
+    path: PathBuf,
+    base: Option<PathBuf>,
 }
 
-/// Load sprites from input paths (files or directories)
-///
-/// When `base_dir` is provided, individual file inputs will have their sprite
-/// names computed as paths relative to that directory. This preserves subdirectory
-/// structure in output metadata (e.g., "ironclad/bash.png" instead of "bash.png").
-/// Config-file loading uses this to pass the config directory as the base.
-///
-/// When `filename_only` is true, all sprites use bare filenames regardless of
-/// directory structure or `base_dir`.
-#[allow(clippy::too_many_arguments)]
+/// Options controlling how sprites are loaded and preprocessed
+#[derive(Debug, Clone)]
+pub struct LoadOptions {
+    /// Remove transparent borders
+    pub trim: bool,
+    /// Transparent border to keep on each edge after trimming
+    pub trim_margin: TrimMargin,
+    /// Per-sprite margins keyed by sprite name pattern; the first match wins
+    pub trim_margin_overrides: Vec<(glob::Pattern, TrimMargin)>,
+    /// Pixels with alpha at or below this count as transparent when trimming
+    pub trim_alpha: u8,
+    /// Sheets to cut into grid cells, keyed by sprite name pattern; the first match wins
+    pub grid_slices: Vec<(glob::Pattern, GridSlice)>,
+    /// Per-sprite trim, scale and pivot, keyed by sprite name pattern in match order
+    pub overrides: Vec<(glob::Pattern, SpriteOverride)>,
+    /// Keep full frames in the atlas but record tight content bounds in metadata.
+    /// Takes precedence over `trim`.
+    pub record_trim: bool,
+    /// Resize to this width, preserving aspect ratio
+    pub resize_width: Option<u32>,
+    /// Resize by this scale factor
+    pub resize_scale: Option<f32>,
+    /// Shrink images wider than this to it, preserving aspect ratio
+    pub resize_max_width: Option<u32>,
+    /// Shrink images larger than this box to fit inside it, preserving aspect ratio
+    pub resize_fit: Option<ResizeFit>,
+    /// Filter used when resizing
+    pub resize_filter: ResizeFilter,
+    /// Resize in linear light rather than on sRGB values
+    pub resize_linear: bool,
+    /// Checked before each image is loaded
+    pub cancel_token: Option<Arc<AtomicBool>>,
+    /// Told after each input file is loaded
+    pub progress: Option<ProgressCallback>,
+    /// When provided, individual file inputs will have their sprite names computed
+    /// as paths relative to this directory. This preserves subdirectory structure in
+    /// output metadata (e.g., "ironclad/bash.png" instead of "bash.png").
+    /// Config-file loading uses this to pass the config directory as the base.
+    pub base_dir: Option<PathBuf>,
+    /// Use bare filenames regardless of directory structure or `base_dir`
+    pub filename_only: bool,
+    /// Skip files and directories whose path ends in a match for one of these
+    pub exclude: Vec<glob::Pattern>,
+    /// Load each visible layer of an Aseprite file as its own sprite per frame
+    pub split_layers: bool,
+    /// Break sort ties by sprite name and join name components with '/' on
+    /// every platform, so output does not depend on input order or OS
+    pub deterministic: bool,
+    /// Order sprites are handed to the packer in
+    pub sort: SpriteSort,
+    /// Scale factors for sources whose file stem ends in a suffix such as
+    /// `@2x`; the suffix is dropped from the sprite name
+    pub scale_suffixes: Vec<(String, f32)>,
+    /// File stem suffixes of companion images such as `_n` for normal maps:
+    /// `hero_n.png` is loaded alongside `hero.png` rather than as a sprite
+    pub companions: Vec<String>,
+    /// Log and skip images that fail to load instead of stopping at the first
+    pub skip_errors: bool,
+}
+
+impl Default for LoadOptions {
+    fn default() -> Self {
+        Self {
+            trim: true,
+            trim_margin: TrimMargin::default(),
+            trim_margin_overrides: Vec::new(),
+            trim_alpha: 0,
+            grid_slices: Vec::new(),
+            overrides: Vec::new(),
+            record_trim: false,
+            resize_width: None,
+            resize_scale: None,
+            resize_max_width: None,
+            resize_fit: None,
+            resize_filter: ResizeFilter::Lanczos3,
+            resize_linear: false,
+            cancel_token: None,
+            progress: None,
+            base_dir: None,
+            filename_only: false,
+            exclude: Vec::new(),
+            split_layers: false,
+            deterministic: false,
+            sort: SpriteSort::Area,
+            scale_suffixes: Vec::new(),
+            companions: Vec::new(),
+            skip_errors: false,
+        }
+    }
+}
+
+impl LoadOptions {
+    /// Trim margin for a sprite, applying the first matching override
+    fn trim_margin_for(&self, name: &str) -> TrimMargin {
+        self.trim_margin_overrides
+            .iter()
+            .find(|(pattern, _)| pattern.matches(name))
+            .map_or(self.trim_margin, |&(_, margin)| margin)
+    }
+
+    /// Whether an exclude pattern matches the path or any of its trailing
+    /// components, so `_wip/*` skips `sprites/_wip/a.png` much like `.gitignore`
+    fn is_excluded(&self, path: &Path) -> bool {
+        if self.exclude.is_empty() {
+            return false;
+        }
+        let components: Vec<Component> = path
+            .components()
+            .filter(|c| !matches!(c, Component::CurDir))
+            .collect();
+        (0..components.len()).any(|start| {
+            let tail: PathBuf = components[start..].iter().collect();
+            self.exclude
+                .iter()
+                .any(|pattern| pattern.matches_path(&tail))
+        })
+    }
+
+    /// Name without its scale suffix and the suffix's factor, when the file
+    /// stem ends in one (`ui/hero@2x.png` becomes `ui/hero.png`); the
+    /// longest matching suffix wins
+    fn strip_scale_suffix(&self, name: &str) -> Option<(String, f32)> {
+        let (stem, extension) = match name.rfind('.') {
+            Some(dot) if !name[dot..].contains(['/', '\\']) => name.split_at(dot),
+            _ => (name, ""),
+        };
+        self.scale_suffixes
+            .iter()
+            .filter(|(suffix, _)| {
+                stem.strip_suffix(suffix.as_str())
+                    .is_some_and(|base| !base.is_empty() && !base.ends_with(['/', '\\']))
+            })
+            .max_by_key(|(suffix, _)| suffix.len())
+            .map(|(suffix, factor)| {
+                let base = &stem[..stem.len() - suffix.len()];
+                (format!("{}{}", base, extension), *factor)
+            })
+    }
+
+    /// Whether a file is the companion of another input, such as `hero_n.png`
+    /// next to `hero.png`
+    fn is_companion(&self, path: &Path) -> bool {
+        let Some(stem) = path.file_stem().and_then(|s| s.to_str()) else {
+            return false;
+        };
+        self.companions.iter().any(|suffix| {
+            stem.strip_suffix(suffix.as_str())
+                .filter(|base| !base.is_empty())
+                .is_some_and(|base| {
+                    let mut file_name = base.to_string();
+                    if let Some(extension) = path.extension().and_then(|e| e.to_str()) {
+                        file_name.push('.');
+                        file_name.push_str(extension);
+                    }
+                    path.with_file_name(file_name).is_file()
+                })
+        })
+    }
+
+    /// Grid to cut a sheet into, from the first matching pattern
+    fn grid_slice_for(&self, name: &str) -> Option<GridSlice> {
+        self.grid_slices
+            .iter()
+            .find(|(pattern, _)| pattern.matches(name))
+            .map(|&(_, grid)| grid)
+    }
+}
+
+/// Load sprites from input paths (files or directories), logging any
+/// [`Warning`]s. Duplicate names are not rejected here, so exclusions and
+/// renames can resolve them first; [`apply_sprite_overrides`] checks them.
 pub fn load_sprites(
     inputs: &[impl AsRef<Path>],
-    trim: bool,
-    trim_margin: u32,
-    resize_width: Option<u32>,
-    resize_scale: Option<f32>,
-    resize_filter: ResizeFilter,
-    cancel_token: Option<&Arc<AtomicBool>>,
-    base_dir: Option<&Path>,
-    filename_only: bool,
+    options: &LoadOptions,
 ) -> Result<Vec<SourceSprite>> {
-    let image_paths = collect_image_paths(inputs, base_dir, filename_only)?;
+    let (sprites, warnings) = load_sprites_with_warnings(inputs, options)?;
+    for warning in &warnings {
+        warn!("{}", warning);
+    }
+    Ok(sprites)
+}
+
+/// Load sprites as [`load_sprites`] does, returning the warnings instead of
+/// logging them
+pub fn load_sprites_with_warnings(
+    inputs: &[impl AsRef<Path>],
+    options: &LoadOptions,
+) -> Result<(Vec<SourceSprite>, Vec<Warning>)> {
+    let mut unsupported = Vec::new();
+    let image_paths = collect_image_paths(inputs, options, &mut unsupported)?;
+    let mut warnings = Vec::new();
+    if !unsupported.is_empty() {
+        warnings.push(Warning::UnsupportedFiles { paths: unsupported });
+    }
 
     if image_paths.is_empty() {
         return Err(BentoError::NoImages.into());
@@ -49,62 +232,383 @@ pub fn load_sprites(
 
     info!("Loading {} images...", image_paths.len());
 
-    let sprites: Result<Vec<_>> = image_paths
-        .par_iter()
-        .map(|img_path| {
-            // Check for cancellation before loading each image
-            if let Some(token) = cancel_token
-                && token.load(Ordering::Relaxed)
-            {
-                return Err(BentoError::Cancelled.into());
-            }
-            load_single_sprite(
-                &img_path.path,
-                img_path.base.as_deref(),
-                trim,
-                trim_margin,
-                resize_width,
-                resize_scale,
-                resize_filter,
-            )
+    let sprites = if options.skip_errors {
+        let (sprites, mut errors): (Vec<_>, Vec<_>) = load_image_paths(&image_paths, options)
+            .partition_map(|result| match result {
+                Ok(sprites) => rayon::iter::Either::Left(sprites),
+                Err(e) => rayon::iter::Either::Right(e),
+            });
+        if let Some(index) = errors
+            .iter()
+            .position(|e| matches!(e.downcast_ref(), Some(BentoError::Cancelled)))
+        {
+            return Err(errors.swap_remove(index));
+        }
+        // Nothing to skip to: report why the first image failed
+        if sprites.is_empty() && !errors.is_empty() {
+            return Err(errors.swap_remove(0));
+        }
+        warnings.extend(errors.iter().map(|e| Warning::SkippedImage {
+            message: format!("{:#}", e),
+        }));
+        sprites
+    } else {
+        load_image_paths(&image_paths, options).collect::<Result<_>>()?
+    };
+
+    let sprites = order_sprites(number_sprites(sprites), options);
+    warnings.extend(sprite_warnings(&sprites, options));
+    Ok((sprites, warnings))
+}
+
+/// Warnings about loaded sprites: ones trimmed away to nothing, and names
+/// that only differ in case
+fn sprite_warnings(sprites: &[SourceSprite], options: &LoadOptions) -> Vec<Warning> {
+    let mut warnings = Vec::new();
+
+    // Trimming leaves a single transparent pixel of an empty image
+    let mut empty: Vec<String> = sprites
+        .iter()
+        .filter(|sprite| {
+            let trim = &sprite.trim_info;
+            (trim.trimmed_width, trim.trimmed_height) == (1, 1)
+                && (trim.source_width, trim.source_height) != (1, 1)
+                && sprite
+                    .image
+                    .get_pixel_checked(0, 0)
+                    .is_some_and(|pixel| pixel[3] <= options.trim_alpha)
         })
+        .map(|sprite| sprite.name.clone())
         .collect();
+    if !empty.is_empty() {
+        empty.sort_unstable();
+        warnings.push(Warning::EmptySprites { names: empty });
+    }
 
-    let mut sprites = sprites?;
-
-    // Check for duplicate sprite names (would cause silent overwrites in Godot output)
-    let mut name_counts: HashMap<&str, usize> = HashMap::new();
-    for sprite in &sprites {
-        *name_counts.entry(&sprite.name).or_insert(0) += 1;
+    let mut by_lowercase: BTreeMap<String, Vec<&str>> = BTreeMap::new();
+    for sprite in sprites {
+        by_lowercase
+            .entry(sprite.name.to_lowercase())
+            .or_default()
+            .push(&sprite.name);
+    }
+    let mut clashing: Vec<String> = by_lowercase
+        .into_values()
+        .filter(|names| names.len() > 1)
+        .flatten()
+        .map(str::to_string)
+        .collect();
+    if !clashing.is_empty() {
+        clashing.sort_unstable();
+        warnings.push(Warning::CaseDuplicates { names: clashing });
     }
-    let duplicates: Vec<&str> = name_counts
+
+    warnings
+}
+
+/// Prepare in-memory images as sprites, as [`load_sprites`] does for files:
+/// each `(name, image)` pair is resized, trimmed and given its overrides from
+/// `options`, without touching the filesystem. Names must be unique.
+pub fn sprites_from_images(
+    images: impl IntoIterator<Item = (String, RgbaImage)>,
+    options: &LoadOptions,
+) -> Result<Vec<SourceSprite>> {
+    let sprites: Vec<_> = images
         .into_iter()
-        .filter(|(_, count)| *count > 1)
-        .map(|(name, _)| name)
+        .map(|(name, image)| {
+            let sprite_name = options
+                .strip_scale_suffix(&name)
+                .map_or_else(|| name.clone(), |(name, _)| name);
+            vec![prepare_sprite(
+                Path::new(&name),
+                sprite_name,
+                image,
+                options,
+            )]
+        })
         .collect();
+    if sprites.is_empty() {
+        return Err(BentoError::NoImages.into());
+    }
+    let sprites = number_sprites(sprites);
+    check_duplicate_names(&sprites)?;
+    Ok(order_sprites(sprites, options))
+}
+
+/// Sort sprites for packing
+fn order_sprites(mut sprites: Vec<SourceSprite>, options: &LoadOptions) -> Vec<SourceSprite> {
+    match options.sort {
+        SpriteSort::None => {}
+        SpriteSort::Name => sprites.sort_by(|a, b| a.name.cmp(&b.name)),
+        sort => sprites.sort_by(|a, b| {
+            // Largest first packs tighter
+            let order = sort_key(b, sort).cmp(&sort_key(a, sort));
+            if options.deterministic {
+                // Names may still clash before renames apply, so paths settle ties
+                order
+                    .then_with(|| a.name.cmp(&b.name))
+                    .then_with(|| a.path.cmp(&b.path))
+            } else {
+                order
+            }
+        }),
+    }
+
+    sprites
+}
+
+/// Size a sprite is sorted by, larger first
+fn sort_key(sprite: &SourceSprite, sort: SpriteSort) -> u64 {
+    let (width, height) = (u64::from(sprite.width()), u64::from(sprite.height()));
+    match sort {
+        SpriteSort::Area => width * height,
+        SpriteSort::MaxSide => width.max(height),
+        SpriteSort::Height => height,
+        SpriteSort::Width => width,
+        SpriteSort::Perimeter => width + height,
+        SpriteSort::Name | SpriteSort::None => 0,
+    }
+}
+
+/// Load every image that can be loaded, returning the failures alongside the
+/// sprites instead of stopping at the first one. Duplicate names are not
+/// checked; see [`duplicate_names`].
+pub fn try_load_sprites(
+    inputs: &[impl AsRef<Path>],
+    options: &LoadOptions,
+) -> Result<(Vec<SourceSprite>, Vec<anyhow::Error>)> {
+    let image_paths = collect_image_paths(inputs, options, &mut Vec::new())?;
+    let (sprites, errors) =
+        load_image_paths(&image_paths, options).partition_map(|result| match result {
+            Ok(sprites) => rayon::iter::Either::Left(sprites),
+            Err(e) => rayon::iter::Either::Right(e),
+        });
+    Ok((number_sprites(sprites), errors))
+}
+
+/// Load images in parallel; an Aseprite file yields a sprite per frame (and layer)
+fn load_image_paths<'a>(
+    image_paths: &'a [ImagePath],
+    options: &'a LoadOptions,
+) -> impl ParallelIterator<Item = Result<Vec<SourceSprite>>> + 'a {
+    let loaded = AtomicUsize::new(0);
+    image_paths.par_iter().map(move |img_path| {
+        // Check for cancellation before loading each image
+        if let Some(token) = &options.cancel_token
+            && token.load(Ordering::Relaxed)
+        {
+            return Err(BentoError::Cancelled.into());
+        }
+        let sprites = load_path_sprites(&img_path.path, img_path.base.as_deref(), options);
+        if let Some(progress) = &options.progress {
+            progress.report(ProgressEvent::SpriteLoaded {
+                done: loaded.fetch_add(1, Ordering::Relaxed) + 1,
+                total: image_paths.len(),
+            });
+        }
+        sprites
+    })
+}
+
+/// Flatten per-file sprites, numbering them in input order
+fn number_sprites(sprites: Vec<Vec<SourceSprite>>) -> Vec<SourceSprite> {
+    sprites
+        .into_iter()
+        .flatten()
+        .enumerate()
+        .map(|(input_index, sprite)| SourceSprite {
+            input_index,
+            ..sprite
+        })
+        .collect()
+}
+
+/// Animations from the tags of Aseprite inputs and from animated GIF and APNG
+/// inputs, naming frames the way [`load_sprites`] does with the same options.
+/// Other inputs are skipped, as are unreadable ones with `skip_errors`.
+pub fn load_animations(
+    inputs: &[impl AsRef<Path>],
+    options: &LoadOptions,
+) -> Result<Vec<Animation>> {
+    let image_paths = collect_image_paths(inputs, options, &mut Vec::new())?;
+    let mut animations = Vec::new();
+    for img_path in &image_paths {
+        match file_animations(img_path, options) {
+            Ok(file) => animations.extend(file),
+            // Already reported when the sprites were loaded
+            Err(_) if options.skip_errors => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(animations)
+}
+
+/// Animations of a single input file
+fn file_animations(img_path: &ImagePath, options: &LoadOptions) -> Result<Vec<Animation>> {
+    let base = sprite_name(&img_path.path, img_path.base.as_deref(), options);
+    if is_aseprite(&img_path.path) {
+        let file = read_aseprite(&img_path.path)?;
+        return Ok(aseprite_animations(&file, &base, options));
+    }
+    let Some(frames) = read_animated_frames(&img_path.path)? else {
+        return Ok(Vec::new());
+    };
+    let title = base
+        .rsplit_once('.')
+        .map_or(base.as_str(), |(title, _)| title);
+    Ok(vec![Animation {
+        name: title.to_string(),
+        sprite: base.clone(),
+        layer: None,
+        direction: AnimationDirection::Forward,
+        frames: frames
+            .iter()
+            .enumerate()
+            .map(|(frame, f)| AnimationFrame {
+                name: animated_frame_name(&base, frame),
+                duration: f.duration,
+            })
+            .collect(),
+    }])
+}
+
+/// One animation per tag of an Aseprite file, and per layer when layers are split
+fn aseprite_animations(file: &AseFile, base: &str, options: &LoadOptions) -> Vec<Animation> {
+    let frame_count = file.frames.len();
+    let layers: Vec<Option<&str>> = if options.split_layers {
+        file.exported_layers()
+            .into_iter()
+            .filter_map(|index| file.layers.get(index))
+            .map(|layer| Some(layer.name.as_str()))
+            .collect()
+    } else {
+        vec![None]
+    };
+    layers
+        .into_iter()
+        .flat_map(|layer| {
+            file.tags.iter().map(move |tag| Animation {
+                name: tag.name.clone(),
+                sprite: base.to_string(),
+                layer: layer.map(str::to_string),
+                direction: tag.direction,
+                frames: (tag.from..=tag.to)
+                    .map(|frame| AnimationFrame {
+                        name: frame_name(base, layer, frame, frame_count),
+                        duration: file.frames.get(frame).map_or(0, |f| f.duration),
+                    })
+                    .collect(),
+            })
+        })
+        .collect()
+}
+
+/// Drop excluded sprites and apply sprite name overrides, both keyed by source path.
+///
+/// Names are re-checked for duplicates afterwards since a rename can collide
+/// with another sprite's name.
+pub fn apply_sprite_overrides(
+    sprites: &mut Vec<SourceSprite>,
+    excluded: &HashSet<PathBuf>,
+    names: &HashMap<PathBuf, String>,
+) -> Result<()> {
+    sprites.retain(|sprite| !excluded.contains(&sprite.path));
+    for sprite in sprites.iter_mut() {
+        if let Some(name) = names.get(&sprite.path) {
+            sprite.name.clone_from(name);
+        }
+    }
+
+    if sprites.is_empty() {
+        return Err(BentoError::NoImages.into());
+    }
+    check_duplicate_names(sprites)
+}
+
+/// Attach nine-slice borders to sprites whose names match a pattern; the first match wins
+pub fn apply_nine_slices(sprites: &mut [SourceSprite], nine_slices: &[(glob::Pattern, NineSlice)]) {
+    for sprite in sprites {
+        sprite.nine_slice = nine_slices
+            .iter()
+            .find(|(pattern, _)| pattern.matches(&sprite.name))
+            .map(|&(_, borders)| borders);
+    }
+}
+
+/// Split sprites by the first directory of their names, so `ui/button.png`
+/// and `ui/icons/gem.png` land in the `ui` group. Sprites without a directory
+/// come first with no group name; groups follow in folder name order, each
+/// keeping the sprites' relative order.
+pub fn group_by_folder(sprites: Vec<SourceSprite>) -> Vec<(Option<String>, Vec<SourceSprite>)> {
+    let mut groups: BTreeMap<Option<String>, Vec<SourceSprite>> = BTreeMap::new();
+    for sprite in sprites {
+        let mut components = Path::new(&sprite.name).components();
+        let folder = match (components.next(), components.next()) {
+            (Some(Component::Normal(folder)), Some(_)) => {
+                Some(folder.to_string_lossy().into_owned())
+            }
+            _ => None,
+        };
+        groups.entry(folder).or_default().push(sprite);
+    }
+    groups.into_iter().collect()
+}
+
+/// Fail if two sprites share a name (would cause silent overwrites in Godot output)
+fn check_duplicate_names(sprites: &[SourceSprite]) -> Result<()> {
+    let duplicates = duplicate_names(sprites);
     if !duplicates.is_empty() {
-        let mut sorted = duplicates;
-        sorted.sort_unstable();
         return Err(BentoError::DuplicateNames {
-            names: sorted.join(", "),
+            names: duplicates.join(", "),
         }
         .into());
     }
 
-    sprites.sort_by(|a, b| {
-        // Sort by area descending for better packing
-        let area_a = u64::from(a.width()) * u64::from(a.height());
-        let area_b = u64::from(b.width()) * u64::from(b.height());
-        area_b.cmp(&area_a)
-    });
+    Ok(())
+}
 
-    Ok(sprites)
+/// Sprite names used by more than one sprite, sorted
+pub fn duplicate_names(sprites: &[SourceSprite]) -> Vec<&str> {
+    let mut name_counts: HashMap<&str, usize> = HashMap::new();
+    for sprite in sprites {
+        *name_counts.entry(&sprite.name).or_insert(0) += 1;
+    }
+    let mut duplicates: Vec<&str> = name_counts
+        .into_iter()
+        .filter(|(_, count)| *count > 1)
+        .map(|(name, _)| name)
+        .collect();
+    duplicates.sort_unstable();
+    duplicates
 }
 
+/// Input paths listed one per line, as printed by `find` or `git ls-files`.
+/// Blank lines are skipped.
+pub fn parse_input_list(content: &str) -> Vec<PathBuf> {
+    content
+        .lines()
+        .map(|line| line.trim_end_matches('\r'))
+        .filter(|line| !line.trim().is_empty())
+        .map(PathBuf::from)
+        .collect()
+}
+
+/// Read an input list from a file, or from stdin for `-`
+pub fn read_input_list(path: &Path) -> Result<Vec<PathBuf>> {
+    let content = if path.as_os_str() == "-" {
+        std::io::read_to_string(std::io::stdin()).context("failed to read input list from stdin")?
+    } else {
+        std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read input list: {}", path.display()))?
+    };
+    Ok(parse_input_list(&content))
+}
+
+/// Image files among the inputs, adding other files to `unsupported`
 fn collect_image_paths(
     inputs: &[impl AsRef<Path>],
-    base_dir: Option<&Path>,
-    filename_only: bool,
+    options: &LoadOptions,
+    unsupported: &mut Vec<PathBuf>,
 ) -> Result<Vec<ImagePath>> {
     let mut paths = Vec::new();
 
@@ -114,19 +618,24 @@ fn collect_image_paths(
             return Err(BentoError::InputNotFound(path.to_path_buf()).into());
         }
 
+        if options.is_excluded(path) || options.is_companion(path) {
+            continue;
+        }
         if path.is_file() {
             if is_supported_image(path) {
                 paths.push(ImagePath {
                     path: path.to_path_buf(),
-                    base: if filename_only {
+                    base: if options.filename_only {
                         None
                     } else {
-                        base_dir.map(Path::to_path_buf)
+                        options.base_dir.clone()
                     },
                 });
+            } else {
+                unsupported.push(path.to_path_buf());
             }
         } else if path.is_dir() {
-            collect_from_directory(path, path, filename_only, &mut paths)?;
+            collect_from_directory(path, path, options, &mut paths, unsupported)?;
         }
     }
 
@@ -136,75 +645,238 @@ fn collect_image_paths(
 fn collect_from_directory(
     base: &Path,
     dir: &Path,
-    filename_only: bool,
+    options: &LoadOptions,
     paths: &mut Vec<ImagePath>,
+    unsupported: &mut Vec<PathBuf>,
 ) -> Result<()> {
-    for entry in std::fs::read_dir(dir).context("Failed to read directory")? {
-        let entry = entry?;
-        let path = entry.path();
-
-        if path.is_file() && is_supported_image(&path) {
-            paths.push(ImagePath {
-                path,
-                base: if filename_only {
-                    None
-                } else {
-                    Some(base.to_path_buf())
-                },
-            });
+    // Sort entries so input order is stable across platforms and runs
+    let mut entries = std::fs::read_dir(dir)
+        .context("Failed to read directory")?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<std::io::Result<Vec<_>>>()?;
+    entries.sort();
+
+    for path in entries {
+        if options.is_excluded(&path) || options.is_companion(&path) {
+            continue;
+        }
+        if path.is_file() {
+            if is_supported_image(&path) {
+                paths.push(ImagePath {
+                    path,
+                    base: if options.filename_only {
+                        None
+                    } else {
+                        Some(base.to_path_buf())
+                    },
+                });
+            } else if !is_hidden(&path) {
+                unsupported.push(path);
+            }
         } else if path.is_dir() {
-            collect_from_directory(base, &path, filename_only, paths)?;
+            collect_from_directory(base, &path, options, paths, unsupported)?;
         }
     }
 
     Ok(())
 }
 
-fn is_supported_image(path: &Path) -> bool {
+/// Dotfiles such as `.DS_Store` or `.gitkeep`, which don't warrant a warning
+fn is_hidden(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.starts_with('.'))
+}
+
+/// Whether a path has the extension of an image format bento can load
+pub(crate) fn is_supported_image(path: &Path) -> bool {
     path.extension()
         .and_then(|ext| ext.to_str())
         .map(|ext| SUPPORTED_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
         .unwrap_or(false)
 }
 
-fn load_single_sprite(
+/// Load the sprites of one input file, with their companion images
+fn load_path_sprites(
     path: &Path,
     base: Option<&Path>,
-    trim: bool,
-    trim_margin: u32,
-    resize_width: Option<u32>,
-    resize_scale: Option<f32>,
-    resize_filter: ResizeFilter,
-) -> Result<SourceSprite> {
-    let img = ImageReader::open(path)
-        .map_err(|e| BentoError::ImageLoad {
-            path: path.to_path_buf(),
-            source: e.into(),
-        })?
-        .decode()
-        .map_err(|e| BentoError::ImageLoad {
+    options: &LoadOptions,
+) -> Result<Vec<SourceSprite>> {
+    let name = sprite_name(path, base, options);
+    let images = load_path_images(path, &name, options)?;
+
+    // Companion frames are matched to sprite frames by name
+    let mut companions = Vec::new();
+    for suffix in &options.companions {
+        let companion = companion_path(path, suffix);
+        if companion.is_file() {
+            let frames: HashMap<String, RgbaImage> = load_path_images(&companion, &name, options)?
+                .into_iter()
+                .collect();
+            companions.push((suffix, companion, frames));
+        }
+    }
+
+    images
+        .into_iter()
+        .map(|(frame_name, img)| {
+            let mut companion_images = Vec::new();
+            for (suffix, companion, frames) in &mut companions {
+                let Some(image) = frames.remove(&frame_name) else {
+                    continue;
+                };
+                if image.dimensions() != img.dimensions() {
+                    return Err(BentoError::CompanionSize {
+                        path: path.to_path_buf(),
+                        companion: companion.clone(),
+                        width: image.width(),
+                        height: image.height(),
+                        expected_width: img.width(),
+                        expected_height: img.height(),
+                    }
+                    .into());
+                }
+                companion_images.push(((*suffix).clone(), image));
+            }
+
+            let mut sprite = prepare_sprite(path, frame_name, img, options);
+            // Companions may hold data such as normals rather than sRGB colors
+            sprite.companions = companion_images
+                .into_iter()
+                .map(|(suffix, image)| {
+                    let image = resize_source(path, &sprite.name, image, options, false);
+                    (suffix, crop_to_trim(&image, &sprite.trim_info))
+                })
+                .collect();
+            Ok(sprite)
+        })
+        .collect()
+}
+
+/// Named source frames of one input file before resizing and trimming: the
+/// image itself, its animation frames, its grid cells or its Aseprite
+/// frames (and layers)
+fn load_path_images(
+    path: &Path,
+    name: &str,
+    options: &LoadOptions,
+) -> Result<Vec<(String, RgbaImage)>> {
+    if !is_aseprite(path) {
+        if let Some(frames) = read_animated_frames(path)? {
+            return Ok(frames
+                .into_iter()
+                .enumerate()
+                .map(|(frame, f)| (animated_frame_name(name, frame), f.image))
+                .collect());
+        }
+        let img = decode_image(path)?;
+        return match options.grid_slice_for(name) {
+            Some(grid) => slice_sheet(path, name, &img, grid),
+            None => Ok(vec![(name.to_string(), img)]),
+        };
+    }
+
+    let file = read_aseprite(path)?;
+    let frame_count = file.frames.len();
+    let layers = file.exported_layers();
+    let mut images = Vec::new();
+    for frame in 0..frame_count {
+        if options.split_layers {
+            for &layer in &layers {
+                let layer_name = file.layers.get(layer).map(|l| l.name.as_str());
+                images.push((
+                    frame_name(name, layer_name, frame, frame_count),
+                    file.render(frame, &[layer]),
+                ));
+            }
+        } else {
+            images.push((
+                frame_name(name, None, frame, frame_count),
+                file.render(frame, &layers),
+            ));
+        }
+    }
+    Ok(images)
+}
+
+/// Path of the companion image of an input, `hero_n.png` for `hero.png`
+fn companion_path(path: &Path, suffix: &str) -> PathBuf {
+    let mut file_name = path.file_stem().unwrap_or_default().to_os_string();
+    file_name.push(suffix);
+    if let Some(extension) = path.extension() {
+        file_name.push(".");
+        file_name.push(extension);
+    }
+    path.with_file_name(file_name)
+}
+
+/// Companion image cut to the region its sprite kept after trimming
+fn crop_to_trim(image: &RgbaImage, trim_info: &TrimInfo) -> RgbaImage {
+    let x = u32::try_from(trim_info.offset_x).unwrap_or(0);
+    let y = u32::try_from(trim_info.offset_y).unwrap_or(0);
+    image::imageops::crop_imm(
+        image,
+        x,
+        y,
+        trim_info.trimmed_width,
+        trim_info.trimmed_height,
+    )
+    .to_image()
+}
+
+/// Cut a sheet into one sprite per grid cell, named like Aseprite frames
+/// (`tiles 0.png`, `tiles 1.png`, ...) by cell index, row by row. Fully
+/// transparent cells are skipped, so the names of the others stay put.
+fn slice_sheet(
+    path: &Path,
+    name: &str,
+    img: &RgbaImage,
+    grid: GridSlice,
+) -> Result<Vec<(String, RgbaImage)>> {
+    let cells = grid.cells(img.width(), img.height());
+    if cells.is_empty() {
+        return Err(BentoError::GridSlice {
             path: path.to_path_buf(),
-            source: e,
-        })?
-        .into_rgba8();
+            message: format!(
+                "no {}x{} cells fit in a {}x{} image",
+                grid.width,
+                grid.height,
+                img.width(),
+                img.height()
+            ),
+        }
+        .into());
+    }
 
-    // Resize if requested (before trimming)
-    let filter = resize_filter.to_image_filter();
-    let img = match (resize_width, resize_scale) {
-        (Some(w), None) => resize_to_width(img, w, filter),
-        (None, Some(s)) => resize_by_scale(img, s, filter),
-        _ => img,
-    };
+    Ok(cells
+        .iter()
+        .enumerate()
+        .filter_map(|(index, &(x, y))| {
+            let cell = image::imageops::crop_imm(img, x, y, grid.width, grid.height).to_image();
+            cell.pixels()
+                .any(|p| p[3] > 0)
+                .then(|| (frame_name(name, None, index, cells.len()), cell))
+        })
+        .collect())
+}
 
-    // Compute sprite name: relative path with extension for directory inputs,
-    // or filename with extension for individual file inputs
+/// Sprite name for an input file: relative path with extension for directory
+/// inputs, or filename with extension for individual file inputs, less any
+/// scale suffix
+fn sprite_name(path: &Path, base: Option<&Path>, options: &LoadOptions) -> String {
     let name = match base {
         Some(base_dir) => {
             // Compute relative path from base directory
-            path.strip_prefix(base_dir)
-                .unwrap_or(path)
-                .to_string_lossy()
-                .to_string()
+            let relative = path.strip_prefix(base_dir).unwrap_or(path);
+            if options.deterministic {
+                relative
+                    .components()
+                    .map(|component| component.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/")
+            } else {
+                relative.to_string_lossy().to_string()
+            }
         }
         None => {
             // Individual file: use filename with extension
@@ -214,20 +886,104 @@ fn load_single_sprite(
                 .to_string()
         }
     };
+    options
+        .strip_scale_suffix(&name)
+        .map_or(name, |(name, _)| name)
+}
 
-    let (image, trim_info) = if trim {
-        trim_sprite(&img, trim_margin)
+fn decode_image(path: &Path) -> Result<RgbaImage> {
+    Ok(ImageReader::open(path)
+        .map_err(|e| BentoError::ImageLoad {
+            path: path.to_path_buf(),
+            source: e.into(),
+        })?
+        .decode()
+        .map_err(|e| BentoError::ImageLoad {
+            path: path.to_path_buf(),
+            source: e,
+        })?
+        .into_rgba8())
+}
+
+/// Resize and trim a loaded image into a source sprite
+fn prepare_sprite(
+    path: &Path,
+    name: String,
+    img: RgbaImage,
+    options: &LoadOptions,
+) -> SourceSprite {
+    let overrides = SpriteOverride::resolve(&options.overrides, &name);
+    let img = resize_source(path, &name, img, options, options.resize_linear);
+
+    let margin = options.trim_margin_for(&name);
+    let (image, trim_info, content_bounds) = if options.record_trim {
+        // Measure the content but keep every pixel of the source frame
+        let (_, trimmed) = trim_sprite(&img, margin, options.trim_alpha);
+        let (w, h) = img.dimensions();
+        (
+            img,
+            TrimInfo::untrimmed(w, h),
+            Some(ContentBounds::from(trimmed)),
+        )
+    } else if overrides.trim.unwrap_or(options.trim) {
+        let (image, trim_info) = trim_sprite(&img, margin, options.trim_alpha);
+        (image, trim_info, None)
     } else {
         let (w, h) = img.dimensions();
-        (img, TrimInfo::untrimmed(w, h))
+        (img, TrimInfo::untrimmed(w, h), None)
     };
 
-    Ok(SourceSprite {
+    SourceSprite {
         path: path.to_path_buf(),
         name,
         image,
         trim_info,
-    })
+        input_index: 0,
+        content_bounds,
+        nine_slice: None,
+        pivot: overrides.pivot,
+        companions: Vec::new(),
+    }
+}
+
+/// Resize a source frame as the options and its sprite's override ask, before
+/// trimming, in linear light when `linear` is set
+fn resize_source(
+    path: &Path,
+    name: &str,
+    img: RgbaImage,
+    options: &LoadOptions,
+    linear: bool,
+) -> RgbaImage {
+    let overrides = SpriteOverride::resolve(&options.overrides, name);
+    let filter = options.resize_filter.to_image_filter();
+
+    // Bring suffixed sources such as `hero@2x.png` to the common resolution first
+    let img = match options.strip_scale_suffix(&path.to_string_lossy()) {
+        Some((_, factor)) => resize_by_scale(img, factor, filter, linear),
+        None => img,
+    };
+
+    // Resize if requested (before trimming); a per-sprite scale replaces the global resize
+    match (
+        overrides.scale,
+        options.resize_width,
+        options.resize_scale,
+        options.resize_max_width,
+        options.resize_fit,
+    ) {
+        (Some(s), ..) => resize_by_scale(img, s, filter, linear),
+        (None, Some(w), None, _, _) => resize_to_width(img, w, filter, linear),
+        (None, None, Some(s), _, _) => resize_by_scale(img, s, filter, linear),
+        // Downscale only: smaller images keep their size
+        (None, None, None, Some(w), _) if img.width() > w => {
+            resize_to_width(img, w, filter, linear)
+        }
+        (None, None, None, None, Some(fit)) => {
+            resize_to_fit(img, fit.width, fit.height, filter, linear)
+        }
+        _ => img,
+    }
 }
 
 #[cfg(test)]
@@ -235,6 +991,7 @@ fn load_single_sprite(
 mod tests {
     use super::*;
     use crate::cli::ResizeFilter;
+    use crate::sprite::Pivot;
 
     /// Create a minimal valid 1x1 PNG file.
     fn write_test_png(path: &Path) {
@@ -261,14 +1018,13 @@ mod tests {
         // With base_dir and filename_only=false, name preserves relative path
         let sprites = load_sprites(
             &[sub.join("bat.png")],
-            false,
-            0,
-            None,
-            None,
-            ResizeFilter::Nearest,
-            None,
-            Some(dir.as_path()),
-            false,
+            &LoadOptions {
+                trim: false,
+                resize_filter: ResizeFilter::Nearest,
+                base_dir: Some(dir.clone()),
+                filename_only: false,
+                ..LoadOptions::default()
+            },
         )
         .expect("load ok");
         assert_eq!(sprites[0].name, "enemies/bat.png");
@@ -276,14 +1032,13 @@ mod tests {
         // With filename_only=true, name is bare filename
         let sprites = load_sprites(
             &[sub.join("bat.png")],
-            false,
-            0,
-            None,
-            None,
-            ResizeFilter::Nearest,
-            None,
-            Some(dir.as_path()),
-            true,
+            &LoadOptions {
+                trim: false,
+                resize_filter: ResizeFilter::Nearest,
+                base_dir: Some(dir.clone()),
+                filename_only: true,
+                ..LoadOptions::default()
+            },
         )
         .expect("load ok");
         assert_eq!(sprites[0].name, "bat.png");
@@ -301,14 +1056,13 @@ mod tests {
         // Without filename_only, directory input preserves relative path
         let sprites = load_sprites(
             std::slice::from_ref(&dir),
-            false,
-            0,
-            None,
-            None,
-            ResizeFilter::Nearest,
-            None,
-            None,
-            false,
+            &LoadOptions {
+                trim: false,
+                resize_filter: ResizeFilter::Nearest,
+                base_dir: None,
+                filename_only: false,
+                ..LoadOptions::default()
+            },
         )
         .expect("load ok");
         assert_eq!(sprites[0].name, "units/hero.png");
@@ -316,14 +1070,13 @@ mod tests {
         // With filename_only, bare filename
         let sprites = load_sprites(
             std::slice::from_ref(&dir),
-            false,
-            0,
-            None,
-            None,
-            ResizeFilter::Nearest,
-            None,
-            None,
-            true,
+            &LoadOptions {
+                trim: false,
+                resize_filter: ResizeFilter::Nearest,
+                base_dir: None,
+                filename_only: true,
+                ..LoadOptions::default()
+            },
         )
         .expect("load ok");
         assert_eq!(sprites[0].name, "hero.png");
@@ -342,18 +1095,22 @@ mod tests {
         write_test_png(&b.join("icon.png"));
 
         // filename_only causes both to be named "icon.png" -> error
-        let result = load_sprites(
-            &[a.join("icon.png"), b.join("icon.png")],
-            false,
-            0,
-            None,
-            None,
-            ResizeFilter::Nearest,
-            None,
-            None,
-            true,
-        );
-        let err = result.expect_err("should fail on duplicates");
+        let load = || {
+            load_sprites(
+                &[a.join("icon.png"), b.join("icon.png")],
+                &LoadOptions {
+                    trim: false,
+                    resize_filter: ResizeFilter::Nearest,
+                    base_dir: None,
+                    filename_only: true,
+                    ..LoadOptions::default()
+                },
+            )
+            .expect("duplicates are checked after overrides")
+        };
+        let mut sprites = load();
+        let err = apply_sprite_overrides(&mut sprites, &HashSet::new(), &HashMap::new())
+            .expect_err("should fail on duplicates");
         let msg = err.to_string();
         assert!(
             msg.contains("icon.png"),
@@ -364,6 +1121,19 @@ mod tests {
             "error should mention 'Duplicate': {msg}"
         );
 
+        // Renaming or excluding one of the pair resolves the clash
+        let names = HashMap::from([(b.join("icon.png"), "b/icon.png".to_string())]);
+        let mut sprites = load();
+        apply_sprite_overrides(&mut sprites, &HashSet::new(), &names).expect("rename resolves");
+        let mut result: Vec<&str> = sprites.iter().map(|s| s.name.as_str()).collect();
+        result.sort_unstable();
+        assert_eq!(result, ["b/icon.png", "icon.png"]);
+
+        let excluded = HashSet::from([a.join("icon.png")]);
+        let mut sprites = load();
+        apply_sprite_overrides(&mut sprites, &excluded, &HashMap::new()).expect("exclude resolves");
+        assert_eq!(sprites.len(), 1);
+
         std::fs::remove_dir_all(&dir).ok();
     }
 
@@ -375,17 +1145,609 @@ mod tests {
 
         let result = load_sprites(
             &[dir.join("alpha.png"), dir.join("beta.png")],
-            false,
-            0,
-            None,
-            None,
-            ResizeFilter::Nearest,
-            None,
-            None,
-            false,
+            &LoadOptions {
+                trim: false,
+                resize_filter: ResizeFilter::Nearest,
+                base_dir: None,
+                filename_only: false,
+                ..LoadOptions::default()
+            },
         );
         assert!(result.is_ok());
 
         std::fs::remove_dir_all(&dir).ok();
     }
+
+    #[test]
+    fn test_apply_sprite_overrides() {
+        let dir = make_temp_dir("overrides");
+        for name in ["a.png", "b.png", "c.png"] {
+            write_test_png(&dir.join(name));
+        }
+        let inputs = [dir.join("a.png"), dir.join("b.png"), dir.join("c.png")];
+        let load = || {
+            load_sprites(
+                &inputs,
+                &LoadOptions {
+                    trim: false,
+                    resize_filter: ResizeFilter::Nearest,
+                    base_dir: None,
+                    filename_only: false,
+                    ..LoadOptions::default()
+                },
+            )
+            .expect("load ok")
+        };
+
+        let excluded = HashSet::from([dir.join("b.png")]);
+        let names = HashMap::from([(dir.join("c.png"), "renamed".to_string())]);
+        let mut sprites = load();
+        apply_sprite_overrides(&mut sprites, &excluded, &names).expect("overrides ok");
+        let mut result: Vec<&str> = sprites.iter().map(|s| s.name.as_str()).collect();
+        result.sort_unstable();
+        assert_eq!(result, ["a.png", "renamed"]);
+
+        // Renaming onto an existing name is rejected
+        let names = HashMap::from([(dir.join("c.png"), "a.png".to_string())]);
+        let mut sprites = load();
+        assert!(apply_sprite_overrides(&mut sprites, &HashSet::new(), &names).is_err());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_record_trim_keeps_full_frame() {
+        let dir = make_temp_dir("record_trim");
+        let mut img = image::RgbaImage::new(8, 6);
+        for (x, y) in [(2, 1), (5, 3)] {
+            img.put_pixel(x, y, image::Rgba([255, 255, 255, 255]));
+        }
+        img.save(dir.join("dot.png"))
+            .expect("failed to write test png");
+
+        let sprites = load_sprites(
+            &[dir.join("dot.png")],
+            &LoadOptions {
+                record_trim: true,
+                ..LoadOptions::default()
+            },
+        )
+        .expect("load ok");
+        let sprite = &sprites[0];
+        assert_eq!((sprite.width(), sprite.height()), (8, 6));
+        assert!(!sprite.trim_info.was_trimmed());
+        assert_eq!(
+            sprite.content_bounds,
+            Some(ContentBounds {
+                x: 2,
+                y: 1,
+                width: 4,
+                height: 3,
+            })
+        );
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_trim_margin_overrides_first_match_wins() {
+        let options = LoadOptions {
+            trim_margin: TrimMargin::uniform(1),
+            trim_margin_overrides: vec![
+                (
+                    glob::Pattern::new("fx/smear.png").expect("valid pattern"),
+                    TrimMargin::uniform(8),
+                ),
+                (
+                    glob::Pattern::new("fx/*").expect("valid pattern"),
+                    TrimMargin {
+                        right: 4,
+                        ..TrimMargin::default()
+                    },
+                ),
+            ],
+            ..LoadOptions::default()
+        };
+
+        assert_eq!(
+            options.trim_margin_for("fx/smear.png"),
+            TrimMargin::uniform(8)
+        );
+        assert_eq!(options.trim_margin_for("fx/shadow.png").right, 4);
+        assert_eq!(options.trim_margin_for("hero.png"), TrimMargin::uniform(1));
+    }
+
+    #[test]
+    fn test_overrides_set_trim_scale_and_pivot() {
+        let dir = make_temp_dir("overrides");
+        // 8x8 with a 4x4 opaque center
+        let mut img = RgbaImage::new(8, 8);
+        for y in 2..6 {
+            for x in 2..6 {
+                img.put_pixel(x, y, image::Rgba([255, 0, 0, 255]));
+            }
+        }
+        for name in ["ui.png", "hero.png"] {
+            img.save(dir.join(name)).expect("failed to write test png");
+        }
+
+        let pivot = Pivot { x: 0.5, y: 1.0 };
+        let options = LoadOptions {
+            overrides: vec![(
+                glob::Pattern::new("ui*").expect("valid pattern"),
+                SpriteOverride {
+                    trim: Some(false),
+                    scale: Some(0.5),
+                    pivot: Some(pivot),
+                    ..SpriteOverride::default()
+                },
+            )],
+            ..LoadOptions::default()
+        };
+        let sprites = load_sprites(&[&dir], &options).expect("load ok");
+        let ui = sprites.iter().find(|s| s.name == "ui.png").expect("ui");
+        let hero = sprites.iter().find(|s| s.name == "hero.png").expect("hero");
+        assert_eq!((ui.width(), ui.height()), (4, 4));
+        assert!(!ui.trim_info.was_trimmed());
+        assert_eq!(ui.pivot, Some(pivot));
+        assert_eq!((hero.width(), hero.height()), (4, 4));
+        assert!(hero.trim_info.was_trimmed());
+        assert_eq!(hero.pivot, None);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_sprites_from_images() {
+        let mut image = RgbaImage::new(8, 8);
+        image.put_pixel(2, 3, image::Rgba([255, 0, 0, 255]));
+        let images = vec![
+            ("dot".to_string(), image),
+            (
+                "big".to_string(),
+                RgbaImage::from_pixel(4, 4, image::Rgba([0, 0, 255, 255])),
+            ),
+        ];
+
+        let sprites = sprites_from_images(images.clone(), &LoadOptions::default()).expect("ok");
+        // Trimmed like file inputs, largest first
+        assert_eq!(sprites[0].name, "big");
+        assert_eq!(sprites[1].name, "dot");
+        assert_eq!(sprites[1].image.dimensions(), (1, 1));
+        assert_eq!(sprites[1].trim_info.offset_x, 2);
+        assert_eq!(sprites[1].input_index, 0);
+
+        let duplicate = vec![images[0].clone(), images[0].clone()];
+        assert!(sprites_from_images(duplicate, &LoadOptions::default()).is_err());
+        assert!(sprites_from_images(Vec::new(), &LoadOptions::default()).is_err());
+    }
+
+    #[test]
+    fn test_resize_max_width_only_shrinks() {
+        let opaque = |w, h| RgbaImage::from_pixel(w, h, image::Rgba([255, 255, 255, 255]));
+        let images = vec![
+            ("icon".to_string(), opaque(16, 16)),
+            ("art".to_string(), opaque(256, 128)),
+        ];
+        let options = LoadOptions {
+            resize_max_width: Some(64),
+            resize_filter: ResizeFilter::Nearest,
+            ..LoadOptions::default()
+        };
+
+        let sprites = sprites_from_images(images, &options).expect("ok");
+        let size = |name: &str| {
+            sprites
+                .iter()
+                .find(|s| s.name == name)
+                .map(|s| s.image.dimensions())
+        };
+        assert_eq!(size("art"), Some((64, 32)));
+        assert_eq!(size("icon"), Some((16, 16)));
+    }
+
+    #[test]
+    fn test_scale_suffix_normalizes_sources() {
+        let dir = make_temp_dir("scale_suffix");
+        let sub = dir.join("ui");
+        std::fs::create_dir_all(&sub).expect("mkdir");
+        let opaque = |w, h| RgbaImage::from_pixel(w, h, image::Rgba([255, 255, 255, 255]));
+        opaque(32, 16).save(sub.join("hero@2x.png")).expect("write");
+        opaque(48, 48).save(sub.join("coin@3x.png")).expect("write");
+        opaque(16, 8).save(sub.join("icon.png")).expect("write");
+
+        let options = LoadOptions {
+            resize_scale: Some(0.5),
+            resize_filter: ResizeFilter::Nearest,
+            scale_suffixes: vec![("@2x".to_string(), 0.5), ("@3x".to_string(), 1.0 / 3.0)],
+            ..LoadOptions::default()
+        };
+        let sprites = load_sprites(std::slice::from_ref(&dir), &options).expect("load ok");
+        let size = |name: &str| {
+            sprites
+                .iter()
+                .find(|s| s.name == name)
+                .map(|s| s.image.dimensions())
+        };
+        // Suffixed sources reach the common resolution before the global resize
+        assert_eq!(size("ui/hero.png"), Some((8, 4)));
+        assert_eq!(size("ui/coin.png"), Some((8, 8)));
+        assert_eq!(size("ui/icon.png"), Some((8, 4)));
+
+        assert_eq!(
+            options.strip_scale_suffix("@2x.png"),
+            None,
+            "a bare suffix is not stripped"
+        );
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_companions_load_with_base_trim() {
+        let dir = make_temp_dir("companions");
+        let mut base = RgbaImage::new(8, 8);
+        let mut normal = RgbaImage::from_pixel(8, 8, image::Rgba([128, 128, 255, 255]));
+        for (x, y) in [(2, 3), (5, 4)] {
+            base.put_pixel(x, y, image::Rgba([255, 0, 0, 255]));
+            normal.put_pixel(x, y, image::Rgba([0, 0, 0, 255]));
+        }
+        base.save(dir.join("hero.png")).expect("write");
+        normal.save(dir.join("hero_n.png")).expect("write");
+        // No base image, so it packs as a sprite of its own
+        normal.save(dir.join("lone_n.png")).expect("write");
+
+        let options = LoadOptions {
+            companions: vec!["_n".to_string(), "_e".to_string()],
+            ..LoadOptions::default()
+        };
+        let sprites = load_sprites(std::slice::from_ref(&dir), &options).expect("load ok");
+        let mut names: Vec<&str> = sprites.iter().map(|s| s.name.as_str()).collect();
+        names.sort_unstable();
+        assert_eq!(names, ["hero.png", "lone_n.png"]);
+
+        let hero = sprites
+            .iter()
+            .find(|s| s.name == "hero.png")
+            .expect("hero loaded");
+        assert_eq!(hero.companions.len(), 1);
+        let (suffix, companion) = &hero.companions[0];
+        assert_eq!(suffix, "_n");
+        // Cut to the base image's trim, not the companion's own content
+        assert_eq!(companion.dimensions(), hero.image.dimensions());
+        assert_eq!(companion.get_pixel(0, 0), &image::Rgba([0, 0, 0, 255]));
+        assert_eq!(
+            companion.get_pixel(1, 0),
+            &image::Rgba([128, 128, 255, 255])
+        );
+
+        RgbaImage::new(4, 8)
+            .save(dir.join("hero_n.png"))
+            .expect("write");
+        let err = load_sprites(std::slice::from_ref(&dir), &options).expect_err("sizes differ");
+        assert!(matches!(
+            err.downcast_ref::<BentoError>(),
+            Some(BentoError::CompanionSize {
+                width: 4,
+                expected_width: 8,
+                ..
+            })
+        ));
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_sort_strategies() {
+        let images = || {
+            [("wide", 8, 2), ("tall", 3, 6), ("big", 6, 5)].map(|(name, w, h)| {
+                (
+                    name.to_string(),
+                    RgbaImage::from_pixel(w, h, image::Rgba([255, 0, 0, 255])),
+                )
+            })
+        };
+        let names = |sort: SpriteSort| -> Vec<String> {
+            let options = LoadOptions {
+                sort,
+                ..LoadOptions::default()
+            };
+            sprites_from_images(images(), &options)
+                .expect("ok")
+                .into_iter()
+                .map(|s| s.name)
+                .collect()
+        };
+
+        assert_eq!(names(SpriteSort::Area), ["big", "tall", "wide"]);
+        assert_eq!(names(SpriteSort::MaxSide), ["wide", "tall", "big"]);
+        assert_eq!(names(SpriteSort::Height), ["tall", "big", "wide"]);
+        assert_eq!(names(SpriteSort::Width), ["wide", "big", "tall"]);
+        assert_eq!(names(SpriteSort::Perimeter), ["big", "wide", "tall"]);
+        assert_eq!(names(SpriteSort::Name), ["big", "tall", "wide"]);
+        assert_eq!(names(SpriteSort::None), ["wide", "tall", "big"]);
+    }
+
+    #[test]
+    fn test_group_by_folder() {
+        let sprites = [
+            "ui/button.png",
+            "hero.png",
+            "enemies/bat.png",
+            "ui/icons/gem.png",
+        ]
+        .into_iter()
+        .map(|name| SourceSprite::from_image(name, RgbaImage::new(1, 1)))
+        .collect();
+
+        let groups: Vec<(Option<String>, Vec<String>)> = group_by_folder(sprites)
+            .into_iter()
+            .map(|(folder, sprites)| (folder, sprites.into_iter().map(|s| s.name).collect()))
+            .collect();
+        assert_eq!(
+            groups,
+            vec![
+                (None, vec!["hero.png".to_string()]),
+                (
+                    Some("enemies".to_string()),
+                    vec!["enemies/bat.png".to_string()]
+                ),
+                (
+                    Some("ui".to_string()),
+                    vec!["ui/button.png".to_string(), "ui/icons/gem.png".to_string()]
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_deterministic_order_ignores_input_order() {
+        let dir = make_temp_dir("deterministic");
+        for name in ["c.png", "a.png", "b.png"] {
+            write_test_png(&dir.join(name));
+        }
+        let forward = [dir.join("a.png"), dir.join("b.png"), dir.join("c.png")];
+        let backward = [dir.join("c.png"), dir.join("b.png"), dir.join("a.png")];
+        let names = |inputs: &[std::path::PathBuf], deterministic| -> Vec<String> {
+            let options = LoadOptions {
+                deterministic,
+                ..LoadOptions::default()
+            };
+            load_sprites(inputs, &options)
+                .expect("load ok")
+                .into_iter()
+                .map(|s| s.name)
+                .collect()
+        };
+
+        // Equal areas keep the input order by default...
+        assert_eq!(names(&backward, false), ["c.png", "b.png", "a.png"]);
+        // ...and fall back to names in deterministic mode
+        assert_eq!(names(&forward, true), ["a.png", "b.png", "c.png"]);
+        assert_eq!(names(&backward, true), ["a.png", "b.png", "c.png"]);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_exclude_patterns_skip_files_and_directories() {
+        let dir = make_temp_dir("exclude");
+        std::fs::create_dir_all(dir.join("_wip/deep")).expect("failed to create subdir");
+        write_test_png(&dir.join("hero.png"));
+        write_test_png(&dir.join("hero.backup.png"));
+        write_test_png(&dir.join("_wip/deep/draft.png"));
+
+        let options = LoadOptions {
+            exclude: ["*.backup.png", "**/_wip/**"]
+                .iter()
+                .map(|p| glob::Pattern::new(p).expect("valid pattern"))
+                .collect(),
+            ..LoadOptions::default()
+        };
+        let sprites = load_sprites(&[&dir], &options).expect("load ok");
+        let names: Vec<&str> = sprites.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["hero.png"]);
+
+        // A pattern naming a directory skips everything below it
+        let options = LoadOptions {
+            exclude: vec![glob::Pattern::new("_wip").expect("valid pattern")],
+            ..LoadOptions::default()
+        };
+        assert_eq!(load_sprites(&[&dir], &options).expect("load ok").len(), 2);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_skip_errors_skips_unreadable_images() {
+        let dir = make_temp_dir("skip_errors");
+        write_test_png(&dir.join("hero.png"));
+        std::fs::write(dir.join("broken.png"), b"not a png").expect("failed to write file");
+
+        assert!(load_sprites(&[&dir], &LoadOptions::default()).is_err());
+
+        let options = LoadOptions {
+            skip_errors: true,
+            ..LoadOptions::default()
+        };
+        let (sprites, warnings) = load_sprites_with_warnings(&[&dir], &options).expect("load ok");
+        let names: Vec<&str> = sprites.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["hero.png"]);
+        assert!(
+            matches!(&warnings[..], [Warning::SkippedImage { message }] if message.contains("broken.png"))
+        );
+
+        // With nothing left to pack, the load error is still reported
+        let broken = [dir.join("broken.png")];
+        assert!(load_sprites(&broken, &options).is_err());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_load_warnings() {
+        let dir = make_temp_dir("load_warnings");
+        write_test_png(&dir.join("hero.png"));
+        write_test_png(&dir.join("Hero.png"));
+        image::RgbaImage::new(8, 8)
+            .save(dir.join("blank.png"))
+            .expect("failed to write test png");
+        std::fs::write(dir.join("notes.txt"), "").expect("failed to write file");
+        std::fs::write(dir.join(".DS_Store"), "").expect("failed to write file");
+
+        let (sprites, warnings) =
+            load_sprites_with_warnings(&[&dir], &LoadOptions::default()).expect("load ok");
+        assert_eq!(sprites.len(), 3);
+        assert_eq!(
+            warnings,
+            vec![
+                Warning::UnsupportedFiles {
+                    paths: vec![dir.join("notes.txt")]
+                },
+                Warning::EmptySprites {
+                    names: vec!["blank.png".to_string()]
+                },
+                Warning::CaseDuplicates {
+                    names: vec!["Hero.png".to_string(), "hero.png".to_string()]
+                },
+            ]
+        );
+
+        // Untrimmed, an empty image keeps its size and isn't worth a warning
+        let options = LoadOptions {
+            trim: false,
+            ..LoadOptions::default()
+        };
+        let (_, warnings) = load_sprites_with_warnings(&[&dir], &options).expect("load ok");
+        assert_eq!(warnings.len(), 2);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_grid_slice_cuts_sheet_into_cells() {
+        let dir = make_temp_dir("grid_slice");
+        let path = dir.join("sheet.png");
+        // 3x2 cells of 4x4 with a 1px margin and 2px spacing; cell 4 stays empty
+        let grid = GridSlice {
+            width: 4,
+            height: 4,
+            margin: 1,
+            spacing: 2,
+        };
+        let mut sheet = RgbaImage::new(18, 12);
+        for (index, (x, y)) in grid.cells(18, 12).into_iter().enumerate() {
+            if index == 4 {
+                continue;
+            }
+            let shade = u8::try_from(index * 40).expect("small index");
+            for (dx, dy) in [(0, 0), (3, 3)] {
+                sheet.put_pixel(x + dx, y + dy, image::Rgba([shade, 0, 0, 255]));
+            }
+        }
+        sheet.save(&path).expect("failed to write test png");
+
+        let options = LoadOptions {
+            trim: false,
+            grid_slices: vec![(glob::Pattern::new("sheet.*").expect("valid pattern"), grid)],
+            ..LoadOptions::default()
+        };
+        let mut sprites = load_sprites(&[&path], &options).expect("load ok");
+        sprites.sort_by_key(|s| s.input_index);
+        let names: Vec<&str> = sprites.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "sheet 0.png",
+                "sheet 1.png",
+                "sheet 2.png",
+                "sheet 3.png",
+                "sheet 5.png"
+            ]
+        );
+        assert!(sprites.iter().all(|s| s.width() == 4 && s.height() == 4));
+        assert_eq!(sprites[4].image.get_pixel(3, 3)[0], 200);
+
+        let too_small = LoadOptions {
+            grid_slices: vec![(
+                glob::Pattern::new("sheet.png").expect("valid pattern"),
+                GridSlice { width: 32, ..grid },
+            )],
+            ..LoadOptions::default()
+        };
+        assert!(load_sprites(&[&path], &too_small).is_err());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_aseprite_frames_layers_and_tags() {
+        let dir = make_temp_dir("aseprite");
+        let path = dir.join("hero.aseprite");
+        std::fs::write(&path, crate::sprite::aseprite::tests::sample_file()).expect("write");
+
+        let options = LoadOptions {
+            trim: false,
+            ..LoadOptions::default()
+        };
+        let mut sprites = load_sprites(&[&path], &options).expect("load ok");
+        sprites.sort_by_key(|s| s.input_index);
+        let names: Vec<&str> = sprites.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["hero 0.aseprite", "hero 1.aseprite"]);
+        assert_eq!(sprites[0].width(), 2);
+
+        let animations = load_animations(&[&path], &options).expect("animations");
+        assert_eq!(animations.len(), 1);
+        assert_eq!(animations[0].name, "walk");
+        assert_eq!(animations[0].sprite, "hero.aseprite");
+        assert_eq!(
+            animations[0].frames,
+            vec![
+                AnimationFrame {
+                    name: "hero 0.aseprite".to_string(),
+                    duration: 100,
+                },
+                AnimationFrame {
+                    name: "hero 1.aseprite".to_string(),
+                    duration: 50,
+                },
+            ]
+        );
+
+        // Each visible layer becomes its own sprite; the hidden one is skipped
+        let options = LoadOptions {
+            split_layers: true,
+            ..options
+        };
+        let mut sprites = load_sprites(&[&path], &options).expect("load ok");
+        sprites.sort_by_key(|s| s.input_index);
+        let names: Vec<&str> = sprites.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "hero (base) 0.aseprite",
+                "hero (top) 0.aseprite",
+                "hero (base) 1.aseprite",
+                "hero (top) 1.aseprite",
+            ]
+        );
+        let animations = load_animations(&[&path], &options).expect("animations");
+        assert_eq!(animations.len(), 2);
+        assert_eq!(animations[1].layer.as_deref(), Some("top"));
+        assert_eq!(animations[1].frames[1].name, "hero (top) 1.aseprite");
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_parse_input_list() {
+        let list = "sprites/a.png\r\n\nsprites/b c.png\n   \nui/button.png";
+        assert_eq!(
+            parse_input_list(list),
+            [
+                PathBuf::from("sprites/a.png"),
+                PathBuf::from("sprites/b c.png"),
+                PathBuf::from("ui/button.png"),
+            ]
+        );
+        assert!(parse_input_list("\n\n").is_empty());
+    }
 }
@@ -1,9 +1,26 @@
+mod animated;
+mod aseprite;
 mod loader;
+mod polygon;
 mod resizer;
+mod stats;
 mod trimmer;
 mod types;
 
-pub use loader::load_sprites;
-pub use resizer::{resize_by_scale, resize_to_width};
+pub use aseprite::{Animation, AnimationDirection, AnimationFrame};
+#[cfg(feature = "gui")]
+pub(crate) use aseprite::{first_frame, is_aseprite};
+pub(crate) use loader::is_supported_image;
+pub use loader::{
+    LoadOptions, apply_nine_slices, apply_sprite_overrides, duplicate_names, group_by_folder,
+    load_animations, load_sprites, load_sprites_with_warnings, parse_input_list, read_input_list,
+    sprites_from_images, try_load_sprites,
+};
+pub use polygon::SpriteMesh;
+pub use resizer::{resize_by_scale, resize_image, resize_to_fit, resize_to_width};
+pub use stats::SpriteStats;
 pub use trimmer::trim_sprite;
-pub use types::{PackedSprite, SourceSprite, TrimInfo};
+pub use types::{
+    ContentBounds, GridSlice, NineSlice, PackedSprite, Pivot, SourceSprite, SpriteOverride,
+    TrimInfo, TrimMargin,
+};
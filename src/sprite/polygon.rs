@@ -0,0 +1,444 @@
+use image::RgbaImage;
+
+/// Outlines with more corners than this are not triangulated
+const MAX_VERTICES: usize = 1024;
+
+/// Outline of a sprite's visible pixels, triangulated, so the sprite can be
+/// drawn with less overdraw than its full rectangle
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpriteMesh {
+    /// Outline corners in pixels of the image the mesh was built from
+    pub vertices: Vec<[u32; 2]>,
+    /// Triangles as indices into `vertices`
+    pub triangles: Vec<[usize; 3]>,
+}
+
+impl SpriteMesh {
+    /// Simplification tolerance in pixels used unless another is given
+    pub const DEFAULT_TOLERANCE: f32 = 2.0;
+
+    /// Mesh covering every pixel of `image` with any alpha.
+    ///
+    /// The outline is traced around the pixels (marching squares), grown by
+    /// `tolerance` pixels, then simplified so no edge strays more than
+    /// `tolerance` from it; growing first means simplifying never cuts into
+    /// a visible pixel. Holes are filled, and separate islands get outlines
+    /// of their own. Returns None for a fully transparent image, or when the
+    /// outline is too detailed or folds onto itself, where the rectangle
+    /// should be drawn instead.
+    pub fn from_image(image: &RgbaImage, tolerance: f32) -> Option<Self> {
+        let (width, height) = image.dimensions();
+        let mut mask = Mask {
+            width,
+            height,
+            cells: image.pixels().map(|p| p[3] > 0).collect(),
+        };
+        if !mask.cells.contains(&true) {
+            return None;
+        }
+        #[expect(
+            clippy::cast_possible_truncation,
+            clippy::cast_sign_loss,
+            reason = "tolerance is clamped to a small positive number of pixels"
+        )]
+        let radius = tolerance.clamp(0.0, 1024.0).ceil() as u32;
+        mask.dilate(radius);
+        mask.fill_holes();
+
+        let mut mesh = Self {
+            vertices: Vec::new(),
+            triangles: Vec::new(),
+        };
+        for outline in mask.outlines() {
+            let outline = simplify(&outline, width, height, f64::from(tolerance.max(0.0)));
+            if mesh.vertices.len() + outline.len() > MAX_VERTICES || !is_simple(&outline) {
+                return None;
+            }
+            let offset = mesh.vertices.len();
+            let triangles = triangulate(&outline)?;
+            mesh.triangles.extend(
+                triangles
+                    .into_iter()
+                    .map(|[a, b, c]| [a + offset, b + offset, c + offset]),
+            );
+            mesh.vertices.extend(
+                outline
+                    .iter()
+                    .map(|&[x, y]| [u32::try_from(x).unwrap_or(0), u32::try_from(y).unwrap_or(0)]),
+            );
+        }
+        Some(mesh)
+    }
+}
+
+/// Which pixels an outline has to cover
+struct Mask {
+    width: u32,
+    height: u32,
+    cells: Vec<bool>,
+}
+
+/// A pixel corner, from (0, 0) to (width, height)
+type Point = [i64; 2];
+
+impl Mask {
+    /// Index of a pixel in `cells`, if it is inside the image
+    fn index(&self, x: i64, y: i64) -> Option<usize> {
+        match (u32::try_from(x), u32::try_from(y)) {
+            (Ok(x), Ok(y)) if x < self.width && y < self.height => {
+                Some(y as usize * self.width as usize + x as usize)
+            }
+            _ => None,
+        }
+    }
+
+    fn get(&self, x: i64, y: i64) -> bool {
+        self.index(x, y).is_some_and(|i| self.cells[i])
+    }
+
+    /// Set every pixel within `radius` pixels (Chebyshev) of a set one,
+    /// one axis at a time
+    fn dilate(&mut self, radius: u32) {
+        if radius == 0 {
+            return;
+        }
+        let (width, height) = (self.width as usize, self.height as usize);
+        let radius = radius as usize;
+        let mut grown = vec![false; self.cells.len()];
+        for y in 0..height {
+            for x in 0..width {
+                let (start, end) = (x.saturating_sub(radius), (x + radius).min(width - 1));
+                grown[y * width + x] = self.cells[y * width + start..=y * width + end]
+                    .iter()
+                    .any(|&set| set);
+            }
+        }
+        for y in 0..height {
+            for x in 0..width {
+                let (start, end) = (y.saturating_sub(radius), (y + radius).min(height - 1));
+                self.cells[y * width + x] = (start..=end).any(|row| grown[row * width + x]);
+            }
+        }
+    }
+
+    /// Set the unset pixels that can't reach the image border, moving
+    /// diagonally too, so outlines never have holes
+    fn fill_holes(&mut self) {
+        let (width, height) = (i64::from(self.width), i64::from(self.height));
+        let mut outside = vec![false; self.cells.len()];
+        let mut stack: Vec<Point> = (0..width)
+            .flat_map(|x| [[x, 0], [x, height - 1]])
+            .chain((0..height).flat_map(|y| [[0, y], [width - 1, y]]))
+            .collect();
+        while let Some([x, y]) = stack.pop() {
+            let Some(i) = self.index(x, y) else {
+                continue;
+            };
+            if outside[i] || self.cells[i] {
+                continue;
+            }
+            outside[i] = true;
+            for (dx, dy) in [
+                (-1, -1),
+                (0, -1),
+                (1, -1),
+                (-1, 0),
+                (1, 0),
+                (-1, 1),
+                (0, 1),
+                (1, 1),
+            ] {
+                stack.push([x + dx, y + dy]);
+            }
+        }
+        for (cell, outside) in self.cells.iter_mut().zip(outside) {
+            *cell = !outside;
+        }
+    }
+
+    /// Outline of each 4-connected island of set pixels, clockwise (y down),
+    /// with a corner wherever the outline turns
+    fn outlines(&self) -> Vec<Vec<Point>> {
+        let (width, height) = (i64::from(self.width), i64::from(self.height));
+        let mut seen = vec![false; self.cells.len()];
+        let mut outlines = Vec::new();
+        for y in 0..height {
+            for x in 0..width {
+                if !self.get(x, y) || self.index(x, y).is_some_and(|i| seen[i]) {
+                    continue;
+                }
+                // The first pixel of an island in scan order has its top-left corner on the outline
+                let mut stack = vec![[x, y]];
+                while let Some([x, y]) = stack.pop() {
+                    match self.index(x, y) {
+                        Some(i) if self.cells[i] && !seen[i] => seen[i] = true,
+                        _ => continue,
+                    }
+                    stack.extend([[x - 1, y], [x + 1, y], [x, y - 1], [x, y + 1]]);
+                }
+                outlines.push(self.trace([x, y]));
+            }
+        }
+        outlines
+    }
+
+    /// Follow pixel edges from `start`, keeping set pixels on the right
+    fn trace(&self, start: Point) -> Vec<Point> {
+        // East, south, west, north: each is a right turn from the one before
+        const DIRECTIONS: [Point; 4] = [[1, 0], [0, 1], [-1, 0], [0, -1]];
+        let mut corners = vec![start];
+        let mut direction = 0;
+        let mut at = [start[0] + 1, start[1]];
+        while at != start {
+            let [x, y] = at;
+            // Pixels ahead on the right and left of the current direction
+            let (right, left) = match direction {
+                0 => (self.get(x, y), self.get(x, y - 1)),
+                1 => (self.get(x - 1, y), self.get(x, y)),
+                2 => (self.get(x - 1, y - 1), self.get(x - 1, y)),
+                _ => (self.get(x, y - 1), self.get(x - 1, y - 1)),
+            };
+            let turn = match (right, left) {
+                // Also away from a pixel touching only diagonally, which is not connected
+                (false, _) => (direction + 1) % 4,
+                (true, false) => direction,
+                (true, true) => (direction + 3) % 4,
+            };
+            if turn != direction {
+                corners.push(at);
+                direction = turn;
+            }
+            let [dx, dy] = DIRECTIONS[direction];
+            at = [x + dx, y + dy];
+        }
+        corners
+    }
+}
+
+/// Drop corners of a closed outline that lie within `tolerance` of the
+/// simplified edges (Douglas-Peucker). Corners on the image border are kept,
+/// so the outline never cuts across pixels there.
+fn simplify(outline: &[Point], width: u32, height: u32, tolerance: f64) -> Vec<Point> {
+    let n = outline.len();
+    let on_border =
+        |[x, y]: Point| x == 0 || y == 0 || x == i64::from(width) || y == i64::from(height);
+    let mut anchors: Vec<usize> = (0..n).filter(|&i| on_border(outline[i])).collect();
+    if anchors.len() < 2 {
+        let first = anchors.first().copied().unwrap_or(0);
+        let farthest = (0..n)
+            .max_by_key(|&i| {
+                let [dx, dy] = [
+                    outline[i][0] - outline[first][0],
+                    outline[i][1] - outline[first][1],
+                ];
+                dx * dx + dy * dy
+            })
+            .unwrap_or(first);
+        anchors = vec![first.min(farthest), first.max(farthest)];
+    }
+
+    let mut keep = vec![false; n];
+    for (k, &start) in anchors.iter().enumerate() {
+        let end = anchors.get(k + 1).copied().unwrap_or(anchors[0] + n);
+        keep[start] = true;
+        let mut spans = vec![(start, end)];
+        while let Some((start, end)) = spans.pop() {
+            let (a, b) = (outline[start % n], outline[end % n]);
+            let farthest = (start + 1..end)
+                .map(|i| (i, segment_distance(outline[i % n], a, b)))
+                .max_by(|x, y| x.1.total_cmp(&y.1));
+            if let Some((i, distance)) = farthest
+                && distance > tolerance
+            {
+                keep[i % n] = true;
+                spans.push((start, i));
+                spans.push((i, end));
+            }
+        }
+    }
+    outline
+        .iter()
+        .zip(keep)
+        .filter_map(|(&point, keep)| keep.then_some(point))
+        .collect()
+}
+
+#[expect(
+    clippy::cast_precision_loss,
+    reason = "pixel coordinates are far below 2^52"
+)]
+fn segment_distance(p: Point, a: Point, b: Point) -> f64 {
+    let (px, py) = ((p[0] - a[0]) as f64, (p[1] - a[1]) as f64);
+    let (dx, dy) = ((b[0] - a[0]) as f64, (b[1] - a[1]) as f64);
+    let length = dx * dx + dy * dy;
+    let t = if length > 0.0 {
+        ((px * dx + py * dy) / length).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    (px - t * dx).hypot(py - t * dy)
+}
+
+/// Twice the signed area of triangle `a b c`
+fn cross(a: Point, b: Point, c: Point) -> i64 {
+    (b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0])
+}
+
+/// Whether no two edges of a closed outline touch, other than neighbors at
+/// their shared corner
+fn is_simple(outline: &[Point]) -> bool {
+    let n = outline.len();
+    if n < 3 {
+        return false;
+    }
+    let edge = |i: usize| (outline[i], outline[(i + 1) % n]);
+    (0..n).all(|i| {
+        (i + 2..n)
+            .filter(|&j| (j + 1) % n != i)
+            .all(|j| !segments_touch(edge(i), edge(j)))
+    })
+}
+
+fn segments_touch((a, b): (Point, Point), (c, d): (Point, Point)) -> bool {
+    let within = |p: Point, q: Point, r: Point| {
+        r[0] >= p[0].min(q[0])
+            && r[0] <= p[0].max(q[0])
+            && r[1] >= p[1].min(q[1])
+            && r[1] <= p[1].max(q[1])
+    };
+    let (d1, d2) = (cross(c, d, a), cross(c, d, b));
+    let (d3, d4) = (cross(a, b, c), cross(a, b, d));
+    if d1.signum() * d2.signum() < 0 && d3.signum() * d4.signum() < 0 {
+        return true;
+    }
+    (d1 == 0 && within(c, d, a))
+        || (d2 == 0 && within(c, d, b))
+        || (d3 == 0 && within(a, b, c))
+        || (d4 == 0 && within(a, b, d))
+}
+
+/// Ear-clip a simple closed outline into triangles of corner indices
+fn triangulate(outline: &[Point]) -> Option<Vec<[usize; 3]>> {
+    let area: i64 = (0..outline.len())
+        .map(|i| cross([0, 0], outline[i], outline[(i + 1) % outline.len()]))
+        .sum();
+    let winding = area.signum();
+    if winding == 0 {
+        return None;
+    }
+
+    let mut remaining: Vec<usize> = (0..outline.len()).collect();
+    let mut triangles = Vec::with_capacity(outline.len().saturating_sub(2));
+    while remaining.len() > 3 {
+        let m = remaining.len();
+        let corners = |i: usize| {
+            (
+                remaining[(i + m - 1) % m],
+                remaining[i],
+                remaining[(i + 1) % m],
+            )
+        };
+        let ear = (0..m).find(|&i| {
+            let (a, b, c) = corners(i);
+            let turn = cross(outline[a], outline[b], outline[c]).signum();
+            // A straight corner is dropped without a triangle
+            turn == 0
+                || (turn == winding
+                    && remaining.iter().all(|&j| {
+                        j == a
+                            || j == b
+                            || j == c
+                            || !in_triangle(outline[j], outline[a], outline[b], outline[c])
+                    }))
+        })?;
+        let (a, b, c) = corners(ear);
+        if cross(outline[a], outline[b], outline[c]) != 0 {
+            triangles.push([a, b, c]);
+        }
+        remaining.remove(ear);
+    }
+    if let [a, b, c] = remaining[..]
+        && cross(outline[a], outline[b], outline[c]) != 0
+    {
+        triangles.push([a, b, c]);
+    }
+    Some(triangles)
+}
+
+/// Whether `p` is inside or on the edge of triangle `a b c`
+fn in_triangle(p: Point, a: Point, b: Point, c: Point) -> bool {
+    let (d1, d2, d3) = (cross(a, b, p), cross(b, c, p), cross(c, a, p));
+    let negative = d1 < 0 || d2 < 0 || d3 < 0;
+    let positive = d1 > 0 || d2 > 0 || d3 > 0;
+    !(negative && positive)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use image::Rgba;
+
+    /// Twice the area covered by a mesh's triangles
+    fn mesh_area(mesh: &SpriteMesh) -> i64 {
+        let point = |i: usize| {
+            [
+                i64::from(mesh.vertices[i][0]),
+                i64::from(mesh.vertices[i][1]),
+            ]
+        };
+        mesh.triangles
+            .iter()
+            .map(|&[a, b, c]| cross(point(a), point(b), point(c)).abs())
+            .sum()
+    }
+
+    #[test]
+    fn test_opaque_image_is_a_quad() {
+        let image = RgbaImage::from_pixel(8, 4, Rgba([255; 4]));
+        let mesh = SpriteMesh::from_image(&image, 2.0).unwrap();
+        assert_eq!(mesh.vertices, [[0, 0], [8, 0], [8, 4], [0, 4]]);
+        assert_eq!(mesh.triangles.len(), 2);
+        assert_eq!(mesh_area(&mesh), 2 * 32);
+
+        assert_eq!(SpriteMesh::from_image(&RgbaImage::new(4, 4), 1.0), None);
+    }
+
+    #[test]
+    fn test_outline_covers_every_visible_pixel() {
+        // A diagonal band, which a quad would draw mostly empty
+        let mut image = RgbaImage::new(32, 32);
+        for i in 0..32u32 {
+            for j in i.saturating_sub(2)..(i + 3).min(32) {
+                image.put_pixel(j, i, Rgba([255, 255, 255, 40]));
+            }
+        }
+        image.put_pixel(31, 0, Rgba([255, 0, 0, 255]));
+
+        for tolerance in [0.0, 1.0, 2.5] {
+            let mesh = SpriteMesh::from_image(&image, tolerance).unwrap();
+            assert!(mesh_area(&mesh) < 2 * 32 * 32 / 2, "{}", tolerance);
+            let point = |i: usize| {
+                [
+                    i64::from(mesh.vertices[i][0]),
+                    i64::from(mesh.vertices[i][1]),
+                ]
+            };
+            for (x, y, pixel) in image.enumerate_pixels() {
+                if pixel[3] == 0 {
+                    continue;
+                }
+                // Every corner of the pixel is covered
+                let covered = |p: Point| {
+                    mesh.triangles
+                        .iter()
+                        .any(|&[a, b, c]| in_triangle(p, point(a), point(b), point(c)))
+                };
+                let (x, y) = (i64::from(x), i64::from(y));
+                for corner in [[x, y], [x + 1, y], [x, y + 1], [x + 1, y + 1]] {
+                    assert!(covered(corner), "{:?} at tolerance {}", corner, tolerance);
+                }
+            }
+        }
+    }
+}
@@ -1,7 +1,12 @@
-use image::{RgbaImage, imageops::FilterType};
+use image::{Rgba, Rgba32FImage, RgbaImage, imageops::FilterType};
 
 /// Resize an image to a target width, preserving aspect ratio
-pub fn resize_to_width(img: RgbaImage, target_width: u32, filter: FilterType) -> RgbaImage {
+pub fn resize_to_width(
+    img: RgbaImage,
+    target_width: u32,
+    filter: FilterType,
+    linear: bool,
+) -> RgbaImage {
     let (w, h) = img.dimensions();
     let scale = target_width as f32 / w as f32;
     #[expect(
@@ -10,11 +15,11 @@ pub fn resize_to_width(img: RgbaImage, target_width: u32, filter: FilterType) ->
         reason = "scale is positive, result fits in u32"
     )]
     let new_height = (h as f32 * scale).round() as u32;
-    image::imageops::resize(&img, target_width, new_height.max(1), filter)
+    resize_image(&img, target_width, new_height.max(1), filter, linear)
 }
 
 /// Resize an image by a scale factor
-pub fn resize_by_scale(img: RgbaImage, scale: f32, filter: FilterType) -> RgbaImage {
+pub fn resize_by_scale(img: RgbaImage, scale: f32, filter: FilterType, linear: bool) -> RgbaImage {
     let (w, h) = img.dimensions();
     #[expect(
         clippy::cast_possible_truncation,
@@ -28,7 +33,113 @@ pub fn resize_by_scale(img: RgbaImage, scale: f32, filter: FilterType) -> RgbaIm
         reason = "scale is positive, result fits in u32"
     )]
     let new_height = (h as f32 * scale).round() as u32;
-    image::imageops::resize(&img, new_width.max(1), new_height.max(1), filter)
+    resize_image(&img, new_width.max(1), new_height.max(1), filter, linear)
+}
+
+/// Shrink an image to fit within `max_width`x`max_height`, preserving aspect
+/// ratio. Images that already fit keep their size.
+pub fn resize_to_fit(
+    img: RgbaImage,
+    max_width: u32,
+    max_height: u32,
+    filter: FilterType,
+    linear: bool,
+) -> RgbaImage {
+    let (w, h) = img.dimensions();
+    if w <= max_width && h <= max_height {
+        return img;
+    }
+    let scale = (max_width as f32 / w as f32).min(max_height as f32 / h as f32);
+    #[expect(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        reason = "scale is positive, result fits in u32"
+    )]
+    let (new_width, new_height) = (
+        (w as f32 * scale).round() as u32,
+        (h as f32 * scale).round() as u32,
+    );
+    resize_image(
+        &img,
+        new_width.clamp(1, max_width.max(1)),
+        new_height.clamp(1, max_height.max(1)),
+        filter,
+        linear,
+    )
+}
+
+/// Resize an image to exactly `width`x`height`.
+///
+/// With `linear`, colors are decoded from sRGB and premultiplied by alpha
+/// before filtering, then encoded again. Filtering sRGB values directly
+/// darkens high-contrast edges on downscale, and lets the color of
+/// transparent pixels bleed into sprite outlines.
+pub fn resize_image(
+    img: &RgbaImage,
+    width: u32,
+    height: u32,
+    filter: FilterType,
+    linear: bool,
+) -> RgbaImage {
+    // Nearest neighbor copies pixels, so there is nothing to blend
+    if !linear || filter == FilterType::Nearest {
+        return image::imageops::resize(img, width, height, filter);
+    }
+
+    let mut decode = [0.0f32; 256];
+    for (value, linear) in (0..=255u8).zip(&mut decode) {
+        *linear = srgb_to_linear(f32::from(value) / 255.0);
+    }
+    let premultiplied = Rgba32FImage::from_fn(img.width(), img.height(), |x, y| {
+        let [r, g, b, a] = img.get_pixel(x, y).0;
+        let alpha = f32::from(a) / 255.0;
+        Rgba([
+            decode[usize::from(r)] * alpha,
+            decode[usize::from(g)] * alpha,
+            decode[usize::from(b)] * alpha,
+            alpha,
+        ])
+    });
+
+    let resized = image::imageops::resize(&premultiplied, width, height, filter);
+    RgbaImage::from_fn(width, height, |x, y| {
+        // Lanczos and Catmull-Rom overshoot, so values can leave 0..1
+        let [r, g, b, a] = resized.get_pixel(x, y).0.map(|c| c.clamp(0.0, 1.0));
+        if a == 0.0 {
+            return Rgba([0, 0, 0, 0]);
+        }
+        let encode = |c: f32| to_u8(linear_to_srgb((c / a).min(1.0)));
+        Rgba([encode(r), encode(g), encode(b), to_u8(a)])
+    })
+}
+
+/// sRGB-encoded value (0..1) to linear light
+fn srgb_to_linear(c: f32) -> f32 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Linear light (0..1) to an sRGB-encoded value
+fn linear_to_srgb(c: f32) -> f32 {
+    if c <= 0.003_130_8 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// 0..1 to a channel value
+fn to_u8(c: f32) -> u8 {
+    #[expect(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        reason = "clamped to 0..=255 first"
+    )]
+    let value = (c * 255.0).round().clamp(0.0, 255.0) as u8;
+    value
 }
 
 #[cfg(test)]
@@ -43,7 +154,7 @@ mod tests {
             *pixel = Rgba([255, 0, 0, 255]);
         }
 
-        let resized = resize_to_width(img, 100, FilterType::Lanczos3);
+        let resized = resize_to_width(img, 100, FilterType::Lanczos3, false);
 
         assert_eq!(resized.width(), 100);
         assert_eq!(resized.height(), 50); // 100 * (100/200) = 50
@@ -56,7 +167,7 @@ mod tests {
             *pixel = Rgba([255, 0, 0, 255]);
         }
 
-        let resized = resize_to_width(img, 50, FilterType::Lanczos3);
+        let resized = resize_to_width(img, 50, FilterType::Lanczos3, false);
 
         assert_eq!(resized.width(), 50);
         assert_eq!(resized.height(), 200); // 400 * (50/100) = 200
@@ -69,7 +180,7 @@ mod tests {
             *pixel = Rgba([255, 0, 0, 255]);
         }
 
-        let resized = resize_by_scale(img, 0.5, FilterType::Lanczos3);
+        let resized = resize_by_scale(img, 0.5, FilterType::Lanczos3, false);
 
         assert_eq!(resized.width(), 50);
         assert_eq!(resized.height(), 40);
@@ -82,7 +193,7 @@ mod tests {
             *pixel = Rgba([255, 0, 0, 255]);
         }
 
-        let resized = resize_by_scale(img, 2.0, FilterType::Lanczos3);
+        let resized = resize_by_scale(img, 2.0, FilterType::Lanczos3, false);
 
         assert_eq!(resized.width(), 100);
         assert_eq!(resized.height(), 60);
@@ -96,12 +207,57 @@ mod tests {
         }
 
         // Very small scale that would round to 0
-        let resized = resize_by_scale(img, 0.001, FilterType::Lanczos3);
+        let resized = resize_by_scale(img, 0.001, FilterType::Lanczos3, false);
 
         assert!(resized.width() >= 1);
         assert!(resized.height() >= 1);
     }
 
+    #[test]
+    fn test_resize_to_fit_shrinks_into_box() {
+        let wide = resize_to_fit(
+            RgbaImage::new(400, 100),
+            100,
+            100,
+            FilterType::Nearest,
+            false,
+        );
+        assert_eq!(wide.dimensions(), (100, 25));
+
+        let tall = resize_to_fit(
+            RgbaImage::new(100, 400),
+            200,
+            100,
+            FilterType::Nearest,
+            false,
+        );
+        assert_eq!(tall.dimensions(), (25, 100));
+
+        // Already inside the box: never enlarged
+        let small = resize_to_fit(RgbaImage::new(16, 16), 100, 100, FilterType::Nearest, false);
+        assert_eq!(small.dimensions(), (16, 16));
+    }
+
+    #[test]
+    fn test_resize_linear_keeps_edges_bright() {
+        let black = Rgba([0, 0, 0, 255]);
+        let white = Rgba([255, 255, 255, 255]);
+        let img = RgbaImage::from_fn(2, 1, |x, _| if x == 0 { black } else { white });
+
+        // Averaging sRGB values gives mid gray; half the light is much brighter
+        let srgb = resize_image(&img, 1, 1, FilterType::Triangle, false);
+        assert_eq!(srgb.get_pixel(0, 0).0[0], 128);
+        let linear = resize_image(&img, 1, 1, FilterType::Triangle, true);
+        assert_eq!(linear.get_pixel(0, 0).0[0], 188);
+
+        // Transparent pixels don't darken the color at an outline
+        let img = RgbaImage::from_fn(2, 1, |x, _| if x == 0 { Rgba([0; 4]) } else { white });
+        let srgb = resize_image(&img, 1, 1, FilterType::Triangle, false);
+        assert_eq!(srgb.get_pixel(0, 0).0, [128, 128, 128, 128]);
+        let linear = resize_image(&img, 1, 1, FilterType::Triangle, true);
+        assert_eq!(linear.get_pixel(0, 0).0, [255, 255, 255, 128]);
+    }
+
     #[test]
     fn test_resize_with_nearest_filter() {
         let mut img = RgbaImage::new(100, 100);
@@ -109,7 +265,7 @@ mod tests {
             *pixel = Rgba([255, 0, 0, 255]);
         }
 
-        let resized = resize_by_scale(img, 0.5, FilterType::Nearest);
+        let resized = resize_by_scale(img, 0.5, FilterType::Nearest, false);
 
         assert_eq!(resized.width(), 50);
         assert_eq!(resized.height(), 50);
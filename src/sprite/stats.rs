@@ -0,0 +1,228 @@
+use super::{PackedSprite, SourceSprite};
+
+/// Number of largest sprites to report
+const LARGEST_COUNT: usize = 5;
+
+/// Summary statistics for a set of sprites, loaded or packed
+#[derive(Debug, Clone, Default)]
+pub struct SpriteStats {
+    /// Number of sprites
+    pub count: usize,
+    /// Total pixel area of the untrimmed source images
+    pub source_pixels: u64,
+    /// Total pixel area after trimming
+    pub trimmed_pixels: u64,
+    /// Histogram of sprite sizes as (max side upper bound, count) buckets
+    pub histogram: Vec<(u32, usize)>,
+    /// Largest sprites by trimmed area as (name, width, height), largest first
+    pub largest: Vec<(String, u32, u32)>,
+    /// Lower bound on total atlas area: sum of padded sprite areas
+    pub min_atlas_area: u64,
+}
+
+/// One sprite as the statistics see it
+struct Entry<'a> {
+    name: &'a str,
+    width: u32,
+    height: u32,
+    source_width: u32,
+    source_height: u32,
+}
+
+impl SpriteStats {
+    /// Compute statistics for loaded sprites, before packing. `spacing` gives
+    /// each sprite's (padding_x, padding_y, extrude), as
+    /// [`AtlasBuilder::spacing_for`](crate::atlas::AtlasBuilder::spacing_for) does.
+    pub fn from_sources(
+        sprites: &[SourceSprite],
+        spacing: impl Fn(&str) -> (u32, u32, u32),
+    ) -> Self {
+        Self::compute(
+            sprites.iter().map(|sprite| Entry {
+                name: &sprite.name,
+                width: sprite.width(),
+                height: sprite.height(),
+                source_width: sprite.trim_info.source_width,
+                source_height: sprite.trim_info.source_height,
+            }),
+            spacing,
+        )
+    }
+
+    /// Compute statistics for packed sprites, with `spacing` as for
+    /// [`from_sources`](Self::from_sources)
+    pub fn from_packed<'a>(
+        sprites: impl IntoIterator<Item = &'a PackedSprite>,
+        spacing: impl Fn(&str) -> (u32, u32, u32),
+    ) -> Self {
+        Self::compute(
+            sprites.into_iter().map(|sprite| Entry {
+                name: &sprite.name,
+                width: sprite.width,
+                height: sprite.height,
+                source_width: sprite.trim_info.source_width,
+                source_height: sprite.trim_info.source_height,
+            }),
+            spacing,
+        )
+    }
+
+    fn compute<'a>(
+        sprites: impl Iterator<Item = Entry<'a>>,
+        spacing: impl Fn(&str) -> (u32, u32, u32),
+    ) -> Self {
+        let mut stats = Self::default();
+        let mut by_area: Vec<(u64, Entry)> = Vec::new();
+
+        for sprite in sprites {
+            let (padding_x, padding_y, extrude) = spacing(sprite.name);
+            let border_x = (padding_x + extrude) * 2;
+            let border_y = (padding_y + extrude) * 2;
+            let area = u64::from(sprite.width) * u64::from(sprite.height);
+
+            stats.count += 1;
+            stats.source_pixels += u64::from(sprite.source_width) * u64::from(sprite.source_height);
+            stats.trimmed_pixels += area;
+            stats.min_atlas_area +=
+                u64::from(sprite.width + border_x) * u64::from(sprite.height + border_y);
+
+            let bucket = sprite.width.max(sprite.height).max(1).next_power_of_two();
+            match stats.histogram.iter_mut().find(|(b, _)| *b == bucket) {
+                Some((_, n)) => *n += 1,
+                None => stats.histogram.push((bucket, 1)),
+            }
+
+            by_area.push((area, sprite));
+        }
+
+        stats.histogram.sort_unstable_by_key(|(b, _)| *b);
+
+        by_area.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.name.cmp(b.1.name)));
+        stats.largest = by_area
+            .into_iter()
+            .take(LARGEST_COUNT)
+            .map(|(_, s)| (s.name.to_string(), s.width, s.height))
+            .collect();
+
+        stats
+    }
+
+    /// Fraction of source pixels removed by trimming (0.0 to 1.0)
+    pub fn trim_savings(&self) -> f64 {
+        if self.source_pixels == 0 {
+            return 0.0;
+        }
+        #[expect(
+            clippy::cast_precision_loss,
+            reason = "approximation acceptable for display"
+        )]
+        {
+            1.0 - self.trimmed_pixels as f64 / self.source_pixels as f64
+        }
+    }
+
+    /// Side length of the smallest square that could hold `min_atlas_area`
+    pub fn min_square_side(&self) -> u32 {
+        #[expect(
+            clippy::cast_possible_truncation,
+            clippy::cast_sign_loss,
+            clippy::cast_precision_loss,
+            reason = "square root of a u64 area fits in u32"
+        )]
+        {
+            (self.min_atlas_area as f64).sqrt().ceil() as u32
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sprite::TrimInfo;
+
+    fn packed(name: &str, w: u32, h: u32, src_w: u32, src_h: u32) -> PackedSprite {
+        PackedSprite {
+            name: name.to_string(),
+            path: std::path::PathBuf::from(name),
+            x: 0,
+            y: 0,
+            width: w,
+            height: h,
+            trim_info: TrimInfo {
+                offset_x: 0,
+                offset_y: 0,
+                source_width: src_w,
+                source_height: src_h,
+                trimmed_width: w,
+                trimmed_height: h,
+            },
+            atlas_index: 0,
+            input_index: 0,
+            content_bounds: None,
+            nine_slice: None,
+            pivot: None,
+            rotated: false,
+        }
+    }
+
+    #[test]
+    fn test_stats_trim_savings() {
+        let sprites = [packed("a", 5, 10, 10, 10), packed("b", 10, 10, 10, 10)];
+        let stats = SpriteStats::from_packed(&sprites, |_| (0, 0, 0));
+
+        assert_eq!(stats.count, 2);
+        assert_eq!(stats.source_pixels, 200);
+        assert_eq!(stats.trimmed_pixels, 150);
+        assert!((stats.trim_savings() - 0.25).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_stats_histogram_and_largest() {
+        let sprites = [
+            packed("small", 8, 8, 8, 8),
+            packed("medium", 30, 20, 30, 20),
+            packed("large", 100, 50, 100, 50),
+            packed("medium2", 32, 32, 32, 32),
+        ];
+        let stats = SpriteStats::from_packed(&sprites, |_| (0, 0, 0));
+
+        assert_eq!(stats.histogram, vec![(8, 1), (32, 2), (128, 1)]);
+        assert_eq!(stats.largest[0].0, "large");
+        assert_eq!(stats.largest[1].0, "medium2");
+    }
+
+    #[test]
+    fn test_stats_min_atlas_area_includes_padding() {
+        let sprites = [packed("a", 10, 10, 10, 10)];
+        let stats = SpriteStats::from_packed(&sprites, |_| (1, 1, 1));
+
+        // 10 + 2 * (1 + 1) = 14
+        assert_eq!(stats.min_atlas_area, 14 * 14);
+        assert_eq!(stats.min_square_side(), 14);
+
+        // No vertical padding: 14 wide, 10 + 2 * 1 = 12 high
+        let stats = SpriteStats::from_packed(&sprites, |_| (1, 0, 1));
+        assert_eq!(stats.min_atlas_area, 14 * 12);
+    }
+
+    #[test]
+    fn test_stats_from_sources_use_per_sprite_spacing() {
+        let mut trimmed = SourceSprite::from_image("trimmed", image::RgbaImage::new(6, 4));
+        trimmed.trim_info.source_width = 10;
+        trimmed.trim_info.source_height = 10;
+        let sprites = [
+            trimmed,
+            SourceSprite::from_image("padded", image::RgbaImage::new(10, 10)),
+        ];
+        let stats = SpriteStats::from_sources(&sprites, |name| match name {
+            "padded" => (2, 2, 0),
+            _ => (0, 0, 0),
+        });
+
+        assert_eq!(stats.count, 2);
+        assert_eq!(stats.source_pixels, 200);
+        assert_eq!(stats.trimmed_pixels, 124);
+        assert_eq!(stats.min_atlas_area, 6 * 4 + 14 * 14);
+        assert_eq!(stats.largest[0], ("padded".to_string(), 10, 10));
+    }
+}
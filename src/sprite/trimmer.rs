@@ -1,9 +1,15 @@
 use image::RgbaImage;
 
-use super::TrimInfo;
+use super::{TrimInfo, TrimMargin};
 
-/// Trim transparent borders from an image, optionally keeping a margin
-pub fn trim_sprite(image: &RgbaImage, margin: u32) -> (RgbaImage, TrimInfo) {
+/// Trim transparent borders from an image, optionally keeping a margin on each edge.
+/// Pixels with alpha at or below `alpha_threshold` count as transparent, so faint
+/// anti-aliasing halos can be trimmed away along with the empty border.
+pub fn trim_sprite(
+    image: &RgbaImage,
+    margin: TrimMargin,
+    alpha_threshold: u8,
+) -> (RgbaImage, TrimInfo) {
     let (width, height) = image.dimensions();
 
     if width == 0 || height == 0 {
@@ -29,7 +35,7 @@ pub fn trim_sprite(image: &RgbaImage, margin: u32) -> (RgbaImage, TrimInfo) {
     for y in 0..height {
         for x in 0..width {
             let pixel = image.get_pixel(x, y);
-            if pixel[3] > 0 {
+            if pixel[3] > alpha_threshold {
                 min_x = min_x.min(x);
                 min_y = min_y.min(y);
                 max_x = max_x.max(x);
@@ -54,10 +60,10 @@ pub fn trim_sprite(image: &RgbaImage, margin: u32) -> (RgbaImage, TrimInfo) {
     }
 
     // Expand bounding box by margin, clamped to image bounds
-    let min_x = min_x.saturating_sub(margin);
-    let min_y = min_y.saturating_sub(margin);
-    let max_x = (max_x + margin).min(width - 1);
-    let max_y = (max_y + margin).min(height - 1);
+    let min_x = min_x.saturating_sub(margin.left);
+    let min_y = min_y.saturating_sub(margin.top);
+    let max_x = max_x.saturating_add(margin.right).min(width - 1);
+    let max_y = max_y.saturating_add(margin.bottom).min(height - 1);
 
     let trimmed_width = max_x - min_x + 1;
     let trimmed_height = max_y - min_y + 1;
@@ -89,7 +95,7 @@ mod tests {
             *pixel = Rgba([255, 0, 0, 255]);
         }
 
-        let (trimmed, info) = trim_sprite(&img, 0);
+        let (trimmed, info) = trim_sprite(&img, TrimMargin::default(), 0);
 
         assert_eq!(trimmed.width(), 10);
         assert_eq!(trimmed.height(), 10);
@@ -98,6 +104,26 @@ mod tests {
         assert!(!info.was_trimmed());
     }
 
+    #[test]
+    fn test_trim_alpha_threshold() {
+        let mut img = RgbaImage::new(10, 10);
+        img.put_pixel(5, 5, Rgba([255, 0, 0, 255]));
+        // Faint halo pixels at the corners
+        img.put_pixel(0, 0, Rgba([255, 0, 0, 8]));
+        img.put_pixel(9, 9, Rgba([255, 0, 0, 16]));
+
+        let (_, info) = trim_sprite(&img, TrimMargin::default(), 0);
+        assert!(!info.was_trimmed());
+
+        let (_, info) = trim_sprite(&img, TrimMargin::default(), 8);
+        assert_eq!((info.offset_x, info.offset_y), (5, 5));
+        assert_eq!((info.trimmed_width, info.trimmed_height), (5, 5));
+
+        let (trimmed, info) = trim_sprite(&img, TrimMargin::default(), 16);
+        assert_eq!((info.offset_x, info.offset_y), (5, 5));
+        assert_eq!(trimmed.dimensions(), (1, 1));
+    }
+
     #[test]
     fn test_trim_with_transparent_border() {
         let mut img = RgbaImage::new(10, 10);
@@ -108,7 +134,7 @@ mod tests {
             }
         }
 
-        let (trimmed, info) = trim_sprite(&img, 0);
+        let (trimmed, info) = trim_sprite(&img, TrimMargin::default(), 0);
 
         assert_eq!(trimmed.width(), 4);
         assert_eq!(trimmed.height(), 4);
@@ -123,7 +149,7 @@ mod tests {
     fn test_trim_fully_transparent() {
         let img = RgbaImage::new(10, 10);
 
-        let (trimmed, info) = trim_sprite(&img, 0);
+        let (trimmed, info) = trim_sprite(&img, TrimMargin::default(), 0);
 
         assert_eq!(trimmed.width(), 1);
         assert_eq!(trimmed.height(), 1);
@@ -142,7 +168,7 @@ mod tests {
         }
 
         // With margin=1, should expand bounding box by 1 on each side
-        let (trimmed, info) = trim_sprite(&img, 1);
+        let (trimmed, info) = trim_sprite(&img, TrimMargin::uniform(1), 0);
 
         assert_eq!(trimmed.width(), 6); // 4 + 2
         assert_eq!(trimmed.height(), 6); // 4 + 2
@@ -158,7 +184,7 @@ mod tests {
         img.put_pixel(9, 9, Rgba([255, 0, 0, 255]));
 
         // Margin of 5 should be clamped to image bounds
-        let (trimmed, info) = trim_sprite(&img, 5);
+        let (trimmed, info) = trim_sprite(&img, TrimMargin::uniform(5), 0);
 
         assert_eq!(trimmed.width(), 10);
         assert_eq!(trimmed.height(), 10);
@@ -166,6 +192,57 @@ mod tests {
         assert_eq!(info.offset_y, 0);
     }
 
+    #[test]
+    fn test_trim_with_asymmetric_margin() {
+        let mut img = RgbaImage::new(10, 10);
+        // Fill center 4x4 with opaque pixels (x: 2-5, y: 3-6)
+        for y in 3..7 {
+            for x in 2..6 {
+                img.put_pixel(x, y, Rgba([255, 0, 0, 255]));
+            }
+        }
+
+        // Shadow-style margin: extra border only on the right and bottom
+        let margin = TrimMargin {
+            left: 0,
+            top: 0,
+            right: 3,
+            bottom: 2,
+        };
+        let (trimmed, info) = trim_sprite(&img, margin, 0);
+
+        assert_eq!(trimmed.width(), 7); // 4 + 3
+        assert_eq!(trimmed.height(), 6); // 4 + 2
+        assert_eq!(info.offset_x, 2);
+        assert_eq!(info.offset_y, 3);
+    }
+
+    #[test]
+    fn test_trim_margin_parse_and_serde() {
+        assert_eq!("2".parse::<TrimMargin>(), Ok(TrimMargin::uniform(2)));
+        let edges: TrimMargin = "0,1,2,3".parse().unwrap_or_default();
+        assert_eq!(
+            (edges.left, edges.top, edges.right, edges.bottom),
+            (0, 1, 2, 3)
+        );
+        assert!("1,2".parse::<TrimMargin>().is_err());
+
+        assert_eq!(
+            serde_json::to_string(&TrimMargin::uniform(2))
+                .ok()
+                .as_deref(),
+            Some("2")
+        );
+        let parsed: Option<TrimMargin> = serde_json::from_str(r#"{"right": 4}"#).ok();
+        assert_eq!(
+            parsed,
+            Some(TrimMargin {
+                right: 4,
+                ..TrimMargin::default()
+            })
+        );
+    }
+
     #[test]
     fn test_godot_margin() {
         let info = TrimInfo {
@@ -1,6 +1,232 @@
 use image::RgbaImage;
 use serde::{Deserialize, Serialize};
+use std::hash::{Hash, Hasher};
 use std::path::PathBuf;
+use std::str::FromStr;
+
+use crate::cli::ExtrudeMode;
+
+/// Transparent border kept on each edge after trimming.
+///
+/// Serialized as a single number when all edges are equal, otherwise as an
+/// object with `left`, `top`, `right` and `bottom`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(from = "TrimMarginRepr", into = "TrimMarginRepr")]
+pub struct TrimMargin {
+    pub left: u32,
+    pub top: u32,
+    pub right: u32,
+    pub bottom: u32,
+}
+
+impl TrimMargin {
+    /// Same margin on every edge
+    pub fn uniform(margin: u32) -> Self {
+        Self {
+            left: margin,
+            top: margin,
+            right: margin,
+            bottom: margin,
+        }
+    }
+
+    /// Returns true if every edge has the same margin
+    pub fn is_uniform(&self) -> bool {
+        self.left == self.top && self.left == self.right && self.left == self.bottom
+    }
+}
+
+impl FromStr for TrimMargin {
+    type Err = String;
+
+    /// Parse `N` (all edges) or `LEFT,TOP,RIGHT,BOTTOM`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let values = s
+            .split(',')
+            .map(|v| v.trim().parse::<u32>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("invalid trim margin '{}': {}", s, e))?;
+
+        match values[..] {
+            [margin] => Ok(Self::uniform(margin)),
+            [left, top, right, bottom] => Ok(Self {
+                left,
+                top,
+                right,
+                bottom,
+            }),
+            _ => Err(format!(
+                "invalid trim margin '{}': expected N or LEFT,TOP,RIGHT,BOTTOM",
+                s
+            )),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum TrimMarginRepr {
+    Uniform(u32),
+    Edges {
+        #[serde(default)]
+        left: u32,
+        #[serde(default)]
+        top: u32,
+        #[serde(default)]
+        right: u32,
+        #[serde(default)]
+        bottom: u32,
+    },
+}
+
+impl From<TrimMarginRepr> for TrimMargin {
+    fn from(repr: TrimMarginRepr) -> Self {
+        match repr {
+            TrimMarginRepr::Uniform(margin) => Self::uniform(margin),
+            TrimMarginRepr::Edges {
+                left,
+                top,
+                right,
+                bottom,
+            } => Self {
+                left,
+                top,
+                right,
+                bottom,
+            },
+        }
+    }
+}
+
+impl From<TrimMargin> for TrimMarginRepr {
+    fn from(margin: TrimMargin) -> Self {
+        if margin.is_uniform() {
+            Self::Uniform(margin.left)
+        } else {
+            Self::Edges {
+                left: margin.left,
+                top: margin.top,
+                right: margin.right,
+                bottom: margin.bottom,
+            }
+        }
+    }
+}
+
+/// Sprite origin as a fraction of the source frame: (0, 0) is the top-left
+/// corner and (0.5, 1) the bottom center
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Pivot {
+    pub x: f32,
+    pub y: f32,
+}
+
+impl Hash for Pivot {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.x.to_bits().hash(state);
+        self.y.to_bits().hash(state);
+    }
+}
+
+/// Settings for sprites matched by name or glob pattern. Unset fields fall
+/// back to the next matching override, then to the global setting.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SpriteOverride {
+    /// Trim transparent borders
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trim: Option<bool>,
+    /// Padding around the sprite
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub padding: Option<u32>,
+    /// Edge extrusion
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extrude: Option<u32>,
+    /// What fills the extruded border
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extrude_mode: Option<ExtrudeMode>,
+    /// Origin written to metadata
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pivot: Option<Pivot>,
+    /// Resize factor, replacing the global resize
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scale: Option<f32>,
+    /// Packing priority; higher values go on earlier pages (default 0)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i32>,
+}
+
+impl Hash for SpriteOverride {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.trim.hash(state);
+        self.padding.hash(state);
+        self.extrude.hash(state);
+        self.extrude_mode.hash(state);
+        self.pivot.hash(state);
+        self.scale.map(f32::to_bits).hash(state);
+        self.priority.hash(state);
+    }
+}
+
+impl SpriteOverride {
+    /// Combine the overrides matching `name`, in match order: each field
+    /// comes from the first override that sets it
+    pub fn resolve(overrides: &[(glob::Pattern, SpriteOverride)], name: &str) -> Self {
+        overrides
+            .iter()
+            .filter(|(pattern, _)| pattern.matches(name))
+            .fold(Self::default(), |merged, (_, o)| Self {
+                trim: merged.trim.or(o.trim),
+                padding: merged.padding.or(o.padding),
+                extrude: merged.extrude.or(o.extrude),
+                extrude_mode: merged.extrude_mode.or(o.extrude_mode),
+                pivot: merged.pivot.or(o.pivot),
+                scale: merged.scale.or(o.scale),
+                priority: merged.priority.or(o.priority),
+            })
+    }
+}
+
+/// Grid of equally sized cells for cutting an existing sprite sheet into
+/// sprites, in source-image pixels
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct GridSlice {
+    /// Cell width
+    pub width: u32,
+    /// Cell height
+    pub height: u32,
+    /// Border around the whole grid
+    #[serde(default)]
+    pub margin: u32,
+    /// Gap between neighboring cells
+    #[serde(default)]
+    pub spacing: u32,
+}
+
+impl GridSlice {
+    /// Top-left corners of every cell that fits in an image of the given
+    /// size, row by row
+    pub fn cells(&self, image_width: u32, image_height: u32) -> Vec<(u32, u32)> {
+        let count = |size: u32, cell: u32| {
+            if cell == 0 {
+                return 0;
+            }
+            (size.saturating_sub(2 * self.margin) + self.spacing) / (cell + self.spacing)
+        };
+        let columns = count(image_width, self.width);
+        let rows = count(image_height, self.height);
+        (0..rows)
+            .flat_map(|row| {
+                (0..columns).map(move |column| {
+                    (
+                        self.margin + column * (self.width + self.spacing),
+                        self.margin + row * (self.height + self.spacing),
+                    )
+                })
+            })
+            .collect()
+    }
+}
 
 /// Trimming information to reconstruct original sprite positioning
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, Default)]
@@ -46,6 +272,59 @@ impl TrimInfo {
     }
 }
 
+/// Tight bounds of the opaque content within an untrimmed sprite frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContentBounds {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl From<TrimInfo> for ContentBounds {
+    fn from(info: TrimInfo) -> Self {
+        Self {
+            x: info.offset_x.max(0).unsigned_abs(),
+            y: info.offset_y.max(0).unsigned_abs(),
+            width: info.trimmed_width,
+            height: info.trimmed_height,
+        }
+    }
+}
+
+/// Nine-slice borders in source-image pixels, measured inward from each edge
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NineSlice {
+    pub left: u32,
+    pub top: u32,
+    pub right: u32,
+    pub bottom: u32,
+}
+
+impl NineSlice {
+    /// Borders relative to the trimmed frame stored in the atlas.
+    ///
+    /// Trimming removes pixels from the edges, so each border shrinks by the
+    /// amount trimmed from its side.
+    pub fn trimmed(&self, trim: &TrimInfo) -> Self {
+        let left_trim = trim.offset_x.max(0).unsigned_abs();
+        let top_trim = trim.offset_y.max(0).unsigned_abs();
+        let right_trim = trim
+            .source_width
+            .saturating_sub(left_trim + trim.trimmed_width);
+        let bottom_trim = trim
+            .source_height
+            .saturating_sub(top_trim + trim.trimmed_height);
+        Self {
+            left: self.left.saturating_sub(left_trim),
+            top: self.top.saturating_sub(top_trim),
+            right: self.right.saturating_sub(right_trim),
+            bottom: self.bottom.saturating_sub(bottom_trim),
+        }
+    }
+}
+
 /// Represents a source sprite before packing
 #[derive(Debug, Clone)]
 pub struct SourceSprite {
@@ -57,9 +336,39 @@ pub struct SourceSprite {
     pub image: RgbaImage,
     /// Trim metadata for offset reconstruction
     pub trim_info: TrimInfo,
+    /// Position of this sprite in the resolved input list
+    pub input_index: usize,
+    /// Content bounds recorded without trimming (record-trim mode)
+    pub content_bounds: Option<ContentBounds>,
+    /// Nine-slice borders, for sprites used as stretchable UI frames
+    pub nine_slice: Option<NineSlice>,
+    /// Origin point for the engine, as a fraction of the source frame
+    pub pivot: Option<Pivot>,
+    /// Companion images such as normal maps, keyed by suffix and cropped to
+    /// the same trim as `image`
+    pub companions: Vec<(String, RgbaImage)>,
 }
 
 impl SourceSprite {
+    /// Sprite from an in-memory image, packed as-is without trimming. See
+    /// [`sprites_from_images`](super::sprites_from_images) to trim and resize
+    /// like file inputs.
+    pub fn from_image(name: impl Into<String>, image: RgbaImage) -> Self {
+        let name = name.into();
+        let (width, height) = image.dimensions();
+        Self {
+            path: PathBuf::from(&name),
+            name,
+            image,
+            trim_info: TrimInfo::untrimmed(width, height),
+            input_index: 0,
+            content_bounds: None,
+            nine_slice: None,
+            pivot: None,
+            companions: Vec::new(),
+        }
+    }
+
     /// Width of the sprite (after trimming)
     pub fn width(&self) -> u32 {
         self.image.width()
@@ -76,6 +385,8 @@ impl SourceSprite {
 pub struct PackedSprite {
     /// Reference to source sprite name
     pub name: String,
+    /// Original file path of the source sprite
+    pub path: PathBuf,
     /// Position in atlas (x coordinate)
     pub x: u32,
     /// Position in atlas (y coordinate)
@@ -88,4 +399,15 @@ pub struct PackedSprite {
     pub trim_info: TrimInfo,
     /// Index of atlas this sprite belongs to
     pub atlas_index: usize,
+    /// Position of the source sprite in the resolved input list
+    pub input_index: usize,
+    /// Content bounds within the frame, relative to its top-left corner
+    pub content_bounds: Option<ContentBounds>,
+    /// Nine-slice borders in source-image pixels
+    pub nine_slice: Option<NineSlice>,
+    /// Origin point as a fraction of the source frame
+    pub pivot: Option<Pivot>,
+    /// Stored rotated 90° clockwise, so `width` and `height` are the source
+    /// sprite's height and width
+    pub rotated: bool,
 }
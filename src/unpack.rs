@@ -0,0 +1,240 @@
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use anyhow::{Result, bail};
+use image::RgbaImage;
+use log::info;
+
+use crate::atlas::Atlas;
+use crate::error::BentoError;
+use crate::output::{SheetPage, read_sheet, save_image};
+
+/// Extract every sprite of a packed sheet as its own PNG under `output_dir`,
+/// returning how many were written.
+///
+/// Atlas images are resolved relative to the metadata file, unless `image`
+/// names the atlas to use instead (single-page sheets only). With
+/// `restore_trim`, sprites get back their original size and transparent border.
+pub fn unpack_sheet(
+    metadata: &Path,
+    image: Option<&Path>,
+    output_dir: &Path,
+    restore_trim: bool,
+) -> Result<usize> {
+    let pages = read_sheet(metadata)?;
+    if image.is_some() && pages.len() > 1 {
+        bail!(
+            "{} has {} atlas pages; --image only works for single-page sheets",
+            metadata.display(),
+            pages.len()
+        );
+    }
+
+    let metadata_dir = metadata.parent().unwrap_or(Path::new(""));
+    let mut written = 0;
+    for page in pages {
+        let image_path = image.map_or_else(|| metadata_dir.join(&page.image), Path::to_path_buf);
+        let atlas_image = load_page_image(&page, &image_path, metadata_dir)?;
+        let atlas = Atlas::from_layout(page.layout, atlas_image);
+
+        for sprite in &atlas.sprites {
+            let Some(path) = sprite_output_path(output_dir, &sprite.name) else {
+                bail!("sprite name '{}' is not a usable file name", sprite.name);
+            };
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent).map_err(|e| BentoError::OutputWrite {
+                    path: parent.to_path_buf(),
+                    source: e,
+                })?;
+            }
+            save_image(
+                &atlas.sprite_image(sprite, restore_trim),
+                &path,
+                false,
+                None,
+                None,
+            )?;
+            written += 1;
+        }
+        info!(
+            "Unpacked {} sprites from {}",
+            atlas.sprites.len(),
+            image_path.display()
+        );
+    }
+    Ok(written)
+}
+
+/// Read a packed sheet back as atlas pages, with images resolved relative
+/// to the metadata file
+pub fn read_atlases(metadata: &Path) -> Result<Vec<Atlas>> {
+    let metadata_dir = metadata.parent().unwrap_or(Path::new(""));
+    read_sheet(metadata)?
+        .into_iter()
+        .map(|page| {
+            let image = load_page_image(&page, &metadata_dir.join(&page.image), metadata_dir)?;
+            Ok(Atlas::from_layout(page.layout, image))
+        })
+        .collect()
+}
+
+/// Load a page's atlas image, folding a split-alpha mask back into its alpha channel
+fn load_page_image(page: &SheetPage, path: &Path, metadata_dir: &Path) -> Result<RgbaImage> {
+    let mut atlas = open_image(path)?.into_rgba8();
+    if let Some(alpha) = &page.alpha {
+        let mask_path = metadata_dir.join(alpha);
+        let mask = open_image(&mask_path)?.into_luma8();
+        if mask.dimensions() != atlas.dimensions() {
+            bail!(
+                "alpha mask {} does not match its atlas size",
+                mask_path.display()
+            );
+        }
+        for (pixel, value) in atlas.pixels_mut().zip(mask.pixels()) {
+            pixel[3] = value[0];
+        }
+    }
+
+    let (width, height) = atlas.dimensions();
+    if (width, height) != (page.layout.width, page.layout.height) {
+        bail!(
+            "{} is {}x{}, but the metadata describes a {}x{} atlas",
+            path.display(),
+            width,
+            height,
+            page.layout.width,
+            page.layout.height
+        );
+    }
+    Ok(atlas)
+}
+
+fn open_image(path: &Path) -> Result<image::DynamicImage> {
+    Ok(image::open(path).map_err(|e| BentoError::ImageLoad {
+        path: path.to_path_buf(),
+        source: e,
+    })?)
+}
+
+/// Output file for a sprite: its name under `output_dir`, ending in `.png`.
+/// Parent, root and prefix components are dropped so names can't escape the
+/// directory; None if nothing usable is left.
+fn sprite_output_path(output_dir: &Path, name: &str) -> Option<PathBuf> {
+    let relative: PathBuf = Path::new(name)
+        .components()
+        .filter(|c| matches!(c, Component::Normal(_)))
+        .collect();
+    let file_name = relative.file_name()?.to_string_lossy();
+    let file_name = if file_name.to_lowercase().ends_with(".png") {
+        file_name.into_owned()
+    } else {
+        format!("{}.png", file_name)
+    };
+    Some(output_dir.join(relative.with_file_name(file_name)))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::atlas::AtlasBuilder;
+    use crate::cli::SpriteOrder;
+    use crate::output::{
+        OutputTemplate, atlas_png_filename, save_atlas_image, write_json, write_tpsheet,
+    };
+    use crate::sprite::{SourceSprite, trim_sprite};
+    use image::Rgba;
+
+    #[test]
+    fn test_sprite_output_path() {
+        let out = Path::new("out");
+        assert_eq!(
+            sprite_output_path(out, "ui/panel.png"),
+            Some(PathBuf::from("out/ui/panel.png"))
+        );
+        assert_eq!(
+            sprite_output_path(out, "hero.jpg"),
+            Some(PathBuf::from("out/hero.jpg.png"))
+        );
+        assert_eq!(
+            sprite_output_path(out, "../../etc/passwd"),
+            Some(PathBuf::from("out/etc/passwd.png"))
+        );
+        assert_eq!(sprite_output_path(out, ".."), None);
+    }
+
+    #[test]
+    fn test_unpack_round_trip() {
+        let dir = std::env::temp_dir().join("bento_unpack_round_trip");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        // A 6x4 source with a 2x2 opaque block, trimmed on packing
+        let mut source = RgbaImage::new(6, 4);
+        source.put_pixel(3, 1, Rgba([255, 0, 0, 255]));
+        source.put_pixel(4, 1, Rgba([0, 255, 0, 255]));
+        source.put_pixel(3, 2, Rgba([0, 0, 255, 255]));
+        source.put_pixel(4, 2, Rgba([255, 255, 0, 255]));
+        let (image, trim_info) = trim_sprite(&source, Default::default(), 0);
+        let sprites = vec![SourceSprite {
+            path: PathBuf::from("fx/spark.png"),
+            name: "fx/spark.png".to_string(),
+            image,
+            trim_info,
+            input_index: 0,
+            content_bounds: None,
+            nine_slice: None,
+            pivot: None,
+            companions: Vec::new(),
+        }];
+        let atlases = AtlasBuilder::new(64, 64).build(sprites).unwrap();
+        save_atlas_image(
+            &atlases[0],
+            &dir.join(atlas_png_filename("sheet", 0, 1)),
+            false,
+            None,
+            None,
+        )
+        .unwrap();
+        write_json(
+            &atlases,
+            &dir,
+            "sheet",
+            &OutputTemplate::default(),
+            SpriteOrder::Packed,
+            false,
+            false,
+            None,
+            None,
+            None,
+            false,
+            &[],
+        )
+        .unwrap();
+        write_tpsheet(
+            &atlases,
+            &dir,
+            "sheet",
+            &OutputTemplate::default(),
+            SpriteOrder::Packed,
+            false,
+            None,
+        )
+        .unwrap();
+
+        for metadata in ["sheet.json", "sheet.tpsheet"] {
+            let out = dir.join(metadata.replace('.', "_"));
+            let count = unpack_sheet(&dir.join(metadata), None, &out, true).unwrap();
+            assert_eq!(count, 1);
+            let restored = image::open(out.join("fx/spark.png")).unwrap().into_rgba8();
+            assert_eq!(restored, source);
+        }
+
+        let out = dir.join("trimmed");
+        unpack_sheet(&dir.join("sheet.json"), None, &out, false).unwrap();
+        let trimmed = image::open(out.join("fx/spark.png")).unwrap();
+        assert_eq!((trimmed.width(), trimmed.height()), (2, 2));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,72 @@
+//! Problems found while loading and packing that don't stop the run.
+
+use std::fmt;
+use std::path::PathBuf;
+
+/// Something likely unintended about the inputs, reported alongside the
+/// sprites or atlases rather than failing
+#[derive(Debug, Clone, PartialEq)]
+pub enum Warning {
+    /// Files in the inputs that aren't in an image format bento reads
+    UnsupportedFiles { paths: Vec<PathBuf> },
+    /// An image that failed to load and was left out, with `skip_errors`
+    SkippedImage { message: String },
+    /// Sprites without a visible pixel, packed as a single transparent pixel
+    EmptySprites { names: Vec<String> },
+    /// Sprite names that differ only in case, so files named after them
+    /// overwrite each other on Windows and macOS
+    CaseDuplicates { names: Vec<String> },
+    /// A source image that is suspiciously large for an atlas, such as a
+    /// background or a source that was never scaled down
+    LargeSprite {
+        name: String,
+        /// Source size before trimming
+        width: u32,
+        height: u32,
+        /// Share of the largest page the packed sprite covers (0.0 to 1.0)
+        share: f64,
+    },
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Warning::UnsupportedFiles { paths } => {
+                let paths: Vec<String> = paths.iter().map(|p| p.display().to_string()).collect();
+                write!(
+                    f,
+                    "Skipped {} file{} in an unsupported format: {}",
+                    paths.len(),
+                    if paths.len() == 1 { "" } else { "s" },
+                    paths.join(", ")
+                )
+            }
+            Warning::SkippedImage { message } => write!(f, "Skipped image: {}", message),
+            Warning::EmptySprites { names } => write!(
+                f,
+                "{} sprite{} fully transparent, packed as 1x1: {}",
+                names.len(),
+                if names.len() == 1 { " is" } else { "s are" },
+                names.join(", ")
+            ),
+            Warning::CaseDuplicates { names } => write!(
+                f,
+                "Sprite names differ only in case and collide on case-insensitive file systems: {}",
+                names.join(", ")
+            ),
+            Warning::LargeSprite {
+                name,
+                width,
+                height,
+                share,
+            } => write!(
+                f,
+                "Sprite '{}' is {}x{} and fills {:.0}% of a page",
+                name,
+                width,
+                height,
+                share * 100.0
+            ),
+        }
+    }
+}